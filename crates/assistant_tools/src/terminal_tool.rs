@@ -284,7 +284,12 @@ impl Tool for TerminalTool {
                     .update(cx, |terminal, cx| terminal.wait_for_completed_task(cx))?
                     .await;
                 let (content, content_line_count) = terminal.read_with(cx, |terminal, _| {
-                    (terminal.get_content(), terminal.total_lines())
+                    // Shells reporting their commands with OSC 133 let us skip the prompt and
+                    // anything printed before the command.
+                    let content = terminal
+                        .last_command_output()
+                        .unwrap_or_else(|| terminal.get_content());
+                    (content, terminal.total_lines())
                 })?;
 
                 let previous_len = content.len();
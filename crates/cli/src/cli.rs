@@ -13,6 +13,8 @@ pub enum CliRequest {
     Open {
         paths: Vec<String>,
         urls: Vec<String>,
+        diff_paths: Vec<[String; 2]>,
+        merge_paths: Vec<[String; 4]>,
         wait: bool,
         open_new_workspace: Option<bool>,
         env: Option<HashMap<String, String>>,
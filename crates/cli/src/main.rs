@@ -49,13 +49,25 @@ Examples:
     `zed path-to-your-project`
           Open your project in Zed
     `zed -n path-to-file `
-          Open file/folder in a new window",
+          Open file/folder in a new window
+    `zed --wait --diff old-file new-file`
+          Compare two files or directories, e.g. as a `git difftool`
+    `zed --wait --merge ours theirs base result`
+          Resolve a conflicted file, e.g. as a `git mergetool`",
     after_help = "To read from stdin, append '-', e.g. 'ps axf | zed -'"
 )]
 struct Args {
     /// Wait for all of the given paths to be opened/closed before exiting.
     #[arg(short, long)]
     wait: bool,
+    /// Open a diff of two files or directories.
+    ///
+    /// Can be given more than once to open several comparisons.
+    #[arg(long, action = clap::ArgAction::Append, num_args = 2, value_names = ["OLD_PATH", "NEW_PATH"])]
+    diff: Vec<String>,
+    /// Open a three-way merge, saving the resolved file to RESULT_PATH.
+    #[arg(long, num_args = 4, value_names = ["OURS_PATH", "THEIRS_PATH", "BASE_PATH", "RESULT_PATH"])]
+    merge: Vec<String>,
     /// Add files to the currently open workspace
     #[arg(short, long, overrides_with = "new")]
     add: bool,
@@ -255,6 +267,24 @@ fn main() -> Result<()> {
         }
     }
 
+    let mut diff_paths = vec![];
+    for diff_pair in args.diff.chunks(2) {
+        diff_paths.push([
+            parse_path_with_position(&diff_pair[0])?,
+            parse_path_with_position(&diff_pair[1])?,
+        ]);
+    }
+
+    let mut merge_paths = vec![];
+    for merge_paths_group in args.merge.chunks(4) {
+        merge_paths.push([
+            parse_path_with_position(&merge_paths_group[0])?,
+            parse_path_with_position(&merge_paths_group[1])?,
+            parse_path_with_position(&merge_paths_group[2])?,
+            parse_path_with_position(&merge_paths_group[3])?,
+        ]);
+    }
+
     anyhow::ensure!(
         args.dev_server_token.is_none(),
         "Dev servers were removed in v0.157.x please upgrade to SSH remoting: https://zed.dev/docs/remote-development"
@@ -270,6 +300,8 @@ fn main() -> Result<()> {
             tx.send(CliRequest::Open {
                 paths,
                 urls,
+                diff_paths,
+                merge_paths,
                 wait: args.wait,
                 open_new_workspace,
                 env,
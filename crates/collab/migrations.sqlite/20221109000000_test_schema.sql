@@ -118,6 +118,8 @@ CREATE TABLE "project_repositories" (
     "current_merge_conflicts" VARCHAR,
     "branch_summary" VARCHAR,
     "head_commit_details" VARCHAR,
+    "in_progress_operation" INTEGER,
+    "is_pure_jujutsu" BOOL NOT NULL DEFAULT false,
     PRIMARY KEY (project_id, id)
 );
 
@@ -0,0 +1,2 @@
+alter table project_repositories
+    add column is_pure_jujutsu boolean not null default false;
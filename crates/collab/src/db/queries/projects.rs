@@ -348,10 +348,13 @@ impl Database {
                                         .unwrap(),
                                 )),
 
-                                // Old clients do not use abs path, entry ids or head_commit_details.
+                                // Old clients do not use abs path, entry ids, head_commit_details,
+                                // in_progress_operation or is_pure_jujutsu.
                                 abs_path: ActiveValue::set(String::new()),
                                 entry_ids: ActiveValue::set("[]".into()),
                                 head_commit_details: ActiveValue::set(None),
+                                in_progress_operation: ActiveValue::set(None),
+                                is_pure_jujutsu: ActiveValue::set(false),
                             }
                         }),
                     )
@@ -500,6 +503,8 @@ impl Database {
                 current_merge_conflicts: ActiveValue::Set(Some(
                     serde_json::to_string(&update.current_merge_conflicts).unwrap(),
                 )),
+                in_progress_operation: ActiveValue::Set(update.in_progress_operation),
+                is_pure_jujutsu: ActiveValue::Set(update.is_pure_jujutsu),
             })
             .on_conflict(
                 OnConflict::columns([
@@ -513,6 +518,8 @@ impl Database {
                     project_repository::Column::AbsPath,
                     project_repository::Column::CurrentMergeConflicts,
                     project_repository::Column::HeadCommitDetails,
+                    project_repository::Column::InProgressOperation,
+                    project_repository::Column::IsPureJujutsu,
                 ])
                 .to_owned(),
             )
@@ -964,6 +971,8 @@ impl Database {
                         current_merge_conflicts,
                         branch_summary,
                         head_commit_details,
+                        in_progress_operation: db_repository_entry.in_progress_operation,
+                        is_pure_jujutsu: db_repository_entry.is_pure_jujutsu,
                         scan_id: db_repository_entry.scan_id as u64,
                         is_last_update: true,
                     });
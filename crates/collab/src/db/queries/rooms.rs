@@ -786,6 +786,8 @@ impl Database {
                             current_merge_conflicts,
                             branch_summary,
                             head_commit_details,
+                            in_progress_operation: db_repository.in_progress_operation,
+                            is_pure_jujutsu: db_repository.is_pure_jujutsu,
                             project_id: project_id.to_proto(),
                             id: db_repository.id as u64,
                             abs_path: db_repository.abs_path,
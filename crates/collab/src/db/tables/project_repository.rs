@@ -20,6 +20,10 @@ pub struct Model {
     pub branch_summary: Option<String>,
     // A JSON object representing the current Head commit values
     pub head_commit_details: Option<String>,
+    // The `proto::GitOperation` waiting to be continued or aborted
+    pub in_progress_operation: Option<i32>,
+    // Whether the repository is a jj workspace without a git working tree
+    pub is_pure_jujutsu: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
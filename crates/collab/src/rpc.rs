@@ -410,6 +410,18 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::LoadCommitDiff>)
             .add_request_handler(forward_read_only_project_request::<proto::GitReset>)
             .add_request_handler(forward_read_only_project_request::<proto::GitCheckoutFiles>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashPush>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitStashList>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashApply>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashPop>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashDrop>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitStashShow>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitLog>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitRebase>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitCherryPick>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitRevert>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitContinueOperation>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitAbortOperation>)
             .add_request_handler(forward_mutating_project_request::<proto::SetIndexText>)
             .add_request_handler(forward_mutating_project_request::<proto::ToggleBreakpoint>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BreakpointsForFile>)
@@ -417,6 +429,16 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::GitDiff>)
             .add_request_handler(forward_mutating_project_request::<proto::GitCreateBranch>)
             .add_request_handler(forward_mutating_project_request::<proto::GitChangeBranch>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitDeleteBranch>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitIsBranchMerged>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitRenameBranch>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitSetUpstream>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitDiffToMergeBase>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitWorktrees>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitTags>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitCreateTag>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitDeleteTag>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitLoadConflictStages>)
             .add_request_handler(forward_mutating_project_request::<proto::CheckForPushedCommits>)
             .add_message_handler(broadcast_project_message_from_host::<proto::AdvertiseContexts>)
             .add_message_handler(update_context);
@@ -18,7 +18,7 @@ use gpui::{
     ParentElement, Render, Styled, Task, WeakEntity, Window,
 };
 use persistence::COMMAND_PALETTE_HISTORY;
-use picker::{Picker, PickerDelegate};
+use picker::{Direction, Picker, PickerDelegate};
 use postage::{sink::Sink, stream::Stream};
 use settings::Settings;
 use ui::{HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, h_flex, prelude::*, v_flex};
@@ -77,18 +77,40 @@ impl CommandPalette {
         query: &str,
         window: &mut Window,
         cx: &mut Context<Workspace>,
+    ) {
+        Self::toggle_impl(workspace, query, false, window, cx)
+    }
+
+    /// Toggles the palette as a command line, where moving up from the first result recalls
+    /// earlier commands whatever the query is.
+    pub fn toggle_command_line(
+        workspace: &mut Workspace,
+        query: &str,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        Self::toggle_impl(workspace, query, true, window, cx)
+    }
+
+    fn toggle_impl(
+        workspace: &mut Workspace,
+        query: &str,
+        command_line: bool,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
     ) {
         let Some(previous_focus_handle) = window.focused(cx) else {
             return;
         };
         workspace.toggle_modal(window, cx, move |window, cx| {
-            CommandPalette::new(previous_focus_handle, query, window, cx)
+            CommandPalette::new(previous_focus_handle, query, command_line, window, cx)
         });
     }
 
     fn new(
         previous_focus_handle: FocusHandle,
         query: &str,
+        command_line: bool,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
@@ -105,12 +127,17 @@ impl CommandPalette {
                 Some(Command {
                     name: humanize_action_name(action.name()),
                     action,
+                    intercepted: false,
                 })
             })
             .collect();
 
-        let delegate =
-            CommandPaletteDelegate::new(cx.entity().downgrade(), commands, previous_focus_handle);
+        let delegate = CommandPaletteDelegate::new(
+            cx.entity().downgrade(),
+            commands,
+            previous_focus_handle,
+            command_line,
+        );
 
         let picker = cx.new(|cx| {
             let picker = Picker::uniform_list(delegate, window, cx);
@@ -152,11 +179,26 @@ pub struct CommandPaletteDelegate {
         Task<()>,
         postage::dispatch::Receiver<(Vec<Command>, Vec<StringMatch>)>,
     )>,
+    history_navigation: Option<HistoryNavigation>,
+    /// Whether the palette was opened as a command line (e.g. by vim's `:`), in which case
+    /// history is recalled for any query rather than only for `:` commands.
+    command_line: bool,
+}
+
+/// The position in the [`CommandPaletteInterceptor`]'s history while earlier queries are
+/// being recalled.
+struct HistoryNavigation {
+    /// The query typed before recalling, which recalled queries start with.
+    prefix: String,
+    ix: usize,
+    recalled: String,
 }
 
 struct Command {
     name: String,
     action: Box<dyn Action>,
+    /// Whether this command was produced by the [`CommandPaletteInterceptor`].
+    intercepted: bool,
 }
 
 impl Clone for Command {
@@ -164,6 +206,7 @@ impl Clone for Command {
         Self {
             name: self.name.clone(),
             action: self.action.boxed_clone(),
+            intercepted: self.intercepted,
         }
     }
 }
@@ -173,6 +216,7 @@ impl CommandPaletteDelegate {
         command_palette: WeakEntity<CommandPalette>,
         commands: Vec<Command>,
         previous_focus_handle: FocusHandle,
+        command_line: bool,
     ) -> Self {
         Self {
             command_palette,
@@ -183,6 +227,8 @@ impl CommandPaletteDelegate {
             previous_focus_handle,
             latest_query: String::new(),
             updating_matches: None,
+            history_navigation: None,
+            command_line,
         }
     }
 
@@ -200,7 +246,8 @@ impl CommandPaletteDelegate {
             .map(|interceptor| interceptor.intercept(&query, cx))
             .unwrap_or_default();
 
-        if parse_zed_link(&query, cx).is_some() {
+        let is_zed_link = parse_zed_link(&query, cx).is_some();
+        if is_zed_link {
             intercept_results = vec![CommandInterceptResult {
                 action: OpenZedUrl { url: query.clone() }.boxed_clone(),
                 string: query.clone(),
@@ -225,6 +272,7 @@ impl CommandPaletteDelegate {
             commands.push(Command {
                 name: string.clone(),
                 action,
+                intercepted: !is_zed_link,
             });
             new_matches.push(StringMatch {
                 candidate_id: commands.len() - 1,
@@ -282,12 +330,76 @@ impl PickerDelegate for CommandPaletteDelegate {
         self.selected_ix = ix;
     }
 
+    fn select_history(
+        &mut self,
+        direction: Direction,
+        query: &str,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Option<String> {
+        // Moving up from the first result of a command line or a `:` command starts recalling
+        // earlier commands. Other queries keep moving the selection.
+        if self.history_navigation.is_none()
+            && (matches!(direction, Direction::Down)
+                || self.selected_ix != 0
+                || !(self.command_line || query.starts_with(':')))
+        {
+            return None;
+        }
+        let history = CommandPaletteInterceptor::history(cx);
+        let (prefix, ix) = match self.history_navigation.take() {
+            Some(navigation) => (navigation.prefix, navigation.ix.min(history.len())),
+            None => (query.to_string(), history.len()),
+        };
+        let recalled_ix = match direction {
+            Direction::Up => history[..ix]
+                .iter()
+                .rposition(|entry| entry.starts_with(&prefix)),
+            Direction::Down => history
+                .iter()
+                .enumerate()
+                .skip(ix + 1)
+                .find(|(_, entry)| entry.starts_with(&prefix))
+                .map(|(ix, _)| ix),
+        };
+        match (recalled_ix, direction) {
+            (Some(ix), _) => {
+                let recalled = history[ix].clone();
+                self.history_navigation = Some(HistoryNavigation {
+                    prefix,
+                    ix,
+                    recalled: recalled.clone(),
+                });
+                Some(recalled)
+            }
+            // Stay on the oldest query that was recalled.
+            (None, Direction::Up) if ix < history.len() => {
+                self.history_navigation = Some(HistoryNavigation {
+                    prefix,
+                    ix,
+                    recalled: query.to_string(),
+                });
+                Some(query.to_string())
+            }
+            (None, Direction::Up) => None,
+            // Moving down past the newest query brings back what was typed.
+            (None, Direction::Down) => Some(prefix),
+        }
+    }
+
     fn update_matches(
         &mut self,
         mut query: String,
         window: &mut Window,
         cx: &mut Context<Picker<Self>>,
     ) -> gpui::Task<()> {
+        if self
+            .history_navigation
+            .as_ref()
+            .is_some_and(|navigation| navigation.recalled != query)
+        {
+            self.history_navigation = None;
+        }
         let settings = WorkspaceSettings::get_global(cx);
         if let Some(alias) = settings.command_aliases.get(&query) {
             query = alias.to_string();
@@ -408,6 +520,9 @@ impl PickerDelegate for CommandPaletteDelegate {
                 .await
         })
         .detach_and_log_err(cx);
+        if command.intercepted {
+            CommandPaletteInterceptor::confirmed(&self.latest_query, cx);
+        }
         let action = command.action;
         window.focus(&self.previous_focus_handle);
         self.dismissed(window, cx);
@@ -482,7 +597,7 @@ impl std::fmt::Debug for Command {
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::{rc::Rc, sync::Arc};
 
     use super::*;
     use editor::Editor;
@@ -635,6 +750,54 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_up_moves_selection_without_command_prefix(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        cx.update(|_, cx| {
+            CommandPaletteInterceptor::update_global(cx, |interceptor, _| {
+                interceptor.set_history_handler(Rc::new(|_| vec![":earlier".to_string()]));
+            })
+        });
+
+        cx.simulate_keystrokes("cmd-shift-p");
+        let palette = workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<CommandPalette>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        });
+        let match_count = palette.read_with(cx, |palette, _| palette.delegate.matches.len());
+        assert!(match_count > 1);
+
+        // Up wraps around to the last command instead of recalling `:earlier`.
+        cx.simulate_keystrokes("up");
+        palette.read_with(cx, |palette, cx| {
+            assert_eq!(palette.query(cx), "");
+            assert_eq!(palette.delegate.selected_ix, match_count - 1);
+        });
+
+        cx.simulate_keystrokes("up");
+        palette.read_with(cx, |palette, cx| {
+            assert_eq!(palette.query(cx), "");
+            assert_eq!(palette.delegate.selected_ix, match_count - 2);
+        });
+
+        // A `:` command recalls earlier commands from the first result.
+        cx.simulate_input(":");
+        palette.update_in(cx, |palette, window, cx| {
+            palette.set_selected_index(0, None, false, window, cx)
+        });
+        cx.simulate_keystrokes("up");
+        palette.read_with(cx, |palette, cx| {
+            assert_eq!(palette.query(cx), ":earlier");
+        });
+    }
+
     #[gpui::test]
     async fn test_go_to_line(cx: &mut TestAppContext) {
         let app_state = init_test(cx);
@@ -687,6 +850,7 @@ mod tests {
                         "bindings": {
                             "cmd-n": "workspace::NewFile",
                             "enter": "menu::Confirm",
+                            "up": "menu::SelectPrevious",
                             "cmd-shift-p": "command_palette::Toggle"
                         }
                     }
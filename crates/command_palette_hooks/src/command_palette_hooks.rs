@@ -2,7 +2,7 @@
 
 #![deny(missing_docs)]
 
-use std::any::TypeId;
+use std::{any::TypeId, rc::Rc};
 
 use collections::HashSet;
 use derive_more::{Deref, DerefMut};
@@ -107,9 +107,11 @@ pub struct CommandInterceptResult {
 
 /// An interceptor for the command palette.
 #[derive(Default)]
-pub struct CommandPaletteInterceptor(
-    Option<Box<dyn Fn(&str, &App) -> Vec<CommandInterceptResult>>>,
-);
+pub struct CommandPaletteInterceptor {
+    handler: Option<Box<dyn Fn(&str, &App) -> Vec<CommandInterceptResult>>>,
+    confirm_handler: Option<Rc<dyn Fn(&str, &mut App)>>,
+    history_handler: Option<Rc<dyn Fn(&App) -> Vec<String>>>,
+}
 
 #[derive(Default)]
 struct GlobalCommandPaletteInterceptor(CommandPaletteInterceptor);
@@ -133,7 +135,7 @@ impl CommandPaletteInterceptor {
 
     /// Intercepts the given query from the command palette.
     pub fn intercept(&self, query: &str, cx: &App) -> Vec<CommandInterceptResult> {
-        if let Some(handler) = self.0.as_ref() {
+        if let Some(handler) = self.handler.as_ref() {
             (handler)(query, cx)
         } else {
             Vec::new()
@@ -142,13 +144,44 @@ impl CommandPaletteInterceptor {
 
     /// Clears the global interceptor.
     pub fn clear(&mut self) {
-        self.0 = None;
+        self.handler = None;
+        self.confirm_handler = None;
+        self.history_handler = None;
     }
 
     /// Sets the global interceptor.
     ///
     /// This will override the previous interceptor, if it exists.
     pub fn set(&mut self, handler: Box<dyn Fn(&str, &App) -> Vec<CommandInterceptResult>>) {
-        self.0 = Some(handler);
+        self.handler = Some(handler);
+    }
+
+    /// Sets a callback to run with the query whenever one of the interceptor's results is
+    /// confirmed.
+    pub fn set_confirm_handler(&mut self, handler: Rc<dyn Fn(&str, &mut App)>) {
+        self.confirm_handler = Some(handler);
+    }
+
+    /// Sets a callback listing earlier queries, oldest first, that can be recalled in the
+    /// command palette.
+    pub fn set_history_handler(&mut self, handler: Rc<dyn Fn(&App) -> Vec<String>>) {
+        self.history_handler = Some(handler);
+    }
+
+    /// Returns the global interceptor's earlier queries, oldest first.
+    pub fn history(cx: &App) -> Vec<String> {
+        Self::try_global(cx)
+            .and_then(|interceptor| interceptor.history_handler.clone())
+            .map(|handler| handler(cx))
+            .unwrap_or_default()
+    }
+
+    /// Notifies the global interceptor that one of its results was confirmed for the given query.
+    pub fn confirmed(query: &str, cx: &mut App) {
+        let handler =
+            Self::try_global(cx).and_then(|interceptor| interceptor.confirm_handler.clone());
+        if let Some(handler) = handler {
+            handler(query, cx);
+        }
     }
 }
@@ -0,0 +1,253 @@
+//! Lays out and draws commit graphs, for both git history and jj's change graph.
+
+use gpui::{Bounds, Corners, Hsla, PathBuilder, Point, canvas, fill, point, size};
+use ui::prelude::*;
+
+const LANE_WIDTH: Pixels = px(12.);
+const NODE_RADIUS: Pixels = px(3.5);
+
+/// How a single row of the commit graph is drawn.
+///
+/// Edges are split at the height of the row's node: `incoming` edges run from a lane at the
+/// top of the row to a lane at the node's height, and `outgoing` edges continue from there
+/// to a lane at the bottom of the row.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphRow {
+    pub node_lane: usize,
+    pub incoming: Vec<(usize, usize)>,
+    pub outgoing: Vec<(usize, usize)>,
+    pub is_merge: bool,
+}
+
+/// Lays out commits into lanes as they're loaded, so that history can be fetched a page at a
+/// time. Commits must be pushed in log order, with children before their parents.
+#[derive(Debug, Default)]
+pub struct CommitGraph {
+    /// The commit that each lane is waiting for.
+    lanes: Vec<Option<SharedString>>,
+    rows: Vec<GraphRow>,
+    lane_count: usize,
+}
+
+impl CommitGraph {
+    pub fn rows(&self) -> &[GraphRow] {
+        &self.rows
+    }
+
+    /// The number of lanes needed to draw every row loaded so far.
+    pub fn lane_count(&self) -> usize {
+        self.lane_count
+    }
+
+    pub fn push(&mut self, sha: &SharedString, parent_shas: &[SharedString]) {
+        let matching_lanes = self
+            .lanes
+            .iter()
+            .enumerate()
+            .filter(|(_, expected)| expected.as_ref() == Some(sha))
+            .map(|(lane, _)| lane)
+            .collect::<Vec<_>>();
+        let node_lane = match matching_lanes.first() {
+            Some(lane) => *lane,
+            None => self.free_lane(),
+        };
+
+        let mut row = GraphRow {
+            node_lane,
+            is_merge: parent_shas.len() > 1,
+            ..Default::default()
+        };
+        let mut passing_lanes = Vec::new();
+        for (lane, expected) in self.lanes.iter().enumerate() {
+            if expected.is_none() {
+                continue;
+            }
+            if matching_lanes.contains(&lane) {
+                row.incoming.push((lane, node_lane));
+            } else {
+                row.incoming.push((lane, lane));
+                passing_lanes.push(lane);
+            }
+        }
+        for lane in &matching_lanes {
+            self.lanes[*lane] = None;
+        }
+
+        for (ix, parent) in parent_shas.iter().enumerate() {
+            if let Some(lane) = self
+                .lanes
+                .iter()
+                .position(|expected| expected.as_ref() == Some(parent))
+            {
+                row.outgoing.push((node_lane, lane));
+                continue;
+            }
+
+            let lane = if ix == 0 && self.lanes[node_lane].is_none() {
+                node_lane
+            } else {
+                self.free_lane()
+            };
+            self.lanes[lane] = Some(parent.clone());
+            row.outgoing.push((node_lane, lane));
+        }
+        row.outgoing
+            .extend(passing_lanes.into_iter().map(|lane| (lane, lane)));
+
+        self.lane_count = self.lane_count.max(self.lanes.len()).max(node_lane + 1);
+        while self.lanes.last().is_some_and(Option::is_none) {
+            self.lanes.pop();
+        }
+        self.rows.push(row);
+    }
+
+    fn free_lane(&mut self) -> usize {
+        match self.lanes.iter().position(Option::is_none) {
+            Some(lane) => lane,
+            None => {
+                self.lanes.push(None);
+                self.lanes.len() - 1
+            }
+        }
+    }
+}
+
+fn lane_color(lane: usize, cx: &App) -> Hsla {
+    cx.theme().accents().color_for_index(lane as u32)
+}
+
+/// Renders the graph column for a single row. A hollow node is drawn as a ring around
+/// `background`, so that it stands out from the filled ones.
+pub fn render_graph_row(
+    row: &GraphRow,
+    lane_count: usize,
+    hollow_node: bool,
+    background: Hsla,
+    cx: &App,
+) -> impl IntoElement {
+    let row = row.clone();
+    let edge_colors = row
+        .incoming
+        .iter()
+        .chain(row.outgoing.iter())
+        .map(|(from, to)| lane_color(*from.max(to), cx))
+        .collect::<Vec<_>>();
+    let node_color = lane_color(row.node_lane, cx);
+
+    canvas(
+        |_, _, _| {},
+        move |bounds: Bounds<Pixels>, _, window: &mut Window, _| {
+            let lane_x = |lane: usize| bounds.left() + LANE_WIDTH * lane as f32 + LANE_WIDTH / 2.;
+            let middle = bounds.center().y;
+
+            let edges = row
+                .incoming
+                .iter()
+                .map(|(from, to)| {
+                    (
+                        point(lane_x(*from), bounds.top()),
+                        point(lane_x(*to), middle),
+                    )
+                })
+                .chain(row.outgoing.iter().map(|(from, to)| {
+                    (
+                        point(lane_x(*from), middle),
+                        point(lane_x(*to), bounds.bottom()),
+                    )
+                }));
+            for ((start, end), color) in edges.zip(edge_colors) {
+                paint_edge(start, end, color, window);
+            }
+
+            let node_center = point(lane_x(row.node_lane), middle);
+            let node_bounds = Bounds::new(
+                node_center - point(NODE_RADIUS, NODE_RADIUS),
+                size(NODE_RADIUS * 2., NODE_RADIUS * 2.),
+            );
+            window
+                .paint_quad(fill(node_bounds, node_color).corner_radii(Corners::all(NODE_RADIUS)));
+            if hollow_node {
+                let inset = px(1.5);
+                let inner_bounds = Bounds::new(
+                    node_bounds.origin + point(inset, inset),
+                    node_bounds.size - size(inset * 2., inset * 2.),
+                );
+                window.paint_quad(
+                    fill(inner_bounds, background).corner_radii(Corners::all(NODE_RADIUS - inset)),
+                );
+            }
+        },
+    )
+    .flex_none()
+    .h_full()
+    .w(LANE_WIDTH * lane_count as f32)
+}
+
+fn paint_edge(start: Point<Pixels>, end: Point<Pixels>, color: Hsla, window: &mut Window) {
+    let mut builder = PathBuilder::stroke(px(1.5));
+    builder.move_to(start);
+    if start.x == end.x {
+        builder.line_to(end);
+    } else {
+        builder.curve_to(end, point(end.x, start.y));
+    }
+    if let Ok(path) = builder.build() {
+        window.paint_path(path, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(commits: &[(&str, &[&str])]) -> CommitGraph {
+        let mut graph = CommitGraph::default();
+        for (sha, parents) in commits {
+            let parents = parents
+                .iter()
+                .map(|parent| SharedString::from(parent.to_string()))
+                .collect::<Vec<_>>();
+            graph.push(&sha.to_string().into(), &parents);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_branch_and_merge() {
+        //   m
+        //  / \
+        // x   y
+        //  \ /
+        //   a
+        let graph = graph(&[("m", &["x", "y"]), ("x", &["a"]), ("y", &["a"]), ("a", &[])]);
+        let rows = graph.rows();
+
+        assert_eq!(rows[0].node_lane, 0);
+        assert!(rows[0].is_merge);
+        assert_eq!(rows[0].outgoing, vec![(0, 0), (0, 1)]);
+
+        assert_eq!(rows[1].node_lane, 0);
+        assert_eq!(rows[1].incoming, vec![(0, 0), (1, 1)]);
+        assert_eq!(rows[1].outgoing, vec![(0, 0), (1, 1)]);
+
+        assert_eq!(rows[2].node_lane, 1);
+        assert_eq!(rows[2].outgoing, vec![(1, 0), (0, 0)]);
+
+        assert_eq!(rows[3].node_lane, 0);
+        assert_eq!(rows[3].incoming, vec![(0, 0)]);
+        assert_eq!(rows[3].outgoing, vec![]);
+        assert_eq!(graph.lane_count(), 2);
+    }
+
+    #[test]
+    fn test_lanes_continue_across_pages() {
+        // The parent of `b` hasn't been loaded yet, so its lane stays open.
+        let mut graph = graph(&[("c", &["b"]), ("b", &["a"])]);
+        assert_eq!(graph.rows()[1].outgoing, vec![(0, 0)]);
+
+        graph.push(&"a".into(), &[]);
+        assert_eq!(graph.rows()[2].node_lane, 0);
+        assert_eq!(graph.rows()[2].incoming, vec![(0, 0)]);
+        assert_eq!(graph.lane_count(), 1);
+    }
+}
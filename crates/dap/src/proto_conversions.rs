@@ -589,3 +589,69 @@ impl ProtoConversion for dap_types::Thread {
         }
     }
 }
+
+impl ProtoConversion for dap_types::DisassembledInstructionPresentationHint {
+    type ProtoType = proto::DapDisassembledInstructionPresentationHint;
+    type Output = Self;
+
+    fn to_proto(&self) -> Self::ProtoType {
+        match self {
+            dap_types::DisassembledInstructionPresentationHint::Invalid => {
+                proto::DapDisassembledInstructionPresentationHint::InstructionInvalid
+            }
+            _ => proto::DapDisassembledInstructionPresentationHint::InstructionNormal,
+        }
+    }
+
+    fn from_proto(payload: Self::ProtoType) -> Self {
+        match payload {
+            proto::DapDisassembledInstructionPresentationHint::InstructionNormal => {
+                dap_types::DisassembledInstructionPresentationHint::Normal
+            }
+            proto::DapDisassembledInstructionPresentationHint::InstructionInvalid => {
+                dap_types::DisassembledInstructionPresentationHint::Invalid
+            }
+        }
+    }
+}
+
+impl ProtoConversion for dap_types::DisassembledInstruction {
+    type ProtoType = proto::DapDisassembledInstruction;
+    type Output = Self;
+
+    fn to_proto(&self) -> Self::ProtoType {
+        proto::DapDisassembledInstruction {
+            address: self.address.clone(),
+            instruction_bytes: self.instruction_bytes.clone(),
+            instruction: self.instruction.clone(),
+            symbol: self.symbol.clone(),
+            location: self.location.as_ref().map(|location| location.to_proto()),
+            line: self.line,
+            column: self.column,
+            end_line: self.end_line,
+            end_column: self.end_column,
+            presentation_hint: self
+                .presentation_hint
+                .as_ref()
+                .map(|hint| hint.to_proto().into()),
+        }
+    }
+
+    fn from_proto(payload: Self::ProtoType) -> Self {
+        Self {
+            address: payload.address,
+            instruction_bytes: payload.instruction_bytes,
+            instruction: payload.instruction,
+            symbol: payload.symbol,
+            location: payload.location.map(Source::from_proto),
+            line: payload.line,
+            column: payload.column,
+            end_line: payload.end_line,
+            end_column: payload.end_column,
+            presentation_hint: payload
+                .presentation_hint
+                .and_then(proto::DapDisassembledInstructionPresentationHint::from_i32)
+                .map(dap_types::DisassembledInstructionPresentationHint::from_proto),
+        }
+    }
+}
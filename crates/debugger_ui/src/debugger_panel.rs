@@ -2,11 +2,12 @@ use crate::persistence::DebuggerPaneItem;
 use crate::session::DebugSession;
 use crate::session::running::RunningState;
 use crate::{
-    ClearAllBreakpoints, Continue, Detach, FocusBreakpointList, FocusConsole, FocusFrames,
-    FocusLoadedSources, FocusModules, FocusTerminal, FocusVariables, NewProcessModal,
-    NewProcessMode, Pause, Restart, ShowStackTrace, StepBack, StepInto, StepOut, StepOver, Stop,
-    ToggleExpandItem, ToggleIgnoreBreakpoints, ToggleSessionPicker, ToggleThreadPicker,
-    persistence, spawn_task_or_modal,
+    ClearAllBreakpoints, Continue, Detach, FocusBreakpointList, FocusConsole, FocusDisassembly,
+    FocusFrames, FocusLoadedSources, FocusModules, FocusTerminal, FocusVariables, FocusWatch,
+    NewProcessModal, NewProcessMode, Pause, Restart, ShowStackTrace, StepBack, StepInto,
+    StepIntoInstruction, StepOut, StepOver, StepOverInstruction, Stop, ToggleExpandItem,
+    ToggleIgnoreBreakpoints, ToggleSessionPicker, ToggleThreadPicker, persistence,
+    spawn_task_or_modal,
 };
 use anyhow::Result;
 use command_palette_hooks::CommandPaletteFilter;
@@ -141,7 +142,13 @@ impl DebugPanel {
     }
 
     pub(crate) fn filter_action_types(&self, cx: &mut App) {
-        let (has_active_session, supports_restart, support_step_back, status) = self
+        let (
+            has_active_session,
+            supports_restart,
+            support_step_back,
+            supports_stepping_granularity,
+            status,
+        ) = self
             .active_session()
             .map(|item| {
                 let running = item.read(cx).running_state().clone();
@@ -150,10 +157,11 @@ impl DebugPanel {
                     !running.read(cx).session().read(cx).is_terminated(),
                     caps.supports_restart_request.unwrap_or_default(),
                     caps.supports_step_back.unwrap_or_default(),
+                    caps.supports_stepping_granularity.unwrap_or_default(),
                     running.read(cx).thread_status(cx),
                 )
             })
-            .unwrap_or((false, false, false, None));
+            .unwrap_or((false, false, false, false, None));
 
         let filter = CommandPaletteFilter::global_mut(cx);
         let debugger_action_types = [
@@ -175,6 +183,10 @@ impl DebugPanel {
         ];
 
         let step_back_action_type = [TypeId::of::<StepBack>()];
+        let instruction_step_action_types = [
+            TypeId::of::<StepOverInstruction>(),
+            TypeId::of::<StepIntoInstruction>(),
+        ];
         let restart_action_type = [TypeId::of::<Restart>()];
 
         if has_active_session {
@@ -206,6 +218,12 @@ impl DebugPanel {
                     filter.hide_action_types(&stopped_action_type);
                 }
             }
+
+            if supports_stepping_granularity && status == Some(ThreadStatus::Stopped) {
+                filter.show_action_types(instruction_step_action_types.iter());
+            } else {
+                filter.hide_action_types(&instruction_step_action_types);
+            }
         } else {
             // show only the `debug: start`
             filter.hide_action_types(&debugger_action_types);
@@ -213,6 +231,7 @@ impl DebugPanel {
             filter.hide_action_types(&restart_action_type);
             filter.hide_action_types(&running_action_types);
             filter.hide_action_types(&stopped_action_type);
+            filter.hide_action_types(&instruction_step_action_types);
         }
     }
 
@@ -920,6 +939,31 @@ impl DebugPanel {
         }
     }
 
+    fn show_disassembly(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(running) = self
+            .active_session()
+            .map(|session| session.read(cx).running_state().clone())
+        else {
+            return;
+        };
+        running.update(cx, |running, cx| {
+            if DebuggerPaneItem::Disassembly.is_supported(&running.capabilities(cx)) {
+                running.ensure_pane_item(DebuggerPaneItem::Disassembly, window, cx);
+                running.activate_item(DebuggerPaneItem::Disassembly, window, cx);
+            }
+        });
+    }
+
+    fn show_watch(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(running) = self
+            .active_session()
+            .map(|session| session.read(cx).running_state().clone())
+        else {
+            return;
+        };
+        running.update(cx, |running, cx| running.show_watch(window, cx));
+    }
+
     pub(crate) fn activate_session_by_id(
         &mut self,
         session_id: SessionId,
@@ -1325,6 +1369,24 @@ impl Render for DebugPanel {
                     .ok();
                 }
             })
+            .on_action({
+                let this = this.clone();
+                move |_: &FocusDisassembly, window, cx| {
+                    this.update(cx, |this, cx| {
+                        this.show_disassembly(window, cx);
+                    })
+                    .ok();
+                }
+            })
+            .on_action({
+                let this = this.clone();
+                move |_: &FocusWatch, window, cx| {
+                    this.update(cx, |this, cx| {
+                        this.show_watch(window, cx);
+                    })
+                    .ok();
+                }
+            })
             .on_action({
                 let this = this.clone();
                 move |_: &ToggleThreadPicker, window, cx| {
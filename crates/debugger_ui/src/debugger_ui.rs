@@ -35,6 +35,8 @@ actions!(
         StepOver,
         StepOut,
         StepBack,
+        StepIntoInstruction,
+        StepOverInstruction,
         Stop,
         ToggleIgnoreBreakpoints,
         ClearAllBreakpoints,
@@ -45,6 +47,8 @@ actions!(
         FocusModules,
         FocusLoadedSources,
         FocusTerminal,
+        FocusDisassembly,
+        FocusWatch,
         ShowStackTrace,
         ToggleThreadPicker,
         ToggleSessionPicker,
@@ -123,6 +127,28 @@ pub fn init(cx: &mut App) {
                         }
                     }
                 })
+                .register_action(|workspace, _: &StepIntoInstruction, _, cx| {
+                    if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
+                        if let Some(active_item) = debug_panel
+                            .read(cx)
+                            .active_session()
+                            .map(|session| session.read(cx).running_state().clone())
+                        {
+                            active_item.update(cx, |item, cx| item.step_in_instruction(cx))
+                        }
+                    }
+                })
+                .register_action(|workspace, _: &StepOverInstruction, _, cx| {
+                    if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
+                        if let Some(active_item) = debug_panel
+                            .read(cx)
+                            .active_session()
+                            .map(|session| session.read(cx).running_state().clone())
+                        {
+                            active_item.update(cx, |item, cx| item.step_over_instruction(cx))
+                        }
+                    }
+                })
                 .register_action(|workspace, _: &StepOut, _, cx| {
                     if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
                         if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
@@ -11,8 +11,9 @@ use workspace::{Member, Pane, PaneAxis, Workspace};
 
 use crate::session::running::{
     self, DebugTerminal, RunningState, SubView, breakpoint_list::BreakpointList, console::Console,
-    loaded_source_list::LoadedSourceList, module_list::ModuleList,
-    stack_frame_list::StackFrameList, variable_list::VariableList,
+    disassembly_view::DisassemblyView, loaded_source_list::LoadedSourceList,
+    memory_view::MemoryView, module_list::ModuleList, stack_frame_list::StackFrameList,
+    variable_list::VariableList,
 };
 
 #[derive(Clone, Hash, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -24,6 +25,9 @@ pub(crate) enum DebuggerPaneItem {
     Modules,
     LoadedSources,
     Terminal,
+    Disassembly,
+    Memory,
+    Watch,
 }
 
 impl DebuggerPaneItem {
@@ -36,6 +40,9 @@ impl DebuggerPaneItem {
             DebuggerPaneItem::Modules,
             DebuggerPaneItem::LoadedSources,
             DebuggerPaneItem::Terminal,
+            DebuggerPaneItem::Disassembly,
+            DebuggerPaneItem::Memory,
+            DebuggerPaneItem::Watch,
         ];
         VARIANTS
     }
@@ -46,6 +53,12 @@ impl DebuggerPaneItem {
             DebuggerPaneItem::LoadedSources => capabilities
                 .supports_loaded_sources_request
                 .unwrap_or_default(),
+            DebuggerPaneItem::Disassembly => capabilities
+                .supports_disassemble_request
+                .unwrap_or_default(),
+            DebuggerPaneItem::Memory => capabilities
+                .supports_read_memory_request
+                .unwrap_or_default(),
             _ => true,
         }
     }
@@ -59,6 +72,9 @@ impl DebuggerPaneItem {
             DebuggerPaneItem::Modules => SharedString::new_static("Modules"),
             DebuggerPaneItem::LoadedSources => SharedString::new_static("Sources"),
             DebuggerPaneItem::Terminal => SharedString::new_static("Terminal"),
+            DebuggerPaneItem::Disassembly => SharedString::new_static("Disassembly"),
+            DebuggerPaneItem::Memory => SharedString::new_static("Memory"),
+            DebuggerPaneItem::Watch => SharedString::new_static("Watch"),
         }
     }
     pub(crate) fn tab_tooltip(self) -> SharedString {
@@ -80,6 +96,15 @@ impl DebuggerPaneItem {
             DebuggerPaneItem::Terminal => {
                 "Provides an interactive terminal session within the debugging environment."
             }
+            DebuggerPaneItem::Disassembly => {
+                "Shows the machine instructions around the current instruction pointer."
+            }
+            DebuggerPaneItem::Memory => {
+                "Shows and edits the raw memory of the program, starting at a variable."
+            }
+            DebuggerPaneItem::Watch => {
+                "Evaluates the project's watch expressions in the current stack frame."
+            }
         };
         SharedString::new_static(tooltip)
     }
@@ -204,6 +229,9 @@ pub(crate) fn deserialize_pane_layout(
     breakpoint_list: &Entity<BreakpointList>,
     loaded_sources: &Entity<LoadedSourceList>,
     terminal: &Entity<DebugTerminal>,
+    disassembly_view: &Entity<DisassemblyView>,
+    memory_view: &Entity<MemoryView>,
+    watch_list: &Entity<VariableList>,
     subscriptions: &mut HashMap<EntityId, Subscription>,
     window: &mut Window,
     cx: &mut Context<RunningState>,
@@ -228,6 +256,9 @@ pub(crate) fn deserialize_pane_layout(
                     breakpoint_list,
                     loaded_sources,
                     terminal,
+                    disassembly_view,
+                    memory_view,
+                    watch_list,
                     subscriptions,
                     window,
                     cx,
@@ -317,6 +348,27 @@ pub(crate) fn deserialize_pane_layout(
                         None,
                         cx,
                     )),
+                    DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                        disassembly_view.focus_handle(cx),
+                        disassembly_view.clone().into(),
+                        DebuggerPaneItem::Disassembly,
+                        None,
+                        cx,
+                    )),
+                    DebuggerPaneItem::Memory => Box::new(SubView::new(
+                        memory_view.focus_handle(cx),
+                        memory_view.clone().into(),
+                        DebuggerPaneItem::Memory,
+                        None,
+                        cx,
+                    )),
+                    DebuggerPaneItem::Watch => Box::new(SubView::new(
+                        watch_list.focus_handle(cx),
+                        watch_list.clone().into(),
+                        DebuggerPaneItem::Watch,
+                        None,
+                        cx,
+                    )),
                 })
                 .collect();
 
@@ -1,6 +1,8 @@
 pub(crate) mod breakpoint_list;
 pub(crate) mod console;
+pub(crate) mod disassembly_view;
 pub(crate) mod loaded_source_list;
+pub(crate) mod memory_view;
 pub(crate) mod module_list;
 pub mod stack_frame_list;
 pub mod variable_list;
@@ -19,11 +21,12 @@ use breakpoint_list::BreakpointList;
 use collections::{HashMap, IndexMap};
 use console::Console;
 use dap::{
-    Capabilities, DapRegistry, RunInTerminalRequestArguments, Thread,
+    Capabilities, DapRegistry, RunInTerminalRequestArguments, SteppingGranularity, Thread,
     adapters::{DebugAdapterName, DebugTaskDefinition},
     client::SessionId,
     debugger_settings::DebuggerSettings,
 };
+use disassembly_view::DisassemblyView;
 use futures::{SinkExt, channel::mpsc};
 use gpui::{
     Action as _, AnyView, AppContext, Axis, Entity, EntityId, EventEmitter, FocusHandle, Focusable,
@@ -31,6 +34,7 @@ use gpui::{
 };
 use language::Buffer;
 use loaded_source_list::LoadedSourceList;
+use memory_view::MemoryView;
 use module_list::ModuleList;
 use project::{
     Project, WorktreeId,
@@ -53,7 +57,7 @@ use ui::{
     VisibleOnHover, VisualContext, Window, div, h_flex, v_flex,
 };
 use util::ResultExt;
-use variable_list::VariableList;
+use variable_list::{VariableList, VariableListEvent};
 use workspace::{
     ActivePaneDecorator, DraggedTab, Item, ItemHandle, Member, Pane, PaneGroup, SplitDirection,
     Workspace, item::TabContentParams, move_item, pane::Event,
@@ -74,6 +78,9 @@ pub struct RunningState {
     module_list: Entity<module_list::ModuleList>,
     console: Entity<Console>,
     breakpoint_list: Entity<BreakpointList>,
+    disassembly_view: Entity<DisassemblyView>,
+    memory_view: Entity<MemoryView>,
+    watch_list: Entity<VariableList>,
     panes: PaneGroup,
     active_pane: Entity<Pane>,
     pane_close_subscriptions: HashMap<EntityId, Subscription>,
@@ -122,6 +129,9 @@ impl Render for RunningState {
         self.variable_list.update(cx, |this, cx| {
             this.disabled(thread_status != ThreadStatus::Stopped, cx);
         });
+        self.watch_list.update(cx, |this, cx| {
+            this.disabled(thread_status != ThreadStatus::Stopped, cx);
+        });
         v_flex()
             .size_full()
             .key_context("DebugSessionItem")
@@ -622,6 +632,10 @@ impl RunningState {
         let variable_list =
             cx.new(|cx| VariableList::new(session.clone(), stack_frame_list.clone(), window, cx));
 
+        let watch_list = cx.new(|cx| {
+            VariableList::new_watch(session.clone(), stack_frame_list.clone(), window, cx)
+        });
+
         let module_list = cx.new(|cx| ModuleList::new(session.clone(), workspace.clone(), cx));
 
         let loaded_source_list = cx.new(|cx| LoadedSourceList::new(session.clone(), cx));
@@ -636,10 +650,42 @@ impl RunningState {
             )
         });
 
-        let breakpoint_list = BreakpointList::new(session.clone(), workspace.clone(), &project, cx);
+        let breakpoint_list =
+            BreakpointList::new(session.clone(), workspace.clone(), &project, window, cx);
+
+        let disassembly_view = cx.new(|cx| {
+            DisassemblyView::new(
+                session.clone(),
+                stack_frame_list.clone(),
+                project.downgrade(),
+                cx,
+            )
+        });
+
+        let memory_view = cx.new(|cx| MemoryView::new(session.clone(), cx));
 
         let _subscriptions = vec![
             cx.observe(&module_list, |_, _, cx| cx.notify()),
+            cx.subscribe_in(
+                &variable_list,
+                window,
+                |this, _, event, window, cx| match event {
+                    VariableListEvent::ViewMemory(memory_reference) => {
+                        this.view_memory(memory_reference.clone(), window, cx);
+                    }
+                    VariableListEvent::WatchAdded => this.show_watch(window, cx),
+                },
+            ),
+            cx.subscribe_in(
+                &watch_list,
+                window,
+                |this, _, event, window, cx| match event {
+                    VariableListEvent::ViewMemory(memory_reference) => {
+                        this.view_memory(memory_reference.clone(), window, cx);
+                    }
+                    VariableListEvent::WatchAdded => {}
+                },
+            ),
             cx.subscribe_in(&session, window, |this, _, event, window, cx| {
                 match event {
                     SessionEvent::Stopped(thread_id) => {
@@ -679,6 +725,12 @@ impl RunningState {
                         {
                             this.remove_pane_item(DebuggerPaneItem::LoadedSources, window, cx);
                         }
+                        if !capabilities.supports_disassemble_request.unwrap_or(false) {
+                            this.remove_pane_item(DebuggerPaneItem::Disassembly, window, cx);
+                        }
+                        if !capabilities.supports_read_memory_request.unwrap_or(false) {
+                            this.remove_pane_item(DebuggerPaneItem::Memory, window, cx);
+                        }
                     }
                     SessionEvent::RunInTerminal { request, sender } => this
                         .handle_run_in_terminal(request, sender.clone(), window, cx)
@@ -707,6 +759,9 @@ impl RunningState {
                 &breakpoint_list,
                 &loaded_source_list,
                 &debug_terminal,
+                &disassembly_view,
+                &memory_view,
+                &watch_list,
                 &mut pane_close_subscriptions,
                 window,
                 cx,
@@ -749,6 +804,9 @@ impl RunningState {
             module_list,
             console,
             breakpoint_list,
+            disassembly_view,
+            memory_view,
+            watch_list,
             loaded_sources_list: loaded_source_list,
             pane_close_subscriptions,
             debug_terminal,
@@ -1040,6 +1098,7 @@ impl RunningState {
                 show_summary: false,
                 show_command: false,
                 show_rerun: false,
+                problem_matchers: Vec::new(),
             })
         } else {
             TerminalKind::Shell(cwd.map(|c| c.to_path_buf()))
@@ -1142,6 +1201,27 @@ impl RunningState {
                 None,
                 cx,
             )),
+            DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                self.disassembly_view.focus_handle(cx),
+                self.disassembly_view.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
+            DebuggerPaneItem::Memory => Box::new(SubView::new(
+                self.memory_view.focus_handle(cx),
+                self.memory_view.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
+            DebuggerPaneItem::Watch => Box::new(SubView::new(
+                self.watch_list.focus_handle(cx),
+                self.watch_list.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
         }
     }
 
@@ -1298,6 +1378,7 @@ impl RunningState {
 
     pub(crate) fn has_open_context_menu(&self, cx: &App) -> bool {
         self.variable_list.read(cx).has_open_context_menu()
+            || self.watch_list.read(cx).has_open_context_menu()
     }
 
     pub fn session(&self) -> &Entity<Session> {
@@ -1326,6 +1407,39 @@ impl RunningState {
         &self.module_list
     }
 
+    #[cfg(test)]
+    pub(crate) fn disassembly_view(&self) -> &Entity<DisassemblyView> {
+        &self.disassembly_view
+    }
+
+    #[cfg(test)]
+    pub(crate) fn memory_view(&self) -> &Entity<MemoryView> {
+        &self.memory_view
+    }
+
+    fn view_memory(
+        &mut self,
+        memory_reference: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.memory_view.update(cx, |view, cx| {
+            view.show_memory(memory_reference, cx);
+        });
+        self.ensure_pane_item(DebuggerPaneItem::Memory, window, cx);
+        self.activate_item(DebuggerPaneItem::Memory, window, cx);
+    }
+
+    pub(crate) fn show_watch(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.ensure_pane_item(DebuggerPaneItem::Watch, window, cx);
+        self.activate_item(DebuggerPaneItem::Watch, window, cx);
+    }
+
+    #[cfg(test)]
+    pub(crate) fn watch_list(&self) -> &Entity<VariableList> {
+        &self.watch_list
+    }
+
     pub(crate) fn activate_item(&self, item: DebuggerPaneItem, window: &mut Window, cx: &mut App) {
         let (variable_list_position, pane) = self
             .panes
@@ -1460,6 +1574,26 @@ impl RunningState {
         });
     }
 
+    pub(crate) fn step_over_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_over(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
+    pub(crate) fn step_in_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_in(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
     pub fn restart_session(&self, cx: &mut Context<Self>) {
         self.session().update(cx, |state, cx| {
             state.restart(None, cx);
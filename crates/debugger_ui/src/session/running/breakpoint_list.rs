@@ -14,16 +14,18 @@ use language::Point;
 use project::{
     Project,
     debugger::{
-        breakpoint_store::{BreakpointEditAction, BreakpointStore, SourceBreakpoint},
+        breakpoint_store::{
+            self, BreakpointEditAction, BreakpointStore, BreakpointStoreEvent, SourceBreakpoint,
+        },
         session::Session,
     },
     worktree_store::WorktreeStore,
 };
 use ui::{
-    App, ButtonCommon, Clickable, Color, Context, Div, FluentBuilder as _, Icon, IconButton,
-    IconName, Indicator, InteractiveElement, IntoElement, Label, LabelCommon, LabelSize, ListItem,
-    ParentElement, Render, Scrollbar, ScrollbarState, SharedString, StatefulInteractiveElement,
-    Styled, Toggleable, Tooltip, Window, div, h_flex, px, v_flex,
+    ActiveTheme, App, ButtonCommon, Clickable, Color, Context, Div, FluentBuilder as _, Icon,
+    IconButton, IconName, Indicator, InteractiveElement, IntoElement, Label, LabelCommon,
+    LabelSize, ListItem, ParentElement, Render, Scrollbar, ScrollbarState, SharedString,
+    StatefulInteractiveElement, Styled, Toggleable, Tooltip, Window, div, h_flex, px, v_flex,
 };
 use util::ResultExt;
 use workspace::Workspace;
@@ -41,6 +43,7 @@ pub(crate) struct BreakpointList {
     focus_handle: FocusHandle,
     scroll_handle: UniformListScrollHandle,
     selected_ix: Option<usize>,
+    function_breakpoint_editor: Entity<Editor>,
 }
 
 impl Focusable for BreakpointList {
@@ -54,6 +57,7 @@ impl BreakpointList {
         session: Entity<Session>,
         workspace: WeakEntity<Workspace>,
         project: &Entity<Project>,
+        window: &mut Window,
         cx: &mut App,
     ) -> Entity<Self> {
         let project = project.read(cx);
@@ -62,8 +66,19 @@ impl BreakpointList {
         let focus_handle = cx.focus_handle();
         let scroll_handle = UniformListScrollHandle::new();
         let scrollbar_state = ScrollbarState::new(scroll_handle.clone());
+        let function_breakpoint_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Add a function breakpoint by name", cx);
+            editor
+        });
 
-        cx.new(|_| {
+        cx.new(|cx| {
+            cx.subscribe(&breakpoint_store, |_, _, event, cx| match event {
+                BreakpointStoreEvent::FunctionBreakpointsUpdated
+                | BreakpointStoreEvent::DataBreakpointsUpdated => cx.notify(),
+                _ => {}
+            })
+            .detach();
             Self {
                 breakpoint_store,
                 worktree_store,
@@ -77,10 +92,53 @@ impl BreakpointList {
                 focus_handle,
                 scroll_handle,
                 selected_ix: None,
+                function_breakpoint_editor,
             }
         })
     }
 
+    fn add_function_breakpoint(
+        &mut self,
+        _: &menu::Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let name = self.function_breakpoint_editor.update(cx, |editor, cx| {
+            let name = editor.text(cx);
+            editor.clear(window, cx);
+            name
+        });
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        self.breakpoint_store.update(cx, |breakpoint_store, cx| {
+            breakpoint_store.add_function_breakpoint(name.into(), cx);
+        });
+    }
+
+    fn edit_function_breakpoint(
+        &mut self,
+        name: &str,
+        action: BreakpointEditAction,
+        cx: &mut Context<Self>,
+    ) {
+        self.breakpoint_store.update(cx, |breakpoint_store, cx| {
+            breakpoint_store.edit_function_breakpoint(name, action, cx);
+        })
+    }
+
+    fn edit_data_breakpoint(
+        &mut self,
+        data_id: &str,
+        action: BreakpointEditAction,
+        cx: &mut Context<Self>,
+    ) {
+        self.breakpoint_store.update(cx, |breakpoint_store, cx| {
+            breakpoint_store.edit_data_breakpoint(data_id, action, cx);
+        })
+    }
+
     fn edit_line_breakpoint(
         &mut self,
         path: Arc<Path>,
@@ -207,7 +265,9 @@ impl BreakpointList {
                 let row = line_breakpoint.breakpoint.row;
                 self.go_to_line_breakpoint(path, row, window, cx);
             }
-            BreakpointEntryKind::ExceptionBreakpoint(_) => {}
+            BreakpointEntryKind::ExceptionBreakpoint(_)
+            | BreakpointEntryKind::FunctionBreakpoint(_)
+            | BreakpointEntryKind::DataBreakpoint(_) => {}
         }
     }
 
@@ -233,6 +293,14 @@ impl BreakpointList {
                     session.toggle_exception_breakpoint(&id, cx);
                 });
             }
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                let name = function_breakpoint.breakpoint.name.clone();
+                self.edit_function_breakpoint(&name, BreakpointEditAction::InvertState, cx);
+            }
+            BreakpointEntryKind::DataBreakpoint(data_breakpoint) => {
+                let data_id = data_breakpoint.breakpoint.data_id.clone();
+                self.edit_data_breakpoint(&data_id, BreakpointEditAction::InvertState, cx);
+            }
         }
         cx.notify();
     }
@@ -253,6 +321,14 @@ impl BreakpointList {
                 let row = line_breakpoint.breakpoint.row;
                 self.edit_line_breakpoint(path, row, BreakpointEditAction::Toggle, cx);
             }
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                let name = function_breakpoint.breakpoint.name.clone();
+                self.edit_function_breakpoint(&name, BreakpointEditAction::Toggle, cx);
+            }
+            BreakpointEntryKind::DataBreakpoint(data_breakpoint) => {
+                let data_id = data_breakpoint.breakpoint.data_id.clone();
+                self.edit_data_breakpoint(&data_id, BreakpointEditAction::Toggle, cx);
+            }
             BreakpointEntryKind::ExceptionBreakpoint(_) => {}
         }
         cx.notify();
@@ -334,6 +410,34 @@ impl BreakpointList {
                 .children(Scrollbar::vertical(self.scrollbar_state.clone())),
         )
     }
+
+    fn render_function_breakpoint_editor(
+        &self,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let supports_function_breakpoints = self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_function_breakpoints
+            .unwrap_or_default();
+        supports_function_breakpoints.then(|| {
+            h_flex()
+                .on_action(cx.listener(Self::add_function_breakpoint))
+                .w_full()
+                .gap_1()
+                .px_1()
+                .py_0p5()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Icon::new(IconName::Plus)
+                        .size(ui::IconSize::XSmall)
+                        .color(Color::Muted),
+                )
+                .child(self.function_breakpoint_editor.clone())
+        })
+    }
 }
 impl Render for BreakpointList {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl ui::IntoElement {
@@ -385,6 +489,27 @@ impl Render for BreakpointList {
                 })
             })
         });
+        let breakpoint_store = self.breakpoint_store.read(cx);
+        let function_breakpoints = breakpoint_store
+            .function_breakpoints()
+            .iter()
+            .map(|breakpoint| BreakpointEntry {
+                kind: BreakpointEntryKind::FunctionBreakpoint(FunctionBreakpoint {
+                    breakpoint: breakpoint.clone(),
+                }),
+                weak: weak.clone(),
+            })
+            .collect::<Vec<_>>();
+        let data_breakpoints = breakpoint_store
+            .data_breakpoints()
+            .iter()
+            .map(|breakpoint| BreakpointEntry {
+                kind: BreakpointEntryKind::DataBreakpoint(DataBreakpoint {
+                    breakpoint: breakpoint.clone(),
+                }),
+                weak: weak.clone(),
+            })
+            .collect::<Vec<_>>();
         let exception_breakpoints =
             self.session
                 .read(cx)
@@ -397,32 +522,43 @@ impl Render for BreakpointList {
                     }),
                     weak: weak.clone(),
                 });
-        self.breakpoints
-            .extend(breakpoints.chain(exception_breakpoints));
+        self.breakpoints.extend(
+            breakpoints
+                .chain(function_breakpoints)
+                .chain(data_breakpoints)
+                .chain(exception_breakpoints),
+        );
         v_flex()
-            .id("breakpoint-list")
-            .key_context("BreakpointList")
-            .track_focus(&self.focus_handle)
-            .on_hover(cx.listener(|this, hovered, window, cx| {
-                if *hovered {
-                    this.show_scrollbar = true;
-                    this.hide_scrollbar_task.take();
-                    cx.notify();
-                } else if !this.focus_handle.contains_focused(window, cx) {
-                    this.hide_scrollbar(window, cx);
-                }
-            }))
-            .on_action(cx.listener(Self::select_next))
-            .on_action(cx.listener(Self::select_previous))
-            .on_action(cx.listener(Self::select_first))
-            .on_action(cx.listener(Self::select_last))
-            .on_action(cx.listener(Self::confirm))
-            .on_action(cx.listener(Self::toggle_enable_breakpoint))
-            .on_action(cx.listener(Self::unset_breakpoint))
             .size_full()
-            .m_0p5()
-            .child(self.render_list(window, cx))
-            .children(self.render_vertical_scrollbar(cx))
+            .child(
+                v_flex()
+                    .id("breakpoint-list")
+                    .key_context("BreakpointList")
+                    .track_focus(&self.focus_handle)
+                    .on_hover(cx.listener(|this, hovered, window, cx| {
+                        if *hovered {
+                            this.show_scrollbar = true;
+                            this.hide_scrollbar_task.take();
+                            cx.notify();
+                        } else if !this.focus_handle.contains_focused(window, cx) {
+                            this.hide_scrollbar(window, cx);
+                        }
+                    }))
+                    .on_action(cx.listener(Self::select_next))
+                    .on_action(cx.listener(Self::select_previous))
+                    .on_action(cx.listener(Self::select_first))
+                    .on_action(cx.listener(Self::select_last))
+                    .on_action(cx.listener(Self::confirm))
+                    .on_action(cx.listener(Self::toggle_enable_breakpoint))
+                    .on_action(cx.listener(Self::unset_breakpoint))
+                    .flex_1()
+                    .min_h_0()
+                    .m_0p5()
+                    .child(self.render_list(window, cx))
+                    .children(self.render_vertical_scrollbar(cx)),
+            )
+            // Kept out of the list's key context, so that typing doesn't trigger its bindings.
+            .children(self.render_function_breakpoint_editor(cx))
     }
 }
 #[derive(Clone, Debug)]
@@ -664,10 +800,169 @@ impl ExceptionBreakpoint {
         )
     }
 }
+/// Renders a breakpoint that isn't tied to a source location, with buttons to toggle and remove it.
+fn render_named_breakpoint(
+    id: SharedString,
+    ix: usize,
+    label: SharedString,
+    detail: Option<SharedString>,
+    is_enabled: bool,
+    focus_handle: FocusHandle,
+    list: WeakEntity<BreakpointList>,
+    edit: impl Fn(&mut BreakpointList, BreakpointEditAction, &mut Context<BreakpointList>)
+    + Clone
+    + 'static,
+) -> ListItem {
+    let icon_name = if is_enabled {
+        IconName::DebugBreakpoint
+    } else {
+        IconName::DebugDisabledBreakpoint
+    };
+    let indicator = div()
+        .id(SharedString::from(format!("{id}-toggle")))
+        .cursor_pointer()
+        .tooltip({
+            let focus_handle = focus_handle.clone();
+            move |window, cx| {
+                Tooltip::for_action_in(
+                    if is_enabled {
+                        "Disable Breakpoint"
+                    } else {
+                        "Enable Breakpoint"
+                    },
+                    &ToggleEnableBreakpoint,
+                    &focus_handle,
+                    window,
+                    cx,
+                )
+            }
+        })
+        .on_click({
+            let list = list.clone();
+            let edit = edit.clone();
+            move |_, _, cx| {
+                list.update(cx, |list, cx| {
+                    edit(list, BreakpointEditAction::InvertState, cx);
+                })
+                .ok();
+            }
+        })
+        .child(Indicator::icon(Icon::new(icon_name)).color(Color::Debugger))
+        .on_mouse_down(MouseButton::Left, move |_, _, _| {});
+
+    ListItem::new(id.clone())
+        .on_click({
+            let list = list.clone();
+            move |_, _, cx| {
+                list.update(cx, |list, cx| list.select_ix(Some(ix), cx))
+                    .ok();
+            }
+        })
+        .start_slot(indicator)
+        .rounded()
+        .on_secondary_mouse_down(|_, _, cx| {
+            cx.stop_propagation();
+        })
+        .end_hover_slot(
+            IconButton::new(SharedString::from(format!("{id}-remove")), IconName::Close)
+                .on_click(move |_, _, cx| {
+                    list.update(cx, |list, cx| {
+                        edit(list, BreakpointEditAction::Toggle, cx);
+                    })
+                    .ok();
+                })
+                .tooltip(move |window, cx| {
+                    Tooltip::for_action_in(
+                        "Unset Breakpoint",
+                        &UnsetBreakpoint,
+                        &focus_handle,
+                        window,
+                        cx,
+                    )
+                })
+                .icon_size(ui::IconSize::Indicator),
+        )
+        .child(
+            h_flex()
+                .py_1()
+                .gap_1()
+                .min_h(px(22.))
+                .child(
+                    Label::new(label)
+                        .size(LabelSize::Small)
+                        .line_height_style(ui::LineHeightStyle::UiLabel),
+                )
+                .children(detail.map(|detail| {
+                    Label::new(detail)
+                        .color(Color::Muted)
+                        .size(LabelSize::Small)
+                        .line_height_style(ui::LineHeightStyle::UiLabel)
+                })),
+        )
+}
+
+#[derive(Clone, Debug)]
+struct FunctionBreakpoint {
+    breakpoint: breakpoint_store::FunctionBreakpoint,
+}
+
+impl FunctionBreakpoint {
+    fn render(
+        &mut self,
+        ix: usize,
+        focus_handle: FocusHandle,
+        list: WeakEntity<BreakpointList>,
+    ) -> ListItem {
+        let name = self.breakpoint.name.clone();
+        render_named_breakpoint(
+            SharedString::from(format!("function-breakpoint-ui-item-{name}")),
+            ix,
+            SharedString::from(format!("{name}()")),
+            Some("function".into()),
+            self.breakpoint.state.is_enabled(),
+            focus_handle,
+            list,
+            move |list, action, cx| list.edit_function_breakpoint(&name, action, cx),
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DataBreakpoint {
+    breakpoint: breakpoint_store::DataBreakpoint,
+}
+
+impl DataBreakpoint {
+    fn render(
+        &mut self,
+        ix: usize,
+        focus_handle: FocusHandle,
+        list: WeakEntity<BreakpointList>,
+    ) -> ListItem {
+        let data_id = self.breakpoint.data_id.clone();
+        let detail = match self.breakpoint.access_type {
+            Some(access_type) => format!("data, on {}", access_type.label()),
+            None => "data".to_owned(),
+        };
+        render_named_breakpoint(
+            SharedString::from(format!("data-breakpoint-ui-item-{data_id}")),
+            ix,
+            SharedString::from(self.breakpoint.description.to_string()),
+            Some(detail.into()),
+            self.breakpoint.state.is_enabled(),
+            focus_handle,
+            list,
+            move |list, action, cx| list.edit_data_breakpoint(&data_id, action, cx),
+        )
+    }
+}
+
 #[derive(Clone, Debug)]
 enum BreakpointEntryKind {
     LineBreakpoint(LineBreakpoint),
     ExceptionBreakpoint(ExceptionBreakpoint),
+    FunctionBreakpoint(FunctionBreakpoint),
+    DataBreakpoint(DataBreakpoint),
 }
 
 #[derive(Clone, Debug)]
@@ -691,6 +986,12 @@ impl BreakpointEntry {
             BreakpointEntryKind::ExceptionBreakpoint(exception_breakpoint) => {
                 exception_breakpoint.render(ix, focus_handle, self.weak.clone())
             }
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                function_breakpoint.render(ix, focus_handle, self.weak.clone())
+            }
+            BreakpointEntryKind::DataBreakpoint(data_breakpoint) => {
+                data_breakpoint.render(ix, focus_handle, self.weak.clone())
+            }
         }
     }
 }
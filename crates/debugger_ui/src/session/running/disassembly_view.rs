@@ -0,0 +1,553 @@
+use std::{path::Path, sync::Arc};
+
+use collections::HashMap;
+use dap::{DisassembledInstruction, DisassembledInstructionPresentationHint, StackFrameId};
+use fs::Fs as _;
+use gpui::{
+    Action as _, AnyElement, Entity, FocusHandle, Focusable, MouseButton, ScrollStrategy, Stateful,
+    Subscription, Task, UniformListScrollHandle, WeakEntity, uniform_list,
+};
+use project::{
+    Project,
+    debugger::session::{Session, SessionEvent},
+};
+use ui::{Indicator, Scrollbar, ScrollbarState, Tooltip, prelude::*};
+use util::ResultExt;
+
+use super::stack_frame_list::{StackFrameList, StackFrameListEvent};
+use crate::{StepIntoInstruction, StepOverInstruction};
+
+/// How many instructions are disassembled on each side of the instruction pointer.
+const INSTRUCTIONS_AROUND_POINTER: u64 = 50;
+
+#[derive(Debug, Clone)]
+pub(crate) enum DisassemblyLine {
+    /// A source line the instructions below it were compiled from.
+    Source {
+        label: SharedString,
+        text: Option<SharedString>,
+    },
+    Instruction(DisassembledInstruction),
+}
+
+pub struct DisassemblyView {
+    session: Entity<Session>,
+    stack_frame_list: Entity<StackFrameList>,
+    project: WeakEntity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    scrollbar_state: ScrollbarState,
+    /// Memory reference of the instruction the selected stack frame is at.
+    instruction_pointer: Option<String>,
+    lines: Vec<DisassemblyLine>,
+    selected_ix: Option<usize>,
+    _fetch_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DisassemblyView {
+    pub fn new(
+        session: Entity<Session>,
+        stack_frame_list: Entity<StackFrameList>,
+        project: WeakEntity<Project>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+
+        let _subscriptions = vec![
+            cx.subscribe(&stack_frame_list, |this, _, event, cx| match event {
+                StackFrameListEvent::SelectedStackFrameChanged(stack_frame_id) => {
+                    this.follow_stack_frame(Some(*stack_frame_id), cx)
+                }
+                StackFrameListEvent::BuiltEntries => {
+                    let stack_frame_id = this.stack_frame_list.read(cx).opened_stack_frame_id();
+                    this.follow_stack_frame(stack_frame_id, cx)
+                }
+            }),
+            cx.subscribe(&session, |this, _, event, cx| {
+                if let SessionEvent::CapabilitiesLoaded = event {
+                    this.fetch_instructions(cx);
+                }
+            }),
+        ];
+
+        let scroll_handle = UniformListScrollHandle::new();
+
+        Self {
+            scrollbar_state: ScrollbarState::new(scroll_handle.clone()),
+            scroll_handle,
+            session,
+            stack_frame_list,
+            project,
+            focus_handle,
+            instruction_pointer: None,
+            lines: Vec::new(),
+            selected_ix: None,
+            _fetch_task: Task::ready(()),
+            _subscriptions,
+        }
+    }
+
+    /// Disassembles around the instruction pointer of the given stack frame, or of the topmost
+    /// one when no frame is selected.
+    fn follow_stack_frame(&mut self, stack_frame_id: Option<StackFrameId>, cx: &mut Context<Self>) {
+        let stack_frames = self.stack_frame_list.read(cx).flatten_entries(true);
+        let instruction_pointer = stack_frame_id
+            .and_then(|id| stack_frames.iter().find(|frame| frame.id == id))
+            .or_else(|| stack_frames.first())
+            .and_then(|frame| frame.instruction_pointer_reference.clone());
+
+        if instruction_pointer == self.instruction_pointer {
+            return;
+        }
+        self.instruction_pointer = instruction_pointer;
+        self.fetch_instructions(cx);
+    }
+
+    fn fetch_instructions(&mut self, cx: &mut Context<Self>) {
+        let Some(memory_reference) = self.instruction_pointer.clone() else {
+            self.lines.clear();
+            self.selected_ix = None;
+            self._fetch_task = Task::ready(());
+            cx.notify();
+            return;
+        };
+
+        let disassemble = self.session.read(cx).disassemble(
+            memory_reference,
+            -(INSTRUCTIONS_AROUND_POINTER as i64),
+            INSTRUCTIONS_AROUND_POINTER * 2 + 1,
+        );
+        let fs = self
+            .project
+            .read_with(cx, |project, _| {
+                project.is_local().then(|| project.fs().clone())
+            })
+            .ok()
+            .flatten();
+
+        self._fetch_task = cx.spawn(async move |this, cx| {
+            let Some(instructions) = disassemble.await.log_err() else {
+                return;
+            };
+
+            // Source lines are read from disk, as the program was compiled from the files there.
+            let mut sources = HashMap::<Arc<Path>, Vec<SharedString>>::default();
+            if let Some(fs) = fs {
+                for path in source_paths(&instructions) {
+                    if let Some(text) = fs.load(&path).await.log_err() {
+                        let lines = text.lines().map(|line| line.to_owned().into()).collect();
+                        sources.insert(path, lines);
+                    }
+                }
+            }
+
+            this.update(cx, |this, cx| {
+                this.lines = disassembly_lines(instructions, |path, line| {
+                    let row = usize::try_from(line).ok()?.checked_sub(1)?;
+                    sources.get(path)?.get(row).cloned()
+                });
+                this.selected_ix = this.instruction_pointer_ix();
+                if let Some(ix) = this.selected_ix {
+                    this.scroll_handle
+                        .scroll_to_item(ix, ScrollStrategy::Center);
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn instruction_pointer_ix(&self) -> Option<usize> {
+        let instruction_pointer = self.instruction_pointer.as_deref()?;
+        self.lines.iter().position(|line| match line {
+            DisassemblyLine::Instruction(instruction) => {
+                same_address(&instruction.address, instruction_pointer)
+            }
+            DisassemblyLine::Source { .. } => false,
+        })
+    }
+
+    #[cfg(test)]
+    pub(crate) fn lines(&self) -> &[DisassemblyLine] {
+        &self.lines
+    }
+
+    fn toggle_breakpoint(&mut self, address: String, cx: &mut Context<Self>) {
+        self.session.update(cx, |session, cx| {
+            session.toggle_instruction_breakpoint(&address, cx);
+        });
+        cx.notify();
+    }
+
+    fn render_entry(&mut self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        match &self.lines[ix] {
+            DisassemblyLine::Source { label, text } => h_flex()
+                .id(("disassembly-source", ix))
+                .w_full()
+                .gap_2()
+                .px_1()
+                .pl_6()
+                .text_ui_sm(cx)
+                .child(
+                    Label::new(label.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Accent),
+                )
+                .when_some(text.clone(), |this, text| {
+                    this.child(
+                        div()
+                            .font_buffer(cx)
+                            .text_color(cx.theme().colors().text_muted)
+                            .child(text),
+                    )
+                })
+                .into_any(),
+            DisassemblyLine::Instruction(instruction) => {
+                let session = self.session.read(cx);
+                let supports_breakpoints = session
+                    .capabilities()
+                    .supports_instruction_breakpoints
+                    .unwrap_or_default();
+                let has_breakpoint = session
+                    .instruction_breakpoints()
+                    .iter()
+                    .any(|reference| same_address(reference, &instruction.address));
+                let is_instruction_pointer = self
+                    .instruction_pointer
+                    .as_deref()
+                    .is_some_and(|pointer| same_address(&instruction.address, pointer));
+                let is_invalid = matches!(
+                    instruction.presentation_hint,
+                    Some(DisassembledInstructionPresentationHint::Invalid)
+                );
+                let address = instruction.address.clone();
+
+                h_flex()
+                    .id(("disassembly-instruction", ix))
+                    .w_full()
+                    .gap_2()
+                    .px_1()
+                    .font_buffer(cx)
+                    .text_ui_sm(cx)
+                    .on_any_mouse_down(|_, _, cx| {
+                        cx.stop_propagation();
+                    })
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.selected_ix = Some(ix);
+                        cx.notify();
+                    }))
+                    .hover(|s| s.bg(cx.theme().colors().element_hover))
+                    .when(Some(ix) == self.selected_ix, |s| {
+                        s.bg(cx.theme().colors().element_selected)
+                    })
+                    .when(is_instruction_pointer, |s| {
+                        s.bg(cx.theme().colors().editor_debugger_active_line_background)
+                    })
+                    .child(
+                        div()
+                            .id(("disassembly-gutter", ix))
+                            .w_4()
+                            .flex_none()
+                            .when(has_breakpoint, |this| {
+                                this.child(Indicator::dot().color(Color::Debugger))
+                            })
+                            .when(supports_breakpoints, |this| {
+                                this.cursor_pointer()
+                                    .tooltip(Tooltip::text(if has_breakpoint {
+                                        "Remove Instruction Breakpoint"
+                                    } else {
+                                        "Set Instruction Breakpoint"
+                                    }))
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        cx.stop_propagation();
+                                        this.toggle_breakpoint(address.clone(), cx);
+                                    }))
+                            }),
+                    )
+                    .child(
+                        div()
+                            .flex_none()
+                            .text_color(cx.theme().colors().text_muted)
+                            .child(instruction.address.clone()),
+                    )
+                    .when_some(instruction.instruction_bytes.clone(), |this, bytes| {
+                        this.child(
+                            div()
+                                .flex_none()
+                                .text_color(cx.theme().colors().text_placeholder)
+                                .child(bytes),
+                        )
+                    })
+                    .child(
+                        div()
+                            .when(is_invalid, |this| {
+                                this.text_color(cx.theme().colors().text_disabled)
+                            })
+                            .child(instruction.instruction.clone()),
+                    )
+                    .when_some(instruction.symbol.clone(), |this, symbol| {
+                        this.child(
+                            div()
+                                .text_color(cx.theme().colors().text_muted)
+                                .child(format!("<{symbol}>")),
+                        )
+                    })
+                    .into_any()
+            }
+        }
+    }
+
+    fn render_vertical_scrollbar(&self, cx: &mut Context<Self>) -> Stateful<Div> {
+        div()
+            .occlude()
+            .id("disassembly-view-vertical-scrollbar")
+            .on_mouse_move(cx.listener(|_, _, _, cx| {
+                cx.notify();
+                cx.stop_propagation()
+            }))
+            .on_hover(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_any_mouse_down(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| {
+                    cx.stop_propagation();
+                }),
+            )
+            .on_scroll_wheel(cx.listener(|_, _, _, cx| {
+                cx.notify();
+            }))
+            .h_full()
+            .absolute()
+            .right_1()
+            .top_1()
+            .bottom_0()
+            .w(px(12.))
+            .cursor_default()
+            .children(Scrollbar::vertical(self.scrollbar_state.clone()))
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle.clone();
+        h_flex()
+            .w_full()
+            .gap_1()
+            .p_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                IconButton::new("disassembly-step-over-instruction", IconName::DebugStepOver)
+                    .icon_size(IconSize::XSmall)
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(StepOverInstruction.boxed_clone(), cx);
+                    })
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |window, cx| {
+                            Tooltip::for_action_in(
+                                "Step Over Instruction",
+                                &StepOverInstruction,
+                                &focus_handle,
+                                window,
+                                cx,
+                            )
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("disassembly-step-into-instruction", IconName::DebugStepInto)
+                    .icon_size(IconSize::XSmall)
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(StepIntoInstruction.boxed_clone(), cx);
+                    })
+                    .tooltip(move |window, cx| {
+                        Tooltip::for_action_in(
+                            "Step Into Instruction",
+                            &StepIntoInstruction,
+                            &focus_handle,
+                            window,
+                            cx,
+                        )
+                    }),
+            )
+    }
+
+    fn select_ix(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
+        self.selected_ix = ix;
+        if let Some(ix) = ix {
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _window: &mut Window, cx: &mut Context<Self>) {
+        let ix = match self.selected_ix {
+            _ if self.lines.is_empty() => None,
+            None => Some(0),
+            Some(ix) => Some((ix + 1) % self.lines.len()),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ix = match self.selected_ix {
+            _ if self.lines.is_empty() => None,
+            None | Some(0) => Some(self.lines.len() - 1),
+            Some(ix) => Some(ix - 1),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn select_first(
+        &mut self,
+        _: &menu::SelectFirst,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ix = (!self.lines.is_empty()).then_some(0);
+        self.select_ix(ix, cx);
+    }
+
+    fn select_last(&mut self, _: &menu::SelectLast, _window: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.lines.len().checked_sub(1);
+        self.select_ix(ix, cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(DisassemblyLine::Instruction(instruction)) =
+            self.selected_ix.and_then(|ix| self.lines.get(ix))
+        else {
+            return;
+        };
+        if self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_instruction_breakpoints
+            .unwrap_or_default()
+        {
+            self.toggle_breakpoint(instruction.address.clone(), cx);
+        }
+    }
+
+    fn render_list(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        uniform_list(
+            cx.entity(),
+            "disassembly-view",
+            self.lines.len(),
+            |this, range, _window, cx| range.map(|ix| this.render_entry(ix, cx)).collect(),
+        )
+        .track_scroll(self.scroll_handle.clone())
+        .size_full()
+    }
+}
+
+/// Source files the instructions were compiled from, each listed once.
+fn source_paths(instructions: &[DisassembledInstruction]) -> Vec<Arc<Path>> {
+    let mut paths = Vec::<Arc<Path>>::new();
+    for path in instructions
+        .iter()
+        .filter_map(|instruction| instruction.location.as_ref()?.path.as_deref())
+    {
+        if !paths.iter().any(|known| known.as_ref() == Path::new(path)) {
+            paths.push(Arc::from(Path::new(path)));
+        }
+    }
+    paths
+}
+
+/// Interleaves the instructions with the source lines they were compiled from, when the adapter
+/// reports them.
+pub(crate) fn disassembly_lines(
+    instructions: Vec<DisassembledInstruction>,
+    mut source_line: impl FnMut(&Path, u64) -> Option<SharedString>,
+) -> Vec<DisassemblyLine> {
+    let mut lines = Vec::with_capacity(instructions.len());
+    // Adapters may only report the location of an instruction when it differs from the previous one.
+    let mut location = None;
+    let mut last_source_line = None;
+    for instruction in instructions {
+        if let Some(instruction_location) = &instruction.location {
+            location = Some(instruction_location.clone());
+        }
+        if let Some(line) = instruction.line {
+            let path = location
+                .as_ref()
+                .and_then(|location| location.path.as_deref());
+            if last_source_line != Some((path.map(str::to_owned), line)) {
+                let name = location
+                    .as_ref()
+                    .and_then(|location| location.name.clone())
+                    .or_else(|| {
+                        let file_name = Path::new(path?).file_name()?;
+                        Some(file_name.to_string_lossy().into_owned())
+                    });
+                lines.push(DisassemblyLine::Source {
+                    label: match name {
+                        Some(name) => format!("{name}:{line}").into(),
+                        None => format!("line {line}").into(),
+                    },
+                    text: path.and_then(|path| source_line(Path::new(path), line)),
+                });
+                last_source_line = Some((path.map(str::to_owned), line));
+            }
+        }
+        lines.push(DisassemblyLine::Instruction(instruction));
+    }
+    lines
+}
+
+/// Compares two memory references, that adapters usually report as hexadecimal addresses that may
+/// be formatted differently.
+fn same_address(lhs: &str, rhs: &str) -> bool {
+    fn parse(reference: &str) -> Option<u64> {
+        let digits = reference
+            .strip_prefix("0x")
+            .or_else(|| reference.strip_prefix("0X"))?;
+        u64::from_str_radix(digits, 16).ok()
+    }
+
+    match (parse(lhs), parse(rhs)) {
+        (Some(lhs), Some(rhs)) => lhs == rhs,
+        _ => lhs == rhs,
+    }
+}
+
+impl Focusable for DisassemblyView {
+    fn focus_handle(&self, _: &gpui::App) -> gpui::FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DisassemblyView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .child(self.render_toolbar(cx))
+            .child(
+                div()
+                    .relative()
+                    .flex_1()
+                    .size_full()
+                    .p_1()
+                    .child(self.render_list(window, cx))
+                    .child(self.render_vertical_scrollbar(cx)),
+            )
+    }
+}
@@ -0,0 +1,526 @@
+use editor::Editor;
+use gpui::{
+    AnyElement, ClickEvent, Entity, FocusHandle, Focusable, MouseButton, ScrollStrategy, Stateful,
+    Subscription, Task, TextStyleRefinement, UniformListScrollHandle, uniform_list,
+};
+use project::debugger::session::{MemoryRegion, Session, SessionEvent};
+use ui::{Scrollbar, ScrollbarState, Tooltip, prelude::*};
+use util::ResultExt;
+
+/// How many bytes are read at once, the view pages through memory by this many bytes.
+const PAGE_SIZE: u64 = 256;
+const BYTES_PER_ROW: usize = 16;
+
+pub struct MemoryView {
+    session: Entity<Session>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    scrollbar_state: ScrollbarState,
+    /// The memory reference of the inspected variable, pages are read relative to it.
+    memory_reference: Option<String>,
+    /// Offset of the shown page from `memory_reference`, in bytes.
+    page_offset: i64,
+    memory: MemoryRegion,
+    selected_byte: Option<usize>,
+    /// Editor for the bytes to write at the selected one, in hexadecimal.
+    byte_editor: Option<Entity<Editor>>,
+    _fetch_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MemoryView {
+    pub fn new(session: Entity<Session>, cx: &mut Context<Self>) -> Self {
+        let focus_handle = cx.focus_handle();
+
+        let _subscriptions = vec![cx.subscribe(&session, |this, _, event, cx| match event {
+            SessionEvent::Stopped(_) | SessionEvent::Memory => {
+                this.fetch_memory(cx);
+            }
+            _ => {}
+        })];
+
+        let scroll_handle = UniformListScrollHandle::new();
+
+        Self {
+            scrollbar_state: ScrollbarState::new(scroll_handle.clone()),
+            scroll_handle,
+            session,
+            focus_handle,
+            memory_reference: None,
+            page_offset: 0,
+            memory: MemoryRegion::default(),
+            selected_byte: None,
+            byte_editor: None,
+            _fetch_task: Task::ready(()),
+            _subscriptions,
+        }
+    }
+
+    /// Shows the memory at the given memory reference, as reported for variables by the adapter.
+    pub(crate) fn show_memory(&mut self, memory_reference: String, cx: &mut Context<Self>) {
+        self.memory_reference = Some(memory_reference);
+        self.page_offset = 0;
+        self.selected_byte = Some(0);
+        self.byte_editor = None;
+        self.fetch_memory(cx);
+    }
+
+    #[cfg(test)]
+    pub(crate) fn memory(&self) -> &MemoryRegion {
+        &self.memory
+    }
+
+    fn fetch_memory(&mut self, cx: &mut Context<Self>) {
+        let Some(memory_reference) = self.memory_reference.clone() else {
+            return;
+        };
+
+        let read_memory =
+            self.session
+                .read(cx)
+                .read_memory(memory_reference, self.page_offset, PAGE_SIZE);
+        self._fetch_task = cx.spawn(async move |this, cx| {
+            let Some(memory) = read_memory.await.log_err() else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                this.memory = memory;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn change_page(&mut self, pages: i64, cx: &mut Context<Self>) {
+        self.page_offset = self.page_offset.saturating_add(pages * PAGE_SIZE as i64);
+        self.byte_editor = None;
+        self.scroll_handle.scroll_to_item(0, ScrollStrategy::Top);
+        self.fetch_memory(cx);
+    }
+
+    fn address_of(&self, byte_ix: usize) -> String {
+        match parse_address(&self.memory.address) {
+            Some(address) => format!("{:#018x}", address.wrapping_add(byte_ix as u64)),
+            None => format!("+{:#x}", self.page_offset + byte_ix as i64),
+        }
+    }
+
+    fn supports_write_memory(&self, cx: &App) -> bool {
+        self.session
+            .read(cx)
+            .capabilities()
+            .supports_write_memory_request
+            .unwrap_or_default()
+    }
+
+    fn edit_selected_byte(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(byte) = self
+            .selected_byte
+            .and_then(|ix| self.memory.data.get(ix).copied())
+        else {
+            return;
+        };
+        if !self.supports_write_memory(cx) {
+            return;
+        }
+
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_text_style_refinement(TextStyleRefinement {
+                font_size: Some(
+                    TextSize::XSmall
+                        .rems(cx)
+                        .to_pixels(window.rem_size())
+                        .into(),
+                ),
+                ..Default::default()
+            });
+            editor.set_placeholder_text("Bytes to write, e.g. de ad be ef", cx);
+            editor.set_text(format!("{byte:02x}"), window, cx);
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+            editor
+        });
+        editor.focus_handle(cx).focus(window);
+        self.byte_editor = Some(editor);
+        cx.notify();
+    }
+
+    fn write_edited_bytes(&mut self, cx: &mut Context<Self>) {
+        let Some(editor) = self.byte_editor.take() else {
+            return;
+        };
+        let (Some(memory_reference), Some(byte_ix)) =
+            (self.memory_reference.clone(), self.selected_byte)
+        else {
+            return;
+        };
+        let Some(data) = parse_hex_bytes(&editor.read(cx).text(cx)) else {
+            log::warn!("Not writing memory, bytes must be written in hexadecimal");
+            return;
+        };
+
+        let write_memory = self.session.read(cx).write_memory(
+            memory_reference,
+            self.page_offset + byte_ix as i64,
+            data,
+        );
+        cx.spawn(async move |this, cx| {
+            write_memory.await?;
+            this.update(cx, |this, cx| this.fetch_memory(cx))
+        })
+        .detach_and_log_err(cx);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.byte_editor.is_some() {
+            self.write_edited_bytes(cx);
+            self.focus_handle.focus(window);
+        } else {
+            self.edit_selected_byte(window, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        self.byte_editor.take();
+        self.focus_handle.focus(window);
+        cx.notify();
+    }
+
+    fn select_byte(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
+        self.selected_byte = ix;
+        self.byte_editor = None;
+        if let Some(ix) = ix {
+            self.scroll_handle
+                .scroll_to_item(ix / BYTES_PER_ROW, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _window: &mut Window, cx: &mut Context<Self>) {
+        let len = self.memory.data.len();
+        let ix = match self.selected_byte {
+            _ if len == 0 => None,
+            None => Some(0),
+            Some(ix) => Some((ix + 1) % len),
+        };
+        self.select_byte(ix, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let len = self.memory.data.len();
+        let ix = match self.selected_byte {
+            _ if len == 0 => None,
+            None | Some(0) => Some(len - 1),
+            Some(ix) => Some(ix - 1),
+        };
+        self.select_byte(ix, cx);
+    }
+
+    fn select_first(
+        &mut self,
+        _: &menu::SelectFirst,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ix = (!self.memory.data.is_empty()).then_some(0);
+        self.select_byte(ix, cx);
+    }
+
+    fn select_last(&mut self, _: &menu::SelectLast, _window: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.memory.data.len().checked_sub(1);
+        self.select_byte(ix, cx);
+    }
+
+    fn row_count(&self) -> usize {
+        let len = self.memory.data.len()
+            + self
+                .memory
+                .unreadable_bytes
+                .min(PAGE_SIZE.saturating_sub(self.memory.data.len() as u64))
+                as usize;
+        len.div_ceil(BYTES_PER_ROW)
+    }
+
+    fn render_row(&mut self, row: usize, cx: &mut Context<Self>) -> AnyElement {
+        let start = row * BYTES_PER_ROW;
+        let mut bytes = h_flex().gap_1();
+        let mut text = String::with_capacity(BYTES_PER_ROW);
+        for ix in start..start + BYTES_PER_ROW {
+            let byte = self.memory.data.get(ix).copied();
+            text.push(match byte {
+                Some(byte) if byte.is_ascii_graphic() || byte == b' ' => byte as char,
+                _ => '.',
+            });
+            bytes = bytes.child(
+                div()
+                    .id(("memory-byte", ix))
+                    .px_0p5()
+                    .rounded_sm()
+                    .when(byte.is_none(), |this| {
+                        this.text_color(cx.theme().colors().text_placeholder)
+                    })
+                    .when(Some(ix) == self.selected_byte, |this| {
+                        this.bg(cx.theme().colors().element_selected)
+                    })
+                    .hover(|s| s.bg(cx.theme().colors().element_hover))
+                    .when(byte.is_some(), |this| {
+                        this.on_click(cx.listener(move |this, click: &ClickEvent, window, cx| {
+                            this.select_byte(Some(ix), cx);
+                            if click.down.click_count >= 2 {
+                                this.edit_selected_byte(window, cx);
+                            }
+                        }))
+                    })
+                    .child(byte.map_or_else(|| "??".to_owned(), |byte| format!("{byte:02x}"))),
+            );
+        }
+
+        h_flex()
+            .id(("memory-row", row))
+            .w_full()
+            .gap_3()
+            .px_1()
+            .font_buffer(cx)
+            .text_ui_sm(cx)
+            .on_any_mouse_down(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .child(
+                div()
+                    .flex_none()
+                    .text_color(cx.theme().colors().text_muted)
+                    .child(self.address_of(start)),
+            )
+            .child(bytes)
+            .child(
+                div()
+                    .flex_none()
+                    .text_color(cx.theme().colors().text_muted)
+                    .child(text),
+            )
+            .into_any()
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .w_full()
+            .gap_1()
+            .p_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                IconButton::new("memory-previous-page", IconName::ChevronLeft)
+                    .icon_size(IconSize::XSmall)
+                    .disabled(self.memory_reference.is_none())
+                    .tooltip(Tooltip::text("Previous Page"))
+                    .on_click(cx.listener(|this, _, _, cx| this.change_page(-1, cx))),
+            )
+            .child(
+                IconButton::new("memory-next-page", IconName::ChevronRight)
+                    .icon_size(IconSize::XSmall)
+                    .disabled(self.memory_reference.is_none())
+                    .tooltip(Tooltip::text("Next Page"))
+                    .on_click(cx.listener(|this, _, _, cx| this.change_page(1, cx))),
+            )
+            .child(
+                Label::new(match &self.memory_reference {
+                    Some(memory_reference) => {
+                        format!("{memory_reference} {:+#x}", self.page_offset)
+                    }
+                    None => "View memory from a variable's context menu".to_owned(),
+                })
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+    }
+
+    fn render_interpretations(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let ix = self.selected_byte?;
+        let bytes = self.memory.data.get(ix..)?;
+        if bytes.is_empty() {
+            return None;
+        }
+
+        Some(
+            v_flex()
+                .w_full()
+                .p_1()
+                .gap_0p5()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .text_ui_sm(cx)
+                .child(
+                    Label::new(format!("{} (little-endian)", self.address_of(ix)))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(
+                    h_flex().flex_wrap().gap_x_3().children(
+                        interpret_bytes(bytes)
+                            .into_iter()
+                            .map(|(data_type, value)| {
+                                h_flex()
+                                    .gap_1()
+                                    .child(
+                                        Label::new(data_type)
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                    .child(div().font_buffer(cx).child(value))
+                            }),
+                    ),
+                )
+                .when_some(self.byte_editor.clone(), |this, editor| {
+                    this.child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Label::new("Write")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(div().flex_1().px_1().child(editor)),
+                    )
+                }),
+        )
+    }
+
+    fn render_vertical_scrollbar(&self, cx: &mut Context<Self>) -> Stateful<Div> {
+        div()
+            .occlude()
+            .id("memory-view-vertical-scrollbar")
+            .on_mouse_move(cx.listener(|_, _, _, cx| {
+                cx.notify();
+                cx.stop_propagation()
+            }))
+            .on_hover(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_any_mouse_down(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| {
+                    cx.stop_propagation();
+                }),
+            )
+            .on_scroll_wheel(cx.listener(|_, _, _, cx| {
+                cx.notify();
+            }))
+            .h_full()
+            .absolute()
+            .right_1()
+            .top_1()
+            .bottom_0()
+            .w(px(12.))
+            .cursor_default()
+            .children(Scrollbar::vertical(self.scrollbar_state.clone()))
+    }
+
+    fn render_list(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        uniform_list(
+            cx.entity(),
+            "memory-view",
+            self.row_count(),
+            |this, range, _window, cx| range.map(|row| this.render_row(row, cx)).collect(),
+        )
+        .track_scroll(self.scroll_handle.clone())
+        .size_full()
+    }
+}
+
+fn parse_address(address: &str) -> Option<u64> {
+    let digits = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))?;
+    u64::from_str_radix(digits, 16).ok()
+}
+
+/// Parses bytes written in hexadecimal, optionally separated by whitespace.
+pub(crate) fn parse_hex_bytes(text: &str) -> Option<Vec<u8>> {
+    let digits = text.split_whitespace().collect::<String>();
+    if digits.is_empty() || !digits.is_ascii() || digits.len() % 2 != 0 {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|ix| u8::from_str_radix(&digits[ix..ix + 2], 16).ok())
+        .collect()
+}
+
+/// Interprets the bytes at the start of `bytes` as each of the common data types, that fit in
+/// them. Values are read in little-endian byte order, the one of the targets native adapters debug.
+pub(crate) fn interpret_bytes(bytes: &[u8]) -> Vec<(&'static str, String)> {
+    fn read<const N: usize>(bytes: &[u8]) -> Option<[u8; N]> {
+        bytes.get(..N)?.try_into().ok()
+    }
+
+    let mut values = Vec::new();
+    if let Some(bytes) = read::<1>(bytes) {
+        values.push(("u8", u8::from_le_bytes(bytes).to_string()));
+        values.push(("i8", i8::from_le_bytes(bytes).to_string()));
+    }
+    if let Some(bytes) = read::<2>(bytes) {
+        values.push(("u16", u16::from_le_bytes(bytes).to_string()));
+        values.push(("i16", i16::from_le_bytes(bytes).to_string()));
+    }
+    if let Some(bytes) = read::<4>(bytes) {
+        values.push(("u32", u32::from_le_bytes(bytes).to_string()));
+        values.push(("i32", i32::from_le_bytes(bytes).to_string()));
+        values.push(("f32", f32::from_le_bytes(bytes).to_string()));
+    }
+    if let Some(bytes) = read::<8>(bytes) {
+        values.push(("u64", u64::from_le_bytes(bytes).to_string()));
+        values.push(("i64", i64::from_le_bytes(bytes).to_string()));
+        values.push(("f64", f64::from_le_bytes(bytes).to_string()));
+    }
+    let character = (1..=bytes.len().min(4))
+        .find_map(|len| std::str::from_utf8(&bytes[..len]).ok())
+        .and_then(|text| text.chars().next());
+    values.push((
+        "UTF-8",
+        match character {
+            Some(character) => format!("'{}' U+{:04X}", character.escape_debug(), character as u32),
+            None => "invalid".to_owned(),
+        },
+    ));
+    values
+}
+
+impl Focusable for MemoryView {
+    fn focus_handle(&self, _: &gpui::App) -> gpui::FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for MemoryView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .child(self.render_toolbar(cx))
+            .child(
+                div()
+                    .relative()
+                    .flex_1()
+                    .size_full()
+                    .p_1()
+                    .child(self.render_list(window, cx))
+                    .child(self.render_vertical_scrollbar(cx)),
+            )
+            .children(self.render_interpretations(cx))
+    }
+}
@@ -2,12 +2,15 @@ use super::stack_frame_list::{StackFrameList, StackFrameListEvent};
 use dap::{ScopePresentationHint, StackFrameId, VariablePresentationHintKind, VariableReference};
 use editor::Editor;
 use gpui::{
-    Action, AnyElement, ClickEvent, ClipboardItem, Context, DismissEvent, Entity, FocusHandle,
-    Focusable, Hsla, MouseButton, MouseDownEvent, Point, Stateful, Subscription,
+    Action, AnyElement, ClickEvent, ClipboardItem, Context, DismissEvent, Entity, EventEmitter,
+    FocusHandle, Focusable, Hsla, MouseButton, MouseDownEvent, Point, Stateful, Subscription, Task,
     TextStyleRefinement, UniformListScrollHandle, actions, anchored, deferred, uniform_list,
 };
 use menu::{SelectFirst, SelectLast, SelectNext, SelectPrevious};
-use project::debugger::session::{Session, SessionEvent};
+use project::debugger::{
+    breakpoint_store::{BreakpointStore, BreakpointStoreEvent},
+    session::{Session, SessionEvent},
+};
 use std::{collections::HashMap, ops::Range, sync::Arc};
 use ui::{ContextMenu, ListItem, Scrollbar, ScrollbarState, prelude::*};
 use util::debug_panic;
@@ -19,10 +22,19 @@ actions!(
         CollapseSelectedEntry,
         CopyVariableName,
         CopyVariableValue,
-        EditVariable
+        EditVariable,
+        ViewMemory,
+        AddDataBreakpoint,
+        AddWatch,
+        RemoveWatch
     ]
 );
 
+pub enum VariableListEvent {
+    ViewMemory(String),
+    WatchAdded,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub(crate) struct EntryState {
     depth: usize,
@@ -45,6 +57,14 @@ impl EntryPath {
         }
     }
 
+    /// Watch expressions have no scope, so they are nested under themselves to be as deep as scopes.
+    fn for_watch(expression: impl Into<SharedString>) -> Self {
+        Self {
+            leaf_name: None,
+            indices: Arc::new([expression.into()]),
+        }
+    }
+
     fn with_name(&self, name: SharedString) -> Self {
         Self {
             leaf_name: Some(name),
@@ -149,9 +169,19 @@ pub struct VariableList {
     focus_handle: FocusHandle,
     edited_path: Option<(EntryPath, Entity<Editor>)>,
     disabled: bool,
+    watch: Option<WatchState>,
     _subscriptions: Vec<Subscription>,
 }
 
+/// State of a variable list that shows the watch expressions instead of the scopes of the selected stack frame.
+struct WatchState {
+    breakpoint_store: Entity<BreakpointStore>,
+    /// Results of evaluating the watch expressions, in the shape of variables named after them.
+    results: Vec<dap::Variable>,
+    editor: Entity<Editor>,
+    _evaluate_task: Task<()>,
+}
+
 impl VariableList {
     pub fn new(
         session: Entity<Session>,
@@ -193,11 +223,103 @@ impl VariableList {
             open_context_menu: None,
             disabled: false,
             edited_path: None,
+            watch: None,
             entries: Default::default(),
             entry_states: Default::default(),
         }
     }
 
+    /// Creates a list of the project's watch expressions, evaluated whenever a stack frame is selected.
+    pub fn new_watch(
+        session: Entity<Session>,
+        stack_frame_list: Entity<StackFrameList>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let breakpoint_store = session.read(cx).breakpoint_store().clone();
+        let mut this = Self::new(session, stack_frame_list, window, cx);
+        this._subscriptions
+            .push(cx.subscribe(&breakpoint_store, |this, _, event, cx| {
+                if let BreakpointStoreEvent::WatchExpressionsUpdated = event {
+                    this.evaluate_watches(cx);
+                }
+            }));
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Add an expression to watch", cx);
+            editor
+        });
+        this.watch = Some(WatchState {
+            breakpoint_store,
+            results: Vec::new(),
+            editor,
+            _evaluate_task: Task::ready(()),
+        });
+        this.evaluate_watches(cx);
+        this
+    }
+
+    fn evaluate_watches(&mut self, cx: &mut Context<Self>) {
+        let Some(watch) = self.watch.as_mut() else {
+            return;
+        };
+        let expressions = watch.breakpoint_store.read(cx).watch_expressions().to_vec();
+        let Some(stack_frame_id) = self.selected_stack_frame_id else {
+            watch.results = expressions
+                .iter()
+                .map(|expression| watch_variable(expression, None))
+                .collect();
+            self.build_entries(cx);
+            return;
+        };
+
+        let evaluations = expressions
+            .iter()
+            .map(|expression| {
+                self.session
+                    .read(cx)
+                    .evaluate_watch(expression.to_string(), Some(stack_frame_id))
+            })
+            .collect::<Vec<_>>();
+        watch._evaluate_task = cx.spawn(async move |this, cx| {
+            let results = futures::future::join_all(evaluations).await;
+            this.update(cx, |this, cx| {
+                if let Some(watch) = this.watch.as_mut() {
+                    watch.results = expressions
+                        .iter()
+                        .zip(results)
+                        .map(|(expression, result)| watch_variable(expression, Some(result)))
+                        .collect();
+                }
+                this.build_entries(cx);
+            })
+            .ok();
+        });
+    }
+
+    fn add_watch_from_editor(
+        &mut self,
+        _: &menu::Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(watch) = self.watch.as_ref() else {
+            return;
+        };
+        let expression = watch.editor.update(cx, |editor, cx| {
+            let expression = editor.text(cx);
+            editor.clear(window, cx);
+            expression
+        });
+        let expression = expression.trim();
+        if expression.is_empty() {
+            return;
+        }
+        watch.breakpoint_store.update(cx, |breakpoint_store, cx| {
+            breakpoint_store.add_watch_expression(expression.into(), cx);
+        });
+    }
+
     pub(super) fn disabled(&mut self, disabled: bool, cx: &mut Context<Self>) {
         let old_disabled = std::mem::take(&mut self.disabled);
         self.disabled = disabled;
@@ -211,43 +333,58 @@ impl VariableList {
     }
 
     fn build_entries(&mut self, cx: &mut Context<Self>) {
-        let Some(stack_frame_id) = self.selected_stack_frame_id else {
-            return;
-        };
-
         let mut entries = vec![];
-        let scopes: Vec<_> = self.session.update(cx, |session, cx| {
-            session.scopes(stack_frame_id, cx).iter().cloned().collect()
-        });
-
         let mut contains_local_scope = false;
 
-        let mut stack = scopes
-            .into_iter()
-            .rev()
-            .filter(|scope| {
-                if scope
-                    .presentation_hint
-                    .as_ref()
-                    .map(|hint| *hint == ScopePresentationHint::Locals)
-                    .unwrap_or(scope.name.to_lowercase().starts_with("local"))
-                {
-                    contains_local_scope = true;
-                }
+        let mut stack = if let Some(watch) = &self.watch {
+            watch
+                .results
+                .iter()
+                .rev()
+                .map(|variable| {
+                    (
+                        0,
+                        variable.variables_reference,
+                        EntryPath::for_watch(variable.name.clone()),
+                        EntryKind::Variable(variable.clone()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        } else {
+            let Some(stack_frame_id) = self.selected_stack_frame_id else {
+                return;
+            };
+            let scopes: Vec<_> = self.session.update(cx, |session, cx| {
+                session.scopes(stack_frame_id, cx).iter().cloned().collect()
+            });
+
+            scopes
+                .into_iter()
+                .rev()
+                .filter(|scope| {
+                    if scope
+                        .presentation_hint
+                        .as_ref()
+                        .map(|hint| *hint == ScopePresentationHint::Locals)
+                        .unwrap_or(scope.name.to_lowercase().starts_with("local"))
+                    {
+                        contains_local_scope = true;
+                    }
 
-                self.session.update(cx, |session, cx| {
-                    session.variables(scope.variables_reference, cx).len() > 0
+                    self.session.update(cx, |session, cx| {
+                        session.variables(scope.variables_reference, cx).len() > 0
+                    })
                 })
-            })
-            .map(|scope| {
-                (
-                    scope.variables_reference,
-                    scope.variables_reference,
-                    EntryPath::for_scope(&scope.name),
-                    EntryKind::Scope(scope),
-                )
-            })
-            .collect::<Vec<_>>();
+                .map(|scope| {
+                    (
+                        scope.variables_reference,
+                        scope.variables_reference,
+                        EntryPath::for_scope(&scope.name),
+                        EntryKind::Scope(scope),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
 
         let scopes_count = stack.len();
 
@@ -312,7 +449,11 @@ impl VariableList {
         match event {
             StackFrameListEvent::SelectedStackFrameChanged(stack_frame_id) => {
                 self.selected_stack_frame_id = Some(*stack_frame_id);
-                self.build_entries(cx);
+                if self.watch.is_some() {
+                    self.evaluate_watches(cx);
+                } else {
+                    self.build_entries(cx);
+                }
             }
             StackFrameListEvent::BuiltEntries => {}
         }
@@ -490,15 +631,54 @@ impl VariableList {
 
     fn deploy_variable_context_menu(
         &mut self,
-        _variable: ListEntry,
+        variable: ListEntry,
         position: Point<Pixels>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        let supports_memory_view = variable
+            .as_variable()
+            .is_some_and(|variable| variable.memory_reference.is_some())
+            && self
+                .session
+                .read(cx)
+                .capabilities()
+                .supports_read_memory_request
+                .unwrap_or_default();
+        let is_watch = self.watch.is_some() && variable.path.indices.len() == 1;
+        let can_watch = self.watch.is_none()
+            && variable
+                .as_variable()
+                .is_some_and(|variable| variable.evaluate_name.is_some());
+        let supports_data_breakpoints = variable.as_variable().is_some()
+            && !is_watch
+            && self
+                .session
+                .read(cx)
+                .capabilities()
+                .supports_data_breakpoints
+                .unwrap_or_default();
+
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.action("Copy Name", CopyVariableName.boxed_clone())
                 .action("Copy Value", CopyVariableValue.boxed_clone())
-                .action("Edit Value", EditVariable.boxed_clone())
+                .when(!is_watch, |menu| {
+                    menu.action("Edit Value", EditVariable.boxed_clone())
+                })
+                .when(can_watch, |menu| {
+                    menu.action("Add to Watch", AddWatch.boxed_clone())
+                })
+                .when(is_watch, |menu| {
+                    menu.action("Remove Watch", RemoveWatch.boxed_clone())
+                })
+                .when(supports_memory_view, |menu| {
+                    menu.separator()
+                        .action("View Memory", ViewMemory.boxed_clone())
+                })
+                .when(supports_data_breakpoints, |menu| {
+                    menu.separator()
+                        .action("Break When Value Changes", AddDataBreakpoint.boxed_clone())
+                })
                 .context(self.focus_handle.clone())
         });
 
@@ -566,6 +746,9 @@ impl VariableList {
         let Some(variable) = entry.as_variable() else {
             return;
         };
+        if !self.can_edit(&entry.path) {
+            return;
+        }
 
         let editor = Self::create_variable_editor(&variable.value, window, cx);
         self.edited_path = Some((entry.path.clone(), editor));
@@ -573,6 +756,92 @@ impl VariableList {
         cx.notify();
     }
 
+    fn view_memory(&mut self, _: &ViewMemory, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(selection) = self.selection.as_ref() else {
+            return;
+        };
+        let Some(entry) = self.entries.iter().find(|entry| &entry.path == selection) else {
+            return;
+        };
+        let Some(memory_reference) = entry
+            .as_variable()
+            .and_then(|variable| variable.memory_reference.clone())
+        else {
+            return;
+        };
+        cx.emit(VariableListEvent::ViewMemory(memory_reference));
+    }
+
+    fn add_watch(&mut self, _: &AddWatch, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(selection) = self.selection.as_ref() else {
+            return;
+        };
+        let Some(entry) = self.entries.iter().find(|entry| &entry.path == selection) else {
+            return;
+        };
+        let Some(evaluate_name) = entry
+            .as_variable()
+            .and_then(|variable| variable.evaluate_name.clone())
+        else {
+            return;
+        };
+        let breakpoint_store = self.session.read(cx).breakpoint_store().clone();
+        breakpoint_store.update(cx, |breakpoint_store, cx| {
+            breakpoint_store.add_watch_expression(evaluate_name.into(), cx);
+        });
+        cx.emit(VariableListEvent::WatchAdded);
+    }
+
+    fn remove_watch(&mut self, _: &RemoveWatch, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(watch) = self.watch.as_ref() else {
+            return;
+        };
+        let Some(selection) = self.selection.as_ref() else {
+            return;
+        };
+        let Some(expression) = selection
+            .indices
+            .first()
+            .filter(|_| selection.indices.len() == 1)
+        else {
+            return;
+        };
+        watch.breakpoint_store.update(cx, |breakpoint_store, cx| {
+            breakpoint_store.remove_watch_expression(expression, cx);
+        });
+    }
+
+    /// Watch expressions aren't contained in a variable, so only their children can be set.
+    fn can_edit(&self, path: &EntryPath) -> bool {
+        self.entry_states
+            .get(path)
+            .is_some_and(|state| state.parent_reference != 0)
+    }
+
+    fn add_data_breakpoint(
+        &mut self,
+        _: &AddDataBreakpoint,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(selection) = self.selection.as_ref() else {
+            return;
+        };
+        let Some(entry) = self.entries.iter().find(|entry| &entry.path == selection) else {
+            return;
+        };
+        let Some(variable) = entry.as_variable() else {
+            return;
+        };
+        let Some(state) = self.entry_states.get(&entry.path) else {
+            return;
+        };
+        let task = self.session.update(cx, |session, cx| {
+            session.add_data_breakpoint(state.parent_reference, variable.name.clone(), cx)
+        });
+        task.detach_and_log_err(cx);
+    }
+
     #[track_caller]
     #[cfg(test)]
     pub(crate) fn assert_visual_entries(&self, expected: Vec<&str>) {
@@ -873,6 +1142,7 @@ impl VariableList {
                                     this.text_color(cx.theme().colors().text_muted)
                                         .when(
                                             !self.disabled
+                                                && self.can_edit(&variable.path)
                                                 && self
                                                     .session
                                                     .read(cx)
@@ -952,6 +1222,8 @@ impl VariableList {
     }
 }
 
+impl EventEmitter<VariableListEvent> for VariableList {}
+
 impl Focusable for VariableList {
     fn focus_handle(&self, _: &App) -> gpui::FocusHandle {
         self.focus_handle.clone()
@@ -960,7 +1232,7 @@ impl Focusable for VariableList {
 
 impl Render for VariableList {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        v_flex()
+        let list = v_flex()
             .track_focus(&self.focus_handle)
             .key_context("VariableList")
             .id("variable-list")
@@ -978,6 +1250,10 @@ impl Render for VariableList {
             .on_action(cx.listener(Self::copy_variable_name))
             .on_action(cx.listener(Self::copy_variable_value))
             .on_action(cx.listener(Self::edit_variable))
+            .on_action(cx.listener(Self::view_memory))
+            .on_action(cx.listener(Self::add_data_breakpoint))
+            .on_action(cx.listener(Self::add_watch))
+            .on_action(cx.listener(Self::remove_watch))
             .child(
                 uniform_list(
                     cx.entity().clone(),
@@ -999,8 +1275,69 @@ impl Render for VariableList {
                 )
                 .with_priority(1)
             }))
-            .child(self.render_vertical_scrollbar(cx))
+            .child(self.render_vertical_scrollbar(cx));
+
+        let Some(watch) = self.watch.as_ref() else {
+            return list.into_any_element();
+        };
+        v_flex()
+            .size_full()
+            .child(list.flex_1().min_h_0())
+            // Kept out of the list's key context, so that typing doesn't trigger its bindings.
+            .child(
+                h_flex()
+                    .on_action(cx.listener(Self::add_watch_from_editor))
+                    .w_full()
+                    .gap_1()
+                    .px_1()
+                    .py_0p5()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Icon::new(IconName::Plus)
+                            .size(IconSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .child(watch.editor.clone()),
+            )
+            .into_any_element()
+    }
+}
+
+/// Shows the result of evaluating a watch expression as a variable named after the expression,
+/// or just the expression when it couldn't be evaluated yet.
+fn watch_variable(
+    expression: &str,
+    result: Option<anyhow::Result<dap::EvaluateResponse>>,
+) -> dap::Variable {
+    let mut variable = dap::Variable {
+        name: expression.to_owned(),
+        value: String::new(),
+        type_: None,
+        presentation_hint: None,
+        evaluate_name: Some(expression.to_owned()),
+        variables_reference: 0,
+        named_variables: None,
+        indexed_variables: None,
+        memory_reference: None,
+        declaration_location_reference: None,
+        value_location_reference: None,
+    };
+    match result {
+        Some(Ok(response)) => {
+            variable.value = response.result;
+            variable.type_ = response.type_;
+            variable.presentation_hint = response.presentation_hint;
+            variable.variables_reference = response.variables_reference;
+            variable.named_variables = response.named_variables;
+            variable.indexed_variables = response.indexed_variables;
+            variable.memory_reference = response.memory_reference;
+            variable.value_location_reference = response.value_location_reference;
+        }
+        Some(Err(error)) => variable.value = error.to_string(),
+        None => {}
     }
+    variable
 }
 
 struct EntryColors {
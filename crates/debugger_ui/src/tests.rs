@@ -15,14 +15,20 @@ use crate::{debugger_panel::DebugPanel, session::DebugSession};
 #[cfg(test)]
 mod attach_modal;
 #[cfg(test)]
+mod breakpoint_list;
+#[cfg(test)]
 mod console;
 #[cfg(test)]
 mod dap_logger;
 #[cfg(test)]
 mod debugger_panel;
 #[cfg(test)]
+mod disassembly_view;
+#[cfg(test)]
 mod inline_values;
 #[cfg(test)]
+mod memory_view;
+#[cfg(test)]
 mod module_list;
 #[cfg(test)]
 mod new_process_modal;
@@ -32,6 +38,8 @@ mod persistence;
 mod stack_frame_list;
 #[cfg(test)]
 mod variable_list;
+#[cfg(test)]
+mod watch_list;
 
 pub fn init_test(cx: &mut gpui::TestAppContext) {
     #[cfg(test)]
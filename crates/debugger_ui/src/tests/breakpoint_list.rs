@@ -0,0 +1,164 @@
+use crate::tests::{init_test, init_test_workspace, start_debug_session};
+use dap::requests::{DataBreakpointInfo, Initialize, SetDataBreakpoints, SetFunctionBreakpoints};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use parking_lot::Mutex;
+use project::{
+    FakeFs, Project,
+    debugger::breakpoint_store::{BreakpointEditAction, DataBreakpointAccessType},
+};
+use serde_json::json;
+use std::sync::Arc;
+use util::path;
+
+#[gpui::test]
+async fn test_function_and_data_breakpoints(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.c": "int main() {\n    int a = 0;\n    a += 1;\n    return a;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let function_breakpoints = Arc::new(Mutex::new(Vec::new()));
+    let data_breakpoints = Arc::new(Mutex::new(Vec::new()));
+    let session = start_debug_session(&workspace, cx, {
+        let function_breakpoints = function_breakpoints.clone();
+        let data_breakpoints = data_breakpoints.clone();
+        move |client| {
+            client.on_request::<Initialize, _>(move |_, _| {
+                Ok(dap::Capabilities {
+                    supports_function_breakpoints: Some(true),
+                    supports_data_breakpoints: Some(true),
+                    ..Default::default()
+                })
+            });
+            let function_breakpoints = function_breakpoints.clone();
+            client.on_request::<SetFunctionBreakpoints, _>(move |_, args| {
+                *function_breakpoints.lock() = args
+                    .breakpoints
+                    .into_iter()
+                    .map(|breakpoint| breakpoint.name)
+                    .collect::<Vec<_>>();
+                Ok(dap::SetFunctionBreakpointsResponse {
+                    breakpoints: Vec::new(),
+                })
+            });
+            client.on_request::<DataBreakpointInfo, _>(move |_, args| {
+                assert_eq!(args.variables_reference, Some(2));
+                Ok(dap::DataBreakpointInfoResponse {
+                    data_id: (args.name == "a").then(|| "a@0x1000".to_string()),
+                    description: args.name,
+                    access_types: Some(vec![
+                        dap::DataBreakpointAccessType::Read,
+                        dap::DataBreakpointAccessType::Write,
+                    ]),
+                    can_persist: Some(false),
+                })
+            });
+            let data_breakpoints = data_breakpoints.clone();
+            client.on_request::<SetDataBreakpoints, _>(move |_, args| {
+                *data_breakpoints.lock() = args
+                    .breakpoints
+                    .into_iter()
+                    .map(|breakpoint| (breakpoint.data_id, breakpoint.access_type))
+                    .collect::<Vec<_>>();
+                Ok(dap::SetDataBreakpointsResponse {
+                    breakpoints: Vec::new(),
+                })
+            });
+        }
+    })
+    .unwrap();
+    cx.run_until_parked();
+
+    let breakpoint_store = project.read_with(cx, |project, _| project.breakpoint_store());
+    breakpoint_store.update(cx, |breakpoint_store, cx| {
+        breakpoint_store.add_function_breakpoint("main".into(), cx);
+        breakpoint_store.add_function_breakpoint("exit".into(), cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        *function_breakpoints.lock(),
+        vec!["main".to_string(), "exit".to_string()]
+    );
+
+    breakpoint_store.update(cx, |breakpoint_store, cx| {
+        breakpoint_store.edit_function_breakpoint("main", BreakpointEditAction::InvertState, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        *function_breakpoints.lock(),
+        vec!["exit".to_string()],
+        "Disabled function breakpoints should not be sent"
+    );
+
+    session
+        .update(cx, |session, cx| {
+            session.add_data_breakpoint(2, "a".into(), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    assert_eq!(
+        *data_breakpoints.lock(),
+        vec![(
+            "a@0x1000".to_string(),
+            Some(dap::DataBreakpointAccessType::Write)
+        )],
+        "Data breakpoints should prefer stopping on writes"
+    );
+    let session_id = session.read_with(cx, |session, _| session.session_id());
+    breakpoint_store.read_with(cx, |breakpoint_store, _| {
+        let data_breakpoints = breakpoint_store.data_breakpoints();
+        assert_eq!(data_breakpoints.len(), 1);
+        assert_eq!(&*data_breakpoints[0].description, "a");
+        assert_eq!(
+            data_breakpoints[0].access_type,
+            Some(DataBreakpointAccessType::Write)
+        );
+        assert_eq!(data_breakpoints[0].session_id, Some(session_id));
+    });
+
+    assert!(
+        session
+            .update(cx, |session, cx| {
+                session.add_data_breakpoint(2, "b".into(), cx)
+            })
+            .await
+            .is_err(),
+        "Variables the adapter can't watch should not get a data breakpoint"
+    );
+
+    breakpoint_store.update(cx, |breakpoint_store, cx| {
+        breakpoint_store.edit_data_breakpoint("a@0x1000", BreakpointEditAction::Toggle, cx);
+    });
+    cx.run_until_parked();
+    assert!(data_breakpoints.lock().is_empty());
+
+    session
+        .update(cx, |session, cx| {
+            session.add_data_breakpoint(2, "a".into(), cx)
+        })
+        .await
+        .unwrap();
+    let shutdown_session = project.update(cx, |project, cx| {
+        project.dap_store().update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(session_id, cx)
+        })
+    });
+    shutdown_session.await.unwrap();
+    breakpoint_store.read_with(cx, |breakpoint_store, _| {
+        assert!(
+            breakpoint_store.data_breakpoints().is_empty(),
+            "Data breakpoints only valid in a session should be dropped when it ends"
+        );
+    });
+}
@@ -0,0 +1,230 @@
+use crate::{
+    debugger_panel::DebugPanel,
+    persistence::DebuggerPaneItem,
+    session::running::disassembly_view::DisassemblyLine,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    DisassembledInstruction, StackFrame, SteppingGranularity, StoppedEvent,
+    requests::{
+        Disassemble, Initialize, Next, Scopes, SetInstructionBreakpoints, StackTrace, Threads,
+    },
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use parking_lot::Mutex;
+use project::{FakeFs, Project};
+use serde_json::json;
+use std::sync::Arc;
+use util::path;
+
+fn instruction(address: &str, line: u64, with_location: bool) -> DisassembledInstruction {
+    DisassembledInstruction {
+        address: address.into(),
+        instruction_bytes: None,
+        instruction: format!("nop ; {address}"),
+        symbol: None,
+        location: with_location.then(|| dap::Source {
+            name: Some("main.c".into()),
+            path: Some(path!("/project/main.c").into()),
+            source_reference: None,
+            presentation_hint: None,
+            origin: None,
+            sources: None,
+            adapter_data: None,
+            checksums: None,
+        }),
+        line: Some(line),
+        column: None,
+        end_line: None,
+        end_column: None,
+        presentation_hint: None,
+    }
+}
+
+#[gpui::test]
+async fn test_disassembly_view(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.c": "int main() {\n    int a = 1;\n    return a;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_disassemble_request: Some(true),
+                supports_instruction_breakpoints: Some(true),
+                supports_stepping_granularity: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 3,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: Some("0x1004".into()),
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    let disassemble_requests = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<Disassemble, _>({
+        let disassemble_requests = disassemble_requests.clone();
+        move |_, args| {
+            disassemble_requests.lock().push((
+                args.memory_reference,
+                args.instruction_offset,
+                args.instruction_count,
+            ));
+            Ok(dap::DisassembleResponse {
+                instructions: vec![
+                    instruction("0x1000", 2, true),
+                    instruction("0x1002", 2, false),
+                    instruction("0x1004", 3, false),
+                ],
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            item.running_state().clone()
+        });
+
+    running_state.update_in(cx, |this, window, cx| {
+        this.ensure_pane_item(DebuggerPaneItem::Disassembly, window, cx);
+        this.activate_item(DebuggerPaneItem::Disassembly, window, cx);
+        cx.refresh_windows();
+    });
+
+    cx.run_until_parked();
+
+    assert_eq!(
+        *disassemble_requests.lock(),
+        vec![("0x1004".to_string(), Some(-50), 101)],
+        "Instructions around the instruction pointer of the selected frame should be requested"
+    );
+
+    running_state.update(cx, |state, cx| {
+        let lines = state
+            .disassembly_view()
+            .read(cx)
+            .lines()
+            .iter()
+            .map(|line| match line {
+                DisassemblyLine::Source { label, text } => {
+                    format!("{label}: {}", text.as_deref().unwrap_or_default())
+                }
+                DisassemblyLine::Instruction(instruction) => instruction.address.clone(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                "main.c:2:     int a = 1;",
+                "0x1000",
+                "0x1002",
+                "main.c:3:     return a;",
+                "0x1004",
+            ],
+            "Source lines should be interleaved with the instructions compiled from them"
+        );
+    });
+
+    let instruction_breakpoints = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<SetInstructionBreakpoints, _>({
+        let instruction_breakpoints = instruction_breakpoints.clone();
+        move |_, args| {
+            *instruction_breakpoints.lock() = args
+                .breakpoints
+                .into_iter()
+                .map(|breakpoint| breakpoint.instruction_reference)
+                .collect::<Vec<_>>();
+            Ok(dap::SetInstructionBreakpointsResponse {
+                breakpoints: Vec::new(),
+            })
+        }
+    });
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x1002", cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(*instruction_breakpoints.lock(), vec!["0x1002".to_string()]);
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x1000", cx);
+        session.toggle_instruction_breakpoint("0x1002", cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(*instruction_breakpoints.lock(), vec!["0x1000".to_string()]);
+
+    let step_granularity = Arc::new(Mutex::new(None));
+    client.on_request::<Next, _>({
+        let step_granularity = step_granularity.clone();
+        move |_, args| {
+            *step_granularity.lock() = args.granularity;
+            Ok(())
+        }
+    });
+
+    running_state.update(cx, |state, cx| state.step_over_instruction(cx));
+    cx.run_until_parked();
+    assert_eq!(
+        *step_granularity.lock(),
+        Some(SteppingGranularity::Instruction)
+    );
+}
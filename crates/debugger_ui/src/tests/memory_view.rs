@@ -0,0 +1,211 @@
+use crate::{
+    debugger_panel::DebugPanel,
+    persistence::DebuggerPaneItem,
+    session::running::memory_view::{interpret_bytes, parse_hex_bytes},
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use collections::HashMap;
+use dap::{
+    StoppedEvent,
+    requests::{Initialize, ReadMemory, Scopes, StackTrace, Threads, WriteMemory},
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use parking_lot::Mutex;
+use project::{FakeFs, Project, debugger::session::MemoryRegion};
+use serde_json::json;
+use std::sync::Arc;
+use util::path;
+
+#[gpui::test]
+async fn test_memory_view(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.c": "int main() {\n    char a[] = \"Hi!\";\n    return 0;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_read_memory_request: Some(true),
+                supports_write_memory_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: Vec::default(),
+            total_frames: None,
+        })
+    });
+
+    let read_requests = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<ReadMemory, _>({
+        let read_requests = read_requests.clone();
+        move |_, args| {
+            read_requests
+                .lock()
+                .push((args.memory_reference, args.offset, args.count));
+            Ok(dap::ReadMemoryResponse {
+                address: "0x2000".into(),
+                unreadable_bytes: Some(4),
+                // "Hi!\0"
+                data: Some("SGkhAA==".into()),
+            })
+        }
+    });
+
+    let stopped_event = || {
+        dap::messages::Events::Stopped(StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        })
+    };
+
+    client.fake_event(stopped_event()).await;
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            item.running_state().clone()
+        });
+
+    running_state.update_in(cx, |this, window, cx| {
+        this.memory_view().update(cx, |view, cx| {
+            view.show_memory("0x2000".into(), cx);
+        });
+        this.ensure_pane_item(DebuggerPaneItem::Memory, window, cx);
+        this.activate_item(DebuggerPaneItem::Memory, window, cx);
+        cx.refresh_windows();
+    });
+    cx.run_until_parked();
+
+    assert_eq!(
+        *read_requests.lock(),
+        vec![("0x2000".to_string(), Some(0), 256)],
+        "The first page of memory at the memory reference should be read"
+    );
+    running_state.update(cx, |state, cx| {
+        assert_eq!(
+            state.memory_view().read(cx).memory(),
+            &MemoryRegion {
+                address: "0x2000".into(),
+                data: b"Hi!\0".to_vec(),
+                unreadable_bytes: 4,
+            }
+        );
+    });
+
+    let written_memory = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<WriteMemory, _>({
+        let written_memory = written_memory.clone();
+        move |_, args| {
+            written_memory
+                .lock()
+                .push((args.memory_reference, args.offset, args.data));
+            Ok(dap::WriteMemoryResponse {
+                offset: None,
+                bytes_written: Some(2),
+            })
+        }
+    });
+
+    session
+        .update(cx, |session, _| {
+            session.write_memory("0x2000".into(), 2, vec![0xde, 0xad])
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        *written_memory.lock(),
+        vec![("0x2000".to_string(), Some(2), "3q0=".to_string())],
+        "Written bytes should be sent base64 encoded"
+    );
+
+    client.fake_event(stopped_event()).await;
+    cx.run_until_parked();
+
+    assert_eq!(
+        read_requests.lock().len(),
+        2,
+        "Memory should be read again when the program stops"
+    );
+}
+
+#[test]
+fn test_parse_hex_bytes() {
+    assert_eq!(
+        parse_hex_bytes("de ad BE ef"),
+        Some(vec![0xde, 0xad, 0xbe, 0xef])
+    );
+    assert_eq!(parse_hex_bytes("0a0b"), Some(vec![0x0a, 0x0b]));
+    assert_eq!(parse_hex_bytes("abc"), None);
+    assert_eq!(parse_hex_bytes("zz"), None);
+    assert_eq!(parse_hex_bytes("  "), None);
+}
+
+#[test]
+fn test_interpret_bytes() {
+    let values = interpret_bytes(&[0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0])
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    assert_eq!(values["u8"], "255");
+    assert_eq!(values["i8"], "-1");
+    assert_eq!(values["u16"], "65535");
+    assert_eq!(values["i16"], "-1");
+    assert_eq!(values["u32"], "4294967295");
+    assert_eq!(values["i32"], "-1");
+    assert_eq!(values["u64"], "4294967295");
+    assert_eq!(values["i64"], "4294967295");
+    assert_eq!(values["UTF-8"], "invalid");
+
+    let values = interpret_bytes(&[0xc3, 0xa9])
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    assert_eq!(values["u16"], "43459");
+    assert_eq!(values["UTF-8"], "'é' U+00E9");
+    assert!(
+        !values.contains_key("u32"),
+        "Data types wider than the remaining bytes should not be interpreted"
+    );
+
+    let values = interpret_bytes(&[0, 0, 0, 0, 0, 0, 0xf0, 0x3f])
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    assert_eq!(values["f64"], "1");
+    assert_eq!(values["f32"], "0");
+}
@@ -0,0 +1,248 @@
+use crate::{
+    debugger_panel::DebugPanel,
+    session::running::variable_list::{ExpandSelectedEntry, RemoveWatch},
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    ErrorResponse, EvaluateArgumentsContext, StackFrame, StoppedEvent, Variable,
+    requests::{Evaluate, Scopes, StackTrace, Threads, Variables},
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use menu::{SelectFirst, SelectNext};
+use parking_lot::Mutex;
+use project::{FakeFs, Project};
+use serde_json::json;
+use std::sync::Arc;
+use util::path;
+
+fn variable(name: &str, value: &str) -> Variable {
+    Variable {
+        name: name.into(),
+        value: value.into(),
+        type_: None,
+        presentation_hint: None,
+        evaluate_name: None,
+        variables_reference: 0,
+        named_variables: None,
+        indexed_variables: None,
+        memory_reference: None,
+        declaration_location_reference: None,
+        value_location_reference: None,
+    }
+}
+
+#[gpui::test]
+async fn test_watch_expressions(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "fn main() {\n    let a = 1;\n    let b = 2;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let breakpoint_store = project.read_with(cx, |project, _| project.breakpoint_store());
+    breakpoint_store.update(cx, |breakpoint_store, cx| {
+        breakpoint_store.add_watch_expression("a + b".into(), cx);
+        breakpoint_store.add_watch_expression("point".into(), cx);
+        breakpoint_store.add_watch_expression("missing".into(), cx);
+        breakpoint_store.add_watch_expression("a + b".into(), cx);
+    });
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: Some(dap::Source {
+                    name: Some("main.rs".into()),
+                    path: Some(path!("/project/main.rs").into()),
+                    source_reference: None,
+                    presentation_hint: None,
+                    origin: None,
+                    sources: None,
+                    adapter_data: None,
+                    checksums: None,
+                }),
+                line: 3,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: None,
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+
+    let evaluated = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<Evaluate, _>({
+        let evaluated = evaluated.clone();
+        move |_, args| {
+            assert_eq!(args.context, Some(EvaluateArgumentsContext::Watch));
+            assert_eq!(args.frame_id, Some(1));
+            evaluated.lock().push(args.expression.clone());
+
+            let (result, variables_reference) = match args.expression.as_str() {
+                "a + b" => ("3", 0),
+                "point" => ("Point", 5),
+                _ => {
+                    return Err(ErrorResponse {
+                        error: Some(dap::Message {
+                            id: 1,
+                            format: "cannot find value `missing`".into(),
+                            variables: None,
+                            send_telemetry: None,
+                            show_user: None,
+                            url: None,
+                            url_label: None,
+                        }),
+                    });
+                }
+            };
+            Ok(dap::EvaluateResponse {
+                result: result.into(),
+                type_: None,
+                presentation_hint: None,
+                variables_reference,
+                named_variables: None,
+                indexed_variables: None,
+                memory_reference: None,
+                value_location_reference: None,
+            })
+        }
+    });
+    client.on_request::<Variables, _>(move |_, args| {
+        assert_eq!(args.variables_reference, 5);
+        Ok(dap::VariablesResponse {
+            variables: vec![variable("x", "1"), variable("y", "2")],
+        })
+    });
+
+    let stopped_event = || {
+        dap::messages::Events::Stopped(StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        })
+    };
+
+    client.fake_event(stopped_event()).await;
+    cx.run_until_parked();
+
+    let evaluated_expressions = |evaluated: &Mutex<Vec<String>>| {
+        let mut expressions = std::mem::take(&mut *evaluated.lock());
+        expressions.sort();
+        expressions.dedup();
+        expressions
+    };
+    assert_eq!(
+        evaluated_expressions(&evaluated),
+        vec!["a + b", "missing", "point"],
+        "Each watch expression should be evaluated in the selected stack frame"
+    );
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            let running = item.running_state().clone();
+            let watch_list = running.update(cx, |state, cx| {
+                state.show_watch(window, cx);
+                state.watch_list().clone()
+            });
+            watch_list.update(cx, |_, cx| cx.focus_self(window));
+            running
+        });
+    cx.run_until_parked();
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.watch_list().update(cx, |watch_list, _| {
+            let variables = watch_list.variables();
+            assert_eq!(variables.len(), 3);
+            assert_eq!(variables[0].value, "3");
+            assert_eq!(variables[1].value, "Point");
+            assert_eq!(variables[1].variables_reference, 5);
+            assert!(
+                !variables[2].value.is_empty(),
+                "Expressions that fail to evaluate should show the error"
+            );
+            watch_list.assert_visual_entries(vec!["> a + b", "> point", "> missing"]);
+        });
+    });
+
+    cx.dispatch_action(SelectFirst);
+    cx.dispatch_action(SelectNext);
+    cx.dispatch_action(ExpandSelectedEntry);
+    cx.run_until_parked();
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.watch_list().update(cx, |watch_list, _| {
+            watch_list.assert_visual_entries(vec![
+                "> a + b",
+                "v point <=== selected",
+                "    > x",
+                "    > y",
+                "> missing",
+            ]);
+        });
+    });
+
+    client.fake_event(stopped_event()).await;
+    cx.run_until_parked();
+    assert_eq!(
+        evaluated_expressions(&evaluated),
+        vec!["a + b", "missing", "point"],
+        "Watch expressions should be evaluated again whenever the program stops"
+    );
+
+    running_state.update_in(cx, |running_state, window, cx| {
+        running_state
+            .watch_list()
+            .update(cx, |_, cx| cx.focus_self(window));
+    });
+    cx.dispatch_action(SelectFirst);
+    cx.dispatch_action(RemoveWatch);
+    cx.run_until_parked();
+
+    assert_eq!(
+        breakpoint_store.read_with(cx, |breakpoint_store, _| {
+            breakpoint_store.watch_expressions().to_vec()
+        }),
+        vec![Arc::<str>::from("point"), Arc::<str>::from("missing")]
+    );
+    running_state.update(cx, |running_state, cx| {
+        running_state.watch_list().update(cx, |watch_list, _| {
+            watch_list.assert_visual_entries(vec!["v point", "    > x", "    > y", "> missing"]);
+        });
+    });
+}
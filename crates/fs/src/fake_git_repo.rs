@@ -5,8 +5,10 @@ use futures::future::{self, BoxFuture};
 use git::{
     blame::Blame,
     repository::{
-        AskPassDelegate, Branch, CommitDetails, CommitOptions, FetchOptions, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode,
+        AskPassDelegate, Branch, CommitDetails, CommitDiff, CommitLogEntry, CommitOptions,
+        ConflictStages, FetchOptions, GitOperation, GitRepository, GitRepositoryCheckpoint,
+        LogOptions, PushOptions, RebaseTodoEntry, Remote, RepoPath, ResetMode, StashEntry, Tag,
+        Worktree,
     },
     status::{FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus},
 };
@@ -104,6 +106,10 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn load_conflict_stages(&self, _path: RepoPath) -> BoxFuture<Result<ConflictStages>> {
+        unimplemented!()
+    }
+
     fn load_commit(
         &self,
         _commit: String,
@@ -112,6 +118,43 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn log(&self, _options: LogOptions) -> BoxFuture<Result<Vec<CommitLogEntry>>> {
+        unimplemented!()
+    }
+
+    fn in_progress_operation(&self) -> BoxFuture<Option<GitOperation>> {
+        async { None }.boxed()
+    }
+
+    fn rebase_interactive(
+        &self,
+        _base: String,
+        _todo: Vec<RebaseTodoEntry>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn cherry_pick(
+        &self,
+        _commit: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn revert(&self, _commit: String, _env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn continue_operation(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn abort_operation(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -153,6 +196,43 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn stash_push(
+        &self,
+        _message: Option<String>,
+        _paths: Vec<RepoPath>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn stash_list(&self) -> BoxFuture<Result<Vec<StashEntry>>> {
+        future::ready(Ok(Vec::new())).boxed()
+    }
+
+    fn stash_apply(
+        &self,
+        _sha: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn stash_pop(&self, _sha: String, _env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn stash_drop(
+        &self,
+        _sha: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn stash_show(&self, _sha: String, _cx: AsyncApp) -> BoxFuture<Result<CommitDiff>> {
+        unimplemented!()
+    }
+
     fn reset(
         &self,
         _commit: String,
@@ -337,13 +417,85 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
-    fn create_branch(&self, name: String) -> BoxFuture<Result<()>> {
+    fn create_branch(&self, name: String, _base_revision: Option<String>) -> BoxFuture<Result<()>> {
         self.with_state_async(true, move |state| {
             state.branches.insert(name.to_owned());
             Ok(())
         })
     }
 
+    fn delete_branch(&self, name: String, _force: bool) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.current_branch_name.as_ref() == Some(&name) {
+                anyhow::bail!("cannot delete the current branch {name}");
+            }
+            if !state.branches.remove(&name) {
+                anyhow::bail!("branch {name} not found");
+            }
+            Ok(())
+        })
+    }
+
+    fn is_branch_merged(&self, _name: String) -> BoxFuture<Result<bool>> {
+        async { Ok(true) }.boxed()
+    }
+
+    fn rename_branch(&self, old_name: String, new_name: String) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            if !state.branches.remove(&old_name) {
+                anyhow::bail!("branch {old_name} not found");
+            }
+            if state.current_branch_name.as_ref() == Some(&old_name) {
+                state.current_branch_name = Some(new_name.clone());
+            }
+            state.branches.insert(new_name);
+            Ok(())
+        })
+    }
+
+    fn set_upstream(&self, _name: String, _upstream: Option<String>) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn diff_to_merge_base(&self, _base: String) -> BoxFuture<Result<CommitDiff>> {
+        unimplemented!()
+    }
+
+    fn worktrees(&self) -> BoxFuture<Result<Vec<Worktree>>> {
+        unimplemented!()
+    }
+
+    fn create_worktree(
+        &self,
+        _path: PathBuf,
+        _branch_name: String,
+        _create_branch: bool,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn remove_worktree(&self, _path: PathBuf, _force: bool) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn tags(&self, _points_at: Option<String>) -> BoxFuture<Result<Vec<Tag>>> {
+        async { Ok(Vec::new()) }.boxed()
+    }
+
+    fn create_tag(
+        &self,
+        _name: String,
+        _commit: String,
+        _message: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn delete_tag(&self, _name: String) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
     fn blame(&self, path: RepoPath, _content: Rope) -> BoxFuture<Result<git::blame::Blame>> {
         self.with_state_async(false, move |state| {
             state
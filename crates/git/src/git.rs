@@ -12,15 +12,19 @@ pub use git2 as libgit;
 use gpui::action_with_deprecated_aliases;
 use gpui::actions;
 use gpui::impl_action_with_deprecated_aliases;
+use gpui::impl_actions;
 pub use repository::WORK_DIRECTORY_REPO_PATH;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::ffi::OsStr;
 use std::fmt;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::LazyLock;
 
 pub static DOT_GIT: LazyLock<&'static OsStr> = LazyLock::new(|| OsStr::new(".git"));
+/// The metadata directory of a jj workspace, whose git backend lives inside it.
+pub static DOT_JJ: LazyLock<&'static OsStr> = LazyLock::new(|| OsStr::new(".jj"));
 pub static GITIGNORE: LazyLock<&'static OsStr> = LazyLock::new(|| OsStr::new(".gitignore"));
 pub static FSMONITOR_DAEMON: LazyLock<&'static OsStr> =
     LazyLock::new(|| OsStr::new("fsmonitor--daemon"));
@@ -39,11 +43,19 @@ actions!(
         // per-file
         StageFile,
         UnstageFile,
+        FileHistory,
+        OpenMergeTool,
         // repo-wide
         StageAll,
         UnstageAll,
         RestoreTrackedFiles,
         TrashUntrackedFiles,
+        StashAll,
+        StashPop,
+        ViewHistory,
+        ContinueOperation,
+        AbortOperation,
+        Worktrees,
         Uncommit,
         Push,
         PushTo,
@@ -66,6 +78,14 @@ pub struct RestoreFile {
     pub skip_prompt: bool,
 }
 
+/// Opens the commit history of a file or directory, such as one selected in the project panel.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct OpenFileHistory {
+    pub abs_path: PathBuf,
+}
+
+impl_actions!(git, [OpenFileHistory]);
 impl_action_with_deprecated_aliases!(git, RestoreFile, ["editor::RevertFile"]);
 action_with_deprecated_aliases!(git, Restore, ["editor::RevertSelectedHunks"]);
 action_with_deprecated_aliases!(git, Blame, ["editor::ToggleGitBlame"]);
@@ -152,6 +152,172 @@ pub struct CommitSummary {
     pub has_parent: bool,
 }
 
+/// A working tree of the repository, as listed by `git worktree list`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Worktree {
+    pub path: PathBuf,
+    /// The checked out branch, or `None` when HEAD is detached.
+    pub ref_name: Option<SharedString>,
+    pub sha: SharedString,
+    /// Whether this is the repository's main working tree, rather than a linked one.
+    pub is_main: bool,
+    pub is_locked: bool,
+    /// Whether a linked working tree has modified or untracked files, which removing it
+    /// would discard.
+    pub has_changes: bool,
+}
+
+impl Worktree {
+    pub fn branch_name(&self) -> Option<&str> {
+        let ref_name = self.ref_name.as_ref()?;
+        Some(
+            ref_name
+                .strip_prefix("refs/heads/")
+                .unwrap_or(ref_name.as_ref()),
+        )
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Tag {
+    pub name: SharedString,
+    /// The commit that the tag points at.
+    pub sha: SharedString,
+    /// The subject of an annotated tag's message, or `None` for a lightweight tag.
+    pub message: Option<SharedString>,
+    /// This is a unix timestamp of when the tag was created, or of the tagged commit for a
+    /// lightweight tag.
+    pub timestamp: i64,
+}
+
+impl Tag {
+    pub fn is_annotated(&self) -> bool {
+        self.message.is_some()
+    }
+}
+
+/// The versions of a conflicted file that git records in the index during a merge.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConflictStages {
+    /// The common ancestor (stage 1), or `None` if the file didn't exist there.
+    pub base: Option<String>,
+    /// The version on the current branch (stage 2).
+    pub ours: Option<String>,
+    /// The version being merged in (stage 3).
+    pub theirs: Option<String>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct StashEntry {
+    /// The position of the entry in the stash, as in `stash@{index}`.
+    pub index: usize,
+    pub sha: SharedString,
+    pub message: SharedString,
+    /// The branch that was checked out when the entry was created.
+    pub branch: Option<SharedString>,
+    /// This is a unix timestamp
+    pub timestamp: i64,
+}
+
+/// Selects the commits returned by [`GitRepository::log`].
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct LogOptions {
+    /// A revision or revision range such as `main..feature`. Defaults to `HEAD`.
+    pub revision_range: Option<String>,
+    /// Only include commits that touch one of these paths.
+    pub paths: Vec<RepoPath>,
+    /// Only include commits whose author matches this pattern.
+    pub author: Option<String>,
+    /// The number of commits to skip, for loading history a page at a time.
+    pub skip: usize,
+    pub limit: usize,
+}
+
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct CommitLogEntry {
+    pub sha: SharedString,
+    pub parent_shas: Vec<SharedString>,
+    pub subject: SharedString,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+    /// This is a unix timestamp
+    pub commit_timestamp: i64,
+    /// Branches and tags pointing at this commit, e.g. `origin/main` or `tag: v1.0`.
+    pub refs: Vec<SharedString>,
+}
+
+impl CommitLogEntry {
+    pub fn short_sha(&self) -> &str {
+        self.sha.get(..SHORT_SHA_LENGTH).unwrap_or(&self.sha)
+    }
+}
+
+/// An operation that stopped partway through, usually because of conflicts, and that has to be
+/// continued or aborted before anything else can be done.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum GitOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+}
+
+impl GitOperation {
+    /// The git subcommand that continues or aborts the operation.
+    pub fn command(&self) -> &'static str {
+        match self {
+            GitOperation::Merge => "merge",
+            GitOperation::Rebase => "rebase",
+            GitOperation::CherryPick => "cherry-pick",
+            GitOperation::Revert => "revert",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            GitOperation::Merge => "Merge",
+            GitOperation::Rebase => "Rebase",
+            GitOperation::CherryPick => "Cherry-pick",
+            GitOperation::Revert => "Revert",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum RebaseAction {
+    Pick,
+    Reword,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl RebaseAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RebaseAction::Pick => "pick",
+            RebaseAction::Reword => "reword",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+        }
+    }
+
+    /// Whether the commit is folded into the one before it.
+    pub fn melds_into_previous(&self) -> bool {
+        matches!(self, RebaseAction::Squash | RebaseAction::Fixup)
+    }
+}
+
+/// A line of an interactive rebase's todo list.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseAction,
+    pub sha: SharedString,
+    /// The new message of a reworded commit.
+    pub message: Option<String>,
+}
+
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
 pub struct CommitDetails {
     pub sha: SharedString,
@@ -310,6 +476,9 @@ pub trait GitRepository: Send + Sync {
     /// Also returns `None` for symlinks.
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<Option<String>>;
 
+    /// Returns the versions of a conflicted file stored in index stages 1, 2 and 3.
+    fn load_conflict_stages(&self, path: RepoPath) -> BoxFuture<Result<ConflictStages>>;
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -342,7 +511,57 @@ pub trait GitRepository: Send + Sync {
     fn branches(&self) -> BoxFuture<Result<Vec<Branch>>>;
 
     fn change_branch(&self, name: String) -> BoxFuture<Result<()>>;
-    fn create_branch(&self, name: String) -> BoxFuture<Result<()>>;
+    /// Creates a branch pointing at `base_revision`, or at HEAD when no revision is given.
+    fn create_branch(&self, name: String, base_revision: Option<String>) -> BoxFuture<Result<()>>;
+
+    /// Deletes a local branch. Unless `force` is set, this fails when the branch hasn't been
+    /// merged into its upstream or HEAD.
+    fn delete_branch(&self, name: String, force: bool) -> BoxFuture<Result<()>>;
+
+    /// Whether deleting the local branch `name` would keep all of its commits reachable, i.e.
+    /// whether it has been merged into its upstream or, when it has none, into HEAD.
+    fn is_branch_merged(&self, name: String) -> BoxFuture<Result<bool>>;
+
+    fn rename_branch(&self, old_name: String, new_name: String) -> BoxFuture<Result<()>>;
+
+    /// Sets the branch that `name` tracks, or stops tracking one when `upstream` is `None`.
+    fn set_upstream(&self, name: String, upstream: Option<String>) -> BoxFuture<Result<()>>;
+
+    /// Loads the files that differ between the working tree and the merge base of HEAD and
+    /// `base`, with their contents at the merge base as the old text.
+    fn diff_to_merge_base(&self, base: String) -> BoxFuture<Result<CommitDiff>>;
+
+    /// Lists the repository's working trees, starting with the main one.
+    fn worktrees(&self) -> BoxFuture<Result<Vec<Worktree>>>;
+
+    /// Adds a linked working tree at `path` with `branch_name` checked out. When `create_branch`
+    /// is set, the branch is created at HEAD first.
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch_name: String,
+        create_branch: bool,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Removes a linked working tree. Unless `force` is set, this fails when the working tree
+    /// has local modifications.
+    fn remove_worktree(&self, path: PathBuf, force: bool) -> BoxFuture<Result<()>>;
+
+    /// Lists the repository's tags, most recently created first. When `points_at` is given,
+    /// only the tags of that commit are listed.
+    fn tags(&self, points_at: Option<String>) -> BoxFuture<Result<Vec<Tag>>>;
+
+    /// Creates a tag pointing at `commit`. The tag is annotated when a `message` is given, and
+    /// lightweight otherwise.
+    fn create_tag(
+        &self,
+        name: String,
+        commit: String,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    fn delete_tag(&self, name: String) -> BoxFuture<Result<()>>;
 
     fn reset(
         &self,
@@ -358,8 +577,60 @@ pub trait GitRepository: Send + Sync {
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<Result<()>>;
 
+    /// Saves the local modifications to `paths` (or to every file, when `paths` is empty) as a
+    /// new stash entry, including untracked files, and reverts them in the working tree.
+    fn stash_push(
+        &self,
+        message: Option<String>,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Lists the stash entries, most recent first.
+    fn stash_list(&self) -> BoxFuture<Result<Vec<StashEntry>>>;
+
+    /// Applies the stash entry whose commit is `sha`. Entries are addressed by commit, because
+    /// their positions shift whenever an entry is pushed or dropped.
+    fn stash_apply(&self, sha: String, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>>;
+
+    fn stash_pop(&self, sha: String, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>>;
+
+    fn stash_drop(&self, sha: String, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>>;
+
+    /// Loads the changes recorded in a stash entry, relative to the commit it was created on,
+    /// including the untracked files it saved.
+    fn stash_show(&self, sha: String, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>>;
+
     fn show(&self, commit: String) -> BoxFuture<Result<CommitDetails>>;
 
+    /// Returns the operation that's waiting to be continued or aborted, if any.
+    fn in_progress_operation(&self) -> BoxFuture<Option<GitOperation>>;
+
+    /// Rewrites the commits after `base` by running `todo`, which lists them oldest first.
+    fn rebase_interactive(
+        &self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    fn cherry_pick(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    fn revert(&self, commit: String, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>>;
+
+    /// Continues the in-progress operation, after its conflicts have been resolved.
+    fn continue_operation(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>>;
+
+    /// Aborts the in-progress operation, restoring the state from before it started.
+    fn abort_operation(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>>;
+
+    /// Lists commits in reverse chronological order, with children before their parents.
+    fn log(&self, options: LogOptions) -> BoxFuture<Result<Vec<CommitLogEntry>>>;
+
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>>;
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<Result<crate::blame::Blame>>;
 
@@ -486,7 +757,11 @@ impl RealGitRepository {
         executor: BackgroundExecutor,
     ) -> Option<Self> {
         let workdir_root = dotgit_path.parent()?;
-        let repository = git2::Repository::open(workdir_root).log_err()?;
+        // The git store of a pure jj workspace is a bare repository with no
+        // work directory of its own, so open it directly.
+        let repository = git2::Repository::open(workdir_root)
+            .or_else(|_| git2::Repository::open_bare(dotgit_path))
+            .log_err()?;
         Some(Self {
             repository: Arc::new(Mutex::new(repository)),
             git_binary_path: git_binary_path.unwrap_or_else(|| PathBuf::from("git")),
@@ -495,12 +770,94 @@ impl RealGitRepository {
     }
 
     fn working_directory(&self) -> Result<PathBuf> {
-        self.repository
-            .lock()
+        let repository = self.repository.lock();
+        if repository.is_bare() {
+            return Ok(repository.path().to_path_buf());
+        }
+        repository
             .workdir()
             .context("failed to read git work directory")
             .map(Path::to_path_buf)
     }
+
+    /// Runs `git branch` with the given arguments, reporting git's error message on failure.
+    fn run_branch_command(&self, args: Vec<String>) -> BoxFuture<'_, Result<()>> {
+        self.run_reporting_stderr("branch", args)
+    }
+
+    /// Runs a git subcommand with the given arguments, reporting git's error message on failure.
+    fn run_reporting_stderr(
+        &self,
+        subcommand: &'static str,
+        args: Vec<String>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(working_directory?)
+                    .arg(subcommand)
+                    .args(&args)
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "{}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    /// Runs a git command that may stop for conflicts, without opening an editor for commit
+    /// messages.
+    fn run_sequencer_command(
+        &self,
+        args: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                run_sequencer_command(&git_binary_path, &working_directory?, &args, &env, None)
+                    .await
+            })
+            .boxed()
+    }
+
+    fn run_stash_command(
+        &self,
+        subcommand: &'static str,
+        sha: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let git =
+                    GitBinary::new(git_binary_path.clone(), working_directory.clone(), executor);
+                let stash_ref = stash_entry_ref(&git, &sha).await?;
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory)
+                    .envs(env.iter())
+                    .args(["stash", subcommand, "--quiet", &stash_ref])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to {subcommand} stash:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -561,13 +918,58 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<Vec<CommitLogEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let revision_range = options.revision_range.as_deref().unwrap_or("HEAD");
+                if revision_range.starts_with('-') {
+                    bail!("invalid revision range {revision_range:?}");
+                }
+
+                let mut args = vec![
+                    "--no-optional-locks".to_string(),
+                    "log".to_string(),
+                    "--date-order".to_string(),
+                    "--decorate=short".to_string(),
+                    format!("--format={LOG_FORMAT}"),
+                    format!("--skip={}", options.skip),
+                    format!("--max-count={}", options.limit),
+                ];
+                if let Some(author) = options.author.filter(|author| !author.is_empty()) {
+                    args.push(format!("--author={author}"));
+                }
+                if !options.paths.is_empty() {
+                    // Rewrite parents to skip commits that don't touch the paths, so that the
+                    // graph stays connected.
+                    args.push("--parents".to_string());
+                }
+                args.push(revision_range.to_string());
+                args.push("--".to_string());
+                args.extend(
+                    options
+                        .paths
+                        .iter()
+                        .map(|path| path.to_string_lossy().into_owned()),
+                );
+
+                let output = git.run(&args).await?;
+                parse_log(&output)
+            })
+            .boxed()
+    }
+
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>> {
         let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
         else {
             return future::ready(Err(anyhow!("no working directory"))).boxed();
         };
+        let git_binary_path = self.git_binary_path.clone();
         cx.background_spawn(async move {
-            let show_output = util::command::new_std_command("git")
+            let show_output = util::command::new_std_command(&git_binary_path)
                 .current_dir(&working_directory)
                 .args([
                     "--no-optional-locks",
@@ -586,76 +988,20 @@ impl GitRepository for RealGitRepository {
 
             let show_stdout = String::from_utf8_lossy(&show_output.stdout);
             let mut lines = show_stdout.split('\n');
-            let parent_sha = lines.next().unwrap().trim().trim_end_matches('\0');
+            let parent_sha = lines
+                .next()
+                .unwrap()
+                .trim()
+                .trim_end_matches('\0')
+                .split(' ')
+                .next()
+                .unwrap_or_default();
             let changes = parse_git_diff_name_status(lines.next().unwrap_or(""));
 
-            let mut cat_file_process = util::command::new_std_command("git")
-                .current_dir(&working_directory)
-                .args(["--no-optional-locks", "cat-file", "--batch=%(objectsize)"])
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .context("starting git cat-file process")?;
-
-            use std::io::Write as _;
-            let mut files = Vec::<CommitFile>::new();
-            let mut stdin = BufWriter::with_capacity(512, cat_file_process.stdin.take().unwrap());
-            let mut stdout = BufReader::new(cat_file_process.stdout.take().unwrap());
-            let mut info_line = String::new();
-            let mut newline = [b'\0'];
+            let mut cat_file = CatFile::spawn(&git_binary_path, &working_directory)?;
+            let mut files = Vec::new();
             for (path, status_code) in changes {
-                match status_code {
-                    StatusCode::Modified => {
-                        writeln!(&mut stdin, "{commit}:{}", path.display())?;
-                        writeln!(&mut stdin, "{parent_sha}:{}", path.display())?;
-                    }
-                    StatusCode::Added => {
-                        writeln!(&mut stdin, "{commit}:{}", path.display())?;
-                    }
-                    StatusCode::Deleted => {
-                        writeln!(&mut stdin, "{parent_sha}:{}", path.display())?;
-                    }
-                    _ => continue,
-                }
-                stdin.flush()?;
-
-                info_line.clear();
-                stdout.read_line(&mut info_line)?;
-
-                let len = info_line.trim_end().parse().with_context(|| {
-                    format!("invalid object size output from cat-file {info_line}")
-                })?;
-                let mut text = vec![0; len];
-                stdout.read_exact(&mut text)?;
-                stdout.read_exact(&mut newline)?;
-                let text = String::from_utf8_lossy(&text).to_string();
-
-                let mut old_text = None;
-                let mut new_text = None;
-                match status_code {
-                    StatusCode::Modified => {
-                        info_line.clear();
-                        stdout.read_line(&mut info_line)?;
-                        let len = info_line.trim_end().parse().with_context(|| {
-                            format!("invalid object size output from cat-file {}", info_line)
-                        })?;
-                        let mut parent_text = vec![0; len];
-                        stdout.read_exact(&mut parent_text)?;
-                        stdout.read_exact(&mut newline)?;
-                        old_text = Some(String::from_utf8_lossy(&parent_text).to_string());
-                        new_text = Some(text);
-                    }
-                    StatusCode::Added => new_text = Some(text),
-                    StatusCode::Deleted => old_text = Some(text),
-                    _ => continue,
-                }
-
-                files.push(CommitFile {
-                    path: path.into(),
-                    old_text,
-                    new_text,
-                })
+                files.extend(cat_file.commit_file(path, status_code, parent_sha, &commit)?);
             }
 
             Ok(CommitDiff { files })
@@ -723,67 +1069,325 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
-    fn load_index_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
-        // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
-        const GIT_MODE_SYMLINK: u32 = 0o120000;
-
-        let repo = self.repository.clone();
+    fn stash_push(
+        &self,
+        message: Option<String>,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
         self.executor
             .spawn(async move {
-                fn logic(repo: &git2::Repository, path: &RepoPath) -> Result<Option<String>> {
-                    // This check is required because index.get_path() unwraps internally :(
-                    check_path_to_repo_path_errors(path)?;
-
-                    let mut index = repo.index()?;
-                    index.read(false)?;
-
-                    const STAGE_NORMAL: i32 = 0;
-                    let oid = match index.get_path(path, STAGE_NORMAL) {
-                        Some(entry) if entry.mode != GIT_MODE_SYMLINK => entry.id,
-                        _ => return Ok(None),
-                    };
-
-                    let content = repo.find_blob(oid)?.content().to_owned();
-                    Ok(String::from_utf8(content).ok())
+                let mut cmd = new_smol_command(&git_binary_path);
+                cmd.current_dir(&working_directory?).envs(env.iter()).args([
+                    "stash",
+                    "push",
+                    "--quiet",
+                    "--include-untracked",
+                ]);
+                if let Some(message) = message {
+                    cmd.arg("--message").arg(message);
                 }
-
-                match logic(&repo.lock(), &path) {
-                    Ok(value) => return value,
-                    Err(err) => log::error!("Error loading index text: {:?}", err),
+                if !paths.is_empty() {
+                    cmd.arg("--").args(paths.iter().map(|p| p.to_unix_style()));
                 }
-                None
+
+                let output = cmd.output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to stash:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
             })
             .boxed()
     }
 
-    fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
-        let repo = self.repository.clone();
+    fn stash_list(&self) -> BoxFuture<'_, Result<Vec<StashEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
         self.executor
             .spawn(async move {
-                let repo = repo.lock();
-                let head = repo.head().ok()?.peel_to_tree().log_err()?;
-                let entry = head.get_path(&path).ok()?;
-                if entry.filemode() == i32::from(git2::FileMode::Link) {
-                    return None;
-                }
-                let content = repo.find_blob(entry.id()).log_err()?.content().to_owned();
-                String::from_utf8(content).ok()
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let output = git
+                    .run(&["stash", "list", "--format=%H%x00%ct%x00%gs"])
+                    .await?;
+                parse_stash_list(&output)
             })
             .boxed()
     }
 
-    fn set_index_text(
+    fn stash_apply(
         &self,
-        path: RepoPath,
-        content: Option<String>,
+        sha: String,
         env: Arc<HashMap<String, String>>,
-    ) -> BoxFuture<'_, anyhow::Result<()>> {
-        let working_directory = self.working_directory();
-        let git_binary_path = self.git_binary_path.clone();
-        self.executor
-            .spawn(async move {
-                let working_directory = working_directory?;
-                if let Some(content) = content {
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_stash_command("apply", sha, env)
+    }
+
+    fn stash_pop(
+        &self,
+        sha: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_stash_command("pop", sha, env)
+    }
+
+    fn stash_drop(
+        &self,
+        sha: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_stash_command("drop", sha, env)
+    }
+
+    fn stash_show(&self, sha: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        cx.background_spawn(async move {
+            let working_directory = working_directory?;
+            let git = GitBinary::new(git_binary_path.clone(), working_directory.clone(), executor);
+            stash_entry_ref(&git, &sha).await?;
+
+            // The entry's first parent is the commit it was created on, and its third parent,
+            // when present, records the untracked files.
+            let base = format!("{sha}^1");
+            let untracked_commit = format!("{sha}^3");
+            let changes = git
+                .run(&["diff", "--no-renames", "--name-status", "-z", &base, &sha])
+                .await?;
+            let untracked = if git
+                .run(&["rev-parse", "--verify", "--quiet", &untracked_commit])
+                .await
+                .is_ok()
+            {
+                git.run(&["ls-tree", "-r", "-z", "--name-only", &untracked_commit])
+                    .await?
+            } else {
+                String::new()
+            };
+
+            let mut cat_file = CatFile::spawn(&git_binary_path, &working_directory)?;
+            let mut files = Vec::new();
+            for (path, status_code) in parse_git_diff_name_status(&changes) {
+                files.extend(cat_file.commit_file(path, status_code, &base, &sha)?);
+            }
+            for path in untracked.split('\0').filter(|path| !path.is_empty()) {
+                files.extend(cat_file.commit_file(
+                    Path::new(path),
+                    StatusCode::Added,
+                    &base,
+                    &untracked_commit,
+                )?);
+            }
+            files.sort_by(|a, b| a.path.cmp(&b.path));
+
+            Ok(CommitDiff { files })
+        })
+        .boxed()
+    }
+
+    fn in_progress_operation(&self) -> BoxFuture<'_, Option<GitOperation>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move { detect_in_progress_operation(&git_dir) })
+            .boxed()
+    }
+
+    fn rebase_interactive(
+        &self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let git_dir = self.path();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                anyhow::ensure!(is_commit_sha(&base), "invalid rebase base {base:?}");
+                let git =
+                    GitBinary::new(git_binary_path.clone(), working_directory.clone(), executor);
+                let mut todo_text = String::new();
+                for entry in &todo {
+                    anyhow::ensure!(is_commit_sha(&entry.sha), "invalid commit {:?}", entry.sha);
+                    match (entry.action, entry.message.as_deref()) {
+                        // Rewording through git would open an editor, so pick a copy of the
+                        // commit that already carries the new message instead.
+                        (RebaseAction::Reword, Some(message)) => {
+                            let sha = git.reworded_commit(&entry.sha, message).await?;
+                            todo_text.push_str(&format!("pick {sha}\n"));
+                        }
+                        (action, _) => {
+                            todo_text.push_str(&format!("{} {}\n", action.as_str(), entry.sha))
+                        }
+                    }
+                }
+
+                let id = Uuid::new_v4();
+                let todo_path = git_dir.join(format!("rebase-todo-{id}"));
+                let editor_path =
+                    git_dir.join(format!("rebase-editor-{id}.{SEQUENCE_EDITOR_EXTENSION}"));
+                smol::fs::write(&todo_path, todo_text).await?;
+                let result = async {
+                    write_sequence_editor(&editor_path, &todo_path).await?;
+                    // Replace the todo list that git generates with ours.
+                    run_sequencer_command(
+                        &git_binary_path,
+                        &working_directory,
+                        &["rebase".into(), "--interactive".into(), base],
+                        &env,
+                        Some(shell_quote(&editor_path.to_string_lossy())),
+                    )
+                    .await
+                }
+                .await;
+                smol::fs::remove_file(&todo_path).await.log_err();
+                smol::fs::remove_file(&editor_path).await.ok();
+                result
+            })
+            .boxed()
+    }
+
+    fn cherry_pick(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        if !is_commit_sha(&commit) {
+            return future::ready(Err(anyhow!("invalid commit {commit:?}"))).boxed();
+        }
+        self.run_sequencer_command(vec!["cherry-pick".into(), commit], env)
+    }
+
+    fn revert(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        if !is_commit_sha(&commit) {
+            return future::ready(Err(anyhow!("invalid commit {commit:?}"))).boxed();
+        }
+        self.run_sequencer_command(vec!["revert".into(), "--no-edit".into(), commit], env)
+    }
+
+    fn continue_operation(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        match detect_in_progress_operation(&self.path()) {
+            Some(operation) => self
+                .run_sequencer_command(vec![operation.command().into(), "--continue".into()], env),
+            None => future::ready(Err(anyhow!("no operation in progress"))).boxed(),
+        }
+    }
+
+    fn abort_operation(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        match detect_in_progress_operation(&self.path()) {
+            Some(operation) => {
+                self.run_sequencer_command(vec![operation.command().into(), "--abort".into()], env)
+            }
+            None => future::ready(Err(anyhow!("no operation in progress"))).boxed(),
+        }
+    }
+
+    fn load_index_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
+        // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
+        const GIT_MODE_SYMLINK: u32 = 0o120000;
+
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                fn logic(repo: &git2::Repository, path: &RepoPath) -> Result<Option<String>> {
+                    // This check is required because index.get_path() unwraps internally :(
+                    check_path_to_repo_path_errors(path)?;
+
+                    let mut index = repo.index()?;
+                    index.read(false)?;
+
+                    const STAGE_NORMAL: i32 = 0;
+                    let oid = match index.get_path(path, STAGE_NORMAL) {
+                        Some(entry) if entry.mode != GIT_MODE_SYMLINK => entry.id,
+                        _ => return Ok(None),
+                    };
+
+                    let content = repo.find_blob(oid)?.content().to_owned();
+                    Ok(String::from_utf8(content).ok())
+                }
+
+                match logic(&repo.lock(), &path) {
+                    Ok(value) => return value,
+                    Err(err) => log::error!("Error loading index text: {:?}", err),
+                }
+                None
+            })
+            .boxed()
+    }
+
+    fn load_conflict_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<ConflictStages>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                check_path_to_repo_path_errors(&path)?;
+
+                let repo = repo.lock();
+                let mut index = repo.index()?;
+                index.read(false)?;
+
+                let load_stage = |stage: i32| -> Result<Option<String>> {
+                    let Some(entry) = index.get_path(&path, stage) else {
+                        return Ok(None);
+                    };
+                    let content = repo.find_blob(entry.id)?.content().to_owned();
+                    Ok(Some(
+                        String::from_utf8(content)
+                            .with_context(|| format!("{path:?} is not valid UTF-8"))?,
+                    ))
+                };
+                let stages = ConflictStages {
+                    base: load_stage(1)?,
+                    ours: load_stage(2)?,
+                    theirs: load_stage(3)?,
+                };
+                anyhow::ensure!(
+                    stages.ours.is_some() || stages.theirs.is_some(),
+                    "{path:?} is not conflicted"
+                );
+                Ok(stages)
+            })
+            .boxed()
+    }
+
+    fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                let repo = repo.lock();
+                let head = repo.head().ok()?.peel_to_tree().log_err()?;
+                let entry = head.get_path(&path).ok()?;
+                if entry.filemode() == i32::from(git2::FileMode::Link) {
+                    return None;
+                }
+                let content = repo.find_blob(entry.id()).log_err()?.content().to_owned();
+                String::from_utf8(content).ok()
+            })
+            .boxed()
+    }
+
+    fn set_index_text(
+        &self,
+        path: RepoPath,
+        content: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, anyhow::Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                if let Some(content) = content {
                     let mut child = new_smol_command(&git_binary_path)
                         .current_dir(&working_directory)
                         .envs(env.iter())
@@ -1018,18 +1622,255 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
-    fn create_branch(&self, name: String) -> BoxFuture<'_, Result<()>> {
+    fn create_branch(
+        &self,
+        name: String,
+        base_revision: Option<String>,
+    ) -> BoxFuture<'_, Result<()>> {
         let repo = self.repository.clone();
         self.executor
             .spawn(async move {
                 let repo = repo.lock();
-                let current_commit = repo.head()?.peel_to_commit()?;
-                repo.branch(&name, &current_commit, false)?;
+                let base_commit = match base_revision {
+                    Some(revision) => repo
+                        .revparse_single(&revision)
+                        .with_context(|| format!("Revision {revision:?} not found"))?
+                        .peel_to_commit()?,
+                    None => repo.head()?.peel_to_commit()?,
+                };
+                repo.branch(&name, &base_commit, false)?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn delete_branch(&self, name: String, force: bool) -> BoxFuture<'_, Result<()>> {
+        let flag = if force { "-D" } else { "-d" };
+        self.run_branch_command(vec![flag.into(), "--".into(), name])
+    }
+
+    fn is_branch_merged(&self, name: String) -> BoxFuture<'_, Result<bool>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let git =
+                    GitBinary::new(git_binary_path.clone(), working_directory.clone(), executor);
+                let branch = format!("refs/heads/{name}");
+                // Like `git branch -d`, compare against the upstream when there is one.
+                let target = git
+                    .run([
+                        "rev-parse",
+                        "--verify",
+                        "--quiet",
+                        &format!("{branch}@{{upstream}}"),
+                    ])
+                    .await
+                    .unwrap_or_else(|_| "HEAD".into());
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory)
+                    .args(["merge-base", "--is-ancestor", &branch, &target])
+                    .output()
+                    .await?;
+                match output.status.code() {
+                    Some(0) => Ok(true),
+                    Some(1) => Ok(false),
+                    _ => Err(anyhow!(
+                        "Failed to check whether {name} is merged:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    )),
+                }
+            })
+            .boxed()
+    }
+
+    fn rename_branch(&self, old_name: String, new_name: String) -> BoxFuture<'_, Result<()>> {
+        self.run_branch_command(vec!["-m".into(), "--".into(), old_name, new_name])
+    }
+
+    fn set_upstream(&self, name: String, upstream: Option<String>) -> BoxFuture<'_, Result<()>> {
+        let args = match upstream {
+            Some(upstream) => vec![format!("--set-upstream-to={upstream}"), "--".into(), name],
+            None => vec!["--unset-upstream".into(), "--".into(), name],
+        };
+        self.run_branch_command(args)
+    }
+
+    fn diff_to_merge_base(&self, base: String) -> BoxFuture<'_, Result<CommitDiff>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                anyhow::ensure!(!base.starts_with('-'), "Invalid revision {base:?}");
+                let git =
+                    GitBinary::new(git_binary_path.clone(), working_directory.clone(), executor);
+                let merge_base = git
+                    .run(["merge-base", "HEAD", &base])
+                    .await
+                    .with_context(|| format!("Failed to find the merge base of HEAD and {base}"))?;
+                let changes = git
+                    .run(["diff", "--no-renames", "--name-status", "-z", &merge_base])
+                    .await
+                    .with_context(|| format!("Failed to compare with {base}"))?;
+                let untracked = git.list_untracked_files().await?;
+
+                let read_working_copy =
+                    |path: &Path| std::fs::read_to_string(working_directory.join(path)).ok();
+                let mut cat_file = CatFile::spawn(&git_binary_path, &working_directory)?;
+                let mut files = Vec::new();
+                for (path, status_code) in parse_git_diff_name_status(&changes) {
+                    let old_text = match status_code {
+                        StatusCode::Added => None,
+                        _ => Some(cat_file.read(&merge_base, path)?),
+                    };
+                    let new_text = match status_code {
+                        StatusCode::Deleted => None,
+                        _ => read_working_copy(path),
+                    };
+                    files.push(CommitFile {
+                        path: path.into(),
+                        old_text,
+                        new_text,
+                    });
+                }
+                for path in untracked {
+                    files.push(CommitFile {
+                        path: path.as_path().into(),
+                        old_text: None,
+                        new_text: read_working_copy(&path),
+                    });
+                }
+                files.sort_by(|a, b| a.path.cmp(&b.path));
+
+                Ok(CommitDiff { files })
+            })
+            .boxed()
+    }
+
+    fn worktrees(&self) -> BoxFuture<'_, Result<Vec<Worktree>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(
+                    git_binary_path.clone(),
+                    working_directory?,
+                    executor.clone(),
+                );
+                let output = git.run(&["worktree", "list", "--porcelain"]).await?;
+                let mut worktrees = parse_worktree_list(&output)?;
+                for worktree in &mut worktrees {
+                    if worktree.is_main {
+                        continue;
+                    }
+                    let git = GitBinary::new(
+                        git_binary_path.clone(),
+                        worktree.path.clone(),
+                        executor.clone(),
+                    );
+                    // A working tree whose directory is missing has nothing left to discard.
+                    worktree.has_changes = git
+                        .run(&["status", "--porcelain"])
+                        .await
+                        .is_ok_and(|status| !status.is_empty());
+                }
+                Ok(worktrees)
+            })
+            .boxed()
+    }
+
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch_name: String,
+        create_branch: bool,
+    ) -> BoxFuture<'_, Result<()>> {
+        let path = path.to_string_lossy().to_string();
+        let args = if create_branch {
+            vec!["add".into(), "-b".into(), branch_name, "--".into(), path]
+        } else {
+            vec!["add".into(), "--".into(), path, branch_name]
+        };
+        self.run_reporting_stderr("worktree", args)
+    }
+
+    fn remove_worktree(&self, path: PathBuf, force: bool) -> BoxFuture<'_, Result<()>> {
+        let mut args = vec!["remove".to_string()];
+        if force {
+            args.push("--force".into());
+        }
+        args.extend(["--".into(), path.to_string_lossy().to_string()]);
+        self.run_reporting_stderr("worktree", args)
+    }
+
+    fn tags(&self, points_at: Option<String>) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let fields = [
+                    "%(refname:strip=2)",
+                    "%(objecttype)",
+                    "%(objectname)",
+                    "%(*objectname)",
+                    "%(creatordate:unix)",
+                    "%(contents:subject)",
+                ]
+                .join("%00");
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let mut args = vec![
+                    "for-each-ref".to_string(),
+                    "--sort=-creatordate".to_string(),
+                    format!("--format={fields}"),
+                ];
+                // Annotated tags are peeled, so they match the commit they tag.
+                args.extend(points_at.map(|commit| format!("--points-at={commit}")));
+                args.push("refs/tags".to_string());
+                let output = git.run(&args).await?;
+                parse_tag_list(&output)
+            })
+            .boxed()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        commit: String,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                anyhow::ensure!(!commit.starts_with('-'), "Invalid revision {commit:?}");
+                let mut command = new_smol_command(&git_binary_path);
+                command.current_dir(&working_directory?).envs(env.iter());
+                command.arg("tag");
+                if let Some(message) = message {
+                    command.args(["--annotate", "--message", &message]);
+                }
+                let output = command.arg("--").args([&name, &commit]).output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "{}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
                 Ok(())
             })
             .boxed()
     }
 
+    fn delete_tag(&self, name: String) -> BoxFuture<'_, Result<()>> {
+        self.run_reporting_stderr("tag", vec!["--delete".into(), "--".into(), name])
+    }
+
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.git_binary_path.clone();
@@ -1553,7 +2394,93 @@ async fn exclude_files(git: &GitBinary) -> Result<GitExcludeOverride> {
     Ok(excludes)
 }
 
-struct GitBinary {
+/// Reads blobs through a single `git cat-file --batch` process.
+struct CatFile {
+    _process: std::process::Child,
+    stdin: BufWriter<std::process::ChildStdin>,
+    stdout: BufReader<std::process::ChildStdout>,
+}
+
+impl CatFile {
+    fn spawn(git_binary_path: &Path, working_directory: &Path) -> Result<Self> {
+        let mut process = new_std_command(git_binary_path)
+            .current_dir(working_directory)
+            .args(["--no-optional-locks", "cat-file", "--batch=%(objectsize)"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("starting git cat-file process")?;
+        let stdin = BufWriter::with_capacity(512, process.stdin.take().unwrap());
+        let stdout = BufReader::new(process.stdout.take().unwrap());
+        Ok(Self {
+            _process: process,
+            stdin,
+            stdout,
+        })
+    }
+
+    fn read(&mut self, revision: &str, path: &Path) -> Result<String> {
+        writeln!(&mut self.stdin, "{revision}:{}", path.display())?;
+        self.stdin.flush()?;
+
+        let mut info_line = String::new();
+        self.stdout.read_line(&mut info_line)?;
+        let len = info_line
+            .trim_end()
+            .parse()
+            .with_context(|| format!("invalid object size output from cat-file {info_line}"))?;
+        let mut text = vec![0; len];
+        self.stdout.read_exact(&mut text)?;
+        let mut newline = [b'\0'];
+        self.stdout.read_exact(&mut newline)?;
+        Ok(String::from_utf8_lossy(&text).to_string())
+    }
+
+    /// Reads the contents of a file changed between `old_revision` and `new_revision`.
+    fn commit_file(
+        &mut self,
+        path: &Path,
+        status_code: StatusCode,
+        old_revision: &str,
+        new_revision: &str,
+    ) -> Result<Option<CommitFile>> {
+        let (old_text, new_text) = match status_code {
+            StatusCode::Modified => (
+                Some(self.read(old_revision, path)?),
+                Some(self.read(new_revision, path)?),
+            ),
+            StatusCode::Added => (None, Some(self.read(new_revision, path)?)),
+            StatusCode::Deleted => (Some(self.read(old_revision, path)?), None),
+            _ => return Ok(None),
+        };
+        Ok(Some(CommitFile {
+            path: path.into(),
+            old_text,
+            new_text,
+        }))
+    }
+}
+
+/// Returns the `stash@{n}` reference for the stash entry whose commit is `sha`, failing if
+/// the entry has been dropped since the caller listed the stash.
+async fn stash_entry_ref(git: &GitBinary, sha: &str) -> Result<String> {
+    anyhow::ensure!(is_commit_sha(sha), "invalid stash entry {sha:?}");
+    let output = git.run(&["stash", "list", "--format=%H"]).await?;
+    let index = output
+        .lines()
+        .position(|line| line == sha)
+        .with_context(|| format!("stash entry {sha} no longer exists"))?;
+    Ok(format!("stash@{{{index}}}"))
+}
+
+/// Whether `text` is a full commit SHA, as opposed to an abbreviation or an arbitrary
+/// revision expression.
+pub fn is_commit_sha(text: &str) -> bool {
+    matches!(text.len(), 40 | 64) && text.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+struct GitBinary {
     git_binary_path: PathBuf,
     working_directory: PathBuf,
     executor: BackgroundExecutor,
@@ -1676,6 +2603,30 @@ impl GitBinary {
         Ok(String::from_utf8(output.stdout)?)
     }
 
+    /// Writes a copy of the commit `sha` with its message replaced, returning the copy's SHA.
+    async fn reworded_commit(&self, sha: &str, message: &str) -> Result<String> {
+        let commit = self.run_raw(["cat-file", "commit", sha]).await?;
+        let mut command = self.build_command(["hash-object", "-t", "commit", "-w", "--stdin"]);
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(reword_commit_object(&commit, message).as_bytes())
+            .await?;
+        let output = child.output().await?;
+        anyhow::ensure!(
+            output.status.success(),
+            "Failed to reword {sha}:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(String::from_utf8(output.stdout)?.trim_end().to_string())
+    }
+
     fn build_command<S>(&self, args: impl IntoIterator<Item = S>) -> smol::process::Command
     where
         S: AsRef<OsStr>,
@@ -1862,6 +2813,250 @@ impl MapSeekTarget<RepoPath> for RepoPathDescendants<'_> {
     }
 }
 
+async fn run_sequencer_command(
+    git_binary_path: &Path,
+    working_directory: &Path,
+    args: &[String],
+    env: &HashMap<String, String>,
+    sequence_editor: Option<String>,
+) -> Result<()> {
+    let mut command = new_smol_command(git_binary_path);
+    command
+        .current_dir(working_directory)
+        .envs(env.iter())
+        // Git skips running an editor named ":" on every platform.
+        .env("GIT_EDITOR", ":")
+        .args(args);
+    if let Some(sequence_editor) = sequence_editor {
+        command.env("GIT_SEQUENCE_EDITOR", sequence_editor);
+    }
+    let output = command.output().await?;
+    anyhow::ensure!(
+        output.status.success(),
+        "Failed to run git {}:\n{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr),
+    );
+    Ok(())
+}
+
+fn detect_in_progress_operation(git_dir: &Path) -> Option<GitOperation> {
+    if git_dir.join("rebase-merge").is_dir() || git_dir.join("rebase-apply").is_dir() {
+        Some(GitOperation::Rebase)
+    } else if git_dir.join("CHERRY_PICK_HEAD").is_file() {
+        Some(GitOperation::CherryPick)
+    } else if git_dir.join("REVERT_HEAD").is_file() {
+        Some(GitOperation::Revert)
+    } else if git_dir.join("MERGE_HEAD").is_file() {
+        Some(GitOperation::Merge)
+    } else {
+        None
+    }
+}
+
+#[cfg(unix)]
+const SEQUENCE_EDITOR_EXTENSION: &str = "sh";
+#[cfg(not(unix))]
+const SEQUENCE_EDITOR_EXTENSION: &str = "cmd";
+
+/// Writes a script that git can run as its sequence editor to replace the todo list it
+/// generated with the one at `todo_path`.
+#[cfg(unix)]
+async fn write_sequence_editor(editor_path: &Path, todo_path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let script = format!(
+        "#!/bin/sh\ncat {} > \"$1\"\n",
+        shell_quote(&todo_path.to_string_lossy())
+    );
+    smol::fs::write(editor_path, script).await?;
+    smol::fs::set_permissions(editor_path, std::fs::Permissions::from_mode(0o755)).await?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn write_sequence_editor(editor_path: &Path, todo_path: &Path) -> Result<()> {
+    let script = format!("@copy /y \"{}\" \"%~1\" >nul\r\n", todo_path.display());
+    smol::fs::write(editor_path, script).await?;
+    Ok(())
+}
+
+/// Returns the raw contents of a commit object with its message replaced by `message`.
+///
+/// Signatures no longer match the rewritten object, so they are dropped along with the
+/// encoding header, as the new message is always UTF-8.
+fn reword_commit_object(commit: &str, message: &str) -> String {
+    let headers = commit
+        .split_once("\n\n")
+        .map_or(commit, |(headers, _)| headers);
+    let mut text = String::new();
+    let mut skipping = false;
+    for line in headers.lines() {
+        // Continuation lines of a multi-line header start with a space.
+        if !line.starts_with(' ') {
+            skipping = line.starts_with("gpgsig") || line.starts_with("encoding ");
+        }
+        if skipping {
+            continue;
+        }
+        text.push_str(line);
+        text.push('\n');
+    }
+    text.push('\n');
+    text.push_str(message.trim_end());
+    text.push('\n');
+    text
+}
+
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+const LOG_FORMAT: &str = "%H%x00%P%x00%ct%x00%an%x00%ae%x00%D%x00%s";
+
+fn parse_log(input: &str) -> Result<Vec<CommitLogEntry>> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let fields = line.splitn(7, '\0').collect::<Vec<_>>();
+            let [
+                sha,
+                parents,
+                timestamp,
+                author_name,
+                author_email,
+                refs,
+                subject,
+            ] = fields[..]
+            else {
+                bail!("unexpected git log output: {line:?}");
+            };
+            Ok(CommitLogEntry {
+                sha: sha.to_string().into(),
+                parent_shas: parents
+                    .split(' ')
+                    .filter(|parent| !parent.is_empty())
+                    .map(|parent| parent.to_string().into())
+                    .collect(),
+                subject: subject.to_string().into(),
+                author_name: author_name.to_string().into(),
+                author_email: author_email.to_string().into(),
+                commit_timestamp: timestamp.parse()?,
+                refs: refs
+                    .split(", ")
+                    .filter(|name| !name.is_empty() && *name != "HEAD")
+                    .map(|name| name.trim_start_matches("HEAD -> ").to_string().into())
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Parses the output of `git stash list --format=%H%x00%ct%x00%gs`.
+fn parse_stash_list(input: &str) -> Result<Vec<StashEntry>> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .map(|(index, line)| {
+            let mut fields = line.splitn(3, '\0');
+            let (Some(sha), Some(timestamp), Some(subject)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                bail!("unexpected git stash list output: {line:?}");
+            };
+
+            // Subjects look like "WIP on main: 1234abc Commit subject" for entries
+            // without a message, and "On main: message" otherwise.
+            let (branch, message) = match subject
+                .strip_prefix("WIP on ")
+                .or_else(|| subject.strip_prefix("On "))
+                .and_then(|rest| rest.split_once(": "))
+            {
+                Some(("(no branch)", message)) => (None, message),
+                Some((branch, message)) => (Some(branch.to_string().into()), message),
+                None => (None, subject),
+            };
+
+            Ok(StashEntry {
+                index,
+                sha: sha.to_string().into(),
+                message: message.to_string().into(),
+                branch,
+                timestamp: timestamp.parse()?,
+            })
+        })
+        .collect()
+}
+
+fn parse_worktree_list(input: &str) -> Result<Vec<Worktree>> {
+    let mut worktrees = Vec::new();
+    // Each working tree is a block of attribute lines, and blocks are separated by blank lines.
+    let blocks = input.split("\n\n").filter(|block| !block.trim().is_empty());
+    for (ix, block) in blocks.enumerate() {
+        let mut path = None;
+        let mut sha = None;
+        let mut ref_name = None;
+        let mut is_bare = false;
+        let mut is_locked = false;
+        for line in block.lines() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "worktree" => path = Some(PathBuf::from(value)),
+                "HEAD" => sha = Some(SharedString::from(value.to_string())),
+                "branch" => ref_name = Some(SharedString::from(value.to_string())),
+                "bare" => is_bare = true,
+                "locked" => is_locked = true,
+                _ => {}
+            }
+        }
+        let Some(path) = path else {
+            bail!("unexpected git worktree list output: {block:?}");
+        };
+        // A bare repository is listed first, but it has no working tree to open.
+        if is_bare {
+            continue;
+        }
+        worktrees.push(Worktree {
+            path,
+            ref_name,
+            sha: sha.unwrap_or_default(),
+            is_main: ix == 0,
+            is_locked,
+            has_changes: false,
+        });
+    }
+    Ok(worktrees)
+}
+
+fn parse_tag_list(input: &str) -> Result<Vec<Tag>> {
+    let mut tags = Vec::new();
+    for line in input.lines() {
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split('\0');
+        let name = fields.next().context("no refname")?.to_string();
+        let object_type = fields.next().context("no objecttype")?;
+        let object_sha = fields.next().context("no objectname")?;
+        // Annotated tags point at a tag object, which is peeled to find the tagged commit.
+        let peeled_sha = fields.next().context("no *objectname")?;
+        let timestamp = fields.next().context("no creatordate")?.parse()?;
+        let subject = fields.next().context("no contents:subject")?;
+        let is_annotated = object_type == "tag";
+        tags.push(Tag {
+            name: name.into(),
+            sha: if is_annotated { peeled_sha } else { object_sha }
+                .to_string()
+                .into(),
+            message: is_annotated.then(|| subject.to_string().into()),
+            timestamp,
+        });
+    }
+    Ok(tags)
+}
+
 fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
     let mut branches = Vec::new();
     for line in input.split('\n') {
@@ -2222,6 +3417,662 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_stash_list_parsing() {
+        let input = concat!(
+            "1f0ba2b0c1d3e1d47ab5cc91fa2d4e5cbd4c1e9a\x001733187470\x00On main: half-done refactor\n",
+            "8e1d9a54fa7ed8c7fc1bb7dff09e6c5d8ab50c21\x001733180000\x00WIP on feature/x: 060964d Fix tests\n",
+            "0a8bc3e1b7a1d9ed24bfa5d9fc2e5bcb1e88ff01\x001733100000\x00WIP on (no branch): 060964d Fix tests\n",
+        );
+        let entries = parse_stash_list(input).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                StashEntry {
+                    index: 0,
+                    sha: "1f0ba2b0c1d3e1d47ab5cc91fa2d4e5cbd4c1e9a".into(),
+                    message: "half-done refactor".into(),
+                    branch: Some("main".into()),
+                    timestamp: 1733187470,
+                },
+                StashEntry {
+                    index: 1,
+                    sha: "8e1d9a54fa7ed8c7fc1bb7dff09e6c5d8ab50c21".into(),
+                    message: "060964d Fix tests".into(),
+                    branch: Some("feature/x".into()),
+                    timestamp: 1733180000,
+                },
+                StashEntry {
+                    index: 2,
+                    sha: "0a8bc3e1b7a1d9ed24bfa5d9fc2e5bcb1e88ff01".into(),
+                    message: "060964d Fix tests".into(),
+                    branch: None,
+                    timestamp: 1733100000,
+                },
+            ]
+        );
+        assert!(parse_stash_list("garbage").is_err());
+    }
+
+    #[test]
+    fn test_worktree_list_parsing() {
+        let input = concat!(
+            "worktree /home/me/project\n",
+            "HEAD 1f0ba2b0c1d3e1d47ab5cc91fa2d4e5cbd4c1e9a\n",
+            "branch refs/heads/main\n",
+            "\n",
+            "worktree /home/me/project-hotfix\n",
+            "HEAD 8e1d9a54fa7ed8c7fc1bb7dff09e6c5d8ab50c21\n",
+            "detached\n",
+            "locked reason\n",
+            "\n",
+        );
+        assert_eq!(
+            parse_worktree_list(input).unwrap(),
+            vec![
+                Worktree {
+                    path: PathBuf::from("/home/me/project"),
+                    ref_name: Some("refs/heads/main".into()),
+                    sha: "1f0ba2b0c1d3e1d47ab5cc91fa2d4e5cbd4c1e9a".into(),
+                    is_main: true,
+                    is_locked: false,
+                    has_changes: false,
+                },
+                Worktree {
+                    path: PathBuf::from("/home/me/project-hotfix"),
+                    ref_name: None,
+                    sha: "8e1d9a54fa7ed8c7fc1bb7dff09e6c5d8ab50c21".into(),
+                    is_main: false,
+                    is_locked: true,
+                    has_changes: false,
+                },
+            ]
+        );
+
+        let bare = "worktree /srv/project.git\nbare\n\nworktree /srv/checkout\nHEAD abc\nbranch refs/heads/dev\n";
+        let worktrees = parse_worktree_list(bare).unwrap();
+        assert_eq!(worktrees.len(), 1);
+        assert_eq!(worktrees[0].branch_name(), Some("dev"));
+        assert!(!worktrees[0].is_main);
+    }
+
+    #[test]
+    fn test_tag_list_parsing() {
+        let input = concat!(
+            "v1.1\0tag\0a0b1c2\0c3\01733187470\0Release 1.1\n",
+            "v1.0\0commit\0c1\0\01733100000\0Initial commit\n",
+        );
+        assert_eq!(
+            parse_tag_list(input).unwrap(),
+            vec![
+                Tag {
+                    name: "v1.1".into(),
+                    sha: "c3".into(),
+                    message: Some("Release 1.1".into()),
+                    timestamp: 1733187470,
+                },
+                Tag {
+                    name: "v1.0".into(),
+                    sha: "c1".into(),
+                    message: None,
+                    timestamp: 1733100000,
+                },
+            ]
+        );
+        assert!(parse_tag_list("v1.0\0commit\0c1\n").is_err());
+    }
+
+    #[test]
+    fn test_log_parsing() {
+        let input = concat!(
+            "c3\0c2 b1\01733187470\0Jane\0jane@example.com\0HEAD -> main, origin/main, tag: v1.0\0Merge branch 'b'\n",
+            "c2\0c1\01733180000\0John\0john@example.com\0\0Fix: the thing\n",
+            "c1\0\01733100000\0Jane\0jane@example.com\0HEAD\0Initial commit\n",
+        );
+        let entries = parse_log(input).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                CommitLogEntry {
+                    sha: "c3".into(),
+                    parent_shas: vec!["c2".into(), "b1".into()],
+                    subject: "Merge branch 'b'".into(),
+                    author_name: "Jane".into(),
+                    author_email: "jane@example.com".into(),
+                    commit_timestamp: 1733187470,
+                    refs: vec!["main".into(), "origin/main".into(), "tag: v1.0".into()],
+                },
+                CommitLogEntry {
+                    sha: "c2".into(),
+                    parent_shas: vec!["c1".into()],
+                    subject: "Fix: the thing".into(),
+                    author_name: "John".into(),
+                    author_email: "john@example.com".into(),
+                    commit_timestamp: 1733180000,
+                    refs: vec![],
+                },
+                CommitLogEntry {
+                    sha: "c1".into(),
+                    parent_shas: vec![],
+                    subject: "Initial commit".into(),
+                    author_name: "Jane".into(),
+                    author_email: "jane@example.com".into(),
+                    commit_timestamp: 1733100000,
+                    refs: vec![],
+                },
+            ]
+        );
+        assert!(parse_log("garbage").is_err());
+    }
+
+    #[gpui::test]
+    async fn test_stash_push_and_pop(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let file_path = repo_dir.path().join("file");
+        smol::fs::write(&file_path, "initial").await.unwrap();
+
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        repo.stage_paths(
+            vec![RepoPath::from_str("file")],
+            Arc::new(HashMap::default()),
+        )
+        .await
+        .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            Arc::new(checkpoint_author_envs()),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::write(&file_path, "modified").await.unwrap();
+        smol::fs::write(repo_dir.path().join("untracked"), "new")
+            .await
+            .unwrap();
+        repo.stash_push(
+            Some("wip".into()),
+            Vec::new(),
+            Arc::new(checkpoint_author_envs()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            smol::fs::read_to_string(&file_path).await.unwrap(),
+            "initial"
+        );
+        assert!(!repo_dir.path().join("untracked").exists());
+        let entries = repo.stash_list().await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message.as_ref(), "wip");
+        let sha = entries[0].sha.to_string();
+
+        let diff = repo.stash_show(sha.clone(), cx.to_async()).await.unwrap();
+        assert_eq!(diff.files.len(), 2);
+        assert_eq!(diff.files[0].path, RepoPath::from_str("file"));
+        assert_eq!(diff.files[0].old_text.as_deref(), Some("initial"));
+        assert_eq!(diff.files[0].new_text.as_deref(), Some("modified"));
+        assert_eq!(diff.files[1].path, RepoPath::from_str("untracked"));
+        assert_eq!(diff.files[1].old_text, None);
+        assert_eq!(diff.files[1].new_text.as_deref(), Some("new"));
+
+        repo.stash_pop(sha.clone(), Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(&file_path).await.unwrap(),
+            "modified"
+        );
+        assert!(repo.stash_list().await.unwrap().is_empty());
+
+        // The entry is gone, so acting on it again fails instead of hitting another entry.
+        assert!(
+            repo.stash_drop(sha, Arc::new(HashMap::default()))
+                .await
+                .is_err()
+        );
+        assert!(
+            repo.stash_drop("stash@{0}".into(), Arc::new(HashMap::default()))
+                .await
+                .is_err()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_interactive_rebase(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+
+        let mut shas = Vec::new();
+        for (name, contents) in [("a", "1"), ("b", "2"), ("c", "3"), ("a", "conflict")] {
+            smol::fs::write(repo_dir.path().join(name), contents)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![RepoPath::from_str(name)], Arc::new(HashMap::default()))
+                .await
+                .unwrap();
+            repo.commit(
+                format!("Write {contents} to {name}").into(),
+                None,
+                CommitOptions::default(),
+                Arc::new(checkpoint_author_envs()),
+            )
+            .await
+            .unwrap();
+            shas.push(repo.head_sha().await.unwrap());
+        }
+
+        repo.rebase_interactive(
+            shas[0].clone(),
+            vec![
+                RebaseTodoEntry {
+                    action: RebaseAction::Reword,
+                    sha: shas[1].clone().into(),
+                    message: Some("Reworded 100% \\o/\n\nWith a 'body'".into()),
+                },
+                RebaseTodoEntry {
+                    action: RebaseAction::Fixup,
+                    sha: shas[2].clone().into(),
+                    message: None,
+                },
+                RebaseTodoEntry {
+                    action: RebaseAction::Drop,
+                    sha: shas[3].clone().into(),
+                    message: None,
+                },
+            ],
+            Arc::new(checkpoint_author_envs()),
+        )
+        .await
+        .unwrap();
+
+        let log = repo
+            .log(LogOptions {
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let subjects = log
+            .iter()
+            .map(|entry| entry.subject.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(subjects, ["Reworded 100% \\o/", "Write 1 to a"]);
+        let head = repo.show("HEAD".into()).await.unwrap();
+        assert_eq!(
+            head.message.trim_end(),
+            "Reworded 100% \\o/\n\nWith a 'body'"
+        );
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("c"))
+                .await
+                .unwrap(),
+            "3"
+        );
+        assert_eq!(repo.in_progress_operation().await, None);
+
+        // Picking the dropped commit again conflicts with the first one.
+        smol::fs::write(repo_dir.path().join("a"), "other")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("a")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Write other to a".into(),
+            None,
+            CommitOptions::default(),
+            Arc::new(checkpoint_author_envs()),
+        )
+        .await
+        .unwrap();
+        assert!(
+            repo.cherry_pick(shas[3].clone(), Arc::new(checkpoint_author_envs()))
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            repo.in_progress_operation().await,
+            Some(GitOperation::CherryPick)
+        );
+
+        repo.abort_operation(Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        assert_eq!(repo.in_progress_operation().await, None);
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("a"))
+                .await
+                .unwrap(),
+            "other"
+        );
+
+        // Only full commit SHAs are accepted, so nothing can be passed as an option.
+        assert!(
+            repo.revert("--quit".into(), Arc::new(HashMap::default()))
+                .await
+                .is_err()
+        );
+        assert!(
+            repo.rebase_interactive(
+                shas[0].clone(),
+                vec![RebaseTodoEntry {
+                    action: RebaseAction::Pick,
+                    sha: format!("{}\nexec touch injected", shas[1]).into(),
+                    message: None,
+                }],
+                Arc::new(HashMap::default()),
+            )
+            .await
+            .is_err()
+        );
+        assert!(!repo_dir.path().join("injected").exists());
+    }
+
+    #[gpui::test]
+    async fn test_load_conflict_stages(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        let mut shas = Vec::new();
+        for content in ["base\n", "theirs\n"] {
+            smol::fs::write(repo_dir.path().join("a"), content)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![RepoPath::from_str("a")], Arc::new(HashMap::default()))
+                .await
+                .unwrap();
+            repo.commit(
+                content.trim().into(),
+                None,
+                CommitOptions::default(),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+            shas.push(repo.head_sha().await.unwrap());
+        }
+
+        repo.create_branch("other".into(), Some(shas[0].clone()))
+            .await
+            .unwrap();
+        repo.change_branch("other".into()).await.unwrap();
+        smol::fs::write(repo_dir.path().join("a"), "ours\n")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("a")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit("ours".into(), None, CommitOptions::default(), env.clone())
+            .await
+            .unwrap();
+        assert!(
+            repo.load_conflict_stages(RepoPath::from_str("a"))
+                .await
+                .is_err()
+        );
+
+        assert!(
+            repo.cherry_pick(shas[1].clone(), env.clone())
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            repo.load_conflict_stages(RepoPath::from_str("a"))
+                .await
+                .unwrap(),
+            ConflictStages {
+                base: Some("base\n".into()),
+                ours: Some("ours\n".into()),
+                theirs: Some("theirs\n".into()),
+            }
+        );
+    }
+
+    #[gpui::test]
+    async fn test_branch_management(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let commit = async |name: &str, contents: &str| {
+            smol::fs::write(repo_dir.path().join(name), contents)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![RepoPath::from_str(name)], Arc::new(HashMap::default()))
+                .await
+                .unwrap();
+            repo.commit(
+                format!("Write {contents} to {name}").into(),
+                None,
+                CommitOptions::default(),
+                Arc::new(checkpoint_author_envs()),
+            )
+            .await
+            .unwrap();
+            repo.head_sha().await.unwrap()
+        };
+        let branch_names = async || {
+            let mut names = repo
+                .branches()
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|branch| branch.name().to_string())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+
+        let first_sha = commit("a", "1").await;
+        commit("a", "2").await;
+        let main_branch = repo
+            .branches()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|branch| branch.is_head)
+            .unwrap()
+            .name()
+            .to_string();
+
+        repo.create_branch("feature".into(), Some(first_sha.clone()))
+            .await
+            .unwrap();
+        repo.create_branch("merged".into(), Some(first_sha.clone()))
+            .await
+            .unwrap();
+        repo.rename_branch("merged".into(), "renamed".into())
+            .await
+            .unwrap();
+        let mut expected = vec!["feature".to_string(), main_branch.clone(), "renamed".into()];
+        expected.sort();
+        assert_eq!(branch_names().await, expected);
+
+        repo.change_branch("feature".into()).await.unwrap();
+        commit("b", "3").await;
+        repo.change_branch(main_branch.clone()).await.unwrap();
+
+        // `feature` has a commit that isn't on the main branch.
+        assert!(!repo.is_branch_merged("feature".into()).await.unwrap());
+        assert!(repo.is_branch_merged("renamed".into()).await.unwrap());
+        assert!(repo.delete_branch("feature".into(), false).await.is_err());
+        repo.delete_branch("feature".into(), true).await.unwrap();
+        repo.delete_branch("renamed".into(), false).await.unwrap();
+        assert_eq!(branch_names().await, vec![main_branch]);
+
+        smol::fs::write(repo_dir.path().join("a"), "4")
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("untracked"), "5")
+            .await
+            .unwrap();
+        let diff = repo.diff_to_merge_base(first_sha).await.unwrap();
+        assert_eq!(diff.files.len(), 2);
+        assert_eq!(diff.files[0].path.as_ref(), Path::new("a"));
+        assert_eq!(diff.files[0].old_text.as_deref(), Some("1"));
+        assert_eq!(diff.files[0].new_text.as_deref(), Some("4"));
+        assert_eq!(diff.files[1].path.as_ref(), Path::new("untracked"));
+        assert_eq!(diff.files[1].old_text, None);
+        assert_eq!(diff.files[1].new_text.as_deref(), Some("5"));
+    }
+
+    #[gpui::test]
+    async fn test_worktree_management(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        let worktrees_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        smol::fs::write(repo_dir.path().join("a"), "1")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("a")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            Arc::new(checkpoint_author_envs()),
+        )
+        .await
+        .unwrap();
+
+        let hotfix_path = worktrees_dir.path().join("hotfix");
+        repo.create_worktree(hotfix_path.clone(), "hotfix".into(), true)
+            .await
+            .unwrap();
+        let worktrees = repo.worktrees().await.unwrap();
+        assert_eq!(worktrees.len(), 2);
+        assert!(worktrees[0].is_main);
+        assert_eq!(worktrees[1].branch_name(), Some("hotfix"));
+        assert_eq!(
+            worktrees[1].path.canonicalize().unwrap(),
+            hotfix_path.canonicalize().unwrap()
+        );
+
+        // A branch can only be checked out in one working tree at a time.
+        assert!(
+            repo.create_worktree(worktrees_dir.path().join("other"), "hotfix".into(), false)
+                .await
+                .is_err()
+        );
+
+        assert!(!worktrees[1].has_changes);
+
+        // Local modifications prevent removal unless it's forced.
+        smol::fs::write(hotfix_path.join("a"), "2").await.unwrap();
+        assert!(repo.worktrees().await.unwrap()[1].has_changes);
+        assert!(
+            repo.remove_worktree(hotfix_path.clone(), false)
+                .await
+                .is_err()
+        );
+        repo.remove_worktree(hotfix_path.clone(), true)
+            .await
+            .unwrap();
+        assert_eq!(repo.worktrees().await.unwrap().len(), 1);
+        assert!(!hotfix_path.exists());
+    }
+
+    #[gpui::test]
+    async fn test_tag_management(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        smol::fs::write(repo_dir.path().join("a"), "1")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("a")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        let head = repo.head_sha().await.unwrap();
+
+        repo.create_tag("v1.0".into(), "HEAD".into(), None, env.clone())
+            .await
+            .unwrap();
+        repo.create_tag(
+            "v1.0-annotated".into(),
+            head.clone(),
+            Some("Release 1.0".into()),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        // Tag names are unique.
+        assert!(
+            repo.create_tag("v1.0".into(), "HEAD".into(), None, env.clone())
+                .await
+                .is_err()
+        );
+
+        let mut tags = repo.tags(None).await.unwrap();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0].name.as_ref(), "v1.0");
+        assert!(!tags[0].is_annotated());
+        assert_eq!(tags[1].name.as_ref(), "v1.0-annotated");
+        assert_eq!(tags[1].message.as_deref(), Some("Release 1.0"));
+        // Both kinds of tag resolve to the commit they were created at.
+        assert!(tags.iter().all(|tag| tag.sha.as_ref() == head));
+
+        smol::fs::write(repo_dir.path().join("a"), "2")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("a")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Untagged commit".into(),
+            None,
+            CommitOptions::default(),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        let untagged = repo.head_sha().await.unwrap();
+        assert_eq!(repo.tags(Some(head.clone())).await.unwrap().len(), 2);
+        assert!(repo.tags(Some(untagged)).await.unwrap().is_empty());
+
+        repo.delete_tag("v1.0".into()).await.unwrap();
+        let tags = repo.tags(None).await.unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name.as_ref(), "v1.0-annotated");
+        assert!(repo.delete_tag("v1.0".into()).await.is_err());
+    }
+
     impl RealGitRepository {
         /// Force a Git garbage collection on the repository.
         fn gc(&self) -> BoxFuture<Result<()>> {
@@ -2,26 +2,42 @@ use anyhow::Context as _;
 use fuzzy::StringMatchCandidate;
 
 use collections::HashSet;
-use git::repository::Branch;
+use git::repository::{Branch, CommitSummary, Tag};
 use gpui::{
-    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
-    IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, Render, SharedString, Styled,
-    Subscription, Task, Window, rems,
+    Action, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, PromptLevel,
+    Render, SharedString, Styled, Subscription, Task, WeakEntity, Window, actions, rems,
 };
 use picker::{Picker, PickerDelegate, PickerEditorPosition};
 use project::git_store::Repository;
 use std::sync::Arc;
 use time::OffsetDateTime;
 use time_format::format_local_timestamp;
-use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use ui::{HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, prelude::*};
 use util::ResultExt;
 use workspace::notifications::DetachAndPromptErr;
 use workspace::{ModalView, Workspace};
 
+use crate::git_panel::GitPanel;
+use crate::project_diff::ProjectDiff;
+
+actions!(
+    branch_picker,
+    [
+        DeleteBranch,
+        RenameBranch,
+        SetUpstream,
+        CompareBranch,
+        ShowTags,
+        PushTag
+    ]
+);
+
 pub fn register(workspace: &mut Workspace) {
     workspace.register_action(open);
     workspace.register_action(switch);
     workspace.register_action(checkout_branch);
+    workspace.register_action(compare_with_branch);
 }
 
 pub fn checkout_branch(
@@ -47,11 +63,55 @@ pub fn open(
     _: &zed_actions::git::Branch,
     window: &mut Window,
     cx: &mut Context<Workspace>,
+) {
+    open_in_mode(workspace, PickerMode::Checkout, window, cx);
+}
+
+pub fn compare_with_branch(
+    workspace: &mut Workspace,
+    _: &zed_actions::git::CompareWithBranch,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    open_in_mode(workspace, PickerMode::CompareWith, window, cx);
+}
+
+/// Opens the tag list, with new tags created at `commit` rather than at HEAD.
+pub(crate) fn create_tag_at(
+    workspace: &mut Workspace,
+    commit: SharedString,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    open_in_mode(
+        workspace,
+        PickerMode::Tags {
+            commit: Some(commit),
+        },
+        window,
+        cx,
+    );
+}
+
+fn open_in_mode(
+    workspace: &mut Workspace,
+    mode: PickerMode,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
 ) {
     let repository = workspace.project().read(cx).active_repository(cx).clone();
+    let workspace_handle = workspace.weak_handle();
     let style = BranchListStyle::Modal;
     workspace.toggle_modal(window, cx, |window, cx| {
-        BranchList::new(repository, style, rems(34.), window, cx)
+        BranchList::new(
+            repository,
+            Some(workspace_handle),
+            mode,
+            style,
+            rems(34.),
+            window,
+            cx,
+        )
     })
 }
 
@@ -61,7 +121,15 @@ pub fn popover(
     cx: &mut App,
 ) -> Entity<BranchList> {
     cx.new(|cx| {
-        let list = BranchList::new(repository, BranchListStyle::Popover, rems(20.), window, cx);
+        let list = BranchList::new(
+            repository,
+            None,
+            PickerMode::Checkout,
+            BranchListStyle::Popover,
+            rems(20.),
+            window,
+            cx,
+        );
         list.focus_handle(cx).focus(window);
         list
     })
@@ -73,6 +141,26 @@ enum BranchListStyle {
     Popover,
 }
 
+/// What confirming an entry in the branch list does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PickerMode {
+    /// Check out the selected branch, or create a new branch from HEAD.
+    Checkout,
+    /// Pick the revision that a new branch named `branch_name` starts from.
+    CreateFrom { branch_name: SharedString },
+    /// Rename `branch` to the query.
+    Rename { branch: Branch },
+    /// Pick the remote branch that `branch` tracks.
+    SetUpstream { branch: Branch },
+    /// Pick a branch or revision to compare the working tree against.
+    CompareWith,
+    /// List tags, or create a tag named after the query at `commit`, or at HEAD when no commit
+    /// is given.
+    Tags { commit: Option<SharedString> },
+    /// Pick the revision that a new tag named `tag_name` points at.
+    TagAt { tag_name: SharedString },
+}
+
 pub struct BranchList {
     width: Rems,
     pub picker: Entity<Picker<BranchListDelegate>>,
@@ -82,59 +170,19 @@ pub struct BranchList {
 impl BranchList {
     fn new(
         repository: Option<Entity<Repository>>,
+        workspace: Option<WeakEntity<Workspace>>,
+        mode: PickerMode,
         style: BranchListStyle,
         width: Rems,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
-        let all_branches_request = repository
-            .clone()
-            .map(|repository| repository.update(cx, |repository, _| repository.branches()));
-
-        cx.spawn_in(window, async move |this, cx| {
-            let mut all_branches = all_branches_request
-                .context("No active repository")?
-                .await??;
-
-            let all_branches = cx
-                .background_spawn(async move {
-                    let remote_upstreams: HashSet<_> = all_branches
-                        .iter()
-                        .filter_map(|branch| {
-                            branch
-                                .upstream
-                                .as_ref()
-                                .filter(|upstream| upstream.is_remote())
-                                .map(|upstream| upstream.ref_name.clone())
-                        })
-                        .collect();
-
-                    all_branches.retain(|branch| !remote_upstreams.contains(&branch.ref_name));
-
-                    all_branches.sort_by_key(|branch| {
-                        branch
-                            .most_recent_commit
-                            .as_ref()
-                            .map(|commit| 0 - commit.commit_timestamp)
-                    });
-
-                    all_branches
-                })
-                .await;
-
-            this.update_in(cx, |this, window, cx| {
-                this.picker.update(cx, |picker, cx| {
-                    picker.delegate.all_branches = Some(all_branches);
-                    picker.refresh(window, cx);
-                })
-            })?;
-
-            anyhow::Ok(())
-        })
-        .detach_and_log_err(cx);
-
-        let delegate = BranchListDelegate::new(repository.clone(), style);
-        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let delegate = BranchListDelegate::new(repository, workspace, mode, style);
+        let picker = cx.new(|cx| {
+            let picker = Picker::uniform_list(delegate, window, cx);
+            picker.delegate.load_branches(window, cx);
+            picker
+        });
 
         let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
             cx.emit(DismissEvent);
@@ -156,6 +204,95 @@ impl BranchList {
         self.picker
             .update(cx, |picker, _| picker.delegate.modifiers = ev.modifiers)
     }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            // Leave the branch-specific modes without closing the list.
+            let previous_mode = match picker.delegate.mode {
+                PickerMode::CreateFrom { .. }
+                | PickerMode::Rename { .. }
+                | PickerMode::SetUpstream { .. }
+                | PickerMode::Tags { commit: None } => PickerMode::Checkout,
+                PickerMode::TagAt { .. } => PickerMode::Tags { commit: None },
+                PickerMode::Checkout | PickerMode::CompareWith | PickerMode::Tags { .. } => {
+                    return;
+                }
+            };
+            BranchListDelegate::set_mode(picker, previous_mode, "", window, cx);
+            cx.stop_propagation();
+        })
+    }
+
+    fn delete_branch(&mut self, _: &DeleteBranch, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            if matches!(picker.delegate.mode, PickerMode::Tags { .. }) {
+                picker.delegate.delete_selected_tag(window, cx)
+            } else {
+                picker.delegate.delete_selected_branch(window, cx)
+            }
+        })
+    }
+
+    fn show_tags(&mut self, _: &ShowTags, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            let mode = match picker.delegate.mode {
+                PickerMode::Checkout => PickerMode::Tags { commit: None },
+                PickerMode::Tags { commit: None } => PickerMode::Checkout,
+                _ => return,
+            };
+            BranchListDelegate::set_mode(picker, mode, "", window, cx);
+        })
+    }
+
+    fn push_tag(&mut self, _: &PushTag, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker.delegate.push_selected_tag(window, cx)
+        })
+    }
+
+    fn rename_branch(&mut self, _: &RenameBranch, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            let Some(branch) = picker.delegate.selected_local_branch() else {
+                return;
+            };
+            let name = branch.name().to_string();
+            BranchListDelegate::set_mode(picker, PickerMode::Rename { branch }, &name, window, cx);
+        })
+    }
+
+    fn set_upstream(&mut self, _: &SetUpstream, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            let Some(branch) = picker.delegate.selected_local_branch() else {
+                return;
+            };
+            BranchListDelegate::set_mode(
+                picker,
+                PickerMode::SetUpstream { branch },
+                "",
+                window,
+                cx,
+            );
+        })
+    }
+
+    fn compare_branch(&mut self, _: &CompareBranch, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            let delegate = &mut picker.delegate;
+            if !matches!(
+                delegate.mode,
+                PickerMode::Checkout | PickerMode::Tags { .. }
+            ) {
+                return;
+            }
+            let Some(entry) = delegate.matches.get(delegate.selected_index) else {
+                return;
+            };
+            if matches!(entry.kind, BranchEntryKind::Existing | BranchEntryKind::Tag) {
+                let base = entry.name().to_string().into();
+                delegate.compare_with(base, window, cx);
+            }
+        })
+    }
 }
 impl ModalView for BranchList {}
 impl EventEmitter<DismissEvent> for BranchList {}
@@ -169,8 +306,16 @@ impl Focusable for BranchList {
 impl Render for BranchList {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         v_flex()
+            .key_context("GitBranchSelector")
             .w(self.width)
             .on_modifiers_changed(cx.listener(Self::handle_modifiers_changed))
+            .capture_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::delete_branch))
+            .on_action(cx.listener(Self::rename_branch))
+            .on_action(cx.listener(Self::set_upstream))
+            .on_action(cx.listener(Self::compare_branch))
+            .on_action(cx.listener(Self::show_tags))
+            .on_action(cx.listener(Self::push_tag))
             .child(self.picker.clone())
             .on_mouse_down_out({
                 cx.listener(move |this, _, window, cx| {
@@ -182,17 +327,75 @@ impl Render for BranchList {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BranchEntryKind {
+    /// A branch that exists in the repository.
+    Existing,
+    /// A branch named after the query, that will be created or renamed to.
+    NewBranch,
+    /// The query, used as a revision.
+    Revision,
+    /// Stop tracking the current upstream.
+    UnsetUpstream,
+    /// A tag that exists in the repository.
+    Tag,
+    /// A tag named after the query, that will be created.
+    NewTag,
+}
+
 #[derive(Debug, Clone)]
 struct BranchEntry {
     branch: Branch,
+    /// The tag that the entry stands for, when it's a `Tag` entry.
+    tag: Option<Tag>,
     positions: Vec<usize>,
-    is_new: bool,
+    kind: BranchEntryKind,
+}
+
+impl BranchEntry {
+    fn existing(branch: Branch) -> Self {
+        Self {
+            branch,
+            tag: None,
+            positions: Vec::new(),
+            kind: BranchEntryKind::Existing,
+        }
+    }
+
+    fn tag(tag: Tag) -> Self {
+        Self {
+            branch: Branch {
+                ref_name: format!("refs/tags/{}", tag.name).into(),
+                is_head: false,
+                upstream: None,
+                most_recent_commit: Some(CommitSummary {
+                    sha: tag.sha.clone(),
+                    subject: tag.message.clone().unwrap_or_default(),
+                    commit_timestamp: tag.timestamp,
+                    has_parent: true,
+                }),
+            },
+            tag: Some(tag),
+            positions: Vec::new(),
+            kind: BranchEntryKind::Tag,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match &self.tag {
+            Some(tag) => tag.name.as_ref(),
+            None => self.branch.name(),
+        }
+    }
 }
 
 pub struct BranchListDelegate {
     matches: Vec<BranchEntry>,
     all_branches: Option<Vec<Branch>>,
+    all_tags: Option<Vec<Tag>>,
     repo: Option<Entity<Repository>>,
+    workspace: Option<WeakEntity<Workspace>>,
+    mode: PickerMode,
     style: BranchListStyle,
     selected_index: usize,
     last_query: String,
@@ -200,21 +403,169 @@ pub struct BranchListDelegate {
 }
 
 impl BranchListDelegate {
-    fn new(repo: Option<Entity<Repository>>, style: BranchListStyle) -> Self {
+    fn new(
+        repo: Option<Entity<Repository>>,
+        workspace: Option<WeakEntity<Workspace>>,
+        mode: PickerMode,
+        style: BranchListStyle,
+    ) -> Self {
         Self {
             matches: vec![],
             repo,
+            workspace,
+            mode,
             style,
             all_branches: None,
+            all_tags: None,
             selected_index: 0,
             last_query: Default::default(),
             modifiers: Default::default(),
         }
     }
 
+    fn load_branches(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let requests = self.repo.clone().map(|repository| {
+            repository.update(cx, |repository, _| {
+                (repository.branches(), repository.tags(None))
+            })
+        });
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let (all_branches_request, all_tags_request) =
+                requests.context("No active repository")?;
+            let mut all_branches = all_branches_request.await??;
+            let all_tags = all_tags_request
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|tags| tags)
+                .log_err()
+                .unwrap_or_default();
+
+            let all_branches = cx
+                .background_spawn(async move {
+                    all_branches.sort_by_key(|branch| {
+                        branch
+                            .most_recent_commit
+                            .as_ref()
+                            .map(|commit| 0 - commit.commit_timestamp)
+                    });
+
+                    all_branches
+                })
+                .await;
+
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.all_branches = Some(all_branches);
+                picker.delegate.all_tags = Some(all_tags);
+                picker.refresh(window, cx);
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn set_mode(
+        picker: &mut Picker<Self>,
+        mode: PickerMode,
+        query: &str,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        picker.delegate.mode = mode;
+        picker.delegate.selected_index = 0;
+        picker.refresh_placeholder(window, cx);
+        picker.set_query(query, window, cx);
+        picker.refresh(window, cx);
+    }
+
+    fn selected_local_branch(&self) -> Option<Branch> {
+        if self.mode != PickerMode::Checkout {
+            return None;
+        }
+        self.matches
+            .get(self.selected_index)
+            .filter(|entry| entry.kind == BranchEntryKind::Existing && !entry.branch.is_remote())
+            .map(|entry| entry.branch.clone())
+    }
+
+    fn selected_tag(&self) -> Option<Tag> {
+        if !matches!(self.mode, PickerMode::Tags { .. }) {
+            return None;
+        }
+        self.matches
+            .get(self.selected_index)
+            .and_then(|entry| entry.tag.clone())
+    }
+
+    fn create_tag(
+        &self,
+        tag_name: SharedString,
+        commit: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let commit = commit.map_or_else(|| "HEAD".to_string(), |commit| commit.to_string());
+        cx.spawn_in(window, async move |picker, cx| {
+            repo.update(cx, |repo, _| {
+                repo.create_tag(tag_name.to_string(), commit, None)
+            })?
+            .await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.load_branches(window, cx);
+                Self::set_mode(picker, PickerMode::Tags { commit: None }, "", window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to create tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn delete_selected_tag(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.selected_tag() else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        cx.spawn_in(window, async move |picker, cx| {
+            repo.update(cx, |repo, _| repo.delete_tag(tag.name.to_string()))?
+                .await??;
+            picker.update_in(cx, |picker, window, cx| {
+                if let Some(all_tags) = picker.delegate.all_tags.as_mut() {
+                    all_tags.retain(|candidate| candidate.name != tag.name);
+                }
+                picker.refresh(window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to delete tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn push_selected_tag(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.selected_tag() else {
+            return;
+        };
+        let Some(panel) = self
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.upgrade())
+            .and_then(|workspace| workspace.read(cx).panel::<GitPanel>(cx))
+        else {
+            return;
+        };
+        cx.emit(DismissEvent);
+        panel.update(cx, |panel, cx| panel.push_tag(tag.name, window, cx));
+    }
+
     fn create_branch(
         &self,
         new_branch_name: SharedString,
+        base_revision: Option<String>,
         window: &mut Window,
         cx: &mut Context<Picker<Self>>,
     ) {
@@ -224,7 +575,7 @@ impl BranchListDelegate {
         let new_branch_name = new_branch_name.to_string().replace(' ', "-");
         cx.spawn(async move |_, cx| {
             repo.update(cx, |repo, _| {
-                repo.create_branch(new_branch_name.to_string())
+                repo.create_branch(new_branch_name.to_string(), base_revision)
             })?
             .await??;
             repo.update(cx, |repo, _| {
@@ -239,13 +590,134 @@ impl BranchListDelegate {
         });
         cx.emit(DismissEvent);
     }
+
+    fn delete_selected_branch(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(branch) = self.selected_local_branch() else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let branch_name = branch.name().to_string();
+        cx.spawn_in(window, async move |picker, cx| {
+            let merged = repo
+                .update(cx, |repo, _| repo.is_branch_merged(branch_name.clone()))?
+                .await??;
+            if merged {
+                repo.update(cx, |repo, _| repo.delete_branch(branch_name.clone(), false))?
+                    .await??;
+            } else {
+                let answer = cx.update(|window, cx| {
+                    window.prompt(
+                        PromptLevel::Warning,
+                        &format!("The branch \"{branch_name}\" is not fully merged."),
+                        Some("Commits that are only on this branch will be lost."),
+                        &["Force Delete", "Cancel"],
+                        cx,
+                    )
+                })?;
+                if answer.await != Ok(0) {
+                    return Ok(());
+                }
+                repo.update(cx, |repo, _| repo.delete_branch(branch_name.clone(), true))?
+                    .await??;
+            }
+
+            picker.update_in(cx, |picker, window, cx| {
+                if let Some(all_branches) = picker.delegate.all_branches.as_mut() {
+                    all_branches.retain(|candidate| candidate.ref_name != branch.ref_name);
+                }
+                picker.refresh(window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to delete branch", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn rename_branch(
+        &self,
+        branch: Branch,
+        new_name: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        cx.spawn_in(window, async move |picker, cx| {
+            repo.update(cx, |repo, _| {
+                repo.rename_branch(branch.name().to_string(), new_name)
+            })?
+            .await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.load_branches(window, cx);
+                Self::set_mode(picker, PickerMode::Checkout, "", window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to rename branch", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn set_upstream(
+        &self,
+        branch: Branch,
+        upstream: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        cx.spawn_in(window, async move |picker, cx| {
+            repo.update(cx, |repo, _| {
+                repo.set_upstream(branch.name().to_string(), upstream)
+            })?
+            .await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.load_branches(window, cx);
+                Self::set_mode(picker, PickerMode::Checkout, "", window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to set upstream", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn compare_with(
+        &self,
+        base: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(workspace) = self
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.upgrade())
+        else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            ProjectDiff::deploy_compare(workspace, base, window, cx);
+        });
+        cx.emit(DismissEvent);
+    }
 }
 
 impl PickerDelegate for BranchListDelegate {
     type ListItem = ListItem;
 
     fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
-        "Select branch...".into()
+        match &self.mode {
+            PickerMode::Checkout => "Select branch...".into(),
+            PickerMode::CreateFrom { .. } => "Select a branch or enter a revision...".into(),
+            PickerMode::Rename { .. } => "Enter the new branch name...".into(),
+            PickerMode::SetUpstream { .. } => "Select remote branch...".into(),
+            PickerMode::CompareWith => "Select a branch or enter a revision...".into(),
+            PickerMode::Tags { .. } => "Select tag or enter a new tag name...".into(),
+            PickerMode::TagAt { .. } => "Select a branch or enter a revision...".into(),
+        }
     }
 
     fn editor_position(&self) -> PickerEditorPosition {
@@ -281,28 +753,74 @@ impl PickerDelegate for BranchListDelegate {
         let Some(all_branches) = self.all_branches.clone() else {
             return Task::ready(());
         };
+        let all_tags = self.all_tags.clone().unwrap_or_default();
+        let mode = self.mode.clone();
 
         const RECENT_BRANCHES_COUNT: usize = 10;
         cx.spawn_in(window, async move |picker, cx| {
+            let candidates = cx
+                .background_spawn({
+                    let mode = mode.clone();
+                    async move {
+                        let branches: Vec<Branch> = match mode {
+                            PickerMode::Checkout => {
+                                let remote_upstreams: HashSet<_> = all_branches
+                                    .iter()
+                                    .filter_map(|branch| {
+                                        branch
+                                            .upstream
+                                            .as_ref()
+                                            .filter(|upstream| upstream.is_remote())
+                                            .map(|upstream| upstream.ref_name.clone())
+                                    })
+                                    .collect();
+                                all_branches
+                                    .into_iter()
+                                    .filter(|branch| !remote_upstreams.contains(&branch.ref_name))
+                                    .collect()
+                            }
+                            PickerMode::SetUpstream { .. } => all_branches
+                                .into_iter()
+                                .filter(|branch| branch.is_remote())
+                                .collect(),
+                            PickerMode::Rename { .. } | PickerMode::Tags { .. } => Vec::new(),
+                            PickerMode::CreateFrom { .. }
+                            | PickerMode::CompareWith
+                            | PickerMode::TagAt { .. } => all_branches,
+                        };
+                        // Tags can be listed, and can stand in for a revision.
+                        let tags = match mode {
+                            PickerMode::Tags { .. }
+                            | PickerMode::CreateFrom { .. }
+                            | PickerMode::CompareWith
+                            | PickerMode::TagAt { .. } => all_tags,
+                            PickerMode::Checkout
+                            | PickerMode::Rename { .. }
+                            | PickerMode::SetUpstream { .. } => Vec::new(),
+                        };
+                        branches
+                            .into_iter()
+                            .map(BranchEntry::existing)
+                            .chain(tags.into_iter().map(BranchEntry::tag))
+                            .collect::<Vec<_>>()
+                    }
+                })
+                .await;
+
             let mut matches: Vec<BranchEntry> = if query.is_empty() {
-                all_branches
+                candidates
                     .into_iter()
-                    .filter(|branch| !branch.is_remote())
+                    .filter(|entry| mode != PickerMode::Checkout || !entry.branch.is_remote())
                     .take(RECENT_BRANCHES_COUNT)
-                    .map(|branch| BranchEntry {
-                        branch,
-                        positions: Vec::new(),
-                        is_new: false,
-                    })
                     .collect()
             } else {
-                let candidates = all_branches
+                let match_candidates = candidates
                     .iter()
                     .enumerate()
-                    .map(|(ix, branch)| StringMatchCandidate::new(ix, branch.name()))
+                    .map(|(ix, entry)| StringMatchCandidate::new(ix, entry.name()))
                     .collect::<Vec<StringMatchCandidate>>();
                 fuzzy::match_strings(
-                    &candidates,
+                    &match_candidates,
                     &query,
                     true,
                     10000,
@@ -312,33 +830,87 @@ impl PickerDelegate for BranchListDelegate {
                 .await
                 .into_iter()
                 .map(|candidate| BranchEntry {
-                    branch: all_branches[candidate.candidate_id].clone(),
                     positions: candidate.positions,
-                    is_new: false,
+                    ..candidates[candidate.candidate_id].clone()
                 })
                 .collect()
             };
             picker
                 .update(cx, |picker, _| {
-                    #[allow(clippy::nonminimal_bool)]
-                    if !query.is_empty()
-                        && !matches
-                            .first()
-                            .is_some_and(|entry| entry.branch.name() == query)
-                    {
-                        let query = query.replace(' ', "-");
-                        matches.push(BranchEntry {
-                            branch: Branch {
-                                ref_name: format!("refs/heads/{query}").into(),
-                                is_head: false,
-                                upstream: None,
-                                most_recent_commit: None,
-                            },
-                            positions: Vec::new(),
-                            is_new: true,
-                        })
+                    let is_exact_match = matches.first().is_some_and(|entry| entry.name() == query);
+                    match &mode {
+                        PickerMode::Checkout | PickerMode::Rename { .. } => {
+                            let query = query.trim().replace(' ', "-");
+                            let is_current_name = matches!(
+                                &mode,
+                                PickerMode::Rename { branch } if branch.name() == query
+                            );
+                            if !query.is_empty() && !is_exact_match && !is_current_name {
+                                matches.push(BranchEntry {
+                                    branch: Branch {
+                                        ref_name: format!("refs/heads/{query}").into(),
+                                        is_head: false,
+                                        upstream: None,
+                                        most_recent_commit: None,
+                                    },
+                                    tag: None,
+                                    positions: Vec::new(),
+                                    kind: BranchEntryKind::NewBranch,
+                                })
+                            }
+                        }
+                        PickerMode::CreateFrom { .. }
+                        | PickerMode::CompareWith
+                        | PickerMode::TagAt { .. } => {
+                            let query = query.trim();
+                            if !query.is_empty() && !is_exact_match {
+                                matches.push(BranchEntry {
+                                    branch: Branch {
+                                        ref_name: query.to_string().into(),
+                                        is_head: false,
+                                        upstream: None,
+                                        most_recent_commit: None,
+                                    },
+                                    tag: None,
+                                    positions: Vec::new(),
+                                    kind: BranchEntryKind::Revision,
+                                })
+                            }
+                        }
+                        PickerMode::Tags { .. } => {
+                            let query = query.trim().replace(' ', "-");
+                            if !query.is_empty() && !is_exact_match {
+                                matches.push(BranchEntry {
+                                    branch: Branch {
+                                        ref_name: query.into(),
+                                        is_head: false,
+                                        upstream: None,
+                                        most_recent_commit: None,
+                                    },
+                                    tag: None,
+                                    positions: Vec::new(),
+                                    kind: BranchEntryKind::NewTag,
+                                })
+                            }
+                        }
+                        PickerMode::SetUpstream { branch } => {
+                            if query.is_empty() && branch.upstream.is_some() {
+                                matches.insert(
+                                    0,
+                                    BranchEntry {
+                                        branch: branch.clone(),
+                                        tag: None,
+                                        positions: Vec::new(),
+                                        kind: BranchEntryKind::UnsetUpstream,
+                                    },
+                                )
+                            }
+                        }
                     }
                     let delegate = &mut picker.delegate;
+                    if delegate.mode != mode {
+                        return;
+                    }
                     delegate.matches = matches;
                     if delegate.matches.is_empty() {
                         delegate.selected_index = 0;
@@ -352,12 +924,82 @@ impl PickerDelegate for BranchListDelegate {
         })
     }
 
-    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
-        let Some(entry) = self.matches.get(self.selected_index()) else {
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index()).cloned() else {
             return;
         };
-        if entry.is_new {
-            self.create_branch(entry.branch.name().to_owned().into(), window, cx);
+        match self.mode.clone() {
+            PickerMode::Checkout => {}
+            PickerMode::CreateFrom { branch_name } => {
+                let base_revision = entry.name().to_string();
+                self.create_branch(branch_name, Some(base_revision), window, cx);
+                return;
+            }
+            PickerMode::Rename { branch } => {
+                if entry.kind == BranchEntryKind::NewBranch {
+                    self.rename_branch(branch, entry.branch.name().to_string(), window, cx);
+                }
+                return;
+            }
+            PickerMode::SetUpstream { branch } => {
+                let upstream = match entry.kind {
+                    BranchEntryKind::UnsetUpstream => None,
+                    _ => Some(entry.branch.name().to_string()),
+                };
+                self.set_upstream(branch, upstream, window, cx);
+                return;
+            }
+            PickerMode::CompareWith => {
+                self.compare_with(entry.name().to_string().into(), window, cx);
+                return;
+            }
+            PickerMode::Tags { commit } => {
+                match entry.kind {
+                    BranchEntryKind::NewTag => {
+                        let tag_name: SharedString = entry.name().to_owned().into();
+                        if secondary && commit.is_none() {
+                            cx.defer_in(window, move |picker, window, cx| {
+                                Self::set_mode(
+                                    picker,
+                                    PickerMode::TagAt { tag_name },
+                                    "",
+                                    window,
+                                    cx,
+                                );
+                            });
+                        } else {
+                            self.create_tag(tag_name, commit, window, cx);
+                        }
+                    }
+                    BranchEntryKind::Tag => {
+                        self.compare_with(entry.name().to_string().into(), window, cx);
+                    }
+                    _ => {}
+                }
+                return;
+            }
+            PickerMode::TagAt { tag_name } => {
+                let commit = entry.name().to_string().into();
+                self.create_tag(tag_name, Some(commit), window, cx);
+                return;
+            }
+        }
+
+        if entry.kind == BranchEntryKind::NewBranch {
+            let branch_name: SharedString = entry.branch.name().to_owned().into();
+            if secondary {
+                cx.defer_in(window, move |picker, window, cx| {
+                    Self::set_mode(
+                        picker,
+                        PickerMode::CreateFrom { branch_name },
+                        "",
+                        window,
+                        cx,
+                    );
+                });
+            } else {
+                self.create_branch(branch_name, None, window, cx);
+            }
             return;
         }
 
@@ -413,7 +1055,158 @@ impl PickerDelegate for BranchListDelegate {
     }
 
     fn render_header(&self, _: &mut Window, _cx: &mut Context<Picker<Self>>) -> Option<AnyElement> {
-        None
+        let title = match &self.mode {
+            PickerMode::Checkout => return None,
+            PickerMode::CreateFrom { branch_name } => {
+                format!("Create \"{branch_name}\" from")
+            }
+            PickerMode::Rename { branch } => format!("Rename \"{}\"", branch.name()),
+            PickerMode::SetUpstream { branch } => {
+                format!("Set upstream of \"{}\"", branch.name())
+            }
+            PickerMode::CompareWith => "Compare working tree with".to_string(),
+            PickerMode::Tags { commit: None } => "Tags".to_string(),
+            PickerMode::Tags {
+                commit: Some(commit),
+            } => format!("Create a tag at {}", short_sha(commit)),
+            PickerMode::TagAt { tag_name } => format!("Create tag \"{tag_name}\" at"),
+        };
+        Some(
+            h_flex()
+                .px_3()
+                .pt_2()
+                .pb_1()
+                .child(
+                    Label::new(title)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .truncate(),
+                )
+                .into_any_element(),
+        )
+    }
+
+    fn render_footer(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        if self.style != BranchListStyle::Modal
+            || !matches!(self.mode, PickerMode::Checkout | PickerMode::Tags { .. })
+        {
+            return None;
+        }
+        let selected_kind = self
+            .matches
+            .get(self.selected_index)
+            .map(|entry| entry.kind);
+
+        let footer = h_flex()
+            .w_full()
+            .p_1p5()
+            .gap_0p5()
+            .justify_end()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant);
+
+        let footer = if let PickerMode::Tags { commit } = &self.mode {
+            let is_tag = selected_kind == Some(BranchEntryKind::Tag);
+            footer
+                .when(
+                    selected_kind == Some(BranchEntryKind::NewTag) && commit.is_none(),
+                    |footer| {
+                        footer.child(footer_button(
+                            "create-tag-at",
+                            "Create At…",
+                            &menu::SecondaryConfirm,
+                            false,
+                            window,
+                            cx,
+                        ))
+                    },
+                )
+                .when(selected_kind != Some(BranchEntryKind::NewTag), |footer| {
+                    footer
+                        .child(footer_button(
+                            "delete-tag",
+                            "Delete",
+                            &DeleteBranch,
+                            !is_tag,
+                            window,
+                            cx,
+                        ))
+                        .when(self.workspace.is_some(), |footer| {
+                            footer.child(footer_button(
+                                "push-tag", "Push", &PushTag, !is_tag, window, cx,
+                            ))
+                        })
+                })
+                .when(commit.is_none(), |footer| {
+                    footer.child(footer_button(
+                        "show-branches",
+                        "Branches",
+                        &ShowTags,
+                        false,
+                        window,
+                        cx,
+                    ))
+                })
+        } else if selected_kind == Some(BranchEntryKind::NewBranch) {
+            footer.child(footer_button(
+                "create-branch-from",
+                "Create From…",
+                &menu::SecondaryConfirm,
+                false,
+                window,
+                cx,
+            ))
+        } else {
+            let is_local = self.selected_local_branch().is_some();
+            footer
+                .child(footer_button(
+                    "delete-branch",
+                    "Delete",
+                    &DeleteBranch,
+                    !is_local,
+                    window,
+                    cx,
+                ))
+                .child(footer_button(
+                    "rename-branch",
+                    "Rename",
+                    &RenameBranch,
+                    !is_local,
+                    window,
+                    cx,
+                ))
+                .child(footer_button(
+                    "set-upstream",
+                    "Upstream",
+                    &SetUpstream,
+                    !is_local,
+                    window,
+                    cx,
+                ))
+                .when(self.workspace.is_some(), |footer| {
+                    footer.child(footer_button(
+                        "compare-branch",
+                        "Compare",
+                        &CompareBranch,
+                        selected_kind.is_none(),
+                        window,
+                        cx,
+                    ))
+                })
+                .child(footer_button(
+                    "show-tags",
+                    "Tags",
+                    &ShowTags,
+                    false,
+                    window,
+                    cx,
+                ))
+        };
+        Some(footer.into_any_element())
     }
 
     fn render_match(
@@ -442,6 +1235,85 @@ impl PickerDelegate for BranchListDelegate {
             })
             .unwrap_or_else(|| (None, None));
 
+        let label = match (entry.kind, &self.mode) {
+            (BranchEntryKind::Existing, _) => {
+                HighlightedLabel::new(entry.branch.name().to_owned(), entry.positions.clone())
+                    .truncate()
+                    .into_any_element()
+            }
+            (BranchEntryKind::Tag, _) => h_flex()
+                .gap_1()
+                .child(
+                    Icon::new(IconName::Hash)
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted),
+                )
+                .child(
+                    HighlightedLabel::new(entry.name().to_owned(), entry.positions.clone())
+                        .truncate(),
+                )
+                .into_any_element(),
+            (BranchEntryKind::NewTag, PickerMode::Tags { commit }) => {
+                let target = commit.as_ref().map_or("HEAD", |commit| short_sha(commit));
+                Label::new(format!("Create tag \"{}\" at {target}", entry.name()))
+                    .single_line()
+                    .into_any_element()
+            }
+            (BranchEntryKind::NewTag, _) => Label::new(format!("Create tag \"{}\"", entry.name()))
+                .single_line()
+                .into_any_element(),
+            (BranchEntryKind::NewBranch, PickerMode::Rename { branch }) => Label::new(format!(
+                "Rename \"{}\" to \"{}\"",
+                branch.name(),
+                entry.branch.name()
+            ))
+            .single_line()
+            .into_any_element(),
+            (BranchEntryKind::NewBranch, _) => {
+                Label::new(format!("Create branch \"{}\"…", entry.branch.name()))
+                    .single_line()
+                    .into_any_element()
+            }
+            (BranchEntryKind::Revision, _) => {
+                Label::new(format!("Use revision \"{}\"", entry.branch.name()))
+                    .single_line()
+                    .into_any_element()
+            }
+            (BranchEntryKind::UnsetUpstream, _) => Label::new("Unset upstream")
+                .single_line()
+                .into_any_element(),
+        };
+
+        let message = match entry.kind {
+            BranchEntryKind::Existing => {
+                Some(subject.unwrap_or("no commits found".into()).to_string())
+            }
+            BranchEntryKind::NewBranch if self.mode == PickerMode::Checkout => {
+                if let Some(current_branch) = self
+                    .repo
+                    .as_ref()
+                    .and_then(|repo| repo.read(cx).branch.as_ref().map(|b| b.name()))
+                {
+                    Some(format!("based off {}", current_branch))
+                } else {
+                    Some("based off the current branch".to_string())
+                }
+            }
+            BranchEntryKind::Tag => entry.tag.as_ref().map(|tag| match &tag.message {
+                Some(message) => message.to_string(),
+                None => format!("lightweight tag at {}", short_sha(&tag.sha)),
+            }),
+            BranchEntryKind::NewBranch | BranchEntryKind::Revision | BranchEntryKind::NewTag => {
+                None
+            }
+            BranchEntryKind::UnsetUpstream => entry.branch.upstream.as_ref().map(|upstream| {
+                let name = upstream
+                    .stripped_ref_name()
+                    .unwrap_or(upstream.ref_name.as_ref());
+                format!("currently tracking {name}")
+            }),
+        };
+
         Some(
             ListItem::new(SharedString::from(format!("vcs-menu-{ix}")))
                 .inset(true)
@@ -461,23 +1333,7 @@ impl PickerDelegate for BranchListDelegate {
                                 .overflow_x_hidden()
                                 .gap_2()
                                 .justify_between()
-                                .child(div().flex_shrink().overflow_x_hidden().child(
-                                    if entry.is_new {
-                                        Label::new(format!(
-                                            "Create branch \"{}\"…",
-                                            entry.branch.name()
-                                        ))
-                                        .single_line()
-                                        .into_any_element()
-                                    } else {
-                                        HighlightedLabel::new(
-                                            entry.branch.name().to_owned(),
-                                            entry.positions.clone(),
-                                        )
-                                        .truncate()
-                                        .into_any_element()
-                                    },
-                                ))
+                                .child(div().flex_shrink().overflow_x_hidden().child(label))
                                 .when_some(commit_time, |el, commit_time| {
                                     el.child(
                                         Label::new(commit_time)
@@ -488,25 +1344,16 @@ impl PickerDelegate for BranchListDelegate {
                                 }),
                         )
                         .when(self.style == BranchListStyle::Modal, |el| {
-                            el.child(div().max_w_96().child({
-                                let message = if entry.is_new {
-                                    if let Some(current_branch) =
-                                        self.repo.as_ref().and_then(|repo| {
-                                            repo.read(cx).branch.as_ref().map(|b| b.name())
-                                        })
-                                    {
-                                        format!("based off {}", current_branch)
-                                    } else {
-                                        "based off the current branch".to_string()
-                                    }
-                                } else {
-                                    subject.unwrap_or("no commits found".into()).to_string()
-                                };
-                                Label::new(message)
-                                    .size(LabelSize::Small)
-                                    .truncate()
-                                    .color(Color::Muted)
-                            }))
+                            el.when_some(message, |el, message| {
+                                el.child(
+                                    div().max_w_96().child(
+                                        Label::new(message)
+                                            .size(LabelSize::Small)
+                                            .truncate()
+                                            .color(Color::Muted),
+                                    ),
+                                )
+                            })
                         }),
                 ),
         )
@@ -516,3 +1363,26 @@ impl PickerDelegate for BranchListDelegate {
         None
     }
 }
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+fn footer_button(
+    id: &'static str,
+    label: &'static str,
+    action: &dyn Action,
+    disabled: bool,
+    window: &mut Window,
+    cx: &App,
+) -> Button {
+    let action = action.boxed_clone();
+    Button::new(id, label)
+        .label_size(LabelSize::Small)
+        .disabled(disabled)
+        .key_binding(
+            KeyBinding::for_action(action.as_ref(), window, cx)
+                .map(|kb| kb.size(rems_from_px(12.))),
+        )
+        .on_click(move |_, window, cx| window.dispatch_action(action.boxed_clone(), cx))
+}
@@ -0,0 +1,729 @@
+use anyhow::Result;
+use commit_graph::CommitGraph;
+use editor::Editor;
+use futures::channel::oneshot;
+use git::repository::{CommitLogEntry, CommitSummary, LogOptions, RepoPath};
+use git::{FileHistory, OpenFileHistory, ViewHistory};
+use gpui::{
+    Action, AnyElement, App, ClickEvent, Corner, DismissEvent, Entity, EventEmitter, FocusHandle,
+    Focusable, MouseButton, MouseDownEvent, Point, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, actions, anchored, deferred, uniform_list,
+};
+use menu::{SelectNext, SelectPrevious};
+use project::ProjectPath;
+use project::git_store::{Repository, RepositoryEvent};
+use time::{OffsetDateTime, UtcOffset};
+use ui::{ContextMenu, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{Item, Workspace, item::ItemEvent, notifications::DetachAndPromptErr as _};
+
+use crate::branch_picker;
+use crate::commit_view::CommitView;
+use crate::project_diff::ProjectDiff;
+use crate::rebase_editor::RebaseEditor;
+
+actions!(
+    git_commit_history,
+    [CherryPickCommit, RevertCommit, RebaseFromCommit, TagCommit]
+);
+
+/// The number of commits loaded at a time as the history is scrolled.
+const PAGE_SIZE: usize = 200;
+const ROW_HEIGHT: Pixels = px(28.);
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &ViewHistory, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        CommitHistory::deploy(workspace, repository, None, window, cx);
+    });
+    workspace.register_action(|workspace, _: &FileHistory, window, cx| {
+        let Some(project_path) = workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+        else {
+            return;
+        };
+        CommitHistory::deploy_for_project_path(workspace, &project_path, window, cx);
+    });
+    workspace.register_action(|workspace, action: &OpenFileHistory, window, cx| {
+        let Some(project_path) = workspace
+            .project()
+            .read(cx)
+            .find_project_path(&action.abs_path, cx)
+        else {
+            return;
+        };
+        CommitHistory::deploy_for_project_path(workspace, &project_path, window, cx);
+    });
+}
+
+/// Shows the commit graph of a repository, optionally limited to the commits that touched a
+/// single path.
+pub struct CommitHistory {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    path: Option<RepoPath>,
+    focus_handle: FocusHandle,
+    revision_editor: Entity<Editor>,
+    author_editor: Entity<Editor>,
+    entries: Vec<CommitLogEntry>,
+    graph: CommitGraph,
+    has_more: bool,
+    selected_ix: Option<usize>,
+    scroll_handle: UniformListScrollHandle,
+    error: Option<SharedString>,
+    pending_load: Option<Task<()>>,
+    context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
+    /// Set when an operation started from the history fails, so that the conflicts it
+    /// stopped on are shown once the repository has been rescanned.
+    show_conflicts_after_scan: bool,
+    _subscription: Subscription,
+}
+
+impl CommitHistory {
+    fn deploy_for_project_path(
+        workspace: &mut Workspace,
+        project_path: &ProjectPath,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some((repository, repo_path)) = workspace
+            .project()
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_project_path(project_path, cx)
+        else {
+            return;
+        };
+        // The repository's root directory covers the whole history.
+        let path = Some(repo_path).filter(|path| !path.as_os_str().is_empty());
+        Self::deploy(workspace, repository, path, window, cx);
+    }
+
+    pub fn deploy(
+        workspace: &mut Workspace,
+        repository: Entity<Repository>,
+        path: Option<RepoPath>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let existing = workspace.items_of_type::<Self>(cx).find(|history| {
+            let history = history.read(cx);
+            history.repository == repository && history.path == path
+        });
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let workspace_handle = cx.entity().downgrade();
+        let history = cx.new(|cx| Self::new(repository, path, workspace_handle, window, cx));
+        workspace.add_item_to_active_pane(Box::new(history), None, true, window, cx);
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        path: Option<RepoPath>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let revision_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Revision range, e.g. main..HEAD", cx);
+            editor
+        });
+        let author_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Author", cx);
+            editor
+        });
+        let subscription =
+            cx.subscribe_in(
+                &repository,
+                window,
+                |this, _, event, window, cx| match event {
+                    RepositoryEvent::Updated { full_scan } => {
+                        this.refresh(cx);
+                        if *full_scan {
+                            this.show_conflicts_if_needed(window, cx);
+                        }
+                    }
+                    RepositoryEvent::MergeHeadsChanged => this.show_conflicts_if_needed(window, cx),
+                },
+            );
+
+        let mut this = Self {
+            repository,
+            workspace,
+            path,
+            focus_handle: cx.focus_handle(),
+            revision_editor,
+            author_editor,
+            entries: Vec::new(),
+            graph: CommitGraph::default(),
+            has_more: true,
+            selected_ix: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            error: None,
+            pending_load: None,
+            context_menu: None,
+            show_conflicts_after_scan: false,
+            _subscription: subscription,
+        };
+        this.reload(cx);
+        this
+    }
+
+    fn log_options(&self, skip: usize, limit: usize, cx: &App) -> LogOptions {
+        let revision_range = self.revision_editor.read(cx).text(cx).trim().to_string();
+        let author = self.author_editor.read(cx).text(cx).trim().to_string();
+        LogOptions {
+            revision_range: Some(revision_range).filter(|range| !range.is_empty()),
+            paths: self.path.iter().cloned().collect(),
+            author: Some(author).filter(|author| !author.is_empty()),
+            skip,
+            limit,
+        }
+    }
+
+    /// Loads the history from the start, after the filters have changed.
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        let selected_sha = self.selected_entry().map(|entry| entry.sha.clone());
+        self.load_page(0, PAGE_SIZE, selected_sha, cx);
+    }
+
+    /// Reloads every page loaded so far after the repository changed, so that the scroll
+    /// position isn't lost.
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let selected_sha = self.selected_entry().map(|entry| entry.sha.clone());
+        let limit = self.entries.len().max(PAGE_SIZE);
+        self.load_page(0, limit, selected_sha, cx);
+    }
+
+    fn load_more(&mut self, cx: &mut Context<Self>) {
+        if self.has_more && self.pending_load.is_none() {
+            self.load_page(self.entries.len(), PAGE_SIZE, None, cx);
+        }
+    }
+
+    /// Fetches the next page once the list is scrolled close to the end of what's loaded.
+    fn load_more_if_scrolled_to_end(&mut self, cx: &mut Context<Self>) {
+        let scroll_handle = self.scroll_handle.0.borrow().base_handle.clone();
+        let visible_end =
+            (-scroll_handle.offset().y + scroll_handle.bounds().size.height) / ROW_HEIGHT;
+        if visible_end as usize + PAGE_SIZE / 4 >= self.entries.len() {
+            self.load_more(cx);
+        }
+    }
+
+    fn load_page(
+        &mut self,
+        skip: usize,
+        limit: usize,
+        selected_sha: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        let options = self.log_options(skip, limit, cx);
+        let log = self
+            .repository
+            .update(cx, |repository, _| repository.log(options));
+        self.pending_load = Some(cx.spawn(async move |this, cx| {
+            let result = log
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+            this.update(cx, |this, cx| {
+                this.pending_load = None;
+                match result {
+                    Ok(entries) => {
+                        this.has_more = entries.len() == limit;
+                        if skip == 0 {
+                            if this.entries == entries && this.error.is_none() {
+                                return;
+                            }
+                            this.entries.clear();
+                            this.graph = CommitGraph::default();
+                            this.selected_ix = None;
+                        }
+                        for entry in &entries {
+                            this.graph.push(&entry.sha, &entry.parent_shas);
+                        }
+                        this.entries.extend(entries);
+                        if let Some(selected_sha) = selected_sha {
+                            this.selected_ix = this
+                                .entries
+                                .iter()
+                                .position(|entry| entry.sha == selected_sha);
+                        }
+                        this.error = None;
+                    }
+                    Err(error) => {
+                        this.entries.clear();
+                        this.graph = CommitGraph::default();
+                        this.selected_ix = None;
+                        this.has_more = false;
+                        this.error = Some(error.to_string().into());
+                    }
+                }
+                cx.notify();
+            })
+            .log_err();
+        }));
+    }
+
+    fn selected_entry(&self) -> Option<&CommitLogEntry> {
+        self.entries.get(self.selected_ix?)
+    }
+
+    fn confirm_filter(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        self.reload(cx);
+        self.focus_handle.focus(window);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let ix = self
+            .selected_ix
+            .map_or(0, |ix| (ix + 1).min(self.entries.len() - 1));
+        self.select_entry(ix, cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let ix = self.selected_ix.map_or(0, |ix| ix.saturating_sub(1));
+        self.select_entry(ix, cx);
+    }
+
+    fn select_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_ix = Some(ix);
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Center);
+        if ix + PAGE_SIZE / 4 >= self.entries.len() {
+            self.load_more(cx);
+        }
+        cx.notify();
+    }
+
+    fn open_selected(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        CommitView::open(
+            CommitSummary {
+                sha: entry.sha.clone(),
+                subject: entry.subject.clone(),
+                commit_timestamp: entry.commit_timestamp,
+                has_parent: !entry.parent_shas.is_empty(),
+            },
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn cherry_pick(&mut self, _: &CherryPickCommit, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(sha) = self.selected_entry().map(|entry| entry.sha.to_string()) else {
+            return;
+        };
+        self.run_operation(
+            "Failed to cherry-pick commit",
+            |repository| repository.cherry_pick(sha),
+            window,
+            cx,
+        );
+    }
+
+    fn revert(&mut self, _: &RevertCommit, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(sha) = self.selected_entry().map(|entry| entry.sha.to_string()) else {
+            return;
+        };
+        self.run_operation(
+            "Failed to revert commit",
+            |repository| repository.revert(sha),
+            window,
+            cx,
+        );
+    }
+
+    fn rebase_from(&mut self, _: &RebaseFromCommit, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(base) = self.selected_entry().cloned() else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let repository = self.repository.clone();
+        let this = cx.entity().downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                RebaseEditor::new(
+                    repository,
+                    base,
+                    move |base, todo, window, cx| {
+                        this.update(cx, |this, cx| {
+                            this.run_operation(
+                                "Failed to rebase",
+                                |repository| repository.rebase_interactive(base, todo),
+                                window,
+                                cx,
+                            );
+                        })
+                        .ok();
+                    },
+                    window,
+                    cx,
+                )
+            });
+        });
+    }
+
+    fn tag(&mut self, _: &TagCommit, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(sha) = self.selected_entry().map(|entry| entry.sha.clone()) else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                branch_picker::create_tag_at(workspace, sha, window, cx);
+            })
+            .ok();
+    }
+
+    fn run_operation(
+        &mut self,
+        error_title: &'static str,
+        operation: impl FnOnce(&mut Repository) -> oneshot::Receiver<Result<()>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let result = self.repository.update(cx, operation);
+        cx.spawn_in(window, async move |this, cx| {
+            let result = result.await?;
+            if result.is_err() {
+                this.update(cx, |this, _| this.show_conflicts_after_scan = true)?;
+            }
+            result
+        })
+        .detach_and_prompt_err(error_title, window, cx, |_, _, _| None);
+    }
+
+    /// Opens the project diff once an operation has stopped on conflicts, so that they can be
+    /// resolved before it's continued.
+    fn show_conflicts_if_needed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.show_conflicts_after_scan {
+            return;
+        }
+        let repository = self.repository.read(cx);
+        if repository.merge.in_progress_operation.is_none() {
+            self.show_conflicts_after_scan = false;
+            return;
+        }
+        if !repository
+            .cached_status()
+            .any(|entry| entry.status.is_conflicted())
+        {
+            return;
+        }
+        self.show_conflicts_after_scan = false;
+        self.workspace
+            .update(cx, |workspace, cx| {
+                ProjectDiff::deploy_at(workspace, None, window, cx);
+            })
+            .ok();
+    }
+
+    fn deploy_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        let is_merge = entry.parent_shas.len() > 1;
+        let is_root = entry.parent_shas.is_empty();
+        let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
+            context_menu
+                .context(self.focus_handle.clone())
+                .action("View Commit", menu::Confirm.boxed_clone())
+                .separator()
+                .when(!is_merge, |menu| {
+                    menu.action("Cherry-Pick", CherryPickCommit.boxed_clone())
+                        .action("Revert", RevertCommit.boxed_clone())
+                })
+                .when(!is_root, |menu| {
+                    menu.action(
+                        "Interactive Rebase from Here…",
+                        RebaseFromCommit.boxed_clone(),
+                    )
+                })
+                .separator()
+                .action("Create Tag…", TagCommit.boxed_clone())
+        });
+        self.selected_ix = Some(ix);
+
+        let subscription = cx.subscribe_in(
+            &context_menu,
+            window,
+            |this, _, _: &DismissEvent, window, cx| {
+                if this.context_menu.as_ref().is_some_and(|context_menu| {
+                    context_menu.0.focus_handle(cx).contains_focused(window, cx)
+                }) {
+                    cx.focus_self(window);
+                }
+                this.context_menu.take();
+                cx.notify();
+            },
+        );
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let entry = &self.entries[ix];
+        let row = &self.graph.rows()[ix];
+        let selected = self.selected_ix == Some(ix);
+        let tooltip_text: SharedString = format!(
+            "{}\n{} <{}>\n\n{}",
+            entry.sha, entry.author_name, entry.author_email, entry.subject
+        )
+        .into();
+
+        h_flex()
+            .id(("commit", ix))
+            .h(ROW_HEIGHT)
+            .w_full()
+            .pl_2()
+            .pr_3()
+            .gap_2()
+            .cursor_pointer()
+            .when(selected, |this| {
+                this.bg(cx.theme().colors().ghost_element_selected)
+            })
+            .hover(|this| this.bg(cx.theme().colors().ghost_element_hover))
+            // Hollow out merge commits, so that they're easy to tell apart.
+            .child(commit_graph::render_graph_row(
+                row,
+                self.graph.lane_count(),
+                row.is_merge,
+                cx.theme().colors().editor_background,
+                cx,
+            ))
+            .children(entry.refs.iter().map(|name| {
+                Label::new(name.clone()).size(LabelSize::Small).color(
+                    if name.starts_with("tag: ") {
+                        Color::Accent
+                    } else {
+                        Color::Created
+                    },
+                )
+            }))
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .child(Label::new(entry.subject.clone()).truncate()),
+            )
+            .child(
+                Label::new(entry.author_name.clone())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                Label::new(relative_timestamp(entry.commit_timestamp))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                Label::new(entry.short_sha().to_string())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+            .tooltip(Tooltip::text(tooltip_text))
+            .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                this.select_entry(ix, cx);
+                this.focus_handle.focus(window);
+                if event.down.click_count > 1 {
+                    this.open_selected(&menu::Confirm, window, cx);
+                }
+            }))
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                    this.deploy_context_menu(event.position, ix, window, cx);
+                    cx.stop_propagation();
+                }),
+            )
+            .into_any_element()
+    }
+
+    fn render_empty_state(&self) -> impl IntoElement {
+        let message: SharedString = if let Some(error) = self.error.as_ref() {
+            error.clone()
+        } else if self.pending_load.is_some() {
+            "Loading…".into()
+        } else {
+            "No commits match these filters".into()
+        };
+
+        v_flex()
+            .size_full()
+            .p_4()
+            .items_center()
+            .justify_center()
+            .child(
+                Label::new(message)
+                    .color(if self.error.is_some() {
+                        Color::Error
+                    } else {
+                        Color::Muted
+                    })
+                    .size(LabelSize::Small),
+            )
+    }
+}
+
+fn relative_timestamp(timestamp: i64) -> String {
+    let Ok(timestamp) = OffsetDateTime::from_unix_timestamp(timestamp) else {
+        return String::new();
+    };
+    let local = chrono::Local::now().offset().local_minus_utc();
+    time_format::format_localized_timestamp(
+        timestamp,
+        OffsetDateTime::now_utc(),
+        UtcOffset::from_whole_seconds(local).unwrap_or(UtcOffset::UTC),
+        time_format::TimestampFormat::Relative,
+    )
+}
+
+impl Render for CommitHistory {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("GitCommitHistory")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .key_context("GitCommitHistoryFilter")
+                    .on_action(cx.listener(Self::confirm_filter))
+                    .h(px(32.))
+                    .px_2()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .when_some(self.path.as_ref(), |this, path| {
+                        this.child(
+                            Label::new(path.to_string())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .child(div().flex_1().child(self.revision_editor.clone()))
+                    .child(div().w(rems(12.)).child(self.author_editor.clone()))
+                    .child(
+                        IconButton::new("refresh-commit-history", IconName::RotateCw)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Refresh"))
+                            .on_click(cx.listener(|this, _, _, cx| this.reload(cx))),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("commit-list")
+                    .key_context("GitCommitList")
+                    .track_focus(&self.focus_handle)
+                    .flex_1()
+                    .size_full()
+                    .overflow_hidden()
+                    .on_action(cx.listener(Self::select_next))
+                    .on_action(cx.listener(Self::select_previous))
+                    .on_action(cx.listener(Self::open_selected))
+                    .on_action(cx.listener(Self::cherry_pick))
+                    .on_action(cx.listener(Self::revert))
+                    .on_action(cx.listener(Self::rebase_from))
+                    .on_action(cx.listener(Self::tag))
+                    .on_scroll_wheel(cx.listener(|this, _, _, cx| {
+                        this.load_more_if_scrolled_to_end(cx);
+                    }))
+                    .map(|this| {
+                        if self.entries.is_empty() {
+                            this.child(self.render_empty_state())
+                        } else {
+                            this.child(
+                                uniform_list(
+                                    cx.entity().clone(),
+                                    "commits",
+                                    self.entries.len(),
+                                    |this, range, _window, cx| {
+                                        range.map(|ix| this.render_entry(ix, cx)).collect()
+                                    },
+                                )
+                                .size_full()
+                                .track_scroll(self.scroll_handle.clone()),
+                            )
+                        }
+                    }),
+            )
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(Corner::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
+    }
+}
+
+impl Focusable for CommitHistory {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<()> for CommitHistory {}
+
+impl Item for CommitHistory {
+    type Event = ();
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(ItemEvent)) {}
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        match self.path.as_ref().and_then(|path| path.file_name()) {
+            Some(file_name) => format!("History: {}", file_name.to_string_lossy()).into(),
+            None => format!("History: {}", self.repository.read(cx).display_name()).into(),
+        }
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let repository = self.repository.read(cx).display_name();
+        Some(match self.path.as_ref() {
+            Some(path) => format!("Commit history of {path} in {repository}").into(),
+            None => format!("Commit history of {repository}").into(),
+        })
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Commit History Opened")
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+}
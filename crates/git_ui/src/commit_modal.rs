@@ -123,6 +123,9 @@ impl CommitModal {
         let Some(git_panel) = workspace.panel::<GitPanel>(cx) else {
             return;
         };
+        if !git_panel.read(cx).supports_staging(cx) {
+            return;
+        }
 
         git_panel.update(cx, |git_panel, cx| {
             if let Some(force_mode) = force_mode {
@@ -6,7 +6,7 @@ use git::repository::CommitSummary;
 use git::{GitRemote, blame::ParsedCommitMessage};
 use gpui::{
     App, Asset, ClipboardItem, Element, Entity, MouseButton, ParentElement, Render, ScrollHandle,
-    StatefulInteractiveElement, WeakEntity, prelude::*,
+    StatefulInteractiveElement, Task, WeakEntity, prelude::*,
 };
 use markdown::{Markdown, MarkdownElement};
 use project::git_store::Repository;
@@ -16,6 +16,7 @@ use theme::ThemeSettings;
 use time::{OffsetDateTime, UtcOffset};
 use time_format::format_local_timestamp;
 use ui::{Avatar, Divider, IconButtonShape, prelude::*, tooltip_container};
+use util::ResultExt as _;
 use workspace::Workspace;
 
 #[derive(Clone, Debug)]
@@ -106,6 +107,9 @@ pub struct CommitTooltip {
     markdown: Entity<Markdown>,
     repository: Entity<Repository>,
     workspace: WeakEntity<Workspace>,
+    /// The tags that point at the commit.
+    tags: Vec<SharedString>,
+    _load_tags: Task<()>,
 }
 
 impl CommitTooltip {
@@ -157,12 +161,36 @@ impl CommitTooltip {
                 cx,
             )
         });
+        let tags = repository.update(cx, |repository, _| {
+            repository.tags(Some(commit.sha.to_string()))
+        });
+        let _load_tags = cx.spawn(async move |this, cx| {
+            let Some(tags) = tags
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|tags| tags)
+                .log_err()
+            else {
+                return;
+            };
+            let tags = tags.into_iter().map(|tag| tag.name).collect::<Vec<_>>();
+            if tags.is_empty() {
+                return;
+            }
+            this.update(cx, |this, cx| {
+                this.tags = tags;
+                cx.notify();
+            })
+            .ok();
+        });
         Self {
             commit,
             repository,
             workspace,
             scroll_handle: ScrollHandle::new(),
             markdown,
+            tags: Vec::new(),
+            _load_tags,
         }
     }
 }
@@ -208,6 +236,7 @@ impl Render for CommitTooltip {
             .as_ref()
             .and_then(|details| details.pull_request.clone());
 
+        let tags = self.tags.clone();
         let ui_font_size = ThemeSettings::get_global(cx).ui_font_size(cx);
         let message_max_height = window.line_height() * 12 + (ui_font_size / 0.4);
         let repo = self.repository.clone();
@@ -255,6 +284,20 @@ impl Render for CommitTooltip {
                                             .child(author_email),
                                     )
                                 })
+                                .children(tags.into_iter().map(|tag| {
+                                    h_flex()
+                                        .gap_0p5()
+                                        .child(
+                                            Icon::new(IconName::Hash)
+                                                .size(IconSize::XSmall)
+                                                .color(Color::Accent),
+                                        )
+                                        .child(
+                                            Label::new(tag)
+                                                .size(LabelSize::Small)
+                                                .color(Color::Accent),
+                                        )
+                                }))
                                 .border_b_1()
                                 .border_color(cx.theme().colors().border_variant),
                         )
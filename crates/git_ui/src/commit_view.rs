@@ -1,7 +1,8 @@
 use anyhow::{Context as _, Result};
 use buffer_diff::{BufferDiff, BufferDiffSnapshot};
 use editor::{Editor, EditorEvent, MultiBuffer};
-use git::repository::{CommitDetails, CommitDiff, CommitSummary, RepoPath};
+use futures::channel::oneshot;
+use git::repository::{CommitDetails, CommitDiff, CommitSummary, RepoPath, StashEntry};
 use gpui::{
     AnyElement, AnyView, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter,
     FocusHandle, Focusable, IntoElement, Render, WeakEntity, Window,
@@ -58,9 +59,32 @@ impl CommitView {
         let commit_diff = repo
             .update(cx, |repo, _| repo.load_commit_diff(commit.sha.to_string()))
             .ok();
-        let commit_details = repo
-            .update(cx, |repo, _| repo.show(commit.sha.to_string()))
+        Self::open_with_diff(commit.sha, commit_diff, repo, workspace, window, cx);
+    }
+
+    /// Opens the changes recorded in a stash entry, relative to the commit it was created on.
+    pub fn open_stash(
+        stash: StashEntry,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let commit_diff = repo
+            .update(cx, |repo, _| repo.stash_show(stash.sha.to_string()))
             .ok();
+        Self::open_with_diff(stash.sha, commit_diff, repo, workspace, window, cx);
+    }
+
+    fn open_with_diff(
+        sha: SharedString,
+        commit_diff: Option<oneshot::Receiver<Result<CommitDiff>>>,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let commit_details = repo.update(cx, |repo, _| repo.show(sha.to_string())).ok();
 
         window
             .spawn(cx, async move |cx| {
@@ -87,8 +111,7 @@ impl CommitView {
                         pane.update(cx, |pane, cx| {
                             let ix = pane.items().position(|item| {
                                 let commit_view = item.downcast::<CommitView>();
-                                commit_view
-                                    .map_or(false, |view| view.read(cx).commit.sha == commit.sha)
+                                commit_view.map_or(false, |view| view.read(cx).commit.sha == sha)
                             });
                             if let Some(ix) = ix {
                                 pane.activate_item(ix, true, true, window, cx);
@@ -0,0 +1,553 @@
+use crate::project_diff::build_diff_against_base_text;
+use anyhow::{Context as _, Result};
+use buffer_diff::BufferDiff;
+use collections::BTreeSet;
+use editor::{Editor, EditorEvent, MultiBuffer};
+use futures::StreamExt as _;
+use gpui::{
+    AnyElement, AnyView, App, AppContext as _, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, IntoElement, Render, Subscription, Task, Window,
+};
+use language::{
+    Anchor, Buffer, Capability, DiskState, LineEnding, OffsetRangeExt as _, Point, TextBuffer,
+};
+use multi_buffer::PathKey;
+use project::{Fs, Project, ProjectPath, WorktreeId};
+use std::{
+    any::{Any, TypeId},
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use ui::{Color, Icon, IconName, Label, LabelCommon as _, SharedString};
+use util::ResultExt as _;
+use workspace::{
+    Item, ItemHandle as _, ItemNavHistory, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, ItemEvent, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+/// Compares two files, or every file in two directories, outside of any git repository.
+///
+/// The right-hand side is opened as a regular project buffer, so it can be edited and saved
+/// in place. This is what `zed --diff` opens when Zed is configured as a `git difftool`.
+pub struct FileDiffView {
+    old_path: PathBuf,
+    new_path: PathBuf,
+    editor: Entity<Editor>,
+    multibuffer: Entity<MultiBuffer>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// A file that only exists on the left-hand side of a directory comparison.
+struct RemovedFile {
+    path: Arc<Path>,
+    worktree_id: WorktreeId,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ChangedFile {
+    path: PathBuf,
+    old_path: Option<PathBuf>,
+    new_path: Option<PathBuf>,
+}
+
+const FILE_NAMESPACE: u32 = 0;
+
+impl FileDiffView {
+    pub fn open(
+        old_path: PathBuf,
+        new_path: PathBuf,
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let workspace_handle = workspace.weak_handle();
+        let project = workspace.project().clone();
+        let fs = project.read(cx).fs().clone();
+        let language_registry = project.read(cx).languages().clone();
+
+        window.spawn(cx, async move |cx| {
+            let old_metadata = fs
+                .metadata(&old_path)
+                .await?
+                .with_context(|| format!("{old_path:?} does not exist"))?;
+            let new_metadata = fs
+                .metadata(&new_path)
+                .await?
+                .with_context(|| format!("{new_path:?} does not exist"))?;
+            anyhow::ensure!(
+                old_metadata.is_dir == new_metadata.is_dir,
+                "cannot compare a file with a directory"
+            );
+
+            let (worktree, _) = project
+                .update(cx, |project, cx| {
+                    project.find_or_create_worktree(&new_path, false, cx)
+                })?
+                .await?;
+            let worktree_id = worktree.read_with(cx, |worktree, _| worktree.id())?;
+
+            let changed_files = if new_metadata.is_dir {
+                changed_files(fs.as_ref(), &old_path, &new_path).await?
+            } else {
+                vec![ChangedFile {
+                    path: new_path.file_name().map(PathBuf::from).unwrap_or_default(),
+                    old_path: Some(old_path.clone()),
+                    new_path: Some(new_path.clone()),
+                }]
+            };
+
+            let mut files = Vec::new();
+            for file in changed_files {
+                let old_text = match &file.old_path {
+                    Some(path) => match fs.load(path).await {
+                        Ok(text) => Some(text),
+                        Err(error) => {
+                            log::warn!("skipping {path:?} in diff: {error}");
+                            continue;
+                        }
+                    },
+                    None => None,
+                };
+                let buffer = match &file.new_path {
+                    Some(path) => {
+                        let buffer = project
+                            .update(cx, |project, cx| project.open_local_buffer(path, cx))?
+                            .await;
+                        match buffer {
+                            Ok(buffer) => buffer,
+                            Err(error) => {
+                                log::warn!("skipping {path:?} in diff: {error}");
+                                continue;
+                            }
+                        }
+                    }
+                    None => {
+                        let file = Arc::new(RemovedFile {
+                            path: file.path.as_path().into(),
+                            worktree_id,
+                        });
+                        let language = language_registry
+                            .language_for_file_path(&file.path)
+                            .await
+                            .ok();
+                        cx.new(|cx| {
+                            let buffer = TextBuffer::new_normalized(
+                                0,
+                                cx.entity_id().as_non_zero_u64().into(),
+                                LineEnding::default(),
+                                "".into(),
+                            );
+                            let mut buffer =
+                                Buffer::build(buffer, Some(file), Capability::ReadOnly);
+                            buffer.set_language(language, cx);
+                            buffer
+                        })?
+                    }
+                };
+                let (diff, subscription) = build_diff_against_base_text(
+                    old_text,
+                    buffer.clone(),
+                    language_registry.clone(),
+                    cx,
+                )
+                .await?;
+                files.push((buffer, diff, subscription));
+            }
+
+            workspace_handle.update_in(cx, |workspace, window, cx| {
+                let is_dir = new_metadata.is_dir;
+                let diff_view =
+                    cx.new(|cx| Self::new(old_path, new_path, is_dir, files, &project, window, cx));
+                workspace.add_item_to_active_pane(
+                    Box::new(diff_view.clone()),
+                    None,
+                    true,
+                    window,
+                    cx,
+                );
+                diff_view
+            })
+        })
+    }
+
+    fn new(
+        old_path: PathBuf,
+        new_path: PathBuf,
+        is_dir: bool,
+        files: Vec<(Entity<Buffer>, Entity<BufferDiff>, Subscription)>,
+        project: &Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let multibuffer = cx.new(|_| MultiBuffer::new(Capability::ReadWrite));
+        let editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx);
+            editor.disable_inline_diagnostics();
+            editor.set_expand_all_diff_hunks(cx);
+            editor
+        });
+
+        let mut subscriptions = Vec::new();
+        multibuffer.update(cx, |multibuffer, cx| {
+            for (buffer, diff, subscription) in files {
+                let snapshot = buffer.read(cx).snapshot();
+                // A single file is shown in full, like an editor; a directory only shows the
+                // changed regions of each file, like the project diff.
+                let (ranges, context_line_count) = if is_dir {
+                    let diff_hunk_ranges = diff
+                        .read(cx)
+                        .hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &snapshot, cx)
+                        .map(|diff_hunk| diff_hunk.buffer_range.to_point(&snapshot))
+                        .collect::<Vec<_>>();
+                    (diff_hunk_ranges, editor::DEFAULT_MULTIBUFFER_CONTEXT)
+                } else {
+                    (vec![Point::zero()..snapshot.max_point()], 0)
+                };
+                let Some(path) = snapshot.file().map(|file| file.path().clone()) else {
+                    continue;
+                };
+                multibuffer.set_excerpts_for_path(
+                    PathKey::namespaced(FILE_NAMESPACE, path),
+                    buffer,
+                    ranges,
+                    context_line_count,
+                    cx,
+                );
+                multibuffer.add_diff(diff, cx);
+                subscriptions.push(subscription);
+            }
+        });
+
+        Self {
+            old_path,
+            new_path,
+            editor,
+            multibuffer,
+            _subscriptions: subscriptions,
+        }
+    }
+}
+
+/// Lists the files that differ between two directories, ordered by their relative path.
+async fn changed_files(fs: &dyn Fs, old_root: &Path, new_root: &Path) -> Result<Vec<ChangedFile>> {
+    let old_files = files_in_directory(fs, old_root).await?;
+    let new_files = files_in_directory(fs, new_root).await?;
+
+    let mut changed_files = Vec::new();
+    for path in old_files.union(&new_files) {
+        let old_path = old_files.contains(path).then(|| old_root.join(path));
+        let new_path = new_files.contains(path).then(|| new_root.join(path));
+        if let Some((old_path, new_path)) = old_path.as_ref().zip(new_path.as_ref()) {
+            let old_content = fs.load_bytes(old_path).await?;
+            let new_content = fs.load_bytes(new_path).await?;
+            if old_content == new_content {
+                continue;
+            }
+        }
+        changed_files.push(ChangedFile {
+            path: path.clone(),
+            old_path,
+            new_path,
+        });
+    }
+    Ok(changed_files)
+}
+
+async fn files_in_directory(fs: &dyn Fs, root: &Path) -> Result<BTreeSet<PathBuf>> {
+    let mut files = BTreeSet::default();
+    let mut directories = vec![root.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        let mut children = fs.read_dir(&directory).await?;
+        while let Some(child) = children.next().await {
+            let child = child?;
+            if child.file_name() == Some(OsStr::new(".git")) {
+                continue;
+            }
+            let Some(metadata) = fs.metadata(&child).await.log_err().flatten() else {
+                continue;
+            };
+            if metadata.is_dir {
+                // Symlinked directories aren't followed, as they could lead back to an ancestor.
+                if !metadata.is_symlink {
+                    directories.push(child);
+                }
+            } else if let Ok(relative_path) = child.strip_prefix(root) {
+                files.insert(relative_path.to_path_buf());
+            }
+        }
+    }
+    Ok(files)
+}
+
+impl language::File for RemovedFile {
+    fn as_local(&self) -> Option<&dyn language::LocalFile> {
+        None
+    }
+
+    fn disk_state(&self) -> DiskState {
+        DiskState::Deleted
+    }
+
+    fn path(&self) -> &Arc<Path> {
+        &self.path
+    }
+
+    fn full_path(&self, _: &App) -> PathBuf {
+        self.path.to_path_buf()
+    }
+
+    fn file_name<'a>(&'a self, _: &'a App) -> &'a OsStr {
+        self.path.file_name().unwrap_or_default()
+    }
+
+    fn worktree_id(&self, _: &App) -> WorktreeId {
+        self.worktree_id
+    }
+
+    fn to_proto(&self, _: &App) -> language::proto::File {
+        unimplemented!()
+    }
+
+    fn is_private(&self) -> bool {
+        false
+    }
+}
+
+impl EventEmitter<EditorEvent> for FileDiffView {}
+
+impl Focusable for FileDiffView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for FileDiffView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Diff).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        let file_name = |path: &Path| {
+            path.file_name()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .into_owned()
+        };
+        format!(
+            "{} ↔ {}",
+            file_name(&self.old_path),
+            file_name(&self.new_path)
+        )
+        .into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(format!("{} ↔ {}", self.old_path.display(), self.new_path.display()).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("File Diff View Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.multibuffer.read(cx).is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.multibuffer.read(cx).has_conflict(cx)
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        format: bool,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.save(format, project, window, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        _: Entity<Project>,
+        _: ProjectPath,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        unreachable!()
+    }
+
+    fn reload(
+        &mut self,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.reload(project, window, cx)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+}
+
+impl Render for FileDiffView {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        self.editor.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_changed_files_in_directories(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/old"),
+            json!({
+                ".git": { "HEAD": "ref: refs/heads/main" },
+                "same.txt": "same\n",
+                "changed.txt": "one\n",
+                "removed.txt": "gone\n",
+                "src": { "lib.rs": "fn a() {}\n" },
+            }),
+        )
+        .await;
+        fs.insert_tree(
+            path!("/new"),
+            json!({
+                "same.txt": "same\n",
+                "changed.txt": "two\n",
+                "added.txt": "new\n",
+                "src": { "lib.rs": "fn b() {}\n" },
+            }),
+        )
+        .await;
+        fs.insert_symlink(path!("/new/src/parent"), path!("/new").into())
+            .await;
+
+        let changed = changed_files(fs.as_ref(), path!("/old").as_ref(), path!("/new").as_ref())
+            .await
+            .unwrap();
+        let summary = changed
+            .iter()
+            .map(|file| {
+                (
+                    file.path.to_string_lossy().replace('\\', "/"),
+                    file.old_path.is_some(),
+                    file.new_path.is_some(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("added.txt".to_string(), false, true),
+                ("changed.txt".to_string(), true, true),
+                ("removed.txt".to_string(), true, false),
+                ("src/lib.rs".to_string(), true, true),
+            ]
+        );
+    }
+}
@@ -3,6 +3,7 @@ use crate::commit_modal::CommitModal;
 use crate::commit_tooltip::CommitTooltip;
 use crate::commit_view::CommitView;
 use crate::git_panel_settings::StatusStyle;
+use crate::merge_view::MergeView;
 use crate::project_diff::{self, Diff, ProjectDiff};
 use crate::remote_output::{self, RemoteAction, SuccessMessage};
 use crate::{branch_picker, picker_prompt, render_remote_button};
@@ -18,14 +19,22 @@ use editor::{
     scroll::ScrollbarAutoHide,
 };
 use futures::StreamExt as _;
+use futures::channel::oneshot;
 use git::blame::ParsedCommitMessage;
 use git::repository::{
     Branch, CommitDetails, CommitOptions, CommitSummary, DiffType, FetchOptions, PushOptions,
-    Remote, RemoteCommandOutput, ResetMode, Upstream, UpstreamTracking, UpstreamTrackingStatus,
+    Remote, RemoteCommandOutput, ResetMode, StashEntry, Upstream, UpstreamTracking,
+    UpstreamTrackingStatus,
 };
 use git::status::StageStatus;
-use git::{Amend, ToggleStaged, repository::RepoPath, status::FileStatus};
-use git::{ExpandCommitEditor, RestoreTrackedFiles, StageAll, TrashUntrackedFiles, UnstageAll};
+use git::{
+    AbortOperation, Amend, ContinueOperation, ToggleStaged, repository::RepoPath,
+    status::FileStatus,
+};
+use git::{
+    ExpandCommitEditor, RestoreTrackedFiles, StageAll, StashAll, StashPop, TrashUntrackedFiles,
+    UnstageAll, ViewHistory,
+};
 use gpui::{
     Action, Animation, AnimationExt as _, AsyncApp, AsyncWindowContext, Axis, ClickEvent, Corner,
     DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, KeyContext,
@@ -110,6 +119,7 @@ struct GitMenuState {
     has_staged_changes: bool,
     has_unstaged_changes: bool,
     has_new_changes: bool,
+    has_stash_entries: bool,
 }
 
 fn git_panel_context_menu(
@@ -155,6 +165,23 @@ fn git_panel_context_menu(
                     menu.disabled_action("Trash Untracked Files", TrashUntrackedFiles.boxed_clone())
                 }
             })
+            .separator()
+            .map(|menu| {
+                if state.has_tracked_changes || state.has_new_changes {
+                    menu.action("Stash All", StashAll.boxed_clone())
+                } else {
+                    menu.disabled_action("Stash All", StashAll.boxed_clone())
+                }
+            })
+            .map(|menu| {
+                if state.has_stash_entries {
+                    menu.action("Pop Stash", StashPop.boxed_clone())
+                } else {
+                    menu.disabled_action("Pop Stash", StashPop.boxed_clone())
+                }
+            })
+            .separator()
+            .action("View History", ViewHistory.boxed_clone())
     })
 }
 
@@ -186,6 +213,7 @@ enum Section {
     Conflict,
     Tracked,
     New,
+    Stash,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -203,6 +231,7 @@ impl GitHeaderEntry {
             }
             Section::Tracked => !status.is_created(),
             Section::New => status.is_created(),
+            Section::Stash => false,
         }
     }
     pub fn title(&self) -> &'static str {
@@ -210,6 +239,7 @@ impl GitHeaderEntry {
             Section::Conflict => "Conflicts",
             Section::Tracked => "Tracked",
             Section::New => "Untracked",
+            Section::Stash => "Stashes",
         }
     }
 }
@@ -218,6 +248,7 @@ impl GitHeaderEntry {
 enum GitListEntry {
     GitStatusEntry(GitStatusEntry),
     Header(GitHeaderEntry),
+    Stash(StashEntry),
 }
 
 impl GitListEntry {
@@ -331,6 +362,8 @@ pub struct GitPanel {
     add_coauthors: bool,
     generate_commit_message_task: Option<Task<Option<()>>>,
     entries: Vec<GitListEntry>,
+    stash_entries: Vec<StashEntry>,
+    load_stash_entries_task: Task<Option<()>>,
     single_staged_entry: Option<GitStatusEntry>,
     single_tracked_entry: Option<GitStatusEntry>,
     focus_handle: FocusHandle,
@@ -499,6 +532,8 @@ impl GitPanel {
                 add_coauthors: true,
                 generate_commit_message_task: None,
                 entries: Vec::new(),
+                stash_entries: Vec::new(),
+                load_stash_entries_task: Task::ready(None),
                 focus_handle: cx.focus_handle(),
                 fs,
                 new_count: 0,
@@ -864,6 +899,11 @@ impl GitPanel {
     }
 
     fn open_diff(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(GitListEntry::Stash(stash)) = self.get_selected_entry() {
+            self.open_stash(stash.clone(), window, cx);
+            return;
+        }
+
         maybe!({
             let entry = self.entries.get(self.selected_entry?)?.status_entry()?;
             let workspace = self.workspace.upgrade()?;
@@ -1251,6 +1291,183 @@ impl GitPanel {
         self.change_file_stage(false, entries, cx);
     }
 
+    pub fn stash_all(&mut self, _: &StashAll, window: &mut Window, cx: &mut Context<Self>) {
+        self.stash_paths(Vec::new(), window, cx);
+    }
+
+    pub fn stash_pop(&mut self, _: &StashPop, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(stash) = self.stash_entries.first().cloned() {
+            self.apply_stash(stash, true, window, cx);
+        }
+    }
+
+    pub fn continue_operation(
+        &mut self,
+        _: &ContinueOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(operation) = active_repository.read(cx).merge.in_progress_operation else {
+            return;
+        };
+        let result = active_repository.update(cx, |repo, _| repo.continue_operation());
+        cx.spawn_in(window, async move |this, cx| {
+            let result = maybe!(async { result.await? }).await;
+            this.update(cx, |this, cx| {
+                if let Err(e) = result {
+                    this.show_error_toast(format!("{} --continue", operation.command()), e, cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    pub fn abort_operation(
+        &mut self,
+        _: &AbortOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(operation) = active_repository.read(cx).merge.in_progress_operation else {
+            return;
+        };
+        let prompt = window.prompt(
+            PromptLevel::Warning,
+            &format!("Abort the {}?", operation.display_name().to_lowercase()),
+            Some("Any conflict resolutions made so far will be lost."),
+            &["Abort", "Cancel"],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            if prompt.await != Ok(0) {
+                return;
+            }
+            let Ok(result) = active_repository.update(cx, |repo, _| repo.abort_operation()) else {
+                return;
+            };
+            let result = maybe!(async { result.await? }).await;
+            this.update(cx, |this, cx| {
+                if let Err(e) = result {
+                    this.show_error_toast(format!("{} --abort", operation.command()), e, cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn stash_paths(&mut self, paths: Vec<RepoPath>, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_stash_operation("stash", |repo| repo.stash_push(None, paths), window, cx);
+    }
+
+    fn open_merge_tool(
+        &mut self,
+        repo_path: RepoPath,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repository) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        MergeView::open(repository, repo_path, workspace.read(cx), window, cx)
+            .detach_and_prompt_err("Failed to open merge tool", window, cx, |_, _, _| None);
+    }
+
+    fn apply_stash(
+        &mut self,
+        stash: StashEntry,
+        pop: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let sha = stash.sha.to_string();
+        if pop {
+            self.run_stash_operation("stash pop", |repo| repo.stash_pop(sha), window, cx);
+        } else {
+            self.run_stash_operation("stash apply", |repo| repo.stash_apply(sha), window, cx);
+        }
+    }
+
+    fn drop_stash(&mut self, stash: StashEntry, window: &mut Window, cx: &mut Context<Self>) {
+        let prompt = window.prompt(
+            PromptLevel::Warning,
+            &format!("Drop stash@{{{}}}?", stash.index),
+            Some("The stashed changes will be lost."),
+            &["Drop", "Cancel"],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            if prompt.await != Ok(0) {
+                return;
+            }
+            this.update_in(cx, |this, window, cx| {
+                let sha = stash.sha.to_string();
+                this.run_stash_operation("stash drop", |repo| repo.stash_drop(sha), window, cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn run_stash_operation(
+        &mut self,
+        action: &'static str,
+        operation: impl FnOnce(&mut Repository) -> oneshot::Receiver<anyhow::Result<()>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        let result = active_repository.update(cx, |repo, _| operation(repo));
+        cx.spawn_in(window, async move |this, cx| {
+            let result = maybe!(async { result.await? }).await;
+            this.update(cx, |this, cx| {
+                if let Err(e) = result {
+                    this.show_error_toast(action, e, cx);
+                }
+                this.load_stash_entries(cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn load_stash_entries(&mut self, cx: &mut Context<Self>) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            self.stash_entries.clear();
+            return;
+        };
+        let stash_list = active_repository.update(cx, |repo, _| repo.stash_list());
+        self.load_stash_entries_task = cx.spawn(async move |this, cx| {
+            let stash_entries = stash_list.await.ok()?.log_err()?;
+            this.update(cx, |this, cx| {
+                if this.stash_entries != stash_entries {
+                    this.stash_entries = stash_entries;
+                    this.update_visible_entries(cx);
+                }
+            })
+            .ok()
+        });
+    }
+
+    fn open_stash(&mut self, stash: StashEntry, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.as_ref() else {
+            return;
+        };
+        CommitView::open_stash(stash, repo.downgrade(), self.workspace.clone(), window, cx);
+    }
+
     fn toggle_staged_for_entry(
         &mut self,
         entry: &GitListEntry,
@@ -1268,6 +1485,11 @@ impl GitPanel {
                     (true, vec![status_entry.clone()])
                 }
             }
+            // Stash entries aren't staged, so neither they nor their header toggle anything.
+            GitListEntry::Stash(_)
+            | GitListEntry::Header(GitHeaderEntry {
+                header: Section::Stash,
+            }) => return,
             GitListEntry::Header(section) => {
                 let goal_staged_state = !self.header_state(section.header).selected();
                 let repository = active_repository.read(cx);
@@ -2175,6 +2397,66 @@ impl GitPanel {
         .detach_and_log_err(cx);
     }
 
+    pub(crate) fn push_tag(
+        &mut self,
+        tag: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        telemetry::event!("Git Tag Pushed");
+        let remote = self.get_remote(true, window, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let remote = match remote.await {
+                Ok(Some(remote)) => remote,
+                Ok(None) => {
+                    return Ok(());
+                }
+                Err(e) => {
+                    log::error!("Failed to get current remote: {}", e);
+                    this.update(cx, |this, cx| this.show_error_toast("push", e, cx))
+                        .ok();
+                    return Ok(());
+                }
+            };
+
+            let askpass_delegate = this.update_in(cx, |this, window, cx| {
+                this.askpass_delegate(format!("git push {}", remote.name), window, cx)
+            })?;
+
+            // Pushing the tag's full ref name pushes it to the same name on the remote.
+            let push = repo.update(cx, |repo, cx| {
+                repo.push(
+                    format!("refs/tags/{tag}").into(),
+                    remote.name.clone(),
+                    None,
+                    askpass_delegate,
+                    cx,
+                )
+            })?;
+
+            let remote_output = push.await?;
+
+            let action = RemoteAction::PushTag(tag, remote);
+            this.update(cx, |this, cx| match remote_output {
+                Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                Err(e) => {
+                    log::error!("Error while pushing tag {:?}", e);
+                    this.show_error_toast(action.name(), e, cx)
+                }
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn askpass_delegate(
         &self,
         operation: impl Into<SharedString>,
@@ -2375,6 +2657,7 @@ impl GitPanel {
                         }
                         git_panel.update_visible_entries(cx);
                         git_panel.update_scrollbar_properties(window, cx);
+                        git_panel.load_stash_entries(cx);
                     })
                     .ok();
             }
@@ -2574,13 +2857,20 @@ impl GitPanel {
             self.entries
                 .extend(new_entries.into_iter().map(GitListEntry::GitStatusEntry));
         }
+        if !self.stash_entries.is_empty() {
+            self.entries.push(GitListEntry::Header(GitHeaderEntry {
+                header: Section::Stash,
+            }));
+            self.entries
+                .extend(self.stash_entries.iter().cloned().map(GitListEntry::Stash));
+        }
 
         if let Some((repo_path, _)) = max_width_item {
             self.max_width_item_index = self.entries.iter().position(|entry| match entry {
                 GitListEntry::GitStatusEntry(git_status_entry) => {
                     git_status_entry.repo_path == repo_path
                 }
-                GitListEntry::Header(_) => false,
+                GitListEntry::Header(_) | GitListEntry::Stash(_) => false,
             });
         }
 
@@ -2603,6 +2893,7 @@ impl GitPanel {
             Section::New => (self.new_staged_count, self.new_count),
             Section::Tracked => (self.tracked_staged_count, self.tracked_count),
             Section::Conflict => (self.conflicted_staged_count, self.conflicted_count),
+            Section::Stash => (0, 0),
         };
         if staged_count == 0 {
             ToggleState::Unselected
@@ -2820,6 +3111,7 @@ impl GitPanel {
         let has_staged_changes = self.has_staged_changes();
         let has_unstaged_changes = self.has_unstaged_changes();
         let has_new_changes = self.new_count > 0;
+        let has_stash_entries = !self.stash_entries.is_empty();
 
         PopoverMenu::new(id.into())
             .trigger(
@@ -2835,6 +3127,7 @@ impl GitPanel {
                         has_staged_changes,
                         has_unstaged_changes,
                         has_new_changes,
+                        has_stash_entries,
                     },
                     window,
                     cx,
@@ -3069,7 +3362,7 @@ impl GitPanel {
                             action.as_ref(),
                             &self.focus_handle,
                         ))
-                        .disabled(self.entry_count == 0)
+                        .disabled(self.entry_count == 0 || !self.supports_staging(cx))
                         .on_click(move |_, _, cx| {
                             let action = action.boxed_clone();
                             cx.defer(move |cx| {
@@ -3139,93 +3432,97 @@ impl GitPanel {
         });
         let has_previous_commit = head_commit.is_some();
 
-        let footer = v_flex()
-            .child(PanelRepoFooter::new(
-                display_name,
-                branch,
-                head_commit,
-                Some(git_panel.clone()),
-            ))
-            .child(
-                panel_editor_container(window, cx)
-                    .id("commit-editor-container")
-                    .relative()
-                    .w_full()
-                    .h(max_height + footer_size)
-                    .border_t_1()
-                    .border_color(cx.theme().colors().border_variant)
-                    .cursor_text()
-                    .on_click(cx.listener(move |this, _: &ClickEvent, window, cx| {
-                        window.focus(&this.commit_editor.focus_handle(cx));
-                    }))
-                    .child(
-                        h_flex()
-                            .id("commit-footer")
-                            .border_t_1()
-                            .when(editor_is_long, |el| {
-                                el.border_color(cx.theme().colors().border_variant)
-                            })
-                            .absolute()
-                            .bottom_0()
-                            .left_0()
-                            .w_full()
-                            .px_2()
-                            .h(footer_size)
-                            .flex_none()
-                            .justify_between()
-                            .child(
-                                self.render_generate_commit_message_button(cx)
-                                    .unwrap_or_else(|| div().into_any_element()),
-                            )
-                            .child(
-                                h_flex()
-                                    .gap_0p5()
-                                    .children(enable_coauthors)
-                                    .child(self.render_commit_button(has_previous_commit, cx)),
-                            ),
-                    )
-                    .child(
-                        div()
-                            .pr_2p5()
-                            .on_action(|&editor::actions::MoveUp, _, cx| {
-                                cx.stop_propagation();
-                            })
-                            .on_action(|&editor::actions::MoveDown, _, cx| {
-                                cx.stop_propagation();
-                            })
-                            .child(EditorElement::new(&self.commit_editor, panel_editor_style)),
-                    )
-                    .child(
-                        h_flex()
-                            .absolute()
-                            .top_2()
-                            .right_2()
-                            .opacity(0.5)
-                            .hover(|this| this.opacity(1.0))
-                            .child(
-                                panel_icon_button("expand-commit-editor", IconName::Maximize)
-                                    .icon_size(IconSize::Small)
-                                    .size(ui::ButtonSize::Default)
-                                    .tooltip(move |window, cx| {
-                                        Tooltip::for_action_in(
-                                            "Open Commit Modal",
-                                            &git::ExpandCommitEditor,
-                                            &expand_tooltip_focus_handle,
-                                            window,
+        let footer = v_flex().child(PanelRepoFooter::new(
+            display_name,
+            branch,
+            head_commit,
+            Some(git_panel.clone()),
+        ));
+        // Pure jj workspaces have nothing to commit from the panel.
+        if !self.supports_staging(cx) {
+            return Some(footer);
+        }
+
+        let footer = footer.child(
+            panel_editor_container(window, cx)
+                .id("commit-editor-container")
+                .relative()
+                .w_full()
+                .h(max_height + footer_size)
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .cursor_text()
+                .on_click(cx.listener(move |this, _: &ClickEvent, window, cx| {
+                    window.focus(&this.commit_editor.focus_handle(cx));
+                }))
+                .child(
+                    h_flex()
+                        .id("commit-footer")
+                        .border_t_1()
+                        .when(editor_is_long, |el| {
+                            el.border_color(cx.theme().colors().border_variant)
+                        })
+                        .absolute()
+                        .bottom_0()
+                        .left_0()
+                        .w_full()
+                        .px_2()
+                        .h(footer_size)
+                        .flex_none()
+                        .justify_between()
+                        .child(
+                            self.render_generate_commit_message_button(cx)
+                                .unwrap_or_else(|| div().into_any_element()),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_0p5()
+                                .children(enable_coauthors)
+                                .child(self.render_commit_button(has_previous_commit, cx)),
+                        ),
+                )
+                .child(
+                    div()
+                        .pr_2p5()
+                        .on_action(|&editor::actions::MoveUp, _, cx| {
+                            cx.stop_propagation();
+                        })
+                        .on_action(|&editor::actions::MoveDown, _, cx| {
+                            cx.stop_propagation();
+                        })
+                        .child(EditorElement::new(&self.commit_editor, panel_editor_style)),
+                )
+                .child(
+                    h_flex()
+                        .absolute()
+                        .top_2()
+                        .right_2()
+                        .opacity(0.5)
+                        .hover(|this| this.opacity(1.0))
+                        .child(
+                            panel_icon_button("expand-commit-editor", IconName::Maximize)
+                                .icon_size(IconSize::Small)
+                                .size(ui::ButtonSize::Default)
+                                .tooltip(move |window, cx| {
+                                    Tooltip::for_action_in(
+                                        "Open Commit Modal",
+                                        &git::ExpandCommitEditor,
+                                        &expand_tooltip_focus_handle,
+                                        window,
+                                        cx,
+                                    )
+                                })
+                                .on_click(cx.listener({
+                                    move |_, _, window, cx| {
+                                        window.dispatch_action(
+                                            git::ExpandCommitEditor.boxed_clone(),
                                             cx,
                                         )
-                                    })
-                                    .on_click(cx.listener({
-                                        move |_, _, window, cx| {
-                                            window.dispatch_action(
-                                                git::ExpandCommitEditor.boxed_clone(),
-                                                cx,
-                                            )
-                                        }
-                                    })),
-                            ),
-                    ),
-            );
+                                    }
+                                })),
+                        ),
+                ),
+        );
 
         Some(footer)
     }
@@ -3392,6 +3689,74 @@ impl GitPanel {
             })
     }
 
+    fn render_in_progress_operation(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let repository = self.active_repository.as_ref()?.read(cx);
+        let operation = repository.merge.in_progress_operation?;
+        let has_conflicts = repository
+            .cached_status()
+            .any(|entry| entry.status.is_conflicted());
+        let has_write_access = self.has_write_access(cx);
+
+        Some(
+            h_flex()
+                .py_1()
+                .px(px(8.))
+                .gap_1p5()
+                .border_b_1()
+                .border_color(cx.theme().colors().border)
+                .child(
+                    Icon::new(IconName::Warning)
+                        .size(IconSize::Small)
+                        .color(Color::Warning),
+                )
+                .child(
+                    div().flex_1().min_w_0().child(
+                        Label::new(if has_conflicts {
+                            format!("{} stopped on conflicts", operation.display_name())
+                        } else {
+                            format!("{} in progress", operation.display_name())
+                        })
+                        .size(LabelSize::Small)
+                        .truncate(),
+                    ),
+                )
+                .child(
+                    panel_button("Abort")
+                        .disabled(!has_write_access)
+                        .tooltip(move |window, cx| {
+                            Tooltip::for_action(
+                                format!("git {} --abort", operation.command()),
+                                &AbortOperation,
+                                window,
+                                cx,
+                            )
+                        })
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.abort_operation(&AbortOperation, window, cx)
+                        })),
+                )
+                .child(
+                    panel_filled_button("Continue")
+                        .disabled(!has_write_access || has_conflicts)
+                        .tooltip(move |window, cx| {
+                            if has_conflicts {
+                                Tooltip::simple("Resolve all conflicts first", cx)
+                            } else {
+                                Tooltip::for_action(
+                                    format!("git {} --continue", operation.command()),
+                                    &ContinueOperation,
+                                    window,
+                                    cx,
+                                )
+                            }
+                        })
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.continue_operation(&ContinueOperation, window, cx)
+                        })),
+                ),
+        )
+    }
+
     fn render_pending_amend(&self, cx: &mut Context<Self>) -> impl IntoElement {
         div()
             .py_2()
@@ -3645,7 +4010,7 @@ impl GitPanel {
         let entry_staging = self.entry_staging(entry.status_entry()?);
 
         let checkbox = Checkbox::new("stage-file", entry_staging.as_bool().into())
-            .disabled(!self.has_write_access(cx))
+            .disabled(!self.has_write_access(cx) || !self.supports_staging(cx))
             .fill()
             .elevation(ElevationIndex::Surface)
             .on_click({
@@ -3734,6 +4099,15 @@ impl GitPanel {
                                                 cx,
                                             ));
                                         }
+                                        Some(GitListEntry::Stash(stash)) => {
+                                            items.push(this.render_stash_entry(
+                                                ix,
+                                                stash,
+                                                has_write_access,
+                                                window,
+                                                cx,
+                                            ));
+                                        }
                                         None => {}
                                     }
                                 }
@@ -3889,6 +4263,10 @@ impl GitPanel {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if let Some(GitListEntry::Stash(stash)) = self.entries.get(ix) {
+            self.deploy_stash_context_menu(position, ix, stash.clone(), window, cx);
+            return;
+        }
         let Some(entry) = self.entries.get(ix).and_then(|e| e.status_entry()) else {
             return;
         };
@@ -3902,6 +4280,7 @@ impl GitPanel {
         } else {
             "Restore File"
         };
+        let this = cx.weak_entity();
         let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
             context_menu
                 .context(self.focus_handle.clone())
@@ -3910,6 +4289,79 @@ impl GitPanel {
                 .separator()
                 .action("Open Diff", Confirm.boxed_clone())
                 .action("Open File", SecondaryConfirm.boxed_clone())
+                .when(entry.status.is_conflicted(), |context_menu| {
+                    let this = this.clone();
+                    let repo_path = entry.repo_path.clone();
+                    context_menu.entry("Open Merge Tool", None, move |window, cx| {
+                        this.update(cx, |this, cx| {
+                            this.open_merge_tool(repo_path.clone(), window, cx)
+                        })
+                        .ok();
+                    })
+                })
+                .separator()
+                .entry("Stash File", None, {
+                    let this = this.clone();
+                    let repo_path = entry.repo_path.clone();
+                    move |window, cx| {
+                        this.update(cx, |this, cx| {
+                            this.stash_paths(vec![repo_path.clone()], window, cx)
+                        })
+                        .ok();
+                    }
+                })
+        });
+        self.selected_entry = Some(ix);
+        self.set_context_menu(context_menu, position, window, cx);
+    }
+
+    fn deploy_stash_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        ix: usize,
+        stash: StashEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let this = cx.weak_entity();
+        let has_write_access = self.has_write_access(cx);
+        let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
+            context_menu
+                .context(self.focus_handle.clone())
+                .action("View Stash", Confirm.boxed_clone())
+                .when(has_write_access, |menu| {
+                    menu.separator()
+                        .entry("Apply Stash", None, {
+                            let this = this.clone();
+                            let stash = stash.clone();
+                            move |window, cx| {
+                                this.update(cx, |this, cx| {
+                                    this.apply_stash(stash.clone(), false, window, cx)
+                                })
+                                .ok();
+                            }
+                        })
+                        .entry("Pop Stash", None, {
+                            let this = this.clone();
+                            let stash = stash.clone();
+                            move |window, cx| {
+                                this.update(cx, |this, cx| {
+                                    this.apply_stash(stash.clone(), true, window, cx)
+                                })
+                                .ok();
+                            }
+                        })
+                        .entry("Drop Stash", None, {
+                            let this = this.clone();
+                            let stash = stash.clone();
+                            move |window, cx| {
+                                this.update(cx, |this, cx| {
+                                    this.drop_stash(stash.clone(), window, cx)
+                                })
+                                .ok();
+                            }
+                        })
+                })
         });
         self.selected_entry = Some(ix);
         self.set_context_menu(context_menu, position, window, cx);
@@ -3928,6 +4380,7 @@ impl GitPanel {
                 has_staged_changes: self.has_staged_changes(),
                 has_unstaged_changes: self.has_unstaged_changes(),
                 has_new_changes: self.new_count > 0,
+                has_stash_entries: !self.stash_entries.is_empty(),
             },
             window,
             cx,
@@ -3968,6 +4421,7 @@ impl GitPanel {
         cx: &Context<Self>,
     ) -> AnyElement {
         let display_name = entry.display_name();
+        let can_stage = has_write_access && self.supports_staging(cx);
 
         let selected = self.selected_entry == Some(ix);
         let marked = self.marked_entries.contains(&ix);
@@ -4107,13 +4561,13 @@ impl GitPanel {
                     .cursor_pointer()
                     .child(
                         Checkbox::new(checkbox_id, is_staged)
-                            .disabled(!has_write_access)
+                            .disabled(!can_stage)
                             .fill()
                             .elevation(ElevationIndex::Surface)
                             .on_click({
                                 let entry = entry.clone();
                                 cx.listener(move |this, _, window, cx| {
-                                    if !has_write_access {
+                                    if !can_stage {
                                         return;
                                     }
                                     this.toggle_staged_for_entry(
@@ -4177,10 +4631,83 @@ impl GitPanel {
             .into_any_element()
     }
 
+    fn render_stash_entry(
+        &self,
+        ix: usize,
+        stash: &StashEntry,
+        has_write_access: bool,
+        window: &Window,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let selected = self.selected_entry == Some(ix);
+        let id: ElementId = ElementId::Name(format!("stash_{}", stash.index).into());
+
+        h_flex()
+            .id(id)
+            .h(self.list_item_height())
+            .w_full()
+            .items_center()
+            .border_1()
+            .when(selected && self.focus_handle.is_focused(window), |el| {
+                el.border_color(cx.theme().colors().border_focused)
+            })
+            .px(rems(0.75)) // ~12px
+            .overflow_hidden()
+            .flex_none()
+            .gap_1p5()
+            .when(selected, |el| el.bg(cx.theme().status().info.alpha(0.08)))
+            .hover(|el| el.bg(cx.theme().colors().ghost_element_hover))
+            .on_click(cx.listener(move |this, _: &ClickEvent, window, cx| {
+                this.selected_entry = Some(ix);
+                this.open_diff(&Default::default(), window, cx);
+                this.focus_handle.focus(window);
+                cx.notify();
+            }))
+            .when(has_write_access, |el| {
+                el.on_mouse_down(
+                    MouseButton::Right,
+                    cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                        this.deploy_entry_context_menu(event.position, ix, window, cx);
+                        cx.stop_propagation();
+                    }),
+                )
+            })
+            .child(
+                Icon::new(IconName::Library)
+                    .size(IconSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                h_flex()
+                    .items_center()
+                    .flex_1()
+                    .gap_1()
+                    .child(self.entry_label(stash.message.clone(), Color::Default))
+                    .when_some(stash.branch.clone(), |this, branch| {
+                        this.child(
+                            self.entry_label(branch, Color::Muted)
+                                .size(LabelSize::Small),
+                        )
+                    }),
+            )
+            .tooltip({
+                let title: SharedString = format!("stash@{{{}}}", stash.index).into();
+                move |_, cx| Tooltip::simple(title.clone(), cx)
+            })
+            .into_any_element()
+    }
+
     fn has_write_access(&self, cx: &App) -> bool {
         !self.project.read(cx).is_read_only(cx)
     }
 
+    /// Pure jj workspaces have no index, so nothing can be staged or committed from the panel.
+    pub(crate) fn supports_staging(&self, cx: &App) -> bool {
+        self.active_repository
+            .as_ref()
+            .is_none_or(|repository| !repository.read(cx).is_pure_jujutsu)
+    }
+
     pub fn amend_pending(&self) -> bool {
         self.amend_pending
     }
@@ -4239,6 +4766,7 @@ impl Render for GitPanel {
             .and_then(|workspace| workspace.read(cx).active_call()?.read(cx).room().cloned());
 
         let has_write_access = self.has_write_access(cx);
+        let can_stage = has_write_access && self.supports_staging(cx);
 
         let has_co_authors = room.map_or(false, |room| {
             room.read(cx)
@@ -4253,6 +4781,15 @@ impl Render for GitPanel {
             .track_focus(&self.focus_handle)
             .on_modifiers_changed(cx.listener(Self::handle_modifiers_changed))
             .when(has_write_access && !project.is_read_only(cx), |this| {
+                this.on_action(cx.listener(Self::restore_tracked_files))
+                    .on_action(cx.listener(Self::revert_selected))
+                    .on_action(cx.listener(Self::clean_all))
+                    .on_action(cx.listener(Self::stash_all))
+                    .on_action(cx.listener(Self::stash_pop))
+                    .on_action(cx.listener(Self::continue_operation))
+                    .on_action(cx.listener(Self::abort_operation))
+            })
+            .when(can_stage && !project.is_read_only(cx), |this| {
                 this.on_action(cx.listener(Self::toggle_staged_for_selected))
                     .on_action(cx.listener(GitPanel::commit))
                     .on_action(cx.listener(GitPanel::amend))
@@ -4261,9 +4798,6 @@ impl Render for GitPanel {
                     .on_action(cx.listener(Self::unstage_all))
                     .on_action(cx.listener(Self::stage_selected))
                     .on_action(cx.listener(Self::unstage_selected))
-                    .on_action(cx.listener(Self::restore_tracked_files))
-                    .on_action(cx.listener(Self::revert_selected))
-                    .on_action(cx.listener(Self::clean_all))
                     .on_action(cx.listener(Self::generate_commit_message_action))
             })
             .on_action(cx.listener(Self::select_first))
@@ -4295,6 +4829,7 @@ impl Render for GitPanel {
                 v_flex()
                     .size_full()
                     .children(self.render_panel_header(window, cx))
+                    .children(self.render_in_progress_operation(cx))
                     .map(|this| {
                         if has_entries {
                             this.child(self.render_entries(has_write_access, window, cx))
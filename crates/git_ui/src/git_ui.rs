@@ -1,4 +1,4 @@
-use std::any::Any;
+use std::any::{Any, TypeId};
 
 use ::settings::Settings;
 use command_palette_hooks::CommandPaletteFilter;
@@ -18,17 +18,22 @@ use workspace::Workspace;
 
 mod askpass_modal;
 pub mod branch_picker;
+pub mod commit_history;
 mod commit_modal;
 pub mod commit_tooltip;
 mod commit_view;
 mod conflict_view;
+pub mod file_diff_view;
 pub mod git_panel;
 mod git_panel_settings;
+pub mod merge_view;
 pub mod onboarding;
 pub mod picker_prompt;
 pub mod project_diff;
+mod rebase_editor;
 pub(crate) mod remote_output;
 pub mod repository_selector;
+pub mod worktree_picker;
 
 actions!(git, [ResetOnboarding]);
 
@@ -45,6 +50,8 @@ pub fn init(cx: &mut App) {
     cx.observe_new(|workspace: &mut Workspace, _, cx| {
         ProjectDiff::register(workspace, cx);
         CommitModal::register(workspace);
+        commit_history::register(workspace);
+        merge_view::register(workspace);
         git_panel::register(workspace);
         repository_selector::register(workspace);
         branch_picker::register(workspace);
@@ -54,6 +61,7 @@ pub fn init(cx: &mut App) {
             return;
         }
         if !project.is_via_collab() {
+            worktree_picker::register(workspace);
             workspace.register_action(|workspace, _: &git::Fetch, window, cx| {
                 let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
                     return;
@@ -119,9 +127,42 @@ pub fn init(cx: &mut App) {
                 panel.unstage_all(action, window, cx);
             });
         });
+        workspace.register_action(|workspace, action: &git::StashAll, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.stash_all(action, window, cx);
+            });
+        });
+        workspace.register_action(|workspace, action: &git::StashPop, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.stash_pop(action, window, cx);
+            });
+        });
+        workspace.register_action(|workspace, action: &git::ContinueOperation, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.continue_operation(action, window, cx);
+            });
+        });
+        workspace.register_action(|workspace, action: &git::AbortOperation, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.abort_operation(action, window, cx);
+            });
+        });
         CommandPaletteFilter::update_global(cx, |filter, _cx| {
             filter.hide_action_types(&[
                 zed_actions::OpenGitIntegrationOnboarding.type_id(),
+                TypeId::of::<git::OpenFileHistory>(),
                 // ResetOnboarding.type_id(),
             ]);
         });
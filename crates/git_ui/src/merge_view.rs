@@ -0,0 +1,874 @@
+use crate::project_diff::build_diff_against_base_text;
+use anyhow::{Context as _, Result};
+use buffer_diff::BufferDiff;
+use editor::{Editor, EditorEvent, MultiBuffer, scroll::Autoscroll};
+use git::{
+    OpenMergeTool,
+    repository::{ConflictStages, RepoPath},
+};
+use gpui::{
+    AnyElement, AnyView, App, AppContext as _, AsyncWindowContext, Context, Entity, EventEmitter,
+    FocusHandle, Focusable, Subscription, Task, WeakEntity, actions,
+};
+use language::{Buffer, Capability, Language, LanguageRegistry, ToOffset as _, line_diff};
+use project::{ConflictRegion, ConflictSet, Project, ProjectPath, git_store::Repository};
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    path::PathBuf,
+    sync::Arc,
+};
+use ui::prelude::*;
+use workspace::{
+    Item, ItemHandle as _, ItemNavHistory, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, ItemEvent, TabContentParams},
+    notifications::DetachAndPromptErr as _,
+    searchable::SearchableItemHandle,
+};
+
+actions!(
+    merge_view,
+    [
+        TakeOurs,
+        TakeTheirs,
+        TakeBoth,
+        NextConflict,
+        PreviousConflict
+    ]
+);
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &OpenMergeTool, window, cx| {
+        let Some(project_path) = workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+        else {
+            return;
+        };
+        let Some((repository, repo_path)) = workspace
+            .project()
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_project_path(&project_path, cx)
+        else {
+            return;
+        };
+        MergeView::open(repository, repo_path, workspace, window, cx).detach_and_prompt_err(
+            "Failed to open merge tool",
+            window,
+            cx,
+            |_, _, _| None,
+        );
+    });
+}
+
+/// A three-way merge of a conflicted file.
+///
+/// Our and their versions are shown next to the common ancestor, each diffed against it, above
+/// the file itself. Conflicts in the file can then be resolved by taking either side, or both.
+pub struct MergeView {
+    title: SharedString,
+    stages: ConflictStages,
+    result_buffer: Entity<Buffer>,
+    ours_editor: Entity<Editor>,
+    base_editor: Entity<Editor>,
+    theirs_editor: Entity<Editor>,
+    result_editor: Entity<Editor>,
+    _subscription: Subscription,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    Ours,
+    Theirs,
+    Both,
+}
+
+struct SideBuffers {
+    ours: (Entity<Buffer>, Entity<BufferDiff>),
+    base: Entity<Buffer>,
+    theirs: (Entity<Buffer>, Entity<BufferDiff>),
+}
+
+impl MergeView {
+    /// Opens a merge view for a conflicted file in a repository, loading each side from the
+    /// index.
+    pub fn open(
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let workspace = workspace.weak_handle();
+        let stages = repository.update(cx, |repository, _| {
+            repository.load_conflict_stages(repo_path.clone())
+        });
+        let project_path = repository
+            .read(cx)
+            .repo_path_to_project_path(&repo_path, cx);
+
+        window.spawn(cx, async move |cx| {
+            let stages = stages.await??;
+            let project_path = project_path.context("conflicted file is not in the project")?;
+            let project = workspace.read_with(cx, |workspace, _| workspace.project().clone())?;
+            let result_buffer = project
+                .update(cx, |project, cx| project.open_buffer(project_path, cx))?
+                .await?;
+            Self::build(stages, result_buffer, workspace, cx).await
+        })
+    }
+
+    /// Opens a merge view for the files that `git mergetool` passes to its tool, writing the
+    /// result to `result_path`.
+    pub fn open_files(
+        ours_path: PathBuf,
+        theirs_path: PathBuf,
+        base_path: PathBuf,
+        result_path: PathBuf,
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let project = workspace.project().clone();
+        let workspace = workspace.weak_handle();
+        let fs = project.read(cx).fs().clone();
+
+        window.spawn(cx, async move |cx| {
+            let ours = fs
+                .load(&ours_path)
+                .await
+                .with_context(|| format!("loading {ours_path:?}"))?;
+            let theirs = fs
+                .load(&theirs_path)
+                .await
+                .with_context(|| format!("loading {theirs_path:?}"))?;
+            // Files added on both sides have no common ancestor, so git passes an empty base.
+            let base = fs
+                .load(&base_path)
+                .await
+                .ok()
+                .filter(|base| !base.is_empty());
+            let stages = ConflictStages {
+                base,
+                ours: Some(ours),
+                theirs: Some(theirs),
+            };
+
+            project
+                .update(cx, |project, cx| {
+                    project.find_or_create_worktree(&result_path, false, cx)
+                })?
+                .await?;
+            let result_buffer = project
+                .update(cx, |project, cx| {
+                    project.open_local_buffer(&result_path, cx)
+                })?
+                .await?;
+            Self::build(stages, result_buffer, workspace, cx).await
+        })
+    }
+
+    async fn build(
+        stages: ConflictStages,
+        result_buffer: Entity<Buffer>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<Entity<Self>> {
+        let project = workspace.read_with(cx, |workspace, _| workspace.project().clone())?;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone())?;
+        let language = result_buffer.read_with(cx, |buffer, _| buffer.language().cloned())?;
+
+        let base = side_buffer(stages.base.clone(), &language, &language_registry, cx)?;
+        let ours = side_buffer(stages.ours.clone(), &language, &language_registry, cx)?;
+        let theirs = side_buffer(stages.theirs.clone(), &language, &language_registry, cx)?;
+        let (ours_diff, _) = build_diff_against_base_text(
+            stages.base.clone(),
+            ours.clone(),
+            language_registry.clone(),
+            cx,
+        )
+        .await?;
+        let (theirs_diff, _) = build_diff_against_base_text(
+            stages.base.clone(),
+            theirs.clone(),
+            language_registry.clone(),
+            cx,
+        )
+        .await?;
+        let sides = SideBuffers {
+            ours: (ours, ours_diff),
+            base,
+            theirs: (theirs, theirs_diff),
+        };
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            let merge_view =
+                cx.new(|cx| Self::new(stages, sides, result_buffer, project, window, cx));
+            workspace.add_item_to_active_pane(Box::new(merge_view.clone()), None, true, window, cx);
+            merge_view
+        })
+    }
+
+    fn new(
+        stages: ConflictStages,
+        sides: SideBuffers,
+        result_buffer: Entity<Buffer>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let title = result_buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string())
+            .into();
+
+        let side_editor = |buffer: Entity<Buffer>,
+                           diff: Option<Entity<BufferDiff>>,
+                           window: &mut Window,
+                           cx: &mut Context<Self>| {
+            let multibuffer = cx.new(|cx| {
+                let mut multibuffer = MultiBuffer::singleton(buffer, cx);
+                if let Some(diff) = diff {
+                    multibuffer.add_diff(diff, cx);
+                }
+                multibuffer
+            });
+            cx.new(|cx| {
+                let mut editor = Editor::for_multibuffer(multibuffer, None, window, cx);
+                editor.set_read_only(true);
+                editor.disable_inline_diagnostics();
+                editor.set_expand_all_diff_hunks(cx);
+                editor
+            })
+        };
+        let (ours, ours_diff) = sides.ours;
+        let (theirs, theirs_diff) = sides.theirs;
+        let ours_editor = side_editor(ours, Some(ours_diff), window, cx);
+        let base_editor = side_editor(sides.base, None, window, cx);
+        let theirs_editor = side_editor(theirs, Some(theirs_diff), window, cx);
+
+        let result_editor =
+            cx.new(|cx| Editor::for_buffer(result_buffer.clone(), Some(project), window, cx));
+        let subscription = cx.subscribe(&result_editor, |_, _, event: &EditorEvent, cx| {
+            cx.emit(event.clone());
+        });
+
+        Self {
+            title,
+            stages,
+            result_buffer,
+            ours_editor,
+            base_editor,
+            theirs_editor,
+            result_editor,
+            _subscription: subscription,
+        }
+    }
+
+    fn conflicts(&self, cx: &App) -> Arc<[ConflictRegion]> {
+        ConflictSet::parse(&self.result_buffer.read(cx).text_snapshot()).conflicts
+    }
+
+    fn cursor_offset(&self, cx: &mut App) -> usize {
+        self.result_editor.update(cx, |editor, cx| {
+            editor.selections.newest::<usize>(cx).head()
+        })
+    }
+
+    /// Applies the side's next change at or after the cursor that the file doesn't have yet, or
+    /// resolves the conflict containing the cursor (or the next one after it) when that comes
+    /// first.
+    fn take(&mut self, side: Side, window: &mut Window, cx: &mut Context<Self>) {
+        let cursor = self.cursor_offset(cx);
+        let snapshot = self.result_buffer.read(cx).text_snapshot();
+        let conflict = self
+            .conflicts(cx)
+            .iter()
+            .find(|conflict| conflict.range.end.to_offset(&snapshot) >= cursor)
+            .cloned();
+        let conflict_start = conflict
+            .as_ref()
+            .map(|conflict| conflict.range.start.to_offset(&snapshot));
+        if conflict_start.is_none_or(|start| start > cursor) {
+            let result_text = snapshot.text();
+            let edit = self
+                .stage_hunk_edit(side, &result_text, cursor)
+                .filter(|(range, _)| conflict_start.is_none_or(|start| range.end <= start));
+            if let Some((range, new_text)) = edit {
+                let end = range.start + new_text.len();
+                self.result_buffer.update(cx, |buffer, cx| {
+                    buffer.edit([(range, new_text)], None, cx);
+                });
+                self.result_editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::fit()), window, cx, |selections| {
+                        selections.select_ranges([end..end]);
+                    });
+                });
+                return;
+            }
+        }
+        let Some(conflict) = conflict else {
+            return;
+        };
+        let ranges = match side {
+            Side::Ours => vec![conflict.ours.clone()],
+            Side::Theirs => vec![conflict.theirs.clone()],
+            Side::Both => vec![conflict.ours.clone(), conflict.theirs.clone()],
+        };
+        conflict.resolve(self.result_buffer.clone(), &ranges, cx);
+        self.select_conflict(true, window, cx);
+    }
+
+    /// Finds the first change that `side` made to the common ancestor at or after `cursor` and
+    /// that `result_text` doesn't contain yet, returning the edit that applies it.
+    ///
+    /// Taking both sides applies overlapping changes of either side together, ours first.
+    fn stage_hunk_edit(
+        &self,
+        side: Side,
+        result_text: &str,
+        cursor: usize,
+    ) -> Option<(Range<usize>, String)> {
+        let base_text = self.stages.base.as_deref().unwrap_or_default();
+        let stage_texts = match side {
+            Side::Ours => vec![self.stages.ours.as_deref().unwrap_or_default()],
+            Side::Theirs => vec![self.stages.theirs.as_deref().unwrap_or_default()],
+            Side::Both => vec![
+                self.stages.ours.as_deref().unwrap_or_default(),
+                self.stages.theirs.as_deref().unwrap_or_default(),
+            ],
+        };
+        let stage_hunks = stage_texts
+            .iter()
+            .map(|text| line_diff(base_text, text))
+            .collect::<Vec<_>>();
+        let result_hunks = line_diff(base_text, result_text);
+        let inverse_result_hunks = result_hunks
+            .iter()
+            .map(|(base, result)| (result.clone(), base.clone()))
+            .collect::<Vec<_>>();
+        let cursor_row = result_text[..cursor].matches('\n').count() as u32;
+        let cursor_base_row = map_row(cursor_row, &inverse_result_hunks, false);
+
+        let mut candidates = stage_hunks
+            .iter()
+            .flatten()
+            .map(|(base, _)| base.clone())
+            .filter(|base| base.end.max(base.start + 1) > cursor_base_row)
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|base| (base.start, base.end));
+        for candidate in candidates {
+            let mut base_range = candidate;
+            while let Some(overlapping) =
+                stage_hunks
+                    .iter()
+                    .flatten()
+                    .map(|(base, _)| base)
+                    .find(|base| {
+                        rows_overlap(base, &base_range)
+                            && (base.start < base_range.start || base.end > base_range.end)
+                    })
+            {
+                base_range =
+                    base_range.start.min(overlapping.start)..base_range.end.max(overlapping.end);
+            }
+
+            let new_text = stage_texts
+                .iter()
+                .zip(&stage_hunks)
+                .filter(|(_, hunks)| {
+                    hunks
+                        .iter()
+                        .any(|(base, _)| rows_overlap(base, &base_range))
+                })
+                .map(|(text, hunks)| row_text(text, map_rows(&base_range, hunks)))
+                .collect::<String>();
+            let result_rows = map_rows(&base_range, &result_hunks);
+            if row_text(result_text, result_rows.clone()) == new_text {
+                continue;
+            }
+            let range = row_offset(result_text, result_rows.start)
+                ..row_offset(result_text, result_rows.end);
+            return Some((range, new_text));
+        }
+        None
+    }
+
+    fn select_conflict(&mut self, forward: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let cursor = self.cursor_offset(cx);
+        let snapshot = self.result_buffer.read(cx).text_snapshot();
+        let starts = self
+            .conflicts(cx)
+            .iter()
+            .map(|conflict| conflict.range.start.to_offset(&snapshot))
+            .collect::<Vec<_>>();
+        let target = if forward {
+            starts
+                .iter()
+                .find(|start| **start > cursor)
+                .or(starts.first())
+        } else {
+            starts
+                .iter()
+                .rev()
+                .find(|start| **start < cursor)
+                .or(starts.last())
+        };
+        let Some(&target) = target else {
+            return;
+        };
+        self.result_editor.update(cx, |editor, cx| {
+            editor.change_selections(Some(Autoscroll::center()), window, cx, |selections| {
+                selections.select_ranges([target..target]);
+            });
+        });
+    }
+
+    fn take_ours(&mut self, _: &TakeOurs, window: &mut Window, cx: &mut Context<Self>) {
+        self.take(Side::Ours, window, cx);
+    }
+
+    fn take_theirs(&mut self, _: &TakeTheirs, window: &mut Window, cx: &mut Context<Self>) {
+        self.take(Side::Theirs, window, cx);
+    }
+
+    fn take_both(&mut self, _: &TakeBoth, window: &mut Window, cx: &mut Context<Self>) {
+        self.take(Side::Both, window, cx);
+    }
+
+    fn next_conflict(&mut self, _: &NextConflict, window: &mut Window, cx: &mut Context<Self>) {
+        self.select_conflict(true, window, cx);
+    }
+
+    fn previous_conflict(
+        &mut self,
+        _: &PreviousConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_conflict(false, window, cx);
+    }
+
+    fn render_pane(
+        &self,
+        title: &'static str,
+        editor: &Entity<Editor>,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .min_h_0()
+            .border_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(title).size(LabelSize::Small).color(Color::Muted)),
+            )
+            .child(div().flex_1().min_h_0().child(editor.clone()))
+    }
+}
+
+/// Maps a row of the old text of `hunks` to the new text. Rows within a changed region map to
+/// its start, or to its end when `is_end` is set.
+fn map_row(row: u32, hunks: &[(Range<u32>, Range<u32>)], is_end: bool) -> u32 {
+    let mut delta = 0i64;
+    for (old, new) in hunks {
+        if row <= old.start {
+            break;
+        }
+        if row < old.end {
+            return if is_end { new.end } else { new.start };
+        }
+        delta = new.end as i64 - old.end as i64;
+    }
+    (row as i64 + delta) as u32
+}
+
+fn map_rows(rows: &Range<u32>, hunks: &[(Range<u32>, Range<u32>)]) -> Range<u32> {
+    map_row(rows.start, hunks, false)..map_row(rows.end, hunks, true)
+}
+
+/// Whether two row ranges overlap, where an empty range is an insertion before its row.
+fn rows_overlap(a: &Range<u32>, b: &Range<u32>) -> bool {
+    a.start < b.end.max(b.start + 1) && b.start < a.end.max(a.start + 1)
+}
+
+fn row_offset(text: &str, row: u32) -> usize {
+    if row == 0 {
+        return 0;
+    }
+    text.match_indices('\n')
+        .nth(row as usize - 1)
+        .map_or(text.len(), |(ix, _)| ix + 1)
+}
+
+fn row_text(text: &str, rows: Range<u32>) -> &str {
+    &text[row_offset(text, rows.start)..row_offset(text, rows.end)]
+}
+
+fn side_buffer(
+    text: Option<String>,
+    language: &Option<Arc<Language>>,
+    language_registry: &Arc<LanguageRegistry>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Entity<Buffer>> {
+    cx.new(|cx| {
+        let mut buffer = Buffer::local(text.unwrap_or_default(), cx);
+        buffer.set_language_registry(language_registry.clone());
+        buffer.set_language(language.clone(), cx);
+        buffer.set_capability(Capability::ReadOnly, cx);
+        buffer
+    })
+}
+
+impl EventEmitter<EditorEvent> for MergeView {}
+
+impl Focusable for MergeView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.result_editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        format!("Merge {}", self.title).into()
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge View Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.result_editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.result_buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.result_buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        format: bool,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor.save(format, project, window, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        _: Entity<Project>,
+        _: ProjectPath,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        unreachable!()
+    }
+
+    fn reload(
+        &mut self,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor.reload(project, window, cx)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.result_editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.result_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.result_editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.result_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.result_editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+}
+
+impl Render for MergeView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("GitMergeView")
+            .size_full()
+            .on_action(cx.listener(Self::take_ours))
+            .on_action(cx.listener(Self::take_theirs))
+            .on_action(cx.listener(Self::take_both))
+            .on_action(cx.listener(Self::next_conflict))
+            .on_action(cx.listener(Self::previous_conflict))
+            .child(
+                h_flex()
+                    .h_1_2()
+                    .w_full()
+                    .child(self.render_pane("Ours", &self.ours_editor, cx))
+                    .child(self.render_pane("Base", &self.base_editor, cx))
+                    .child(self.render_pane("Theirs", &self.theirs_editor, cx)),
+            )
+            .child(self.render_pane("Result", &self.result_editor, cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_take_sides_from_mergetool_files(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/merge"),
+            json!({
+                "ours.txt": "one\nours\nthree\nours again\n",
+                "theirs.txt": "one\ntheirs\nthree\ntheirs again\n",
+                "base.txt": "one\ntwo\nthree\nfour\n",
+                "result.txt": "one\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> branch\nthree\n<<<<<<< HEAD\nours again\n=======\ntheirs again\n>>>>>>> branch\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let merge_view = workspace
+            .update_in(cx, |workspace, window, cx| {
+                MergeView::open_files(
+                    path!("/merge/ours.txt").into(),
+                    path!("/merge/theirs.txt").into(),
+                    path!("/merge/base.txt").into(),
+                    path!("/merge/result.txt").into(),
+                    workspace,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let result_text = |cx: &mut VisualTestContext| {
+            merge_view.read_with(cx, |merge_view, cx| {
+                merge_view.result_buffer.read(cx).text()
+            })
+        };
+        merge_view.update_in(cx, |merge_view, window, cx| {
+            merge_view.take(Side::Theirs, window, cx);
+        });
+        assert_eq!(
+            result_text(cx),
+            "one\ntheirs\nthree\n<<<<<<< HEAD\nours again\n=======\ntheirs again\n>>>>>>> branch\n"
+        );
+
+        // Taking a side moves on to the next conflict.
+        merge_view.update_in(cx, |merge_view, window, cx| {
+            merge_view.take(Side::Both, window, cx);
+        });
+        assert_eq!(
+            result_text(cx),
+            "one\ntheirs\nthree\nours again\ntheirs again\n"
+        );
+        assert!(merge_view.read_with(cx, |merge_view, cx| merge_view.is_dirty(cx)));
+    }
+
+    #[gpui::test]
+    async fn test_take_hunks_without_conflict_markers(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/merge"),
+            json!({
+                "ours.txt": "one
+ours
+three
+four
+ours again
+",
+                "theirs.txt": "one
+theirs
+three
+four
+theirs again
+",
+                "base.txt": "one
+two
+three
+four
+five
+",
+                "result.txt": "one
+two
+three
+four
+five
+",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let merge_view = workspace
+            .update_in(cx, |workspace, window, cx| {
+                MergeView::open_files(
+                    path!("/merge/ours.txt").into(),
+                    path!("/merge/theirs.txt").into(),
+                    path!("/merge/base.txt").into(),
+                    path!("/merge/result.txt").into(),
+                    workspace,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let result_text = |cx: &mut VisualTestContext| {
+            merge_view.read_with(cx, |merge_view, cx| {
+                merge_view.result_buffer.read(cx).text()
+            })
+        };
+        merge_view.update_in(cx, |merge_view, window, cx| {
+            merge_view.take(Side::Ours, window, cx);
+        });
+        assert_eq!(result_text(cx), "one\nours\nthree\nfour\nfive\n");
+
+        // Hunks the file already has are skipped.
+        merge_view.update_in(cx, |merge_view, window, cx| {
+            merge_view.take(Side::Both, window, cx);
+        });
+        assert_eq!(
+            result_text(cx),
+            "one\nours\nthree\nfour\nours again\ntheirs again\n"
+        );
+
+        // Nothing is left to take after the cursor.
+        merge_view.update_in(cx, |merge_view, window, cx| {
+            merge_view.take(Side::Theirs, window, cx);
+        });
+        assert_eq!(
+            result_text(cx),
+            "one\nours\nthree\nfour\nours again\ntheirs again\n"
+        );
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
 };
 use anyhow::Result;
 use buffer_diff::{BufferDiff, DiffHunkSecondaryStatus};
-use collections::HashSet;
+use collections::{HashMap, HashSet};
 use editor::{
     Editor, EditorEvent,
     actions::{GoToHunk, GoToPreviousHunk},
@@ -16,14 +16,15 @@ use futures::StreamExt;
 use git::{
     Commit, StageAll, StageAndNext, ToggleStaged, UnstageAll, UnstageAndNext,
     repository::{Branch, Upstream, UpstreamTracking, UpstreamTrackingStatus},
-    status::FileStatus,
+    status::{FileStatus, StatusCode, TrackedStatus},
 };
 use gpui::{
-    Action, AnyElement, AnyView, App, AppContext as _, AsyncWindowContext, Entity, EventEmitter,
-    FocusHandle, Focusable, Render, Subscription, Task, WeakEntity, actions,
+    Action, AnyElement, AnyView, App, AppContext as _, AsyncApp, AsyncWindowContext, Entity,
+    EventEmitter, FocusHandle, Focusable, Render, Subscription, Task, WeakEntity, actions,
 };
-use language::{Anchor, Buffer, Capability, OffsetRangeExt};
+use language::{Anchor, Buffer, BufferEvent, Capability, LanguageRegistry, OffsetRangeExt};
 use multi_buffer::{MultiBuffer, PathKey};
+use persistence::PROJECT_DIFF;
 use project::{
     Project, ProjectPath,
     git_store::{GitStore, GitStoreEvent, RepositoryEvent},
@@ -31,6 +32,7 @@ use project::{
 use settings::{Settings, SettingsStore};
 use std::any::{Any, TypeId};
 use std::ops::Range;
+use std::sync::Arc;
 use theme::ActiveTheme;
 use ui::{KeyBinding, Tooltip, prelude::*, vertical_divider};
 use util::ResultExt as _;
@@ -50,6 +52,10 @@ pub struct ProjectDiff {
     git_store: Entity<GitStore>,
     workspace: WeakEntity<Workspace>,
     focus_handle: FocusHandle,
+    /// The branch or revision whose merge base with HEAD the working tree is compared against.
+    /// When this is `None`, the diff shows uncommitted changes against the index and HEAD.
+    base: Option<SharedString>,
+    base_text_subscriptions: HashMap<PathKey, Subscription>,
     update_needed: postage::watch::Sender<()>,
     pending_scroll: Option<PathKey>,
     _task: Task<Result<()>>,
@@ -100,13 +106,50 @@ impl ProjectDiff {
                 "Action"
             }
         );
-        let project_diff = if let Some(existing) = workspace.item_of_type::<Self>(cx) {
+        let project_diff = Self::deploy_with_base(workspace, None, window, cx);
+        if let Some(entry) = entry {
+            project_diff.update(cx, |project_diff, cx| {
+                project_diff.move_to_entry(entry, window, cx);
+            })
+        }
+    }
+
+    /// Opens a diff of the working tree against its merge base with `base`, which can be a
+    /// branch name or any other revision.
+    pub fn deploy_compare(
+        workspace: &mut Workspace,
+        base: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        telemetry::event!("Git Branch Diff Opened");
+        Self::deploy_with_base(workspace, Some(base), window, cx);
+    }
+
+    fn deploy_with_base(
+        workspace: &mut Workspace,
+        base: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let existing = workspace
+            .items_of_type::<Self>(cx)
+            .filter(|item| item.read(cx).base == base)
+            .max_by_key(|item| item.item_id());
+        if let Some(existing) = existing {
             workspace.activate_item(&existing, true, true, window, cx);
             existing
         } else {
             let workspace_handle = cx.entity();
-            let project_diff =
-                cx.new(|cx| Self::new(workspace.project().clone(), workspace_handle, window, cx));
+            let project_diff = cx.new(|cx| {
+                Self::new(
+                    workspace.project().clone(),
+                    workspace_handle,
+                    base,
+                    window,
+                    cx,
+                )
+            });
             workspace.add_item_to_active_pane(
                 Box::new(project_diff.clone()),
                 None,
@@ -115,14 +158,13 @@ impl ProjectDiff {
                 cx,
             );
             project_diff
-        };
-        if let Some(entry) = entry {
-            project_diff.update(cx, |project_diff, cx| {
-                project_diff.move_to_entry(entry, window, cx);
-            })
         }
     }
 
+    pub fn base(&self) -> Option<&SharedString> {
+        self.base.as_ref()
+    }
+
     pub fn autoscroll(&self, cx: &mut Context<Self>) {
         self.editor.update(cx, |editor, cx| {
             editor.request_autoscroll(Autoscroll::fit(), cx);
@@ -132,6 +174,7 @@ impl ProjectDiff {
     fn new(
         project: Entity<Project>,
         workspace: Entity<Workspace>,
+        base: Option<SharedString>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
@@ -201,6 +244,8 @@ impl ProjectDiff {
             focus_handle,
             editor,
             multibuffer,
+            base,
+            base_text_subscriptions: HashMap::default(),
             pending_scroll: None,
             update_needed: send,
             _task: worker,
@@ -297,21 +342,23 @@ impl ProjectDiff {
                 }
             }
         }
+        let mut supports_staging = true;
         let mut stage_all = false;
         let mut unstage_all = false;
         self.workspace
             .read_with(cx, |workspace, cx| {
                 if let Some(git_panel) = workspace.panel::<GitPanel>(cx) {
                     let git_panel = git_panel.read(cx);
-                    stage_all = git_panel.can_stage_all();
-                    unstage_all = git_panel.can_unstage_all();
+                    supports_staging = git_panel.supports_staging(cx);
+                    stage_all = supports_staging && git_panel.can_stage_all();
+                    unstage_all = supports_staging && git_panel.can_unstage_all();
                 }
             })
             .ok();
 
         return ButtonStates {
-            stage: has_unstaged_hunks,
-            unstage: has_staged_hunks,
+            stage: supports_staging && has_unstaged_hunks,
+            unstage: supports_staging && has_staged_hunks,
             prev_next,
             selection,
             stage_all,
@@ -411,6 +458,97 @@ impl ProjectDiff {
         result
     }
 
+    fn load_buffers_against_base(
+        &mut self,
+        base: SharedString,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Task<Result<DiffBuffer>>>>> {
+        let Some(repo) = self.git_store.read(cx).active_repository() else {
+            self.multibuffer.update(cx, |multibuffer, cx| {
+                multibuffer.clear(cx);
+            });
+            self.base_text_subscriptions.clear();
+            return Task::ready(Ok(Vec::new()));
+        };
+
+        let changes = repo.update(cx, |repo, _| repo.diff_to_merge_base(base.to_string()));
+        cx.spawn(async move |this, cx| {
+            let changes = changes.await??;
+            this.update(cx, |this, cx| {
+                let mut previous_paths = this.multibuffer.read(cx).paths().collect::<HashSet<_>>();
+                let language_registry = this.project.read(cx).languages().clone();
+
+                let mut result = vec![];
+                for file in changes.files {
+                    let Some(project_path) =
+                        repo.read(cx).repo_path_to_project_path(&file.path, cx)
+                    else {
+                        continue;
+                    };
+                    let file_status = if file.old_text.is_none() {
+                        FileStatus::Tracked(TrackedStatus {
+                            index_status: StatusCode::Added,
+                            worktree_status: StatusCode::Unmodified,
+                        })
+                    } else if file.new_text.is_none() {
+                        FileStatus::Tracked(TrackedStatus {
+                            index_status: StatusCode::Unmodified,
+                            worktree_status: StatusCode::Deleted,
+                        })
+                    } else {
+                        FileStatus::Tracked(TrackedStatus {
+                            index_status: StatusCode::Unmodified,
+                            worktree_status: StatusCode::Modified,
+                        })
+                    };
+                    let namespace = if !GitPanelSettings::get_global(cx).sort_by_path
+                        && file_status.is_created()
+                    {
+                        NEW_NAMESPACE
+                    } else {
+                        TRACKED_NAMESPACE
+                    };
+                    let path_key = PathKey::namespaced(namespace, file.path.0.clone());
+
+                    previous_paths.remove(&path_key);
+                    let load_buffer = this
+                        .project
+                        .update(cx, |project, cx| project.open_buffer(project_path, cx));
+
+                    let language_registry = language_registry.clone();
+                    result.push(cx.spawn(async move |this, cx| {
+                        let buffer = load_buffer.await?;
+                        let (diff, subscription) = build_diff_against_base_text(
+                            file.old_text,
+                            buffer.clone(),
+                            language_registry,
+                            cx,
+                        )
+                        .await?;
+                        this.update(cx, |this, _| {
+                            this.base_text_subscriptions
+                                .insert(path_key.clone(), subscription);
+                        })?;
+                        Ok(DiffBuffer {
+                            path_key,
+                            buffer,
+                            diff,
+                            file_status,
+                        })
+                    }));
+                }
+
+                this.multibuffer.update(cx, |multibuffer, cx| {
+                    for path in previous_paths {
+                        this.base_text_subscriptions.remove(&path);
+                        multibuffer.remove_excerpts_for_path(path, cx);
+                    }
+                });
+                result
+            })
+        })
+    }
+
     fn register_buffer(
         &mut self,
         diff_buffer: DiffBuffer,
@@ -490,7 +628,14 @@ impl ProjectDiff {
         cx: &mut AsyncWindowContext,
     ) -> Result<()> {
         while let Some(_) = recv.next().await {
-            let buffers_to_load = this.update(cx, |this, cx| this.load_buffers(cx))?;
+            let buffers_to_load = match this.update(cx, |this, _| this.base.clone())? {
+                Some(base) => this
+                    .update(cx, |this, cx| this.load_buffers_against_base(base, cx))?
+                    .await
+                    .log_err()
+                    .unwrap_or_default(),
+                None => this.update(cx, |this, cx| this.load_buffers(cx))?,
+            };
             for buffer_to_load in buffers_to_load {
                 if let Some(buffer) = buffer_to_load.await.log_err() {
                     cx.update(|window, cx| {
@@ -518,6 +663,39 @@ impl ProjectDiff {
     }
 }
 
+/// Builds a diff of `buffer` against `base_text`, and keeps it up to date as the buffer is edited.
+pub(crate) async fn build_diff_against_base_text(
+    base_text: Option<String>,
+    buffer: Entity<Buffer>,
+    language_registry: Arc<LanguageRegistry>,
+    cx: &mut AsyncApp,
+) -> Result<(Entity<BufferDiff>, Subscription)> {
+    let base_buffer = cx.new(|cx| {
+        let mut base_buffer = Buffer::local(base_text.unwrap_or_default(), cx);
+        base_buffer.set_language_registry(language_registry);
+        base_buffer.set_language(buffer.read(cx).language().cloned(), cx);
+        base_buffer
+    })?;
+    let diff = cx.new(|cx| BufferDiff::new(&buffer.read(cx).text_snapshot(), cx))?;
+    let diff_updated = diff.update(cx, |diff, cx| {
+        diff.set_base_text_buffer(base_buffer.clone(), buffer.read(cx).text_snapshot(), cx)
+    })?;
+    diff_updated.await.ok();
+
+    let subscription = cx.update(|cx| {
+        let diff = diff.clone();
+        cx.subscribe(&buffer, move |buffer, event, cx| {
+            if let BufferEvent::Edited = event {
+                let snapshot = buffer.read(cx).text_snapshot();
+                diff.update(cx, |diff, cx| {
+                    diff.set_base_text_buffer(base_buffer.clone(), snapshot, cx);
+                });
+            }
+        })
+    })?;
+    Ok((diff, subscription))
+}
+
 impl EventEmitter<EditorEvent> for ProjectDiff {}
 
 impl Focusable for ProjectDiff {
@@ -557,11 +735,14 @@ impl Item for ProjectDiff {
     }
 
     fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
-        Some("Project Diff".into())
+        match &self.base {
+            Some(base) => Some(format!("Changes since the merge base with {base}").into()),
+            None => Some("Project Diff".into()),
+        }
     }
 
-    fn tab_content(&self, params: TabContentParams, _window: &Window, _: &App) -> AnyElement {
-        Label::new("Uncommitted Changes")
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(0, cx))
             .color(if params.selected {
                 Color::Default
             } else {
@@ -571,7 +752,10 @@ impl Item for ProjectDiff {
     }
 
     fn tab_content_text(&self, _detail: usize, _: &App) -> SharedString {
-        "Uncommitted Changes".into()
+        match &self.base {
+            Some(base) => format!("Changes since {base}").into(),
+            None => "Uncommitted Changes".into(),
+        }
     }
 
     fn telemetry_event_text(&self) -> Option<&'static str> {
@@ -615,7 +799,8 @@ impl Item for ProjectDiff {
         Self: Sized,
     {
         let workspace = self.workspace.upgrade()?;
-        Some(cx.new(|cx| ProjectDiff::new(self.project.clone(), workspace, window, cx)))
+        let base = self.base.clone();
+        Some(cx.new(|cx| ProjectDiff::new(self.project.clone(), workspace, base, window, cx)))
     }
 
     fn is_dirty(&self, cx: &App) -> bool {
@@ -717,15 +902,15 @@ impl Render for ProjectDiff {
                     None
                 };
                 let keybinding_focus_handle = self.focus_handle(cx).clone();
+                let base = self.base.clone();
                 el.child(
                     v_flex()
                         .gap_1()
-                        .child(
-                            h_flex()
-                                .justify_around()
-                                .child(Label::new("No uncommitted changes")),
-                        )
-                        .map(|el| match remote_button {
+                        .child(h_flex().justify_around().child(Label::new(match &base {
+                            Some(base) => format!("No changes since {base}"),
+                            None => "No uncommitted changes".to_string(),
+                        })))
+                        .when(base.is_none(), |el| match remote_button {
                             Some(button) => el.child(h_flex().justify_around().child(button)),
                             None => el.child(
                                 h_flex()
@@ -764,39 +949,61 @@ impl SerializableItem for ProjectDiff {
     }
 
     fn cleanup(
-        _: workspace::WorkspaceId,
-        _: Vec<workspace::ItemId>,
-        _: &mut Window,
-        _: &mut App,
+        workspace_id: workspace::WorkspaceId,
+        alive_items: Vec<workspace::ItemId>,
+        _window: &mut Window,
+        cx: &mut App,
     ) -> Task<Result<()>> {
-        Task::ready(Ok(()))
+        workspace::delete_unloaded_items(
+            alive_items,
+            workspace_id,
+            "project_diffs",
+            &PROJECT_DIFF,
+            cx,
+        )
     }
 
     fn deserialize(
         _project: Entity<Project>,
         workspace: WeakEntity<Workspace>,
-        _workspace_id: workspace::WorkspaceId,
-        _item_id: workspace::ItemId,
+        workspace_id: workspace::WorkspaceId,
+        item_id: workspace::ItemId,
         window: &mut Window,
         cx: &mut App,
     ) -> Task<Result<Entity<Self>>> {
         window.spawn(cx, async move |cx| {
+            let base = PROJECT_DIFF
+                .get_base(item_id, workspace_id)?
+                .map(SharedString::from);
             workspace.update_in(cx, |workspace, window, cx| {
                 let workspace_handle = cx.entity();
-                cx.new(|cx| Self::new(workspace.project().clone(), workspace_handle, window, cx))
+                cx.new(|cx| {
+                    Self::new(
+                        workspace.project().clone(),
+                        workspace_handle,
+                        base,
+                        window,
+                        cx,
+                    )
+                })
             })
         })
     }
 
     fn serialize(
         &mut self,
-        _workspace: &mut Workspace,
-        _item_id: workspace::ItemId,
+        workspace: &mut Workspace,
+        item_id: workspace::ItemId,
         _closing: bool,
         _window: &mut Window,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) -> Option<Task<Result<()>>> {
-        None
+        // Only comparisons need to remember anything to be restored.
+        let base = self.base.as_ref()?.to_string();
+        let workspace_id = workspace.database_id()?;
+        Some(cx.background_spawn(async move {
+            PROJECT_DIFF.save_base(item_id, workspace_id, base).await
+        }))
     }
 
     fn should_serialize(&self, _: &Self::Event) -> bool {
@@ -804,6 +1011,48 @@ impl SerializableItem for ProjectDiff {
     }
 }
 
+mod persistence {
+    use db::{define_connection, query, sqlez_macros::sql};
+    use workspace::{ItemId, WorkspaceDb, WorkspaceId};
+
+    define_connection! {
+        pub static ref PROJECT_DIFF: ProjectDiffDb<WorkspaceDb> =
+            &[sql!(
+                CREATE TABLE project_diffs (
+                    workspace_id INTEGER,
+                    item_id INTEGER UNIQUE,
+
+                    base TEXT NOT NULL,
+
+                    PRIMARY KEY(workspace_id, item_id),
+                    FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                    ON DELETE CASCADE
+                ) STRICT;
+            )];
+    }
+
+    impl ProjectDiffDb {
+        query! {
+            pub async fn save_base(
+                item_id: ItemId,
+                workspace_id: WorkspaceId,
+                base: String
+            ) -> Result<()> {
+                INSERT OR REPLACE INTO project_diffs(item_id, workspace_id, base)
+                VALUES (?, ?, ?)
+            }
+        }
+
+        query! {
+            pub fn get_base(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<String>> {
+                SELECT base
+                FROM project_diffs
+                WHERE item_id = ? AND workspace_id = ?
+            }
+        }
+    }
+}
+
 pub struct ProjectDiffToolbar {
     project_diff: Option<WeakEntity<ProjectDiff>>,
     workspace: WeakEntity<Workspace>,
@@ -901,6 +1150,8 @@ impl Render for ProjectDiffToolbar {
         };
         let focus_handle = project_diff.focus_handle(cx);
         let button_states = project_diff.read(cx).button_states(cx);
+        // Staging doesn't apply when comparing against another branch.
+        let is_compare = project_diff.read(cx).base.is_some();
 
         h_group_xl()
             .my_neg_1()
@@ -908,47 +1159,49 @@ impl Render for ProjectDiffToolbar {
             .items_center()
             .flex_wrap()
             .justify_between()
-            .child(
-                h_group_sm()
-                    .when(button_states.selection, |el| {
-                        el.child(
-                            Button::new("stage", "Toggle Staged")
-                                .tooltip(Tooltip::for_action_title_in(
-                                    "Toggle Staged",
-                                    &ToggleStaged,
-                                    &focus_handle,
-                                ))
-                                .disabled(!button_states.stage && !button_states.unstage)
-                                .on_click(cx.listener(|this, _, window, cx| {
-                                    this.dispatch_action(&ToggleStaged, window, cx)
-                                })),
-                        )
-                    })
-                    .when(!button_states.selection, |el| {
-                        el.child(
-                            Button::new("stage", "Stage")
-                                .tooltip(Tooltip::for_action_title_in(
-                                    "Stage and go to next hunk",
-                                    &StageAndNext,
-                                    &focus_handle,
-                                ))
-                                .on_click(cx.listener(|this, _, window, cx| {
-                                    this.dispatch_action(&StageAndNext, window, cx)
-                                })),
-                        )
-                        .child(
-                            Button::new("unstage", "Unstage")
-                                .tooltip(Tooltip::for_action_title_in(
-                                    "Unstage and go to next hunk",
-                                    &UnstageAndNext,
-                                    &focus_handle,
-                                ))
-                                .on_click(cx.listener(|this, _, window, cx| {
-                                    this.dispatch_action(&UnstageAndNext, window, cx)
-                                })),
-                        )
-                    }),
-            )
+            .when(!is_compare, |el| {
+                el.child(
+                    h_group_sm()
+                        .when(button_states.selection, |el| {
+                            el.child(
+                                Button::new("stage", "Toggle Staged")
+                                    .tooltip(Tooltip::for_action_title_in(
+                                        "Toggle Staged",
+                                        &ToggleStaged,
+                                        &focus_handle,
+                                    ))
+                                    .disabled(!button_states.stage && !button_states.unstage)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.dispatch_action(&ToggleStaged, window, cx)
+                                    })),
+                            )
+                        })
+                        .when(!button_states.selection, |el| {
+                            el.child(
+                                Button::new("stage", "Stage")
+                                    .tooltip(Tooltip::for_action_title_in(
+                                        "Stage and go to next hunk",
+                                        &StageAndNext,
+                                        &focus_handle,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.dispatch_action(&StageAndNext, window, cx)
+                                    })),
+                            )
+                            .child(
+                                Button::new("unstage", "Unstage")
+                                    .tooltip(Tooltip::for_action_title_in(
+                                        "Unstage and go to next hunk",
+                                        &UnstageAndNext,
+                                        &focus_handle,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.dispatch_action(&UnstageAndNext, window, cx)
+                                    })),
+                            )
+                        }),
+                )
+            })
             // n.b. the only reason these arrows are here is because we don't
             // support "undo" for staging so we need a way to go back.
             .child(
@@ -980,58 +1233,59 @@ impl Render for ProjectDiffToolbar {
                             })),
                     ),
             )
-            .child(vertical_divider())
-            .child(
-                h_group_sm()
-                    .when(
-                        button_states.unstage_all && !button_states.stage_all,
-                        |el| {
-                            el.child(
-                                Button::new("unstage-all", "Unstage All")
-                                    .tooltip(Tooltip::for_action_title_in(
-                                        "Unstage all changes",
-                                        &UnstageAll,
-                                        &focus_handle,
-                                    ))
-                                    .on_click(cx.listener(|this, _, window, cx| {
-                                        this.unstage_all(window, cx)
-                                    })),
-                            )
-                        },
-                    )
-                    .when(
-                        !button_states.unstage_all || button_states.stage_all,
-                        |el| {
-                            el.child(
-                                // todo make it so that changing to say "Unstaged"
-                                // doesn't change the position.
-                                div().child(
-                                    Button::new("stage-all", "Stage All")
-                                        .disabled(!button_states.stage_all)
+            .when(!is_compare, |el| {
+                el.child(vertical_divider()).child(
+                    h_group_sm()
+                        .when(
+                            button_states.unstage_all && !button_states.stage_all,
+                            |el| {
+                                el.child(
+                                    Button::new("unstage-all", "Unstage All")
                                         .tooltip(Tooltip::for_action_title_in(
-                                            "Stage all changes",
-                                            &StageAll,
+                                            "Unstage all changes",
+                                            &UnstageAll,
                                             &focus_handle,
                                         ))
                                         .on_click(cx.listener(|this, _, window, cx| {
-                                            this.stage_all(window, cx)
+                                            this.unstage_all(window, cx)
                                         })),
-                                ),
-                            )
-                        },
-                    )
-                    .child(
-                        Button::new("commit", "Commit")
-                            .tooltip(Tooltip::for_action_title_in(
-                                "Commit",
-                                &Commit,
-                                &focus_handle,
-                            ))
-                            .on_click(cx.listener(|this, _, window, cx| {
-                                this.dispatch_action(&Commit, window, cx);
-                            })),
-                    ),
-            )
+                                )
+                            },
+                        )
+                        .when(
+                            !button_states.unstage_all || button_states.stage_all,
+                            |el| {
+                                el.child(
+                                    // todo make it so that changing to say "Unstaged"
+                                    // doesn't change the position.
+                                    div().child(
+                                        Button::new("stage-all", "Stage All")
+                                            .disabled(!button_states.stage_all)
+                                            .tooltip(Tooltip::for_action_title_in(
+                                                "Stage all changes",
+                                                &StageAll,
+                                                &focus_handle,
+                                            ))
+                                            .on_click(cx.listener(|this, _, window, cx| {
+                                                this.stage_all(window, cx)
+                                            })),
+                                    ),
+                                )
+                            },
+                        )
+                        .child(
+                            Button::new("commit", "Commit")
+                                .tooltip(Tooltip::for_action_title_in(
+                                    "Commit",
+                                    &Commit,
+                                    &focus_handle,
+                                ))
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.dispatch_action(&Commit, window, cx);
+                                })),
+                        ),
+                )
+            })
     }
 }
 
@@ -1380,7 +1634,7 @@ mod tests {
         let (workspace, cx) =
             cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
         let diff = cx.new_window_entity(|window, cx| {
-            ProjectDiff::new(project.clone(), workspace, window, cx)
+            ProjectDiff::new(project.clone(), workspace, None, window, cx)
         });
         cx.run_until_parked();
 
@@ -1435,7 +1689,7 @@ mod tests {
         let (workspace, cx) =
             cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
         let diff = cx.new_window_entity(|window, cx| {
-            ProjectDiff::new(project.clone(), workspace, window, cx)
+            ProjectDiff::new(project.clone(), workspace, None, window, cx)
         });
         cx.run_until_parked();
 
@@ -1517,7 +1771,7 @@ mod tests {
             Editor::for_buffer(buffer, Some(project.clone()), window, cx)
         });
         let diff = cx.new_window_entity(|window, cx| {
-            ProjectDiff::new(project.clone(), workspace, window, cx)
+            ProjectDiff::new(project.clone(), workspace, None, window, cx)
         });
         cx.run_until_parked();
 
@@ -1800,7 +2054,7 @@ mod tests {
         let (workspace, cx) =
             cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
         let diff = cx.new_window_entity(|window, cx| {
-            ProjectDiff::new(project.clone(), workspace, window, cx)
+            ProjectDiff::new(project.clone(), workspace, None, window, cx)
         });
         cx.run_until_parked();
 
@@ -0,0 +1,424 @@
+use editor::Editor;
+use git::repository::{CommitLogEntry, LogOptions, RebaseAction, RebaseTodoEntry};
+use gpui::{
+    App, ClickEvent, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ScrollHandle,
+    Task, actions,
+};
+use menu::{SelectNext, SelectPrevious};
+use project::git_store::Repository;
+use ui::{ContextMenu, DropdownMenu, prelude::*};
+use util::ResultExt as _;
+use workspace::ModalView;
+
+actions!(
+    git_rebase_editor,
+    [
+        PickCommit,
+        RewordCommit,
+        SquashCommit,
+        FixupCommit,
+        DropCommit,
+        MoveUp,
+        MoveDown
+    ]
+);
+
+/// The most commits that can be rewritten by a single interactive rebase from the editor.
+const MAX_COMMITS: usize = 500;
+
+const REBASE_ACTIONS: [RebaseAction; 5] = [
+    RebaseAction::Pick,
+    RebaseAction::Reword,
+    RebaseAction::Squash,
+    RebaseAction::Fixup,
+    RebaseAction::Drop,
+];
+
+struct TodoRow {
+    commit: CommitLogEntry,
+    action: RebaseAction,
+    /// Created the first time the commit is reworded, so that switching back and forth
+    /// between actions keeps the edited message.
+    message_editor: Option<Entity<Editor>>,
+}
+
+/// A modal for editing the todo list of an interactive rebase: commits after the base are
+/// listed oldest first, and can be reordered, reworded, squashed, fixed up or dropped.
+pub struct RebaseEditor {
+    repository: Entity<Repository>,
+    base: CommitLogEntry,
+    rows: Vec<TodoRow>,
+    selected_ix: usize,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+    error: Option<SharedString>,
+    on_confirm: Option<Box<dyn FnOnce(String, Vec<RebaseTodoEntry>, &mut Window, &mut App)>>,
+    _load: Task<()>,
+}
+
+impl RebaseEditor {
+    pub fn new(
+        repository: Entity<Repository>,
+        base: CommitLogEntry,
+        on_confirm: impl FnOnce(String, Vec<RebaseTodoEntry>, &mut Window, &mut App) + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let log = repository.update(cx, |repository, _| {
+            repository.log(LogOptions {
+                revision_range: Some(format!("{}..HEAD", base.sha)),
+                limit: MAX_COMMITS + 1,
+                ..Default::default()
+            })
+        });
+        let load = cx.spawn_in(window, async move |this, cx| {
+            let result = log
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(commits) if commits.len() > MAX_COMMITS => {
+                        this.error = Some(
+                            format!("More than {MAX_COMMITS} commits would be rebased").into(),
+                        );
+                    }
+                    Ok(commits) => {
+                        // Like `git rebase` without `--rebase-merges`, merge commits are
+                        // left out of the todo list.
+                        this.rows = commits
+                            .into_iter()
+                            .rev()
+                            .filter(|commit| commit.parent_shas.len() <= 1)
+                            .map(|commit| TodoRow {
+                                commit,
+                                action: RebaseAction::Pick,
+                                message_editor: None,
+                            })
+                            .collect();
+                        if this.rows.is_empty() {
+                            this.error = Some("There are no commits to rebase".into());
+                        }
+                    }
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .log_err();
+        });
+
+        Self {
+            repository,
+            base,
+            rows: Vec::new(),
+            selected_ix: 0,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+            error: None,
+            on_confirm: Some(Box::new(on_confirm)),
+            _load: load,
+        }
+    }
+
+    fn set_action(
+        &mut self,
+        ix: usize,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(row) = self.rows.get_mut(ix) else {
+            return;
+        };
+        row.action = action;
+        if action == RebaseAction::Reword && row.message_editor.is_none() {
+            let editor = cx.new(|cx| {
+                let mut editor = Editor::auto_height(8, window, cx);
+                editor.set_text(row.commit.subject.to_string(), window, cx);
+                editor
+            });
+            // The log only includes subjects, so load the whole message to edit.
+            let show = self.repository.update(cx, |repository, _| {
+                repository.show(row.commit.sha.to_string())
+            });
+            cx.spawn_in(window, {
+                let editor = editor.downgrade();
+                async move |_, cx| {
+                    let details = show.await.ok()?.log_err()?;
+                    editor
+                        .update_in(cx, |editor, window, cx| {
+                            editor.set_text(details.message.trim_end().to_string(), window, cx);
+                        })
+                        .ok()
+                }
+            })
+            .detach();
+            row.message_editor = Some(editor);
+        }
+        cx.notify();
+    }
+
+    fn set_selected_action(
+        &mut self,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_action(self.selected_ix, action, window, cx);
+    }
+
+    fn pick_commit(&mut self, _: &PickCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_selected_action(RebaseAction::Pick, window, cx);
+    }
+
+    fn reword_commit(&mut self, _: &RewordCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_selected_action(RebaseAction::Reword, window, cx);
+    }
+
+    fn squash_commit(&mut self, _: &SquashCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_selected_action(RebaseAction::Squash, window, cx);
+    }
+
+    fn fixup_commit(&mut self, _: &FixupCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_selected_action(RebaseAction::Fixup, window, cx);
+    }
+
+    fn drop_commit(&mut self, _: &DropCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_selected_action(RebaseAction::Drop, window, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_ix > 0 && self.selected_ix < self.rows.len() {
+            self.rows.swap(self.selected_ix, self.selected_ix - 1);
+            self.select_entry(self.selected_ix - 1, cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_ix + 1 < self.rows.len() {
+            self.rows.swap(self.selected_ix, self.selected_ix + 1);
+            self.select_entry(self.selected_ix + 1, cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_ix + 1 < self.rows.len() {
+            self.select_entry(self.selected_ix + 1, cx);
+        }
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_ix > 0 {
+            self.select_entry(self.selected_ix - 1, cx);
+        }
+    }
+
+    fn select_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_ix = ix;
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    /// Why the todo list can't be run as it is, if there's a reason.
+    fn validation_error(&self) -> Option<&'static str> {
+        let first_kept = self
+            .rows
+            .iter()
+            .find(|row| row.action != RebaseAction::Drop)?;
+        if first_kept.action.melds_into_previous() {
+            return Some("The first commit can't be squashed or fixed up");
+        }
+        None
+    }
+
+    fn todo(&self, cx: &App) -> Vec<RebaseTodoEntry> {
+        self.rows
+            .iter()
+            .map(|row| RebaseTodoEntry {
+                action: row.action,
+                sha: row.commit.sha.clone(),
+                message: row
+                    .message_editor
+                    .as_ref()
+                    .filter(|_| row.action == RebaseAction::Reword)
+                    .map(|editor| editor.read(cx).text(cx)),
+            })
+            .collect()
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.rows.is_empty() || self.validation_error().is_some() {
+            return;
+        }
+        let todo = self.todo(cx);
+        if let Some(on_confirm) = self.on_confirm.take() {
+            on_confirm(self.base.sha.to_string(), todo, window, cx);
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_row(&self, ix: usize, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
+        let row = &self.rows[ix];
+        let selected = ix == self.selected_ix;
+        let this = cx.entity().downgrade();
+        let action_menu = ContextMenu::build(window, cx, |mut menu, _, _| {
+            for action in REBASE_ACTIONS {
+                let this = this.clone();
+                menu = menu.entry(action.as_str(), None, move |window, cx| {
+                    this.update(cx, |this, cx| this.set_action(ix, action, window, cx))
+                        .ok();
+                });
+            }
+            menu
+        });
+        let dropped = row.action == RebaseAction::Drop;
+
+        h_flex()
+            .id(("rebase-todo", ix))
+            .w_full()
+            .px_2()
+            .py_0p5()
+            .gap_2()
+            .when(selected, |this| {
+                this.bg(cx.theme().colors().ghost_element_selected)
+            })
+            .hover(|this| this.bg(cx.theme().colors().ghost_element_hover))
+            .child(div().w(rems(6.)).child(DropdownMenu::new(
+                ("rebase-action", ix),
+                row.action.as_str(),
+                action_menu,
+            )))
+            .child(
+                Label::new(row.commit.short_sha().to_string())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+            .child(
+                div().flex_1().min_w_0().child(
+                    Label::new(row.commit.subject.clone())
+                        .truncate()
+                        .when(dropped, |label| {
+                            label.strikethrough().color(Color::Disabled)
+                        }),
+                ),
+            )
+            .on_click(cx.listener(move |this, _: &ClickEvent, window, cx| {
+                this.select_entry(ix, cx);
+                this.focus_handle.focus(window);
+            }))
+            .into_any_element()
+    }
+}
+
+impl ModalView for RebaseEditor {}
+
+impl EventEmitter<DismissEvent> for RebaseEditor {}
+
+impl Focusable for RebaseEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for RebaseEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let message_editor = self
+            .rows
+            .get(self.selected_ix)
+            .filter(|row| row.action == RebaseAction::Reword)
+            .and_then(|row| row.message_editor.clone());
+        let error = self
+            .error
+            .clone()
+            .or_else(|| self.validation_error().map(SharedString::from));
+        let can_confirm = !self.rows.is_empty() && error.is_none();
+        let rows = (0..self.rows.len())
+            .map(|ix| self.render_row(ix, window, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .key_context("GitRebaseEditor")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .w(rems(40.))
+            .elevation_2(cx)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new("Rebase onto").color(Color::Muted))
+                    .child(
+                        Label::new(self.base.short_sha().to_string())
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    )
+                    .child(
+                        div()
+                            .min_w_0()
+                            .child(Label::new(self.base.subject.clone()).truncate()),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("rebase-todo-list")
+                    .key_context("GitRebaseTodoList")
+                    .track_focus(&self.focus_handle)
+                    .track_scroll(&self.scroll_handle)
+                    .max_h(rems(24.))
+                    .overflow_y_scroll()
+                    .py_1()
+                    .on_action(cx.listener(Self::select_next))
+                    .on_action(cx.listener(Self::select_previous))
+                    .on_action(cx.listener(Self::move_up))
+                    .on_action(cx.listener(Self::move_down))
+                    .on_action(cx.listener(Self::pick_commit))
+                    .on_action(cx.listener(Self::reword_commit))
+                    .on_action(cx.listener(Self::squash_commit))
+                    .on_action(cx.listener(Self::fixup_commit))
+                    .on_action(cx.listener(Self::drop_commit))
+                    .children(rows),
+            )
+            .when_some(message_editor, |this, editor| {
+                this.child(
+                    div()
+                        .px_2()
+                        .py_1()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(editor),
+                )
+            })
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(div().flex_1().min_w_0().children(
+                        error.map(|error| {
+                            Label::new(error).size(LabelSize::Small).color(Color::Error)
+                        }),
+                    ))
+                    .child(Button::new("cancel-rebase", "Cancel").on_click(
+                        cx.listener(|this, _, window, cx| this.cancel(&menu::Cancel, window, cx)),
+                    ))
+                    .child(
+                        Button::new("start-rebase", "Rebase")
+                            .style(ButtonStyle::Filled)
+                            .disabled(!can_confirm)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.confirm(&menu::Confirm, window, cx)
+                            })),
+                    ),
+            )
+    }
+}
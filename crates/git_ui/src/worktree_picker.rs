@@ -0,0 +1,598 @@
+use anyhow::Context as _;
+use fuzzy::StringMatchCandidate;
+
+use collections::HashSet;
+use git::repository::{Branch, Worktree};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
+    IntoElement, ParentElement, PromptLevel, Render, SharedString, Styled, Subscription, Task,
+    WeakEntity, Window, actions, rems,
+};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use recent_projects::open_ssh_project;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use ui::{HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, OpenOptions, Workspace};
+
+actions!(worktree_picker, [RemoveWorktree]);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &git::Worktrees,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx).clone();
+    let workspace_handle = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        WorktreeList::new(repository, workspace_handle, window, cx)
+    })
+}
+
+pub struct WorktreeList {
+    picker: Entity<Picker<WorktreeListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl WorktreeList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = WorktreeListDelegate::new(repository, workspace);
+        let picker = cx.new(|cx| {
+            let picker = Picker::uniform_list(delegate, window, cx);
+            picker.delegate.load(window, cx);
+            picker
+        });
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+
+    fn remove_worktree(&mut self, _: &RemoveWorktree, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker.delegate.remove_selected_worktree(window, cx)
+        })
+    }
+}
+
+impl ModalView for WorktreeList {}
+impl EventEmitter<DismissEvent> for WorktreeList {}
+
+impl Focusable for WorktreeList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for WorktreeList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("GitWorktreeSelector")
+            .w(rems(34.))
+            .on_action(cx.listener(Self::remove_worktree))
+            .child(self.picker.clone())
+            .on_mouse_down_out({
+                cx.listener(move |this, _, window, cx| {
+                    this.picker.update(cx, |this, cx| {
+                        this.cancel(&Default::default(), window, cx);
+                    })
+                })
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum WorktreeEntry {
+    Worktree(Worktree),
+    /// A local branch that isn't checked out in any working tree yet.
+    Branch(Branch),
+    /// A branch named after the query, that will be created at HEAD.
+    NewBranch(SharedString),
+}
+
+impl WorktreeEntry {
+    fn name(&self) -> SharedString {
+        match self {
+            WorktreeEntry::Worktree(worktree) => match worktree.branch_name() {
+                Some(branch_name) => branch_name.to_string().into(),
+                None => worktree
+                    .sha
+                    .chars()
+                    .take(git::SHORT_SHA_LENGTH)
+                    .collect::<String>()
+                    .into(),
+            },
+            WorktreeEntry::Branch(branch) => branch.name().to_string().into(),
+            WorktreeEntry::NewBranch(name) => name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct WorktreeMatch {
+    entry: WorktreeEntry,
+    positions: Vec<usize>,
+}
+
+pub struct WorktreeListDelegate {
+    matches: Vec<WorktreeMatch>,
+    /// Every working tree, followed by the local branches that can get one.
+    all_entries: Option<Vec<WorktreeEntry>>,
+    /// The main working tree, next to which new working trees are created.
+    main_path: Option<PathBuf>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+}
+
+impl WorktreeListDelegate {
+    fn new(repo: Option<Entity<Repository>>, workspace: WeakEntity<Workspace>) -> Self {
+        Self {
+            matches: Vec::new(),
+            all_entries: None,
+            main_path: None,
+            repo,
+            workspace,
+            selected_index: 0,
+        }
+    }
+
+    fn load(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let requests = self
+            .repo
+            .clone()
+            .map(|repo| repo.update(cx, |repo, _| (repo.worktrees(), repo.branches())));
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let (worktrees, branches) = requests.context("No active repository")?;
+            let worktrees = worktrees.await??;
+            let mut branches = branches.await??;
+
+            let checked_out = worktrees
+                .iter()
+                .filter_map(|worktree| worktree.ref_name.clone())
+                .collect::<HashSet<_>>();
+            branches
+                .retain(|branch| !branch.is_remote() && !checked_out.contains(&branch.ref_name));
+            branches.sort_by_key(|branch| {
+                branch
+                    .most_recent_commit
+                    .as_ref()
+                    .map(|commit| 0 - commit.commit_timestamp)
+            });
+
+            let main_path = worktrees
+                .iter()
+                .find(|worktree| worktree.is_main)
+                .map(|worktree| worktree.path.clone());
+            let entries = worktrees
+                .into_iter()
+                .map(WorktreeEntry::Worktree)
+                .chain(branches.into_iter().map(WorktreeEntry::Branch))
+                .collect();
+
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.all_entries = Some(entries);
+                picker.delegate.main_path = main_path;
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn selected_worktree(&self) -> Option<&Worktree> {
+        match &self.matches.get(self.selected_index)?.entry {
+            WorktreeEntry::Worktree(worktree) => Some(worktree),
+            _ => None,
+        }
+    }
+
+    /// Where a new working tree for `branch_name` is created: next to the main one, named after
+    /// both of them.
+    fn path_for_branch(&self, branch_name: &str) -> Option<PathBuf> {
+        let main_path = self.main_path.as_ref()?;
+        Some(worktree_path_for_branch(main_path, branch_name))
+    }
+
+    fn is_current(&self, worktree: &Worktree, cx: &App) -> bool {
+        self.repo.as_ref().is_some_and(|repo| {
+            repo.read(cx).work_directory_abs_path.as_ref() == worktree.path.as_path()
+        })
+    }
+
+    fn create_and_open(
+        &self,
+        branch_name: SharedString,
+        create_branch: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let Some(path) = self.path_for_branch(&branch_name) else {
+            return;
+        };
+        let create = repo.update(cx, |repo, _| {
+            repo.create_worktree(path.clone(), branch_name.to_string(), create_branch)
+        });
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            create.await??;
+            cx.update(|window, cx| open_worktree(&workspace, path, window, cx))?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to create worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn remove_selected_worktree(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(worktree) = self.selected_worktree().cloned() else {
+            return;
+        };
+        if worktree.is_main || self.is_current(&worktree, cx) {
+            return;
+        }
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        cx.spawn_in(window, async move |picker, cx| {
+            if worktree.has_changes {
+                let answer = cx.update(|window, cx| {
+                    window.prompt(
+                        PromptLevel::Warning,
+                        &format!(
+                            "The worktree at {} has uncommitted changes.",
+                            worktree.path.display()
+                        ),
+                        Some("Removing it discards them."),
+                        &["Force Remove", "Cancel"],
+                        cx,
+                    )
+                })?;
+                if answer.await != Ok(0) {
+                    return Ok(());
+                }
+            }
+            repo.update(cx, |repo, _| {
+                repo.remove_worktree(worktree.path.clone(), worktree.has_changes)
+            })?
+            .await??;
+
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.load(window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to remove worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+}
+
+fn worktree_path_for_branch(main_path: &Path, branch_name: &str) -> PathBuf {
+    let main_name = main_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let suffix = branch_name.replace(['/', '\\'], "-");
+    main_path.with_file_name(format!("{main_name}-{suffix}"))
+}
+
+/// Opens a working tree in a new window, connecting to the same SSH host as the current project
+/// when it's remote. Working trees of a shared project live on the host, so guests can't open
+/// them.
+fn open_worktree(
+    workspace: &WeakEntity<Workspace>,
+    path: PathBuf,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(workspace) = workspace.upgrade() else {
+        return;
+    };
+    let workspace = workspace.read(cx);
+    let app_state = workspace.app_state().clone();
+    if workspace.project().read(cx).is_via_collab() {
+        log::error!("can't open {path:?}, as it's on the host of a shared project");
+    } else if let Some(connection_options) = workspace.project().read(cx).ssh_connection_options(cx)
+    {
+        window
+            .spawn(cx, async move |cx| {
+                open_ssh_project(
+                    connection_options,
+                    vec![path],
+                    app_state,
+                    OpenOptions::default(),
+                    cx,
+                )
+                .await
+            })
+            .detach_and_log_err(cx);
+    } else {
+        workspace::open_paths(
+            &[path],
+            app_state,
+            OpenOptions {
+                open_new_workspace: Some(true),
+                ..Default::default()
+            },
+            cx,
+        )
+        .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for WorktreeListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select a worktree or a branch to check out in a new one...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_entries) = self.all_entries.clone() else {
+            return Task::ready(());
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let mut matches: Vec<WorktreeMatch> = if query.is_empty() {
+                all_entries
+                    .into_iter()
+                    .map(|entry| WorktreeMatch {
+                        entry,
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let candidates = all_entries
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, entry)| StringMatchCandidate::new(ix, &entry.name()))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| WorktreeMatch {
+                    entry: all_entries[candidate.candidate_id].clone(),
+                    positions: candidate.positions,
+                })
+                .collect()
+            };
+
+            let query = query.trim().replace(' ', "-");
+            if !query.is_empty()
+                && !matches
+                    .iter()
+                    .any(|m| m.entry.name().as_ref() == query.as_str())
+            {
+                matches.push(WorktreeMatch {
+                    entry: WorktreeEntry::NewBranch(query.into()),
+                    positions: Vec::new(),
+                });
+            }
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self
+            .matches
+            .get(self.selected_index)
+            .map(|m| m.entry.clone())
+        else {
+            return;
+        };
+        match entry {
+            WorktreeEntry::Worktree(worktree) => {
+                if !self.is_current(&worktree, cx) {
+                    open_worktree(&self.workspace, worktree.path, window, cx);
+                }
+                cx.emit(DismissEvent);
+            }
+            WorktreeEntry::Branch(branch) => {
+                self.create_and_open(branch.name().to_string().into(), false, window, cx);
+            }
+            WorktreeEntry::NewBranch(name) => {
+                self.create_and_open(name, true, window, cx);
+            }
+        }
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let worktree_match = &self.matches[ix];
+        let name = worktree_match.entry.name();
+
+        let (label, detail, tag) = match &worktree_match.entry {
+            WorktreeEntry::Worktree(worktree) => {
+                let tag = if self.is_current(worktree, cx) {
+                    Some("current")
+                } else if worktree.is_main {
+                    Some("main")
+                } else if worktree.is_locked {
+                    Some("locked")
+                } else {
+                    None
+                };
+                (
+                    HighlightedLabel::new(name, worktree_match.positions.clone())
+                        .truncate()
+                        .into_any_element(),
+                    Some(worktree.path.to_string_lossy().to_string()),
+                    tag,
+                )
+            }
+            WorktreeEntry::Branch(branch) => (
+                HighlightedLabel::new(name, worktree_match.positions.clone())
+                    .truncate()
+                    .into_any_element(),
+                self.path_for_branch(branch.name())
+                    .map(|path| format!("new worktree at {}", path.display())),
+                None,
+            ),
+            WorktreeEntry::NewBranch(name) => (
+                Label::new(format!("Create branch \"{name}\" in a new worktree…"))
+                    .single_line()
+                    .into_any_element(),
+                self.path_for_branch(name)
+                    .map(|path| format!("at {}", path.display())),
+                None,
+            ),
+        };
+
+        Some(
+            ListItem::new(SharedString::from(format!("worktree-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(
+                    Icon::new(match &worktree_match.entry {
+                        WorktreeEntry::Worktree(_) => IconName::Folder,
+                        WorktreeEntry::Branch(_) | WorktreeEntry::NewBranch(_) => {
+                            IconName::GitBranch
+                        }
+                    })
+                    .color(Color::Muted),
+                )
+                .child(
+                    v_flex()
+                        .w_full()
+                        .child(
+                            h_flex()
+                                .w_full()
+                                .gap_2()
+                                .justify_between()
+                                .child(div().flex_shrink().overflow_x_hidden().child(label))
+                                .when_some(tag, |el, tag| {
+                                    el.child(
+                                        Label::new(tag).size(LabelSize::Small).color(Color::Muted),
+                                    )
+                                }),
+                        )
+                        .when_some(detail, |el, detail| {
+                            el.child(
+                                Label::new(detail)
+                                    .size(LabelSize::Small)
+                                    .truncate()
+                                    .color(Color::Muted),
+                            )
+                        }),
+                ),
+        )
+    }
+
+    fn render_footer(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        let worktree = self.selected_worktree()?;
+        let can_remove = !worktree.is_main && !self.is_current(worktree, cx);
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("remove-worktree", "Remove")
+                        .label_size(LabelSize::Small)
+                        .disabled(!can_remove)
+                        .key_binding(KeyBinding::for_action(&RemoveWorktree, window, cx))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(Box::new(RemoveWorktree), cx)
+                        }),
+                )
+                .into_any_element(),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worktree_path_for_branch() {
+        assert_eq!(
+            worktree_path_for_branch(Path::new("/code/zed"), "feature/fix-panic"),
+            PathBuf::from("/code/zed-feature-fix-panic")
+        );
+    }
+}
@@ -1130,9 +1130,12 @@ impl Window {
     }
 }
 
+/// The outcome of dispatching an event with [`Window::dispatch_event`].
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
-pub(crate) struct DispatchEventResult {
+pub struct DispatchEventResult {
+    /// Whether the event was left to propagate, i.e. no handler stopped it.
     pub propagate: bool,
+    /// Whether a handler called `prevent_default` on the event.
     pub default_prevented: bool,
 }
 
@@ -1,30 +1,140 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Output;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
 use gpui::SharedString;
 use jj_lib::config::StackedConfig;
-use jj_lib::repo::StoreFactories;
+use jj_lib::repo::{ReadonlyRepo, RepoLoader, StoreFactories};
 use jj_lib::settings::UserSettings;
 use jj_lib::workspace::{self, DefaultWorkspaceLoaderFactory, WorkspaceLoaderFactory};
+use parking_lot::Mutex;
+use util::ResultExt as _;
+use util::command::new_std_command;
+
+/// The revset used by `jj log` when the user hasn't configured one.
+pub const DEFAULT_LOG_REVSET: &str =
+    "present(@) | ancestors(immutable_heads().., 2) | present(trunk())";
 
 #[derive(Debug, Clone)]
 pub struct Bookmark {
     pub ref_name: SharedString,
 }
 
+/// A single change (revision) in a Jujutsu repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub change_id: SharedString,
+    pub commit_id: SharedString,
+    /// The commit IDs of this change's parents.
+    pub parent_commit_ids: Vec<SharedString>,
+    pub description: SharedString,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+    /// The author timestamp, in seconds since the Unix epoch.
+    pub author_timestamp: i64,
+    pub bookmarks: Vec<SharedString>,
+    pub is_working_copy: bool,
+    pub is_empty: bool,
+    pub is_immutable: bool,
+    pub has_conflict: bool,
+}
+
+impl Change {
+    /// The first line of the description.
+    pub fn summary(&self) -> &str {
+        self.description.lines().next().unwrap_or_default()
+    }
+
+    /// The change ID abbreviated the way `jj log` abbreviates it by default.
+    pub fn short_change_id(&self) -> &str {
+        &self.change_id[..self.change_id.len().min(8)]
+    }
+
+    pub fn short_commit_id(&self) -> &str {
+        &self.commit_id[..self.commit_id.len().min(8)]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChangeKind {
+    Added,
+    Modified,
+    Removed,
+    Conflicted,
+}
+
+/// A file that differs between a change and its parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: String,
+    pub kind: FileChangeKind,
+}
+
 pub trait JujutsuRepository: Send + Sync {
     fn list_bookmarks(&self) -> Vec<Bookmark>;
+
+    /// Lists the changes matched by `revset`, newest first.
+    fn log(&self, revset: &str, limit: Option<usize>) -> Result<Vec<Change>>;
+
+    /// Replaces the description of `revision` with `message`.
+    fn describe(&self, revision: &str, message: &str) -> Result<()>;
+
+    /// Creates a new, empty change on top of `parents` and makes it the working-copy change.
+    fn new_change(&self, parents: &[&str], message: Option<&str>) -> Result<()>;
+
+    /// Makes `revision` the working-copy change.
+    fn edit(&self, revision: &str) -> Result<()>;
+
+    /// Moves the changes in `revision` into `destination`, or into its parent when no
+    /// destination is given.
+    fn squash(&self, revision: &str, destination: Option<&str>) -> Result<()>;
+
+    fn abandon(&self, revisions: &[&str]) -> Result<()>;
+
+    fn create_bookmark(&self, name: &str, revision: &str) -> Result<()>;
+
+    fn move_bookmark(&self, name: &str, revision: &str, allow_backwards: bool) -> Result<()>;
+
+    fn delete_bookmark(&self, name: &str) -> Result<()>;
+
+    /// Whether the workspace shares its work directory with a git repository.
+    fn is_colocated(&self) -> bool;
+
+    /// Lists the files that the working-copy change modifies relative to its parent, as paths
+    /// relative to the workspace root.
+    ///
+    /// Unlike other reads, this snapshots the working copy first, like `jj status` does, so
+    /// that it includes the edits made since the last jj command.
+    fn working_copy_changes(&self) -> Result<Vec<FileChange>>;
+
+    /// Lists the files with conflicts in the working-copy change.
+    fn conflicted_paths(&self) -> Result<Vec<String>>;
+
+    /// Returns the contents of `path` (relative to the workspace root) at `revision`, or `None`
+    /// if the file doesn't exist there.
+    fn file_text(&self, revision: &str, path: &str) -> Result<Option<String>>;
 }
 
 pub struct RealJujutsuRepository {
-    repository: Arc<jj_lib::repo::ReadonlyRepo>,
+    workspace_root: PathBuf,
+    jj_binary_path: PathBuf,
+    /// The environment that the `jj` binary runs in.
+    environment: Arc<HashMap<String, String>>,
+    repo_loader: RepoLoader,
+    repository: Mutex<Arc<ReadonlyRepo>>,
 }
 
 impl RealJujutsuRepository {
-    pub fn new(cwd: &Path) -> Result<Self> {
+    pub fn new(
+        cwd: &Path,
+        jj_binary_path: Option<PathBuf>,
+        environment: Arc<HashMap<String, String>>,
+    ) -> Result<Self> {
+        let workspace_root = Self::find_workspace_dir(cwd);
         let workspace_loader_factory = DefaultWorkspaceLoaderFactory;
-        let workspace_loader = workspace_loader_factory.create(Self::find_workspace_dir(cwd))?;
+        let workspace_loader = workspace_loader_factory.create(workspace_root)?;
 
         let config = StackedConfig::with_defaults();
         let settings = UserSettings::from_config(config)?;
@@ -35,10 +145,16 @@ impl RealJujutsuRepository {
             &workspace::default_working_copy_factories(),
         )?;
 
-        let repo_loader = workspace.repo_loader();
+        let repo_loader = workspace.repo_loader().clone();
         let repository = repo_loader.load_at_head()?;
 
-        Ok(Self { repository })
+        Ok(Self {
+            workspace_root: workspace_root.to_path_buf(),
+            jj_binary_path: jj_binary_path.unwrap_or_else(|| PathBuf::from("jj")),
+            environment,
+            repo_loader,
+            repository: Mutex::new(repository),
+        })
     }
 
     fn find_workspace_dir(cwd: &Path) -> &Path {
@@ -46,12 +162,63 @@ impl RealJujutsuRepository {
             .find(|path| path.join(".jj").is_dir())
             .unwrap_or(cwd)
     }
+
+    /// Runs the `jj` binary in the workspace root and returns its standard output.
+    ///
+    /// Mutating operations go through the CLI rather than `jj-lib` so that they snapshot the
+    /// working copy, run hooks and record operations exactly like they would in a terminal.
+    fn run(&self, args: &[&str], snapshot: bool) -> Result<String> {
+        let output = self.output(args, snapshot)?;
+        anyhow::ensure!(
+            output.status.success(),
+            "jj {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    /// Runs the `jj` binary. Unless `snapshot` is set, the working copy is left alone, so that
+    /// the command doesn't record an operation that could race with the user's own commands.
+    fn output(&self, args: &[&str], snapshot: bool) -> Result<Output> {
+        let mut command = new_std_command(&self.jj_binary_path);
+        command
+            .envs(self.environment.iter())
+            .current_dir(&self.workspace_root)
+            .args(["--no-pager", "--color=never"]);
+        if !snapshot {
+            command.arg("--ignore-working-copy");
+        }
+        command.args(args).output().context("failed to run jj")
+    }
+
+    /// Runs a read-only command.
+    fn query(&self, args: &[&str]) -> Result<String> {
+        self.run(args, false)
+    }
+
+    /// Runs a command that rewrites the repository, then reloads the view at the new operation.
+    fn run_mutation(&self, args: &[&str]) -> Result<()> {
+        self.run(args, true)?;
+        self.reload()
+    }
+
+    /// Reloads the view at the current operation head, to pick up operations recorded since it
+    /// was loaded.
+    fn reload(&self) -> Result<()> {
+        let repository = self.repo_loader.load_at_head()?;
+        *self.repository.lock() = repository;
+        Ok(())
+    }
 }
 
 impl JujutsuRepository for RealJujutsuRepository {
     fn list_bookmarks(&self) -> Vec<Bookmark> {
+        // Bookmarks may have moved through `jj` outside of Zed since the view was loaded.
+        self.reload().log_err();
         let bookmarks = self
             .repository
+            .lock()
             .view()
             .bookmarks()
             .map(|(ref_name, _target)| Bookmark {
@@ -61,12 +228,692 @@ impl JujutsuRepository for RealJujutsuRepository {
 
         bookmarks
     }
+
+    fn log(&self, revset: &str, limit: Option<usize>) -> Result<Vec<Change>> {
+        let limit = limit.map(|limit| limit.to_string());
+        let mut args = vec!["log", "--no-graph", "-r", revset, "-T", LOG_TEMPLATE];
+        if let Some(limit) = limit.as_deref() {
+            args.extend(["--limit", limit]);
+        }
+
+        let output = self.query(&args)?;
+        parse_log_output(&output)
+    }
+
+    fn describe(&self, revision: &str, message: &str) -> Result<()> {
+        self.run_mutation(&["describe", revision, "-m", message])
+    }
+
+    fn new_change(&self, parents: &[&str], message: Option<&str>) -> Result<()> {
+        let mut args = vec!["new"];
+        args.extend_from_slice(parents);
+        if let Some(message) = message {
+            args.extend(["-m", message]);
+        }
+        self.run_mutation(&args)
+    }
+
+    fn edit(&self, revision: &str) -> Result<()> {
+        self.run_mutation(&["edit", revision])
+    }
+
+    fn squash(&self, revision: &str, destination: Option<&str>) -> Result<()> {
+        // Without a destination, `-r` lets jj pick the parent, and reject merge changes.
+        let mut args = vec!["squash", "--use-destination-message"];
+        match destination {
+            Some(destination) => args.extend(["--from", revision, "--into", destination]),
+            None => args.extend(["-r", revision]),
+        }
+        self.run_mutation(&args)
+    }
+
+    fn abandon(&self, revisions: &[&str]) -> Result<()> {
+        let mut args = vec!["abandon"];
+        args.extend_from_slice(revisions);
+        self.run_mutation(&args)
+    }
+
+    fn create_bookmark(&self, name: &str, revision: &str) -> Result<()> {
+        self.run_mutation(&["bookmark", "create", name, "-r", revision])
+    }
+
+    fn move_bookmark(&self, name: &str, revision: &str, allow_backwards: bool) -> Result<()> {
+        let mut args = vec!["bookmark", "set", name, "-r", revision];
+        if allow_backwards {
+            args.push("--allow-backwards");
+        }
+        self.run_mutation(&args)
+    }
+
+    fn delete_bookmark(&self, name: &str) -> Result<()> {
+        self.run_mutation(&["bookmark", "delete", name])
+    }
+
+    fn is_colocated(&self) -> bool {
+        self.workspace_root.join(".git").exists()
+    }
+
+    fn working_copy_changes(&self) -> Result<Vec<FileChange>> {
+        let mut changes = parse_diff_summary(&self.run(&["diff", "--summary", "-r", "@"], true)?)?;
+        // Snapshotting may have recorded a new operation.
+        self.reload()?;
+        for path in self.conflicted_paths()? {
+            match changes.iter_mut().find(|change| change.path == path) {
+                Some(change) => change.kind = FileChangeKind::Conflicted,
+                None => changes.push(FileChange {
+                    path,
+                    kind: FileChangeKind::Conflicted,
+                }),
+            }
+        }
+        Ok(changes)
+    }
+
+    fn conflicted_paths(&self) -> Result<Vec<String>> {
+        let has_conflict = self
+            .log("@", Some(1))?
+            .first()
+            .is_some_and(|change| change.has_conflict);
+        if !has_conflict {
+            return Ok(Vec::new());
+        }
+        Ok(parse_resolve_list(
+            &self.query(&["resolve", "--list", "-r", "@"])?,
+        ))
+    }
+
+    fn file_text(&self, revision: &str, path: &str) -> Result<Option<String>> {
+        let fileset = root_file_pattern(path);
+        let output = self.output(&["file", "show", "-r", revision, &fileset], false)?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::ensure!(
+            output.status.success(),
+            "jj file show failed: {}",
+            stderr.trim()
+        );
+        // Paths that don't match any file only produce a warning.
+        if output.stdout.is_empty() && stderr.contains("No matching entries") {
+            return Ok(None);
+        }
+        Ok(Some(String::from_utf8(output.stdout)?))
+    }
+}
+
+/// Builds a fileset that matches exactly one file, relative to the workspace root.
+fn root_file_pattern(path: &str) -> String {
+    let escaped = path.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("root-file:\"{escaped}\"")
+}
+
+/// Parses the output of `jj diff --summary`, which prints a status letter and a path per line.
+/// Renames and copies are printed as `R {old => new}`, and are reported as an addition of the
+/// new path (plus a removal of the old path, for renames).
+fn parse_diff_summary(output: &str) -> Result<Vec<FileChange>> {
+    let mut changes = Vec::new();
+    for line in output.lines().filter(|line| !line.is_empty()) {
+        let (status, path) = line
+            .split_once(' ')
+            .with_context(|| format!("unexpected jj diff output: {line:?}"))?;
+        let kind = match status {
+            "M" => FileChangeKind::Modified,
+            "A" => FileChangeKind::Added,
+            "D" => FileChangeKind::Removed,
+            "R" | "C" => {
+                let (old_path, new_path) = expand_rename(path)
+                    .with_context(|| format!("unexpected jj diff output: {line:?}"))?;
+                if status == "R" {
+                    changes.push(FileChange {
+                        path: old_path,
+                        kind: FileChangeKind::Removed,
+                    });
+                }
+                changes.push(FileChange {
+                    path: new_path,
+                    kind: FileChangeKind::Added,
+                });
+                continue;
+            }
+            _ => anyhow::bail!("unexpected jj diff status {status:?}"),
+        };
+        changes.push(FileChange {
+            path: path.to_string(),
+            kind,
+        });
+    }
+    Ok(changes)
+}
+
+/// Expands `dir/{old => new}/file` into `dir/old/file` and `dir/new/file`.
+fn expand_rename(path: &str) -> Option<(String, String)> {
+    let (prefix, rest) = path.split_once('{')?;
+    let (renamed, suffix) = rest.split_once('}')?;
+    let (old, new) = renamed.split_once(" => ")?;
+    let join = |middle: &str| {
+        let path = format!("{prefix}{middle}{suffix}");
+        // An empty side leaves a doubled separator behind, as in `{ => dir}/file`.
+        path.replace("//", "/").trim_start_matches('/').to_string()
+    };
+    Some((join(old), join(new)))
+}
+
+/// Parses the output of `jj resolve --list`, which prints each conflicted path followed by a
+/// description of the conflict, such as `2-sided conflict`.
+fn parse_resolve_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let description_start = line.find("-sided conflict")?;
+            let path = line[..description_start]
+                .trim_end_matches(|c: char| c.is_ascii_digit())
+                .trim_end();
+            (!path.is_empty()).then(|| path.to_string())
+        })
+        .collect()
+}
+
+const LOG_FIELD_COUNT: usize = 12;
+
+/// Emits [`LOG_FIELD_COUNT`] NUL-terminated fields per change.
+const LOG_TEMPLATE: &str = r#"
+    change_id ++ "\0" ++
+    commit_id ++ "\0" ++
+    parents.map(|c| c.commit_id()).join(",") ++ "\0" ++
+    description ++ "\0" ++
+    author.name() ++ "\0" ++
+    author.email() ++ "\0" ++
+    author.timestamp().format("%s") ++ "\0" ++
+    local_bookmarks.map(|b| b.name()).join(",") ++ "\0" ++
+    if(current_working_copy, "1", "0") ++ "\0" ++
+    if(empty, "1", "0") ++ "\0" ++
+    if(immutable, "1", "0") ++ "\0" ++
+    if(conflict, "1", "0") ++ "\0"
+"#;
+
+fn parse_log_output(output: &str) -> Result<Vec<Change>> {
+    let fields = output.split('\0').collect::<Vec<_>>();
+    // The output ends with a terminator, so there's always one trailing empty field.
+    let fields = &fields[..fields.len() - 1];
+    anyhow::ensure!(
+        fields.len() % LOG_FIELD_COUNT == 0,
+        "unexpected jj log output: {output:?}"
+    );
+
+    fields
+        .chunks_exact(LOG_FIELD_COUNT)
+        .map(|fields| {
+            let split_list = |field: &str| {
+                field
+                    .split(',')
+                    .filter(|item| !item.is_empty())
+                    .map(|item| SharedString::from(item.to_string()))
+                    .collect::<Vec<_>>()
+            };
+
+            Ok(Change {
+                change_id: fields[0].trim().to_string().into(),
+                commit_id: fields[1].to_string().into(),
+                parent_commit_ids: split_list(fields[2]),
+                description: fields[3].trim_end().to_string().into(),
+                author_name: fields[4].to_string().into(),
+                author_email: fields[5].to_string().into(),
+                author_timestamp: fields[6]
+                    .parse()
+                    .map_err(|_| anyhow!("invalid timestamp {:?}", fields[6]))?,
+                bookmarks: split_list(fields[7]),
+                is_working_copy: fields[8] == "1",
+                is_empty: fields[9] == "1",
+                is_immutable: fields[10] == "1",
+                has_conflict: fields[11] == "1",
+            })
+        })
+        .collect()
+}
+
+/// An in-memory [`JujutsuRepository`] for tests.
+///
+/// Revisions are addressed by change ID, plus `@` for the working-copy change.
+#[derive(Default)]
+pub struct FakeJujutsuRepository {
+    state: Mutex<FakeJujutsuState>,
+}
+
+#[derive(Default)]
+struct FakeJujutsuState {
+    /// Changes in log order (newest first).
+    changes: Vec<Change>,
+    bookmarks: HashMap<String, String>,
+    next_id: usize,
+    working_copy_changes: Vec<FileChange>,
+    colocated: bool,
+    /// File contents, keyed by change ID and path.
+    files: HashMap<(String, String), String>,
+}
+
+impl FakeJujutsuRepository {
+    pub fn new(changes: Vec<Change>) -> Self {
+        let bookmarks = changes
+            .iter()
+            .flat_map(|change| {
+                change
+                    .bookmarks
+                    .iter()
+                    .map(|bookmark| (bookmark.to_string(), change.change_id.to_string()))
+            })
+            .collect();
+
+        Self {
+            state: Mutex::new(FakeJujutsuState {
+                changes,
+                bookmarks,
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn set_working_copy_changes(&self, changes: Vec<FileChange>) {
+        self.state.lock().working_copy_changes = changes;
+    }
+
+    pub fn set_colocated(&self, colocated: bool) {
+        self.state.lock().colocated = colocated;
+    }
+
+    pub fn set_file_text(&self, revision: &str, path: &str, text: Option<&str>) -> Result<()> {
+        let mut state = self.state.lock();
+        let change_id = state.changes[state.resolve(revision)?]
+            .change_id
+            .to_string();
+        let key = (change_id, path.to_string());
+        match text {
+            Some(text) => state.files.insert(key, text.to_string()),
+            None => state.files.remove(&key),
+        };
+        Ok(())
+    }
 }
 
-pub struct FakeJujutsuRepository {}
+impl FakeJujutsuState {
+    fn resolve(&self, revision: &str) -> Result<usize> {
+        self.changes
+            .iter()
+            .position(|change| {
+                if revision == "@" {
+                    change.is_working_copy
+                } else {
+                    change.change_id == revision
+                        || self.bookmarks.get(revision).map(String::as_str)
+                            == Some(change.change_id.as_ref())
+                }
+            })
+            .with_context(|| format!("revision {revision:?} doesn't exist"))
+    }
+
+    fn set_working_copy(&mut self, ix: usize) {
+        for (change_ix, change) in self.changes.iter_mut().enumerate() {
+            change.is_working_copy = change_ix == ix;
+        }
+    }
+
+    fn sync_bookmarks(&mut self) {
+        for change in &mut self.changes {
+            let mut bookmarks = self
+                .bookmarks
+                .iter()
+                .filter(|(_, change_id)| change.change_id == change_id.as_str())
+                .map(|(name, _)| SharedString::from(name.clone()))
+                .collect::<Vec<_>>();
+            bookmarks.sort();
+            change.bookmarks = bookmarks;
+        }
+    }
+}
 
 impl JujutsuRepository for FakeJujutsuRepository {
     fn list_bookmarks(&self) -> Vec<Bookmark> {
-        Vec::new()
+        let mut bookmarks = self
+            .state
+            .lock()
+            .bookmarks
+            .keys()
+            .map(|name| Bookmark {
+                ref_name: name.clone().into(),
+            })
+            .collect::<Vec<_>>();
+        bookmarks.sort_by(|a, b| a.ref_name.cmp(&b.ref_name));
+        bookmarks
+    }
+
+    fn log(&self, _revset: &str, limit: Option<usize>) -> Result<Vec<Change>> {
+        let state = self.state.lock();
+        Ok(state
+            .changes
+            .iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect())
+    }
+
+    fn describe(&self, revision: &str, message: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        let ix = state.resolve(revision)?;
+        state.changes[ix].description = message.to_string().into();
+        Ok(())
+    }
+
+    fn new_change(&self, parents: &[&str], message: Option<&str>) -> Result<()> {
+        let mut state = self.state.lock();
+        let parents = if parents.is_empty() {
+            &["@"][..]
+        } else {
+            parents
+        };
+        let parent_commit_ids = parents
+            .iter()
+            .map(|parent| {
+                let ix = state.resolve(parent)?;
+                Ok(state.changes[ix].commit_id.clone())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        state.next_id += 1;
+        let id = state.next_id;
+        state.changes.insert(
+            0,
+            Change {
+                change_id: format!("fake-change-{id}").into(),
+                commit_id: format!("fake-commit-{id}").into(),
+                parent_commit_ids,
+                description: message.unwrap_or_default().to_string().into(),
+                author_name: SharedString::default(),
+                author_email: SharedString::default(),
+                author_timestamp: 0,
+                bookmarks: Vec::new(),
+                is_working_copy: false,
+                is_empty: true,
+                is_immutable: false,
+                has_conflict: false,
+            },
+        );
+        state.set_working_copy(0);
+        Ok(())
+    }
+
+    fn edit(&self, revision: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        let ix = state.resolve(revision)?;
+        anyhow::ensure!(
+            !state.changes[ix].is_immutable,
+            "commit {} is immutable",
+            state.changes[ix].short_commit_id()
+        );
+        state.set_working_copy(ix);
+        Ok(())
+    }
+
+    fn squash(&self, revision: &str, destination: Option<&str>) -> Result<()> {
+        let mut state = self.state.lock();
+        let source_ix = state.resolve(revision)?;
+        let destination_ix = match destination {
+            Some(destination) => state.resolve(destination)?,
+            None => {
+                let parents = &state.changes[source_ix].parent_commit_ids;
+                anyhow::ensure!(
+                    parents.len() <= 1,
+                    "cannot squash a merge change without a destination"
+                );
+                let parent = parents
+                    .first()
+                    .cloned()
+                    .context("cannot squash a root change")?;
+                state
+                    .changes
+                    .iter()
+                    .position(|change| change.commit_id == parent)
+                    .context("parent change not found")?
+            }
+        };
+        anyhow::ensure!(
+            source_ix != destination_ix,
+            "cannot squash a change into itself"
+        );
+        anyhow::ensure!(
+            !state.changes[destination_ix].is_immutable,
+            "commit {} is immutable",
+            state.changes[destination_ix].short_commit_id()
+        );
+
+        if !state.changes[source_ix].is_empty {
+            state.changes[destination_ix].is_empty = false;
+        }
+        // The source becomes empty, like `jj squash` leaves behind an empty working-copy change.
+        let source = &mut state.changes[source_ix];
+        source.is_empty = true;
+        source.description = SharedString::default();
+        Ok(())
+    }
+
+    fn abandon(&self, revisions: &[&str]) -> Result<()> {
+        let mut state = self.state.lock();
+        for revision in revisions {
+            let ix = state.resolve(revision)?;
+            let abandoned = state.changes.remove(ix);
+            for change in &mut state.changes {
+                if let Some(position) = change
+                    .parent_commit_ids
+                    .iter()
+                    .position(|parent| *parent == abandoned.commit_id)
+                {
+                    change
+                        .parent_commit_ids
+                        .splice(position..=position, abandoned.parent_commit_ids.clone());
+                }
+            }
+            state
+                .bookmarks
+                .retain(|_, change_id| change_id.as_str() != abandoned.change_id.as_ref());
+        }
+        state.sync_bookmarks();
+        Ok(())
+    }
+
+    fn create_bookmark(&self, name: &str, revision: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        anyhow::ensure!(
+            !state.bookmarks.contains_key(name),
+            "bookmark already exists: {name}"
+        );
+        let ix = state.resolve(revision)?;
+        let change_id = state.changes[ix].change_id.to_string();
+        state.bookmarks.insert(name.to_string(), change_id);
+        state.sync_bookmarks();
+        Ok(())
+    }
+
+    fn move_bookmark(&self, name: &str, revision: &str, _allow_backwards: bool) -> Result<()> {
+        let mut state = self.state.lock();
+        let ix = state.resolve(revision)?;
+        let change_id = state.changes[ix].change_id.to_string();
+        state.bookmarks.insert(name.to_string(), change_id);
+        state.sync_bookmarks();
+        Ok(())
+    }
+
+    fn delete_bookmark(&self, name: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        state
+            .bookmarks
+            .remove(name)
+            .with_context(|| format!("no such bookmark: {name}"))?;
+        state.sync_bookmarks();
+        Ok(())
+    }
+
+    fn is_colocated(&self) -> bool {
+        self.state.lock().colocated
+    }
+
+    fn working_copy_changes(&self) -> Result<Vec<FileChange>> {
+        Ok(self.state.lock().working_copy_changes.clone())
+    }
+
+    fn conflicted_paths(&self) -> Result<Vec<String>> {
+        Ok(self
+            .state
+            .lock()
+            .working_copy_changes
+            .iter()
+            .filter(|change| change.kind == FileChangeKind::Conflicted)
+            .map(|change| change.path.clone())
+            .collect())
+    }
+
+    fn file_text(&self, revision: &str, path: &str) -> Result<Option<String>> {
+        let state = self.state.lock();
+        let revision = match revision.strip_suffix('-') {
+            Some(revision) => {
+                let change = &state.changes[state.resolve(revision)?];
+                let parent = change
+                    .parent_commit_ids
+                    .first()
+                    .with_context(|| format!("{revision:?} has no parents"))?;
+                state
+                    .changes
+                    .iter()
+                    .find(|change| &change.commit_id == parent)
+                    .context("parent isn't known to the fake repository")?
+                    .change_id
+                    .to_string()
+            }
+            None => state.changes[state.resolve(revision)?]
+                .change_id
+                .to_string(),
+        };
+        Ok(state.files.get(&(revision, path.to_string())).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_output() {
+        let output = concat!(
+            "zxyw\0c0ffee\0beef01,beef02\0Fix the thing\n\nMore details\n\0Jane\0jane@example.com\01700000000\0main,feature\01\00\00\00\0",
+            "qrst\0beef01\0\0\0\0\00\0\00\01\01\00\0",
+        );
+
+        let changes = parse_log_output(output).unwrap();
+        assert_eq!(changes.len(), 2);
+
+        assert_eq!(changes[0].change_id, "zxyw");
+        assert_eq!(changes[0].parent_commit_ids, vec!["beef01", "beef02"]);
+        assert_eq!(changes[0].summary(), "Fix the thing");
+        assert_eq!(changes[0].description, "Fix the thing\n\nMore details");
+        assert_eq!(changes[0].author_timestamp, 1_700_000_000);
+        assert_eq!(changes[0].bookmarks, vec!["main", "feature"]);
+        assert!(changes[0].is_working_copy);
+        assert!(!changes[0].is_empty);
+
+        assert_eq!(changes[1].summary(), "");
+        assert!(changes[1].parent_commit_ids.is_empty());
+        assert!(changes[1].bookmarks.is_empty());
+        assert!(changes[1].is_empty);
+        assert!(changes[1].is_immutable);
+    }
+
+    #[test]
+    fn test_parse_log_output_rejects_truncated_output() {
+        assert!(parse_log_output("zxyw\0c0ffee\0").is_err());
+    }
+
+    #[test]
+    fn test_parse_diff_summary() {
+        let output = "M src/main.rs\nA docs/new file.md\nD old.txt\nR src/{lib.rs => core.rs}\nC {a => b}/c.txt\n";
+        let changes = parse_diff_summary(output).unwrap();
+        let change = |path: &str, kind| FileChange {
+            path: path.to_string(),
+            kind,
+        };
+        assert_eq!(
+            changes,
+            vec![
+                change("src/main.rs", FileChangeKind::Modified),
+                change("docs/new file.md", FileChangeKind::Added),
+                change("old.txt", FileChangeKind::Removed),
+                change("src/lib.rs", FileChangeKind::Removed),
+                change("src/core.rs", FileChangeKind::Added),
+                change("b/c.txt", FileChangeKind::Added),
+            ]
+        );
+
+        assert_eq!(
+            expand_rename("{ => nested}/file.rs"),
+            Some(("file.rs".to_string(), "nested/file.rs".to_string()))
+        );
+        assert!(parse_diff_summary("X what").is_err());
+    }
+
+    #[test]
+    fn test_parse_resolve_list() {
+        let output = "src/main.rs    2-sided conflict\nmy file.txt    3-sided conflict including 1 deletion\n";
+        assert_eq!(
+            parse_resolve_list(output),
+            vec!["src/main.rs", "my file.txt"]
+        );
+        assert_eq!(
+            root_file_pattern("a \"b\".txt"),
+            r#"root-file:"a \"b\".txt""#
+        );
+    }
+
+    #[test]
+    fn test_fake_repository_operations() {
+        let root = Change {
+            change_id: "root".into(),
+            commit_id: "root-commit".into(),
+            parent_commit_ids: Vec::new(),
+            description: "initial".into(),
+            author_name: "".into(),
+            author_email: "".into(),
+            author_timestamp: 0,
+            bookmarks: vec!["main".into()],
+            is_working_copy: true,
+            is_empty: false,
+            is_immutable: false,
+            has_conflict: false,
+        };
+        let repository = FakeJujutsuRepository::new(vec![root]);
+
+        repository.new_change(&[], Some("wip")).unwrap();
+        let changes = repository.log(DEFAULT_LOG_REVSET, None).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert!(changes[0].is_working_copy);
+        assert_eq!(changes[0].parent_commit_ids, vec!["root-commit"]);
+
+        repository.describe("@", "work in progress").unwrap();
+        repository.create_bookmark("feature", "@").unwrap();
+        repository.move_bookmark("main", "@", false).unwrap();
+        let changes = repository.log(DEFAULT_LOG_REVSET, None).unwrap();
+        assert_eq!(changes[0].summary(), "work in progress");
+        assert_eq!(changes[0].bookmarks, vec!["feature", "main"]);
+        assert!(changes[1].bookmarks.is_empty());
+
+        repository
+            .new_change(&["root", "@"], Some("merge"))
+            .unwrap();
+        assert!(
+            repository.squash("@", None).is_err(),
+            "Merge changes have no single parent to squash into"
+        );
+        repository.abandon(&["@"]).unwrap();
+
+        repository.delete_bookmark("feature").unwrap();
+        repository.edit("root").unwrap();
+        repository.abandon(&["main"]).unwrap();
+        let changes = repository.log(DEFAULT_LOG_REVSET, None).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].is_working_copy);
+        assert!(repository.list_bookmarks().is_empty());
     }
 }
@@ -1,9 +1,10 @@
 use std::path::Path;
 use std::sync::Arc;
 
-use gpui::{App, Entity, Global, prelude::*};
+use anyhow::Result;
+use gpui::{App, Entity, EventEmitter, Global, Task, prelude::*};
 
-use crate::{JujutsuRepository, RealJujutsuRepository};
+use crate::{Change, JujutsuRepository, RealJujutsuRepository};
 
 /// Note: We won't ultimately be storing the jj store in a global, we're just doing this for exploration purposes.
 struct GlobalJujutsuStore(Entity<JujutsuStore>);
@@ -14,9 +15,18 @@ pub struct JujutsuStore {
     repository: Arc<dyn JujutsuRepository>,
 }
 
+pub enum JujutsuStoreEvent {
+    /// The repository was rewritten by an operation run through this store.
+    RepositoryUpdated,
+}
+
+impl EventEmitter<JujutsuStoreEvent> for JujutsuStore {}
+
 impl JujutsuStore {
     pub fn init_global(cx: &mut App) {
-        let Some(repository) = RealJujutsuRepository::new(&Path::new(".")).ok() else {
+        let Some(repository) =
+            RealJujutsuRepository::new(Path::new("."), None, Default::default()).ok()
+        else {
             return;
         };
 
@@ -38,4 +48,33 @@ impl JujutsuStore {
     pub fn repository(&self) -> &Arc<dyn JujutsuRepository> {
         &self.repository
     }
+
+    pub fn log(
+        &self,
+        revset: String,
+        limit: Option<usize>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Change>>> {
+        let repository = self.repository.clone();
+        cx.background_spawn(async move { repository.log(&revset, limit) })
+    }
+
+    /// Runs `operation` against the repository on a background thread, notifying
+    /// subscribers once it has rewritten the repository.
+    pub fn run_operation(
+        &self,
+        operation: impl FnOnce(&dyn JujutsuRepository) -> Result<()> + Send + 'static,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let repository = self.repository.clone();
+        cx.spawn(async move |this, cx| {
+            let result = cx
+                .background_spawn(async move { operation(repository.as_ref()) })
+                .await;
+            if result.is_ok() {
+                this.update(cx, |_, cx| cx.emit(JujutsuStoreEvent::RepositoryUpdated))?;
+            }
+            result
+        })
+    }
 }
@@ -9,7 +9,7 @@ use jj::{Bookmark, JujutsuStore};
 use picker::{Picker, PickerDelegate};
 use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
 use util::ResultExt as _;
-use workspace::{ModalView, Workspace};
+use workspace::{ModalView, Workspace, notifications::NotifyTaskExt as _};
 
 pub fn register(workspace: &mut Workspace) {
     workspace.register_action(open);
@@ -25,12 +25,40 @@ fn open(
         return;
     };
 
+    toggle(
+        workspace,
+        jj_store,
+        BookmarkPickerMode::NewChange,
+        window,
+        cx,
+    );
+}
+
+pub(crate) fn toggle(
+    workspace: &mut Workspace,
+    jj_store: Entity<JujutsuStore>,
+    mode: BookmarkPickerMode,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
     workspace.toggle_modal(window, cx, |window, cx| {
-        let delegate = BookmarkPickerDelegate::new(cx.entity().downgrade(), jj_store, cx);
+        let delegate = BookmarkPickerDelegate::new(cx.entity().downgrade(), jj_store, mode, cx);
         BookmarkPicker::new(delegate, window, cx)
     });
 }
 
+/// What happens to the bookmark picked in a [`BookmarkPicker`].
+#[derive(Debug, Clone)]
+pub(crate) enum BookmarkPickerMode {
+    /// Start a new change on top of the bookmark, similar to checking out a branch.
+    NewChange,
+    /// Move the bookmark to the given revision. Secondary confirm allows moving it backwards.
+    MoveTo {
+        revision: SharedString,
+    },
+    Delete,
+}
+
 pub struct BookmarkPicker {
     picker: Entity<Picker<BookmarkPickerDelegate>>,
 }
@@ -70,6 +98,8 @@ struct BookmarkEntry {
 
 pub struct BookmarkPickerDelegate {
     picker: WeakEntity<BookmarkPicker>,
+    jj_store: Entity<JujutsuStore>,
+    mode: BookmarkPickerMode,
     matches: Vec<BookmarkEntry>,
     all_bookmarks: Vec<Bookmark>,
     selected_index: usize,
@@ -79,12 +109,15 @@ impl BookmarkPickerDelegate {
     fn new(
         picker: WeakEntity<BookmarkPicker>,
         jj_store: Entity<JujutsuStore>,
+        mode: BookmarkPickerMode,
         cx: &mut Context<BookmarkPicker>,
     ) -> Self {
         let bookmarks = jj_store.read(cx).repository().list_bookmarks();
 
         Self {
             picker,
+            jj_store,
+            mode,
             matches: Vec::new(),
             all_bookmarks: bookmarks,
             selected_index: 0,
@@ -96,7 +129,11 @@ impl PickerDelegate for BookmarkPickerDelegate {
     type ListItem = ListItem;
 
     fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
-        "Select Bookmark…".into()
+        match self.mode {
+            BookmarkPickerMode::NewChange => "Select Bookmark…".into(),
+            BookmarkPickerMode::MoveTo { .. } => "Select Bookmark to Move…".into(),
+            BookmarkPickerMode::Delete => "Select Bookmark to Delete…".into(),
+        }
     }
 
     fn match_count(&self) -> usize {
@@ -164,8 +201,31 @@ impl PickerDelegate for BookmarkPickerDelegate {
         })
     }
 
-    fn confirm(&mut self, _secondary: bool, _window: &mut Window, _cx: &mut Context<Picker<Self>>) {
-        //
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index) else {
+            return;
+        };
+
+        let name = entry.bookmark.ref_name.clone();
+        let mode = self.mode.clone();
+        self.jj_store
+            .update(cx, |jj_store, cx| {
+                jj_store.run_operation(
+                    move |repository| match mode {
+                        BookmarkPickerMode::NewChange => {
+                            repository.new_change(&[name.as_ref()], None)
+                        }
+                        BookmarkPickerMode::MoveTo { revision } => {
+                            repository.move_bookmark(&name, &revision, secondary)
+                        }
+                        BookmarkPickerMode::Delete => repository.delete_bookmark(&name),
+                    },
+                    cx,
+                )
+            })
+            .detach_and_notify_err(window, cx);
+
+        self.dismissed(window, cx);
     }
 
     fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
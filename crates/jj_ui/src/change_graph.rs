@@ -0,0 +1,63 @@
+use collections::HashSet;
+use commit_graph::CommitGraph;
+use jj::Change;
+
+/// Lays out changes given in `jj log` order (children before parents).
+///
+/// Parents outside of the given changes aren't drawn, so that revsets that only select part
+/// of the history don't leave dangling lanes behind.
+pub fn layout_graph(changes: &[Change]) -> CommitGraph {
+    let visible_commits = changes
+        .iter()
+        .map(|change| change.commit_id.clone())
+        .collect::<HashSet<_>>();
+
+    let mut graph = CommitGraph::default();
+    for change in changes {
+        let parents = change
+            .parent_commit_ids
+            .iter()
+            .filter(|parent| visible_commits.contains(*parent))
+            .cloned()
+            .collect::<Vec<_>>();
+        graph.push(&change.commit_id, &parents);
+    }
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::SharedString;
+
+    fn change(commit_id: &str, parents: &[&str]) -> Change {
+        Change {
+            change_id: commit_id.to_string().into(),
+            commit_id: commit_id.to_string().into(),
+            parent_commit_ids: parents
+                .iter()
+                .map(|parent| parent.to_string().into())
+                .collect(),
+            description: SharedString::default(),
+            author_name: SharedString::default(),
+            author_email: SharedString::default(),
+            author_timestamp: 0,
+            bookmarks: Vec::new(),
+            is_working_copy: false,
+            is_empty: false,
+            is_immutable: false,
+            has_conflict: false,
+        }
+    }
+
+    #[test]
+    fn test_hidden_parents_are_not_drawn() {
+        let graph = layout_graph(&[change("b", &["a"]), change("c", &["z"])]);
+        let rows = graph.rows();
+
+        assert_eq!(rows[0].outgoing, vec![]);
+        assert_eq!(rows[1].node_lane, 0);
+        assert_eq!(rows[1].incoming, vec![]);
+        assert_eq!(graph.lane_count(), 1);
+    }
+}
@@ -0,0 +1,680 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use commit_graph::CommitGraph;
+use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
+use fs::Fs;
+use gpui::{
+    Action, AsyncWindowContext, ClickEvent, Corner, DismissEvent, Entity, EventEmitter,
+    FocusHandle, Focusable, MouseButton, MouseDownEvent, Point, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, anchored, deferred, uniform_list,
+};
+use jj::{Change, DEFAULT_LOG_REVSET, JujutsuRepository, JujutsuStore, JujutsuStoreEvent};
+use menu::{SelectNext, SelectPrevious};
+use serde::{Deserialize, Serialize};
+use settings::Settings as _;
+use ui::{ContextMenu, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+    notifications::NotifyTaskExt as _,
+};
+
+use crate::bookmark_picker::{self, BookmarkPickerMode};
+use crate::change_graph;
+use crate::input_modal::InputModal;
+use crate::jj_panel_settings::JjPanelSettings;
+use crate::{
+    Abandon, CreateBookmark, DeleteBookmark, Describe, Edit, MoveBookmark, NewChange, Refresh,
+    Squash, ToggleFocus,
+};
+
+/// The maximum number of changes shown in the graph at once.
+const LOG_LIMIT: usize = 500;
+const JJ_PANEL_KEY: &str = "JujutsuChangeGraphPanel";
+
+#[derive(Serialize, Deserialize)]
+struct SerializedJjPanel {
+    width: Option<Pixels>,
+}
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+        workspace.toggle_panel_focus::<ChangeGraphPanel>(window, cx);
+    });
+}
+
+/// A panel showing the change graph of the Jujutsu repository, with operations for
+/// rewriting the selected change.
+pub struct ChangeGraphPanel {
+    workspace: WeakEntity<Workspace>,
+    jj_store: Option<Entity<JujutsuStore>>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    revset_editor: Entity<Editor>,
+    changes: Vec<Change>,
+    graph: CommitGraph,
+    selected_ix: Option<usize>,
+    scroll_handle: UniformListScrollHandle,
+    context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
+    error: Option<SharedString>,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    pending_refresh: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ChangeGraphPanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> Result<Entity<Self>> {
+        let serialized_panel = cx
+            .background_spawn(async move { KEY_VALUE_STORE.read_kvp(JJ_PANEL_KEY) })
+            .await
+            .context("loading jj panel")
+            .log_err()
+            .flatten()
+            .and_then(|panel| serde_json::from_str::<SerializedJjPanel>(&panel).log_err());
+
+        workspace.update_in(&mut cx, |workspace, window, cx| {
+            let fs = workspace.app_state().fs.clone();
+            let workspace = cx.entity().downgrade();
+            let panel = cx.new(|cx| Self::new(workspace, fs, window, cx));
+
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width;
+                    cx.notify();
+                })
+            }
+
+            panel
+        })
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        fs: Arc<dyn Fs>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let jj_store = JujutsuStore::try_global(cx);
+
+        let revset_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(DEFAULT_LOG_REVSET, cx);
+            editor
+        });
+
+        let mut subscriptions = Vec::new();
+        if let Some(jj_store) = jj_store.as_ref() {
+            subscriptions.push(cx.subscribe(jj_store, |this, _, event, cx| match event {
+                JujutsuStoreEvent::RepositoryUpdated => this.refresh(cx),
+            }));
+        }
+
+        let mut this = Self {
+            workspace,
+            jj_store,
+            fs,
+            focus_handle: cx.focus_handle(),
+            revset_editor,
+            changes: Vec::new(),
+            graph: CommitGraph::default(),
+            selected_ix: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            context_menu: None,
+            error: None,
+            width: None,
+            pending_serialization: Task::ready(None),
+            pending_refresh: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        JJ_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedJjPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn revset(&self, cx: &App) -> String {
+        let revset = self.revset_editor.read(cx).text(cx);
+        if revset.trim().is_empty() {
+            DEFAULT_LOG_REVSET.to_string()
+        } else {
+            revset
+        }
+    }
+
+    fn handle_refresh(&mut self, _: &Refresh, _: &mut Window, cx: &mut Context<Self>) {
+        self.refresh(cx);
+    }
+
+    fn confirm_revset(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        self.refresh(cx);
+        self.focus_handle.focus(window);
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let Some(jj_store) = self.jj_store.clone() else {
+            return;
+        };
+
+        let revset = self.revset(cx);
+        let log = jj_store.update(cx, |jj_store, cx| jj_store.log(revset, Some(LOG_LIMIT), cx));
+        self.pending_refresh = cx.spawn(async move |this, cx| {
+            let result = log.await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(changes) => {
+                        let selected_change_id = this
+                            .selected_change()
+                            .map(|change| change.change_id.clone());
+                        this.graph = change_graph::layout_graph(&changes);
+                        this.selected_ix = selected_change_id
+                            .and_then(|change_id| {
+                                changes
+                                    .iter()
+                                    .position(|change| change.change_id == change_id)
+                            })
+                            .or_else(|| changes.iter().position(|change| change.is_working_copy));
+                        this.changes = changes;
+                        this.error = None;
+                    }
+                    Err(error) => {
+                        this.changes.clear();
+                        this.graph = CommitGraph::default();
+                        this.selected_ix = None;
+                        this.error = Some(error.to_string().into());
+                    }
+                }
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
+
+    fn selected_change(&self) -> Option<&Change> {
+        self.changes.get(self.selected_ix?)
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if self.changes.is_empty() {
+            return;
+        }
+        let ix = self
+            .selected_ix
+            .map_or(0, |ix| (ix + 1).min(self.changes.len() - 1));
+        self.select_entry(ix, cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        if self.changes.is_empty() {
+            return;
+        }
+        let ix = self.selected_ix.map_or(0, |ix| ix.saturating_sub(1));
+        self.select_entry(ix, cx);
+    }
+
+    fn select_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_ix = Some(ix);
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Center);
+        cx.notify();
+    }
+
+    fn run_on_selected(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        operation: impl FnOnce(&dyn JujutsuRepository, &str) -> Result<()> + Send + 'static,
+    ) {
+        if let Some(change_id) = self
+            .selected_change()
+            .map(|change| change.change_id.clone())
+        {
+            self.run_on_change(change_id, window, cx, operation);
+        }
+    }
+
+    /// Runs `operation` against the given change and reports failures to the workspace.
+    fn run_on_change(
+        &mut self,
+        change_id: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        operation: impl FnOnce(&dyn JujutsuRepository, &str) -> Result<()> + Send + 'static,
+    ) {
+        let Some(jj_store) = self.jj_store.clone() else {
+            return;
+        };
+
+        jj_store
+            .update(cx, |jj_store, cx| {
+                jj_store.run_operation(move |repository| operation(repository, &change_id), cx)
+            })
+            .detach_and_notify_err(window, cx);
+    }
+
+    fn new_change(&mut self, _: &NewChange, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_on_selected(window, cx, |repository, change_id| {
+            repository.new_change(&[change_id], None)
+        });
+    }
+
+    fn edit(&mut self, _: &Edit, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_on_selected(window, cx, |repository, change_id| {
+            repository.edit(change_id)
+        });
+    }
+
+    fn squash(&mut self, _: &Squash, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_on_selected(window, cx, |repository, change_id| {
+            repository.squash(change_id, None)
+        });
+    }
+
+    fn abandon(&mut self, _: &Abandon, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_on_selected(window, cx, |repository, change_id| {
+            repository.abandon(&[change_id])
+        });
+    }
+
+    fn describe(&mut self, _: &Describe, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(change) = self.selected_change() else {
+            return;
+        };
+        let change_id = change.change_id.clone();
+        let description = change.description.clone();
+        let title = format!("Describe {}", change.short_change_id());
+        let this = cx.entity().downgrade();
+        self.toggle_input_modal(
+            title,
+            &description,
+            true,
+            window,
+            cx,
+            move |message, window, cx| {
+                this.update(cx, |this, cx| {
+                    this.run_on_change(change_id, window, cx, move |repository, change_id| {
+                        repository.describe(change_id, &message)
+                    })
+                })
+                .log_err();
+            },
+        );
+    }
+
+    fn create_bookmark(&mut self, _: &CreateBookmark, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(change) = self.selected_change() else {
+            return;
+        };
+        let change_id = change.change_id.clone();
+        let title = format!("Create Bookmark at {}", change.short_change_id());
+        let this = cx.entity().downgrade();
+        self.toggle_input_modal(title, "", false, window, cx, move |name, window, cx| {
+            let name = name.trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            this.update(cx, |this, cx| {
+                this.run_on_change(change_id, window, cx, move |repository, change_id| {
+                    repository.create_bookmark(&name, change_id)
+                })
+            })
+            .log_err();
+        });
+    }
+
+    fn move_bookmark(&mut self, _: &MoveBookmark, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(change) = self.selected_change() else {
+            return;
+        };
+        let mode = BookmarkPickerMode::MoveTo {
+            revision: change.change_id.clone(),
+        };
+        self.toggle_bookmark_picker(mode, window, cx);
+    }
+
+    fn delete_bookmark(&mut self, _: &DeleteBookmark, window: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_bookmark_picker(BookmarkPickerMode::Delete, window, cx);
+    }
+
+    fn toggle_bookmark_picker(
+        &mut self,
+        mode: BookmarkPickerMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(jj_store) = self.jj_store.clone() else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                bookmark_picker::toggle(workspace, jj_store, mode, window, cx);
+            })
+            .log_err();
+    }
+
+    fn toggle_input_modal(
+        &mut self,
+        title: String,
+        initial_text: &str,
+        multi_line: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        on_confirm: impl FnOnce(String, &mut Window, &mut App) + 'static,
+    ) {
+        let initial_text = initial_text.to_string();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    InputModal::new(title, &initial_text, multi_line, on_confirm, window, cx)
+                });
+            })
+            .log_err();
+    }
+
+    fn deploy_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(change) = self.changes.get(ix) else {
+            return;
+        };
+        let is_immutable = change.is_immutable;
+        let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
+            context_menu
+                .context(self.focus_handle.clone())
+                .action("New Change on Top", NewChange.boxed_clone())
+                .when(!is_immutable, |menu| {
+                    menu.action("Edit", Edit.boxed_clone())
+                        .action("Describe…", Describe.boxed_clone())
+                        .action("Squash into Parent", Squash.boxed_clone())
+                        .action("Abandon", Abandon.boxed_clone())
+                })
+                .separator()
+                .action("Create Bookmark…", CreateBookmark.boxed_clone())
+                .action("Move Bookmark Here…", MoveBookmark.boxed_clone())
+                .action("Delete Bookmark…", DeleteBookmark.boxed_clone())
+        });
+        self.selected_ix = Some(ix);
+
+        let subscription = cx.subscribe_in(
+            &context_menu,
+            window,
+            |this, _, _: &DismissEvent, window, cx| {
+                if this.context_menu.as_ref().is_some_and(|context_menu| {
+                    context_menu.0.focus_handle(cx).contains_focused(window, cx)
+                }) {
+                    cx.focus_self(window);
+                }
+                this.context_menu.take();
+                cx.notify();
+            },
+        );
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let change = &self.changes[ix];
+        let row = &self.graph.rows()[ix];
+        let selected = self.selected_ix == Some(ix);
+
+        let summary = if change.summary().is_empty() {
+            Label::new("(no description set)")
+                .color(Color::Muted)
+                .italic()
+                .truncate()
+        } else {
+            Label::new(change.summary().to_string()).truncate()
+        };
+        let tooltip_text: SharedString = format!(
+            "{}\nCommit {}\nAuthor: {} <{}>",
+            change.change_id,
+            change.short_commit_id(),
+            change.author_name,
+            change.author_email
+        )
+        .into();
+
+        h_flex()
+            .id(("change", ix))
+            .h(px(28.))
+            .w_full()
+            .pl_1()
+            .pr_2()
+            .gap_1p5()
+            .cursor_pointer()
+            .when(selected, |this| {
+                this.bg(cx.theme().colors().ghost_element_selected)
+            })
+            .hover(|this| this.bg(cx.theme().colors().ghost_element_hover))
+            // Hollow out regular changes, so that the working-copy change stands out.
+            .child(commit_graph::render_graph_row(
+                row,
+                self.graph.lane_count(),
+                !change.is_working_copy,
+                cx.theme().colors().panel_background,
+                cx,
+            ))
+            .child(
+                Label::new(change.short_change_id().to_string())
+                    .size(LabelSize::Small)
+                    .color(if change.is_working_copy {
+                        Color::Accent
+                    } else {
+                        Color::Muted
+                    })
+                    .buffer_font(cx),
+            )
+            .children(change.bookmarks.iter().map(|bookmark| {
+                Label::new(bookmark.clone())
+                    .size(LabelSize::Small)
+                    .color(Color::Created)
+            }))
+            .when(change.has_conflict, |this| {
+                this.child(
+                    Icon::new(IconName::Warning)
+                        .size(IconSize::Small)
+                        .color(Color::Conflict),
+                )
+            })
+            .child(div().flex_1().min_w_0().child(summary))
+            .tooltip(Tooltip::text(tooltip_text))
+            .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                this.select_entry(ix, cx);
+                this.focus_handle.focus(window);
+                if event.down.click_count > 1 {
+                    this.describe(&Describe, window, cx);
+                }
+            }))
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                    this.deploy_context_menu(event.position, ix, window, cx);
+                    cx.stop_propagation();
+                }),
+            )
+            .into_any_element()
+    }
+
+    fn render_empty_state(&self) -> impl IntoElement {
+        let message: SharedString = if self.jj_store.is_none() {
+            "No Jujutsu repository found".into()
+        } else if let Some(error) = self.error.as_ref() {
+            error.clone()
+        } else {
+            "No changes match this revset".into()
+        };
+
+        v_flex()
+            .size_full()
+            .p_4()
+            .items_center()
+            .justify_center()
+            .child(
+                Label::new(message)
+                    .color(if self.error.is_some() {
+                        Color::Error
+                    } else {
+                        Color::Muted
+                    })
+                    .size(LabelSize::Small),
+            )
+    }
+}
+
+impl Render for ChangeGraphPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("JjChangeGraphPanel")
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .on_action(cx.listener(Self::handle_refresh))
+            .child(
+                h_flex()
+                    .key_context("JjRevsetEditor")
+                    .on_action(cx.listener(Self::confirm_revset))
+                    .h(px(32.))
+                    .px_2()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(div().flex_1().child(self.revset_editor.clone()))
+                    .child(
+                        IconButton::new("refresh-change-graph", IconName::RotateCw)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::for_action_title("Refresh", &Refresh))
+                            .on_click(cx.listener(|this, _, _, cx| this.refresh(cx))),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("change-list")
+                    .key_context("JjChangeList")
+                    .track_focus(&self.focus_handle)
+                    .flex_1()
+                    .size_full()
+                    .overflow_hidden()
+                    .on_action(cx.listener(Self::select_next))
+                    .on_action(cx.listener(Self::select_previous))
+                    .on_action(cx.listener(Self::new_change))
+                    .on_action(cx.listener(Self::edit))
+                    .on_action(cx.listener(Self::describe))
+                    .on_action(cx.listener(Self::squash))
+                    .on_action(cx.listener(Self::abandon))
+                    .on_action(cx.listener(Self::create_bookmark))
+                    .on_action(cx.listener(Self::move_bookmark))
+                    .on_action(cx.listener(Self::delete_bookmark))
+                    .map(|this| {
+                        if self.changes.is_empty() {
+                            this.child(self.render_empty_state())
+                        } else {
+                            this.child(
+                                uniform_list(
+                                    cx.entity().clone(),
+                                    "changes",
+                                    self.changes.len(),
+                                    |this, range, _window, cx| {
+                                        range.map(|ix| this.render_entry(ix, cx)).collect()
+                                    },
+                                )
+                                .size_full()
+                                .track_scroll(self.scroll_handle.clone()),
+                            )
+                        }
+                    }),
+            )
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(Corner::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
+    }
+}
+
+impl Focusable for ChangeGraphPanel {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for ChangeGraphPanel {}
+
+impl Panel for ChangeGraphPanel {
+    fn persistent_name() -> &'static str {
+        "JujutsuChangeGraphPanel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        JjPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file::<JjPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| JjPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, _: &App) -> Option<IconName> {
+        Some(IconName::GitBranch)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Jujutsu Change Graph")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        10
+    }
+
+    fn enabled(&self, _: &App) -> bool {
+        self.jj_store.is_some()
+    }
+}
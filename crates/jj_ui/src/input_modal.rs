@@ -0,0 +1,82 @@
+use editor::Editor;
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable};
+use ui::prelude::*;
+use workspace::ModalView;
+
+/// A modal that asks for a line (or several lines) of text, such as a change description
+/// or a bookmark name.
+pub struct InputModal {
+    title: SharedString,
+    editor: Entity<Editor>,
+    on_confirm: Option<Box<dyn FnOnce(String, &mut Window, &mut App)>>,
+}
+
+impl InputModal {
+    pub fn new(
+        title: impl Into<SharedString>,
+        initial_text: &str,
+        multi_line: bool,
+        on_confirm: impl FnOnce(String, &mut Window, &mut App) + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = if multi_line {
+                Editor::auto_height(8, window, cx)
+            } else {
+                Editor::single_line(window, cx)
+            };
+            editor.set_text(initial_text, window, cx);
+            editor.select_all(&Default::default(), window, cx);
+            editor
+        });
+
+        Self {
+            title: title.into(),
+            editor,
+            on_confirm: Some(Box::new(on_confirm)),
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.editor.read(cx).text(cx);
+        if let Some(on_confirm) = self.on_confirm.take() {
+            on_confirm(text, window, cx);
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for InputModal {}
+
+impl EventEmitter<DismissEvent> for InputModal {}
+
+impl Focusable for InputModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for InputModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .elevation_2(cx)
+            .key_context("JjInputModal")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(self.title.clone()).color(Color::Muted)),
+            )
+            .child(div().px_2().py_1().child(self.editor.clone()))
+    }
+}
@@ -0,0 +1,36 @@
+use gpui::Pixels;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use workspace::dock::DockPosition;
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct JjPanelSettingsContent {
+    /// Where to dock the panel.
+    ///
+    /// Default: left
+    pub dock: Option<DockPosition>,
+    /// Default width of the panel in pixels.
+    ///
+    /// Default: 360
+    pub default_width: Option<f32>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct JjPanelSettings {
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+impl Settings for JjPanelSettings {
+    const KEY: Option<&'static str> = Some("jj_panel");
+
+    type FileContent = JjPanelSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::App,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
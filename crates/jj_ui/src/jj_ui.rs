@@ -1,16 +1,42 @@
 mod bookmark_picker;
+mod change_graph;
+mod change_graph_panel;
+mod input_modal;
+mod jj_panel_settings;
 
 use command_palette_hooks::CommandPaletteFilter;
 use feature_flags::FeatureFlagAppExt as _;
-use gpui::App;
+use gpui::{App, actions};
 use jj::JujutsuStore;
+use jj_panel_settings::JjPanelSettings;
+use settings::Settings as _;
 use workspace::Workspace;
 
+pub use change_graph_panel::ChangeGraphPanel;
+
+actions!(
+    jj,
+    [
+        ToggleFocus,
+        Refresh,
+        NewChange,
+        Describe,
+        Edit,
+        Squash,
+        Abandon,
+        CreateBookmark,
+        MoveBookmark,
+        DeleteBookmark
+    ]
+);
+
 pub fn init(cx: &mut App) {
+    JjPanelSettings::register(cx);
     JujutsuStore::init_global(cx);
 
     cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
         bookmark_picker::register(workspace);
+        change_graph_panel::register(workspace);
     })
     .detach();
 
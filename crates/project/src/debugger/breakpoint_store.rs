@@ -156,6 +156,10 @@ pub struct ActiveStackFrame {
 
 pub struct BreakpointStore {
     breakpoints: BTreeMap<Arc<Path>, BreakpointsInFile>,
+    function_breakpoints: Vec<FunctionBreakpoint>,
+    data_breakpoints: Vec<DataBreakpoint>,
+    /// Expressions evaluated and shown by debug sessions whenever they stop.
+    watch_expressions: Vec<Arc<str>>,
     downstream_client: Option<(AnyProtoClient, u64)>,
     active_stack_frame: Option<ActiveStackFrame>,
     // E.g ssh
@@ -170,6 +174,9 @@ impl BreakpointStore {
     pub fn local(worktree_store: Entity<WorktreeStore>, buffer_store: Entity<BufferStore>) -> Self {
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            function_breakpoints: Vec::new(),
+            data_breakpoints: Vec::new(),
+            watch_expressions: Vec::new(),
             mode: BreakpointStoreMode::Local(LocalBreakpointStore {
                 worktree_store,
                 buffer_store,
@@ -182,6 +189,9 @@ impl BreakpointStore {
     pub(crate) fn remote(upstream_project_id: u64, upstream_client: AnyProtoClient) -> Self {
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            function_breakpoints: Vec::new(),
+            data_breakpoints: Vec::new(),
+            watch_expressions: Vec::new(),
             mode: BreakpointStoreMode::Remote(RemoteBreakpointStore {
                 upstream_client,
                 _upstream_project_id: upstream_project_id,
@@ -601,6 +611,167 @@ impl BreakpointStore {
         let breakpoint_paths = self.breakpoints.keys().cloned().collect();
         self.breakpoints.clear();
         cx.emit(BreakpointStoreEvent::BreakpointsCleared(breakpoint_paths));
+
+        if !self.function_breakpoints.is_empty() {
+            self.function_breakpoints.clear();
+            cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+        }
+        if !self.data_breakpoints.is_empty() {
+            self.data_breakpoints.clear();
+            cx.emit(BreakpointStoreEvent::DataBreakpointsUpdated);
+        }
+    }
+
+    pub fn function_breakpoints(&self) -> &[FunctionBreakpoint] {
+        &self.function_breakpoints
+    }
+
+    /// Adds a breakpoint on the function with the given name, unless there already is one.
+    pub fn add_function_breakpoint(&mut self, name: Arc<str>, cx: &mut Context<Self>) {
+        if name.trim().is_empty()
+            || self
+                .function_breakpoints
+                .iter()
+                .any(|breakpoint| breakpoint.name == name)
+        {
+            return;
+        }
+
+        self.function_breakpoints.push(FunctionBreakpoint {
+            name,
+            condition: None,
+            hit_condition: None,
+            state: BreakpointState::Enabled,
+        });
+        cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+        cx.notify();
+    }
+
+    pub fn edit_function_breakpoint(
+        &mut self,
+        name: &str,
+        edit_action: BreakpointEditAction,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ix) = self
+            .function_breakpoints
+            .iter()
+            .position(|breakpoint| &*breakpoint.name == name)
+        else {
+            return;
+        };
+
+        let breakpoint = &mut self.function_breakpoints[ix];
+        match edit_action {
+            BreakpointEditAction::Toggle => {
+                self.function_breakpoints.remove(ix);
+            }
+            BreakpointEditAction::InvertState => {
+                breakpoint.state = breakpoint.state.inverted();
+            }
+            BreakpointEditAction::EditCondition(condition) => {
+                breakpoint.condition = (!condition.is_empty()).then_some(condition);
+            }
+            BreakpointEditAction::EditHitCondition(hit_condition) => {
+                breakpoint.hit_condition = (!hit_condition.is_empty()).then_some(hit_condition);
+            }
+            BreakpointEditAction::EditLogMessage(_) => return,
+        }
+        cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+        cx.notify();
+    }
+
+    pub fn data_breakpoints(&self) -> &[DataBreakpoint] {
+        &self.data_breakpoints
+    }
+
+    /// Adds a data breakpoint, replacing the one on the same data, if any.
+    pub fn add_data_breakpoint(&mut self, breakpoint: DataBreakpoint, cx: &mut Context<Self>) {
+        self.data_breakpoints
+            .retain(|existing| existing.data_id != breakpoint.data_id);
+        self.data_breakpoints.push(breakpoint);
+        cx.emit(BreakpointStoreEvent::DataBreakpointsUpdated);
+        cx.notify();
+    }
+
+    /// Drops the data breakpoints whose data id was only valid in the given session.
+    pub fn remove_session_data_breakpoints(
+        &mut self,
+        session_id: SessionId,
+        cx: &mut Context<Self>,
+    ) {
+        let len = self.data_breakpoints.len();
+        self.data_breakpoints
+            .retain(|breakpoint| breakpoint.session_id != Some(session_id));
+        if self.data_breakpoints.len() != len {
+            cx.emit(BreakpointStoreEvent::DataBreakpointsUpdated);
+            cx.notify();
+        }
+    }
+
+    pub fn edit_data_breakpoint(
+        &mut self,
+        data_id: &str,
+        edit_action: BreakpointEditAction,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ix) = self
+            .data_breakpoints
+            .iter()
+            .position(|breakpoint| &*breakpoint.data_id == data_id)
+        else {
+            return;
+        };
+
+        let breakpoint = &mut self.data_breakpoints[ix];
+        match edit_action {
+            BreakpointEditAction::Toggle => {
+                self.data_breakpoints.remove(ix);
+            }
+            BreakpointEditAction::InvertState => {
+                breakpoint.state = breakpoint.state.inverted();
+            }
+            BreakpointEditAction::EditCondition(condition) => {
+                breakpoint.condition = (!condition.is_empty()).then_some(condition);
+            }
+            BreakpointEditAction::EditHitCondition(hit_condition) => {
+                breakpoint.hit_condition = (!hit_condition.is_empty()).then_some(hit_condition);
+            }
+            BreakpointEditAction::EditLogMessage(_) => return,
+        }
+        cx.emit(BreakpointStoreEvent::DataBreakpointsUpdated);
+        cx.notify();
+    }
+
+    pub fn watch_expressions(&self) -> &[Arc<str>] {
+        &self.watch_expressions
+    }
+
+    /// Adds an expression to watch, unless it is already watched.
+    pub fn add_watch_expression(&mut self, expression: Arc<str>, cx: &mut Context<Self>) {
+        if expression.trim().is_empty() || self.watch_expressions.contains(&expression) {
+            return;
+        }
+        self.watch_expressions.push(expression);
+        cx.emit(BreakpointStoreEvent::WatchExpressionsUpdated);
+        cx.notify();
+    }
+
+    pub fn remove_watch_expression(&mut self, expression: &str, cx: &mut Context<Self>) {
+        let Some(ix) = self
+            .watch_expressions
+            .iter()
+            .position(|existing| &**existing == expression)
+        else {
+            return;
+        };
+        self.watch_expressions.remove(ix);
+        cx.emit(BreakpointStoreEvent::WatchExpressionsUpdated);
+        cx.notify();
+    }
+
+    pub fn with_serialized_watch_expressions(&mut self, expressions: Vec<Arc<str>>) {
+        self.watch_expressions = expressions;
     }
 
     pub fn breakpoints<'a>(
@@ -771,12 +942,16 @@ impl BreakpointStore {
     }
 
     pub fn with_serialized_breakpoints(
-        &self,
+        &mut self,
         breakpoints: BTreeMap<Arc<Path>, Vec<SourceBreakpoint>>,
+        function_breakpoints: Vec<FunctionBreakpoint>,
+        data_breakpoints: Vec<DataBreakpoint>,
         cx: &mut Context<BreakpointStore>,
     ) -> Task<Result<()>> {
         if let BreakpointStoreMode::Local(mode) = &self.mode {
             let mode = mode.clone();
+            self.function_breakpoints = function_breakpoints;
+            self.data_breakpoints = data_breakpoints;
             cx.spawn(async move |this, cx| {
                 let mut new_breakpoints = BTreeMap::default();
                 for (path, bps) in breakpoints {
@@ -872,6 +1047,9 @@ pub enum BreakpointStoreEvent {
     ClearDebugLines,
     BreakpointsUpdated(Arc<Path>, BreakpointUpdatedReason),
     BreakpointsCleared(Vec<Arc<Path>>),
+    FunctionBreakpointsUpdated,
+    DataBreakpointsUpdated,
+    WatchExpressionsUpdated,
 }
 
 impl EventEmitter<BreakpointStoreEvent> for BreakpointStore {}
@@ -911,6 +1089,14 @@ impl BreakpointState {
             BreakpointState::Disabled => 1,
         }
     }
+
+    #[inline]
+    pub fn inverted(&self) -> Self {
+        match self {
+            BreakpointState::Enabled => BreakpointState::Disabled,
+            BreakpointState::Disabled => BreakpointState::Enabled,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -1033,3 +1219,104 @@ impl From<SourceBreakpoint> for dap::SourceBreakpoint {
         }
     }
 }
+
+/// Breakpoint that stops when a function with the given name is entered.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct FunctionBreakpoint {
+    pub name: Arc<str>,
+    pub condition: Option<Arc<str>>,
+    pub hit_condition: Option<Arc<str>>,
+    pub state: BreakpointState,
+}
+
+impl From<FunctionBreakpoint> for dap::FunctionBreakpoint {
+    fn from(bp: FunctionBreakpoint) -> Self {
+        Self {
+            name: String::from(bp.name.as_ref()),
+            condition: bp
+                .condition
+                .map(|condition| String::from(condition.as_ref())),
+            hit_condition: bp
+                .hit_condition
+                .map(|hit_condition| String::from(hit_condition.as_ref())),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum DataBreakpointAccessType {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl DataBreakpointAccessType {
+    #[inline]
+    pub fn to_int(&self) -> i32 {
+        match self {
+            DataBreakpointAccessType::Read => 0,
+            DataBreakpointAccessType::Write => 1,
+            DataBreakpointAccessType::ReadWrite => 2,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DataBreakpointAccessType::Read => "read",
+            DataBreakpointAccessType::Write => "write",
+            DataBreakpointAccessType::ReadWrite => "read/write",
+        }
+    }
+}
+
+impl From<DataBreakpointAccessType> for dap::DataBreakpointAccessType {
+    fn from(access_type: DataBreakpointAccessType) -> Self {
+        match access_type {
+            DataBreakpointAccessType::Read => dap::DataBreakpointAccessType::Read,
+            DataBreakpointAccessType::Write => dap::DataBreakpointAccessType::Write,
+            DataBreakpointAccessType::ReadWrite => dap::DataBreakpointAccessType::ReadWrite,
+        }
+    }
+}
+
+impl From<dap::DataBreakpointAccessType> for DataBreakpointAccessType {
+    fn from(access_type: dap::DataBreakpointAccessType) -> Self {
+        match access_type {
+            dap::DataBreakpointAccessType::Read => DataBreakpointAccessType::Read,
+            dap::DataBreakpointAccessType::Write => DataBreakpointAccessType::Write,
+            dap::DataBreakpointAccessType::ReadWrite => DataBreakpointAccessType::ReadWrite,
+        }
+    }
+}
+
+/// Breakpoint that stops when data (e.g. a variable) is accessed, also known as a watchpoint.
+/// The data is identified by the debug adapter, see [`crate::debugger::session::Session::data_breakpoint_info`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct DataBreakpoint {
+    pub data_id: Arc<str>,
+    /// Name of the data shown to the user, as reported by the debug adapter.
+    pub description: Arc<str>,
+    /// Which accesses the breakpoint stops at, the debug adapter picks when not set.
+    pub access_type: Option<DataBreakpointAccessType>,
+    pub condition: Option<Arc<str>>,
+    pub hit_condition: Option<Arc<str>>,
+    pub state: BreakpointState,
+    /// The debug session the data id is only valid in, if it does not stay valid across
+    /// sessions. Such breakpoints are not saved, and are dropped when their session ends.
+    pub session_id: Option<SessionId>,
+}
+
+impl From<DataBreakpoint> for dap::DataBreakpoint {
+    fn from(bp: DataBreakpoint) -> Self {
+        Self {
+            data_id: String::from(bp.data_id.as_ref()),
+            access_type: bp.access_type.map(Into::into),
+            condition: bp
+                .condition
+                .map(|condition| String::from(condition.as_ref())),
+            hit_condition: bp
+                .hit_condition
+                .map(|hit_condition| String::from(hit_condition.as_ref())),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use anyhow::{Context as _, Ok, Result};
+use base64::prelude::{BASE64_STANDARD, Engine as _};
 use dap::{
     Capabilities, ContinueArguments, ExceptionFilterOptions, InitializeRequestArguments,
     InitializeRequestArgumentsPathFormat, NextArguments, SetVariableResponse, SourceBreakpoint,
@@ -1177,6 +1178,194 @@ impl DapCommand for LoadedSourcesCommand {
     }
 }
 
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct DisassembleCommand {
+    pub memory_reference: String,
+    pub offset: Option<i64>,
+    pub instruction_offset: Option<i64>,
+    pub instruction_count: u64,
+    pub resolve_symbols: Option<bool>,
+}
+
+impl LocalDapCommand for DisassembleCommand {
+    type Response = Vec<dap::DisassembledInstruction>;
+    type DapRequest = dap::requests::Disassemble;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_disassemble_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::DisassembleArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            instruction_offset: self.instruction_offset,
+            instruction_count: self.instruction_count,
+            resolve_symbols: self.resolve_symbols,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.instructions)
+    }
+}
+
+impl DapCommand for DisassembleCommand {
+    type ProtoRequest = proto::DapDisassembleRequest;
+    type ProtoResponse = proto::DapDisassembleResponse;
+    const CACHEABLE: bool = true;
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            memory_reference: request.memory_reference.clone(),
+            offset: request.offset,
+            instruction_offset: request.instruction_offset,
+            instruction_count: request.instruction_count,
+            resolve_symbols: request.resolve_symbols,
+        }
+    }
+
+    fn to_proto(
+        &self,
+        debug_client_id: SessionId,
+        upstream_project_id: u64,
+    ) -> proto::DapDisassembleRequest {
+        proto::DapDisassembleRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            instruction_offset: self.instruction_offset,
+            instruction_count: self.instruction_count,
+            resolve_symbols: self.resolve_symbols,
+        }
+    }
+
+    fn response_to_proto(
+        debug_client_id: SessionId,
+        message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::DapDisassembleResponse {
+            instructions: message
+                .into_iter()
+                .map(|instruction| instruction.to_proto())
+                .collect(),
+            client_id: debug_client_id.to_proto(),
+        }
+    }
+
+    fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(message
+            .instructions
+            .into_iter()
+            .map(dap::DisassembledInstruction::from_proto)
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct ReadMemoryCommand {
+    pub memory_reference: String,
+    pub offset: Option<i64>,
+    pub count: u64,
+}
+
+impl LocalDapCommand for ReadMemoryCommand {
+    type Response = super::session::MemoryRegion;
+    type DapRequest = dap::requests::ReadMemory;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_read_memory_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::ReadMemoryArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            count: self.count,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        let data = match message.data {
+            Some(data) => BASE64_STANDARD
+                .decode(data)
+                .context("Decoding memory read by the debug adapter")?,
+            None => Vec::new(),
+        };
+        Ok(super::session::MemoryRegion {
+            address: message.address,
+            data,
+            unreadable_bytes: message.unreadable_bytes.unwrap_or_default(),
+        })
+    }
+}
+
+impl DapCommand for ReadMemoryCommand {
+    type ProtoRequest = proto::DapReadMemoryRequest;
+    type ProtoResponse = proto::DapReadMemoryResponse;
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            memory_reference: request.memory_reference.clone(),
+            offset: request.offset,
+            count: request.count,
+        }
+    }
+
+    fn to_proto(
+        &self,
+        debug_client_id: SessionId,
+        upstream_project_id: u64,
+    ) -> proto::DapReadMemoryRequest {
+        proto::DapReadMemoryRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            count: self.count,
+        }
+    }
+
+    fn response_to_proto(
+        debug_client_id: SessionId,
+        message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::DapReadMemoryResponse {
+            client_id: debug_client_id.to_proto(),
+            address: message.address,
+            data: message.data,
+            unreadable_bytes: message.unreadable_bytes,
+        }
+    }
+
+    fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(super::session::MemoryRegion {
+            address: message.address,
+            data: message.data,
+            unreadable_bytes: message.unreadable_bytes,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub(crate) struct StackTraceCommand {
     pub thread_id: u64,
@@ -1704,6 +1893,158 @@ impl LocalDapCommand for SetExceptionBreakpoints {
     }
 }
 
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct SetInstructionBreakpoints {
+    pub(super) breakpoints: Vec<dap::InstructionBreakpoint>,
+}
+
+impl LocalDapCommand for SetInstructionBreakpoints {
+    type Response = Vec<dap::Breakpoint>;
+    type DapRequest = dap::requests::SetInstructionBreakpoints;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_instruction_breakpoints
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetInstructionBreakpointsArguments {
+            breakpoints: self.breakpoints.clone(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.breakpoints)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct SetFunctionBreakpoints {
+    pub(super) breakpoints: Vec<dap::FunctionBreakpoint>,
+}
+
+impl LocalDapCommand for SetFunctionBreakpoints {
+    type Response = Vec<dap::Breakpoint>;
+    type DapRequest = dap::requests::SetFunctionBreakpoints;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_function_breakpoints
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetFunctionBreakpointsArguments {
+            breakpoints: self.breakpoints.clone(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.breakpoints)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct SetDataBreakpoints {
+    pub(super) breakpoints: Vec<dap::DataBreakpoint>,
+}
+
+impl LocalDapCommand for SetDataBreakpoints {
+    type Response = Vec<dap::Breakpoint>;
+    type DapRequest = dap::requests::SetDataBreakpoints;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities.supports_data_breakpoints.unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetDataBreakpointsArguments {
+            breakpoints: self.breakpoints.clone(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.breakpoints)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct DataBreakpointInfo {
+    pub(super) variables_reference: u64,
+    pub(super) name: String,
+}
+
+impl LocalDapCommand for DataBreakpointInfo {
+    type Response = dap::DataBreakpointInfoResponse;
+    type DapRequest = dap::requests::DataBreakpointInfo;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities.supports_data_breakpoints.unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::DataBreakpointInfoArguments {
+            variables_reference: Some(self.variables_reference),
+            name: self.name.clone(),
+            frame_id: None,
+            bytes: None,
+            as_address: None,
+            mode: None,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct WriteMemory {
+    pub(super) memory_reference: String,
+    pub(super) offset: Option<i64>,
+    pub(super) data: Vec<u8>,
+}
+
+impl LocalDapCommand for WriteMemory {
+    type Response = ();
+    type DapRequest = dap::requests::WriteMemory;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_write_memory_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::WriteMemoryArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            allow_partial: Some(false),
+            data: BASE64_STANDARD.encode(&self.data),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        _message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub(super) struct LocationsCommand {
     pub(super) reference: u64,
@@ -55,6 +55,10 @@ impl DapLocator for GoLocator {
                     tags: vec![],
                     show_summary: true,
                     show_command: true,
+                    depends_on: vec![],
+                    depends_order: Default::default(),
+                    problem_matcher: vec![],
+                    watch: vec![],
                 };
 
                 Some(DebugScenario {
@@ -94,6 +98,10 @@ impl DapLocator for GoLocator {
                     tags: vec![],
                     show_summary: true,
                     show_command: true,
+                    depends_on: vec![],
+                    depends_order: Default::default(),
+                    problem_matcher: vec![],
+                    watch: vec![],
                 };
 
                 Some(DebugScenario {
@@ -189,6 +197,10 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matcher: vec![],
+            watch: vec![],
         };
 
         let scenario =
@@ -237,6 +249,10 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matcher: vec![],
+            watch: vec![],
         };
 
         let scenario =
@@ -263,6 +279,10 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matcher: vec![],
+            watch: vec![],
         };
 
         let scenario = locator.create_scenario(
@@ -295,6 +315,10 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matcher: vec![],
+            watch: vec![],
         };
 
         let scenario =
@@ -351,6 +375,10 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matcher: vec![],
+            watch: vec![],
         };
 
         let scenario =
@@ -389,6 +417,10 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matcher: vec![],
+            watch: vec![],
         };
 
         let scenario =
@@ -422,6 +454,7 @@ mod tests {
             show_summary: true,
             show_command: true,
             show_rerun: true,
+            problem_matchers: Vec::new(),
         };
 
         let result = futures::executor::block_on(locator.run(build_config));
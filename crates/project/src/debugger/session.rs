@@ -1,15 +1,18 @@
 use crate::debugger::breakpoint_store::BreakpointSessionState;
 
 use super::breakpoint_store::{
-    BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason, SourceBreakpoint,
+    BreakpointState, BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason,
+    DataBreakpoint, DataBreakpointAccessType, SourceBreakpoint,
 };
 use super::dap_command::{
-    self, Attach, ConfigurationDone, ContinueCommand, DapCommand, DisconnectCommand,
-    EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
-    ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetExceptionBreakpoints, SetVariableValueCommand, StackTraceCommand,
-    StepBackCommand, StepCommand, StepInCommand, StepOutCommand, TerminateCommand,
-    TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    self, Attach, ConfigurationDone, ContinueCommand, DapCommand, DataBreakpointInfo,
+    DisassembleCommand, DisconnectCommand, EvaluateCommand, Initialize, Launch,
+    LoadedSourcesCommand, LocalDapCommand, LocationsCommand, ModulesCommand, NextCommand,
+    PauseCommand, ReadMemoryCommand, RestartCommand, RestartStackFrameCommand, ScopesCommand,
+    SetDataBreakpoints, SetExceptionBreakpoints, SetFunctionBreakpoints, SetInstructionBreakpoints,
+    SetVariableValueCommand, StackTraceCommand, StepBackCommand, StepCommand, StepInCommand,
+    StepOutCommand, TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    WriteMemory,
 };
 use super::dap_store::DapStore;
 use anyhow::{Context as _, Result, anyhow};
@@ -37,7 +40,7 @@ use gpui::{
 use serde_json::Value;
 use smol::stream::StreamExt;
 use std::any::TypeId;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::u64;
 use std::{
     any::Any,
@@ -297,6 +300,54 @@ impl RunningMode {
         self.request(arg)
     }
 
+    fn send_function_breakpoints(
+        &self,
+        ignore_breakpoints: bool,
+        breakpoint_store: &Entity<BreakpointStore>,
+        cx: &App,
+    ) -> Task<Result<Vec<dap::Breakpoint>>> {
+        let breakpoints = if ignore_breakpoints {
+            vec![]
+        } else {
+            breakpoint_store
+                .read(cx)
+                .function_breakpoints()
+                .iter()
+                .filter(|bp| bp.state.is_enabled())
+                .cloned()
+                .map(Into::into)
+                .collect()
+        };
+        self.request(SetFunctionBreakpoints { breakpoints })
+    }
+
+    fn send_data_breakpoints(
+        &self,
+        ignore_breakpoints: bool,
+        breakpoint_store: &Entity<BreakpointStore>,
+        cx: &App,
+    ) -> Task<Result<Vec<dap::Breakpoint>>> {
+        let session_id = self.client.id();
+        let breakpoints = if ignore_breakpoints {
+            vec![]
+        } else {
+            breakpoint_store
+                .read(cx)
+                .data_breakpoints()
+                .iter()
+                .filter(|bp| {
+                    bp.state.is_enabled()
+                        && bp
+                            .session_id
+                            .is_none_or(|bp_session| bp_session == session_id)
+                })
+                .cloned()
+                .map(Into::into)
+                .collect()
+        };
+        self.request(SetDataBreakpoints { breakpoints })
+    }
+
     fn send_source_breakpoints(
         &self,
         ignore_breakpoints: bool,
@@ -404,6 +455,8 @@ impl RunningMode {
         let supports_exception_filters = capabilities
             .supports_exception_filter_options
             .unwrap_or_default();
+        let supports_function_breakpoints = SetFunctionBreakpoints::is_supported(capabilities);
+        let supports_data_breakpoints = SetDataBreakpoints::is_supported(capabilities);
         let this = self.clone();
         let worktree = self.worktree().clone();
         let configuration_sequence = cx.spawn({
@@ -442,6 +495,17 @@ impl RunningMode {
                     }
                 })?;
 
+                if supports_function_breakpoints {
+                    cx.update(|cx| this.send_function_breakpoints(false, &breakpoint_store, cx))?
+                        .await
+                        .log_err();
+                }
+                if supports_data_breakpoints {
+                    cx.update(|cx| this.send_data_breakpoints(false, &breakpoint_store, cx))?
+                        .await
+                        .log_err();
+                }
+
                 this.send_exception_breakpoints(exception_filters, supports_exception_filters)
                     .await
                     .ok();
@@ -596,6 +660,8 @@ pub struct Session {
     pub(crate) breakpoint_store: Entity<BreakpointStore>,
     ignore_breakpoints: bool,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
+    /// Memory references of the instructions to break at, only meaningful for this session.
+    instruction_breakpoints: BTreeSet<String>,
     background_tasks: Vec<Task<()>>,
 }
 
@@ -671,6 +737,15 @@ impl CompletionsQuery {
     }
 }
 
+/// Contents of the debuggee memory, starting at `address`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryRegion {
+    pub address: String,
+    pub data: Vec<u8>,
+    /// How many bytes after `data` could not be read.
+    pub unreadable_bytes: u64,
+}
+
 #[derive(Debug)]
 pub enum SessionEvent {
     Modules,
@@ -680,6 +755,7 @@ pub enum SessionEvent {
     Variables,
     Threads,
     InvalidateInlineValue,
+    Memory,
     CapabilitiesLoaded,
     RunInTerminal {
         request: RunInTerminalRequestArguments,
@@ -733,7 +809,15 @@ impl Session {
                         local.unset_breakpoints_from_paths(paths, cx).detach();
                     }
                 }
-                BreakpointStoreEvent::SetDebugLine | BreakpointStoreEvent::ClearDebugLines => {}
+                BreakpointStoreEvent::FunctionBreakpointsUpdated => {
+                    this.send_function_breakpoints(cx);
+                }
+                BreakpointStoreEvent::DataBreakpointsUpdated => {
+                    this.send_data_breakpoints(cx);
+                }
+                BreakpointStoreEvent::SetDebugLine
+                | BreakpointStoreEvent::ClearDebugLines
+                | BreakpointStoreEvent::WatchExpressionsUpdated => {}
             })
             .detach();
             cx.on_app_quit(Self::on_app_quit).detach();
@@ -759,6 +843,7 @@ impl Session {
                 ignore_breakpoints: false,
                 breakpoint_store,
                 exception_breakpoints: Default::default(),
+                instruction_breakpoints: BTreeSet::default(),
                 label,
                 adapter,
             };
@@ -873,6 +958,10 @@ impl Session {
         &self.capabilities
     }
 
+    pub fn breakpoint_store(&self) -> &Entity<BreakpointStore> {
+        &self.breakpoint_store
+    }
+
     pub fn binary(&self) -> &DebugAdapterBinary {
         let Mode::Running(local_mode) = &self.mode else {
             panic!("Session is not running");
@@ -1324,7 +1413,9 @@ impl Session {
                 self.capabilities = self.capabilities.merge(event.capabilities);
                 cx.notify();
             }
-            Events::Memory(_) => {}
+            Events::Memory(_) => {
+                cx.emit(SessionEvent::Memory);
+            }
             Events::Process(_) => {}
             Events::ProgressEnd(_) => {}
             Events::ProgressStart(_) => {}
@@ -1551,6 +1642,9 @@ impl Session {
         }
 
         self.ignore_breakpoints = ignore;
+        self.send_instruction_breakpoints(cx);
+        self.send_function_breakpoints(cx);
+        self.send_data_breakpoints(cx);
 
         if let Some(local) = self.as_running() {
             local.send_source_breakpoints(ignore, &self.breakpoint_store, cx)
@@ -1593,6 +1687,189 @@ impl Session {
         }
     }
 
+    pub fn instruction_breakpoints(&self) -> &BTreeSet<String> {
+        &self.instruction_breakpoints
+    }
+
+    pub fn toggle_instruction_breakpoint(&mut self, instruction_reference: &str, cx: &mut App) {
+        if !self.instruction_breakpoints.remove(instruction_reference) {
+            self.instruction_breakpoints
+                .insert(instruction_reference.to_owned());
+        }
+        self.send_instruction_breakpoints(cx);
+    }
+
+    fn send_instruction_breakpoints(&self, cx: &App) {
+        if !SetInstructionBreakpoints::is_supported(&self.capabilities) {
+            return;
+        }
+        let Some(local) = self.as_running() else {
+            return;
+        };
+
+        let breakpoints = if self.ignore_breakpoints {
+            Vec::new()
+        } else {
+            self.instruction_breakpoints
+                .iter()
+                .map(|instruction_reference| dap::InstructionBreakpoint {
+                    instruction_reference: instruction_reference.clone(),
+                    offset: None,
+                    condition: None,
+                    hit_condition: None,
+                    mode: None,
+                })
+                .collect()
+        };
+        local
+            .request(SetInstructionBreakpoints { breakpoints })
+            .detach_and_log_err(cx);
+    }
+
+    fn send_function_breakpoints(&self, cx: &App) {
+        if !SetFunctionBreakpoints::is_supported(&self.capabilities) {
+            return;
+        }
+        if let Some(local) = self.as_running() {
+            local
+                .send_function_breakpoints(self.ignore_breakpoints, &self.breakpoint_store, cx)
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn send_data_breakpoints(&self, cx: &App) {
+        if !SetDataBreakpoints::is_supported(&self.capabilities) {
+            return;
+        }
+        if let Some(local) = self.as_running() {
+            local
+                .send_data_breakpoints(self.ignore_breakpoints, &self.breakpoint_store, cx)
+                .detach_and_log_err(cx);
+        }
+    }
+
+    /// Asks the debug adapter whether a data breakpoint can be set on the variable named `name`
+    /// in the container with `variables_reference`, and what identifies the data if so.
+    pub fn data_breakpoint_info(
+        &self,
+        variables_reference: u64,
+        name: String,
+    ) -> Task<Result<dap::DataBreakpointInfoResponse>> {
+        if !DataBreakpointInfo::is_supported(&self.capabilities) {
+            return Task::ready(Err(anyhow!(
+                "debug adapter does not support data breakpoints"
+            )));
+        }
+        let Some(local) = self.as_running() else {
+            return Task::ready(Err(anyhow!("no adapter running to set data breakpoints")));
+        };
+        local.request(DataBreakpointInfo {
+            variables_reference,
+            name,
+        })
+    }
+
+    /// Asks the debug adapter whether the variable `name` in the container `variables_reference`
+    /// can be watched and, if so, adds a data breakpoint stopping when it is written to.
+    pub fn add_data_breakpoint(
+        &mut self,
+        variables_reference: u64,
+        name: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let info = self.data_breakpoint_info(variables_reference, name);
+        let breakpoint_store = self.breakpoint_store.clone();
+        let session_id = self.session_id();
+        cx.spawn(async move |_, cx| {
+            let info = info.await?;
+            let Some(data_id) = info.data_id else {
+                return Err(anyhow!("cannot watch this variable: {}", info.description));
+            };
+            let access_type = info.access_types.and_then(|access_types| {
+                access_types
+                    .iter()
+                    .find(|access_type| **access_type == dap::DataBreakpointAccessType::Write)
+                    .or_else(|| access_types.first())
+                    .cloned()
+                    .map(DataBreakpointAccessType::from)
+            });
+            breakpoint_store.update(cx, |breakpoint_store, cx| {
+                breakpoint_store.add_data_breakpoint(
+                    DataBreakpoint {
+                        data_id: data_id.into(),
+                        description: info.description.into(),
+                        access_type,
+                        condition: None,
+                        hit_condition: None,
+                        state: BreakpointState::Enabled,
+                        session_id: (!info.can_persist.unwrap_or_default()).then_some(session_id),
+                    },
+                    cx,
+                );
+            })
+        })
+    }
+
+    /// Disassembles `instruction_count` instructions around the one at `memory_reference`,
+    /// starting `instruction_offset` instructions away from it.
+    pub fn disassemble(
+        &self,
+        memory_reference: String,
+        instruction_offset: i64,
+        instruction_count: u64,
+    ) -> Task<Result<Vec<dap::DisassembledInstruction>>> {
+        if !DisassembleCommand::is_supported(&self.capabilities) {
+            return Task::ready(Err(anyhow!("debug adapter does not support disassembling")));
+        }
+        self.mode.request_dap(DisassembleCommand {
+            memory_reference,
+            offset: None,
+            instruction_offset: Some(instruction_offset),
+            instruction_count,
+            resolve_symbols: Some(true),
+        })
+    }
+
+    /// Reads `count` bytes of memory at `offset` bytes from `memory_reference`.
+    pub fn read_memory(
+        &self,
+        memory_reference: String,
+        offset: i64,
+        count: u64,
+    ) -> Task<Result<MemoryRegion>> {
+        if !ReadMemoryCommand::is_supported(&self.capabilities) {
+            return Task::ready(Err(anyhow!(
+                "debug adapter does not support reading memory"
+            )));
+        }
+        self.mode.request_dap(ReadMemoryCommand {
+            memory_reference,
+            offset: Some(offset),
+            count,
+        })
+    }
+
+    pub fn write_memory(
+        &self,
+        memory_reference: String,
+        offset: i64,
+        data: Vec<u8>,
+    ) -> Task<Result<()>> {
+        if !WriteMemory::is_supported(&self.capabilities) {
+            return Task::ready(Err(anyhow!(
+                "debug adapter does not support writing memory"
+            )));
+        }
+        let Some(local) = self.as_running() else {
+            return Task::ready(Err(anyhow!("no adapter running to write memory")));
+        };
+        local.request(WriteMemory {
+            memory_reference,
+            offset: Some(offset),
+            data,
+        })
+    }
+
     pub fn breakpoints_enabled(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -1713,6 +1990,10 @@ impl Session {
     pub fn shutdown(&mut self, cx: &mut Context<Self>) -> Task<()> {
         self.is_session_terminated = true;
         self.thread_states.exit_all_threads();
+        let session_id = self.session_id();
+        self.breakpoint_store.update(cx, |breakpoint_store, cx| {
+            breakpoint_store.remove_session_data_breakpoints(session_id, cx)
+        });
         cx.notify();
 
         let task = if self
@@ -2149,6 +2430,20 @@ impl Session {
         })
     }
 
+    /// Evaluates a watch expression in the given stack frame, without echoing it to the console.
+    pub fn evaluate_watch(
+        &self,
+        expression: String,
+        frame_id: Option<u64>,
+    ) -> Task<Result<dap::EvaluateResponse>> {
+        self.mode.request_dap(EvaluateCommand {
+            expression,
+            context: Some(EvaluateArgumentsContext::Watch),
+            frame_id,
+            source: None,
+        })
+    }
+
     pub fn location(
         &mut self,
         reference: u64,
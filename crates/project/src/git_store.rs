@@ -11,7 +11,9 @@ use askpass::AskPassDelegate;
 use buffer_diff::{BufferDiff, BufferDiffEvent};
 use client::ProjectId;
 use collections::HashMap;
-pub use conflict_set::{ConflictRegion, ConflictSet, ConflictSetSnapshot, ConflictSetUpdate};
+pub use conflict_set::{
+    ConflictRegion, ConflictSet, ConflictSetSnapshot, ConflictSetUpdate, ConflictSide,
+};
 use fs::Fs;
 use futures::{
     FutureExt, StreamExt as _,
@@ -19,22 +21,26 @@ use futures::{
     future::{self, Shared},
 };
 use git::{
-    BuildPermalinkParams, GitHostingProviderRegistry, WORK_DIRECTORY_REPO_PATH,
+    BuildPermalinkParams, DOT_JJ, GitHostingProviderRegistry, WORK_DIRECTORY_REPO_PATH,
     blame::Blame,
     parse_git_remote_url,
     repository::{
-        Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
-        GitRepository, GitRepositoryCheckpoint, PushOptions, Remote, RemoteCommandOutput, RepoPath,
-        ResetMode, UpstreamTrackingStatus,
+        Branch, CommitDetails, CommitDiff, CommitFile, CommitLogEntry, CommitOptions,
+        ConflictStages, DiffType, FetchOptions, GitOperation, GitRepository,
+        GitRepositoryCheckpoint, LogOptions, PushOptions, RebaseAction, RebaseTodoEntry, Remote,
+        RemoteCommandOutput, RepoPath, ResetMode, StashEntry, Tag, UpstreamTrackingStatus,
+        Worktree, is_commit_sha,
     },
     status::{
-        FileStatus, GitSummary, StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode,
+        FileStatus, GitStatus, GitSummary, StatusCode, TrackedStatus, UnmergedStatus,
+        UnmergedStatusCode,
     },
 };
 use gpui::{
-    App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Subscription, Task,
-    WeakEntity,
+    App, AppContext, AsyncApp, BackgroundExecutor, Context, Entity, EventEmitter, SharedString,
+    Subscription, Task, WeakEntity,
 };
+use jj::{FileChangeKind, JujutsuRepository, RealJujutsuRepository};
 use language::{
     Buffer, BufferEvent, Language, LanguageRegistry,
     proto::{deserialize_version, serialize_version},
@@ -234,6 +240,8 @@ pub struct MergeDetails {
     pub conflicted_paths: TreeSet<RepoPath>,
     pub message: Option<SharedString>,
     pub heads: Vec<Option<SharedString>>,
+    /// The merge, rebase, cherry-pick or revert that's waiting to be continued or aborted.
+    pub in_progress_operation: Option<GitOperation>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -245,6 +253,9 @@ pub struct RepositorySnapshot {
     pub head_commit: Option<CommitDetails>,
     pub scan_id: u64,
     pub merge: MergeDetails,
+    /// Whether this is a jj workspace without a git working tree of its own. Its git store is
+    /// bare, so there's no index to stage into or commit from.
+    pub is_pure_jujutsu: bool,
 }
 
 type JobId = u64;
@@ -278,11 +289,22 @@ impl std::ops::Deref for Repository {
     }
 }
 
+/// The jj workspaces that tests open in place of real ones, keyed by work directory.
+#[cfg(any(test, feature = "test-support"))]
+#[derive(Default)]
+pub struct FakeJujutsuRepositories(pub HashMap<PathBuf, Arc<dyn JujutsuRepository>>);
+
+#[cfg(any(test, feature = "test-support"))]
+impl gpui::Global for FakeJujutsuRepositories {}
+
 #[derive(Clone)]
 pub enum RepositoryState {
     Local {
         backend: Arc<dyn GitRepository>,
         environment: Arc<HashMap<String, String>>,
+        /// Set when the repository is colocated with a jj workspace, in which case diffs and
+        /// statuses reflect the working-copy change rather than the git index.
+        jujutsu: Option<Arc<dyn JujutsuRepository>>,
     },
     Remote {
         project_id: ProjectId,
@@ -413,6 +435,18 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_get_branches);
         client.add_entity_request_handler(Self::handle_change_branch);
         client.add_entity_request_handler(Self::handle_create_branch);
+        client.add_entity_request_handler(Self::handle_delete_branch);
+        client.add_entity_request_handler(Self::handle_is_branch_merged);
+        client.add_entity_request_handler(Self::handle_rename_branch);
+        client.add_entity_request_handler(Self::handle_set_upstream);
+        client.add_entity_request_handler(Self::handle_diff_to_merge_base);
+        client.add_entity_request_handler(Self::handle_worktrees);
+        client.add_entity_request_handler(Self::handle_create_worktree);
+        client.add_entity_request_handler(Self::handle_remove_worktree);
+        client.add_entity_request_handler(Self::handle_tags);
+        client.add_entity_request_handler(Self::handle_create_tag);
+        client.add_entity_request_handler(Self::handle_delete_tag);
+        client.add_entity_request_handler(Self::handle_load_conflict_stages);
         client.add_entity_request_handler(Self::handle_git_init);
         client.add_entity_request_handler(Self::handle_push);
         client.add_entity_request_handler(Self::handle_pull);
@@ -424,6 +458,18 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_show);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
         client.add_entity_request_handler(Self::handle_checkout_files);
+        client.add_entity_request_handler(Self::handle_stash_push);
+        client.add_entity_request_handler(Self::handle_stash_list);
+        client.add_entity_request_handler(Self::handle_stash_apply);
+        client.add_entity_request_handler(Self::handle_stash_pop);
+        client.add_entity_request_handler(Self::handle_stash_drop);
+        client.add_entity_request_handler(Self::handle_stash_show);
+        client.add_entity_request_handler(Self::handle_log);
+        client.add_entity_request_handler(Self::handle_rebase);
+        client.add_entity_request_handler(Self::handle_cherry_pick);
+        client.add_entity_request_handler(Self::handle_revert);
+        client.add_entity_request_handler(Self::handle_continue_operation);
+        client.add_entity_request_handler(Self::handle_abort_operation);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
         client.add_entity_request_handler(Self::handle_set_index_text);
         client.add_entity_request_handler(Self::handle_askpass);
@@ -1799,10 +1845,231 @@ impl GitStore {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
         let branch_name = envelope.payload.branch_name;
+        let base_revision = envelope.payload.base_revision;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.create_branch(branch_name, base_revision)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_delete_branch(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDeleteBranch>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle
+                    .delete_branch(envelope.payload.branch_name, envelope.payload.force)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_is_branch_merged(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitIsBranchMerged>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitIsBranchMergedResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let merged = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.is_branch_merged(envelope.payload.branch_name)
+            })?
+            .await??;
+        Ok(proto::GitIsBranchMergedResponse { merged })
+    }
+
+    async fn handle_rename_branch(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRenameBranch>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle
+                    .rename_branch(envelope.payload.old_name, envelope.payload.new_name)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_set_upstream(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSetUpstream>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle
+                    .set_upstream(envelope.payload.branch_name, envelope.payload.upstream)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_diff_to_merge_base(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDiffToMergeBase>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadCommitDiffResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let commit_diff = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.diff_to_merge_base(envelope.payload.base)
+            })?
+            .await??;
+        Ok(commit_diff_to_proto(commit_diff))
+    }
+
+    async fn handle_worktrees(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitWorktrees>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitWorktreesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let worktrees = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.worktrees()
+            })?
+            .await??;
+        Ok(proto::GitWorktreesResponse {
+            worktrees: worktrees.iter().map(worktree_to_proto).collect(),
+        })
+    }
+
+    async fn handle_create_worktree(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateWorktree>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.create_worktree(
+                    PathBuf::from(envelope.payload.path),
+                    envelope.payload.branch_name,
+                    envelope.payload.create_branch,
+                )
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_remove_worktree(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRemoveWorktree>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle
+                    .remove_worktree(PathBuf::from(envelope.payload.path), envelope.payload.force)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_tags(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitTags>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitTagsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let tags = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.tags(envelope.payload.points_at)
+            })?
+            .await??;
+        Ok(proto::GitTagsResponse {
+            tags: tags.iter().map(tag_to_proto).collect(),
+        })
+    }
+
+    async fn handle_load_conflict_stages(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLoadConflictStages>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLoadConflictStagesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let repo_path = RepoPath::from_str(&envelope.payload.path);
+
+        let stages = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.load_conflict_stages(repo_path)
+            })?
+            .await??;
+        Ok(proto::GitLoadConflictStagesResponse {
+            base: stages.base,
+            ours: stages.ours,
+            theirs: stages.theirs,
+        })
+    }
+
+    async fn handle_create_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.create_tag(
+                    envelope.payload.name,
+                    envelope.payload.commit,
+                    envelope.payload.message,
+                )
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_delete_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDeleteTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
         repository_handle
             .update(&mut cx, |repository_handle, _| {
-                repository_handle.create_branch(branch_name)
+                repository_handle.delete_tag(envelope.payload.name)
             })?
             .await??;
 
@@ -1862,17 +2129,7 @@ impl GitStore {
                 repository_handle.load_commit_diff(envelope.payload.commit)
             })?
             .await??;
-        Ok(proto::LoadCommitDiffResponse {
-            files: commit_diff
-                .files
-                .into_iter()
-                .map(|file| proto::CommitFile {
-                    path: file.path.to_string(),
-                    old_text: file.old_text,
-                    new_text: file.new_text,
-                })
-                .collect(),
-        })
+        Ok(commit_diff_to_proto(commit_diff))
     }
 
     async fn handle_reset(
@@ -1918,109 +2175,366 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
-    async fn handle_open_commit_message_buffer(
-        this: Entity<Self>,
-        envelope: TypedEnvelope<proto::OpenCommitMessageBuffer>,
-        mut cx: AsyncApp,
-    ) -> Result<proto::OpenBufferResponse> {
-        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
-        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
-        let buffer = repository
-            .update(&mut cx, |repository, cx| {
-                repository.open_commit_buffer(None, this.read(cx).buffer_store.clone(), cx)
-            })?
-            .await?;
-
-        let buffer_id = buffer.read_with(&cx, |buffer, _| buffer.remote_id())?;
-        this.update(&mut cx, |this, cx| {
-            this.buffer_store.update(cx, |buffer_store, cx| {
-                buffer_store
-                    .create_buffer_for_peer(
-                        &buffer,
-                        envelope.original_sender_id.unwrap_or(envelope.sender_id),
-                        cx,
-                    )
-                    .detach_and_log_err(cx);
-            })
-        })?;
-
-        Ok(proto::OpenBufferResponse {
-            buffer_id: buffer_id.to_proto(),
-        })
-    }
-
-    async fn handle_askpass(
+    async fn handle_stash_push(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::AskPassRequest>,
+        envelope: TypedEnvelope<proto::GitStashPush>,
         mut cx: AsyncApp,
-    ) -> Result<proto::AskPassResponse> {
+    ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
-        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
-
-        let delegates = cx.update(|cx| repository.read(cx).askpass_delegates.clone())?;
-        let Some(mut askpass) = delegates.lock().remove(&envelope.payload.askpass_id) else {
-            debug_panic!("no askpass found");
-            anyhow::bail!("no askpass found");
-        };
-
-        let response = askpass.ask_password(envelope.payload.prompt).await?;
-
-        delegates
-            .lock()
-            .insert(envelope.payload.askpass_id, askpass);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|s| RepoPath::from_str(s))
+            .collect();
 
-        Ok(proto::AskPassResponse { response })
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_push(envelope.payload.message, paths)
+            })?
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    async fn handle_check_for_pushed_commits(
+    async fn handle_stash_list(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::CheckForPushedCommits>,
+        envelope: TypedEnvelope<proto::GitStashList>,
         mut cx: AsyncApp,
-    ) -> Result<proto::CheckForPushedCommitsResponse> {
+    ) -> Result<proto::GitStashListResponse> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        let branches = repository_handle
+        let entries = repository_handle
             .update(&mut cx, |repository_handle, _| {
-                repository_handle.check_for_pushed_commits()
+                repository_handle.stash_list()
             })?
             .await??;
-        Ok(proto::CheckForPushedCommitsResponse {
-            pushed_to: branches
+        Ok(proto::GitStashListResponse {
+            entries: entries
                 .into_iter()
-                .map(|commit| commit.to_string())
+                .map(|entry| proto::StashEntry {
+                    index: entry.index as u64,
+                    sha: entry.sha.to_string(),
+                    message: entry.message.to_string(),
+                    branch: entry.branch.map(|branch| branch.to_string()),
+                    timestamp: entry.timestamp,
+                })
                 .collect(),
         })
     }
 
-    async fn handle_git_diff(
+    async fn handle_stash_apply(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitDiff>,
+        envelope: TypedEnvelope<proto::GitStashApply>,
         mut cx: AsyncApp,
-    ) -> Result<proto::GitDiffResponse> {
+    ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
-        let diff_type = match envelope.payload.diff_type() {
-            proto::git_diff::DiffType::HeadToIndex => DiffType::HeadToIndex,
-            proto::git_diff::DiffType::HeadToWorktree => DiffType::HeadToWorktree,
-        };
 
-        let mut diff = repository_handle
-            .update(&mut cx, |repository_handle, cx| {
-                repository_handle.diff(diff_type, cx)
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_apply(envelope.payload.sha)
             })?
             .await??;
-        const ONE_MB: usize = 1_000_000;
-        if diff.len() > ONE_MB {
-            diff = diff.chars().take(ONE_MB).collect()
-        }
-
-        Ok(proto::GitDiffResponse { diff })
+        Ok(proto::Ack {})
     }
 
-    async fn handle_open_unstaged_diff(
+    async fn handle_stash_pop(
         this: Entity<Self>,
-        request: TypedEnvelope<proto::OpenUnstagedDiff>,
+        envelope: TypedEnvelope<proto::GitStashPop>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_pop(envelope.payload.sha)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_stash_drop(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitStashDrop>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_drop(envelope.payload.sha)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_stash_show(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitStashShow>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadCommitDiffResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let commit_diff = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_show(envelope.payload.sha)
+            })?
+            .await??;
+        Ok(commit_diff_to_proto(commit_diff))
+    }
+
+    async fn handle_rebase(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebase>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let todo = envelope
+            .payload
+            .todo
+            .into_iter()
+            .map(proto_to_rebase_todo_entry)
+            .collect::<Vec<_>>();
+        anyhow::ensure!(
+            is_commit_sha(&envelope.payload.base)
+                && todo.iter().all(|entry| is_commit_sha(&entry.sha)),
+            "rebase requires full commit SHAs"
+        );
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_interactive(envelope.payload.base, todo)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_cherry_pick(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCherryPick>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let commit = envelope.payload.commit;
+        anyhow::ensure!(is_commit_sha(&commit), "invalid commit {commit:?}");
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.cherry_pick(commit)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_revert(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRevert>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let commit = envelope.payload.commit;
+        anyhow::ensure!(is_commit_sha(&commit), "invalid commit {commit:?}");
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.revert(commit)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_continue_operation(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitContinueOperation>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.continue_operation()
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_abort_operation(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitAbortOperation>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.abort_operation()
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_log(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLog>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLogResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let options = LogOptions {
+            revision_range: envelope.payload.revision_range,
+            paths: envelope
+                .payload
+                .paths
+                .iter()
+                .map(|s| RepoPath::from_str(s))
+                .collect(),
+            author: envelope.payload.author,
+            skip: envelope.payload.skip as usize,
+            limit: envelope.payload.limit as usize,
+        };
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.log(options)
+            })?
+            .await??;
+        Ok(proto::GitLogResponse {
+            entries: entries
+                .into_iter()
+                .map(|entry| proto::CommitLogEntry {
+                    sha: entry.sha.to_string(),
+                    parent_shas: entry
+                        .parent_shas
+                        .into_iter()
+                        .map(|sha| sha.to_string())
+                        .collect(),
+                    subject: entry.subject.to_string(),
+                    author_name: entry.author_name.to_string(),
+                    author_email: entry.author_email.to_string(),
+                    commit_timestamp: entry.commit_timestamp,
+                    refs: entry
+                        .refs
+                        .into_iter()
+                        .map(|name| name.to_string())
+                        .collect(),
+                })
+                .collect(),
+        })
+    }
+
+    async fn handle_open_commit_message_buffer(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::OpenCommitMessageBuffer>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::OpenBufferResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let buffer = repository
+            .update(&mut cx, |repository, cx| {
+                repository.open_commit_buffer(None, this.read(cx).buffer_store.clone(), cx)
+            })?
+            .await?;
+
+        let buffer_id = buffer.read_with(&cx, |buffer, _| buffer.remote_id())?;
+        this.update(&mut cx, |this, cx| {
+            this.buffer_store.update(cx, |buffer_store, cx| {
+                buffer_store
+                    .create_buffer_for_peer(
+                        &buffer,
+                        envelope.original_sender_id.unwrap_or(envelope.sender_id),
+                        cx,
+                    )
+                    .detach_and_log_err(cx);
+            })
+        })?;
+
+        Ok(proto::OpenBufferResponse {
+            buffer_id: buffer_id.to_proto(),
+        })
+    }
+
+    async fn handle_askpass(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::AskPassRequest>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::AskPassResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let delegates = cx.update(|cx| repository.read(cx).askpass_delegates.clone())?;
+        let Some(mut askpass) = delegates.lock().remove(&envelope.payload.askpass_id) else {
+            debug_panic!("no askpass found");
+            anyhow::bail!("no askpass found");
+        };
+
+        let response = askpass.ask_password(envelope.payload.prompt).await?;
+
+        delegates
+            .lock()
+            .insert(envelope.payload.askpass_id, askpass);
+
+        Ok(proto::AskPassResponse { response })
+    }
+
+    async fn handle_check_for_pushed_commits(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::CheckForPushedCommits>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::CheckForPushedCommitsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let branches = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.check_for_pushed_commits()
+            })?
+            .await??;
+        Ok(proto::CheckForPushedCommitsResponse {
+            pushed_to: branches
+                .into_iter()
+                .map(|commit| commit.to_string())
+                .collect(),
+        })
+    }
+
+    async fn handle_git_diff(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitDiffResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let diff_type = match envelope.payload.diff_type() {
+            proto::git_diff::DiffType::HeadToIndex => DiffType::HeadToIndex,
+            proto::git_diff::DiffType::HeadToWorktree => DiffType::HeadToWorktree,
+        };
+
+        let mut diff = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.diff(diff_type, cx)
+            })?
+            .await??;
+        const ONE_MB: usize = 1_000_000;
+        if diff.len() > ONE_MB {
+            diff = diff.chars().take(ONE_MB).collect()
+        }
+
+        Ok(proto::GitDiffResponse { diff })
+    }
+
+    async fn handle_open_unstaged_diff(
+        this: Entity<Self>,
+        request: TypedEnvelope<proto::OpenUnstagedDiff>,
         mut cx: AsyncApp,
     ) -> Result<proto::OpenUnstagedDiffResponse> {
         let buffer_id = BufferId::new(request.payload.buffer_id)?;
@@ -2554,6 +3068,7 @@ impl RepositorySnapshot {
             head_commit: None,
             scan_id: 0,
             merge: Default::default(),
+            is_pure_jujutsu: false,
         }
     }
 
@@ -2573,12 +3088,17 @@ impl RepositorySnapshot {
                 .iter()
                 .map(|repo_path| repo_path.to_proto())
                 .collect(),
+            in_progress_operation: self
+                .merge
+                .in_progress_operation
+                .map(|operation| git_operation_to_proto(operation) as i32),
             project_id,
             id: self.id.to_proto(),
             abs_path: self.work_directory_abs_path.to_proto(),
             entry_ids: vec![self.id.to_proto()],
             scan_id: self.scan_id,
             is_last_update: true,
+            is_pure_jujutsu: self.is_pure_jujutsu,
         }
     }
 
@@ -2635,12 +3155,17 @@ impl RepositorySnapshot {
                 .iter()
                 .map(|path| path.as_ref().to_proto())
                 .collect(),
+            in_progress_operation: self
+                .merge
+                .in_progress_operation
+                .map(|operation| git_operation_to_proto(operation) as i32),
             project_id,
             id: self.id.to_proto(),
             abs_path: self.work_directory_abs_path.to_proto(),
             entry_ids: vec![],
             scan_id: self.scan_id,
             is_last_update: true,
+            is_pure_jujutsu: self.is_pure_jujutsu,
         }
     }
 
@@ -2711,6 +3236,7 @@ impl MergeDetails {
             .into_iter()
             .map(|opt| opt.map(SharedString::from))
             .collect::<Vec<_>>();
+        let in_progress_operation = backend.in_progress_operation().await;
         let merge_heads_changed = heads != prev_snapshot.merge.heads;
         let conflicted_paths = if merge_heads_changed {
             let current_conflicted_paths = TreeSet::from_ordered_entries(
@@ -2733,6 +3259,7 @@ impl MergeDetails {
                 return Ok((
                     MergeDetails {
                         message: message.map(SharedString::from),
+                        in_progress_operation,
                         ..prev_snapshot.merge.clone()
                     },
                     false,
@@ -2747,6 +3274,7 @@ impl MergeDetails {
             conflicted_paths,
             message: message.map(SharedString::from),
             heads,
+            in_progress_operation,
         };
         Ok((details, merge_heads_changed))
     }
@@ -2768,7 +3296,8 @@ impl Repository {
         git_store: WeakEntity<GitStore>,
         cx: &mut Context<Self>,
     ) -> Self {
-        let snapshot = RepositorySnapshot::empty(id, work_directory_abs_path.clone());
+        let mut snapshot = RepositorySnapshot::empty(id, work_directory_abs_path.clone());
+        snapshot.is_pure_jujutsu = dot_git_abs_path.file_name() == Some(*DOT_JJ);
         Repository {
             this: cx.weak_entity(),
             git_store,
@@ -3285,28 +3814,337 @@ impl Repository {
                             commit,
                         })
                         .await?;
-                    Ok(CommitDiff {
-                        files: response
-                            .files
-                            .into_iter()
-                            .map(|file| CommitFile {
-                                path: Path::new(&file.path).into(),
-                                old_text: file.old_text,
-                                new_text: file.new_text,
-                            })
-                            .collect(),
-                    })
+                    Ok(proto_to_commit_diff(response))
                 }
             }
         })
     }
 
-    fn buffer_store(&self, cx: &App) -> Option<Entity<BufferStore>> {
-        Some(self.git_store.upgrade()?.read(cx).buffer_store.clone())
+    pub fn stash_push(
+        &mut self,
+        message: Option<String>,
+        paths: Vec<RepoPath>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(Some("git stash".into()), move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.stash_push(message, paths, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitStashPush {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            message,
+                            paths: paths
+                                .into_iter()
+                                .map(|p| p.to_string_lossy().to_string())
+                                .collect(),
+                        })
+                        .await?;
+                    Ok(())
+                }
+            }
+        })
     }
 
-    pub fn stage_entries(
-        &self,
+    pub fn stash_list(&mut self) -> oneshot::Receiver<Result<Vec<StashEntry>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.stash_list().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitStashList {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+                    Ok(response
+                        .entries
+                        .into_iter()
+                        .map(|entry| StashEntry {
+                            index: entry.index as usize,
+                            sha: entry.sha.into(),
+                            message: entry.message.into(),
+                            branch: entry.branch.map(Into::into),
+                            timestamp: entry.timestamp,
+                        })
+                        .collect())
+                }
+            }
+        })
+    }
+
+    pub fn stash_apply(&mut self, sha: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git stash apply".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.stash_apply(sha, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitStashApply {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                sha,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn stash_pop(&mut self, sha: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git stash pop".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.stash_pop(sha, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitStashPop {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                sha,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn stash_drop(&mut self, sha: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git stash drop".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.stash_drop(sha, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitStashDrop {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                sha,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn stash_show(&mut self, sha: String) -> oneshot::Receiver<Result<CommitDiff>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.stash_show(sha, cx).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitStashShow {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            sha,
+                        })
+                        .await?;
+                    Ok(proto_to_commit_diff(response))
+                }
+            }
+        })
+    }
+
+    pub fn log(&mut self, options: LogOptions) -> oneshot::Receiver<Result<Vec<CommitLogEntry>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.log(options).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitLog {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            revision_range: options.revision_range,
+                            paths: options
+                                .paths
+                                .into_iter()
+                                .map(|p| p.to_string_lossy().to_string())
+                                .collect(),
+                            author: options.author,
+                            skip: options.skip as u64,
+                            limit: options.limit as u64,
+                        })
+                        .await?;
+                    Ok(response
+                        .entries
+                        .into_iter()
+                        .map(|entry| CommitLogEntry {
+                            sha: entry.sha.into(),
+                            parent_shas: entry.parent_shas.into_iter().map(Into::into).collect(),
+                            subject: entry.subject.into(),
+                            author_name: entry.author_name.into(),
+                            author_email: entry.author_email.into(),
+                            commit_timestamp: entry.commit_timestamp,
+                            refs: entry.refs.into_iter().map(Into::into).collect(),
+                        })
+                        .collect())
+                }
+            }
+        })
+    }
+
+    pub fn rebase_interactive(
+        &mut self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git rebase --interactive".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.rebase_interactive(base, todo, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitRebase {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                base,
+                                todo: todo.into_iter().map(rebase_todo_entry_to_proto).collect(),
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn cherry_pick(&mut self, commit: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git cherry-pick".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.cherry_pick(commit, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitCherryPick {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commit,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn revert(&mut self, commit: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(Some("git revert".into()), move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.revert(commit, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitRevert {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                        })
+                        .await?;
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn continue_operation(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(Some("git continue".into()), move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.continue_operation(environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitContinueOperation {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn abort_operation(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(Some("git abort".into()), move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.abort_operation(environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitAbortOperation {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    fn buffer_store(&self, cx: &App) -> Option<Entity<BufferStore>> {
+        Some(self.git_store.upgrade()?.read(cx).buffer_store.clone())
+    }
+
+    pub fn stage_entries(
+        &self,
         entries: Vec<RepoPath>,
         cx: &mut Context<Self>,
     ) -> Task<anyhow::Result<()>> {
@@ -3689,177 +4527,499 @@ impl Repository {
                             .await
                             .context("sending pull request")?;
 
-                        Ok(RemoteCommandOutput {
-                            stdout: response.stdout,
-                            stderr: response.stderr,
+                        Ok(RemoteCommandOutput {
+                            stdout: response.stdout,
+                            stderr: response.stderr,
+                        })
+                    }
+                }
+            },
+        )
+    }
+
+    fn spawn_set_index_text_job(
+        &mut self,
+        path: RepoPath,
+        content: Option<String>,
+        hunk_staging_operation_count: Option<usize>,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<anyhow::Result<()>> {
+        let id = self.id;
+        let this = cx.weak_entity();
+        let git_store = self.git_store.clone();
+        self.send_keyed_job(
+            Some(GitJobKey::WriteIndex(path.clone())),
+            None,
+            move |git_repo, mut cx| async move {
+                log::debug!("start updating index text for buffer {}", path.display());
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend
+                            .set_index_text(path.clone(), content, environment.clone())
+                            .await?;
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::SetIndexText {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                path: path.as_ref().to_proto(),
+                                text: content,
+                            })
+                            .await?;
+                    }
+                }
+                log::debug!("finish updating index text for buffer {}", path.display());
+
+                if let Some(hunk_staging_operation_count) = hunk_staging_operation_count {
+                    let project_path = this
+                        .read_with(&cx, |this, cx| this.repo_path_to_project_path(&path, cx))
+                        .ok()
+                        .flatten();
+                    git_store.update(&mut cx, |git_store, cx| {
+                        let buffer_id = git_store
+                            .buffer_store
+                            .read(cx)
+                            .get_by_path(&project_path?, cx)?
+                            .read(cx)
+                            .remote_id();
+                        let diff_state = git_store.diffs.get(&buffer_id)?;
+                        diff_state.update(cx, |diff_state, _| {
+                            diff_state.hunk_staging_operation_count_as_of_write =
+                                hunk_staging_operation_count;
+                        });
+                        Some(())
+                    })?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    pub fn get_remotes(
+        &mut self,
+        branch_name: Option<String>,
+    ) -> oneshot::Receiver<Result<Vec<Remote>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.get_remotes(branch_name).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GetRemotes {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            branch_name,
+                        })
+                        .await?;
+
+                    let remotes = response
+                        .remotes
+                        .into_iter()
+                        .map(|remotes| git::repository::Remote {
+                            name: remotes.name.into(),
+                        })
+                        .collect();
+
+                    Ok(remotes)
+                }
+            }
+        })
+    }
+
+    pub fn branches(&mut self) -> oneshot::Receiver<Result<Vec<Branch>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.branches().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitGetBranches {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    let branches = response
+                        .branches
+                        .into_iter()
+                        .map(|branch| proto_to_branch(&branch))
+                        .collect();
+
+                    Ok(branches)
+                }
+            }
+        })
+    }
+
+    pub fn diff(&mut self, diff_type: DiffType, _cx: &App) -> oneshot::Receiver<Result<String>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.diff(diff_type).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitDiff {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            diff_type: match diff_type {
+                                DiffType::HeadToIndex => {
+                                    proto::git_diff::DiffType::HeadToIndex.into()
+                                }
+                                DiffType::HeadToWorktree => {
+                                    proto::git_diff::DiffType::HeadToWorktree.into()
+                                }
+                            },
+                        })
+                        .await?;
+
+                    Ok(response.diff)
+                }
+            }
+        })
+    }
+
+    pub fn create_branch(
+        &mut self,
+        branch_name: String,
+        base_revision: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let description = match base_revision.as_ref() {
+            Some(base_revision) => format!("git branch {branch_name} {base_revision}"),
+            None => format!("git switch -c {branch_name}"),
+        };
+        self.send_job(Some(description.into()), move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => {
+                    backend.create_branch(branch_name, base_revision).await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitCreateBranch {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            branch_name,
+                            base_revision,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn delete_branch(
+        &mut self,
+        branch_name: String,
+        force: bool,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let flag = if force { "-D" } else { "-d" };
+        self.send_job(
+            Some(format!("git branch {flag} {branch_name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => {
+                        backend.delete_branch(branch_name, force).await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitDeleteBranch {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                branch_name,
+                                force,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn is_branch_merged(&mut self, branch_name: String) -> oneshot::Receiver<Result<bool>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => {
+                    backend.is_branch_merged(branch_name).await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitIsBranchMerged {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            branch_name,
+                        })
+                        .await?;
+                    Ok(response.merged)
+                }
+            }
+        })
+    }
+
+    pub fn rename_branch(
+        &mut self,
+        old_name: String,
+        new_name: String,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git branch -m {old_name} {new_name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => {
+                        backend.rename_branch(old_name, new_name).await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitRenameBranch {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                old_name,
+                                new_name,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn set_upstream(
+        &mut self,
+        branch_name: String,
+        upstream: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let description = match upstream.as_ref() {
+            Some(upstream) => format!("git branch --set-upstream-to={upstream} {branch_name}"),
+            None => format!("git branch --unset-upstream {branch_name}"),
+        };
+        self.send_job(Some(description.into()), move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => {
+                    backend.set_upstream(branch_name, upstream).await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitSetUpstream {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            branch_name,
+                            upstream,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn diff_to_merge_base(&mut self, base: String) -> oneshot::Receiver<Result<CommitDiff>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.diff_to_merge_base(base).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitDiffToMergeBase {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            base,
+                        })
+                        .await?;
+                    Ok(proto_to_commit_diff(response))
+                }
+            }
+        })
+    }
+
+    pub fn worktrees(&mut self) -> oneshot::Receiver<Result<Vec<Worktree>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.worktrees().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitWorktrees {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
                         })
-                    }
+                        .await?;
+                    Ok(response.worktrees.iter().map(proto_to_worktree).collect())
                 }
-            },
-        )
+            }
+        })
     }
 
-    fn spawn_set_index_text_job(
+    pub fn create_worktree(
         &mut self,
-        path: RepoPath,
-        content: Option<String>,
-        hunk_staging_operation_count: Option<usize>,
-        cx: &mut Context<Self>,
-    ) -> oneshot::Receiver<anyhow::Result<()>> {
+        path: PathBuf,
+        branch_name: String,
+        create_branch: bool,
+    ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        let this = cx.weak_entity();
-        let git_store = self.git_store.clone();
-        self.send_keyed_job(
-            Some(GitJobKey::WriteIndex(path.clone())),
-            None,
-            move |git_repo, mut cx| async move {
-                log::debug!("start updating index text for buffer {}", path.display());
-                match git_repo {
-                    RepositoryState::Local {
-                        backend,
-                        environment,
-                        ..
-                    } => {
-                        backend
-                            .set_index_text(path.clone(), content, environment.clone())
-                            .await?;
+        let description = if create_branch {
+            format!("git worktree add -b {branch_name} {}", path.display())
+        } else {
+            format!("git worktree add {} {branch_name}", path.display())
+        };
+        self.send_job(Some(description.into()), move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => {
+                    backend
+                        .create_worktree(path, branch_name, create_branch)
+                        .await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitCreateWorktree {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            path: path.to_string_lossy().to_string(),
+                            branch_name,
+                            create_branch,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn remove_worktree(&mut self, path: PathBuf, force: bool) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git worktree remove {}", path.display()).into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => {
+                        backend.remove_worktree(path, force).await
                     }
                     RepositoryState::Remote { project_id, client } => {
                         client
-                            .request(proto::SetIndexText {
+                            .request(proto::GitRemoveWorktree {
                                 project_id: project_id.0,
                                 repository_id: id.to_proto(),
-                                path: path.as_ref().to_proto(),
-                                text: content,
+                                path: path.to_string_lossy().to_string(),
+                                force,
                             })
                             .await?;
-                    }
-                }
-                log::debug!("finish updating index text for buffer {}", path.display());
 
-                if let Some(hunk_staging_operation_count) = hunk_staging_operation_count {
-                    let project_path = this
-                        .read_with(&cx, |this, cx| this.repo_path_to_project_path(&path, cx))
-                        .ok()
-                        .flatten();
-                    git_store.update(&mut cx, |git_store, cx| {
-                        let buffer_id = git_store
-                            .buffer_store
-                            .read(cx)
-                            .get_by_path(&project_path?, cx)?
-                            .read(cx)
-                            .remote_id();
-                        let diff_state = git_store.diffs.get(&buffer_id)?;
-                        diff_state.update(cx, |diff_state, _| {
-                            diff_state.hunk_staging_operation_count_as_of_write =
-                                hunk_staging_operation_count;
-                        });
-                        Some(())
-                    })?;
+                        Ok(())
+                    }
                 }
-                Ok(())
             },
         )
     }
 
-    pub fn get_remotes(
-        &mut self,
-        branch_name: Option<String>,
-    ) -> oneshot::Receiver<Result<Vec<Remote>>> {
+    pub fn tags(&mut self, points_at: Option<String>) -> oneshot::Receiver<Result<Vec<Tag>>> {
         let id = self.id;
         self.send_job(None, move |repo, _cx| async move {
             match repo {
-                RepositoryState::Local { backend, .. } => backend.get_remotes(branch_name).await,
+                RepositoryState::Local { backend, .. } => backend.tags(points_at).await,
                 RepositoryState::Remote { project_id, client } => {
                     let response = client
-                        .request(proto::GetRemotes {
+                        .request(proto::GitTags {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            branch_name,
+                            points_at,
                         })
                         .await?;
-
-                    let remotes = response
-                        .remotes
-                        .into_iter()
-                        .map(|remotes| git::repository::Remote {
-                            name: remotes.name.into(),
-                        })
-                        .collect();
-
-                    Ok(remotes)
+                    Ok(response.tags.iter().map(proto_to_tag).collect())
                 }
             }
         })
     }
 
-    pub fn branches(&mut self) -> oneshot::Receiver<Result<Vec<Branch>>> {
+    pub fn load_conflict_stages(
+        &mut self,
+        path: RepoPath,
+    ) -> oneshot::Receiver<Result<ConflictStages>> {
         let id = self.id;
-        self.send_job(None, move |repo, _| async move {
+        self.send_job(None, move |repo, _cx| async move {
             match repo {
-                RepositoryState::Local { backend, .. } => backend.branches().await,
+                RepositoryState::Local { backend, .. } => backend.load_conflict_stages(path).await,
                 RepositoryState::Remote { project_id, client } => {
                     let response = client
-                        .request(proto::GitGetBranches {
+                        .request(proto::GitLoadConflictStages {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
+                            path: path.as_ref().to_proto(),
                         })
                         .await?;
-
-                    let branches = response
-                        .branches
-                        .into_iter()
-                        .map(|branch| proto_to_branch(&branch))
-                        .collect();
-
-                    Ok(branches)
+                    Ok(ConflictStages {
+                        base: response.base,
+                        ours: response.ours,
+                        theirs: response.theirs,
+                    })
                 }
             }
         })
     }
 
-    pub fn diff(&mut self, diff_type: DiffType, _cx: &App) -> oneshot::Receiver<Result<String>> {
+    pub fn create_tag(
+        &mut self,
+        name: String,
+        commit: String,
+        message: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        self.send_job(None, move |repo, _cx| async move {
+        let description = if message.is_some() {
+            format!("git tag --annotate {name} {commit}")
+        } else {
+            format!("git tag {name} {commit}")
+        };
+        self.send_job(Some(description.into()), move |repo, _cx| async move {
             match repo {
-                RepositoryState::Local { backend, .. } => backend.diff(diff_type).await,
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.create_tag(name, commit, message, environment).await,
                 RepositoryState::Remote { project_id, client } => {
-                    let response = client
-                        .request(proto::GitDiff {
+                    client
+                        .request(proto::GitCreateTag {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            diff_type: match diff_type {
-                                DiffType::HeadToIndex => {
-                                    proto::git_diff::DiffType::HeadToIndex.into()
-                                }
-                                DiffType::HeadToWorktree => {
-                                    proto::git_diff::DiffType::HeadToWorktree.into()
-                                }
-                            },
+                            name,
+                            commit,
+                            message,
                         })
                         .await?;
 
-                    Ok(response.diff)
+                    Ok(())
                 }
             }
         })
     }
 
-    pub fn create_branch(&mut self, branch_name: String) -> oneshot::Receiver<Result<()>> {
+    pub fn delete_tag(&mut self, name: String) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
         self.send_job(
-            Some(format!("git switch -c {branch_name}").into()),
+            Some(format!("git tag --delete {name}").into()),
             move |repo, _cx| async move {
                 match repo {
-                    RepositoryState::Local { backend, .. } => {
-                        backend.create_branch(branch_name).await
-                    }
+                    RepositoryState::Local { backend, .. } => backend.delete_tag(name).await,
                     RepositoryState::Remote { project_id, client } => {
                         client
-                            .request(proto::GitCreateBranch {
+                            .request(proto::GitDeleteTag {
                                 project_id: project_id.0,
                                 repository_id: id.to_proto(),
-                                branch_name,
+                                name,
                             })
                             .await?;
 
@@ -3957,6 +5117,11 @@ impl Repository {
             .map(proto_to_commit_details);
 
         self.snapshot.merge.conflicted_paths = conflicted_paths;
+        self.snapshot.is_pure_jujutsu = update.is_pure_jujutsu;
+        self.snapshot.merge.in_progress_operation = update
+            .in_progress_operation
+            .and_then(proto::GitOperation::from_i32)
+            .map(proto_to_git_operation);
 
         let edits = update
             .removed_statuses
@@ -4026,16 +5191,21 @@ impl Repository {
                 let Some(this) = this.upgrade() else {
                     return Ok(());
                 };
-                let RepositoryState::Local { backend, .. } = state else {
+                let RepositoryState::Local {
+                    backend, jujutsu, ..
+                } = state
+                else {
                     bail!("not a local repository")
                 };
                 let (snapshot, events) = this
-                    .read_with(&mut cx, |this, _| {
+                    .read_with(&mut cx, |this, cx| {
                         compute_snapshot(
                             this.id,
                             this.work_directory_abs_path.clone(),
                             this.snapshot.clone(),
                             backend.clone(),
+                            jujutsu.clone(),
+                            cx.background_executor().clone(),
                         )
                     })?
                     .await?;
@@ -4058,7 +5228,7 @@ impl Repository {
     fn spawn_local_git_worker(
         work_directory_abs_path: Arc<Path>,
         dot_git_abs_path: Arc<Path>,
-        _repository_dir_abs_path: Arc<Path>,
+        repository_dir_abs_path: Arc<Path>,
         _common_dir_abs_path: Arc<Path>,
         project_environment: WeakEntity<ProjectEnvironment>,
         fs: Arc<dyn Fs>,
@@ -4079,11 +5249,25 @@ impl Repository {
                     HashMap::default()
                 });
             let backend = cx
-                .background_spawn(async move {
-                    fs.open_repo(&dot_git_abs_path)
-                        .with_context(|| format!("opening repository at {dot_git_abs_path:?}"))
+                .background_spawn({
+                    let fs = fs.clone();
+                    async move {
+                        // A pure jj workspace has no `.git`, so open the git store
+                        // that backs it directly.
+                        let git_dir_abs_path = if dot_git_abs_path.file_name() == Some(*DOT_JJ) {
+                            &repository_dir_abs_path
+                        } else {
+                            &dot_git_abs_path
+                        };
+                        fs.open_repo(git_dir_abs_path)
+                            .with_context(|| format!("opening repository at {git_dir_abs_path:?}"))
+                    }
                 })
                 .await?;
+            let environment = Arc::new(environment);
+            let jujutsu =
+                open_jujutsu_repo(work_directory_abs_path.clone(), &environment, fs.as_ref(), cx)
+                    .await;
 
             if let Some(git_hosting_provider_registry) =
                 cx.update(|cx| GitHostingProviderRegistry::try_global(cx))?
@@ -4096,7 +5280,8 @@ impl Repository {
 
             let state = RepositoryState::Local {
                 backend,
-                environment: Arc::new(environment),
+                environment,
+                jujutsu,
             };
             let mut jobs = VecDeque::new();
             loop {
@@ -4171,9 +5356,19 @@ impl Repository {
         repo_path: RepoPath,
         cx: &App,
     ) -> Task<Result<Option<String>>> {
-        let rx = self.send_job(None, move |state, _| async move {
+        let rx = self.send_job(None, move |state, cx| async move {
             match state {
-                RepositoryState::Local { backend, .. } => {
+                RepositoryState::Local {
+                    backend, jujutsu, ..
+                } => {
+                    if let Some(jujutsu) = jujutsu {
+                        match load_jujutsu_parent_text(jujutsu, repo_path.clone(), &cx).await {
+                            Ok(parent_text) => return anyhow::Ok(parent_text),
+                            Err(error) => {
+                                log::error!("failed to load jj parent text, using git's: {error:?}")
+                            }
+                        }
+                    }
                     anyhow::Ok(backend.load_index_text(repo_path).await)
                 }
                 RepositoryState::Remote { project_id, client } => {
@@ -4196,9 +5391,22 @@ impl Repository {
         repo_path: RepoPath,
         cx: &App,
     ) -> Task<Result<DiffBasesChange>> {
-        let rx = self.send_job(None, move |state, _| async move {
+        let rx = self.send_job(None, move |state, cx| async move {
             match state {
-                RepositoryState::Local { backend, .. } => {
+                RepositoryState::Local {
+                    backend, jujutsu, ..
+                } => {
+                    if let Some(jujutsu) = jujutsu {
+                        // jj has no index, so both bases are the working-copy change's parent.
+                        match load_jujutsu_parent_text(jujutsu, repo_path.clone(), &cx).await {
+                            Ok(parent_text) => {
+                                return anyhow::Ok(DiffBasesChange::SetBoth(parent_text));
+                            }
+                            Err(error) => {
+                                log::error!("failed to load jj parent text, using git's: {error:?}")
+                            }
+                        }
+                    }
                     let committed_text = backend.load_committed_text(repo_path.clone()).await;
                     let staged_text = backend.load_index_text(repo_path).await;
                     let diff_bases_change = if committed_text == staged_text {
@@ -4255,12 +5463,17 @@ impl Repository {
                         mem::take(&mut this.paths_needing_status_update),
                     )
                 })?;
-                let RepositoryState::Local { backend, .. } = state else {
+                let RepositoryState::Local {
+                    backend, jujutsu, ..
+                } = state
+                else {
                     bail!("not a local repository")
                 };
 
                 let paths = changed_paths.iter().cloned().collect::<Vec<_>>();
-                let statuses = backend.status(&paths).await?;
+                let statuses =
+                    repository_status(jujutsu, &backend, paths, cx.background_executor().clone())
+                        .await?;
 
                 let changed_path_statuses = cx
                     .background_spawn(async move {
@@ -4537,16 +5750,143 @@ fn proto_to_commit_details(proto: &proto::GitCommitDetails) -> CommitDetails {
     }
 }
 
+fn git_operation_to_proto(operation: GitOperation) -> proto::GitOperation {
+    match operation {
+        GitOperation::Merge => proto::GitOperation::Merge,
+        GitOperation::Rebase => proto::GitOperation::Rebase,
+        GitOperation::CherryPick => proto::GitOperation::CherryPick,
+        GitOperation::Revert => proto::GitOperation::Revert,
+    }
+}
+
+fn proto_to_git_operation(operation: proto::GitOperation) -> GitOperation {
+    match operation {
+        proto::GitOperation::Merge => GitOperation::Merge,
+        proto::GitOperation::Rebase => GitOperation::Rebase,
+        proto::GitOperation::CherryPick => GitOperation::CherryPick,
+        proto::GitOperation::Revert => GitOperation::Revert,
+    }
+}
+
+fn rebase_todo_entry_to_proto(entry: RebaseTodoEntry) -> proto::RebaseTodoEntry {
+    let action = match entry.action {
+        RebaseAction::Pick => proto::rebase_todo_entry::Action::Pick,
+        RebaseAction::Reword => proto::rebase_todo_entry::Action::Reword,
+        RebaseAction::Squash => proto::rebase_todo_entry::Action::Squash,
+        RebaseAction::Fixup => proto::rebase_todo_entry::Action::Fixup,
+        RebaseAction::Drop => proto::rebase_todo_entry::Action::Drop,
+    };
+    proto::RebaseTodoEntry {
+        action: action as i32,
+        sha: entry.sha.to_string(),
+        message: entry.message,
+    }
+}
+
+fn proto_to_rebase_todo_entry(entry: proto::RebaseTodoEntry) -> RebaseTodoEntry {
+    let action = match entry.action() {
+        proto::rebase_todo_entry::Action::Pick => RebaseAction::Pick,
+        proto::rebase_todo_entry::Action::Reword => RebaseAction::Reword,
+        proto::rebase_todo_entry::Action::Squash => RebaseAction::Squash,
+        proto::rebase_todo_entry::Action::Fixup => RebaseAction::Fixup,
+        proto::rebase_todo_entry::Action::Drop => RebaseAction::Drop,
+    };
+    RebaseTodoEntry {
+        action,
+        sha: entry.sha.into(),
+        message: entry.message,
+    }
+}
+
+fn commit_diff_to_proto(commit_diff: CommitDiff) -> proto::LoadCommitDiffResponse {
+    proto::LoadCommitDiffResponse {
+        files: commit_diff
+            .files
+            .into_iter()
+            .map(|file| proto::CommitFile {
+                path: file.path.to_string(),
+                old_text: file.old_text,
+                new_text: file.new_text,
+            })
+            .collect(),
+    }
+}
+
+fn proto_to_commit_diff(response: proto::LoadCommitDiffResponse) -> CommitDiff {
+    CommitDiff {
+        files: response
+            .files
+            .into_iter()
+            .map(|file| CommitFile {
+                path: Path::new(&file.path).into(),
+                old_text: file.old_text,
+                new_text: file.new_text,
+            })
+            .collect(),
+    }
+}
+
+fn worktree_to_proto(worktree: &Worktree) -> proto::GitWorktree {
+    proto::GitWorktree {
+        path: worktree.path.to_string_lossy().to_string(),
+        ref_name: worktree
+            .ref_name
+            .as_ref()
+            .map(|ref_name| ref_name.to_string()),
+        sha: worktree.sha.to_string(),
+        is_main: worktree.is_main,
+        is_locked: worktree.is_locked,
+        has_changes: worktree.has_changes,
+    }
+}
+
+fn proto_to_worktree(proto: &proto::GitWorktree) -> Worktree {
+    Worktree {
+        path: PathBuf::from(&proto.path),
+        ref_name: proto.ref_name.clone().map(Into::into),
+        sha: proto.sha.clone().into(),
+        is_main: proto.is_main,
+        is_locked: proto.is_locked,
+        has_changes: proto.has_changes,
+    }
+}
+
+fn tag_to_proto(tag: &Tag) -> proto::GitTag {
+    proto::GitTag {
+        name: tag.name.to_string(),
+        sha: tag.sha.to_string(),
+        message: tag.message.as_ref().map(|message| message.to_string()),
+        timestamp: tag.timestamp,
+    }
+}
+
+fn proto_to_tag(proto: &proto::GitTag) -> Tag {
+    Tag {
+        name: proto.name.clone().into(),
+        sha: proto.sha.clone().into(),
+        message: proto.message.clone().map(Into::into),
+        timestamp: proto.timestamp,
+    }
+}
+
 async fn compute_snapshot(
     id: RepositoryId,
     work_directory_abs_path: Arc<Path>,
     prev_snapshot: RepositorySnapshot,
     backend: Arc<dyn GitRepository>,
+    jujutsu: Option<Arc<dyn JujutsuRepository>>,
+    executor: BackgroundExecutor,
 ) -> Result<(RepositorySnapshot, Vec<RepositoryEvent>)> {
     let mut events = Vec::new();
     let branches = backend.branches().await?;
     let branch = branches.into_iter().find(|branch| branch.is_head);
-    let statuses = backend.status(&[WORK_DIRECTORY_REPO_PATH.clone()]).await?;
+    let statuses = repository_status(
+        jujutsu,
+        &backend,
+        vec![WORK_DIRECTORY_REPO_PATH.clone()],
+        executor,
+    )
+    .await?;
     let statuses_by_path = SumTree::from_iter(
         statuses
             .entries
@@ -4588,11 +5928,175 @@ async fn compute_snapshot(
         branch,
         head_commit,
         merge: merge_details,
+        is_pure_jujutsu: prev_snapshot.is_pure_jujutsu,
     };
 
     Ok((snapshot, events))
 }
 
+/// Opens the jj workspace rooted at `work_directory_abs_path`, if there is one. jj is found
+/// through the project environment's `PATH`, like the language servers are.
+async fn open_jujutsu_repo(
+    work_directory_abs_path: Arc<Path>,
+    environment: &Arc<HashMap<String, String>>,
+    fs: &dyn Fs,
+    cx: &AsyncApp,
+) -> Option<Arc<dyn JujutsuRepository>> {
+    if !fs.is_dir(&work_directory_abs_path.join(*DOT_JJ)).await {
+        return None;
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    if fs.is_fake() {
+        return cx
+            .update(|cx| {
+                cx.try_global::<FakeJujutsuRepositories>()?
+                    .0
+                    .get(work_directory_abs_path.as_ref())
+                    .cloned()
+            })
+            .ok()
+            .flatten();
+    }
+
+    let jj_binary_path = which::which_in(
+        "jj",
+        environment.get("PATH"),
+        work_directory_abs_path.as_ref(),
+    )
+    .ok();
+    let environment = environment.clone();
+    cx.background_spawn(async move {
+        let repository =
+            RealJujutsuRepository::new(&work_directory_abs_path, jj_binary_path, environment)
+                .with_context(|| format!("opening jj workspace at {work_directory_abs_path:?}"))
+                .log_err()?;
+        Some(Arc::new(repository) as Arc<dyn JujutsuRepository>)
+    })
+    .await
+}
+
+/// Computes the repository's statuses, through jj when it's a jj workspace. If jj fails, the
+/// error is logged and git's own statuses are used instead.
+async fn repository_status(
+    jujutsu: Option<Arc<dyn JujutsuRepository>>,
+    backend: &Arc<dyn GitRepository>,
+    path_prefixes: Vec<RepoPath>,
+    executor: BackgroundExecutor,
+) -> Result<GitStatus> {
+    if let Some(jujutsu) = jujutsu {
+        match jujutsu_status(jujutsu, backend, path_prefixes.clone(), executor).await {
+            Ok(statuses) => return Ok(statuses),
+            Err(error) => log::error!("failed to compute jj status, using git's: {error:?}"),
+        }
+    }
+    backend.status(&path_prefixes).await
+}
+
+/// Loads the contents of `repo_path` in the parent of the jj working-copy change.
+async fn load_jujutsu_parent_text(
+    jujutsu: Arc<dyn JujutsuRepository>,
+    repo_path: RepoPath,
+    cx: &AsyncApp,
+) -> Result<Option<String>> {
+    cx.background_spawn(async move {
+        jujutsu.file_text("@-", &repo_path.to_unix_style().to_string_lossy())
+    })
+    .await
+}
+
+/// Computes git-style statuses for the files changed in the jj working-copy change.
+///
+/// jj has no index, so every change is reported as an unstaged worktree change, and files
+/// with first-class conflicts are reported as unmerged.
+///
+/// jj only records changes to the files when it snapshots the working copy. In colocated
+/// repositories, jj keeps git's HEAD and index at the working-copy change's parent, so git
+/// reports the changes on disk without waiting for the next snapshot.
+async fn jujutsu_status(
+    jujutsu: Arc<dyn JujutsuRepository>,
+    backend: &Arc<dyn GitRepository>,
+    path_prefixes: Vec<RepoPath>,
+    executor: BackgroundExecutor,
+) -> Result<GitStatus> {
+    let git_status = if jujutsu.is_colocated() {
+        Some(backend.status(&path_prefixes).await?)
+    } else {
+        None
+    };
+    executor
+        .spawn(async move {
+            let conflicted = |path: String| -> (RepoPath, FileStatus) {
+                let status = UnmergedStatus {
+                    first_head: UnmergedStatusCode::Updated,
+                    second_head: UnmergedStatusCode::Updated,
+                };
+                (RepoPath::from_str(&path), status.into())
+            };
+            let mut entries = match git_status {
+                Some(git_status) => {
+                    let conflicts = jujutsu
+                        .conflicted_paths()?
+                        .into_iter()
+                        .map(conflicted)
+                        .collect::<Vec<_>>();
+                    git_status
+                        .entries
+                        .iter()
+                        .filter(|(repo_path, _)| {
+                            !conflicts.iter().any(|(path, _)| path == repo_path)
+                        })
+                        .filter_map(|(repo_path, status)| {
+                            let status = if status.is_conflicted() {
+                                *status
+                            } else if status.is_created() {
+                                StatusCode::Added.worktree()
+                            } else if status.is_deleted() {
+                                StatusCode::Deleted.worktree()
+                            } else if status.is_modified() {
+                                StatusCode::Modified.worktree()
+                            } else {
+                                return None;
+                            };
+                            Some((repo_path.clone(), status))
+                        })
+                        .chain(conflicts)
+                        .collect::<Vec<_>>()
+                }
+                None => jujutsu
+                    .working_copy_changes()?
+                    .into_iter()
+                    .map(|change| match change.kind {
+                        FileChangeKind::Added => (
+                            RepoPath::from_str(&change.path),
+                            StatusCode::Added.worktree(),
+                        ),
+                        FileChangeKind::Modified => (
+                            RepoPath::from_str(&change.path),
+                            StatusCode::Modified.worktree(),
+                        ),
+                        FileChangeKind::Removed => (
+                            RepoPath::from_str(&change.path),
+                            StatusCode::Deleted.worktree(),
+                        ),
+                        FileChangeKind::Conflicted => conflicted(change.path),
+                    })
+                    .collect(),
+            };
+            entries.retain(|(repo_path, _)| {
+                path_prefixes
+                    .iter()
+                    .any(|prefix| repo_path.starts_with(prefix))
+            });
+            entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+            entries.dedup_by(|(a, _), (b, _)| a == b);
+            anyhow::Ok(GitStatus {
+                entries: entries.into(),
+            })
+        })
+        .await
+}
+
 fn status_from_proto(
     simple_status: i32,
     status: Option<proto::GitFileStatus>,
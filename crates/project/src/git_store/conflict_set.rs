@@ -94,6 +94,14 @@ pub struct ConflictRegion {
     pub ours: Range<Anchor>,
     pub theirs: Range<Anchor>,
     pub base: Option<Range<Anchor>>,
+    /// Which side, if any, is written as a diff against the base, as jj does by default.
+    pub diff_side: Option<ConflictSide>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictSide {
+    Ours,
+    Theirs,
 }
 
 impl ConflictRegion {
@@ -109,10 +117,33 @@ impl ConflictRegion {
         let outer_range = self.range.to_offset(&buffer_snapshot);
         let mut offset = outer_range.start;
         for kept_range in ranges {
+            let is_diff = match self.diff_side {
+                Some(ConflictSide::Ours) => *kept_range == self.ours,
+                Some(ConflictSide::Theirs) => *kept_range == self.theirs,
+                None => false,
+            };
             let kept_range = kept_range.to_offset(&buffer_snapshot);
             if kept_range.start > offset {
                 deletions.push((offset..kept_range.start, empty));
             }
+            if is_diff {
+                // Keep the side's version of the diff: drop the removed lines, and the
+                // prefix of the others.
+                let mut line_start = kept_range.start;
+                for line in buffer_snapshot
+                    .text_for_range(kept_range.clone())
+                    .collect::<String>()
+                    .split_inclusive('\n')
+                {
+                    let line_end = line_start + line.len();
+                    if line.starts_with('-') {
+                        deletions.push((line_start..line_end, empty));
+                    } else if line.starts_with([' ', '+']) {
+                        deletions.push((line_start..line_start + 1, empty));
+                    }
+                    line_start = line_end;
+                }
+            }
             offset = kept_range.end;
         }
         if outer_range.end > offset {
@@ -167,6 +198,11 @@ impl ConflictSet {
         cx.emit(update);
     }
 
+    /// Finds the conflicts in `buffer`, in either git's marker style or jj's. In jj's markers,
+    /// each side follows a `+++++++` line and the base follows a `-------` line, or a side
+    /// is written as a diff against the base after a `%%%%%%%` line.
+    ///
+    /// jj conflicts with more than two sides have no single "theirs", so they are skipped.
     pub fn parse(buffer: &text::BufferSnapshot) -> ConflictSetSnapshot {
         let mut conflicts = Vec::new();
 
@@ -180,6 +216,9 @@ impl ConflictSet {
         let mut base_start: Option<usize> = None;
         let mut base_end: Option<usize> = None;
         let mut theirs_start: Option<usize> = None;
+        let mut jj_style = false;
+        // The header and body offsets of each section of a jj conflict.
+        let mut jj_sections: Vec<(JjSection, usize, usize)> = Vec::new();
 
         while let Some(line) = lines.next() {
             let line_end = line_pos + line.len();
@@ -189,6 +228,59 @@ impl ConflictSet {
                 // abandon the previous one and start a new one
                 conflict_start = Some(line_pos);
                 ours_start = Some(line_end + 1);
+                ours_end = None;
+                base_start = None;
+                base_end = None;
+                theirs_start = None;
+                jj_style = line
+                    .trim_start_matches('<')
+                    .trim_start()
+                    .starts_with("Conflict ");
+                jj_sections.clear();
+            } else if let Some(section) =
+                JjSection::from_header(line).filter(|_| jj_style && conflict_start.is_some())
+            {
+                jj_sections.push((section, line_pos, line_end + 1));
+            } else if jj_style && line.starts_with(">>>>>>> ") && conflict_start.is_some() {
+                let conflict_end = (line_end + 1).min(buffer_len);
+                let body = |ix: usize| {
+                    let (_, _, start) = jj_sections[ix];
+                    let end = jj_sections
+                        .get(ix + 1)
+                        .map_or(line_pos, |(_, header_start, _)| *header_start);
+                    buffer.anchor_after(start)..buffer.anchor_before(end)
+                };
+                let sections = jj_sections
+                    .iter()
+                    .map(|(section, _, _)| *section)
+                    .collect::<Vec<_>>();
+                let conflict = match sections.as_slice() {
+                    [JjSection::Side, JjSection::Base, JjSection::Side] => {
+                        Some((body(0), Some(body(1)), body(2), None))
+                    }
+                    [JjSection::Diff, JjSection::Side] => {
+                        Some((body(0), None, body(1), Some(ConflictSide::Ours)))
+                    }
+                    [JjSection::Side, JjSection::Diff] => {
+                        Some((body(0), None, body(1), Some(ConflictSide::Theirs)))
+                    }
+                    _ => None,
+                };
+                if let Some((ours, base, theirs, diff_side)) = conflict {
+                    conflicts.push(ConflictRegion {
+                        range: buffer.anchor_after(conflict_start.unwrap())
+                            ..buffer.anchor_before(conflict_end),
+                        ours,
+                        theirs,
+                        base,
+                        diff_side,
+                    });
+                }
+
+                conflict_start = None;
+                ours_start = None;
+                jj_style = false;
+                jj_sections.clear();
             } else if line.starts_with("||||||| ")
                 && conflict_start.is_some()
                 && ours_start.is_some()
@@ -231,6 +323,7 @@ impl ConflictSet {
                     ours,
                     theirs,
                     base,
+                    diff_side: None,
                 });
 
                 conflict_start = None;
@@ -253,6 +346,31 @@ impl ConflictSet {
 
 impl EventEmitter<ConflictSetUpdate> for ConflictSet {}
 
+/// A section of a conflict materialized by jj.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JjSection {
+    /// The contents of a side, after `+++++++`.
+    Side,
+    /// The contents of the base, after `-------`.
+    Base,
+    /// A side as a diff against the base, after `%%%%%%%`.
+    Diff,
+}
+
+impl JjSection {
+    fn from_header(line: &str) -> Option<Self> {
+        if line.starts_with("+++++++ ") {
+            Some(Self::Side)
+        } else if line.starts_with("------- ") {
+            Some(Self::Base)
+        } else if line.starts_with("%%%%%%% ") {
+            Some(Self::Diff)
+        } else {
+            None
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{path::Path, sync::mpsc};
@@ -262,7 +380,7 @@ mod tests {
     use super::*;
     use fs::FakeFs;
     use git::status::{UnmergedStatus, UnmergedStatusCode};
-    use gpui::{BackgroundExecutor, TestAppContext};
+    use gpui::{AppContext as _, BackgroundExecutor, TestAppContext};
     use language::language_settings::AllLanguageSettings;
     use serde_json::json;
     use settings::Settings as _;
@@ -391,6 +509,103 @@ mod tests {
         assert_eq!(their_text, "This is their version in a nested conflict\n");
     }
 
+    #[test]
+    fn test_jj_conflict_markers() {
+        let test_content = r#"
+            fn main() {
+            <<<<<<< Conflict 1 of 2
+            +++++++ Contents of side #1
+                println!("ours");
+            ------- Contents of base
+                println!("base");
+            +++++++ Contents of side #2
+                println!("theirs");
+            >>>>>>> Conflict 1 of 2 ends
+            <<<<<<< Conflict 2 of 2
+            %%%%%%% Changes from base to side #1
+            -    let a = 1;
+            +    let a = 2;
+            +++++++ Contents of side #2
+                let a = 3;
+            >>>>>>> Conflict 2 of 2 ends
+            }
+        "#
+        .unindent();
+
+        let buffer_id = BufferId::new(1).unwrap();
+        let buffer = Buffer::new(0, buffer_id, test_content);
+        let snapshot = buffer.snapshot();
+
+        let conflict_snapshot = ConflictSet::parse(&snapshot);
+        assert_eq!(conflict_snapshot.conflicts.len(), 2);
+
+        let conflict = &conflict_snapshot.conflicts[0];
+        let text = |range: Range<Anchor>| snapshot.text_for_range(range).collect::<String>();
+        assert_eq!(conflict.diff_side, None);
+        assert_eq!(text(conflict.ours.clone()), "    println!(\"ours\");\n");
+        assert_eq!(
+            text(conflict.base.clone().unwrap()),
+            "    println!(\"base\");\n"
+        );
+        assert_eq!(text(conflict.theirs.clone()), "    println!(\"theirs\");\n");
+        assert!(text(conflict.range.clone()).starts_with("<<<<<<< Conflict 1 of 2\n"));
+        assert!(text(conflict.range.clone()).ends_with(">>>>>>> Conflict 1 of 2 ends\n"));
+
+        let conflict = &conflict_snapshot.conflicts[1];
+        assert_eq!(conflict.diff_side, Some(ConflictSide::Ours));
+        assert_eq!(
+            text(conflict.ours.clone()),
+            "-    let a = 1;\n+    let a = 2;\n"
+        );
+        assert_eq!(text(conflict.theirs.clone()), "    let a = 3;\n");
+        assert!(conflict.base.is_none());
+    }
+
+    #[gpui::test]
+    fn test_resolve_jj_diff_conflict(cx: &mut TestAppContext) {
+        let test_content = r#"
+            <<<<<<< Conflict 1 of 1
+            +++++++ Contents of side #1
+                let a = 3;
+            %%%%%%% Changes from base to side #2
+                 let b = 0;
+            -    let a = 1;
+            +    let a = 2;
+            >>>>>>> Conflict 1 of 1 ends
+        "#
+        .unindent();
+
+        let buffer = cx.new(|cx| language::Buffer::local(test_content.clone(), cx));
+        let conflict = buffer.read_with(cx, |buffer, _| {
+            let conflicts = ConflictSet::parse(&buffer.snapshot()).conflicts;
+            assert_eq!(conflicts.len(), 1);
+            conflicts[0].clone()
+        });
+        assert_eq!(conflict.diff_side, Some(ConflictSide::Theirs));
+
+        cx.update(|cx| conflict.resolve(buffer.clone(), &[conflict.theirs.clone()], cx));
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "    let b = 0;\n    let a = 2;\n"
+        );
+
+        buffer.update(cx, |buffer, cx| buffer.set_text(test_content, cx));
+        let conflict = buffer.read_with(cx, |buffer, _| {
+            ConflictSet::parse(&buffer.snapshot()).conflicts[0].clone()
+        });
+        cx.update(|cx| {
+            conflict.resolve(
+                buffer.clone(),
+                &[conflict.ours.clone(), conflict.theirs.clone()],
+                cx,
+            )
+        });
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "    let a = 3;\n    let b = 0;\n    let a = 2;\n"
+        );
+    }
+
     #[test]
     fn test_conflict_markers_at_eof() {
         let test_content = r#"
@@ -1,6 +1,7 @@
 pub mod clangd_ext;
 pub mod lsp_ext_command;
 pub mod rust_analyzer_ext;
+mod task_diagnostics;
 
 use crate::{
     CodeAction, Completion, CompletionResponse, CompletionSource, CoreCompletion, Hover, InlayHint,
@@ -167,6 +168,8 @@ pub struct LocalLspStore {
     lsp_tree: Entity<LanguageServerTree>,
     registered_buffers: HashMap<BufferId, usize>,
     buffer_pull_diagnostics_result_ids: HashMap<BufferId, Option<String>>,
+    /// Diagnostics of task problem matchers, by task template id.
+    task_diagnostics: HashMap<String, task_diagnostics::TaskDiagnostics>,
 }
 
 impl LocalLspStore {
@@ -3688,6 +3691,7 @@ impl LspStore {
                 lsp_tree: LanguageServerTree::new(manifest_tree, languages.clone(), cx),
                 registered_buffers: HashMap::default(),
                 buffer_pull_diagnostics_result_ids: HashMap::default(),
+                task_diagnostics: HashMap::default(),
             }),
             last_formatting_failure: None,
             downstream_client: None,
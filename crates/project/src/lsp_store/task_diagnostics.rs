@@ -0,0 +1,130 @@
+//! Diagnostics from the problems task problem matchers find in the task output.
+//!
+//! Every task template reports its diagnostics under a language server id of its own, that is not
+//! backed by any language server: this way, rerunning the task replaces its previous diagnostics
+//! only, even when it runs in another context, such as with another file open.
+//!
+//! Only local projects report task diagnostics: the terminals of remote projects run on the
+//! client, while the diagnostics live on the host.
+
+use std::path::PathBuf;
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use gpui::Context;
+use language::DiagnosticSourceKind;
+use lsp::{DiagnosticSeverity, LanguageServerId};
+use task::{Problem, ProblemSeverity, TaskId};
+use url::Url;
+use util::ResultExt as _;
+
+use super::LspStore;
+
+pub(super) struct TaskDiagnostics {
+    server_id: LanguageServerId,
+    /// Files with diagnostics from the last report, to clear them from if they are fixed.
+    paths: HashSet<PathBuf>,
+}
+
+impl LspStore {
+    /// Replaces the diagnostics of the task template with the problems found in the output of
+    /// the task.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_id: &TaskId,
+        problems: Vec<Problem>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let local = self
+            .as_local_mut()
+            .context("task diagnostics are only reported in local projects")?;
+        let languages = local.languages.clone();
+        let task_diagnostics = local
+            .task_diagnostics
+            .entry(task_id.template_id().to_string())
+            .or_insert_with(|| TaskDiagnostics {
+                server_id: languages.next_language_server_id(),
+                paths: HashSet::default(),
+            });
+        let server_id = task_diagnostics.server_id;
+
+        let mut diagnostics_by_path = HashMap::<PathBuf, Vec<lsp::Diagnostic>>::default();
+        let mut sources = Vec::new();
+        for problem in problems {
+            if let Some(source) = &problem.source {
+                if !sources.contains(source) {
+                    sources.push(source.clone());
+                }
+            }
+            diagnostics_by_path
+                .entry(problem.path.clone())
+                .or_default()
+                .push(diagnostic_for_problem(problem));
+        }
+        let previous_paths = std::mem::replace(
+            &mut task_diagnostics.paths,
+            diagnostics_by_path.keys().cloned().collect(),
+        );
+        for path in previous_paths {
+            diagnostics_by_path.entry(path).or_default();
+        }
+
+        for (path, diagnostics) in diagnostics_by_path {
+            let Ok(uri) = Url::from_file_path(&path) else {
+                log::debug!("skipping task diagnostics for a path that is not absolute: {path:?}");
+                continue;
+            };
+            self.update_diagnostics(
+                server_id,
+                lsp::PublishDiagnosticsParams {
+                    uri,
+                    diagnostics,
+                    version: None,
+                },
+                None,
+                DiagnosticSourceKind::Other,
+                // Tasks report problems of the files on disk.
+                &sources,
+                cx,
+            )
+            .log_err();
+        }
+        Ok(())
+    }
+}
+
+fn diagnostic_for_problem(problem: Problem) -> lsp::Diagnostic {
+    let line = problem.line.saturating_sub(1);
+    let range = match problem.column {
+        Some(column) => {
+            let start = lsp::Position::new(line, column.saturating_sub(1));
+            let end = match (problem.end_line, problem.end_column) {
+                (end_line, Some(end_column)) => lsp::Position::new(
+                    end_line.map_or(line, |end_line| end_line.saturating_sub(1)),
+                    end_column.saturating_sub(1),
+                ),
+                (Some(end_line), None) => lsp::Position::new(end_line.saturating_sub(1), u32::MAX),
+                (None, None) => start,
+            };
+            lsp::Range::new(start, end)
+        }
+        // Positions past the end of the line are clipped to it.
+        None => lsp::Range::new(
+            lsp::Position::new(line, 0),
+            lsp::Position::new(line, u32::MAX),
+        ),
+    };
+    lsp::Diagnostic {
+        range,
+        severity: Some(match problem.severity {
+            ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+            ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+            ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+            ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+        }),
+        code: problem.code.map(lsp::NumberOrString::String),
+        source: problem.source,
+        message: problem.message,
+        ..lsp::Diagnostic::default()
+    }
+}
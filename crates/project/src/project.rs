@@ -128,7 +128,8 @@ pub use language::Location;
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
-    BasicContextProvider, ContextProviderWithTasks, Inventory, TaskContexts, TaskSourceKind,
+    BasicContextProvider, ContextProviderWithTasks, Inventory, TaskContexts, TaskGraph,
+    TaskGraphNode, TaskSourceKind,
 };
 
 pub use buffer_store::ProjectTransaction;
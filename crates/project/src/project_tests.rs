@@ -1,7 +1,10 @@
 #![allow(clippy::format_collect)]
 
 use crate::{
-    Event, git_store::StatusEntry, task_inventory::TaskContexts, task_store::TaskSettingsLocation,
+    Event,
+    git_store::{FakeJujutsuRepositories, StatusEntry},
+    task_inventory::TaskContexts,
+    task_store::{TaskSettingsLocation, TaskStoreEvent},
     *,
 };
 use buffer_diff::{
@@ -37,7 +40,7 @@ use serde_json::json;
 #[cfg(not(windows))]
 use std::os;
 use std::{env, mem, num::NonZeroU32, ops::Range, str::FromStr, sync::OnceLock, task::Poll};
-use task::{ResolvedTask, TaskContext};
+use task::{ResolvedTask, TaskContext, TaskTemplate};
 use unindent::Unindent as _;
 use util::{
     TryFutureExt as _, assert_set_eq, maybe, path,
@@ -579,6 +582,112 @@ async fn test_fallback_to_single_worktree_tasks(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_watched_task_restarts(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    TaskStore::init(None);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".gitignore": "target",
+            "README.md": "# Readme",
+            "src": {
+                "main.rs": "fn main() {}",
+            },
+            "target": {
+                "out.rs": "",
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.executor().run_until_parked();
+
+    let task = TaskTemplate {
+        label: "cargo check".to_string(),
+        command: "cargo".to_string(),
+        args: vec!["check".to_string()],
+        watch: vec!["**/*.rs".to_string()],
+        ..TaskTemplate::default()
+    }
+    .resolve_task(
+        "test",
+        &TaskContext {
+            cwd: Some(PathBuf::from(path!("/dir"))),
+            ..TaskContext::default()
+        },
+    )
+    .unwrap();
+    let task_store = project.read_with(cx, |project, _| project.task_store().clone());
+    let restarts = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        let restarts = restarts.clone();
+        cx.subscribe(
+            &task_store,
+            move |_, event: &TaskStoreEvent, _| match event {
+                TaskStoreEvent::RestartWatchedTask { task, .. } => {
+                    restarts.lock().push(task.id.clone())
+                }
+            },
+        )
+        .detach();
+    });
+    task_store
+        .update(cx, |task_store, cx| {
+            task_store.watch_task(TaskSourceKind::UserInput, task.clone(), cx)
+        })
+        .unwrap();
+
+    for path in [path!("/dir/README.md"), path!("/dir/target/out.rs")] {
+        fs.save(path.as_ref(), &"changed".into(), Default::default())
+            .await
+            .unwrap();
+    }
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert!(
+        restarts.lock().is_empty(),
+        "Changes of unwatched and ignored files should not restart the task"
+    );
+
+    for contents in ["fn main() { 1 }", "fn main() { 2 }"] {
+        fs.save(
+            path!("/dir/src/main.rs").as_ref(),
+            &contents.into(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        cx.executor().run_until_parked();
+    }
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(
+        *restarts.lock(),
+        vec![task.id.clone()],
+        "A burst of changes should restart the task once"
+    );
+
+    task_store.update(cx, |task_store, cx| {
+        assert!(task_store.is_task_watched(&task.id));
+        assert!(task_store.unwatch_task(&task.id, cx));
+        assert!(!task_store.is_task_watched(&task.id));
+    });
+    fs.save(
+        path!("/dir/src/main.rs").as_ref(),
+        &"fn main() { 3 }".into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(restarts.lock().len(), 1);
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -7826,6 +7935,121 @@ async fn test_git_status_postprocessing(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_pure_jujutsu_repository(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".jj": {
+                "repo": {
+                    "store": {
+                        "git": {},
+                        "git_target": "git",
+                    },
+                },
+            },
+            "a.txt": "one\nTWO\nthree\n",
+            "b.txt": "new\n",
+        }),
+    )
+    .await;
+
+    let root = jj::Change {
+        change_id: "root".into(),
+        commit_id: "root-commit".into(),
+        parent_commit_ids: Vec::new(),
+        description: "initial".into(),
+        author_name: "".into(),
+        author_email: "".into(),
+        author_timestamp: 0,
+        bookmarks: vec!["main".into()],
+        is_working_copy: true,
+        is_empty: false,
+        is_immutable: false,
+        has_conflict: false,
+    };
+    let jujutsu = Arc::new(jj::FakeJujutsuRepository::new(vec![root]));
+    jujutsu
+        .set_file_text("root", "a.txt", Some("one\ntwo\nthree\n"))
+        .unwrap();
+    jujutsu.new_change(&[], None).unwrap();
+    jujutsu.set_working_copy_changes(vec![
+        jj::FileChange {
+            path: "a.txt".into(),
+            kind: jj::FileChangeKind::Modified,
+        },
+        jj::FileChange {
+            path: "b.txt".into(),
+            kind: jj::FileChangeKind::Added,
+        },
+    ]);
+    cx.update(|cx| {
+        cx.default_global::<FakeJujutsuRepositories>()
+            .0
+            .insert(PathBuf::from(path!("/project")), jujutsu);
+    });
+
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+    let tree = project.read_with(cx, |project, cx| project.worktrees(cx).next().unwrap());
+    tree.flush_fs_events(cx).await;
+    project
+        .update(cx, |project, cx| project.git_scans_complete(cx))
+        .await;
+    cx.executor().run_until_parked();
+
+    let repository = project.read_with(cx, |project, cx| {
+        let repositories = project.repositories(cx).values().collect::<Vec<_>>();
+        assert_eq!(repositories.len(), 1);
+        repositories[0].clone()
+    });
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(
+            repository.work_directory_abs_path.as_ref(),
+            Path::new(path!("/project"))
+        );
+        assert!(repository.is_pure_jujutsu);
+        assert_eq!(
+            repository.cached_status().collect::<Vec<_>>(),
+            [
+                StatusEntry {
+                    repo_path: "a.txt".into(),
+                    status: StatusCode::Modified.worktree(),
+                },
+                StatusEntry {
+                    repo_path: "b.txt".into(),
+                    status: StatusCode::Added.worktree(),
+                },
+            ]
+        );
+    });
+
+    // The diff base is the working-copy change's parent.
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/project/a.txt"), cx)
+        })
+        .await
+        .unwrap();
+    let diff = project
+        .update(cx, |project, cx| {
+            project.open_uncommitted_diff(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    diff.update(cx, |diff, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        assert_hunks(
+            diff.hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &snapshot, cx),
+            &snapshot,
+            &diff.base_text_string().unwrap(),
+            &[(1..2, "two\n", "TWO\n", DiffHunkStatus::modified_none())],
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_repository_subfolder_git_status(
     executor: gpui::BackgroundExecutor,
@@ -71,6 +71,10 @@ impl SearchHistory {
         cursor.selection = Some(self.history.len() - 1);
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
     pub fn next(&mut self, cursor: &mut SearchHistoryCursor) -> Option<&str> {
         let history_size = self.history.len();
         if history_size == 0 {
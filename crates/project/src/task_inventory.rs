@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result, bail};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
 use gpui::{App, AppContext as _, Entity, SharedString, Task};
@@ -199,6 +199,72 @@ impl TaskContexts {
     }
 }
 
+/// A task along with all the tasks it depends on, directly or transitively.
+#[derive(Debug, Clone)]
+pub struct TaskGraph {
+    /// The tasks in an order they can be started in: every task comes after all the tasks it depends on,
+    /// and the task the graph was built for comes last.
+    /// Tasks that several others depend on are included once.
+    pub tasks: Vec<TaskGraphNode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskGraphNode {
+    pub source_kind: TaskSourceKind,
+    pub template: TaskTemplate,
+    /// Indices of the tasks this task depends on, in the order of its [`TaskTemplate::depends_on`].
+    pub dependencies: Vec<usize>,
+}
+
+struct TaskGraphBuilder<'a> {
+    templates_by_label: &'a HashMap<String, (TaskSourceKind, TaskTemplate)>,
+    tasks: Vec<TaskGraphNode>,
+    task_indices: HashMap<String, usize>,
+    /// Labels of the tasks whose dependencies are being added, to detect the dependency cycles.
+    path: Vec<String>,
+}
+
+impl TaskGraphBuilder<'_> {
+    fn add(&mut self, source_kind: TaskSourceKind, template: TaskTemplate) -> Result<usize> {
+        if let Some(cycle_start) = self.path.iter().position(|label| label == &template.label) {
+            let cycle = self.path[cycle_start..]
+                .iter()
+                .chain(Some(&template.label))
+                .join(" -> ");
+            bail!("Task dependency cycle: {cycle}");
+        }
+        if let Some(&index) = self.task_indices.get(&template.label) {
+            return Ok(index);
+        }
+
+        self.path.push(template.label.clone());
+        let mut dependencies = Vec::with_capacity(template.depends_on.len());
+        for label in &template.depends_on {
+            let (dependency_source_kind, dependency) = self
+                .templates_by_label
+                .get(label)
+                .cloned()
+                .with_context(|| {
+                    format!(
+                        "Task {:?} depends on unknown task {label:?}",
+                        template.label
+                    )
+                })?;
+            dependencies.push(self.add(dependency_source_kind, dependency)?);
+        }
+        self.path.pop();
+
+        let index = self.tasks.len();
+        self.task_indices.insert(template.label.clone(), index);
+        self.tasks.push(TaskGraphNode {
+            source_kind,
+            template,
+            dependencies,
+        });
+        Ok(index)
+    }
+}
+
 impl TaskSourceKind {
     pub fn to_id_base(&self) -> String {
         match self {
@@ -330,6 +396,35 @@ impl Inventory {
             .map(|val| val.1)
     }
 
+    /// Builds the graph of the tasks the task given depends on, looking them up by label among the worktree and global tasks.
+    /// Fails if any of the dependencies is not found, or if the dependencies form a cycle.
+    pub fn task_graph(
+        &self,
+        source_kind: TaskSourceKind,
+        task: TaskTemplate,
+        worktree: Option<WorktreeId>,
+        cx: &App,
+    ) -> Result<TaskGraph> {
+        let mut templates_by_label = HashMap::default();
+        // Worktree tasks are listed first, taking precedence over global tasks with the same label.
+        for (source_kind, template) in self.list_tasks(None, None, worktree, cx) {
+            templates_by_label
+                .entry(template.label.clone())
+                .or_insert((source_kind, template));
+        }
+
+        let mut builder = TaskGraphBuilder {
+            templates_by_label: &templates_by_label,
+            tasks: Vec::new(),
+            task_indices: HashMap::default(),
+            path: Vec::new(),
+        };
+        builder.add(source_kind, task)?;
+        Ok(TaskGraph {
+            tasks: builder.tasks,
+        })
+    }
+
     /// Pulls its task sources relevant to the worktree and the language given,
     /// returns all task templates with their source kinds, worktree tasks first, language tasks second
     /// and global tasks last. No specific order inside source kinds groups.
@@ -1310,6 +1405,75 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_task_graph(cx: &mut TestAppContext) {
+        init_test(cx);
+        let inventory = cx.update(Inventory::new);
+        inventory.update(cx, |inventory, _| {
+            inventory
+                .update_file_based_tasks(
+                    TaskSettingsLocation::Global(tasks_file()),
+                    Some(
+                        &json!([
+                            { "label": "lint", "command": "lint" },
+                            { "label": "test", "command": "test", "depends_on": ["lint"] },
+                            { "label": "build", "command": "build" },
+                            {
+                                "label": "check",
+                                "depends_on": ["test", "build", "lint"],
+                                "depends_order": "sequence"
+                            },
+                            { "label": "cycle_a", "command": "a", "depends_on": ["cycle_b"] },
+                            { "label": "cycle_b", "command": "b", "depends_on": ["cycle_a"] },
+                            { "label": "broken", "depends_on": ["missing"] },
+                        ])
+                        .to_string(),
+                    ),
+                )
+                .unwrap();
+        });
+
+        let task_graph = |label: &str, cx: &mut TestAppContext| {
+            inventory.update(cx, |inventory, cx| {
+                let (source_kind, template) = inventory
+                    .list_tasks(None, None, None, cx)
+                    .into_iter()
+                    .find(|(_, template)| template.label == label)
+                    .unwrap();
+                inventory.task_graph(source_kind, template, None, cx)
+            })
+        };
+
+        let graph = task_graph("check", cx).unwrap();
+        assert_eq!(
+            graph
+                .tasks
+                .iter()
+                .map(|task| (task.template.label.as_str(), task.dependencies.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("lint", vec![]),
+                ("test", vec![0]),
+                ("build", vec![]),
+                ("check", vec![1, 2, 0]),
+            ],
+            "Dependencies should come before their dependents, shared dependencies only once"
+        );
+        assert_eq!(
+            graph.tasks.last().unwrap().template.depends_order,
+            task::DependsOrder::Sequence
+        );
+
+        assert_eq!(
+            task_graph("cycle_a", cx).unwrap_err().to_string(),
+            "Task dependency cycle: cycle_a -> cycle_b -> cycle_a"
+        );
+        assert_eq!(
+            task_graph("broken", cx).unwrap_err().to_string(),
+            "Task \"broken\" depends on unknown task \"missing\""
+        );
+    }
+
     fn init_test(_cx: &mut TestAppContext) {
         zlog::init_test();
         TaskStore::init(None);
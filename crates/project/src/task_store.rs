@@ -1,27 +1,35 @@
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::Context as _;
 use collections::HashMap;
 use fs::Fs;
-use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Task, WeakEntity};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity};
 use language::{
     ContextLocation, ContextProvider as _, LanguageToolchainStore, Location,
     proto::{deserialize_anchor, serialize_anchor},
 };
 use rpc::{AnyProtoClient, TypedEnvelope, proto};
 use settings::{InvalidSettingsError, SettingsLocation};
-use task::{TaskContext, TaskVariables, VariableName};
+use task::{ResolvedTask, TaskContext, TaskId, TaskVariables, VariableName};
 use text::{BufferId, OffsetRangeExt};
 use util::ResultExt;
+use worktree::{PathChange, UpdatedEntriesSet, Worktree, WorktreeId};
 
 use crate::{
-    BasicContextProvider, Inventory, ProjectEnvironment, buffer_store::BufferStore,
-    worktree_store::WorktreeStore,
+    BasicContextProvider, Inventory, ProjectEnvironment, TaskSourceKind,
+    buffer_store::BufferStore,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
 
+/// How long the watched files should stay unchanged before restarting a watched task,
+/// so that a burst of changes (e.g. a formatter or a branch checkout) restarts it once.
+const WATCHED_TASK_DEBOUNCE: Duration = Duration::from_millis(300);
+
 // platform-dependent warning
 pub enum TaskStore {
     Functional(StoreState),
@@ -34,6 +42,26 @@ pub struct StoreState {
     buffer_store: WeakEntity<BufferStore>,
     worktree_store: Entity<WorktreeStore>,
     toolchain_store: Arc<dyn LanguageToolchainStore>,
+    watched_tasks: HashMap<TaskId, WatchedTask>,
+    _worktree_store_subscription: Subscription,
+}
+
+/// A task with `watch` patterns, restarted when the files matching them change.
+struct WatchedTask {
+    task_source_kind: TaskSourceKind,
+    task: ResolvedTask,
+    /// The worktree the patterns are relative to, all worktrees if the task does not belong to any.
+    worktree_id: Option<WorktreeId>,
+    patterns: GlobSet,
+    pending_restart: Option<Task<()>>,
+}
+
+pub enum TaskStoreEvent {
+    /// Files watched by a task changed, and it should be restarted.
+    RestartWatchedTask {
+        task_source_kind: TaskSourceKind,
+        task: ResolvedTask,
+    },
 }
 
 enum StoreMode {
@@ -48,6 +76,7 @@ enum StoreMode {
 }
 
 impl EventEmitter<crate::Event> for TaskStore {}
+impl EventEmitter<TaskStoreEvent> for TaskStore {}
 
 #[derive(Debug)]
 pub enum TaskSettingsLocation<'a> {
@@ -173,6 +202,9 @@ impl TaskStore {
             task_inventory: Inventory::new(cx),
             buffer_store,
             toolchain_store,
+            watched_tasks: HashMap::default(),
+            _worktree_store_subscription: cx
+                .subscribe(&worktree_store, Self::on_worktree_store_event),
             worktree_store,
         })
     }
@@ -193,6 +225,9 @@ impl TaskStore {
             task_inventory: Inventory::new(cx),
             buffer_store,
             toolchain_store,
+            watched_tasks: HashMap::default(),
+            _worktree_store_subscription: cx
+                .subscribe(&worktree_store, Self::on_worktree_store_event),
             worktree_store,
         })
     }
@@ -298,6 +333,161 @@ impl TaskStore {
             inventory.update_file_based_scenarios(location, raw_tasks_json)
         })
     }
+
+    /// Restarts the task every time the files matching its `watch` patterns change,
+    /// or stops doing so if the task has no such patterns.
+    pub fn watch_task(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        task: ResolvedTask,
+        cx: &mut Context<Self>,
+    ) -> anyhow::Result<()> {
+        let TaskStore::Functional(state) = self else {
+            return Ok(());
+        };
+        let watch = &task.original_task().watch;
+        if watch.is_empty() {
+            if state.watched_tasks.remove(&task.id).is_some() {
+                cx.notify();
+            }
+            return Ok(());
+        }
+
+        let mut patterns = GlobSetBuilder::new();
+        for pattern in watch {
+            patterns.add(
+                Glob::new(pattern).with_context(|| format!("invalid watch pattern {pattern:?}"))?,
+            );
+        }
+        let patterns = patterns.build()?;
+        let worktree_id = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => task
+                .resolved
+                .cwd
+                .as_ref()
+                .and_then(|cwd| state.worktree_store.read(cx).find_worktree(cwd, cx))
+                .map(|(worktree, _)| worktree.read(cx).id()),
+        };
+        // Restarting the task watches it anew, keep the restart running.
+        let pending_restart = state
+            .watched_tasks
+            .remove(&task.id)
+            .and_then(|watched_task| watched_task.pending_restart);
+        state.watched_tasks.insert(
+            task.id.clone(),
+            WatchedTask {
+                task_source_kind,
+                task,
+                worktree_id,
+                patterns,
+                pending_restart,
+            },
+        );
+        cx.notify();
+        Ok(())
+    }
+
+    /// Stops restarting the task on file changes, returns whether it was watched.
+    pub fn unwatch_task(&mut self, task_id: &TaskId, cx: &mut Context<Self>) -> bool {
+        let TaskStore::Functional(state) = self else {
+            return false;
+        };
+        let watched = state.watched_tasks.remove(task_id).is_some();
+        if watched {
+            cx.notify();
+        }
+        watched
+    }
+
+    pub fn unwatch_all_tasks(&mut self, cx: &mut Context<Self>) {
+        if let TaskStore::Functional(state) = self {
+            state.watched_tasks.clear();
+            cx.notify();
+        }
+    }
+
+    pub fn is_task_watched(&self, task_id: &TaskId) -> bool {
+        match self {
+            TaskStore::Functional(state) => state.watched_tasks.contains_key(task_id),
+            TaskStore::Noop => false,
+        }
+    }
+
+    fn on_worktree_store_event(
+        &mut self,
+        worktree_store: Entity<WorktreeStore>,
+        event: &WorktreeStoreEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let WorktreeStoreEvent::WorktreeUpdatedEntries(worktree_id, updated_entries) = event else {
+            return;
+        };
+        let TaskStore::Functional(state) = self else {
+            return;
+        };
+        if state.watched_tasks.is_empty() {
+            return;
+        }
+        let Some(worktree) = worktree_store.read(cx).worktree_for_id(*worktree_id, cx) else {
+            return;
+        };
+        let changed_paths = changed_watchable_paths(worktree.read(cx), updated_entries);
+        if changed_paths.is_empty() {
+            return;
+        }
+
+        for (task_id, watched_task) in &mut state.watched_tasks {
+            if watched_task
+                .worktree_id
+                .is_some_and(|watched_worktree_id| watched_worktree_id != *worktree_id)
+                || !changed_paths
+                    .iter()
+                    .any(|path| watched_task.patterns.is_match(path))
+            {
+                continue;
+            }
+            let task_id = task_id.clone();
+            watched_task.pending_restart = Some(cx.spawn(async move |task_store, cx| {
+                cx.background_executor().timer(WATCHED_TASK_DEBOUNCE).await;
+                task_store
+                    .update(cx, |task_store, cx| {
+                        task_store.restart_watched_task(&task_id, cx)
+                    })
+                    .ok();
+            }));
+        }
+    }
+
+    fn restart_watched_task(&mut self, task_id: &TaskId, cx: &mut Context<Self>) {
+        let TaskStore::Functional(state) = self else {
+            return;
+        };
+        if let Some(watched_task) = state.watched_tasks.get(task_id) {
+            cx.emit(TaskStoreEvent::RestartWatchedTask {
+                task_source_kind: watched_task.task_source_kind.clone(),
+                task: watched_task.task.clone(),
+            });
+        }
+    }
+}
+
+/// Paths of the entries changed, except for the ones found by the initial scan and the ignored ones:
+/// tasks often write their output into ignored directories, and should not restart themselves with it.
+fn changed_watchable_paths(
+    worktree: &Worktree,
+    updated_entries: &UpdatedEntriesSet,
+) -> Vec<Arc<Path>> {
+    updated_entries
+        .iter()
+        .filter(|(_, entry_id, change)| {
+            *change != PathChange::Loaded
+                && worktree
+                    .entry_for_id(*entry_id)
+                    .is_none_or(|entry| !entry.is_ignored)
+        })
+        .map(|(path, _, _)| path.clone())
+        .collect()
 }
 
 fn local_task_context_for_location(
@@ -29,6 +29,12 @@ pub struct Terminals {
 pub enum TerminalKind {
     /// Run a shell at the given path (or $HOME if None)
     Shell(Option<PathBuf>),
+    /// Reattach to the persistent session of a shell from a previous Zed session,
+    /// or run a new shell at the given path if that session is gone.
+    Session {
+        session_id: String,
+        working_directory: Option<PathBuf>,
+    },
     /// Run a task.
     Task(SpawnInTerminal),
 }
@@ -89,7 +95,11 @@ impl Project {
         cx: &mut Context<Self>,
     ) -> Task<Result<Entity<Terminal>>> {
         let path: Option<Arc<Path>> = match &kind {
-            TerminalKind::Shell(path) => path.as_ref().map(|path| Arc::from(path.as_ref())),
+            TerminalKind::Shell(path)
+            | TerminalKind::Session {
+                working_directory: path,
+                ..
+            } => path.as_ref().map(|path| Arc::from(path.as_ref())),
             TerminalKind::Task(spawn_task) => {
                 if let Some(cwd) = &spawn_task.cwd {
                     Some(Arc::from(cwd.as_ref()))
@@ -192,7 +202,11 @@ impl Project {
     ) -> Result<Entity<Terminal>> {
         let this = &mut *self;
         let path: Option<Arc<Path>> = match &kind {
-            TerminalKind::Shell(path) => path.as_ref().map(|path| Arc::from(path.as_ref())),
+            TerminalKind::Shell(path)
+            | TerminalKind::Session {
+                working_directory: path,
+                ..
+            } => path.as_ref().map(|path| Arc::from(path.as_ref())),
             TerminalKind::Task(spawn_task) => {
                 if let Some(cwd) = &spawn_task.cwd {
                     Some(Arc::from(cwd.as_ref()))
@@ -234,8 +248,24 @@ impl Project {
 
         let mut python_venv_activate_command = None;
 
+        // Local shells are hosted by the session server, when enabled.
+        let use_persistent_session = ssh_details.is_none() && settings.persistent_sessions;
+        let persistent_session = match &kind {
+            TerminalKind::Shell(_) if use_persistent_session => {
+                Some(uuid::Uuid::new_v4().to_string())
+            }
+            TerminalKind::Session { session_id, .. } if use_persistent_session => {
+                Some(session_id.clone())
+            }
+            TerminalKind::Session { session_id, .. } => {
+                terminal::end_persistent_session(session_id);
+                None
+            }
+            _ => None,
+        };
+
         let (spawn_task, shell) = match kind {
-            TerminalKind::Shell(_) => {
+            TerminalKind::Shell(_) | TerminalKind::Session { .. } => {
                 if let Some(python_venv_directory) = &python_venv_directory {
                     python_venv_activate_command =
                         this.python_activate_command(python_venv_directory, &settings.detect_venv);
@@ -278,6 +308,7 @@ impl Project {
                     show_summary: spawn_task.show_summary,
                     show_command: spawn_task.show_command,
                     show_rerun: spawn_task.show_rerun,
+                    problem_matchers: spawn_task.problem_matchers,
                     completion_rx,
                 });
 
@@ -339,6 +370,7 @@ impl Project {
             settings.alternate_scroll,
             settings.max_scroll_history_lines,
             ssh_details.is_some(),
+            persistent_session,
             window,
             completion_tx,
             cx,
@@ -364,6 +396,33 @@ impl Project {
             })
             .detach();
 
+            let problems_task = terminal_handle
+                .read(cx)
+                .task()
+                .filter(|task| !task.problem_matchers.is_empty())
+                .map(|task| (task.id.clone(), task.label.clone()));
+            if let Some((task_id, label)) = problems_task {
+                // Remote projects keep their diagnostics on the host, while task terminals run
+                // here, so problem matchers are only supported in local projects.
+                if this.is_local() {
+                    cx.subscribe(&terminal_handle, move |project, terminal, event, cx| {
+                        if let terminal::Event::TaskProblemsChanged = event {
+                            let problems = terminal.read(cx).task_problems();
+                            project.lsp_store.update(cx, |lsp_store, cx| {
+                                lsp_store
+                                    .update_task_diagnostics(&task_id, problems, cx)
+                                    .log_err();
+                            });
+                        }
+                    })
+                    .detach();
+                } else {
+                    log::info!(
+                        "ignoring the problem matchers of task {label:?} in a remote project"
+                    );
+                }
+            }
+
             if let Some(activate_command) = python_venv_activate_command {
                 this.activate_python_virtual_environment(activate_command, &terminal_handle, cx);
             }
@@ -514,7 +573,9 @@ impl Project {
         terminal_handle: &Entity<Terminal>,
         cx: &mut App,
     ) {
-        terminal_handle.update(cx, |terminal, _| terminal.input(command.into_bytes()));
+        terminal_handle.update(cx, |terminal, _| {
+            terminal.input_to_new_shell(command.into_bytes())
+        });
     }
 
     pub fn local_terminal_handles(&self) -> &Vec<WeakEntity<terminal::Terminal>> {
@@ -770,6 +770,17 @@ impl ProjectPanel {
             let is_read_only = project.is_read_only(cx);
             let is_remote = project.is_via_collab();
             let is_local = project.is_local();
+            let is_in_repository = project
+                .git_store()
+                .read(cx)
+                .repository_and_path_for_project_path(
+                    &ProjectPath {
+                        worktree_id: worktree.id(),
+                        path: entry.path.clone(),
+                    },
+                    cx,
+                )
+                .is_some();
 
             let settings = ProjectPanelSettings::get_global(cx);
             let visible_worktrees_count = project.visible_worktrees(cx).count();
@@ -825,6 +836,10 @@ impl ProjectPanel {
                                 "Copy Relative Path",
                                 Box::new(zed_actions::workspace::CopyRelativePath),
                             )
+                            .when(is_in_repository, |menu| {
+                                menu.separator()
+                                    .action("View File History", Box::new(git::FileHistory))
+                            })
                             .separator()
                             .when(!should_hide_rename, |menu| {
                                 menu.action("Rename", Box::new(Rename))
@@ -2465,6 +2480,14 @@ impl ProjectPanel {
         }
     }
 
+    fn file_history(&mut self, _: &git::FileHistory, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some((worktree, entry)) = self.selected_sub_entry(cx) {
+            if let Some(abs_path) = worktree.read(cx).absolutize(&entry.path).log_err() {
+                window.dispatch_action(git::OpenFileHistory { abs_path }.boxed_clone(), cx);
+            }
+        }
+    }
+
     fn open_in_terminal(
         &mut self,
         _: &OpenInTerminal,
@@ -4902,6 +4925,7 @@ impl Render for ProjectPanel {
                 .when(project.is_via_ssh(), |el| {
                     el.on_action(cx.listener(Self::open_in_terminal))
                 })
+                .on_action(cx.listener(Self::file_history))
                 .on_mouse_down(
                     MouseButton::Right,
                     cx.listener(move |this, event: &MouseDownEvent, window, cx| {
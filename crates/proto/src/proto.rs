@@ -286,6 +286,20 @@ messages!(
     (GitCheckoutFiles, Background),
     (GitShow, Background),
     (GitCommitDetails, Background),
+    (GitStashPush, Background),
+    (GitStashList, Background),
+    (GitStashListResponse, Background),
+    (GitStashApply, Background),
+    (GitStashPop, Background),
+    (GitStashDrop, Background),
+    (GitStashShow, Background),
+    (GitLog, Background),
+    (GitLogResponse, Background),
+    (GitRebase, Background),
+    (GitCherryPick, Background),
+    (GitRevert, Background),
+    (GitContinueOperation, Background),
+    (GitAbortOperation, Background),
     (SetIndexText, Background),
     (Push, Background),
     (Fetch, Background),
@@ -297,6 +311,22 @@ messages!(
     (AskPassResponse, Background),
     (GitCreateBranch, Background),
     (GitChangeBranch, Background),
+    (GitDeleteBranch, Background),
+    (GitIsBranchMerged, Background),
+    (GitIsBranchMergedResponse, Background),
+    (GitRenameBranch, Background),
+    (GitSetUpstream, Background),
+    (GitDiffToMergeBase, Background),
+    (GitWorktrees, Background),
+    (GitWorktreesResponse, Background),
+    (GitCreateWorktree, Background),
+    (GitRemoveWorktree, Background),
+    (GitTags, Background),
+    (GitTagsResponse, Background),
+    (GitCreateTag, Background),
+    (GitDeleteTag, Background),
+    (GitLoadConflictStages, Background),
+    (GitLoadConflictStagesResponse, Background),
     (CheckForPushedCommits, Background),
     (CheckForPushedCommitsResponse, Background),
     (GitDiff, Background),
@@ -458,6 +488,18 @@ request_messages!(
     (GitShow, GitCommitDetails),
     (GitReset, Ack),
     (GitCheckoutFiles, Ack),
+    (GitStashPush, Ack),
+    (GitStashList, GitStashListResponse),
+    (GitStashApply, Ack),
+    (GitStashPop, Ack),
+    (GitStashDrop, Ack),
+    (GitStashShow, LoadCommitDiffResponse),
+    (GitLog, GitLogResponse),
+    (GitRebase, Ack),
+    (GitCherryPick, Ack),
+    (GitRevert, Ack),
+    (GitContinueOperation, Ack),
+    (GitAbortOperation, Ack),
     (SetIndexText, Ack),
     (Push, RemoteMessageResponse),
     (Fetch, RemoteMessageResponse),
@@ -466,6 +508,18 @@ request_messages!(
     (AskPassRequest, AskPassResponse),
     (GitCreateBranch, Ack),
     (GitChangeBranch, Ack),
+    (GitDeleteBranch, Ack),
+    (GitIsBranchMerged, GitIsBranchMergedResponse),
+    (GitRenameBranch, Ack),
+    (GitSetUpstream, Ack),
+    (GitDiffToMergeBase, LoadCommitDiffResponse),
+    (GitWorktrees, GitWorktreesResponse),
+    (GitCreateWorktree, Ack),
+    (GitRemoveWorktree, Ack),
+    (GitTags, GitTagsResponse),
+    (GitCreateTag, Ack),
+    (GitDeleteTag, Ack),
+    (GitLoadConflictStages, GitLoadConflictStagesResponse),
     (CheckForPushedCommits, CheckForPushedCommitsResponse),
     (GitDiff, GitDiffResponse),
     (GitInit, Ack),
@@ -583,6 +637,18 @@ entity_messages!(
     GitShow,
     GitReset,
     GitCheckoutFiles,
+    GitStashPush,
+    GitStashList,
+    GitStashApply,
+    GitStashPop,
+    GitStashDrop,
+    GitStashShow,
+    GitLog,
+    GitRebase,
+    GitCherryPick,
+    GitRevert,
+    GitContinueOperation,
+    GitAbortOperation,
     SetIndexText,
 
     Push,
@@ -592,6 +658,18 @@ entity_messages!(
     AskPassRequest,
     GitChangeBranch,
     GitCreateBranch,
+    GitDeleteBranch,
+    GitIsBranchMerged,
+    GitRenameBranch,
+    GitSetUpstream,
+    GitDiffToMergeBase,
+    GitWorktrees,
+    GitCreateWorktree,
+    GitRemoveWorktree,
+    GitTags,
+    GitCreateTag,
+    GitDeleteTag,
+    GitLoadConflictStages,
     CheckForPushedCommits,
     GitDiff,
     GitInit,
@@ -888,6 +888,19 @@ impl BufferSearchBar {
         self.query_editor.read(cx).text(cx)
     }
 
+    /// Fills an empty search history with the given queries, oldest first, e.g. to restore
+    /// the history of a previous session.
+    pub fn restore_search_history(&mut self, queries: impl IntoIterator<Item = String>) {
+        if !self.search_history.is_empty() {
+            return;
+        }
+        for query in queries {
+            self.search_history
+                .add(&mut self.search_history_cursor, query);
+        }
+        self.search_history_cursor.reset();
+    }
+
     pub fn replacement(&self, cx: &mut App) -> String {
         self.replacement_editor.read(cx).text(cx)
     }
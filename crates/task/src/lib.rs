@@ -2,6 +2,7 @@
 
 mod adapter_schema;
 mod debug_format;
+mod problem_matcher;
 mod serde_helpers;
 pub mod static_source;
 mod task_template;
@@ -21,8 +22,12 @@ pub use debug_format::{
     AttachRequest, BuildTaskDefinition, DebugRequest, DebugScenario, DebugTaskFile, LaunchRequest,
     Request, TcpArgumentsTemplate, ZedDebugConfig,
 };
+pub use problem_matcher::{
+    BackgroundMatcher, FileLocation, Problem, ProblemCollector, ProblemMatcher,
+    ProblemMatcherReference, ProblemPattern, ProblemSeverity,
+};
 pub use task_template::{
-    DebugArgsRequest, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
+    DebugArgsRequest, DependsOrder, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
     substitute_variables_in_map, substitute_variables_in_str,
 };
 pub use vscode_debug_format::VsCodeDebugTaskFile;
@@ -34,6 +39,16 @@ pub use zed_actions::RevealTarget;
 #[derive(Default, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
 pub struct TaskId(pub String);
 
+impl TaskId {
+    /// The part of a resolved task's id shared by every context its template is resolved in:
+    /// the task source and the template hash, without the hash of the task variables.
+    pub fn template_id(&self) -> &str {
+        self.0
+            .rsplit_once('_')
+            .map_or(self.0.as_str(), |(template_id, _)| template_id)
+    }
+}
+
 /// Contains all information needed by Zed to spawn a new terminal tab for the given task.
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct SpawnInTerminal {
@@ -72,6 +87,8 @@ pub struct SpawnInTerminal {
     pub show_command: bool,
     /// Whether to show the rerun button in the terminal tab.
     pub show_rerun: bool,
+    /// Problem matchers to turn the task output into diagnostics with.
+    pub problem_matchers: Vec<ProblemMatcher>,
 }
 
 impl SpawnInTerminal {
@@ -119,6 +136,8 @@ pub struct ResolvedTask {
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
     substituted_variables: HashSet<VariableName>,
+    /// The context the task got resolved with, to resolve the tasks it depends on with.
+    task_context: TaskContext,
     /// Further actions that need to take place after the resolved task is spawned,
     /// with all task variables resolved.
     pub resolved: SpawnInTerminal,
@@ -135,6 +154,11 @@ impl ResolvedTask {
         &self.substituted_variables
     }
 
+    /// The context the task got resolved with.
+    pub fn task_context(&self) -> &TaskContext {
+        &self.task_context
+    }
+
     /// A human-readable label to display in the UI.
     pub fn display_label(&self) -> &str {
         self.resolved.label.as_str()
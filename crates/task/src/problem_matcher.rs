@@ -0,0 +1,756 @@
+use std::path::{Path, PathBuf};
+
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::serde_helpers::{one_or_many, one_or_many_json_schema};
+
+/// A problem matcher, either one of the built-in ones, referenced by name (e.g. `$rustc`),
+/// or a matcher defined in place.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcherReference {
+    Named(String),
+    Inline(ProblemMatcher),
+}
+
+impl ProblemMatcherReference {
+    /// Looks up the built-in matcher referenced, applying the overrides of an in place matcher.
+    /// Returns `None` if the matcher refers to an unknown built-in matcher.
+    pub fn resolve(&self) -> Option<ProblemMatcher> {
+        match self {
+            Self::Named(name) => ProblemMatcher::builtin(name),
+            Self::Inline(matcher) => {
+                let Some(base) = matcher.base.as_deref() else {
+                    return Some(matcher.clone());
+                };
+                let mut resolved = ProblemMatcher::builtin(base)?;
+                if matcher.owner.is_some() {
+                    resolved.owner = matcher.owner.clone();
+                }
+                if matcher.source.is_some() {
+                    resolved.source = matcher.source.clone();
+                }
+                if matcher.severity.is_some() {
+                    resolved.severity = matcher.severity;
+                }
+                if matcher.file_location.is_some() {
+                    resolved.file_location = matcher.file_location;
+                }
+                if matcher.base_directory.is_some() {
+                    resolved.base_directory = matcher.base_directory.clone();
+                }
+                if !matcher.pattern.is_empty() {
+                    resolved.pattern = matcher.pattern.clone();
+                }
+                if matcher.background.is_some() {
+                    resolved.background = matcher.background.clone();
+                }
+                Some(resolved)
+            }
+        }
+    }
+}
+
+/// Describes how to find problems (errors, warnings, ...) in the output of a task,
+/// to show them as diagnostics.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemMatcher {
+    /// Name of a built-in matcher (e.g. `$tsc`) to extend: the fields set in this matcher override the built-in ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// Name of the tool the problems come from, used as the diagnostics source if `source` is not set.
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Source to show for the diagnostics produced.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Severity of the problems that do not match a severity of their own, `error` by default.
+    #[serde(default)]
+    pub severity: Option<ProblemSeverity>,
+    /// How to interpret the file paths in the output:
+    /// * `relative` — relative to `base_directory`, absolute paths are used as is (default)
+    /// * `absolute` — paths are always absolute
+    #[serde(default)]
+    pub file_location: Option<FileLocation>,
+    /// Directory relative paths are resolved against, defaults to the task's working directory.
+    #[serde(default)]
+    pub base_directory: Option<String>,
+    /// Patterns to match, each against a line following the one matched by the previous pattern.
+    #[serde(default, deserialize_with = "one_or_many")]
+    #[schemars(schema_with = "one_or_many_json_schema::<ProblemPattern>")]
+    pub pattern: Vec<ProblemPattern>,
+    /// For tasks that keep running, such as compilers in watch mode: lines that start and end a compilation.
+    /// Problems are reported when the compilation ends, replacing the ones from the previous compilation.
+    #[serde(default)]
+    pub background: Option<BackgroundMatcher>,
+}
+
+/// A regular expression matching a line of the task output, along with the capture groups
+/// holding the problem's details.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// Regular expression to match the line with.
+    pub regexp: String,
+    /// Group of the file path.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// Group of the location, in the `line`, `line,column` or `line,column,end_line,end_column` format.
+    #[serde(default)]
+    pub location: Option<usize>,
+    /// Group of the 1-based line.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// Group of the 1-based column.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// Group of the 1-based line the problem ends at.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// Group of the 1-based column the problem ends at.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// Group of the severity, e.g. `error` or `warning`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// Group of the error code.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// Group of the message.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// For the last pattern: whether to keep matching it against the following lines,
+    /// reporting a problem for each line matched.
+    #[serde(default, rename = "loop")]
+    pub r#loop: bool,
+}
+
+/// Lines of the output delimiting a compilation of a task that keeps running.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BackgroundMatcher {
+    /// Whether a compilation starts along with the task, before any line matches `begins_pattern`.
+    #[serde(default)]
+    pub activates_on_start: bool,
+    /// Regular expression matching the line printed when a compilation starts.
+    pub begins_pattern: String,
+    /// Regular expression matching the line printed when a compilation ends.
+    pub ends_pattern: String,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl ProblemSeverity {
+    /// Parses the severity printed by a tool, such as `error`, `warn` or `note`.
+    fn from_output(severity: &str) -> Option<Self> {
+        let severity = severity.trim().to_lowercase();
+        if severity.starts_with("err") || severity == "fatal" {
+            Some(Self::Error)
+        } else if severity.starts_with("warn") {
+            Some(Self::Warning)
+        } else if severity.starts_with("info") || severity == "note" {
+            Some(Self::Info)
+        } else if severity == "hint" || severity == "help" {
+            Some(Self::Hint)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileLocation {
+    /// Paths are relative to the base directory, absolute paths are used as is.
+    #[default]
+    Relative,
+    /// Paths are always absolute.
+    Absolute,
+}
+
+impl ProblemMatcher {
+    /// Names of the built-in matchers, usable in place of a matcher definition.
+    pub const BUILTIN_NAMES: &'static [&'static str] = &[
+        "$rustc",
+        "$rustc-watch",
+        "$tsc",
+        "$tsc-watch",
+        "$gcc",
+        "$eslint-stylish",
+    ];
+
+    /// Returns the built-in matcher with the given name, such as `$rustc`.
+    pub fn builtin(name: &str) -> Option<Self> {
+        let matcher = match name {
+            "$rustc" => Self::rustc(),
+            "$rustc-watch" => Self {
+                background: Some(BackgroundMatcher {
+                    activates_on_start: false,
+                    begins_pattern: r"^\[Running\b".to_string(),
+                    ends_pattern: r"^\[Finished running\b".to_string(),
+                }),
+                ..Self::rustc()
+            },
+            "$tsc" => Self::tsc(),
+            "$tsc-watch" => Self {
+                background: Some(BackgroundMatcher {
+                    activates_on_start: true,
+                    begins_pattern: r"(?:Starting compilation in watch mode|File change detected\. Starting incremental compilation)\.\.\.".to_string(),
+                    ends_pattern: r"(?:Compilation complete\.|Found \d+ errors?\.) Watching for file changes\.".to_string(),
+                }),
+                ..Self::tsc()
+            },
+            "$gcc" => Self {
+                owner: Some("gcc".to_string()),
+                source: Some("gcc".to_string()),
+                pattern: vec![ProblemPattern {
+                    regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$"
+                        .to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    message: Some(5),
+                    ..ProblemPattern::default()
+                }],
+                ..Self::default()
+            },
+            "$eslint-stylish" => Self {
+                owner: Some("eslint".to_string()),
+                source: Some("eslint".to_string()),
+                file_location: Some(FileLocation::Absolute),
+                pattern: vec![
+                    ProblemPattern {
+                        regexp: r"^((?:[a-zA-Z]:)*[./\\]+.*?)$".to_string(),
+                        file: Some(1),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.+?)(?:\s\s+(.*))?$"
+                            .to_string(),
+                        line: Some(1),
+                        column: Some(2),
+                        severity: Some(3),
+                        message: Some(4),
+                        code: Some(5),
+                        r#loop: true,
+                        ..ProblemPattern::default()
+                    },
+                ],
+                ..Self::default()
+            },
+            _ => return None,
+        };
+        Some(matcher)
+    }
+
+    fn rustc() -> Self {
+        Self {
+            owner: Some("rustc".to_string()),
+            source: Some("rustc".to_string()),
+            pattern: vec![
+                ProblemPattern {
+                    regexp: r"^(warning|warn|error)(?:\[(.*?)\])?: (.*)$".to_string(),
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^[\s\->=]*(.*?):(\d*):(\d*)\s*$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..ProblemPattern::default()
+                },
+            ],
+            ..Self::default()
+        }
+    }
+
+    fn tsc() -> Self {
+        Self {
+            owner: Some("typescript".to_string()),
+            source: Some("ts".to_string()),
+            pattern: vec![ProblemPattern {
+                regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+TS(\d+)\s*:\s*(.*)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..ProblemPattern::default()
+            }],
+            ..Self::default()
+        }
+    }
+}
+
+/// A problem found in the task output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// Path of the file with the problem, relative paths are resolved if the matcher has a base directory.
+    pub path: PathBuf,
+    /// 1-based line the problem starts at.
+    pub line: u32,
+    /// 1-based column the problem starts at, the problem spans the whole line if missing.
+    pub column: Option<u32>,
+    pub end_line: Option<u32>,
+    pub end_column: Option<u32>,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+    pub source: Option<String>,
+}
+
+/// Matches the task output line by line against its problem matchers.
+pub struct ProblemCollector {
+    matchers: Vec<MatcherState>,
+}
+
+impl ProblemCollector {
+    /// Compiles the matchers given, skipping the ones with invalid regular expressions.
+    pub fn new(matchers: &[ProblemMatcher]) -> Self {
+        Self {
+            matchers: matchers
+                .iter()
+                .filter_map(|matcher| match MatcherState::new(matcher) {
+                    Ok(state) => Some(state),
+                    Err(e) => {
+                        log::error!("Invalid problem matcher {matcher:?}: {e:#}");
+                        None
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matchers.is_empty()
+    }
+
+    /// Matches a line of the output, without the line terminator.
+    /// Returns whether the problems reported changed.
+    pub fn process_line(&mut self, line: &str) -> bool {
+        let mut changed = false;
+        for matcher in &mut self.matchers {
+            changed |= matcher.process_line(line);
+        }
+        changed
+    }
+
+    /// Reports the problems of a compilation the task did not finish, as its output ended.
+    /// Returns whether the problems reported changed.
+    pub fn finish(&mut self) -> bool {
+        let mut changed = false;
+        for matcher in &mut self.matchers {
+            changed |= matcher.finish();
+        }
+        changed
+    }
+
+    /// The problems reported: for tasks that keep running, the ones of the last finished compilation.
+    pub fn problems(&self) -> impl Iterator<Item = &Problem> {
+        self.matchers
+            .iter()
+            .flat_map(|matcher| matcher.reported.iter())
+    }
+}
+
+struct CompiledPattern {
+    regex: Regex,
+    pattern: ProblemPattern,
+}
+
+struct MatcherState {
+    matcher: ProblemMatcher,
+    patterns: Vec<CompiledPattern>,
+    background: Option<(Regex, Regex)>,
+    /// Whether a compilation is in progress, always the case for matchers without `background`.
+    active: bool,
+    /// Index of the pattern to match the next line against, equal to the number of patterns
+    /// when looping over the last one.
+    next_pattern: usize,
+    /// Details captured by the patterns matched so far.
+    data: ProblemData,
+    /// Problems of the compilation in progress.
+    collected: Vec<Problem>,
+    reported: Vec<Problem>,
+}
+
+impl MatcherState {
+    fn new(matcher: &ProblemMatcher) -> anyhow::Result<Self> {
+        let patterns = matcher
+            .pattern
+            .iter()
+            .map(|pattern| {
+                Regex::new(&pattern.regexp).map(|regex| CompiledPattern {
+                    regex,
+                    pattern: pattern.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let background = matcher
+            .background
+            .as_ref()
+            .map(|background| {
+                anyhow::Ok((
+                    Regex::new(&background.begins_pattern)?,
+                    Regex::new(&background.ends_pattern)?,
+                ))
+            })
+            .transpose()?;
+        Ok(Self {
+            active: matcher
+                .background
+                .as_ref()
+                .is_none_or(|background| background.activates_on_start),
+            matcher: matcher.clone(),
+            patterns,
+            background,
+            next_pattern: 0,
+            data: ProblemData::default(),
+            collected: Vec::new(),
+            reported: Vec::new(),
+        })
+    }
+
+    fn process_line(&mut self, line: &str) -> bool {
+        if let Some((begins, ends)) = &self.background {
+            if begins.is_match(line) {
+                self.active = true;
+                self.collected.clear();
+                self.reset();
+                return false;
+            }
+            if ends.is_match(line) {
+                let was_active = std::mem::replace(&mut self.active, false);
+                self.reset();
+                if was_active {
+                    self.reported = std::mem::take(&mut self.collected);
+                }
+                return was_active;
+            }
+        }
+        if !self.active || self.patterns.is_empty() {
+            return false;
+        }
+
+        let Some(problem) = self.match_line(line) else {
+            return false;
+        };
+        if self.background.is_some() {
+            self.collected.push(problem);
+            false
+        } else {
+            self.reported.push(problem);
+            true
+        }
+    }
+
+    fn finish(&mut self) -> bool {
+        self.reset();
+        if self.background.is_some() && self.active {
+            self.active = false;
+            self.reported = std::mem::take(&mut self.collected);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn reset(&mut self) {
+        self.next_pattern = 0;
+        self.data = ProblemData::default();
+    }
+
+    fn match_line(&mut self, line: &str) -> Option<Problem> {
+        if self.next_pattern > 0 {
+            if let Some(problem) = self.match_next_pattern(line) {
+                return problem;
+            }
+            self.reset();
+        }
+        self.match_next_pattern(line).flatten()
+    }
+
+    /// Matches the line against the pattern expected next, returns `None` if it does not match
+    /// and the problem found if the line matched the last pattern.
+    fn match_next_pattern(&mut self, line: &str) -> Option<Option<Problem>> {
+        let last = self.patterns.len() - 1;
+        let pattern = &self.patterns[self.next_pattern.min(last)];
+        let captures = pattern.regex.captures(line)?;
+        if self.next_pattern < last {
+            self.data.fill(&pattern.pattern, &captures);
+            self.next_pattern += 1;
+            return Some(None);
+        }
+
+        // The details captured by the last pattern are only kept for the problem on its line,
+        // so that a looping pattern starts anew on every line.
+        let looping = pattern.pattern.r#loop && last > 0;
+        let mut data = self.data.clone();
+        data.fill(&pattern.pattern, &captures);
+        let problem = self.problem(data);
+        if looping {
+            self.next_pattern = self.patterns.len();
+        } else {
+            self.reset();
+        }
+        Some(problem)
+    }
+
+    fn problem(&self, data: ProblemData) -> Option<Problem> {
+        let path = self.resolve_path(&data.file?);
+        Some(Problem {
+            path,
+            line: data.line?,
+            column: data.column,
+            end_line: data.end_line,
+            end_column: data.end_column,
+            severity: data
+                .severity
+                .as_deref()
+                .and_then(ProblemSeverity::from_output)
+                .or(self.matcher.severity)
+                .unwrap_or_default(),
+            code: data.code,
+            message: data.message.unwrap_or_default(),
+            source: self
+                .matcher
+                .source
+                .clone()
+                .or_else(|| self.matcher.owner.clone()),
+        })
+    }
+
+    fn resolve_path(&self, file: &str) -> PathBuf {
+        let path = Path::new(file.trim());
+        match (
+            self.matcher.file_location.unwrap_or_default(),
+            self.matcher.base_directory.as_deref(),
+        ) {
+            (FileLocation::Relative, Some(base_directory)) => Path::new(base_directory).join(path),
+            _ => path.to_path_buf(),
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+struct ProblemData {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<String>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl ProblemData {
+    fn fill(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let group = |ix: Option<usize>| {
+            captures
+                .get(ix?)
+                .map(|group| group.as_str())
+                .filter(|group| !group.is_empty())
+        };
+        let number = |ix: Option<usize>| group(ix)?.trim().parse::<u32>().ok();
+
+        if let Some(file) = group(pattern.file) {
+            self.file = Some(file.to_string());
+        }
+        if let Some(location) = group(pattern.location) {
+            let mut numbers = location.split(',').map(|n| n.trim().parse::<u32>().ok());
+            self.line = numbers.next().flatten();
+            self.column = numbers.next().flatten();
+            self.end_line = numbers.next().flatten();
+            self.end_column = numbers.next().flatten();
+        }
+        if let Some(line) = number(pattern.line) {
+            self.line = Some(line);
+        }
+        if let Some(column) = number(pattern.column) {
+            self.column = Some(column);
+        }
+        if let Some(end_line) = number(pattern.end_line) {
+            self.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            self.end_column = Some(end_column);
+        }
+        if let Some(severity) = group(pattern.severity) {
+            self.severity = Some(severity.to_string());
+        }
+        if let Some(code) = group(pattern.code) {
+            self.code = Some(code.to_string());
+        }
+        if let Some(message) = group(pattern.message) {
+            self.message = Some(message.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(matcher: &str, output: &str) -> Vec<Problem> {
+        let mut collector = ProblemCollector::new(&[ProblemMatcher::builtin(matcher).unwrap()]);
+        for line in output.lines() {
+            collector.process_line(line);
+        }
+        collector.finish();
+        collector.problems().cloned().collect()
+    }
+
+    #[test]
+    fn test_builtin_matchers_compile() {
+        for name in ProblemMatcher::BUILTIN_NAMES {
+            let matcher = ProblemMatcher::builtin(name).unwrap();
+            assert!(
+                !ProblemCollector::new(&[matcher]).is_empty(),
+                "built-in matcher {name} should compile"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rustc_matcher() {
+        let problems = collect(
+            "$rustc",
+            "   Compiling zed v0.1.0\n\
+             error[E0425]: cannot find value `x` in this scope\n  \
+             --> src/main.rs:2:5\n   \
+             |\n\
+             warning: unused variable: `y`\n \
+             --> src/lib.rs:10:9\n",
+        );
+        assert_eq!(
+            problems,
+            vec![
+                Problem {
+                    path: PathBuf::from("src/main.rs"),
+                    line: 2,
+                    column: Some(5),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0425".to_string()),
+                    message: "cannot find value `x` in this scope".to_string(),
+                    source: Some("rustc".to_string()),
+                },
+                Problem {
+                    path: PathBuf::from("src/lib.rs"),
+                    line: 10,
+                    column: Some(9),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `y`".to_string(),
+                    source: Some("rustc".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_looping_pattern() {
+        let problems = collect(
+            "$eslint-stylish",
+            "/project/src/app.js\n  \
+             1:10  error    'foo' is defined but never used  no-unused-vars\n  \
+             3:1   warning  Unexpected console statement     no-console\n\
+             \n\
+             ✖ 2 problems (1 error, 1 warning)\n",
+        );
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.clone(),
+                    problem.line,
+                    problem.severity,
+                    problem.code.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    PathBuf::from("/project/src/app.js"),
+                    1,
+                    ProblemSeverity::Error,
+                    Some("no-unused-vars".to_string())
+                ),
+                (
+                    PathBuf::from("/project/src/app.js"),
+                    3,
+                    ProblemSeverity::Warning,
+                    Some("no-console".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_background_matcher() {
+        let mut matcher = ProblemMatcher::builtin("$tsc-watch").unwrap();
+        matcher.base_directory = Some("/project".to_string());
+        let mut collector = ProblemCollector::new(&[matcher]);
+
+        assert!(!collector.process_line("12:00:00 - Starting compilation in watch mode..."));
+        assert!(!collector.process_line(
+            "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'."
+        ));
+        assert_eq!(
+            collector.problems().count(),
+            0,
+            "Problems should only be reported when the compilation ends"
+        );
+        assert!(collector.process_line("12:00:01 - Found 1 error. Watching for file changes."));
+        let problems = collector.problems().cloned().collect::<Vec<_>>();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, PathBuf::from("/project/src/index.ts"));
+        assert_eq!((problems[0].line, problems[0].column), (3, Some(7)));
+        assert_eq!(problems[0].code.as_deref(), Some("2322"));
+
+        collector
+            .process_line("12:01:00 - File change detected. Starting incremental compilation...");
+        assert_eq!(
+            collector.problems().count(),
+            1,
+            "Problems of the previous compilation should be kept until the next one ends"
+        );
+        assert!(collector.process_line("12:01:01 - Found 0 errors. Watching for file changes."));
+        assert_eq!(collector.problems().count(), 0);
+    }
+
+    #[test]
+    fn test_extending_builtin_matcher() {
+        let reference: ProblemMatcherReference = serde_json_lenient::from_str(
+            r#"{ "base": "$tsc", "file_location": "absolute", "source": "tsc" }"#,
+        )
+        .unwrap();
+        let matcher = reference.resolve().unwrap();
+        assert_eq!(matcher.file_location, Some(FileLocation::Absolute));
+        assert_eq!(matcher.source.as_deref(), Some("tsc"));
+        assert_eq!(matcher.pattern, ProblemMatcher::tsc().pattern);
+
+        let unknown = ProblemMatcherReference::Named("$unknown".to_string());
+        assert_eq!(unknown.resolve(), None);
+    }
+}
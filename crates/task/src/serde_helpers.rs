@@ -1,8 +1,14 @@
 use schemars::{
-    SchemaGenerator,
-    schema::{ArrayValidation, InstanceType, Schema, SchemaObject, SingleOrVec, StringValidation},
+    JsonSchema, SchemaGenerator,
+    schema::{
+        ArrayValidation, InstanceType, Schema, SchemaObject, SingleOrVec, StringValidation,
+        SubschemaValidation,
+    },
+};
+use serde::{
+    Deserialize,
+    de::{self, Deserializer, Visitor},
 };
-use serde::de::{self, Deserializer, Visitor};
 use std::fmt;
 
 /// Generates a JSON schema for a non-empty string array.
@@ -62,3 +68,36 @@ where
 
     deserializer.deserialize_seq(NonEmptyStringVecVisitor)
 }
+
+/// Generates a JSON schema for either a single value or an array of values.
+pub fn one_or_many_json_schema<T: JsonSchema>(generator: &mut SchemaGenerator) -> Schema {
+    Schema::Object(SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation {
+            any_of: Some(vec![
+                generator.subschema_for::<T>(),
+                generator.subschema_for::<Vec<T>>(),
+            ]),
+            ..Default::default()
+        })),
+        ..Default::default()
+    })
+}
+
+/// Deserializes either a single value or an array of values into an array.
+pub fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
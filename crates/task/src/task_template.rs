@@ -8,9 +8,12 @@ use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX,
-    serde_helpers::{non_empty_string_vec, non_empty_string_vec_json_schema},
+    AttachRequest, ProblemMatcherReference, ResolvedTask, RevealTarget, Shell, SpawnInTerminal,
+    TaskContext, TaskId, VariableName, ZED_VARIABLE_NAME_PREFIX,
+    serde_helpers::{
+        non_empty_string_vec, non_empty_string_vec_json_schema, one_or_many,
+        one_or_many_json_schema,
+    },
 };
 
 /// A template definition of a Zed task to run.
@@ -25,6 +28,8 @@ pub struct TaskTemplate {
     /// Human readable name of the task to display in the UI.
     pub label: String,
     /// Executable command to spawn.
+    /// May be omitted by tasks that only run the tasks they depend on.
+    #[serde(default)]
     pub command: String,
     /// Arguments to the command.
     #[serde(default)]
@@ -72,6 +77,24 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks to run before this one; the task only starts after all of them succeed.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks from `depends_on`:
+    /// * `parallel` — start all of them at once (default)
+    /// * `sequence` — start each one after the previous one succeeds, in the order listed
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Problem matchers to turn the task output into diagnostics with: either names of the built-in ones
+    /// (`$rustc`, `$rustc-watch`, `$tsc`, `$tsc-watch`, `$gcc`, `$eslint-stylish`), or matchers defined in place.
+    #[serde(default, deserialize_with = "one_or_many")]
+    #[schemars(schema_with = "one_or_many_json_schema::<ProblemMatcherReference>")]
+    pub problem_matcher: Vec<ProblemMatcherReference>,
+    /// Glob patterns, relative to the worktree root, of the files to watch: once the task is spawned,
+    /// it is restarted every time any of the matching files change, cancelling the previous run.
+    #[serde(default, deserialize_with = "non_empty_string_vec")]
+    #[schemars(schema_with = "non_empty_string_vec_json_schema")]
+    pub watch: Vec<String>,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -109,6 +132,17 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// How to run the tasks a task depends on.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Start all dependencies at once.
+    #[default]
+    Parallel,
+    /// Start each dependency after the previous one succeeds, in the order listed.
+    Sequence,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -126,6 +160,11 @@ impl TaskTemplates {
 }
 
 impl TaskTemplate {
+    /// Whether the task has no command of its own and only runs the tasks it depends on.
+    pub fn is_compound(&self) -> bool {
+        self.command.trim().is_empty() && !self.depends_on.is_empty()
+    }
+
     /// Replaces all `VariableName` task variables in the task template string fields.
     /// If any replacement fails or the new string substitutions still have [`ZED_VARIABLE_NAME_PREFIX`],
     /// `None` is returned.
@@ -133,7 +172,9 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty()
+            || (self.command.trim().is_empty() && self.depends_on.is_empty())
+        {
             return None;
         }
 
@@ -209,6 +250,27 @@ impl TaskTemplate {
             &mut substituted_variables,
         )?;
 
+        let mut problem_matchers = Vec::with_capacity(self.problem_matcher.len());
+        for reference in &self.problem_matcher {
+            let Some(mut matcher) = reference.resolve() else {
+                log::warn!(
+                    "Skipping unknown problem matcher {reference:?} of task {:?}",
+                    self.label
+                );
+                continue;
+            };
+            matcher.base_directory = match matcher.base_directory.as_deref() {
+                Some(base_directory) => Some(substitute_all_template_variables_in_str(
+                    base_directory,
+                    &task_variables,
+                    &variable_names,
+                    &mut substituted_variables,
+                )?),
+                None => cwd.as_ref().map(|cwd| cwd.to_string_lossy().into_owned()),
+            };
+            problem_matchers.push(matcher);
+        }
+
         let task_hash = to_hex_hash(self)
             .context("hashing task template")
             .log_err()?;
@@ -241,6 +303,7 @@ impl TaskTemplate {
             id: id.clone(),
             substituted_variables,
             original_task: self.clone(),
+            task_context: cx.clone(),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
                 id,
@@ -267,6 +330,7 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                problem_matchers,
             },
         })
     }
@@ -464,6 +528,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolving_compound_templates() {
+        let compound_task = TaskTemplate {
+            label: "build all".to_string(),
+            depends_on: vec!["build server".to_string(), "build client".to_string()],
+            ..TaskTemplate::default()
+        };
+        assert!(compound_task.is_compound());
+
+        let resolved_task = compound_task
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("compound task without a command should resolve");
+        assert_eq!(resolved_task.resolved.label, "build all");
+        assert!(resolved_task.resolved.command.is_empty());
+        assert_eq!(resolved_task.task_context(), &TaskContext::default());
+
+        let task_with_command = TaskTemplate {
+            command: "echo done".to_string(),
+            ..compound_task
+        };
+        assert!(!task_with_command.is_compound());
+    }
+
     #[test]
     fn test_template_cwd_resolution() {
         let task_without_cwd = TaskTemplate {
@@ -888,4 +975,99 @@ mod tests {
             "overwritten"
         );
     }
+
+    #[test]
+    fn test_problem_matcher_resolution() {
+        let template: TaskTemplate = serde_json_lenient::from_str(
+            r#"{
+                "label": "build",
+                "command": "cargo build",
+                "cwd": "/project",
+                "problem_matcher": [
+                    "$rustc",
+                    "$unknown",
+                    { "base": "$tsc", "base_directory": "$ZED_WORKTREE_ROOT/client" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let context = TaskContext {
+            task_variables: TaskVariables::from_iter([(
+                VariableName::WorktreeRoot,
+                "/worktree".to_string(),
+            )]),
+            ..TaskContext::default()
+        };
+
+        let problem_matchers = template
+            .resolve_task(TEST_ID_BASE, &context)
+            .unwrap()
+            .resolved
+            .problem_matchers;
+        assert_eq!(
+            problem_matchers
+                .iter()
+                .map(|matcher| (matcher.owner.as_deref(), matcher.base_directory.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some("rustc"), Some("/project")),
+                (Some("typescript"), Some("/worktree/client")),
+            ],
+            "Unknown matchers should be skipped, and relative paths resolved against the task's cwd by default"
+        );
+
+        let single_matcher: TaskTemplate = serde_json_lenient::from_str(
+            r#"{ "label": "lint", "command": "eslint", "problem_matcher": "$eslint-stylish" }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            single_matcher.problem_matcher,
+            vec![ProblemMatcherReference::Named(
+                "$eslint-stylish".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_template_id() {
+        let template = TaskTemplate {
+            label: "check $ZED_FILE".into(),
+            command: "check".into(),
+            args: vec!["$ZED_FILE".into()],
+            ..TaskTemplate::default()
+        };
+        let resolve = |file: &str| {
+            let context = TaskContext {
+                task_variables: TaskVariables::from_iter([(VariableName::File, file.to_string())]),
+                ..TaskContext::default()
+            };
+            template.resolve_task(TEST_ID_BASE, &context).unwrap().id
+        };
+
+        let first = resolve("/project/a.rs");
+        let second = resolve("/project/b.rs");
+        assert_ne!(first, second);
+        assert_eq!(first.template_id(), second.template_id());
+        assert!(first.template_id().starts_with(TEST_ID_BASE));
+
+        let other_template = TaskTemplate {
+            command: "check --all".into(),
+            ..template.clone()
+        };
+        let context = TaskContext {
+            task_variables: TaskVariables::from_iter([(
+                VariableName::File,
+                "/project/a.rs".to_string(),
+            )]),
+            ..TaskContext::default()
+        };
+        assert_ne!(
+            other_template
+                .resolve_task(TEST_ID_BASE, &context)
+                .unwrap()
+                .id
+                .template_id(),
+            first.template_id()
+        );
+    }
 }
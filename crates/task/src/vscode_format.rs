@@ -1,9 +1,13 @@
-use anyhow::bail;
+use anyhow::{Context as _, bail};
 use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    BackgroundMatcher, DependsOrder, EnvVariableReplacer, FileLocation, ProblemMatcher,
+    ProblemMatcherReference, ProblemPattern, ProblemSeverity, TaskTemplate, TaskTemplates,
+    VariableName, serde_helpers::one_or_many,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -41,23 +45,176 @@ enum Command {
     },
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatcherReference {
+    Named(String),
+    Inline(VsCodeProblemMatcher),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemMatcher {
+    base: Option<String>,
+    owner: Option<String>,
+    source: Option<String>,
+    severity: Option<String>,
+    /// Either the kind of location, or the kind followed by the directory paths are relative to.
+    #[serde(default, deserialize_with = "one_or_many")]
+    file_location: Vec<String>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pattern: Vec<VsCodeProblemPattern>,
+    background: Option<VsCodeBackgroundMatcher>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    location: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default)]
+    r#loop: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeBackgroundMatcher {
+    #[serde(default)]
+    active_begin: bool,
+    begins_pattern: VsCodeBackgroundPattern,
+    ends_pattern: VsCodeBackgroundPattern,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeBackgroundPattern {
+    Regexp(String),
+    Pattern { regexp: String },
+}
+
+impl VsCodeBackgroundPattern {
+    fn into_regexp(self) -> String {
+        match self {
+            Self::Regexp(regexp) | Self::Pattern { regexp } => regexp,
+        }
+    }
+}
+
+impl VsCodeProblemMatcherReference {
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> ProblemMatcherReference {
+        let matcher = match self {
+            Self::Named(name) => return ProblemMatcherReference::Named(name),
+            Self::Inline(matcher) => matcher,
+        };
+        let mut file_location = matcher.file_location.into_iter();
+        ProblemMatcherReference::Inline(ProblemMatcher {
+            base: matcher.base,
+            owner: matcher.owner,
+            source: matcher.source,
+            severity: matcher
+                .severity
+                .and_then(|severity| match severity.as_str() {
+                    "error" => Some(ProblemSeverity::Error),
+                    "warning" => Some(ProblemSeverity::Warning),
+                    "info" => Some(ProblemSeverity::Info),
+                    "hint" => Some(ProblemSeverity::Hint),
+                    _ => None,
+                }),
+            // `autoDetect` also looks for relative paths in the base directory first.
+            file_location: file_location.next().map(|kind| match kind.as_str() {
+                "absolute" => FileLocation::Absolute,
+                _ => FileLocation::Relative,
+            }),
+            base_directory: file_location.next().map(|dir| replacer.replace(&dir)),
+            pattern: matcher
+                .pattern
+                .into_iter()
+                .map(|pattern| ProblemPattern {
+                    regexp: pattern.regexp,
+                    file: pattern.file,
+                    location: pattern.location,
+                    line: pattern.line,
+                    column: pattern.column,
+                    end_line: pattern.end_line,
+                    end_column: pattern.end_column,
+                    severity: pattern.severity,
+                    code: pattern.code,
+                    message: pattern.message,
+                    r#loop: pattern.r#loop,
+                })
+                .collect(),
+            background: matcher.background.map(|background| BackgroundMatcher {
+                activates_on_start: background.active_begin,
+                begins_pattern: background.begins_pattern.into_regexp(),
+                ends_pattern: background.ends_pattern.into_regexp(),
+            }),
+        })
+    }
+}
+
 impl VsCodeTaskDefinition {
     fn into_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
-        if self.other_attributes.contains_key("dependsOn") {
-            bail!("Encountered unsupported `dependsOn` key during deserialization");
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+        let depends_on = match self.other_attributes.get("dependsOn") {
+            None => Vec::new(),
+            Some(serde_json_lenient::Value::String(label)) => vec![label.clone()],
+            Some(serde_json_lenient::Value::Array(labels)) => labels
+                .iter()
+                .map(|label| {
+                    label
+                        .as_str()
+                        .map(ToOwned::to_owned)
+                        .context("Only task labels are supported in `dependsOn`")
+                })
+                .collect::<anyhow::Result<_>>()?,
+            Some(_) => bail!("Only task labels are supported in `dependsOn`"),
+        };
+        let depends_order = match self
+            .other_attributes
+            .get("dependsOrder")
+            .and_then(|order| order.as_str())
+        {
+            Some("sequence") => DependsOrder::Sequence,
+            _ => DependsOrder::Parallel,
+        };
+
+        let problem_matcher = match self.other_attributes.get("problemMatcher") {
+            Some(problem_matcher) => {
+                #[derive(Deserialize)]
+                struct ProblemMatchers(
+                    #[serde(deserialize_with = "one_or_many")] Vec<VsCodeProblemMatcherReference>,
+                );
+
+                serde_json_lenient::from_value::<ProblemMatchers>(problem_matcher.clone())
+                    .context("Invalid `problemMatcher`")
+                    .log_err()
+                    .map(|matchers| {
+                        matchers
+                            .0
+                            .into_iter()
+                            .map(|matcher| matcher.into_zed_format(replacer))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            }
+            None => Vec::new(),
         };
 
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        // `type` might not be set in tasks that only use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
+        // as such tasks are imported as compound tasks, with no command of their own.
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
@@ -66,6 +223,9 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
+            problem_matcher,
             ..Default::default()
         };
         if let Some(options) = self.options {
@@ -112,7 +272,8 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        BackgroundMatcher, DependsOrder, FileLocation, ProblemMatcher, ProblemMatcherReference,
+        ProblemPattern, TaskTemplate, TaskTemplates, VsCodeTaskFile,
         vscode_format::{Command, VsCodeTaskDefinition},
     };
 
@@ -215,6 +376,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: vec![ProblemMatcherReference::Named("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
@@ -226,18 +388,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matcher: vec![ProblemMatcherReference::Named("$tsc-watch".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matcher: vec![ProblemMatcherReference::Named("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: vec![ProblemMatcherReference::Named("$tsc".to_string())],
                 ..Default::default()
             },
         ];
@@ -318,32 +483,188 @@ mod tests {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matcher: vec![ProblemMatcherReference::Inline(ProblemMatcher {
+                    base: Some("$tsc-watch".to_string()),
+                    file_location: Some(FileLocation::Relative),
+                    base_directory: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+                    ..Default::default()
+                })],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matcher: vec![ProblemMatcherReference::Inline(ProblemMatcher {
+                    base: Some("$tsc".to_string()),
+                    file_location: Some(FileLocation::Relative),
+                    base_directory: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+                    ..Default::default()
+                })],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matcher: vec![ProblemMatcherReference::Named("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matcher: vec![ProblemMatcherReference::Named("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matcher: vec![ProblemMatcherReference::Inline(ProblemMatcher {
+                    base: Some("$tsc".to_string()),
+                    file_location: Some(FileLocation::Relative),
+                    base_directory: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+                    ..Default::default()
+                })],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                problem_matcher: vec![ProblemMatcherReference::Named("$rustc".to_string())],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                problem_matcher: vec![ProblemMatcherReference::Named("$rustc".to_string())],
                 ..Default::default()
             },
         ];
         let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
         assert_eq!(tasks.0, expected);
     }
+
+    #[test]
+    fn can_deserialize_depends_on() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "version": "2.0.0",
+                "tasks": [
+                    {
+                        "label": "lint",
+                        "type": "shell",
+                        "command": "eslint ."
+                    },
+                    {
+                        "label": "test",
+                        "type": "shell",
+                        "command": "jest",
+                        "dependsOn": "lint"
+                    },
+                    {
+                        "label": "check",
+                        "dependsOn": ["lint", "test"],
+                        "dependsOrder": "sequence"
+                    },
+                    {
+                        "label": "build",
+                        "dependsOn": [{ "type": "npm", "script": "build" }]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "lint".to_string(),
+                    command: "eslint .".to_string(),
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "test".to_string(),
+                    command: "jest".to_string(),
+                    depends_on: vec!["lint".to_string()],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "check".to_string(),
+                    depends_on: vec!["lint".to_string(), "test".to_string()],
+                    depends_order: DependsOrder::Sequence,
+                    ..Default::default()
+                },
+            ],
+            "Tasks depending on anything other than task labels should be skipped"
+        );
+    }
+
+    #[test]
+    fn can_deserialize_problem_matchers() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "version": "2.0.0",
+                "tasks": [
+                    {
+                        "label": "watch",
+                        "type": "shell",
+                        "command": "webpack --watch",
+                        "isBackground": true,
+                        "problemMatcher": {
+                            "owner": "webpack",
+                            "severity": "warning",
+                            "fileLocation": "absolute",
+                            "pattern": {
+                                "regexp": "^(.*):(\\d+):(\\d+) (.*)$",
+                                "file": 1,
+                                "line": 2,
+                                "column": 3,
+                                "message": 4
+                            },
+                            "background": {
+                                "activeBegin": true,
+                                "beginsPattern": { "regexp": "Compiling" },
+                                "endsPattern": "Compiled"
+                            }
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![TaskTemplate {
+                label: "watch".to_string(),
+                command: "webpack --watch".to_string(),
+                problem_matcher: vec![ProblemMatcherReference::Inline(ProblemMatcher {
+                    owner: Some("webpack".to_string()),
+                    severity: Some(crate::ProblemSeverity::Warning),
+                    file_location: Some(FileLocation::Absolute),
+                    pattern: vec![ProblemPattern {
+                        regexp: "^(.*):(\\d+):(\\d+) (.*)$".to_string(),
+                        file: Some(1),
+                        line: Some(2),
+                        column: Some(3),
+                        message: Some(4),
+                        ..Default::default()
+                    }],
+                    background: Some(BackgroundMatcher {
+                        activates_on_start: true,
+                        begins_pattern: "Compiling".to_string(),
+                        ends_pattern: "Compiled".to_string(),
+                    }),
+                    ..Default::default()
+                })],
+                ..Default::default()
+            }]
+        );
+    }
 }
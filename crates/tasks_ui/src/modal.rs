@@ -21,7 +21,7 @@ use ui::{
 
 use util::{ResultExt, truncate_and_trailoff};
 use workspace::{ModalView, Workspace};
-pub use zed_actions::{Rerun, Spawn};
+pub use zed_actions::{Rerun, Spawn, StopWatching};
 
 /// A modal used to spawn new tasks.
 pub struct TasksModalDelegate {
@@ -445,6 +445,14 @@ impl PickerDelegate for TasksModalDelegate {
             tooltip_label_text.push_str(&resolved_task.resolved.command_label);
         }
 
+        if !template.depends_on.is_empty() {
+            if !tooltip_label_text.trim().is_empty() {
+                tooltip_label_text.push('\n');
+            }
+            tooltip_label_text.push_str("Depends on: ");
+            tooltip_label_text.push_str(&template.depends_on.join(", "));
+        }
+
         if template.tags.len() > 0 {
             tooltip_label_text.push('\n');
             tooltip_label_text.push_str(
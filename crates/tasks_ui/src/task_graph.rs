@@ -0,0 +1,63 @@
+use gpui::{AnyElement, AppContext as _, Context, Entity};
+use ui::prelude::*;
+use workspace::{
+    Workspace,
+    notifications::{NotificationId, simple_message_notification::MessageNotification},
+    tasks::{TaskGraphRun, TaskRunStatus},
+};
+
+/// Shows the status of every task in the run, until the run succeeds or the notification is dismissed.
+pub(crate) fn show_task_graph_progress(
+    workspace: &mut Workspace,
+    run: Entity<TaskGraphRun>,
+    cx: &mut Context<Workspace>,
+) {
+    let notification_id =
+        NotificationId::composite::<TaskGraphRun>(("task-graph", run.entity_id()));
+    let title = format!("Running {}", run.read(cx).label);
+    workspace.show_notification(notification_id.clone(), cx, |cx| {
+        cx.new(|cx| {
+            cx.observe(&run, |_, _, cx| cx.notify()).detach();
+            let run = run.clone();
+            MessageNotification::new_from_builder(cx, move |_, cx| render_progress(&run, cx))
+                .with_title(title)
+                .show_suppress_button(false)
+        })
+    });
+
+    cx.observe(&run, move |workspace, run, cx| {
+        let succeeded = run.read(cx).is_finished() && run.read(cx).succeeded();
+        if succeeded {
+            workspace.dismiss_notification(&notification_id, cx);
+        }
+    })
+    .detach();
+}
+
+fn render_progress(
+    run: &Entity<TaskGraphRun>,
+    cx: &mut Context<MessageNotification>,
+) -> AnyElement {
+    v_flex()
+        .gap_0p5()
+        .children(run.read(cx).tasks.iter().map(|(label, status)| {
+            let (icon, color) = match status {
+                TaskRunStatus::Pending => (IconName::Circle, Color::Muted),
+                TaskRunStatus::Running => (IconName::ArrowCircle, Color::Info),
+                TaskRunStatus::Succeeded => (IconName::Check, Color::Success),
+                TaskRunStatus::Failed => (IconName::XCircle, Color::Error),
+                TaskRunStatus::Skipped => (IconName::Dash, Color::Muted),
+            };
+            h_flex()
+                .gap_1()
+                .child(Icon::new(icon).size(IconSize::Small).color(color))
+                .child(Label::new(label.clone()).size(LabelSize::Small).color(
+                    if *status == TaskRunStatus::Skipped {
+                        Color::Muted
+                    } else {
+                        Color::Default
+                    },
+                ))
+        }))
+        .into_any_element()
+}
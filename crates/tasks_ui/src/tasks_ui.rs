@@ -8,14 +8,31 @@ use task::{RevealTarget, TaskContext, TaskId, TaskTemplate, TaskVariables, Varia
 use workspace::Workspace;
 
 mod modal;
+mod task_graph;
 
-pub use modal::{Rerun, ShowAttachModal, Spawn, TaskOverrides, TasksModal};
+pub use modal::{Rerun, ShowAttachModal, Spawn, StopWatching, TaskOverrides, TasksModal};
 
 pub fn init(cx: &mut App) {
     cx.observe_new(
-        |workspace: &mut Workspace, _: Option<&mut Window>, _: &mut Context<Workspace>| {
+        |workspace: &mut Workspace, _: Option<&mut Window>, cx: &mut Context<Workspace>| {
+            let workspace_handle = cx.entity();
+            cx.subscribe(&workspace_handle, |workspace, _, event, cx| {
+                if let workspace::Event::TaskGraphStarted(run) = event {
+                    task_graph::show_task_graph_progress(workspace, run.clone(), cx);
+                }
+            })
+            .detach();
             workspace
                 .register_action(spawn_task_or_modal)
+                .register_action(|workspace, action: &StopWatching, _, cx| {
+                    let task_store = workspace.project().read(cx).task_store().clone();
+                    task_store.update(cx, |task_store, cx| match &action.task_id {
+                        Some(task_id) => {
+                            task_store.unwatch_task(&TaskId(task_id.clone()), cx);
+                        }
+                        None => task_store.unwatch_all_tasks(cx),
+                    });
+                })
                 .register_action(move |workspace, action: &modal::Rerun, window, cx| {
                     if let Some((task_source_kind, mut last_scheduled_task)) = workspace
                         .project()
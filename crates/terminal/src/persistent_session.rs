@@ -0,0 +1,440 @@
+//! Terminals whose shell is hosted by a background process, the session server, rather than by
+//! Zed itself: the shell keeps running when Zed quits, and reopening the workspace reattaches to
+//! it, along with its screen and scrollback.
+//!
+//! Every terminal talks to the server over two connections to its Unix socket: the data
+//! connection carries the terminal input and output as is, while the control connection carries
+//! resizes to the server and the exit of the shell back to Zed.
+
+use std::{
+    fs::DirBuilder,
+    io::{self, Read, Write},
+    os::{
+        fd::AsRawFd as _,
+        unix::{
+            fs::{DirBuilderExt as _, MetadataExt as _, PermissionsExt as _},
+            net::UnixStream,
+            process::CommandExt as _,
+        },
+    },
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use alacritty_terminal::{
+    event::{OnResize, WindowSize},
+    tty::{ChildEvent, EventedPty, EventedReadWrite},
+};
+use anyhow::{Context as _, Result, bail};
+use collections::HashMap;
+use polling::{Event, PollMode, Poller};
+use release_channel::RELEASE_CHANNEL;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use util::ResultExt as _;
+
+const PROTOCOL_VERSION: u32 = 1;
+const SERVER_START_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_MESSAGE_LEN: usize = 1024 * 1024;
+
+/// The keys alacritty's event loop expects the PTY events under.
+pub(crate) const PTY_READ_WRITE_TOKEN: usize = 0;
+pub(crate) const PTY_CHILD_EVENT_TOKEN: usize = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum Request {
+    /// Attaches to the session, starting its shell if the session does not exist. The connection
+    /// then carries the terminal input and output.
+    Attach {
+        session_id: String,
+        shell: Option<SessionShell>,
+        working_directory: Option<PathBuf>,
+        env: HashMap<String, String>,
+        scrolling_history: usize,
+        window_size: SessionWindowSize,
+    },
+    /// Opens the control connection of an attached session.
+    Control { session_id: String },
+    /// Ends the session, hanging up its shell.
+    Kill { session_id: String },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum Response {
+    Attached { pid: u32, reattached: bool },
+    Error { message: String },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum ControlMessage {
+    Resize(SessionWindowSize),
+    Exited { exit_code: Option<i32> },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct SessionShell {
+    pub(crate) program: String,
+    pub(crate) args: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(crate) struct SessionWindowSize {
+    pub(crate) lines: u16,
+    pub(crate) columns: u16,
+    pub(crate) cell_width: u16,
+    pub(crate) cell_height: u16,
+}
+
+impl From<WindowSize> for SessionWindowSize {
+    fn from(size: WindowSize) -> Self {
+        Self {
+            lines: size.num_lines,
+            columns: size.num_cols,
+            cell_width: size.cell_width,
+            cell_height: size.cell_height,
+        }
+    }
+}
+
+impl From<SessionWindowSize> for WindowSize {
+    fn from(size: SessionWindowSize) -> Self {
+        Self {
+            num_lines: size.lines,
+            num_cols: size.columns,
+            cell_width: size.cell_width,
+            cell_height: size.cell_height,
+        }
+    }
+}
+
+/// The socket of the session server, which is specific to the release channel and protocol
+/// version, so that different Zed versions do not talk to each other's servers.
+pub(crate) fn socket_path() -> PathBuf {
+    paths::temp_dir().join("terminal_sessions").join(format!(
+        "{}-{PROTOCOL_VERSION}.sock",
+        RELEASE_CHANNEL.dev_name()
+    ))
+}
+
+/// Creates the directory of the socket, which only the current user may access, so that other
+/// users cannot connect to the shells.
+pub(crate) fn create_socket_dir(socket_path: &Path) -> Result<()> {
+    let dir = socket_path.parent().context("socket path has no parent")?;
+    if let Some(parent) = dir.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    match DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(error) => return Err(error).with_context(|| format!("creating {dir:?}")),
+    }
+
+    let metadata = std::fs::metadata(dir)?;
+    if metadata.uid() != unsafe { libc::getuid() } {
+        bail!("{dir:?} belongs to another user");
+    }
+    if metadata.permissions().mode() & 0o777 != 0o700 {
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+/// Fails unless the process on the other end of the socket belongs to the current user.
+pub(crate) fn check_peer(stream: &UnixStream) -> io::Result<()> {
+    if peer_uid(stream)? == unsafe { libc::getuid() } {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "terminal session peer belongs to another user",
+        ))
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn peer_uid(stream: &UnixStream) -> io::Result<libc::uid_t> {
+    let mut credentials = libc::ucred {
+        pid: 0,
+        uid: 0,
+        gid: 0,
+    };
+    let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            &mut credentials as *mut libc::ucred as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if result == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(credentials.uid)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn peer_uid(stream: &UnixStream) -> io::Result<libc::uid_t> {
+    let mut uid = 0;
+    let mut gid = 0;
+    if unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(uid)
+}
+
+pub(crate) fn write_message(stream: &mut impl Write, message: &impl Serialize) -> io::Result<()> {
+    let mut bytes = serde_json::to_vec(message)?;
+    bytes.push(b'\n');
+    stream.write_all(&bytes)
+}
+
+/// Reads one message a byte at a time, so that nothing past it is consumed: on the data
+/// connection, the terminal output follows right after.
+pub(crate) fn read_message<T: DeserializeOwned>(stream: &mut impl Read) -> io::Result<T> {
+    let mut line = Vec::new();
+    let mut byte = [0];
+    loop {
+        match stream.read(&mut byte) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(_) if byte[0] == b'\n' => break,
+            Ok(_) if line.len() >= MAX_MESSAGE_LEN => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "message is too long",
+                ));
+            }
+            Ok(_) => line.push(byte[0]),
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(serde_json::from_slice(&line)?)
+}
+
+/// Connects to the session server, starting it if it is not running.
+fn connect() -> Result<UnixStream> {
+    let socket_path = socket_path();
+    if let Ok(stream) = UnixStream::connect(&socket_path) {
+        check_peer(&stream)?;
+        return Ok(stream);
+    }
+
+    start_server(&socket_path)?;
+    let started_at = Instant::now();
+    loop {
+        match UnixStream::connect(&socket_path) {
+            Ok(stream) => {
+                check_peer(&stream)?;
+                return Ok(stream);
+            }
+            Err(error) if started_at.elapsed() > SERVER_START_TIMEOUT => {
+                return Err(error).context("connecting to the terminal session server");
+            }
+            Err(_) => thread::sleep(Duration::from_millis(10)),
+        }
+    }
+}
+
+fn start_server(socket_path: &Path) -> Result<()> {
+    create_socket_dir(socket_path)?;
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .arg(format!(
+            "--terminal-session-server={}",
+            socket_path.display()
+        ))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Leave Zed's session, so that the server is not hung up along with Zed.
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let mut server = command
+        .spawn()
+        .context("starting the terminal session server")?;
+    thread::spawn(move || server.wait());
+    Ok(())
+}
+
+/// A session attached to by this terminal.
+pub(crate) struct AttachedSession {
+    pub(crate) pty: SessionPty,
+    /// The process id of the session's shell.
+    pub(crate) pid: u32,
+    /// Whether the session was already running, rather than started by this attach.
+    pub(crate) reattached: bool,
+}
+
+pub(crate) fn attach(
+    session_id: &str,
+    shell: Option<SessionShell>,
+    working_directory: Option<PathBuf>,
+    env: HashMap<String, String>,
+    scrolling_history: usize,
+    window_size: WindowSize,
+) -> Result<AttachedSession> {
+    let mut data = connect()?;
+    write_message(
+        &mut data,
+        &Request::Attach {
+            session_id: session_id.to_string(),
+            shell,
+            working_directory,
+            env,
+            scrolling_history,
+            window_size: window_size.into(),
+        },
+    )?;
+    let (pid, reattached) = match read_message(&mut data)? {
+        Response::Attached { pid, reattached } => (pid, reattached),
+        Response::Error { message } => bail!("failed to attach to terminal session: {message}"),
+    };
+
+    let mut control = connect()?;
+    write_message(
+        &mut control,
+        &Request::Control {
+            session_id: session_id.to_string(),
+        },
+    )?;
+
+    data.set_nonblocking(true)?;
+    control.set_nonblocking(true)?;
+    Ok(AttachedSession {
+        pty: SessionPty {
+            data,
+            control,
+            control_buf: Vec::new(),
+        },
+        pid,
+        reattached,
+    })
+}
+
+/// Ends the session, if the session server is running. The server is told from another thread,
+/// so that callers on the main thread never wait on the socket.
+pub(crate) fn end_session(session_id: &str) {
+    let request = Request::Kill {
+        session_id: session_id.to_string(),
+    };
+    thread::spawn(move || {
+        let Ok(mut stream) = UnixStream::connect(socket_path()) else {
+            return;
+        };
+        if check_peer(&stream).log_err().is_some() {
+            write_message(&mut stream, &request).log_err();
+        }
+    });
+}
+
+/// The PTY of a session, as seen from Zed.
+pub(crate) struct SessionPty {
+    data: UnixStream,
+    control: UnixStream,
+    control_buf: Vec<u8>,
+}
+
+impl EventedReadWrite for SessionPty {
+    type Reader = UnixStream;
+    type Writer = UnixStream;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        mut interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        interest.key = PTY_READ_WRITE_TOKEN;
+        unsafe {
+            poll.add_with_mode(&self.data, interest, mode)?;
+            poll.add_with_mode(
+                &self.control,
+                Event::readable(PTY_CHILD_EVENT_TOKEN),
+                PollMode::Level,
+            )
+        }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        mut interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        interest.key = PTY_READ_WRITE_TOKEN;
+        poll.modify_with_mode(&self.data, interest, mode)?;
+        poll.modify_with_mode(
+            &self.control,
+            Event::readable(PTY_CHILD_EVENT_TOKEN),
+            PollMode::Level,
+        )
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        poll.delete(&self.data)?;
+        poll.delete(&self.control)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        &mut self.data
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        &mut self.data
+    }
+}
+
+impl EventedPty for SessionPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        let mut buf = [0; 1024];
+        loop {
+            if let Some(end) = self.control_buf.iter().position(|&byte| byte == b'\n') {
+                let line = self.control_buf.drain(..=end).collect::<Vec<_>>();
+                match serde_json::from_slice(&line) {
+                    Ok(ControlMessage::Exited { exit_code }) => {
+                        return Some(ChildEvent::Exited(exit_code));
+                    }
+                    Ok(ControlMessage::Resize(_)) => {}
+                    Err(error) => log::warn!("invalid terminal session message: {error}"),
+                }
+                continue;
+            }
+
+            match self.control.read(&mut buf) {
+                Ok(0) => {
+                    // The session is gone without telling how its shell exited.
+                    return Some(ChildEvent::Exited(None));
+                }
+                Ok(read) => self.control_buf.extend_from_slice(&buf[..read]),
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => return None,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => {
+                    log::warn!("terminal session connection failed: {error}");
+                    return Some(ChildEvent::Exited(None));
+                }
+            }
+        }
+    }
+}
+
+impl OnResize for SessionPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        // The control connection is non-blocking, but resizes are too small and rare to ever
+        // fill its buffer.
+        write_message(
+            &mut self.control,
+            &ControlMessage::Resize(window_size.into()),
+        )
+        .log_err();
+    }
+}
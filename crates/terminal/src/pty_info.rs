@@ -24,9 +24,22 @@ impl ProcessIdGetter {
         }
     }
 
+    /// For a shell whose PTY lives in the session server, where the foreground process group
+    /// cannot be queried, so the shell itself is reported. The pid is 0 until the terminal has
+    /// attached to the session.
+    fn for_session(pid: u32) -> ProcessIdGetter {
+        ProcessIdGetter {
+            handle: -1,
+            fallback_pid: pid,
+        }
+    }
+
     fn pid(&self) -> Option<Pid> {
         let pid = unsafe { libc::tcgetpgrp(self.handle) };
         if pid < 0 {
+            if self.fallback_pid == 0 {
+                return None;
+            }
             return Some(Pid::from_u32(self.fallback_pid));
         }
         Some(Pid::from_u32(pid as u32))
@@ -89,6 +102,15 @@ pub struct PtyProcessInfo {
 
 impl PtyProcessInfo {
     pub fn new(pty: &Pty) -> PtyProcessInfo {
+        Self::with_pid_getter(ProcessIdGetter::new(pty))
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn for_session(pid: u32) -> PtyProcessInfo {
+        Self::with_pid_getter(ProcessIdGetter::for_session(pid))
+    }
+
+    fn with_pid_getter(pid_getter: ProcessIdGetter) -> PtyProcessInfo {
         let process_refresh_kind = ProcessRefreshKind::new()
             .with_cmd(UpdateKind::Always)
             .with_cwd(UpdateKind::Always)
@@ -99,7 +121,7 @@ impl PtyProcessInfo {
         PtyProcessInfo {
             system,
             refresh_kind: process_refresh_kind,
-            pid_getter: ProcessIdGetter::new(pty),
+            pid_getter,
             current: None,
         }
     }
@@ -0,0 +1,385 @@
+//! Rewriting of the PTY output before it reaches alacritty, for the sequences alacritty ignores:
+//! shell integration markers and inline images. Task output is also matched against the task's
+//! problem matchers here.
+
+use std::{
+    io::{self, Read, Write as _},
+    sync::Arc,
+};
+
+use alacritty_terminal::{
+    event::{OnResize, WindowSize},
+    tty::{ChildEvent, EventedPty, EventedReadWrite},
+    vte::Parser,
+};
+use polling::{Event, PollMode, Poller};
+
+use crate::{
+    shell_integration::{Marker, MarkerPerformer, ShellIntegration, ZoneTracker},
+    task_problems::{OutputLines, TaskProblems},
+    terminal_images::{GraphicsScanner, ImageCursor, ImagePlacement, TerminalImages, image_uri},
+};
+
+const READ_BUFFER_SIZE: usize = 0x4000;
+
+const BEL: u8 = 0x07;
+const ESC: u8 = 0x1b;
+
+struct OutputScanner {
+    parser: Parser,
+    performer: MarkerPerformer,
+    zones: ZoneTracker,
+    graphics: GraphicsScanner,
+    lines: OutputLines,
+    after_escape: bool,
+    /// A marker terminated by `ESC \`, whose zone tag goes after the `\`.
+    awaiting_string_terminator: Option<Marker>,
+    /// Replies to the program, such as graphics protocol acknowledgements.
+    replies: Vec<u8>,
+}
+
+impl OutputScanner {
+    fn new(integration: ShellIntegration, images: TerminalImages, problems: TaskProblems) -> Self {
+        Self {
+            parser: Parser::new(),
+            performer: MarkerPerformer::default(),
+            zones: ZoneTracker::new(integration),
+            graphics: GraphicsScanner::new(images),
+            lines: OutputLines::new(problems),
+            after_escape: false,
+            awaiting_string_terminator: None,
+            replies: Vec::new(),
+        }
+    }
+
+    fn scan(&mut self, mut input: &[u8], output: &mut Vec<u8>) {
+        self.lines.advance(input);
+        while !input.is_empty() {
+            // Sequences end with BEL, ESC or the `\` following an ESC, so checking for
+            // markers and images after those is enough.
+            let end = if self.after_escape {
+                1
+            } else {
+                input
+                    .iter()
+                    .position(|&byte| byte == BEL || byte == ESC)
+                    .map_or(input.len(), |ix| ix + 1)
+            };
+            let (segment, rest) = input.split_at(end);
+            input = rest;
+
+            let mut awaiting_marker = self.awaiting_string_terminator.take();
+            if segment != b"\\" {
+                if let Some(marker) = awaiting_marker.take() {
+                    // The ESC starts another sequence instead: the zone tag aborts it,
+                    // so it has to be repeated after the tag.
+                    self.zones.apply(marker, output);
+                    output.push(ESC);
+                }
+            }
+
+            self.parser.advance(&mut self.performer, segment);
+            output.extend_from_slice(segment);
+            self.after_escape = segment.last() == Some(&ESC);
+
+            if let Some(marker) = awaiting_marker {
+                self.zones.apply(marker, output);
+            }
+            if let Some(placement) = self.graphics.advance(segment, &mut self.replies) {
+                self.place_image(placement, output);
+            }
+            if let Some(marker) = self.performer.marker.take() {
+                if self.after_escape {
+                    self.awaiting_string_terminator = Some(marker);
+                } else {
+                    self.zones.apply(marker, output);
+                }
+            }
+        }
+    }
+
+    /// Anchors the image to the cell under the cursor, then moves the cursor past the image.
+    fn place_image(&mut self, placement: ImagePlacement, output: &mut Vec<u8>) {
+        self.zones
+            .write_hyperlink(None, &image_uri(placement.image_id), output);
+        output.push(b' ');
+        self.zones.write_current_zone(output);
+
+        match placement.cursor {
+            ImageCursor::NextLine => {
+                output.push(b'\r');
+                output.extend(std::iter::repeat_n(b'\n', placement.lines));
+            }
+            ImageCursor::AfterImage => {
+                output.extend(std::iter::repeat_n(b'\n', placement.lines - 1));
+                if placement.columns > 1 {
+                    output.extend_from_slice(format!("\x1b[{}C", placement.columns - 1).as_bytes());
+                }
+            }
+            ImageCursor::Unchanged => output.push(b'\x08'),
+        }
+    }
+}
+
+/// A PTY whose output goes through an [`OutputScanner`] before reaching the terminal.
+pub(crate) struct ScannedPty<P> {
+    pty: P,
+    scanner: OutputScanner,
+    read_buf: Box<[u8]>,
+    pending: Vec<u8>,
+}
+
+impl<P> ScannedPty<P> {
+    pub(crate) fn new(
+        pty: P,
+        integration: ShellIntegration,
+        images: TerminalImages,
+        problems: TaskProblems,
+        window_size: WindowSize,
+    ) -> Self {
+        let mut scanner = OutputScanner::new(integration, images, problems);
+        scanner
+            .graphics
+            .set_cell_size(window_size.cell_width, window_size.cell_height);
+        Self {
+            pty,
+            scanner,
+            read_buf: vec![0; READ_BUFFER_SIZE].into_boxed_slice(),
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl<P: EventedReadWrite> Read for ScannedPty<P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.pending.is_empty() {
+            // Leave room for the added sequences, so that the output is usually handed out
+            // in one read.
+            let len = (buf.len() / 2).clamp(1, self.read_buf.len());
+            let read = self.pty.reader().read(&mut self.read_buf[..len])?;
+            if read == 0 {
+                return Ok(0);
+            }
+            self.scanner.scan(&self.read_buf[..read], &mut self.pending);
+
+            if !self.scanner.replies.is_empty() {
+                let replies = std::mem::take(&mut self.scanner.replies);
+                if let Err(error) = self.pty.writer().write_all(&replies) {
+                    log::warn!("failed to reply to the terminal program: {error}");
+                }
+            }
+        }
+
+        let len = buf.len().min(self.pending.len());
+        buf[..len].copy_from_slice(&self.pending[..len]);
+        self.pending.drain(..len);
+        Ok(len)
+    }
+}
+
+impl<P: EventedReadWrite> EventedReadWrite for ScannedPty<P> {
+    type Reader = Self;
+    type Writer = P::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe { self.pty.register(poll, interest, mode) }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl<P: EventedPty> EventedPty for ScannedPty<P> {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl<P: OnResize> OnResize for ScannedPty<P> {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.scanner
+            .graphics
+            .set_cell_size(window_size.cell_width, window_size.cell_height);
+        self.pty.on_resize(window_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CommandStatus;
+
+    fn scan(scanner: &mut OutputScanner, input: &str) -> String {
+        let mut output = Vec::new();
+        scanner.scan(input.as_bytes(), &mut output);
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_zone_tags() {
+        let integration = ShellIntegration::default();
+        let mut scanner = OutputScanner::new(
+            integration.clone(),
+            TerminalImages::default(),
+            TaskProblems::default(),
+        );
+        assert!(!integration.is_active());
+
+        assert_eq!(
+            scan(&mut scanner, "\x1b]133;A\x07$ \x1b]133;B\x07"),
+            "\x1b]133;A\x07\x1b]8;;zed-shell-zone:prompt:0\x1b\\$ \
+             \x1b]133;B\x07\x1b]8;;zed-shell-zone:command:0\x1b\\"
+        );
+        assert!(integration.is_active());
+        assert_eq!(integration.command_status(0), Some(CommandStatus::Pending));
+
+        assert_eq!(
+            scan(&mut scanner, "ls\r\n\x1b]133;C\x07a.txt\r\n"),
+            "ls\r\n\x1b]133;C\x07\x1b]8;;zed-shell-zone:output:0\x1b\\a.txt\r\n"
+        );
+        assert_eq!(integration.command_status(0), Some(CommandStatus::Running));
+        assert_eq!(integration.last_finished_command(), None);
+
+        assert_eq!(
+            scan(&mut scanner, "\x1b]133;D;2\x07"),
+            "\x1b]133;D;2\x07\x1b]8;;\x1b\\"
+        );
+        assert_eq!(
+            integration.command_status(0),
+            Some(CommandStatus::Exited(Some(2)))
+        );
+        assert_eq!(integration.last_finished_command(), Some(0));
+
+        // An empty command line is not reported as a finished command.
+        scan(
+            &mut scanner,
+            "\x1b]133;A\x07$ \x1b]133;B\x07\r\n\x1b]133;D;0\x07",
+        );
+        assert_eq!(integration.command_status(1), Some(CommandStatus::Pending));
+        assert_eq!(integration.last_finished_command(), Some(0));
+    }
+
+    #[test]
+    fn test_string_terminator_across_reads() {
+        let integration = ShellIntegration::default();
+        let mut scanner = OutputScanner::new(
+            integration.clone(),
+            TerminalImages::default(),
+            TaskProblems::default(),
+        );
+
+        assert_eq!(scan(&mut scanner, "\x1b]133;A\x1b"), "\x1b]133;A\x1b");
+        assert_eq!(
+            scan(&mut scanner, "\\$ "),
+            "\\\x1b]8;;zed-shell-zone:prompt:0\x1b\\$ "
+        );
+
+        // A program's own hyperlink closes the zone tag, which is then reopened.
+        scan(&mut scanner, "\x1b]133;C\x1b\\");
+        assert_eq!(
+            scan(
+                &mut scanner,
+                "\x1b]8;;https://zed.dev\x1b\\zed\x1b]8;;\x1b\\"
+            ),
+            "\x1b]8;;https://zed.dev\x1b\\\
+             \x1b]8;id=zed-shell-zone.output.0.0;https://zed.dev\x1b\\\
+             zed\x1b]8;;\x1b\\\x1b]8;;zed-shell-zone:output:0\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_forged_tags() {
+        let integration = ShellIntegration::default();
+        let mut scanner = OutputScanner::new(
+            integration.clone(),
+            TerminalImages::default(),
+            TaskProblems::default(),
+        );
+
+        // Tags printed by a program are replaced by the current zone's.
+        scan(
+            &mut scanner,
+            "\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C\x07",
+        );
+        assert_eq!(
+            scan(&mut scanner, "\x1b]8;;zed-shell-zone:prompt:7\x07x"),
+            "\x1b]8;;zed-shell-zone:prompt:7\x07\x1b]8;;zed-shell-zone:output:0\x1b\\x"
+        );
+        assert_eq!(
+            scan(&mut scanner, "\x1b]8;;zed-image:0\x1b\\x"),
+            "\x1b]8;;zed-image:0\x1b\\\x1b]8;;zed-shell-zone:output:0\x1b\\x"
+        );
+        assert_eq!(
+            scan(
+                &mut scanner,
+                "\x1b]8;id=zed-shell-zone.prompt.7.1;https://zed.dev\x07x"
+            ),
+            "\x1b]8;id=zed-shell-zone.prompt.7.1;https://zed.dev\x07\
+             \x1b]8;;zed-shell-zone:output:0\x1b\\x"
+        );
+
+        // A program's link keeps its id, and its URI may contain `;`.
+        assert_eq!(
+            scan(&mut scanner, "\x1b]8;id=docs;https://zed.dev/?a;b\x07"),
+            "\x1b]8;id=docs;https://zed.dev/?a;b\x07\
+             \x1b]8;id=zed-shell-zone.output.0.docs;https://zed.dev/?a;b\x1b\\"
+        );
+
+        // Outside of zones, forged tags are closed.
+        scan(&mut scanner, "\x1b]8;;\x07\x1b]133;D;0\x07");
+        assert_eq!(
+            scan(&mut scanner, "\x1b]8;;zed-shell-zone:output:0\x07"),
+            "\x1b]8;;zed-shell-zone:output:0\x07\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_image_anchor() {
+        let images = TerminalImages::default();
+        let mut scanner = OutputScanner::new(
+            ShellIntegration::default(),
+            images.clone(),
+            TaskProblems::default(),
+        );
+        scanner.graphics.set_cell_size(1, 3);
+
+        scan(&mut scanner, "\x1b]133;A\x07\x1b]133;C\x07");
+        let sixel = "\x1bPq#1~~-~~\x1b\\";
+        assert_eq!(
+            scan(&mut scanner, &format!("{sixel}done")),
+            format!(
+                "{sixel}\x1b]8;id=zed-shell-zone.output.0.0;zed-image:0\x1b\\ \
+                 \x1b]8;;zed-shell-zone:output:0\x1b\\\r\n\n\n\n\ndone"
+            )
+        );
+        scanner.graphics.finish_decoding();
+        let image = images.get(0).unwrap();
+        assert_eq!((image.columns, image.lines), (2, 4));
+    }
+}
@@ -0,0 +1,640 @@
+//! The session server: a background process hosting the shells of persistent terminals.
+//!
+//! The server keeps its own copy of every terminal's screen and scrollback, which is replayed to
+//! Zed as plain text and escape sequences when it reattaches. It exits once its last session ends.
+
+use std::{
+    fs::File,
+    io::{self, Read, Write},
+    net::Shutdown,
+    os::{
+        fd::AsRawFd as _,
+        unix::{
+            fs::PermissionsExt as _,
+            net::{UnixListener, UnixStream},
+        },
+    },
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use alacritty_terminal::{
+    Term,
+    event::{OnResize, VoidListener},
+    grid::Dimensions,
+    index::{Column, Line},
+    term::{
+        Config, TermMode,
+        cell::{Cell, Flags, Hyperlink},
+    },
+    tty::{self, ChildEvent, EventedPty, EventedReadWrite, Pty},
+    vte::ansi::{Color, NamedColor, Processor, StdSyncHandler},
+};
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use parking_lot::Mutex;
+use polling::{Event, Events, PollMode, Poller};
+
+use crate::persistent_session::{
+    ControlMessage, PTY_CHILD_EVENT_TOKEN, PTY_READ_WRITE_TOKEN, Request, Response, SessionShell,
+    SessionWindowSize, check_peer, create_socket_dir, read_message, write_message,
+};
+
+const READ_BUFFER_SIZE: usize = 0x10000;
+/// How long the server waits for its first session, in case Zed went away right after starting it.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Runs the session server on the given socket, until its last session ends.
+pub fn run_session_server(socket_path: &Path) -> Result<()> {
+    create_socket_dir(socket_path)?;
+    let lock = lock_socket(socket_path)?;
+    if UnixStream::connect(socket_path).is_ok() {
+        // Another server got there first.
+        return Ok(());
+    }
+    std::fs::remove_file(socket_path).ok();
+    let listener = UnixListener::bind(socket_path)
+        .with_context(|| format!("binding terminal session socket {socket_path:?}"))?;
+    std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))?;
+    drop(lock);
+
+    // Setup Alacritty's env, which modifies the current process's environment
+    tty::setup_env();
+
+    let server = Arc::new(Server {
+        socket_path: socket_path.to_path_buf(),
+        sessions: Mutex::default(),
+    });
+    thread::spawn({
+        let server = server.clone();
+        move || {
+            thread::sleep(STARTUP_TIMEOUT);
+            server.exit_if_idle();
+        }
+    });
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let server = server.clone();
+        thread::spawn(move || {
+            server.handle_connection(stream).ok();
+        });
+    }
+    Ok(())
+}
+
+struct Server {
+    socket_path: PathBuf,
+    sessions: Mutex<HashMap<String, Arc<Session>>>,
+}
+
+impl Server {
+    fn handle_connection(self: &Arc<Self>, mut stream: UnixStream) -> Result<()> {
+        check_peer(&stream)?;
+        match read_message(&mut stream)? {
+            Request::Attach {
+                session_id,
+                shell,
+                working_directory,
+                env,
+                scrolling_history,
+                window_size,
+            } => {
+                let existing = self.sessions.lock().get(&session_id).cloned();
+                let (session, reattached) = match existing {
+                    Some(session) => (session, true),
+                    None => {
+                        let options = SessionOptions {
+                            shell,
+                            working_directory,
+                            env,
+                            scrolling_history,
+                            window_size,
+                        };
+                        match self.start_session(session_id, options) {
+                            Ok(session) => (session, false),
+                            Err(error) => {
+                                let message = format!("{error:#}");
+                                write_message(&mut stream, &Response::Error { message })?;
+                                return Ok(());
+                            }
+                        }
+                    }
+                };
+                write_message(
+                    &mut stream,
+                    &Response::Attached {
+                        pid: session.pid,
+                        reattached,
+                    },
+                )?;
+                session.attach(stream)
+            }
+            Request::Control { session_id } => {
+                let session = self
+                    .sessions
+                    .lock()
+                    .get(&session_id)
+                    .cloned()
+                    .context("no such session")?;
+                session.control(stream)
+            }
+            Request::Kill { session_id } => {
+                if let Some(session) = self.sessions.lock().get(&session_id) {
+                    // The session ends once the shell exits.
+                    unsafe { libc::kill(session.pid as libc::pid_t, libc::SIGHUP) };
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn start_session(
+        self: &Arc<Self>,
+        session_id: String,
+        options: SessionOptions,
+    ) -> Result<Arc<Session>> {
+        let mut sessions = self.sessions.lock();
+        let pty_options = tty::Options {
+            shell: options
+                .shell
+                .map(|shell| tty::Shell::new(shell.program, shell.args)),
+            working_directory: options.working_directory,
+            drain_on_exit: true,
+            env: options.env,
+        };
+        let mut pty = tty::new(&pty_options, options.window_size.into(), 0)?;
+        let pid = pty.child().id();
+        let input = pty.file().try_clone()?;
+        let poller = Arc::new(Poller::new()?);
+        unsafe { pty.register(&poller, Event::readable(0), PollMode::Level)? };
+
+        let config = Config {
+            scrolling_history: options.scrolling_history,
+            ..Config::default()
+        };
+        let session = Arc::new(Session {
+            pid,
+            pty: Mutex::new(pty),
+            input: Mutex::new(input),
+            state: Mutex::new(SessionState {
+                term: Term::new(config, &SessionSize(options.window_size), VoidListener),
+                parser: Processor::new(),
+                client: None,
+                control: None,
+            }),
+        });
+        sessions.insert(session_id.clone(), session.clone());
+
+        let server = self.clone();
+        let reader = session.clone();
+        thread::spawn(move || {
+            let exit_code = reader.read_output(&poller);
+            server.sessions.lock().remove(&session_id);
+            reader.exited(exit_code);
+            server.exit_if_idle();
+        });
+        Ok(session)
+    }
+
+    fn exit_if_idle(&self) {
+        let sessions = self.sessions.lock();
+        if sessions.is_empty() {
+            let _lock = lock_socket(&self.socket_path).ok();
+            std::fs::remove_file(&self.socket_path).ok();
+            std::process::exit(0);
+        }
+    }
+}
+
+/// Locks the file next to the socket, which servers hold while they bind or remove the socket,
+/// so that a server starting up does not race with another one starting up or exiting. The lock
+/// is released when the file is closed.
+fn lock_socket(socket_path: &Path) -> Result<File> {
+    let lock_path = socket_path.with_extension("lock");
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("opening terminal session lock {lock_path:?}"))?;
+    loop {
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } == 0 {
+            return Ok(file);
+        }
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error).with_context(|| format!("locking {lock_path:?}"));
+        }
+    }
+}
+
+struct SessionOptions {
+    shell: Option<SessionShell>,
+    working_directory: Option<PathBuf>,
+    env: HashMap<String, String>,
+    scrolling_history: usize,
+    window_size: SessionWindowSize,
+}
+
+struct SessionSize(SessionWindowSize);
+
+impl Dimensions for SessionSize {
+    fn total_lines(&self) -> usize {
+        self.screen_lines()
+    }
+
+    fn screen_lines(&self) -> usize {
+        self.0.lines as usize
+    }
+
+    fn columns(&self) -> usize {
+        self.0.columns as usize
+    }
+}
+
+struct Session {
+    pid: u32,
+    pty: Mutex<Pty>,
+    /// A separate handle on the PTY, so that writing the input does not wait for the output.
+    input: Mutex<File>,
+    state: Mutex<SessionState>,
+}
+
+struct SessionState {
+    term: Term<VoidListener>,
+    parser: Processor<StdSyncHandler>,
+    /// The data connection of the attached terminal.
+    client: Option<UnixStream>,
+    control: Option<UnixStream>,
+}
+
+impl Session {
+    /// Processes the shell output until it exits, returning its exit code.
+    fn read_output(&self, poller: &Poller) -> Option<i32> {
+        let mut buf = vec![0; READ_BUFFER_SIZE];
+        let mut events = Events::new();
+        loop {
+            events.clear();
+            if let Err(error) = poller.wait(&mut events, None) {
+                if error.kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return None;
+            }
+
+            for event in events.iter() {
+                match event.key {
+                    PTY_READ_WRITE_TOKEN => self.read_available(&mut buf),
+                    PTY_CHILD_EVENT_TOKEN => {
+                        let child_event = self.pty.lock().next_child_event();
+                        if let Some(ChildEvent::Exited(exit_code)) = child_event {
+                            self.read_available(&mut buf);
+                            return exit_code;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn read_available(&self, buf: &mut [u8]) {
+        loop {
+            let read = match self.pty.lock().reader().read(buf) {
+                Ok(0) => return,
+                Ok(read) => read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                // Linux also fails with `EIO` once the shell exits.
+                Err(_) => return,
+            };
+
+            let state = &mut *self.state.lock();
+            state.parser.advance(&mut state.term, &buf[..read]);
+            if let Some(client) = state.client.as_mut() {
+                if client.write_all(&buf[..read]).is_err() {
+                    state.client = None;
+                }
+            }
+        }
+    }
+
+    fn exited(&self, exit_code: Option<i32>) {
+        let mut state = self.state.lock();
+        if let Some(mut control) = state.control.take() {
+            write_message(&mut control, &ControlMessage::Exited { exit_code }).ok();
+        }
+        if let Some(client) = state.client.take() {
+            client.shutdown(Shutdown::Both).ok();
+        }
+    }
+
+    /// Replays the terminal to the new client, then forwards its input until it detaches.
+    fn attach(&self, mut stream: UnixStream) -> Result<()> {
+        {
+            let mut state = self.state.lock();
+            stream.write_all(&restore_sequence(&state.term))?;
+            if let Some(previous_client) = state.client.replace(stream.try_clone()?) {
+                previous_client.shutdown(Shutdown::Both).ok();
+            }
+        }
+
+        let mut buf = vec![0; 4096];
+        loop {
+            let read = match stream.read(&mut buf) {
+                Ok(0) => return Ok(()),
+                Ok(read) => read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error.into()),
+            };
+            self.write_input(&buf[..read])?;
+        }
+    }
+
+    fn write_input(&self, mut input: &[u8]) -> io::Result<()> {
+        let mut pty_input = self.input.lock();
+        while !input.is_empty() {
+            match pty_input.write(input) {
+                Ok(written) => input = &input[written..],
+                // The PTY is non-blocking, and full until the shell reads its input.
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(1))
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+
+    fn control(&self, mut stream: UnixStream) -> Result<()> {
+        if let Some(previous_control) = self.state.lock().control.replace(stream.try_clone()?) {
+            previous_control.shutdown(Shutdown::Both).ok();
+        }
+
+        loop {
+            match read_message(&mut stream)? {
+                ControlMessage::Resize(window_size) => {
+                    self.pty.lock().on_resize(window_size.into());
+                    self.state.lock().term.resize(SessionSize(window_size));
+                }
+                ControlMessage::Exited { .. } => {}
+            }
+        }
+    }
+}
+
+/// Escape sequences drawing the terminal's scrollback and screen, and restoring its cursor and
+/// modes, into an empty terminal of the same size.
+fn restore_sequence(term: &Term<VoidListener>) -> Vec<u8> {
+    let mut output = Vec::new();
+    let mode = *term.mode();
+    let grid = term.grid();
+    if mode.contains(TermMode::ALT_SCREEN) {
+        output.extend_from_slice(b"\x1b[?1049h");
+    }
+
+    let mut style = CellStyle::default();
+    let mut hyperlink = None::<Hyperlink>;
+    let first_line = -(grid.history_size() as i32);
+    let last_line = grid.screen_lines() as i32 - 1;
+    for line in first_line..=last_line {
+        let row = &grid[Line(line)];
+        let wrapped = row[Column(grid.columns() - 1)]
+            .flags
+            .contains(Flags::WRAPLINE);
+        // Wrapped lines are written out in full, so that they wrap again and can be reflowed.
+        let end = if wrapped {
+            grid.columns()
+        } else {
+            (0..grid.columns())
+                .rposition(|column| !is_blank(&row[Column(column)]))
+                .map_or(0, |column| column + 1)
+        };
+
+        for column in 0..end {
+            let cell = &row[Column(column)];
+            if cell
+                .flags
+                .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+            {
+                continue;
+            }
+
+            let cell_hyperlink = cell.hyperlink();
+            if cell_hyperlink != hyperlink {
+                match &cell_hyperlink {
+                    Some(link) => output.extend_from_slice(
+                        format!("\x1b]8;id={};{}\x1b\\", link.id(), link.uri()).as_bytes(),
+                    ),
+                    None => output.extend_from_slice(b"\x1b]8;;\x1b\\"),
+                }
+                hyperlink = cell_hyperlink;
+            }
+            let cell_style = CellStyle::from(cell);
+            if cell_style != style {
+                cell_style.write(&mut output);
+                style = cell_style;
+            }
+
+            let mut buf = [0; 4];
+            output.extend_from_slice(cell.c.encode_utf8(&mut buf).as_bytes());
+            for character in cell.zerowidth().unwrap_or_default() {
+                output.extend_from_slice(character.encode_utf8(&mut buf).as_bytes());
+            }
+        }
+
+        if line != last_line && !wrapped {
+            if style.has_background() {
+                // Avoid painting the next line with the background.
+                style = CellStyle::default();
+                style.write(&mut output);
+            }
+            output.extend_from_slice(b"\r\n");
+        }
+    }
+    if hyperlink.is_some() {
+        output.extend_from_slice(b"\x1b]8;;\x1b\\");
+    }
+    CellStyle::default().write(&mut output);
+
+    let cursor = grid.cursor.point;
+    output.extend_from_slice(
+        format!("\x1b[{};{}H", cursor.line.0 + 1, cursor.column.0 + 1).as_bytes(),
+    );
+    for (flag, sequence) in [
+        (TermMode::APP_CURSOR, "\x1b[?1h"),
+        (TermMode::APP_KEYPAD, "\x1b="),
+        (TermMode::BRACKETED_PASTE, "\x1b[?2004h"),
+        (TermMode::MOUSE_REPORT_CLICK, "\x1b[?1000h"),
+        (TermMode::MOUSE_DRAG, "\x1b[?1002h"),
+        (TermMode::MOUSE_MOTION, "\x1b[?1003h"),
+        (TermMode::FOCUS_IN_OUT, "\x1b[?1004h"),
+        (TermMode::UTF8_MOUSE, "\x1b[?1005h"),
+        (TermMode::SGR_MOUSE, "\x1b[?1006h"),
+    ] {
+        if mode.contains(flag) {
+            output.extend_from_slice(sequence.as_bytes());
+        }
+    }
+    if !mode.contains(TermMode::LINE_WRAP) {
+        output.extend_from_slice(b"\x1b[?7l");
+    }
+    if !mode.contains(TermMode::SHOW_CURSOR) {
+        output.extend_from_slice(b"\x1b[?25l");
+    }
+    output
+}
+
+fn is_blank(cell: &Cell) -> bool {
+    cell.c == ' '
+        && cell.bg == Color::Named(NamedColor::Background)
+        && !cell
+            .flags
+            .intersects(Flags::INVERSE | Flags::ALL_UNDERLINES)
+        && cell.hyperlink().is_none()
+}
+
+const STYLE_FLAGS: Flags = Flags::BOLD
+    .union(Flags::DIM)
+    .union(Flags::ITALIC)
+    .union(Flags::ALL_UNDERLINES)
+    .union(Flags::INVERSE)
+    .union(Flags::HIDDEN)
+    .union(Flags::STRIKEOUT);
+
+#[derive(Clone, Copy, PartialEq)]
+struct CellStyle {
+    fg: Color,
+    bg: Color,
+    flags: Flags,
+}
+
+impl Default for CellStyle {
+    fn default() -> Self {
+        Self {
+            fg: Color::Named(NamedColor::Foreground),
+            bg: Color::Named(NamedColor::Background),
+            flags: Flags::empty(),
+        }
+    }
+}
+
+impl From<&Cell> for CellStyle {
+    fn from(cell: &Cell) -> Self {
+        Self {
+            fg: cell.fg,
+            bg: cell.bg,
+            flags: cell.flags & STYLE_FLAGS,
+        }
+    }
+}
+
+impl CellStyle {
+    fn has_background(&self) -> bool {
+        self.bg != Color::Named(NamedColor::Background) || self.flags.contains(Flags::INVERSE)
+    }
+
+    /// Writes the SGR sequence setting this style from scratch.
+    fn write(&self, output: &mut Vec<u8>) {
+        let mut parameters = vec!["0".to_string()];
+        for (flag, parameter) in [
+            (Flags::BOLD, "1"),
+            (Flags::DIM, "2"),
+            (Flags::ITALIC, "3"),
+            (Flags::UNDERLINE, "4"),
+            (Flags::DOUBLE_UNDERLINE, "4:2"),
+            (Flags::UNDERCURL, "4:3"),
+            (Flags::DOTTED_UNDERLINE, "4:4"),
+            (Flags::DASHED_UNDERLINE, "4:5"),
+            (Flags::INVERSE, "7"),
+            (Flags::HIDDEN, "8"),
+            (Flags::STRIKEOUT, "9"),
+        ] {
+            if self.flags.contains(flag) {
+                parameters.push(parameter.to_string());
+            }
+        }
+        parameters.extend(color_parameter(self.fg, 30));
+        parameters.extend(color_parameter(self.bg, 40));
+
+        output.extend_from_slice(format!("\x1b[{}m", parameters.join(";")).as_bytes());
+    }
+}
+
+/// The SGR parameter of a foreground (base 30) or background (base 40) color, if not the default.
+fn color_parameter(color: Color, base: u8) -> Option<String> {
+    match color {
+        Color::Named(named) => {
+            let index = named as usize;
+            if index < 8 {
+                Some((base as usize + index).to_string())
+            } else if index < 16 {
+                Some((base as usize + 60 + index - 8).to_string())
+            } else {
+                None
+            }
+        }
+        Color::Indexed(index) => Some(format!("{};5;{index}", base + 8)),
+        Color::Spec(rgb) => Some(format!("{};2;{};{};{}", base + 8, rgb.r, rgb.g, rgb.b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(columns: u16, lines: u16) -> Term<VoidListener> {
+        Term::new(
+            Config::default(),
+            &SessionSize(SessionWindowSize {
+                lines,
+                columns,
+                cell_width: 1,
+                cell_height: 1,
+            }),
+            VoidListener,
+        )
+    }
+
+    fn text(term: &Term<VoidListener>) -> Vec<String> {
+        let grid = term.grid();
+        (-(grid.history_size() as i32)..grid.screen_lines() as i32)
+            .map(|line| {
+                let row = &grid[Line(line)];
+                (0..grid.columns())
+                    .map(|column| row[Column(column)].c)
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_restore_sequence() {
+        let mut original = term(10, 3);
+        Processor::<StdSyncHandler>::new().advance(
+            &mut original,
+            b"one\r\n\x1b[1;31mtwo\x1b[0m\r\nthree is long\r\n$ \x1b[?2004h",
+        );
+
+        let mut restored = term(10, 3);
+        Processor::<StdSyncHandler>::new().advance(&mut restored, &restore_sequence(&original));
+
+        assert_eq!(text(&restored), text(&original));
+        assert_eq!(text(&restored), ["one", "two", "three is l", "ong", "$"]);
+        assert_eq!(restored.grid().cursor.point, original.grid().cursor.point);
+        assert!(restored.mode().contains(TermMode::BRACKETED_PASTE));
+
+        let red_cell = &restored.grid()[Line(-1)][Column(0)];
+        assert_eq!(red_cell.fg, Color::Named(NamedColor::Red));
+        assert!(red_cell.flags.contains(Flags::BOLD));
+        let wrapped = &restored.grid()[Line(0)][Column(9)];
+        assert!(wrapped.flags.contains(Flags::WRAPLINE));
+    }
+}
@@ -0,0 +1,331 @@
+//! Shell integration via the OSC 133 ("FinalTerm") semantic prompt sequences.
+//!
+//! Alacritty ignores these sequences, so they are picked out of the PTY output before it reaches
+//! the terminal. Every prompt, command line and command output is then tagged with an OSC 8
+//! hyperlink carrying a `zed-shell-zone:` URI: this way the zone travels with the cells it
+//! belongs to through scrolling, reflow and history truncation, and can be read back from the grid.
+//! Hyperlinks printed by programs carry the zone in their id instead, and programs can't print
+//! the tags themselves.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use alacritty_terminal::{term::cell::Hyperlink, vte::Perform};
+use parking_lot::Mutex;
+
+use crate::terminal_images::is_image_uri;
+
+const ZONE_URI_PREFIX: &str = "zed-shell-zone:";
+/// Hyperlink ids can't contain `:`, which separates the hyperlink's parameters.
+const ZONE_ID_PREFIX: &str = "zed-shell-zone.";
+const MAX_TRACKED_COMMANDS: usize = 1000;
+
+/// The part of a shell interaction a terminal cell belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShellZoneKind {
+    Prompt,
+    Command,
+    Output,
+}
+
+impl ShellZoneKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ShellZoneKind::Prompt => "prompt",
+            ShellZoneKind::Command => "command",
+            ShellZoneKind::Output => "output",
+        }
+    }
+
+    fn from_str(kind: &str) -> Option<Self> {
+        match kind {
+            "prompt" => Some(ShellZoneKind::Prompt),
+            "command" => Some(ShellZoneKind::Command),
+            "output" => Some(ShellZoneKind::Output),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShellZone {
+    pub kind: ShellZoneKind,
+    pub command_id: u64,
+}
+
+impl ShellZone {
+    pub fn from_hyperlink(hyperlink: &Hyperlink) -> Option<Self> {
+        Self::from_uri(hyperlink.uri()).or_else(|| Self::from_link_id(hyperlink.id()))
+    }
+
+    fn from_uri(uri: &str) -> Option<Self> {
+        let (kind, command_id) = uri.strip_prefix(ZONE_URI_PREFIX)?.split_once(':')?;
+        Some(Self {
+            kind: ShellZoneKind::from_str(kind)?,
+            command_id: command_id.parse().ok()?,
+        })
+    }
+
+    fn to_uri(self) -> String {
+        format!(
+            "{ZONE_URI_PREFIX}{}:{}",
+            self.kind.as_str(),
+            self.command_id
+        )
+    }
+
+    fn from_link_id(id: &str) -> Option<Self> {
+        let mut parts = id.strip_prefix(ZONE_ID_PREFIX)?.split('.');
+        Some(Self {
+            kind: ShellZoneKind::from_str(parts.next()?)?,
+            command_id: parts.next()?.parse().ok()?,
+        })
+    }
+
+    /// The id of a hyperlink within the zone, where `link` tells the zone's links apart.
+    fn to_link_id(self, link: &str) -> String {
+        format!(
+            "{ZONE_ID_PREFIX}{}.{}.{link}",
+            self.kind.as_str(),
+            self.command_id
+        )
+    }
+}
+
+/// Whether the hyperlink is a zone tag rather than a link printed by a program.
+pub fn is_shell_zone(hyperlink: &Hyperlink) -> bool {
+    hyperlink.uri().starts_with(ZONE_URI_PREFIX)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandStatus {
+    /// The prompt is shown and the command is being typed.
+    Pending,
+    Running,
+    /// The command finished, with the exit code reported by the shell, if any.
+    Exited(Option<i32>),
+}
+
+/// The commands seen in a terminal, shared between the terminal and its PTY reader.
+#[derive(Clone, Default)]
+pub struct ShellIntegration(Arc<Mutex<ShellIntegrationState>>);
+
+#[derive(Default)]
+struct ShellIntegrationState {
+    active: bool,
+    commands: BTreeMap<u64, CommandStatus>,
+    last_finished_command: Option<u64>,
+}
+
+impl ShellIntegration {
+    /// Whether the shell has emitted any OSC 133 prompt marker.
+    pub fn is_active(&self) -> bool {
+        self.0.lock().active
+    }
+
+    pub fn command_status(&self, command_id: u64) -> Option<CommandStatus> {
+        self.0.lock().commands.get(&command_id).copied()
+    }
+
+    /// The most recent command that ran and exited.
+    pub fn last_finished_command(&self) -> Option<u64> {
+        self.0.lock().last_finished_command
+    }
+
+    fn start_prompt(&self, command_id: u64) {
+        let mut state = self.0.lock();
+        state.active = true;
+        state.commands.insert(command_id, CommandStatus::Pending);
+        while state.commands.len() > MAX_TRACKED_COMMANDS {
+            state.commands.pop_first();
+        }
+    }
+
+    fn start_command(&self, command_id: u64) {
+        if let Some(status) = self.0.lock().commands.get_mut(&command_id) {
+            *status = CommandStatus::Running;
+        }
+    }
+
+    fn finish_command(&self, command_id: u64, exit_code: Option<i32>) {
+        let mut state = self.0.lock();
+        // Shells also report an empty command line as finished, which is of no interest.
+        if let Some(status @ CommandStatus::Running) = state.commands.get_mut(&command_id) {
+            *status = CommandStatus::Exited(exit_code);
+            state.last_finished_command = Some(command_id);
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Marker {
+    PromptStart,
+    CommandStart,
+    OutputStart,
+    CommandFinished(Option<i32>),
+    HyperlinkOpened { id: Option<String>, uri: String },
+    HyperlinkClosed,
+}
+
+/// Records the last OSC 133 marker, or hyperlink, seen by a parser.
+#[derive(Default)]
+pub(crate) struct MarkerPerformer {
+    pub(crate) marker: Option<Marker>,
+}
+
+impl Perform for MarkerPerformer {
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        self.marker = match params {
+            [b"133", b"A", ..] => Some(Marker::PromptStart),
+            [b"133", b"B", ..] => Some(Marker::CommandStart),
+            [b"133", b"C", ..] => Some(Marker::OutputStart),
+            [b"133", b"D", rest @ ..] => {
+                let exit_code = rest
+                    .first()
+                    .and_then(|code| std::str::from_utf8(code).ok()?.parse().ok());
+                Some(Marker::CommandFinished(exit_code))
+            }
+            [b"8", _, b""] => Some(Marker::HyperlinkClosed),
+            // The URI itself may contain `;`.
+            [b"8", link_params, uri @ ..] if !uri.is_empty() => {
+                let id = link_params
+                    .split(|&byte| byte == b':')
+                    .find_map(|param| param.strip_prefix(b"id="))
+                    .map(|id| String::from_utf8_lossy(id).into_owned());
+                let uri = uri
+                    .iter()
+                    .map(|part| String::from_utf8_lossy(part))
+                    .collect::<Vec<_>>()
+                    .join(";");
+                Some(Marker::HyperlinkOpened { id, uri })
+            }
+            _ => None,
+        };
+    }
+}
+
+/// Tags the output with the zone the shell reported last.
+pub(crate) struct ZoneTracker {
+    integration: ShellIntegration,
+    next_command_id: u64,
+    next_link_id: u64,
+    current_zone: Option<ShellZone>,
+}
+
+impl ZoneTracker {
+    pub(crate) fn new(integration: ShellIntegration) -> Self {
+        Self {
+            integration,
+            next_command_id: 0,
+            next_link_id: 0,
+            current_zone: None,
+        }
+    }
+
+    pub(crate) fn apply(&mut self, marker: Marker, output: &mut Vec<u8>) {
+        let zone = match marker {
+            Marker::PromptStart => {
+                let command_id = self.next_command_id;
+                self.next_command_id += 1;
+                self.integration.start_prompt(command_id);
+                Some(ShellZone {
+                    kind: ShellZoneKind::Prompt,
+                    command_id,
+                })
+            }
+            Marker::CommandStart => self.current_zone.map(|zone| ShellZone {
+                kind: ShellZoneKind::Command,
+                ..zone
+            }),
+            Marker::OutputStart => self.current_zone.map(|zone| {
+                self.integration.start_command(zone.command_id);
+                ShellZone {
+                    kind: ShellZoneKind::Output,
+                    ..zone
+                }
+            }),
+            Marker::CommandFinished(exit_code) => {
+                if let Some(zone) = self.current_zone {
+                    self.integration.finish_command(zone.command_id, exit_code);
+                }
+                None
+            }
+            Marker::HyperlinkOpened { id, uri } => {
+                let is_forged = ShellZone::from_uri(&uri).is_some()
+                    || is_image_uri(&uri)
+                    || id.as_deref().and_then(ShellZone::from_link_id).is_some();
+                if is_forged {
+                    // Only the scanner adds tags: a program's are dropped.
+                    self.write_current_zone(output);
+                } else if self.current_zone.is_some() {
+                    self.write_hyperlink(id.as_deref(), &uri, output);
+                }
+                return;
+            }
+            // A program closed its own hyperlink, which also closed the zone tag.
+            Marker::HyperlinkClosed => match self.current_zone {
+                Some(zone) => Some(zone),
+                None => return,
+            },
+        };
+
+        self.current_zone = zone;
+        self.write_current_zone(output);
+    }
+
+    /// Opens the hyperlink of the current zone, or closes any hyperlink outside of zones.
+    pub(crate) fn write_current_zone(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(b"\x1b]8;;");
+        if let Some(zone) = self.current_zone {
+            output.extend_from_slice(zone.to_uri().as_bytes());
+        }
+        output.extend_from_slice(b"\x1b\\");
+    }
+
+    /// Opens a hyperlink to `uri` whose id carries the current zone, so that the cells under it
+    /// stay in the zone.
+    pub(crate) fn write_hyperlink(&mut self, id: Option<&str>, uri: &str, output: &mut Vec<u8>) {
+        output.extend_from_slice(b"\x1b]8;");
+        let id = match (self.current_zone, id) {
+            (Some(zone), Some(id)) => Some(zone.to_link_id(id)),
+            (Some(zone), None) => {
+                let link_id = self.next_link_id;
+                self.next_link_id += 1;
+                Some(zone.to_link_id(&link_id.to_string()))
+            }
+            (None, id) => id.map(str::to_string),
+        };
+        if let Some(id) = id {
+            output.extend_from_slice(b"id=");
+            output.extend_from_slice(id.as_bytes());
+        }
+        output.push(b';');
+        output.extend_from_slice(uri.as_bytes());
+        output.extend_from_slice(b"\x1b\\");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zone_from_uri() {
+        assert_eq!(
+            ShellZone::from_uri("zed-shell-zone:output:12"),
+            Some(ShellZone {
+                kind: ShellZoneKind::Output,
+                command_id: 12,
+            })
+        );
+        assert_eq!(ShellZone::from_uri("https://zed.dev"), None);
+        assert_eq!(ShellZone::from_uri("zed-shell-zone:other:1"), None);
+        assert_eq!(
+            ShellZone::from_link_id("zed-shell-zone.command.3.docs.1"),
+            Some(ShellZone {
+                kind: ShellZoneKind::Command,
+                command_id: 3,
+            })
+        );
+        assert_eq!(ShellZone::from_link_id("3_alacritty"), None);
+    }
+}
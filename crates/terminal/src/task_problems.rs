@@ -0,0 +1,161 @@
+//! Matching of a task's output against its problem matchers.
+//!
+//! The output is split into lines of plain text on the PTY reader thread, as it is read: this way
+//! no line is missed because of the terminal history being truncated or the output reflowing.
+
+use std::sync::Arc;
+
+use alacritty_terminal::vte::{Parser, Perform};
+use parking_lot::Mutex;
+use task::{Problem, ProblemCollector, ProblemMatcher};
+
+/// Longer lines are truncated, problem matchers are not expected to need more of them.
+const MAX_LINE_LENGTH: usize = 4096;
+
+/// Problems found in the output of a task, inactive for tasks without problem matchers.
+#[derive(Clone, Default)]
+pub(crate) struct TaskProblems(Option<Arc<Mutex<TaskProblemsState>>>);
+
+struct TaskProblemsState {
+    collector: ProblemCollector,
+    /// The last line of the output, not terminated yet.
+    unterminated_line: String,
+    changed: bool,
+}
+
+impl TaskProblems {
+    pub(crate) fn new(matchers: &[ProblemMatcher]) -> Self {
+        let collector = ProblemCollector::new(matchers);
+        if collector.is_empty() {
+            return Self::default();
+        }
+        Self(Some(Arc::new(Mutex::new(TaskProblemsState {
+            collector,
+            unterminated_line: String::new(),
+            changed: false,
+        }))))
+    }
+
+    /// Matches the last line of the output, once the task is finished.
+    pub(crate) fn finish(&self) {
+        let Some(state) = &self.0 else {
+            return;
+        };
+        let mut state = state.lock();
+        let line = std::mem::take(&mut state.unterminated_line);
+        let mut changed = !line.is_empty() && state.collector.process_line(&line);
+        changed |= state.collector.finish();
+        state.changed |= changed;
+    }
+
+    /// Whether the problems reported changed since the last call.
+    pub(crate) fn take_changed(&self) -> bool {
+        self.0
+            .as_ref()
+            .is_some_and(|state| std::mem::take(&mut state.lock().changed))
+    }
+
+    pub(crate) fn problems(&self) -> Vec<Problem> {
+        self.0.as_ref().map_or_else(Vec::new, |state| {
+            state.lock().collector.problems().cloned().collect()
+        })
+    }
+}
+
+/// Splits the PTY output into lines of text, dropping the escape sequences, for the problem matchers.
+pub(crate) struct OutputLines {
+    problems: TaskProblems,
+    parser: Parser,
+    performer: LinePerformer,
+}
+
+impl OutputLines {
+    pub(crate) fn new(problems: TaskProblems) -> Self {
+        Self {
+            problems,
+            parser: Parser::new(),
+            performer: LinePerformer::default(),
+        }
+    }
+
+    pub(crate) fn advance(&mut self, bytes: &[u8]) {
+        let Some(state) = &self.problems.0 else {
+            return;
+        };
+        self.parser.advance(&mut self.performer, bytes);
+
+        let mut state = state.lock();
+        for line in self.performer.lines.drain(..) {
+            let changed = state.collector.process_line(&line);
+            state.changed |= changed;
+        }
+        state.unterminated_line.clone_from(&self.performer.line);
+    }
+}
+
+#[derive(Default)]
+struct LinePerformer {
+    lines: Vec<String>,
+    line: String,
+    /// A carriage return not followed by a line feed overwrites the line, as progress bars do.
+    carriage_return: bool,
+}
+
+impl Perform for LinePerformer {
+    fn print(&mut self, c: char) {
+        if std::mem::take(&mut self.carriage_return) {
+            self.line.clear();
+        }
+        if self.line.len() < MAX_LINE_LENGTH {
+            self.line.push(c);
+        }
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\n' => {
+                self.carriage_return = false;
+                self.lines.push(std::mem::take(&mut self.line));
+            }
+            b'\r' => self.carriage_return = true,
+            b'\t' => self.print('\t'),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_lines() {
+        let problems = TaskProblems::new(&[ProblemMatcher::builtin("$gcc").unwrap()]);
+        let mut lines = OutputLines::new(problems.clone());
+
+        lines.advance(b"Building... 10%\rBuilding... 100%\r\n");
+        lines.advance(b"\x1b[1mmain.c:3:5: \x1b[31merror:\x1b[0m unknown type name 'foo'\r\n");
+        assert!(problems.take_changed());
+        assert!(!problems.take_changed());
+
+        lines.advance(b"main.c:7:1: warning: no newline at end of file");
+        assert!(
+            !problems.take_changed(),
+            "An unterminated line might still be written to"
+        );
+        problems.finish();
+        assert!(problems.take_changed());
+
+        let problems = problems.problems();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (problem.line, problem.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (3, "unknown type name 'foo'"),
+                (7, "no newline at end of file")
+            ]
+        );
+    }
+}
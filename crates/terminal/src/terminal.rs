@@ -2,13 +2,21 @@ pub mod mappings;
 
 pub use alacritty_terminal;
 
+#[cfg(unix)]
+mod persistent_session;
 mod pty_info;
+mod pty_scanner;
+#[cfg(unix)]
+mod session_server;
+mod shell_integration;
+mod task_problems;
 mod terminal_hyperlinks;
+mod terminal_images;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
     Term,
-    event::{Event as AlacTermEvent, EventListener, Notify, WindowSize},
+    event::{Event as AlacTermEvent, EventListener, OnResize, WindowSize},
     event_loop::{EventLoop, Msg, Notifier},
     grid::{Dimensions, Grid, Row, Scroll as AlacScroll},
     index::{Boundary, Column, Direction as AlacDirection, Line, Point as AlacPoint},
@@ -16,16 +24,16 @@ use alacritty_terminal::{
     sync::FairMutex,
     term::{
         Config, RenderableCursor, TermMode,
-        cell::{Cell, Flags},
+        cell::{Cell, Flags, Hyperlink},
         search::{Match, RegexIter, RegexSearch},
     },
-    tty::{self},
+    tty::{self, EventedPty},
     vi_mode::{ViModeCursor, ViMotion},
     vte::ansi::{
         ClearMode, CursorStyle as AlacCursorStyle, Handler, NamedPrivateMode, PrivateMode,
     },
 };
-use anyhow::{Result, bail};
+use anyhow::{Context as _, Result, bail};
 
 use futures::{
     FutureExt,
@@ -39,18 +47,31 @@ use mappings::mouse::{
 
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
+#[cfg(unix)]
+use persistent_session::SessionShell;
 use pty_info::PtyProcessInfo;
+use pty_scanner::ScannedPty;
 use serde::{Deserialize, Serialize};
+#[cfg(unix)]
+pub use session_server::run_session_server;
 use settings::Settings;
+pub use shell_integration::{CommandStatus, ShellZone, ShellZoneKind};
+use shell_integration::{ShellIntegration, is_shell_zone};
 use smol::channel::{Receiver, Sender};
-use task::{HideStrategy, Shell, TaskId};
+use task::{HideStrategy, Problem, ProblemMatcher, Shell, TaskId};
+use task_problems::TaskProblems;
 use terminal_hyperlinks::RegexSearches;
+pub use terminal_images::TerminalImage;
+use terminal_images::{TerminalImages, image_id, is_image_anchor};
 use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
 use theme::{ActiveTheme, Theme};
+#[cfg(unix)]
+use util::ResultExt as _;
 use util::{paths::home_dir, truncate_and_trailoff};
 
 use std::{
     borrow::Cow,
+    cell::RefCell,
     cmp::{self, min},
     fmt::Display,
     ops::{Deref, RangeInclusive},
@@ -83,6 +104,10 @@ actions!(
         ScrollPageDown,
         ScrollToTop,
         ScrollToBottom,
+        ScrollToPreviousPrompt,
+        ScrollToNextPrompt,
+        SelectCommandOutput,
+        CopyCommandOutput,
         ToggleViMode,
     ]
 );
@@ -111,6 +136,8 @@ pub enum Event {
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
+    /// The problems the task's problem matchers found in its output changed.
+    TaskProblemsChanged,
 }
 
 #[derive(Clone, Debug)]
@@ -139,6 +166,9 @@ enum InternalEvent {
     // FocusNextMatch,
     Scroll(AlacScroll),
     ScrollToAlacPoint(AlacPoint),
+    ScrollToPreviousPrompt,
+    ScrollToNextPrompt,
+    SelectCommandOutput { copy: bool },
     SetSelection(Option<(Selection, AlacPoint)>),
     UpdateSelection(Point<Pixels>),
     // Adjusted mouse position, should open
@@ -318,6 +348,22 @@ pub const MAX_SCROLL_HISTORY_LINES: usize = 100_000;
 pub struct TerminalBuilder {
     terminal: Terminal,
     events_rx: UnboundedReceiver<AlacTermEvent>,
+    #[cfg(unix)]
+    attach: Option<(
+        Task<Result<persistent_session::AttachedSession>>,
+        AttachingSession,
+    )>,
+}
+
+/// A persistent session the terminal is attaching to in the background, along with what it
+/// takes to start a regular PTY instead, should attaching fail.
+#[cfg(unix)]
+struct AttachingSession {
+    session_id: String,
+    pty_options: tty::Options,
+    window_size: WindowSize,
+    window_id: u64,
+    events_tx: UnboundedSender<AlacTermEvent>,
 }
 
 impl TerminalBuilder {
@@ -331,6 +377,7 @@ impl TerminalBuilder {
         alternate_scroll: AlternateScroll,
         max_scroll_history_lines: Option<usize>,
         is_ssh_terminal: bool,
+        persistent_session: Option<String>,
         window: AnyWindowHandle,
         completion_tx: Sender<Option<ExitStatus>>,
         cx: &App,
@@ -354,33 +401,32 @@ impl TerminalBuilder {
 
         let mut terminal_title_override = None;
 
-        let pty_options = {
-            let alac_shell = match shell.clone() {
-                Shell::System => {
-                    #[cfg(target_os = "windows")]
-                    {
-                        Some(alacritty_terminal::tty::Shell::new(
-                            util::get_windows_system_shell(),
-                            Vec::new(),
-                        ))
-                    }
-                    #[cfg(not(target_os = "windows"))]
-                    {
-                        None
-                    }
-                }
-                Shell::Program(program) => {
-                    Some(alacritty_terminal::tty::Shell::new(program, Vec::new()))
+        let shell_command = match shell.clone() {
+            Shell::System => {
+                #[cfg(target_os = "windows")]
+                {
+                    Some((util::get_windows_system_shell(), Vec::new()))
                 }
-                Shell::WithArguments {
-                    program,
-                    args,
-                    title_override,
-                } => {
-                    terminal_title_override = title_override;
-                    Some(alacritty_terminal::tty::Shell::new(program, args))
+                #[cfg(not(target_os = "windows"))]
+                {
+                    None
                 }
-            };
+            }
+            Shell::Program(program) => Some((program, Vec::new())),
+            Shell::WithArguments {
+                program,
+                args,
+                title_override,
+            } => {
+                terminal_title_override = title_override;
+                Some((program, args))
+            }
+        };
+
+        let pty_options = {
+            let alac_shell = shell_command
+                .clone()
+                .map(|(program, args)| alacritty_terminal::tty::Shell::new(program, args));
 
             alacritty_terminal::tty::Options {
                 shell: alac_shell,
@@ -428,41 +474,102 @@ impl TerminalBuilder {
         }
 
         let term = Arc::new(FairMutex::new(term));
-
-        //Setup the pty...
-        let pty = match tty::new(
-            &pty_options,
-            TerminalBounds::default().into(),
-            window.window_id().as_u64(),
-        ) {
-            Ok(pty) => pty,
-            Err(error) => {
-                bail!(TerminalError {
-                    directory: working_directory,
-                    shell,
-                    source: error,
-                });
-            }
+        let shell_integration = ShellIntegration::default();
+        let images = {
+            let listener = ZedListener(events_tx.clone());
+            TerminalImages::new(move || listener.send_event(AlacTermEvent::Wakeup))
         };
+        let task_problems = TaskProblems::new(
+            task.as_ref()
+                .map_or(&[][..], |task| task.problem_matchers.as_slice()),
+        );
+        let window_size: WindowSize = TerminalBounds::default().into();
+
+        // Host the shell in the session server. Attaching may have to start the server first, so
+        // it happens in the background, and the PTY starts once it is done: see `subscribe`.
+        #[cfg(unix)]
+        let attach = persistent_session.map(|session_id| {
+            let attach = cx.background_spawn({
+                let session_id = session_id.clone();
+                let shell = shell_command.map(|(program, args)| SessionShell { program, args });
+                let working_directory = pty_options.working_directory.clone();
+                let env = pty_options.env.clone();
+                async move {
+                    persistent_session::attach(
+                        &session_id,
+                        shell,
+                        working_directory,
+                        env,
+                        scrolling_history,
+                        window_size,
+                    )
+                }
+            });
+            let attaching = AttachingSession {
+                session_id,
+                pty_options: pty_options.clone(),
+                window_size,
+                window_id: window.window_id().as_u64(),
+                events_tx: events_tx.clone(),
+            };
+            (attach, attaching)
+        });
+        #[cfg(unix)]
+        let attached_session = attach.as_ref().map(|(_, attaching)| {
+            let persistent_session = PersistentSession {
+                id: attaching.session_id.clone(),
+                reattached: false,
+                keep_alive: false,
+            };
+            (
+                PtySender::attaching(),
+                PtyProcessInfo::for_session(0),
+                persistent_session,
+            )
+        });
+        #[cfg(not(unix))]
+        let attached_session = persistent_session.and(None);
 
-        let pty_info = PtyProcessInfo::new(&pty);
+        let (pty_tx, pty_info, persistent_session) = match attached_session {
+            Some((pty_tx, pty_info, persistent_session)) => {
+                (pty_tx, pty_info, Some(persistent_session))
+            }
+            None => {
+                //Setup the pty...
+                let pty = match tty::new(&pty_options, window_size, window.window_id().as_u64()) {
+                    Ok(pty) => pty,
+                    Err(error) => {
+                        bail!(TerminalError {
+                            directory: working_directory,
+                            shell,
+                            source: error,
+                        });
+                    }
+                };
 
-        //And connect them together
-        let event_loop = EventLoop::new(
-            term.clone(),
-            ZedListener(events_tx.clone()),
-            pty,
-            pty_options.drain_on_exit,
-            false,
-        )?;
+                let pty_info = PtyProcessInfo::new(&pty);
+                let pty = ScannedPty::new(
+                    pty,
+                    shell_integration.clone(),
+                    images.clone(),
+                    task_problems.clone(),
+                    window_size,
+                );
 
-        //Kick things off
-        let pty_tx = event_loop.channel();
-        let _io_thread = event_loop.spawn(); // DANGER
+                //And connect them together
+                let pty_tx = spawn_event_loop(
+                    term.clone(),
+                    ZedListener(events_tx.clone()),
+                    pty,
+                    pty_options.drain_on_exit,
+                )?;
+                (PtySender::Running(pty_tx), pty_info, None)
+            }
+        };
 
         let terminal = Terminal {
             task,
-            pty_tx: Notifier(pty_tx),
+            pty_tx,
             completion_tx,
             term,
             term_config: config,
@@ -473,6 +580,10 @@ impl TerminalBuilder {
             matches: Vec::new(),
             selection_head: None,
             pty_info,
+            shell_integration,
+            images,
+            task_problems,
+            persistent_session,
             breadcrumb_text: String::new(),
             scroll_px: px(0.),
             next_link_id: 0,
@@ -487,10 +598,31 @@ impl TerminalBuilder {
         Ok(TerminalBuilder {
             terminal,
             events_rx,
+            #[cfg(unix)]
+            attach,
         })
     }
 
     pub fn subscribe(mut self, cx: &Context<Terminal>) -> Terminal {
+        #[cfg(unix)]
+        if let Some((attach, attaching)) = self.attach.take() {
+            cx.spawn(async move |terminal, cx| {
+                let session = attach.await;
+                let Some(terminal) = terminal.upgrade() else {
+                    // Nobody saw the shell of a new session, while a reattached one is kept
+                    // for next time.
+                    if session.is_ok_and(|session| !session.reattached) {
+                        end_persistent_session(&attaching.session_id);
+                    }
+                    return anyhow::Ok(());
+                };
+                terminal.update(cx, |terminal, cx| {
+                    terminal.start_session_pty(session, attaching, cx)
+                })
+            })
+            .detach();
+        }
+
         //Event loop
         cx.spawn(async move |terminal, cx| {
             while let Some(event) = self.events_rx.next().await {
@@ -549,6 +681,16 @@ impl TerminalBuilder {
         })
         .detach();
 
+        if self.terminal.persistent_session.is_some() {
+            cx.on_app_quit(|terminal, _| {
+                if let Some(session) = terminal.persistent_session.as_mut() {
+                    session.keep_alive = true;
+                }
+                futures::future::ready(())
+            })
+            .detach();
+        }
+
         self.terminal
     }
 }
@@ -582,6 +724,23 @@ pub struct TerminalContent {
     pub last_hovered_word: Option<HoveredWord>,
     pub scrolled_to_top: bool,
     pub scrolled_to_bottom: bool,
+    /// The visible lines where a shell prompt starts, when the shell reports its prompts.
+    pub command_marks: Vec<CommandMark>,
+    /// The images covering part of the viewport.
+    pub images: Vec<IndexedImage>,
+}
+
+#[derive(Clone)]
+pub struct IndexedImage {
+    /// The top left cell of the image.
+    pub point: AlacPoint,
+    pub image: TerminalImage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandMark {
+    pub line: Line,
+    pub status: CommandStatus,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -608,6 +767,8 @@ impl Default for TerminalContent {
             last_hovered_word: None,
             scrolled_to_top: false,
             scrolled_to_bottom: false,
+            command_marks: Vec::new(),
+            images: Vec::new(),
         }
     }
 }
@@ -619,7 +780,7 @@ pub enum SelectionPhase {
 }
 
 pub struct Terminal {
-    pty_tx: Notifier,
+    pty_tx: PtySender,
     completion_tx: Sender<Option<ExitStatus>>,
     term: Arc<FairMutex<Term<ZedListener>>>,
     term_config: Config,
@@ -631,6 +792,10 @@ pub struct Terminal {
     pub selection_head: Option<AlacPoint>,
     pub breadcrumb_text: String,
     pub pty_info: PtyProcessInfo,
+    shell_integration: ShellIntegration,
+    images: TerminalImages,
+    task_problems: TaskProblems,
+    persistent_session: Option<PersistentSession>,
     title_override: Option<SharedString>,
     pub python_venv_directory: Option<PathBuf>,
     scroll_px: Pixels,
@@ -642,6 +807,73 @@ pub struct Terminal {
     is_ssh_terminal: bool,
 }
 
+/// Sends the terminal input and resizes to the PTY event loop. While the terminal attaches to its
+/// persistent session, there is no event loop yet, and they are queued.
+enum PtySender {
+    Running(Notifier),
+    #[cfg(unix)]
+    Attaching {
+        messages: RefCell<Vec<Msg>>,
+        /// Input only meant for a new shell, which a reattached shell already got.
+        new_shell_input: Vec<Cow<'static, [u8]>>,
+    },
+}
+
+impl PtySender {
+    #[cfg(unix)]
+    fn attaching() -> Self {
+        PtySender::Attaching {
+            messages: RefCell::default(),
+            new_shell_input: Vec::new(),
+        }
+    }
+
+    fn send(&self, message: Msg) {
+        match self {
+            PtySender::Running(notifier) => {
+                notifier.0.send(message).ok();
+            }
+            #[cfg(unix)]
+            PtySender::Attaching { messages, .. } => messages.borrow_mut().push(message),
+        }
+    }
+
+    fn notify(&self, bytes: impl Into<Cow<'static, [u8]>>) {
+        let bytes = bytes.into();
+        if !bytes.is_empty() {
+            self.send(Msg::Input(bytes));
+        }
+    }
+
+    /// Hands the queued messages to the event loop, once it runs.
+    #[cfg(unix)]
+    fn start(&mut self, notifier: Notifier, new_shell: bool) {
+        let attaching = std::mem::replace(self, PtySender::Running(notifier));
+        if let PtySender::Attaching {
+            messages,
+            new_shell_input,
+        } = attaching
+        {
+            if new_shell {
+                for input in new_shell_input {
+                    self.notify(input);
+                }
+            }
+            for message in messages.into_inner() {
+                self.send(message);
+            }
+        }
+    }
+}
+
+struct PersistentSession {
+    id: String,
+    /// Whether the shell was already running when the terminal attached to it.
+    reattached: bool,
+    /// Whether the session outlives the terminal, as Zed is quitting.
+    keep_alive: bool,
+}
+
 pub struct TaskState {
     pub id: TaskId,
     pub full_label: String,
@@ -653,6 +885,7 @@ pub struct TaskState {
     pub show_summary: bool,
     pub show_command: bool,
     pub show_rerun: bool,
+    pub problem_matchers: Vec<ProblemMatcher>,
 }
 
 /// A status of the current terminal tab's task.
@@ -724,6 +957,10 @@ impl Terminal {
             AlacTermEvent::Wakeup => {
                 cx.emit(Event::Wakeup);
 
+                if self.task_problems.take_changed() {
+                    cx.emit(Event::TaskProblemsChanged);
+                }
+
                 if self.pty_info.has_changed() {
                     cx.emit(Event::TitleChanged);
                 }
@@ -766,7 +1003,7 @@ impl Terminal {
 
                 self.last_content.terminal_bounds = new_bounds;
 
-                self.pty_tx.0.send(Msg::Resize(new_bounds.into())).ok();
+                self.pty_tx.send(Msg::Resize(new_bounds.into()));
 
                 term.resize(new_bounds);
             }
@@ -886,6 +1123,50 @@ impl Terminal {
                 term.scroll_to_point(*point);
                 self.refresh_hovered_word(window);
             }
+            InternalEvent::ScrollToPreviousPrompt | InternalEvent::ScrollToNextPrompt => {
+                let top_line = Line(-(term.grid().display_offset() as i32));
+                let step = if matches!(event, InternalEvent::ScrollToPreviousPrompt) {
+                    -1
+                } else {
+                    1
+                };
+                let mut line = Line(top_line.0 + step);
+                while line >= term.topmost_line() && line <= term.bottommost_line() {
+                    if is_prompt_start(term, line) {
+                        // Bring the prompt to the top of the viewport, as far as scrolling allows.
+                        let display_offset = (-line.0).clamp(0, term.history_size() as i32);
+                        term.scroll_display(AlacScroll::Delta(display_offset + top_line.0));
+                        self.refresh_hovered_word(window);
+                        break;
+                    }
+                    line = Line(line.0 + step);
+                }
+            }
+            InternalEvent::SelectCommandOutput { copy } => {
+                let command_id = if term.grid().display_offset() == 0 {
+                    self.shell_integration.last_finished_command()
+                } else {
+                    let top_line = Line(-(term.grid().display_offset() as i32));
+                    command_at_or_above(term, top_line)
+                };
+                let Some(range) = command_id.and_then(|id| command_output_range(term, id)) else {
+                    return;
+                };
+                if *copy {
+                    let text = term.bounds_to_string(*range.start(), *range.end());
+                    cx.write_to_clipboard(ClipboardItem::new_string(text));
+                } else {
+                    term.selection = Some(make_selection(&range));
+
+                    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+                    if let Some(selection_text) = term.selection_to_string() {
+                        cx.write_to_primary(ClipboardItem::new_string(selection_text));
+                    }
+
+                    self.selection_head = Some(*range.end());
+                    cx.emit(Event::SelectionsChanged)
+                }
+            }
             InternalEvent::ToggleViMode => {
                 self.vi_mode_enabled = !self.vi_mode_enabled;
                 term.toggle_vi_mode();
@@ -1081,6 +1362,26 @@ impl Terminal {
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
     }
 
+    pub fn scroll_to_previous_prompt(&mut self) {
+        self.events.push_back(InternalEvent::ScrollToPreviousPrompt);
+    }
+
+    pub fn scroll_to_next_prompt(&mut self) {
+        self.events.push_back(InternalEvent::ScrollToNextPrompt);
+    }
+
+    /// Selects the output of the last command, or of the command at the top of the viewport
+    /// when scrolled up. Needs a shell reporting its prompts with OSC 133.
+    pub fn select_command_output(&mut self) {
+        self.events
+            .push_back(InternalEvent::SelectCommandOutput { copy: false });
+    }
+
+    pub fn copy_command_output(&mut self) {
+        self.events
+            .push_back(InternalEvent::SelectCommandOutput { copy: true });
+    }
+
     pub fn scrolled_to_top(&self) -> bool {
         self.last_content.scrolled_to_top
     }
@@ -1109,6 +1410,99 @@ impl Terminal {
         self.write_to_pty(input);
     }
 
+    /// Writes input meant for a shell this terminal started, such as the activation of a
+    /// virtual environment, which a reattached shell already got.
+    pub fn input_to_new_shell(&mut self, input: impl Into<Cow<'static, [u8]>>) {
+        #[cfg(unix)]
+        if let PtySender::Attaching {
+            new_shell_input, ..
+        } = &mut self.pty_tx
+        {
+            new_shell_input.push(input.into());
+            return;
+        }
+        if !self.is_reattached() {
+            self.input(input);
+        }
+    }
+
+    /// Starts the PTY of the persistent session the terminal attached to, or a regular PTY if
+    /// attaching failed.
+    #[cfg(unix)]
+    fn start_session_pty(
+        &mut self,
+        session: Result<persistent_session::AttachedSession>,
+        attaching: AttachingSession,
+        cx: &mut Context<Self>,
+    ) {
+        let attached = session
+            .and_then(|session| {
+                let pty = ScannedPty::new(
+                    session.pty,
+                    self.shell_integration.clone(),
+                    self.images.clone(),
+                    self.task_problems.clone(),
+                    attaching.window_size,
+                );
+                let listener = ZedListener(attaching.events_tx.clone());
+                let pty_tx = spawn_event_loop(self.term.clone(), listener, pty, true)?;
+                let pty_info = PtyProcessInfo::for_session(session.pid);
+                Ok((pty_tx, pty_info, session.reattached))
+            })
+            .log_err();
+        let started = match attached {
+            Some(attached) => Ok(attached),
+            None => {
+                self.persistent_session = None;
+                self.start_pty(&attaching)
+                    .map(|(pty_tx, pty_info)| (pty_tx, pty_info, false))
+            }
+        };
+
+        match started {
+            Ok((pty_tx, pty_info, reattached)) => {
+                self.pty_info = pty_info;
+                if let Some(session) = self.persistent_session.as_mut() {
+                    session.reattached = reattached;
+                }
+                self.pty_tx.start(pty_tx, !reattached);
+            }
+            Err(error) => {
+                log::error!("{error:#}");
+                let message = format!("Failed to start the terminal: {error:#}");
+                // SAFETY: the terminal has no PTY.
+                unsafe { append_text_to_term(&mut self.term.lock(), &[&message]) };
+            }
+        }
+        cx.notify();
+    }
+
+    #[cfg(unix)]
+    fn start_pty(&self, attaching: &AttachingSession) -> Result<(Notifier, PtyProcessInfo)> {
+        let pty = tty::new(
+            &attaching.pty_options,
+            attaching.window_size,
+            attaching.window_id,
+        )
+        .context("starting the shell")?;
+        let pty_info = PtyProcessInfo::new(&pty);
+        let pty = ScannedPty::new(
+            pty,
+            self.shell_integration.clone(),
+            self.images.clone(),
+            self.task_problems.clone(),
+            attaching.window_size,
+        );
+        let listener = ZedListener(attaching.events_tx.clone());
+        let pty_tx = spawn_event_loop(
+            self.term.clone(),
+            listener,
+            pty,
+            attaching.pty_options.drain_on_exit,
+        )?;
+        Ok((pty_tx, pty_info))
+    }
+
     pub fn toggle_vi_mode(&mut self) {
         self.events.push_back(InternalEvent::ToggleViMode);
     }
@@ -1262,10 +1656,20 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, window, cx)
         }
 
-        self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content = Self::make_content(
+            &terminal,
+            &self.last_content,
+            &self.shell_integration,
+            &self.images,
+        );
     }
 
-    fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
+    fn make_content(
+        term: &Term<ZedListener>,
+        last_content: &TerminalContent,
+        shell_integration: &ShellIntegration,
+        images: &TerminalImages,
+    ) -> TerminalContent {
         let content = term.renderable_content();
         TerminalContent {
             cells: content
@@ -1292,6 +1696,8 @@ impl Terminal {
             last_hovered_word: last_content.last_hovered_word.clone(),
             scrolled_to_top: content.display_offset == term.history_size(),
             scrolled_to_bottom: content.display_offset == 0,
+            command_marks: command_marks(term, shell_integration),
+            images: visible_images(term, images),
         }
     }
 
@@ -1302,6 +1708,18 @@ impl Terminal {
         term.bounds_to_string(start, end)
     }
 
+    /// The output of the last command that finished, if the shell reports its commands with
+    /// OSC 133 sequences.
+    pub fn last_command_output(&self) -> Option<String> {
+        let command_id = self.shell_integration.last_finished_command()?;
+        let term = self.term.lock_unfair();
+        Some(
+            command_output_range(&term, command_id)
+                .map(|range| term.bounds_to_string(*range.start(), *range.end()))
+                .unwrap_or_default(),
+        )
+    }
+
     pub fn last_n_non_empty_lines(&self, n: usize) -> Vec<String> {
         let term = self.term.clone();
         let terminal = term.lock_unfair();
@@ -1566,7 +1984,10 @@ impl Terminal {
             if self.selection_phase == SelectionPhase::Ended {
                 let mouse_cell_index =
                     content_index_for_mouse(position, &self.last_content.terminal_bounds);
-                if let Some(link) = self.last_content.cells[mouse_cell_index].hyperlink() {
+                if let Some(link) = self.last_content.cells[mouse_cell_index]
+                    .hyperlink()
+                    .filter(|link| !is_internal_hyperlink(link))
+                {
                     cx.open_url(link.uri());
                 } else if e.modifiers.secondary() {
                     self.events
@@ -1734,6 +2155,25 @@ impl Terminal {
         self.task.as_ref()
     }
 
+    /// Problems the task's problem matchers found in its output so far.
+    pub fn task_problems(&self) -> Vec<Problem> {
+        self.task_problems.problems()
+    }
+
+    /// The id of the persistent session hosting the shell, for reattaching to it later.
+    pub fn persistent_session_id(&self) -> Option<&str> {
+        self.persistent_session
+            .as_ref()
+            .map(|session| session.id.as_str())
+    }
+
+    /// Whether the terminal reattached to a shell left running by a previous Zed session.
+    pub fn is_reattached(&self) -> bool {
+        self.persistent_session
+            .as_ref()
+            .is_some_and(|session| session.reattached)
+    }
+
     pub fn wait_for_completed_task(&self, cx: &App) -> Task<Option<ExitStatus>> {
         if let Some(task) = self.task() {
             if task.status == TaskStatus::Running {
@@ -1759,6 +2199,10 @@ impl Terminal {
         });
 
         self.completion_tx.try_send(e).ok();
+        self.task_problems.finish();
+        if self.task_problems.take_changed() {
+            cx.emit(Event::TaskProblemsChanged);
+        }
         let task = match &mut self.task {
             Some(task) => task,
             None => {
@@ -1886,12 +2330,157 @@ unsafe fn append_text_to_term(term: &mut Term<ZedListener>, text_lines: &[&str])
 
 impl Drop for Terminal {
     fn drop(&mut self) {
-        self.pty_tx.0.send(Msg::Shutdown).ok();
+        self.pty_tx.send(Msg::Shutdown);
+        // Closing the terminal ends its session, unless it is closed by Zed quitting.
+        if let Some(session) = &self.persistent_session {
+            if !session.keep_alive {
+                end_persistent_session(&session.id);
+            }
+        }
     }
 }
 
+fn spawn_event_loop<P>(
+    term: Arc<FairMutex<Term<ZedListener>>>,
+    listener: ZedListener,
+    pty: P,
+    drain_on_exit: bool,
+) -> Result<Notifier>
+where
+    P: EventedPty + OnResize + Send + 'static,
+{
+    let event_loop = EventLoop::new(term, listener, pty, drain_on_exit, false)?;
+
+    //Kick things off
+    let pty_tx = event_loop.channel();
+    let _io_thread = event_loop.spawn(); // DANGER
+    Ok(Notifier(pty_tx))
+}
+
+/// Ends a persistent session that is not going to be reattached to.
+pub fn end_persistent_session(session_id: &str) {
+    #[cfg(unix)]
+    persistent_session::end_session(session_id);
+    #[cfg(not(unix))]
+    let _ = session_id;
+}
+
 impl EventEmitter<Event> for Terminal {}
 
+/// Whether the hyperlink is one of the tags added to the PTY output, rather than a link
+/// printed by a program.
+pub fn is_internal_hyperlink(hyperlink: &Hyperlink) -> bool {
+    is_shell_zone(hyperlink) || is_image_anchor(hyperlink)
+}
+
+fn visible_images<T>(term: &Term<T>, images: &TerminalImages) -> Vec<IndexedImage> {
+    if images.is_empty() {
+        return Vec::new();
+    }
+    let top_line = -(term.grid().display_offset() as i32);
+    // Images anchored above the viewport can still reach into it.
+    let first_line = (top_line + 1 - images.max_lines() as i32).max(term.topmost_line().0);
+    let mut visible_images = Vec::new();
+    for line in (first_line..top_line + term.screen_lines() as i32).map(Line) {
+        for column in (0..term.columns()).map(Column) {
+            let Some(image_id) = term.grid()[line][column]
+                .hyperlink()
+                .and_then(|hyperlink| image_id(&hyperlink))
+            else {
+                continue;
+            };
+            let Some(image) = images.get(image_id) else {
+                continue;
+            };
+            if line.0 + image.lines as i32 > top_line {
+                visible_images.push(IndexedImage {
+                    point: AlacPoint::new(line, column),
+                    image,
+                });
+            }
+        }
+    }
+    visible_images
+}
+
+fn line_zone<T>(term: &Term<T>, line: Line, kind: ShellZoneKind) -> Option<u64> {
+    if line < term.topmost_line() || line > term.bottommost_line() {
+        return None;
+    }
+    term.grid()[line][..Column(term.columns())]
+        .iter()
+        .filter_map(|cell| ShellZone::from_hyperlink(&cell.hyperlink()?))
+        .find(|zone| zone.kind == kind)
+        .map(|zone| zone.command_id)
+}
+
+fn is_prompt_start<T>(term: &Term<T>, line: Line) -> bool {
+    let prompt = line_zone(term, line, ShellZoneKind::Prompt);
+    prompt.is_some() && prompt != line_zone(term, Line(line.0 - 1), ShellZoneKind::Prompt)
+}
+
+fn command_marks<T>(term: &Term<T>, shell_integration: &ShellIntegration) -> Vec<CommandMark> {
+    if !shell_integration.is_active() {
+        return Vec::new();
+    }
+    let top_line = -(term.grid().display_offset() as i32);
+    (top_line..top_line + term.screen_lines() as i32)
+        .map(Line)
+        .filter(|&line| is_prompt_start(term, line))
+        .filter_map(|line| {
+            let command_id = line_zone(term, line, ShellZoneKind::Prompt)?;
+            Some(CommandMark {
+                line,
+                status: shell_integration.command_status(command_id)?,
+            })
+        })
+        .collect()
+}
+
+/// The command whose prompt, command line or output is the closest at or above the line.
+fn command_at_or_above<T>(term: &Term<T>, mut line: Line) -> Option<u64> {
+    while line >= term.topmost_line() {
+        let command_id = term.grid()[line][..Column(term.columns())]
+            .iter()
+            .find_map(|cell| ShellZone::from_hyperlink(&cell.hyperlink()?))
+            .map(|zone| zone.command_id);
+        if command_id.is_some() {
+            return command_id;
+        }
+        line = Line(line.0 - 1);
+    }
+    None
+}
+
+fn command_output_range<T>(term: &Term<T>, command_id: u64) -> Option<RangeInclusive<AlacPoint>> {
+    let is_output = |cell: &Cell| {
+        cell.hyperlink()
+            .and_then(|hyperlink| ShellZone::from_hyperlink(&hyperlink))
+            == Some(ShellZone {
+                kind: ShellZoneKind::Output,
+                command_id,
+            })
+    };
+
+    let mut range: Option<RangeInclusive<AlacPoint>> = None;
+    let mut line = term.bottommost_line();
+    while line >= term.topmost_line() {
+        let row = &term.grid()[line];
+        let columns = (0..term.columns()).map(Column);
+        let first = columns.clone().find(|&column| is_output(&row[column]));
+        let last = columns.rev().find(|&column| is_output(&row[column]));
+        if let Some((first, last)) = first.zip(last) {
+            let end = range.map_or(AlacPoint::new(line, last), |range| *range.end());
+            range = Some(AlacPoint::new(line, first)..=end);
+        } else if line_zone(term, line, ShellZoneKind::Prompt) == Some(command_id) {
+            // The output always follows the command's prompt.
+            break;
+        }
+        line = Line(line.0 - 1);
+    }
+    range
+}
+
 fn make_selection(range: &RangeInclusive<AlacPoint>) -> Selection {
     let mut selection = Selection::new(SelectionType::Simple, *range.start(), AlacDirection::Left);
     selection.update(*range.end(), AlacDirection::Right);
@@ -0,0 +1,1059 @@
+//! Inline images, sent with the Kitty graphics protocol or as Sixel data.
+//!
+//! Alacritty drops these sequences, so they are decoded from the PTY output before it reaches the
+//! terminal. Each image is then anchored to the grid cell it was placed at, with an OSC 8
+//! hyperlink carrying a `zed-image:` URI, and is drawn over the cells below and to the right of
+//! that anchor. Only the image's size is read from the PTY output as it goes by: the image itself
+//! is decoded on a thread of its own, and shows up once it's ready.
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    io::{Cursor, Read as _},
+    iter::Peekable,
+    sync::{Arc, mpsc},
+    thread,
+};
+
+use alacritty_terminal::term::cell::Hyperlink;
+use anyhow::{Context as _, Result, anyhow, bail};
+use base64::Engine as _;
+use flate2::read::ZlibDecoder;
+use gpui::RenderImage;
+use image::{DynamicImage, Frame, ImageDecoder as _, RgbaImage, codecs::png::PngDecoder};
+use parking_lot::Mutex;
+
+const IMAGE_URI_PREFIX: &str = "zed-image:";
+/// Graphics sequences longer than this are dropped.
+const MAX_SEQUENCE_LEN: usize = 64 * 1024 * 1024;
+const MAX_IMAGE_SIZE: u32 = 4096;
+/// Decompressed Kitty payloads longer than this, the size of the largest RGBA image, are dropped.
+const MAX_DECOMPRESSED_LEN: u64 = MAX_IMAGE_SIZE as u64 * MAX_IMAGE_SIZE as u64 * 4;
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+/// The signature, then the length, type, width and height of the IHDR chunk.
+const PNG_HEADER_LEN: usize = 24;
+/// How many bytes of decoded images a terminal keeps before dropping the oldest ones.
+const MAX_IMAGES_BYTES: usize = 256 * 1024 * 1024;
+const MAX_TRANSMITTED_IMAGES: usize = 64;
+
+const ESC: u8 = 0x1b;
+
+pub fn is_image_anchor(hyperlink: &Hyperlink) -> bool {
+    is_image_uri(hyperlink.uri())
+}
+
+pub(crate) fn is_image_uri(uri: &str) -> bool {
+    uri.starts_with(IMAGE_URI_PREFIX)
+}
+
+pub(crate) fn image_id(hyperlink: &Hyperlink) -> Option<u64> {
+    hyperlink.uri().strip_prefix(IMAGE_URI_PREFIX)?.parse().ok()
+}
+
+pub(crate) fn image_uri(image_id: u64) -> String {
+    format!("{IMAGE_URI_PREFIX}{image_id}")
+}
+
+/// An image shown in the terminal, covering a block of cells.
+#[derive(Clone)]
+pub struct TerminalImage {
+    pub image: Arc<RenderImage>,
+    pub columns: usize,
+    pub lines: usize,
+    kitty_image_id: Option<u32>,
+}
+
+/// The images shown in a terminal, shared between the terminal and its PTY reader.
+#[derive(Clone, Default)]
+pub(crate) struct TerminalImages(Arc<Mutex<TerminalImagesState>>);
+
+#[derive(Default)]
+struct TerminalImagesState {
+    next_id: u64,
+    images: BTreeMap<u64, TerminalImage>,
+    /// The Kitty image ids of the placed images that are still decoding.
+    decoding: HashMap<u64, Option<u32>>,
+    total_bytes: usize,
+    on_decoded: Option<Arc<dyn Fn() + Send + Sync>>,
+}
+
+impl TerminalImages {
+    /// Creates the images of a terminal, calling `on_decoded` whenever one is ready to be shown.
+    pub(crate) fn new(on_decoded: impl Fn() + Send + Sync + 'static) -> Self {
+        Self(Arc::new(Mutex::new(TerminalImagesState {
+            on_decoded: Some(Arc::new(on_decoded)),
+            ..Default::default()
+        })))
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.lock().images.is_empty()
+    }
+
+    pub(crate) fn get(&self, image_id: u64) -> Option<TerminalImage> {
+        self.0.lock().images.get(&image_id).cloned()
+    }
+
+    /// The height of the tallest image, which bounds how far above the viewport an image
+    /// covering it can be anchored.
+    pub(crate) fn max_lines(&self) -> usize {
+        let state = self.0.lock();
+        state
+            .images
+            .values()
+            .map(|image| image.lines)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Allocates the id of an image that is about to be decoded.
+    fn reserve(&self, kitty_image_id: Option<u32>) -> u64 {
+        let mut state = self.0.lock();
+        let image_id = state.next_id;
+        state.next_id += 1;
+        state.decoding.insert(image_id, kitty_image_id);
+        image_id
+    }
+
+    /// Shows a decoded image, unless it was deleted while it was decoding.
+    fn insert(&self, image_id: u64, image: Arc<RgbaImage>, columns: usize, lines: usize) {
+        // gpui expects BGRA pixels.
+        let mut data = Arc::unwrap_or_clone(image);
+        for pixel in data.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+        let image = Arc::new(RenderImage::new(vec![Frame::new(data)]));
+
+        let mut state = self.0.lock();
+        let Some(kitty_image_id) = state.decoding.remove(&image_id) else {
+            return;
+        };
+        let image = TerminalImage {
+            image,
+            columns,
+            lines,
+            kitty_image_id,
+        };
+        state.total_bytes += image_bytes(&image);
+        state.images.insert(image_id, image);
+        while state.total_bytes > MAX_IMAGES_BYTES {
+            let Some((_, image)) = state.images.pop_first() else {
+                break;
+            };
+            state.total_bytes -= image_bytes(&image);
+        }
+        let on_decoded = state.on_decoded.clone();
+        drop(state);
+        if let Some(on_decoded) = on_decoded {
+            on_decoded();
+        }
+    }
+
+    fn forget(&self, image_id: u64) {
+        self.0.lock().decoding.remove(&image_id);
+    }
+
+    /// Removes the images, including those still decoding, whose Kitty image id matches.
+    fn remove(&self, mut predicate: impl FnMut(Option<u32>) -> bool) {
+        let mut state = self.0.lock();
+        let mut removed_bytes = 0;
+        state.images.retain(|_, image| {
+            let remove = predicate(image.kitty_image_id);
+            if remove {
+                removed_bytes += image_bytes(image);
+            }
+            !remove
+        });
+        state
+            .decoding
+            .retain(|_, kitty_image_id| !predicate(*kitty_image_id));
+        state.total_bytes -= removed_bytes;
+    }
+}
+
+/// Runs the decoding of images, in order, on a thread of its own that is started with the
+/// first image.
+#[derive(Default)]
+struct DecoderThread {
+    jobs: Option<mpsc::Sender<Box<dyn FnOnce() + Send>>>,
+}
+
+impl DecoderThread {
+    fn spawn(&mut self, job: impl FnOnce() + Send + 'static) {
+        let jobs = self.jobs.get_or_insert_with(|| {
+            let (jobs_tx, jobs_rx) = mpsc::channel::<Box<dyn FnOnce() + Send>>();
+            let spawned = thread::Builder::new()
+                .name("terminal image decoder".to_string())
+                .spawn(move || {
+                    for job in jobs_rx {
+                        job();
+                    }
+                });
+            if let Err(error) = spawned {
+                log::error!("failed to start decoding terminal images: {error}");
+            }
+            jobs_tx
+        });
+        if jobs.send(Box::new(job)).is_err() {
+            log::warn!("dropping a terminal image, as images can't be decoded");
+        }
+    }
+}
+
+/// An image transmitted with the Kitty protocol, to be placed later on.
+#[derive(Clone)]
+struct KittyImage {
+    width: u32,
+    height: u32,
+    /// Set once the image is decoded. Placements decode after it, so they find it set unless
+    /// decoding failed.
+    decoded: Arc<Mutex<Option<Arc<RgbaImage>>>>,
+}
+
+fn image_bytes(image: &TerminalImage) -> usize {
+    image.image.as_bytes(0).map_or(0, |bytes| bytes.len())
+}
+
+/// Where the cursor goes once an image is placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ImageCursor {
+    /// At the start of the line below the image, as Sixel does.
+    NextLine,
+    /// Right of the image, on its last line, as the Kitty protocol does by default.
+    AfterImage,
+    /// Where the image was placed.
+    Unchanged,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ImagePlacement {
+    pub image_id: u64,
+    pub columns: usize,
+    pub lines: usize,
+    pub cursor: ImageCursor,
+}
+
+enum State {
+    Ground,
+    Escape,
+    /// An APC (`ESC _`) or DCS (`ESC P`) string, until `ESC \`.
+    String {
+        kind: u8,
+        data: Vec<u8>,
+        escape: bool,
+    },
+}
+
+impl State {
+    fn after_escape(byte: u8) -> Self {
+        match byte {
+            b'_' | b'P' => State::String {
+                kind: byte,
+                data: Vec::new(),
+                escape: false,
+            },
+            ESC => State::Escape,
+            _ => State::Ground,
+        }
+    }
+}
+
+/// Picks the Kitty graphics (APC) and Sixel (DCS) sequences out of the PTY output.
+pub(crate) struct GraphicsScanner {
+    state: State,
+    images: TerminalImages,
+    cell_width: u32,
+    cell_height: u32,
+    kitty_chunks: Option<(KittyCommand, Vec<u8>)>,
+    kitty_images: HashMap<u32, KittyImage>,
+    kitty_image_order: VecDeque<u32>,
+    decoder: DecoderThread,
+}
+
+impl GraphicsScanner {
+    pub(crate) fn new(images: TerminalImages) -> Self {
+        Self {
+            state: State::Ground,
+            images,
+            cell_width: 0,
+            cell_height: 0,
+            kitty_chunks: None,
+            kitty_images: HashMap::default(),
+            kitty_image_order: VecDeque::new(),
+            decoder: DecoderThread::default(),
+        }
+    }
+
+    pub(crate) fn set_cell_size(&mut self, cell_width: u16, cell_height: u16) {
+        self.cell_width = cell_width.into();
+        self.cell_height = cell_height.into();
+    }
+
+    /// Scans the output, returning an image to place if a sequence ended in it.
+    /// Replies to the program are appended to `replies`.
+    pub(crate) fn advance(
+        &mut self,
+        bytes: &[u8],
+        replies: &mut Vec<u8>,
+    ) -> Option<ImagePlacement> {
+        let mut placement = None;
+        for &byte in bytes {
+            match &mut self.state {
+                State::Ground => {
+                    if byte == ESC {
+                        self.state = State::Escape;
+                    }
+                }
+                State::Escape => self.state = State::after_escape(byte),
+                State::String { kind, data, escape } => {
+                    if *escape {
+                        if byte == b'\\' {
+                            let kind = *kind;
+                            let data = std::mem::take(data);
+                            self.state = State::Ground;
+                            placement = self.dispatch(kind, &data, replies).or(placement);
+                        } else {
+                            // Any other ESC aborts the string and starts a new sequence.
+                            self.state = State::after_escape(byte);
+                        }
+                    } else if byte == ESC {
+                        *escape = true;
+                    } else if data.len() < MAX_SEQUENCE_LEN {
+                        data.push(byte);
+                    } else {
+                        log::warn!(
+                            "dropping a terminal graphics sequence of over {MAX_SEQUENCE_LEN} bytes"
+                        );
+                        self.state = State::Ground;
+                    }
+                }
+            }
+        }
+        placement
+    }
+
+    fn dispatch(&mut self, kind: u8, data: &[u8], replies: &mut Vec<u8>) -> Option<ImagePlacement> {
+        match kind {
+            b'_' => {
+                let data = data.strip_prefix(b"G")?;
+                self.kitty_command(data, replies)
+            }
+            _ => {
+                // Sixel data follows the DCS parameters and the `q` final byte.
+                let start = data
+                    .iter()
+                    .position(|byte| !matches!(byte, b'0'..=b'9' | b';'))?;
+                if data[start] != b'q' {
+                    return None;
+                }
+                let data = data[start + 1..].to_vec();
+                let (width, height) = sixel_size(&data);
+                if width == 0 || height == 0 {
+                    log::warn!("dropping an empty sixel image");
+                    return None;
+                }
+                let decode = move || {
+                    decode_sixel(&data)
+                        .map(Arc::new)
+                        .context("decoding sixel image")
+                };
+                Some(self.place(
+                    (width, height),
+                    None,
+                    None,
+                    None,
+                    ImageCursor::NextLine,
+                    decode,
+                ))
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn finish_decoding(&mut self) {
+        let (done_tx, done_rx) = mpsc::channel();
+        self.decoder.spawn(move || done_tx.send(()).unwrap());
+        done_rx.recv().unwrap();
+    }
+
+    fn kitty_command(&mut self, data: &[u8], replies: &mut Vec<u8>) -> Option<ImagePlacement> {
+        let (control, payload) = match data.iter().position(|&byte| byte == b';') {
+            Some(ix) => (&data[..ix], &data[ix + 1..]),
+            None => (data, &[][..]),
+        };
+        let command = KittyCommand::parse(control);
+
+        // Later chunks only carry `m` and `q`, the rest comes from the first one.
+        let (command, payload) = match self.kitty_chunks.take() {
+            Some((first, mut chunks)) => {
+                chunks.extend_from_slice(payload);
+                if command.more {
+                    self.kitty_chunks = Some((first, chunks));
+                    return None;
+                }
+                (first, chunks)
+            }
+            None if command.more => {
+                self.kitty_chunks = Some((command, payload.to_vec()));
+                return None;
+            }
+            None => (command, payload.to_vec()),
+        };
+
+        let result = self.kitty_action(&command, payload);
+        if command.image_id != 0 {
+            match &result {
+                Ok(_) if command.quiet == 0 && command.action != b'd' => {
+                    replies.extend_from_slice(
+                        format!("\x1b_Gi={};OK\x1b\\", command.image_id).as_bytes(),
+                    );
+                }
+                Err(error) if command.quiet < 2 => {
+                    replies.extend_from_slice(
+                        format!("\x1b_Gi={};EINVAL:{error}\x1b\\", command.image_id).as_bytes(),
+                    );
+                }
+                _ => {}
+            }
+        }
+        result
+            .map_err(|error| log::warn!("kitty graphics command failed: {error:#}"))
+            .ok()
+            .flatten()
+    }
+
+    /// Runs a Kitty command. Replies only tell whether the image's size could be read, as the
+    /// image is decoded later.
+    fn kitty_action(
+        &mut self,
+        command: &KittyCommand,
+        payload: Vec<u8>,
+    ) -> Result<Option<ImagePlacement>> {
+        match command.action {
+            b'q' => {
+                kitty_image_size(command, &payload)?;
+                Ok(None)
+            }
+            b't' | b'T' => {
+                let (width, height) = kitty_image_size(command, &payload)?;
+                let decoded = Arc::new(Mutex::new(None));
+                if command.image_id != 0 {
+                    self.store_kitty_image(
+                        command.image_id,
+                        KittyImage {
+                            width,
+                            height,
+                            decoded: decoded.clone(),
+                        },
+                    );
+                }
+                let decode = {
+                    let command = command.clone();
+                    move || {
+                        let image = Arc::new(decode_kitty_image(&command, &payload)?);
+                        *decoded.lock() = Some(image.clone());
+                        Ok(image)
+                    }
+                };
+                if command.action == b'T' {
+                    Ok(Some(self.place_kitty_image(
+                        command,
+                        (width, height),
+                        decode,
+                    )))
+                } else {
+                    self.decoder.spawn(move || {
+                        if let Err(error) = decode() {
+                            log::warn!("decoding kitty image: {error:#}");
+                        }
+                    });
+                    Ok(None)
+                }
+            }
+            b'p' => {
+                let image = self
+                    .kitty_images
+                    .get(&command.image_id)
+                    .cloned()
+                    .with_context(|| format!("no image with id {}", command.image_id))?;
+                let decode = move || {
+                    image
+                        .decoded
+                        .lock()
+                        .clone()
+                        .context("the image could not be decoded")
+                };
+                Ok(Some(self.place_kitty_image(
+                    command,
+                    (image.width, image.height),
+                    decode,
+                )))
+            }
+            b'd' => {
+                match command.delete {
+                    b'i' | b'I' => {
+                        let image_id = command.image_id;
+                        self.images
+                            .remove(|kitty_image_id| kitty_image_id == Some(image_id));
+                        if command.delete == b'I' {
+                            self.kitty_images.remove(&image_id);
+                        }
+                    }
+                    b'a' | b'A' => {
+                        self.images
+                            .remove(|kitty_image_id| kitty_image_id.is_some());
+                        if command.delete == b'A' {
+                            self.kitty_images.clear();
+                            self.kitty_image_order.clear();
+                        }
+                    }
+                    delete => bail!("unsupported deletion {:?}", delete as char),
+                }
+                Ok(None)
+            }
+            action => Err(anyhow!("unsupported action {:?}", action as char)),
+        }
+    }
+
+    fn store_kitty_image(&mut self, image_id: u32, image: KittyImage) {
+        if self.kitty_images.insert(image_id, image).is_none() {
+            self.kitty_image_order.push_back(image_id);
+        }
+        while self.kitty_image_order.len() > MAX_TRANSMITTED_IMAGES {
+            if let Some(image_id) = self.kitty_image_order.pop_front() {
+                self.kitty_images.remove(&image_id);
+            }
+        }
+    }
+
+    fn place_kitty_image(
+        &mut self,
+        command: &KittyCommand,
+        size: (u32, u32),
+        decode: impl FnOnce() -> Result<Arc<RgbaImage>> + Send + 'static,
+    ) -> ImagePlacement {
+        let cursor = if command.cursor_unchanged {
+            ImageCursor::Unchanged
+        } else {
+            ImageCursor::AfterImage
+        };
+        self.place(
+            size,
+            Some(command.image_id).filter(|&image_id| image_id != 0),
+            Some(command.columns as usize).filter(|&columns| columns != 0),
+            Some(command.rows as usize).filter(|&rows| rows != 0),
+            cursor,
+            decode,
+        )
+    }
+
+    /// Places an image of the given size, which `decode` then produces on the decoder thread.
+    fn place(
+        &mut self,
+        (width, height): (u32, u32),
+        kitty_image_id: Option<u32>,
+        columns: Option<usize>,
+        lines: Option<usize>,
+        cursor: ImageCursor,
+        decode: impl FnOnce() -> Result<Arc<RgbaImage>> + Send + 'static,
+    ) -> ImagePlacement {
+        let cell_width = self.cell_width.max(1);
+        let cell_height = self.cell_height.max(1);
+        let columns = columns
+            .unwrap_or_else(|| width.div_ceil(cell_width) as usize)
+            .max(1);
+        let lines = lines
+            .unwrap_or_else(|| height.div_ceil(cell_height) as usize)
+            .max(1);
+
+        let image_id = self.images.reserve(kitty_image_id);
+        let images = self.images.clone();
+        self.decoder.spawn(move || match decode() {
+            Ok(image) => images.insert(image_id, image, columns, lines),
+            Err(error) => {
+                log::warn!("{error:#}");
+                images.forget(image_id);
+            }
+        });
+
+        ImagePlacement {
+            image_id,
+            columns,
+            lines,
+            cursor,
+        }
+    }
+}
+
+/// The control data of a Kitty graphics command: `key=value` pairs, separated by commas.
+#[derive(Clone)]
+struct KittyCommand {
+    action: u8,
+    format: u32,
+    medium: u8,
+    width: u32,
+    height: u32,
+    compressed: bool,
+    more: bool,
+    image_id: u32,
+    quiet: u32,
+    columns: u32,
+    rows: u32,
+    cursor_unchanged: bool,
+    delete: u8,
+}
+
+impl KittyCommand {
+    fn parse(control: &[u8]) -> Self {
+        let mut command = Self {
+            action: b't',
+            format: 32,
+            medium: b'd',
+            width: 0,
+            height: 0,
+            compressed: false,
+            more: false,
+            image_id: 0,
+            quiet: 0,
+            columns: 0,
+            rows: 0,
+            cursor_unchanged: false,
+            delete: b'a',
+        };
+        for pair in control.split(|&byte| byte == b',') {
+            let [key, b'=', value @ ..] = pair else {
+                continue;
+            };
+            let number = || {
+                std::str::from_utf8(value)
+                    .ok()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(0)
+            };
+            let letter = value.first().copied().unwrap_or(0);
+            match key {
+                b'a' => command.action = letter,
+                b'f' => command.format = number(),
+                b't' => command.medium = letter,
+                b's' => command.width = number(),
+                b'v' => command.height = number(),
+                b'o' => command.compressed = letter == b'z',
+                b'm' => command.more = number() == 1,
+                b'i' => command.image_id = number(),
+                b'q' => command.quiet = number(),
+                b'c' => command.columns = number(),
+                b'r' => command.rows = number(),
+                b'C' => command.cursor_unchanged = number() == 1,
+                b'd' => command.delete = letter,
+                _ => {}
+            }
+        }
+        command
+    }
+}
+
+/// Reads the size of the image a Kitty command transmits, without decoding it.
+fn kitty_image_size(command: &KittyCommand, payload: &[u8]) -> Result<(u32, u32)> {
+    if command.medium != b'd' {
+        bail!("only direct transmission is supported");
+    }
+    let (width, height) = match command.format {
+        100 => {
+            // Enough base64 for the PNG header, even once compressed.
+            let head = &payload[..payload.len().min(1024)];
+            let head = base64::engine::general_purpose::STANDARD
+                .decode(&head[..head.len() - head.len() % 4])
+                .context("decoding base64 payload")?;
+            let mut header = Vec::new();
+            if command.compressed {
+                ZlibDecoder::new(head.as_slice())
+                    .take(PNG_HEADER_LEN as u64)
+                    .read_to_end(&mut header)
+                    .context("decompressing payload")?;
+            } else {
+                header = head;
+            }
+            png_size(&header)?
+        }
+        24 | 32 => (command.width, command.height),
+        format => bail!("unsupported format {format}"),
+    };
+    check_image_size(width, height)?;
+    Ok((width, height))
+}
+
+/// Reads the size of a PNG image from its IHDR chunk, which comes first.
+fn png_size(data: &[u8]) -> Result<(u32, u32)> {
+    let header = data.get(..PNG_HEADER_LEN).context("truncated PNG header")?;
+    if !header.starts_with(PNG_SIGNATURE) || &header[12..16] != b"IHDR" {
+        bail!("not a PNG image");
+    }
+    let width = u32::from_be_bytes(header[16..20].try_into()?);
+    let height = u32::from_be_bytes(header[20..24].try_into()?);
+    Ok((width, height))
+}
+
+fn check_image_size(width: u32, height: u32) -> Result<()> {
+    if width == 0 || height == 0 || width > MAX_IMAGE_SIZE || height > MAX_IMAGE_SIZE {
+        bail!("invalid image size {width}x{height}");
+    }
+    Ok(())
+}
+
+fn decode_kitty_image(command: &KittyCommand, payload: &[u8]) -> Result<RgbaImage> {
+    if command.medium != b'd' {
+        bail!("only direct transmission is supported");
+    }
+    let mut data = base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .context("decoding base64 payload")?;
+    if command.compressed {
+        let mut decompressed = Vec::new();
+        ZlibDecoder::new(data.as_slice())
+            .take(MAX_DECOMPRESSED_LEN + 1)
+            .read_to_end(&mut decompressed)
+            .context("decompressing payload")?;
+        if decompressed.len() as u64 > MAX_DECOMPRESSED_LEN {
+            bail!("decompressed payload is over {MAX_DECOMPRESSED_LEN} bytes");
+        }
+        data = decompressed;
+    }
+
+    let image = match command.format {
+        100 => {
+            let decoder = PngDecoder::new(Cursor::new(data)).context("reading PNG header")?;
+            let (width, height) = decoder.dimensions();
+            check_image_size(width, height)?;
+            DynamicImage::from_decoder(decoder)?.into_rgba8()
+        }
+        24 | 32 => {
+            let (width, height) = (command.width, command.height);
+            check_image_size(width, height)?;
+            let pixel_count = (width * height) as usize;
+            let pixels = if command.format == 32 {
+                data.get(..pixel_count * 4)
+                    .context("not enough pixel data")?
+                    .to_vec()
+            } else {
+                data.get(..pixel_count * 3)
+                    .context("not enough pixel data")?
+                    .chunks_exact(3)
+                    .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 0xff])
+                    .collect()
+            };
+            RgbaImage::from_raw(width, height, pixels).context("invalid pixel data")?
+        }
+        format => bail!("unsupported format {format}"),
+    };
+    Ok(image)
+}
+
+/// Decodes Sixel data: the bytes following the `q` of the DCS sequence.
+fn decode_sixel(data: &[u8]) -> Result<RgbaImage> {
+    let mut image = SixelImage::default();
+    parse_sixel(data, &mut image);
+    image.into_image().context("empty image")
+}
+
+/// Reads the size of a Sixel image, without painting its pixels.
+fn sixel_size(data: &[u8]) -> (u32, u32) {
+    let mut image = SixelImage {
+        size_only: true,
+        ..Default::default()
+    };
+    parse_sixel(data, &mut image);
+    (image.width, image.height)
+}
+
+fn parse_sixel(data: &[u8], image: &mut SixelImage) {
+    let mut palette = SIXEL_PALETTE
+        .iter()
+        .map(|&(r, g, b)| percent_rgb(r, g, b))
+        .chain(std::iter::repeat([0, 0, 0, 0xff]))
+        .take(256)
+        .collect::<Vec<_>>();
+    let mut color = 0;
+    let (mut x, mut y) = (0u32, 0u32);
+
+    let mut bytes = data.iter().copied().peekable();
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'"' => {
+                let numbers = sixel_numbers(&mut bytes);
+                if let [_, _, width, height] = numbers[..] {
+                    image.resize(width.min(MAX_IMAGE_SIZE), height.min(MAX_IMAGE_SIZE));
+                }
+            }
+            b'#' => {
+                let numbers = sixel_numbers(&mut bytes);
+                let register = numbers[0] as usize % palette.len();
+                match numbers[..] {
+                    [_, 1, hue, lightness, saturation] => {
+                        palette[register] = hls_rgb(hue, lightness, saturation)
+                    }
+                    [_, 2, r, g, b] => palette[register] = percent_rgb(r, g, b),
+                    _ => {}
+                }
+                color = register;
+            }
+            b'!' => {
+                let count = sixel_numbers(&mut bytes)[0].min(MAX_IMAGE_SIZE);
+                if let Some(sixel @ b'?'..=b'~') = bytes.next() {
+                    image.put(x, y, count, sixel - b'?', palette[color]);
+                    x = x.saturating_add(count);
+                }
+            }
+            b'$' => x = 0,
+            b'-' => {
+                x = 0;
+                y = y.saturating_add(6);
+            }
+            b'?'..=b'~' => {
+                image.put(x, y, 1, byte - b'?', palette[color]);
+                x = x.saturating_add(1);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Reads the `;` separated numbers of a Sixel command.
+fn sixel_numbers(bytes: &mut Peekable<impl Iterator<Item = u8>>) -> Vec<u32> {
+    let mut numbers = vec![0u32];
+    while let Some(&byte) = bytes.peek() {
+        match byte {
+            b'0'..=b'9' => {
+                let number = numbers.last_mut().unwrap();
+                *number = number
+                    .saturating_mul(10)
+                    .saturating_add((byte - b'0') as u32);
+            }
+            b';' => numbers.push(0),
+            _ => break,
+        }
+        bytes.next();
+    }
+    numbers
+}
+
+/// A Sixel image being decoded, whose buffer grows ahead of its size when the size is not
+/// declared upfront.
+#[derive(Default)]
+struct SixelImage {
+    width: u32,
+    height: u32,
+    buffer_width: u32,
+    buffer_height: u32,
+    pixels: Vec<u8>,
+    /// Whether only the size is tracked, without any pixels.
+    size_only: bool,
+}
+
+impl SixelImage {
+    fn resize(&mut self, width: u32, height: u32) {
+        self.reserve(width, height);
+        self.width = self.width.max(width);
+        self.height = self.height.max(height);
+    }
+
+    fn reserve(&mut self, width: u32, height: u32) {
+        if self.size_only || width <= self.buffer_width && height <= self.buffer_height {
+            return;
+        }
+        let grow = |needed: u32, current: u32| {
+            if needed <= current {
+                current
+            } else {
+                needed.max(current * 2).min(MAX_IMAGE_SIZE)
+            }
+        };
+        let buffer_width = grow(width, self.buffer_width);
+        let buffer_height = grow(height, self.buffer_height);
+        let mut pixels = vec![0; (buffer_width * buffer_height * 4) as usize];
+        let row_len = self.buffer_width as usize * 4;
+        for row in 0..self.buffer_height as usize {
+            let new_row = row * buffer_width as usize * 4;
+            pixels[new_row..new_row + row_len]
+                .copy_from_slice(&self.pixels[row * row_len..(row + 1) * row_len]);
+        }
+        self.buffer_width = buffer_width;
+        self.buffer_height = buffer_height;
+        self.pixels = pixels;
+    }
+
+    /// Paints a sixel, a column of six pixels, `count` times from `(x, y)`.
+    fn put(&mut self, x: u32, y: u32, count: u32, sixel: u8, color: [u8; 4]) {
+        if sixel == 0 || x >= MAX_IMAGE_SIZE || y >= MAX_IMAGE_SIZE {
+            return;
+        }
+        let end_x = (x + count).min(MAX_IMAGE_SIZE);
+        let end_y = (y + 6).min(MAX_IMAGE_SIZE);
+        self.resize(end_x, end_y);
+        if self.size_only {
+            return;
+        }
+        for bit in 0..(end_y - y) {
+            if sixel & (1 << bit) == 0 {
+                continue;
+            }
+            let row = ((y + bit) * self.buffer_width) as usize * 4;
+            for column in x..end_x {
+                let ix = row + column as usize * 4;
+                self.pixels[ix..ix + 4].copy_from_slice(&color);
+            }
+        }
+    }
+
+    fn into_image(self) -> Option<RgbaImage> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        let pixels = if self.width == self.buffer_width {
+            let mut pixels = self.pixels;
+            pixels.truncate((self.width * self.height * 4) as usize);
+            pixels
+        } else {
+            self.pixels
+                .chunks_exact(self.buffer_width as usize * 4)
+                .take(self.height as usize)
+                .flat_map(|row| &row[..self.width as usize * 4])
+                .copied()
+                .collect()
+        };
+        RgbaImage::from_raw(self.width, self.height, pixels)
+    }
+}
+
+/// The default VT340 palette, in percents.
+const SIXEL_PALETTE: [(u32, u32, u32); 16] = [
+    (0, 0, 0),
+    (20, 20, 80),
+    (80, 13, 13),
+    (20, 80, 20),
+    (80, 20, 80),
+    (20, 80, 80),
+    (80, 80, 20),
+    (53, 53, 53),
+    (26, 26, 26),
+    (33, 33, 60),
+    (60, 26, 26),
+    (33, 60, 33),
+    (60, 33, 60),
+    (33, 60, 60),
+    (60, 60, 33),
+    (80, 80, 80),
+];
+
+fn percent_rgb(r: u32, g: u32, b: u32) -> [u8; 4] {
+    let channel = |percent: u32| (percent.min(100) * 255 / 100) as u8;
+    [channel(r), channel(g), channel(b), 0xff]
+}
+
+/// Converts a Sixel HLS color, where blue is at 0 degrees and red at 120.
+fn hls_rgb(hue: u32, lightness: u32, saturation: u32) -> [u8; 4] {
+    let hue = ((hue + 240) % 360) as f32;
+    let lightness = lightness.min(100) as f32 / 100.;
+    let saturation = saturation.min(100) as f32 / 100.;
+    let chroma = (1. - (2. * lightness - 1.).abs()) * saturation;
+    let x = chroma * (1. - ((hue / 60.) % 2. - 1.).abs());
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x),
+    };
+    let m = lightness - chroma / 2.;
+    let channel = |value: f32| ((value + m) * 255.).round().clamp(0., 255.) as u8;
+    [channel(r), channel(g), channel(b), 0xff]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(scanner: &mut GraphicsScanner, input: &[u8]) -> (Option<ImagePlacement>, String) {
+        let mut replies = Vec::new();
+        let placement = scanner.advance(input, &mut replies);
+        (placement, String::from_utf8(replies).unwrap())
+    }
+
+    #[test]
+    fn test_sixel() {
+        let image = decode_sixel(b"\"1;1;3;7#1;2;100;0;0#1!3~-#2;2;0;0;100@").unwrap();
+        assert_eq!(image.dimensions(), (3, 7));
+        assert_eq!(image.get_pixel(2, 5).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(0, 6).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(1, 6).0, [0, 0, 0, 0]);
+        assert_eq!(sixel_size(b"#1!3~-#2@"), (3, 7));
+    }
+
+    #[test]
+    fn test_sixel_sequence() {
+        let images = TerminalImages::default();
+        let mut scanner = GraphicsScanner::new(images.clone());
+        scanner.set_cell_size(2, 4);
+
+        let (placement, _) = scan(&mut scanner, b"\x1bP0;1q\"1;1;3;7#1~~~-~~~\x1b");
+        assert_eq!(placement, None);
+        let (placement, _) = scan(&mut scanner, b"\\");
+        let placement = placement.unwrap();
+        assert_eq!((placement.columns, placement.lines), (2, 3));
+        assert_eq!(placement.cursor, ImageCursor::NextLine);
+        scanner.finish_decoding();
+        assert!(images.get(placement.image_id).is_some());
+
+        // Other DCS sequences are not images.
+        let (placement, _) = scan(&mut scanner, b"\x1bP$qm\x1b\\");
+        assert_eq!(placement, None);
+    }
+
+    #[test]
+    fn test_kitty_chunks_and_replies() {
+        let images = TerminalImages::default();
+        let mut scanner = GraphicsScanner::new(images.clone());
+        scanner.set_cell_size(1, 1);
+
+        // A 2x1 RGB image, sent in two chunks.
+        let (placement, replies) = scan(&mut scanner, b"\x1b_Ga=T,f=24,s=2,v=1,i=7,m=1;AAAA\x1b\\");
+        assert_eq!((placement, replies.as_str()), (None, ""));
+        let (placement, replies) = scan(&mut scanner, b"\x1b_Gm=0;AAAA\x1b\\");
+        let placement = placement.unwrap();
+        assert_eq!((placement.columns, placement.lines), (2, 1));
+        assert_eq!(placement.cursor, ImageCursor::AfterImage);
+        assert_eq!(replies, "\x1b_Gi=7;OK\x1b\\");
+
+        let (placement, replies) = scan(&mut scanner, b"\x1b_Ga=p,i=7,c=4,r=2,C=1,q=1\x1b\\");
+        let placement = placement.unwrap();
+        assert_eq!((placement.columns, placement.lines), (4, 2));
+        assert_eq!(placement.cursor, ImageCursor::Unchanged);
+        assert_eq!(replies, "");
+        scanner.finish_decoding();
+        assert!(images.get(placement.image_id).is_some());
+
+        let (_, replies) = scan(&mut scanner, b"\x1b_Ga=p,i=8\x1b\\");
+        assert!(replies.starts_with("\x1b_Gi=8;EINVAL:"));
+
+        scan(&mut scanner, b"\x1b_Ga=d,d=i,i=7\x1b\\");
+        assert!(images.is_empty());
+    }
+
+    #[test]
+    fn test_kitty_png_size() {
+        let images = TerminalImages::default();
+        let mut scanner = GraphicsScanner::new(images.clone());
+
+        // The header of a PNG image larger than MAX_IMAGE_SIZE is enough to refuse it.
+        let mut header = PNG_SIGNATURE.to_vec();
+        header.extend_from_slice(&13u32.to_be_bytes());
+        header.extend_from_slice(b"IHDR");
+        header.extend_from_slice(&(MAX_IMAGE_SIZE + 1).to_be_bytes());
+        header.extend_from_slice(&1u32.to_be_bytes());
+        let payload = base64::engine::general_purpose::STANDARD.encode(&header);
+        let input = format!("\x1b_Ga=T,f=100,i=3;{payload}\x1b\\");
+        let (placement, replies) = scan(&mut scanner, input.as_bytes());
+        assert_eq!(placement, None);
+        assert!(replies.starts_with("\x1b_Gi=3;EINVAL:"));
+        assert_eq!(png_size(&header).unwrap(), (MAX_IMAGE_SIZE + 1, 1));
+    }
+}
@@ -46,6 +46,7 @@ pub struct TerminalSettings {
     pub default_height: Pixels,
     pub detect_venv: VenvSettings,
     pub max_scroll_history_lines: Option<usize>,
+    pub persistent_sessions: bool,
     pub toolbar: Toolbar,
     pub scrollbar: ScrollbarSettings,
 }
@@ -219,6 +220,12 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: 10_000
     pub max_scroll_history_lines: Option<usize>,
+    /// Whether to keep the shells of terminals running in a background process when Zed quits,
+    /// and reattach to them, with their scrollback, when the workspace is reopened.
+    /// Closing a terminal still ends its shell. Only supported on macOS and Linux.
+    ///
+    /// Default: false
+    pub persistent_sessions: Option<bool>,
     /// Toolbar related settings
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar-related settings
@@ -264,6 +271,10 @@ impl settings::Settings for TerminalSettings {
         vscode.bool_setting(&name("copyOnSelection"), &mut current.copy_on_select);
         vscode.bool_setting("macOptionIsMeta", &mut current.option_as_meta);
         vscode.usize_setting("scrollback", &mut current.max_scroll_history_lines);
+        vscode.bool_setting(
+            &name("enablePersistentSessions"),
+            &mut current.persistent_sessions,
+        );
         match vscode.read_bool(&name("cursorBlinking")) {
             Some(true) => current.blinking = Some(TerminalBlink::On),
             Some(false) => current.blinking = Some(TerminalBlink::Off),
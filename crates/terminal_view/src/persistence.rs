@@ -412,6 +412,9 @@ define_connection! {
             ALTER TABLE terminals ADD COLUMN working_directory_path TEXT;
             UPDATE terminals SET working_directory_path = CAST(working_directory AS TEXT);
         ),
+        sql! (
+            ALTER TABLE terminals ADD COLUMN persistent_session_id TEXT;
+        ),
     ];
 }
 
@@ -433,25 +436,29 @@ impl TerminalDb {
         item_id: ItemId,
         workspace_id: WorkspaceId,
         working_directory: PathBuf,
+        persistent_session_id: Option<String>,
     ) -> Result<()> {
         log::debug!(
             "Saving working directory {working_directory:?} for item {item_id} in workspace {workspace_id:?}"
         );
         let query =
-            "INSERT INTO terminals(item_id, workspace_id, working_directory, working_directory_path)
-            VALUES (?1, ?2, ?3, ?4)
+            "INSERT INTO terminals(item_id, workspace_id, working_directory, working_directory_path, persistent_session_id)
+            VALUES (?1, ?2, ?3, ?4, ?5)
             ON CONFLICT DO UPDATE SET
                 item_id = ?1,
                 workspace_id = ?2,
                 working_directory = ?3,
-                working_directory_path = ?4"
+                working_directory_path = ?4,
+                persistent_session_id = ?5"
         ;
         self.write(move |conn| {
             let mut statement = Statement::prepare(conn, query)?;
             let mut next_index = statement.bind(&item_id, 1)?;
             next_index = statement.bind(&workspace_id, next_index)?;
             next_index = statement.bind(&working_directory, next_index)?;
-            statement.bind(&working_directory.to_string_lossy().to_string(), next_index)?;
+            next_index =
+                statement.bind(&working_directory.to_string_lossy().to_string(), next_index)?;
+            statement.bind(&persistent_session_id, next_index)?;
             statement.exec()
         })
         .await
@@ -464,4 +471,12 @@ impl TerminalDb {
             WHERE item_id = ? AND workspace_id = ?
         }
     }
+
+    query! {
+        pub fn get_persistent_session_id(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT persistent_session_id
+            FROM terminals
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
 }
@@ -1,6 +1,6 @@
 use editor::{CursorLayout, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, DispatchPhase, Element,
+    AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, Corners, DispatchPhase, Element,
     ElementId, Entity, FocusHandle, Font, FontStyle, FontWeight, GlobalElementId, HighlightStyle,
     Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity, IntoElement, LayoutId,
     ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels, Point, ShapedLine,
@@ -12,7 +12,7 @@ use itertools::Itertools;
 use language::CursorShape;
 use settings::Settings;
 use terminal::{
-    IndexedCell, Terminal, TerminalBounds, TerminalContent,
+    CommandStatus, IndexedCell, Terminal, TerminalBounds, TerminalContent, TerminalImage,
     alacritty_terminal::{
         grid::Dimensions,
         index::Point as AlacPoint,
@@ -22,6 +22,7 @@ use terminal::{
             CursorShape as AlacCursorShape, NamedColor,
         },
     },
+    is_internal_hyperlink,
     terminal_settings::TerminalSettings,
 };
 use theme::{ActiveTheme, Theme, ThemeSettings};
@@ -47,6 +48,9 @@ pub struct LayoutState {
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    /// Exit status marks drawn in the gutter, by display line.
+    command_marks: Vec<(i32, Hsla)>,
+    images: Vec<(DisplayCursor, TerminalImage)>,
     block_below_cursor_element: Option<AnyElement>,
     base_text_style: TextStyle,
 }
@@ -337,6 +341,43 @@ impl TerminalElement {
         }
     }
 
+    /// Paints an image over the cells it covers, keeping its aspect ratio, as the cell size may
+    /// have changed since the image was placed.
+    fn paint_image(
+        position: &DisplayCursor,
+        image: &TerminalImage,
+        origin: Point<Pixels>,
+        dimensions: &TerminalBounds,
+        window: &mut Window,
+    ) {
+        let image_size = image.image.size(0);
+        if image_size.width.0 <= 0 || image_size.height.0 <= 0 {
+            return;
+        }
+        let (width, height) = (
+            px(image_size.width.0 as f32),
+            px(image_size.height.0 as f32),
+        );
+        let scale = (dimensions.cell_width * image.columns as f32 / width)
+            .min(dimensions.line_height * image.lines as f32 / height);
+        let image_bounds = Bounds::new(
+            point(
+                origin.x + position.col() as f32 * dimensions.cell_width,
+                origin.y + position.line() as f32 * dimensions.line_height,
+            ),
+            size(width * scale, height * scale),
+        );
+        window
+            .paint_image(
+                image_bounds,
+                Corners::default(),
+                image.image.clone(),
+                0,
+                false,
+            )
+            .log_err();
+    }
+
     /// Converts the Alacritty cell styles to GPUI text styles and background color.
     fn cell_style(
         indexed: &IndexedCell,
@@ -356,7 +397,10 @@ impl TerminalElement {
         }
 
         let underline = (flags.intersects(Flags::ALL_UNDERLINES)
-            || indexed.cell.hyperlink().is_some())
+            || indexed
+                .cell
+                .hyperlink()
+                .is_some_and(|link| !is_internal_hyperlink(&link)))
         .then(|| UnderlineStyle {
             color: Some(fg),
             thickness: Pixels::from(1.0),
@@ -810,11 +854,35 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    command_marks,
+                    images,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
 
+                let command_marks = command_marks
+                    .iter()
+                    .filter_map(|mark| {
+                        let color = match mark.status {
+                            CommandStatus::Pending => return None,
+                            CommandStatus::Running => theme.colors().text_muted,
+                            CommandStatus::Exited(Some(0)) => theme.status().success,
+                            CommandStatus::Exited(_) => theme.status().error,
+                        };
+                        Some((mark.line.0 + display_offset as i32, color))
+                    })
+                    .collect();
+                let images = images
+                    .iter()
+                    .map(|image| {
+                        (
+                            DisplayCursor::from(image.point, display_offset),
+                            image.image.clone(),
+                        )
+                    })
+                    .collect();
+
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
                 for search_match in search_matches {
@@ -930,6 +998,8 @@ impl Element for TerminalElement {
                     display_offset,
                     hyperlink_tooltip,
                     gutter,
+                    command_marks,
+                    images,
                     block_below_cursor_element,
                     base_text_style: text_style,
                 }
@@ -1009,6 +1079,17 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, window);
                     }
 
+                    for (line, color) in &layout.command_marks {
+                        let mark_bounds = Bounds::new(
+                            point(
+                                bounds.origin.x + layout.gutter * 0.25,
+                                origin.y + *line as f32 * layout.dimensions.line_height,
+                            ),
+                            size(layout.gutter * 0.5, layout.dimensions.line_height),
+                        );
+                        window.paint_quad(fill(mark_bounds, *color));
+                    }
+
                     for (relative_highlighted_range, color) in
                         layout.relative_highlighted_ranges.iter()
                     {
@@ -1030,6 +1111,16 @@ impl Element for TerminalElement {
                         cell.paint(origin, &layout.dimensions, bounds, window, cx);
                     }
 
+                    for (position, image) in &layout.images {
+                        TerminalElement::paint_image(
+                            position,
+                            image,
+                            origin,
+                            &layout.dimensions,
+                            window,
+                        );
+                    }
+
                     if let Some(text_to_mark) = &marked_text_cloned {
                         if !text_to_mark.is_empty() {
                             if let Some(cursor_layout) = &original_cursor {
@@ -1216,7 +1307,10 @@ pub fn is_blank(cell: &IndexedCell) -> bool {
         return false;
     }
 
-    if cell.hyperlink().is_some() {
+    if cell
+        .hyperlink()
+        .is_some_and(|link| !is_internal_hyperlink(&link))
+    {
         return false;
     }
 
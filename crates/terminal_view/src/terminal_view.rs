@@ -19,8 +19,9 @@ use project::{Entry, Metadata, Project, search::SearchQuery, terminals::Terminal
 use schemars::JsonSchema;
 use task::TaskId;
 use terminal::{
-    Clear, Copy, Event, HoveredWord, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp,
-    ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskState,
+    Clear, Copy, CopyCommandOutput, Event, HoveredWord, MaybeNavigationTarget, Paste,
+    ScrollLineDown, ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToNextPrompt,
+    ScrollToPreviousPrompt, ScrollToTop, SelectCommandOutput, ShowCharacterPalette, TaskState,
     TaskStatus, Terminal, TerminalBounds, ToggleViMode,
     alacritty_terminal::{
         index::Point,
@@ -209,6 +210,19 @@ impl TerminalView {
 
         let scroll_handle = TerminalScrollHandle::new(terminal.read(cx));
 
+        let mut subscriptions = vec![
+            focus_in,
+            focus_out,
+            cx.observe_global::<SettingsStore>(Self::settings_changed),
+            // A closed terminal no longer restarts its task when the files it watches change.
+            cx.on_release(|terminal_view, cx| terminal_view.unwatch_task(cx)),
+        ];
+        if let Some(project) = project.upgrade() {
+            // The tab shows whether the task is watched.
+            let task_store = project.read(cx).task_store().clone();
+            subscriptions.push(cx.observe(&task_store, |_, _, cx| cx.emit(ItemEvent::UpdateTab)));
+        }
+
         Self {
             terminal,
             workspace: workspace_handle,
@@ -235,11 +249,7 @@ impl TerminalView {
             cwd_serialized: false,
             marked_text: None,
             marked_range_utf16: None,
-            _subscriptions: vec![
-                focus_in,
-                focus_out,
-                cx.observe_global::<SettingsStore>(Self::settings_changed),
-            ],
+            _subscriptions: subscriptions,
             _terminal_subscriptions: terminal_subscriptions,
         }
     }
@@ -419,6 +429,29 @@ impl TerminalView {
         window.dispatch_action(Box::new(task), cx);
     }
 
+    fn unwatch_task(&self, cx: &mut App) {
+        let Some(task_id) = self.terminal.read(cx).task().map(|task| task.id.clone()) else {
+            return;
+        };
+        if let Some(project) = self.project.upgrade() {
+            let task_store = project.read(cx).task_store().clone();
+            task_store.update(cx, |task_store, cx| task_store.unwatch_task(&task_id, cx));
+        }
+    }
+
+    fn is_task_watched(&self, cx: &App) -> bool {
+        let Some(task) = self.terminal.read(cx).task() else {
+            return false;
+        };
+        self.project.upgrade().is_some_and(|project| {
+            project
+                .read(cx)
+                .task_store()
+                .read(cx)
+                .is_task_watched(&task.id)
+        })
+    }
+
     fn clear(&mut self, _: &Clear, _: &mut Window, cx: &mut Context<Self>) {
         self.scroll_top = px(0.);
         self.terminal.update(cx, |term, _| term.clear());
@@ -551,6 +584,50 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(
+        &mut self,
+        _: &ScrollToNextPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
+    fn select_command_output(
+        &mut self,
+        _: &SelectCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.select_command_output());
+        cx.notify();
+    }
+
+    fn copy_command_output(
+        &mut self,
+        _: &CopyCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.copy_command_output());
+        cx.notify();
+    }
+
     fn toggle_vi_mode(&mut self, _: &ToggleViMode, _: &mut Window, cx: &mut Context<Self>) {
         self.terminal.update(cx, |term, _| term.toggle_vi_mode());
         cx.notify();
@@ -925,6 +1002,22 @@ impl TerminalView {
                 }),
         )
     }
+
+    fn stop_watching_button(task: &TaskState) -> IconButton {
+        let task_id = task.id.clone();
+        IconButton::new("stop-watching-icon", IconName::Eye)
+            .icon_size(IconSize::Small)
+            .size(ButtonSize::Compact)
+            .icon_color(Color::Muted)
+            .shape(ui::IconButtonShape::Square)
+            .tooltip(Tooltip::text("Stop restarting the task on file changes"))
+            .on_click(move |_, window, cx| {
+                let action = zed_actions::StopWatching {
+                    task_id: Some(task_id.0.clone()),
+                };
+                window.dispatch_action(Box::new(action), cx);
+            })
+    }
 }
 
 fn terminal_rerun_override(task: &TaskId) -> zed_actions::Rerun {
@@ -1138,6 +1231,8 @@ fn subscribe_for_terminal_events(
                     window.invalidate_character_coordinates();
                     cx.emit(SearchEvent::ActiveMatchChanged)
                 }
+                // Reported as diagnostics by the project.
+                Event::TaskProblemsChanged => {}
             }
         },
     );
@@ -1451,6 +1546,10 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
+            .on_action(cx.listener(TerminalView::select_command_output))
+            .on_action(cx.listener(TerminalView::copy_command_output))
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
@@ -1550,6 +1649,10 @@ impl Item for TerminalView {
             },
             None => (IconName::Terminal, Color::Muted, None),
         };
+        let stop_watching_button = terminal
+            .task()
+            .filter(|_| self.is_task_watched(cx))
+            .map(TerminalView::stop_watching_button);
 
         h_flex()
             .gap_1()
@@ -1574,6 +1677,7 @@ impl Item for TerminalView {
                     }),
             )
             .child(Label::new(title).color(params.text_color()))
+            .children(stop_watching_button)
             .into_any()
     }
 
@@ -1718,9 +1822,10 @@ impl SerializableItem for TerminalView {
 
         if let Some((cwd, workspace_id)) = terminal.working_directory().zip(self.workspace_id) {
             self.cwd_serialized = true;
+            let persistent_session_id = terminal.persistent_session_id().map(ToOwned::to_owned);
             Some(cx.background_spawn(async move {
                 TERMINAL_DB
-                    .save_working_directory(item_id, workspace_id, cwd)
+                    .save_working_directory(item_id, workspace_id, cwd, persistent_session_id)
                     .await
             }))
         } else {
@@ -1761,10 +1866,21 @@ impl SerializableItem for TerminalView {
                 })
                 .ok()
                 .flatten();
+            let kind = match TERMINAL_DB
+                .get_persistent_session_id(item_id, workspace_id)
+                .log_err()
+                .flatten()
+            {
+                Some(session_id) => TerminalKind::Session {
+                    session_id,
+                    working_directory: cwd,
+                },
+                None => TerminalKind::Shell(cwd),
+            };
 
             let terminal = project
                 .update(cx, |project, cx| {
-                    project.create_terminal(TerminalKind::Shell(cwd), window_handle, cx)
+                    project.create_terminal(kind, window_handle, cx)
                 })?
                 .await?;
             cx.update(|window, cx| {
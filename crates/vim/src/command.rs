@@ -1,16 +1,19 @@
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet};
 use command_palette_hooks::CommandInterceptResult;
 use editor::{
-    Bias, Editor, ToPoint,
+    Anchor, Bias, Editor, ToPoint,
     actions::{SortLinesCaseInsensitive, SortLinesCaseSensitive},
     display_map::ToDisplayPoint,
     scroll::Autoscroll,
 };
-use gpui::{Action, App, AppContext as _, Context, Global, Window, actions, impl_internal_actions};
-use itertools::Itertools;
+use gpui::{
+    Action, App, AppContext as _, Context, Global, Keystroke, Modifiers, Window, actions,
+    impl_internal_actions,
+};
+use itertools::{Either, Itertools};
 use language::Point;
-use multi_buffer::MultiBufferRow;
+use multi_buffer::{MultiBufferRow, MultiBufferSnapshot};
 use project::ProjectPath;
 use regex::Regex;
 use schemars::JsonSchema;
@@ -35,13 +38,15 @@ use zed_actions::{OpenDocs, RevealTarget};
 
 use crate::{
     ToggleMarksView, ToggleRegistersView, Vim,
+    insert::NormalBefore,
     motion::{EndOfDocument, Motion, MotionKind, StartOfDocument},
     normal::{
         JoinLines,
+        repeat::Replayer,
         search::{FindCommand, ReplaceCommand, Replacement},
     },
     object::Object,
-    state::{Mark, Mode},
+    state::{Mark, Mode, ReplayableAction},
     visual::VisualDeleteLine,
 };
 
@@ -68,6 +73,19 @@ pub struct WithCount {
     action: WrappedAction,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct VimNorm {
+    range: Option<CommandRange>,
+    keys: String,
+}
+
+/// Ends whatever the previous line's keys left unfinished (as `<Esc>` would),
+/// and then moves to the start of the next line `:normal` runs on.
+#[derive(Clone, Debug, PartialEq)]
+struct NormalCommandLine {
+    line: Option<Anchor>,
+}
+
 #[derive(Clone, Deserialize, JsonSchema, PartialEq)]
 pub enum VimOption {
     Wrap(bool),
@@ -174,7 +192,13 @@ enum DeleteMarks {
 
 actions!(
     vim,
-    [VisualCommand, CountCommand, ShellCommand, ArgumentRequired]
+    [
+        OpenCommandLine,
+        VisualCommand,
+        CountCommand,
+        ShellCommand,
+        ArgumentRequired
+    ]
 );
 #[derive(Clone, Deserialize, JsonSchema, PartialEq)]
 struct VimEdit {
@@ -188,6 +212,8 @@ impl_internal_actions!(
         YankCommand,
         WithRange,
         WithCount,
+        VimNorm,
+        NormalCommandLine,
         OnMatchingLines,
         ShellExec,
         VimSet,
@@ -237,12 +263,21 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
             });
         }
     });
+    Vim::action(editor, cx, |vim, _: &OpenCommandLine, window, cx| {
+        let Some(workspace) = vim.workspace(window) else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            command_palette::CommandPalette::toggle_command_line(workspace, "", window, cx);
+        })
+    });
+
     Vim::action(editor, cx, |vim, _: &VisualCommand, window, cx| {
         let Some(workspace) = vim.workspace(window) else {
             return;
         };
         workspace.update(cx, |workspace, cx| {
-            command_palette::CommandPalette::toggle(workspace, "'<,'>", window, cx);
+            command_palette::CommandPalette::toggle_command_line(workspace, "'<,'>", window, cx);
         })
     });
 
@@ -417,7 +452,7 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
             ".".to_string()
         };
         workspace.update(cx, |workspace, cx| {
-            command_palette::CommandPalette::toggle(workspace, &n, window, cx);
+            command_palette::CommandPalette::toggle_command_line(workspace, &n, window, cx);
         })
     });
 
@@ -425,7 +460,7 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
         vim.switch_mode(Mode::Normal, false, window, cx);
         let result = vim.update_editor(window, cx, |vim, editor, window, cx| {
             let snapshot = editor.snapshot(window, cx);
-            let buffer_row = action.range.head().buffer_row(vim, editor, window, cx)?;
+            let buffer_row = action.range.head_row(vim, editor, window, cx)?;
             let current = editor.selections.newest::<Point>(cx);
             let target = snapshot
                 .buffer_snapshot
@@ -524,6 +559,28 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
         });
     });
 
+    Vim::action(editor, cx, |vim, action: &VimNorm, window, cx| {
+        action.run(vim, window, cx)
+    });
+
+    Vim::action(editor, cx, |vim, action: &NormalCommandLine, window, cx| {
+        vim.clear_operator(window, cx);
+        if matches!(vim.mode, Mode::Insert | Mode::Replace) {
+            vim.normal_before(&NormalBefore, window, cx);
+        }
+        if vim.mode != Mode::Normal {
+            vim.switch_mode(Mode::Normal, false, window, cx);
+        }
+        let Some(line) = action.line else {
+            return;
+        };
+        vim.update_editor(window, cx, |_, editor, window, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.select_anchor_ranges([line..line]);
+            });
+        });
+    });
+
     Vim::action(editor, cx, |vim, action: &OnMatchingLines, window, cx| {
         action.run(vim, window, cx)
     });
@@ -552,6 +609,7 @@ struct VimCommand {
         >,
     >,
     has_count: bool,
+    keeps_trailing_whitespace: bool,
 }
 
 impl VimCommand {
@@ -600,6 +658,13 @@ impl VimCommand {
         self
     }
 
+    // Only leading whitespace is stripped from the arguments, as trailing
+    // whitespace is significant (e.g. `:normal i<space>`).
+    fn keep_trailing_whitespace(mut self) -> Self {
+        self.keeps_trailing_whitespace = true;
+        self
+    }
+
     fn parse(
         &self,
         query: &str,
@@ -617,11 +682,16 @@ impl VimCommand {
             .collect::<String>();
         let has_bang = rest.starts_with('!');
         let args = if has_bang {
-            rest.strip_prefix('!')?.trim().to_string()
+            rest.strip_prefix('!')?
         } else if rest.is_empty() {
-            "".into()
+            ""
+        } else {
+            rest.strip_prefix(' ')?
+        };
+        let args = if self.keeps_trailing_whitespace {
+            args.trim_start().to_string()
         } else {
-            rest.strip_prefix(' ')?.trim().to_string()
+            args.trim().to_string()
         };
 
         let action = if has_bang && self.bang_action.is_some() {
@@ -635,10 +705,10 @@ impl VimCommand {
         };
         if !args.is_empty() {
             // if command does not accept args and we have args then we should do no action
-            if let Some(args_fn) = &self.args {
-                args_fn.deref()(action, args)
-            } else {
-                None
+            let action = self.args.as_ref()?.deref()(action, args)?;
+            match (range, self.range.as_ref()) {
+                (Some(range), Some(range_fn)) => range_fn(action, range),
+                _ => Some(action),
             }
         } else if let Some(range) = range {
             self.range.as_ref().and_then(|f| f(action, range))
@@ -647,7 +717,6 @@ impl VimCommand {
         }
     }
 
-    // TODO: ranges with search queries
     fn parse_range(query: &str) -> (Option<CommandRange>, String) {
         let mut chars = query.chars().peekable();
 
@@ -658,6 +727,7 @@ impl VimCommand {
                     Some(CommandRange {
                         start: Position::Line { row: 1, offset: 0 },
                         end: Some(Position::LastLine { offset: 0 }),
+                        end_relative_to_start: false,
                     }),
                     chars.collect(),
                 );
@@ -674,6 +744,7 @@ impl VimCommand {
                             name: '>',
                             offset: 0,
                         }),
+                        end_relative_to_start: false,
                     }),
                     chars.collect(),
                 );
@@ -685,17 +756,22 @@ impl VimCommand {
 
         match chars.peek() {
             Some(',' | ';') => {
-                chars.next();
+                let end_relative_to_start = chars.next() == Some(';');
                 (
                     Some(CommandRange {
                         start: start.unwrap_or(Position::CurrentLine { offset: 0 }),
                         end: Self::parse_position(&mut chars),
+                        end_relative_to_start,
                     }),
                     chars.collect(),
                 )
             }
             _ => (
-                start.map(|start| CommandRange { start, end: None }),
+                start.map(|start| CommandRange {
+                    start,
+                    end: None,
+                    end_relative_to_start: false,
+                }),
                 chars.collect(),
             ),
         }
@@ -733,10 +809,64 @@ impl VimCommand {
                     offset: Self::parse_offset(chars),
                 })
             }
+            '/' | '?' => {
+                // without a closing delimiter this is a search (`:/foo`), not an address
+                let mut lookahead = chars.clone();
+                let delimiter = lookahead.next()?;
+                let pattern = Self::parse_pattern(&mut lookahead, delimiter)?;
+                *chars = lookahead;
+                Some(Position::Pattern {
+                    pattern,
+                    backwards: delimiter == '?',
+                    offset: Self::parse_offset(chars),
+                })
+            }
+            '\\' => {
+                let mut lookahead = chars.clone();
+                lookahead.next();
+                let kind = lookahead.next().filter(|c| matches!(c, '/' | '?' | '&'))?;
+                *chars = lookahead;
+                let offset = Self::parse_offset(chars);
+                if kind == '&' {
+                    Some(Position::LastSubstitutePattern { offset })
+                } else {
+                    Some(Position::Pattern {
+                        pattern: String::new(),
+                        backwards: kind == '?',
+                        offset,
+                    })
+                }
+            }
             _ => None,
         }
     }
 
+    // Like `OnMatchingLines::parse`, this flips \( and \) to ( and ) (and vice-versa).
+    // Returns None if the pattern is not terminated by `delimiter`.
+    fn parse_pattern(chars: &mut Peekable<Chars>, delimiter: char) -> Option<String> {
+        let mut pattern = String::new();
+        let mut escaped = false;
+        for c in chars.by_ref() {
+            if escaped {
+                escaped = false;
+                if c != '(' && c != ')' && c != delimiter {
+                    pattern.push('\\')
+                }
+                pattern.push(c)
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == delimiter {
+                return Some(pattern);
+            } else {
+                if c == '(' || c == ')' {
+                    pattern.push('\\')
+                }
+                pattern.push(c)
+            }
+        }
+        None
+    }
+
     fn parse_offset(chars: &mut Peekable<Chars>) -> i32 {
         let mut res: i32 = 0;
         while matches!(chars.peek(), Some('+' | '-')) {
@@ -764,10 +894,30 @@ impl VimCommand {
 
 #[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq)]
 enum Position {
-    Line { row: u32, offset: i32 },
-    Mark { name: char, offset: i32 },
-    LastLine { offset: i32 },
-    CurrentLine { offset: i32 },
+    Line {
+        row: u32,
+        offset: i32,
+    },
+    Mark {
+        name: char,
+        offset: i32,
+    },
+    LastLine {
+        offset: i32,
+    },
+    CurrentLine {
+        offset: i32,
+    },
+    /// The next line matching `pattern` (or the previous one if `backwards`),
+    /// with an empty pattern standing for the last search.
+    Pattern {
+        pattern: String,
+        backwards: bool,
+        offset: i32,
+    },
+    LastSubstitutePattern {
+        offset: i32,
+    },
 }
 
 impl Position {
@@ -775,6 +925,7 @@ impl Position {
         &self,
         vim: &Vim,
         editor: &mut Editor,
+        current_row: MultiBufferRow,
         window: &mut Window,
         cx: &mut App,
     ) -> Result<MultiBufferRow> {
@@ -814,43 +965,138 @@ impl Position {
                 .max_row()
                 .0
                 .saturating_add_signed(*offset),
-            Position::CurrentLine { offset } => editor
-                .selections
-                .newest_anchor()
-                .head()
-                .to_point(&snapshot.buffer_snapshot)
-                .row
-                .saturating_add_signed(*offset),
+            Position::CurrentLine { offset } => current_row.0.saturating_add_signed(*offset),
+            Position::Pattern {
+                pattern,
+                backwards,
+                offset,
+            } => {
+                let pattern = if pattern.is_empty() {
+                    last_search_pattern(vim, window, cx)
+                        .context("no previous regular expression")?
+                } else {
+                    pattern.clone()
+                };
+                matching_row(&snapshot.buffer_snapshot, &pattern, current_row, *backwards)?
+                    .0
+                    .saturating_add_signed(*offset)
+            }
+            Position::LastSubstitutePattern { offset } => {
+                let pattern = Vim::globals(cx)
+                    .last_substitute_pattern
+                    .clone()
+                    .context("no previous substitute regular expression")?;
+                matching_row(&snapshot.buffer_snapshot, &pattern, current_row, false)?
+                    .0
+                    .saturating_add_signed(*offset)
+            }
         };
 
         Ok(MultiBufferRow(target).min(snapshot.buffer_snapshot.max_row()))
     }
 }
 
+fn last_search_pattern(vim: &Vim, window: &mut Window, cx: &App) -> Option<String> {
+    let workspace = vim.workspace(window)?;
+    let pane = workspace.read(cx).active_pane().clone();
+    let search_bar = pane
+        .read(cx)
+        .toolbar()
+        .read(cx)
+        .item_of_type::<BufferSearchBar>()?;
+    let query = search_bar.read(cx).query(cx);
+    (!query.is_empty()).then_some(query)
+}
+
+// As in vim, the search starts on the line after (or before) the current one,
+// and wraps around the end of the buffer.
+fn matching_row(
+    snapshot: &MultiBufferSnapshot,
+    pattern: &str,
+    current_row: MultiBufferRow,
+    backwards: bool,
+) -> Result<MultiBufferRow> {
+    let regex = Regex::new(pattern)?;
+    let current_row = current_row.0;
+    let max_row = snapshot.max_row().0;
+    let rows = if backwards {
+        Either::Left((0..current_row).rev().chain((current_row..=max_row).rev()))
+    } else {
+        Either::Right((current_row + 1..=max_row).chain(0..=current_row))
+    };
+    for row in rows {
+        let line_end = Point::new(row, snapshot.line_len(MultiBufferRow(row)));
+        let line = snapshot
+            .text_for_range(Point::new(row, 0)..line_end)
+            .collect::<String>();
+        if regex.is_match(&line) {
+            return Ok(MultiBufferRow(row));
+        }
+    }
+    anyhow::bail!("pattern not found: {pattern}")
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) struct CommandRange {
     start: Position,
     end: Option<Position>,
+    /// Whether the positions were separated by `;` rather than `,`, in which case
+    /// the end is found from the start rather than from the cursor.
+    end_relative_to_start: bool,
 }
 
 impl CommandRange {
-    fn head(&self) -> &Position {
-        self.end.as_ref().unwrap_or(&self.start)
-    }
-
-    pub(crate) fn buffer_range(
+    /// Returns the rows of the start and end of the range, in the order they were written.
+    fn buffer_rows(
         &self,
         vim: &Vim,
         editor: &mut Editor,
         window: &mut Window,
         cx: &mut App,
-    ) -> Result<Range<MultiBufferRow>> {
-        let start = self.start.buffer_row(vim, editor, window, cx)?;
+    ) -> Result<(MultiBufferRow, MultiBufferRow)> {
+        let snapshot = editor.snapshot(window, cx);
+        let current_row = MultiBufferRow(
+            editor
+                .selections
+                .newest_anchor()
+                .head()
+                .to_point(&snapshot.buffer_snapshot)
+                .row,
+        );
+        let start = self
+            .start
+            .buffer_row(vim, editor, current_row, window, cx)?;
         let end = if let Some(end) = self.end.as_ref() {
-            end.buffer_row(vim, editor, window, cx)?
+            let relative_to = if self.end_relative_to_start {
+                start
+            } else {
+                current_row
+            };
+            end.buffer_row(vim, editor, relative_to, window, cx)?
         } else {
             start
         };
+        Ok((start, end))
+    }
+
+    fn head_row(
+        &self,
+        vim: &Vim,
+        editor: &mut Editor,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Result<MultiBufferRow> {
+        Ok(self.buffer_rows(vim, editor, window, cx)?.1)
+    }
+
+    pub(crate) fn buffer_range(
+        &self,
+        vim: &Vim,
+        editor: &mut Editor,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Result<Range<MultiBufferRow>> {
+        let (start, end) = self.buffer_rows(vim, editor, window, cx)?;
         if end < start {
             anyhow::Ok(end..start)
         } else {
@@ -862,6 +1108,7 @@ impl CommandRange {
         if let CommandRange {
             start: Position::Line { row, offset: 0 },
             end: None,
+            ..
         } = &self
         {
             Some(*row)
@@ -1094,6 +1341,20 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         VimCommand::str(("No", "tifications"), "notification_panel::ToggleFocus"),
         VimCommand::str(("A", "I"), "agent::ToggleFocus"),
         VimCommand::str(("G", "it"), "git_panel::ToggleFocus"),
+        VimCommand::new(
+            ("norm", "al"),
+            VimNorm {
+                range: None,
+                keys: String::new(),
+            },
+        )
+        .args(|_, keys| Some(VimNorm { range: None, keys }.boxed_clone()))
+        .keep_trailing_whitespace()
+        .range(|action, range| {
+            let mut action = action.as_any().downcast_ref::<VimNorm>()?.clone();
+            action.range = Some(range.clone());
+            Some(action.boxed_clone())
+        }),
         VimCommand::new(("noh", "lsearch"), search::buffer_search::Dismiss),
         VimCommand::new(("$", ""), EndOfDocument),
         VimCommand::new(("%", ""), EndOfDocument),
@@ -1163,7 +1424,8 @@ pub fn command_interceptor(mut input: &str, cx: &App) -> Vec<CommandInterceptRes
 
     let (range, query) = VimCommand::parse_range(input);
     let range_prefix = input[0..(input.len() - query.len())].to_string();
-    let query = query.as_str().trim();
+    let untrimmed_query = query.as_str().trim_start();
+    let query = untrimmed_query.trim_end();
 
     let action = if range.is_some() && query.is_empty() {
         Some(
@@ -1204,6 +1466,7 @@ pub fn command_interceptor(mut input: &str, cx: &App) -> Vec<CommandInterceptRes
             let range = range.clone().unwrap_or(CommandRange {
                 start: Position::CurrentLine { offset: 0 },
                 end: None,
+                end_relative_to_start: false,
             });
             Some(ReplaceCommand { replacement, range }.boxed_clone())
         } else {
@@ -1228,6 +1491,7 @@ pub fn command_interceptor(mut input: &str, cx: &App) -> Vec<CommandInterceptRes
         let range = range.clone().unwrap_or(CommandRange {
             start: Position::Line { row: 0, offset: 0 },
             end: Some(Position::LastLine { offset: 0 }),
+            end_relative_to_start: false,
         });
         if let Some(action) = OnMatchingLines::parse(query, invert, range, cx) {
             Some(action.boxed_clone())
@@ -1250,7 +1514,7 @@ pub fn command_interceptor(mut input: &str, cx: &App) -> Vec<CommandInterceptRes
     }
 
     for command in commands(cx).iter() {
-        if let Some(action) = command.parse(query, &range, cx) {
+        if let Some(action) = command.parse(untrimmed_query, &range, cx) {
             let mut string = ":".to_owned() + &range_prefix + command.prefix + command.suffix;
             if query.contains('!') {
                 string.push('!');
@@ -1481,6 +1745,138 @@ impl OnMatchingLines {
     }
 }
 
+impl VimNorm {
+    pub fn run(&self, vim: &mut Vim, window: &mut Window, cx: &mut Context<Vim>) {
+        let keystrokes = parse_normal_keys(&self.keys);
+        if keystrokes.is_empty() {
+            return;
+        }
+
+        // Lines are tracked with anchors so that keys which add or remove lines
+        // don't shift the lines that are still to come (anchoring after the line
+        // start keeps text pasted above a line out of it). Without a range,
+        // `:normal` runs once per cursor, which is how `:g/pat/normal` reaches
+        // each match.
+        let result = vim.update_editor(window, cx, |vim, editor, window, cx| {
+            let rows = if let Some(range) = &self.range {
+                let range = range.buffer_range(vim, editor, window, cx)?;
+                (range.start.0..=range.end.0).collect::<Vec<_>>()
+            } else {
+                editor
+                    .selections
+                    .all::<Point>(cx)
+                    .into_iter()
+                    .map(|selection| selection.head().row)
+                    .dedup()
+                    .collect()
+            };
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            anyhow::Ok(
+                rows.into_iter()
+                    .map(|row| snapshot.anchor_after(Point::new(row, 0)))
+                    .collect::<Vec<_>>(),
+            )
+        });
+
+        let lines = match result {
+            None => return,
+            Some(e @ Err(_)) => {
+                let Some(workspace) = vim.workspace(window) else {
+                    return;
+                };
+                workspace.update(cx, |workspace, cx| {
+                    e.notify_err(workspace, cx);
+                });
+                return;
+            }
+            Some(Ok(lines)) => lines,
+        };
+
+        let mut actions = Vec::new();
+        for line in lines {
+            actions.push(ReplayableAction::Action(
+                NormalCommandLine { line: Some(line) }.boxed_clone(),
+            ));
+            actions.extend(keystrokes.iter().cloned().map(ReplayableAction::Keystroke));
+        }
+        actions.push(ReplayableAction::Action(
+            NormalCommandLine { line: None }.boxed_clone(),
+        ));
+
+        let mut replayer = Vim::globals(cx)
+            .replayer
+            .get_or_insert_with(Replayer::new)
+            .clone();
+        replayer.replay(actions, window, cx);
+    }
+}
+
+// `:normal` types its argument one character at a time. As there is no way to
+// enter a literal <Esc> in the command palette, vim's <key> notation is also
+// accepted for special keys, e.g. `:normal Afoo<esc>0x`.
+fn parse_normal_keys(keys: &str) -> Vec<Keystroke> {
+    let mut keystrokes = Vec::new();
+    let mut rest = keys;
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            if let Some((name, after)) = rest[1..].split_once('>') {
+                if let Some(keystroke) = special_key(name) {
+                    keystrokes.push(keystroke);
+                    rest = after;
+                    continue;
+                }
+            }
+        }
+        keystrokes.push(keystroke_for_char(c));
+        rest = &rest[c.len_utf8()..];
+    }
+    keystrokes
+}
+
+fn special_key(name: &str) -> Option<Keystroke> {
+    let name = name.to_ascii_lowercase();
+    let key = match name.as_str() {
+        "esc" => "escape",
+        "cr" | "enter" | "return" => "enter",
+        "tab" => "tab",
+        "bs" => "backspace",
+        "del" => "delete",
+        "space" => "space",
+        "lt" => return Some(keystroke_for_char('<')),
+        "bar" => return Some(keystroke_for_char('|')),
+        _ => {
+            let (modifier, key) = name.split_once('-')?;
+            let modifier = match modifier {
+                "c" => "ctrl",
+                "a" | "m" => "alt",
+                "s" => "shift",
+                _ => return None,
+            };
+            let key = special_key(key).map_or(key.to_string(), |keystroke| keystroke.key);
+            return Keystroke::parse(&format!("{modifier}-{key}")).ok();
+        }
+    };
+    Keystroke::parse(key).ok()
+}
+
+fn keystroke_for_char(c: char) -> Keystroke {
+    let key = match c {
+        ' ' => "space".to_string(),
+        '\t' => "tab".to_string(),
+        '\n' => "enter".to_string(),
+        c => c.to_ascii_lowercase().to_string(),
+    };
+    Keystroke {
+        modifiers: if c.is_ascii_uppercase() {
+            Modifiers::shift()
+        } else {
+            Modifiers::none()
+        },
+        key,
+        key_char: Some(c.to_string()),
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ShellExec {
     command: String,
@@ -1683,6 +2079,7 @@ impl ShellExec {
                     show_summary: false,
                     show_command: false,
                     show_rerun: false,
+                    problem_matchers: Vec::new(),
                 };
 
                 let task_status = workspace.spawn_in_terminal(spawn_in_terminal, window, cx);
@@ -1830,8 +2227,8 @@ mod test {
     use std::path::Path;
 
     use crate::{
-        VimAddon,
-        state::Mode,
+        Vim, VimAddon,
+        state::{HistoryKind, Mode, PersistedState},
         test::{NeovimBackedTestContext, VimTestContext},
     };
     use editor::Editor;
@@ -1860,6 +2257,46 @@ mod test {
         });
     }
 
+    #[gpui::test]
+    async fn test_command_history(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇa\nb\nc\nd", Mode::Normal);
+        cx.update(|_, cx| {
+            Vim::update_globals(cx, |globals, cx| {
+                globals.restore(
+                    PersistedState {
+                        history: vec![
+                            (HistoryKind::Command, "4".to_string()),
+                            (HistoryKind::Command, "s/b/x/".to_string()),
+                            (HistoryKind::Command, "2".to_string()),
+                        ],
+                        ..Default::default()
+                    },
+                    cx,
+                )
+            })
+        });
+
+        cx.simulate_keystrokes(": up enter");
+        cx.assert_state("a\nˇb\nc\nd", Mode::Normal);
+
+        // only commands starting with what was typed are recalled
+        cx.simulate_keystrokes(": s up enter");
+        cx.run_until_parked();
+        cx.assert_state("a\nˇx\nc\nd", Mode::Normal);
+
+        cx.simulate_keystrokes(": up up up enter");
+        cx.assert_state("a\nx\nc\nˇd", Mode::Normal);
+
+        cx.simulate_keystrokes(": 1 enter");
+        cx.assert_state("ˇa\nx\nc\nd", Mode::Normal);
+
+        // moving down recalls newer commands again
+        cx.simulate_keystrokes(": up up up down enter");
+        cx.assert_state("a\nx\nc\nˇd", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_command_goto(cx: &mut TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -2015,6 +2452,56 @@ mod test {
         cx.shared_state().await.assert_eq("1\nˇ2 3 4\n1");
     }
 
+    #[gpui::test]
+    async fn test_command_pattern_ranges(cx: &mut TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇa
+            begin
+            b
+            end
+            c"})
+            .await;
+        cx.simulate_shared_keystrokes(": / b e g i n / , / e n d / d enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            a
+            ˇc"});
+
+        cx.set_shared_state(indoc! {"
+            ˇx
+            x
+            }
+            x"})
+            .await;
+        cx.simulate_shared_keystrokes(": . , / ^ } / s / x / y / enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            y
+            ˇy
+            }
+            x"});
+
+        // with `;` the second pattern is searched for from the first line
+        cx.set_shared_state("ˇ1\n2\na\nb\na\nc").await;
+        cx.simulate_shared_keystrokes(": 3 ; / a / d enter").await;
+        cx.shared_state().await.assert_eq("1\n2\nˇc");
+
+        cx.set_shared_state("ˇ1\n2\na\nb\na\nc").await;
+        cx.simulate_shared_keystrokes(": 3 , / a / d enter").await;
+        cx.shared_state().await.assert_eq("1\n2\nˇb\na\nc");
+
+        cx.set_shared_state("1\na\nb\na\nˇc").await;
+        cx.simulate_shared_keystrokes(": ? a ? - 1 d enter").await;
+        cx.shared_state().await.assert_eq("1\na\nˇa\nc");
+
+        cx.set_shared_state("ˇa\nb\nc\nb\nd").await;
+        cx.simulate_shared_keystrokes("/ b enter").await;
+        cx.simulate_shared_keystrokes(": \\ / d enter").await;
+        cx.shared_state().await.assert_eq("a\nb\nc\nˇd");
+    }
+
     #[gpui::test]
     async fn test_command_visual_replace(cx: &mut TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -2184,6 +2671,86 @@ mod test {
             ˇa"});
     }
 
+    #[gpui::test]
+    async fn test_normal_command(cx: &mut TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇa
+            b
+            c"})
+            .await;
+
+        cx.simulate_shared_keystrokes(": % n o r m space A x enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            ax
+            bx
+            cˇx"});
+
+        // lines added by earlier lines are skipped
+        cx.simulate_shared_keystrokes(": 1 , 2 n o r m a l space y y p enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            ax
+            ax
+            bx
+            ˇbx
+            cx"});
+
+        // only leading whitespace is dropped from the keys
+        cx.simulate_shared_keystrokes(": n o r m space space A space space enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            ax
+            ax
+            bx
+            bx ˇ\u{20}
+            cx"});
+    }
+
+    #[gpui::test]
+    async fn test_normal_command_special_keys(cx: &mut TestAppContext) {
+        // Neovim types `<esc>` literally, so this isn't checked against it.
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇax", Mode::Normal);
+        cx.simulate_keystrokes(": n o r m space 0 i < l t > < e s c > l r > enter");
+        cx.run_until_parked();
+        cx.assert_state("<ˇ>x", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_normal_command_on_matching_lines(cx: &mut TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇa
+            b
+            a
+            b
+            a
+        "})
+            .await;
+
+        cx.simulate_shared_keystrokes(": g / a / n o r m space I - enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            -a
+            b
+            -a
+            b
+            ˇ-a
+        "});
+
+        cx.simulate_shared_keystrokes(": v / a / n o r m a l space d d enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            -a
+            -a
+            ˇ-a"});
+    }
+
     #[gpui::test]
     async fn test_del_marks(cx: &mut TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
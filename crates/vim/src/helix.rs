@@ -1,16 +1,69 @@
-use editor::{DisplayPoint, Editor, movement, scroll::Autoscroll};
+use editor::{DisplayPoint, Editor, EditorSettings, ToOffset, movement, scroll::Autoscroll};
 use gpui::{Action, actions};
 use gpui::{Context, Window};
-use language::{CharClassifier, CharKind};
+use language::{CharClassifier, CharKind, Selection};
+use regex::RegexBuilder;
+use search::{BufferSearchBar, SearchOptions};
+use settings::Settings;
+use std::sync::Arc;
+use workspace::searchable::Direction;
 
 use crate::motion::MotionKind;
+use crate::object::Object;
+use crate::state::{Operator, RegexSelection, SearchState};
+use crate::surrounds::SurroundsType;
 use crate::{Vim, motion::Motion, state::Mode};
 
-actions!(vim, [HelixNormalAfter, HelixDelete]);
+actions!(
+    vim,
+    [
+        HelixNormalAfter,
+        HelixDelete,
+        HelixYank,
+        HelixChange,
+        HelixSelectRegex,
+        HelixSplitRegex
+    ]
+);
 
 pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, Vim::helix_normal_after);
     Vim::action(editor, cx, Vim::helix_delete);
+    Vim::action(editor, cx, Vim::helix_yank);
+    Vim::action(editor, cx, Vim::helix_change);
+    Vim::action(editor, cx, |vim, _: &HelixSelectRegex, window, cx| {
+        vim.helix_search_selections(RegexSelection::Matches, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixSplitRegex, window, cx| {
+        vim.helix_search_selections(RegexSelection::Splits, window, cx)
+    });
+}
+
+/// Collapses each selection to the character helix treats as its cursor: the last selected
+/// character, or the first one for a reversed selection.
+fn collapse_to_cursors(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+    editor.change_selections(None, window, cx, |s| {
+        s.move_with(|map, selection| {
+            let cursor = if selection.is_empty() || selection.reversed {
+                selection.head()
+            } else {
+                movement::left(map, selection.head())
+            };
+            selection.collapse_to(cursor, selection.goal);
+        });
+    });
+}
+
+/// Helix treats a cursor as a selection of the character under it, so
+/// expand empty selections to cover one character before operating on them.
+fn expand_cursors(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+    editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+        s.move_with(|map, selection| {
+            if selection.is_empty() && !selection.reversed {
+                selection.end = movement::right(map, selection.end);
+            }
+        });
+    });
 }
 
 impl Vim {
@@ -40,6 +93,182 @@ impl Vim {
         self.helix_move_cursor(motion, times, window, cx);
     }
 
+    pub fn helix_select_motion(
+        &mut self,
+        motion: Motion,
+        times: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            let text_layout_details = editor.text_layout_details(window);
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.move_with(|map, selection| {
+                    // The anchor and the cursor are both characters that stay selected.
+                    let (anchor, cursor) = if selection.is_empty() {
+                        (selection.head(), selection.head())
+                    } else if selection.reversed {
+                        (movement::left(map, selection.tail()), selection.head())
+                    } else {
+                        (selection.tail(), movement::left(map, selection.head()))
+                    };
+
+                    let Some((mut point, goal)) =
+                        motion.move_point(map, cursor, selection.goal, times, &text_layout_details)
+                    else {
+                        return;
+                    };
+                    // helix extends `w` up to the character before the next word.
+                    if matches!(motion, Motion::NextWordStart { .. }) && point > cursor {
+                        point = movement::left(map, point);
+                    }
+
+                    if point < anchor {
+                        selection.start = point;
+                        selection.end = movement::right(map, anchor);
+                        selection.reversed = true;
+                    } else {
+                        selection.start = anchor;
+                        selection.end = movement::right(map, point);
+                        selection.reversed = false;
+                    }
+                    selection.goal = goal;
+                })
+            });
+        });
+    }
+
+    pub(crate) fn helix_object(
+        &mut self,
+        object: Object,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mut waiting_operator = None;
+        match self.maybe_pop_operator() {
+            Some(Operator::Object { around }) => {
+                self.update_editor(window, cx, |_, editor, window, cx| {
+                    editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                        s.move_with(|map, selection| {
+                            let cursor = if selection.is_empty() || selection.reversed {
+                                selection.head()
+                            } else {
+                                movement::left(map, selection.head())
+                            };
+                            let mut cursor_selection = selection.clone();
+                            cursor_selection.collapse_to(cursor, selection.goal);
+                            if let Some(range) = object.range(map, cursor_selection, around) {
+                                if !range.is_empty() {
+                                    selection.start = range.start;
+                                    selection.end = range.end;
+                                    selection.reversed = false;
+                                }
+                            }
+                        });
+                    });
+                });
+            }
+            Some(Operator::ChangeSurrounds { target: None }) => {
+                let valid = self.helix_keep_selections(window, cx, |vim, window, cx| {
+                    vim.update_editor(window, cx, |_, editor, window, cx| {
+                        collapse_to_cursors(editor, window, cx);
+                    });
+                    vim.check_and_move_to_valid_bracket_pair(object, window, cx)
+                });
+                if valid {
+                    waiting_operator = Some(Operator::ChangeSurrounds {
+                        target: Some(object),
+                    });
+                }
+            }
+            _ => {}
+        }
+        self.clear_operator(window, cx);
+        if let Some(operator) = waiting_operator {
+            self.push_operator(operator, window, cx);
+        }
+    }
+
+    pub(crate) fn helix_add_surrounds(
+        &mut self,
+        text: Arc<str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            expand_cursors(editor, window, cx);
+        });
+        self.helix_keep_selections(window, cx, |vim, window, cx| {
+            vim.add_surrounds(text, SurroundsType::Selection, window, cx);
+        });
+    }
+
+    pub(crate) fn helix_change_surrounds(
+        &mut self,
+        text: Arc<str>,
+        target: Object,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.helix_keep_selections(window, cx, |vim, window, cx| {
+            vim.update_editor(window, cx, |_, editor, window, cx| {
+                collapse_to_cursors(editor, window, cx);
+            });
+            if vim.check_and_move_to_valid_bracket_pair(target, window, cx) {
+                vim.change_surrounds(text, target, window, cx);
+            }
+        });
+    }
+
+    pub(crate) fn helix_delete_surrounds(
+        &mut self,
+        text: Arc<str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.helix_keep_selections(window, cx, |vim, window, cx| {
+            vim.update_editor(window, cx, |_, editor, window, cx| {
+                collapse_to_cursors(editor, window, cx);
+            });
+            vim.delete_surrounds(text, window, cx);
+        });
+    }
+
+    /// Runs `f` and then restores the selections from before it ran. Both ends grow over
+    /// text inserted next to them, so a surround edit leaves the surrounded text selected
+    /// along with its new pair.
+    fn helix_keep_selections<T>(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        f: impl FnOnce(&mut Self, &mut Window, &mut Context<Self>) -> T,
+    ) -> T {
+        let selections = self
+            .update_editor(window, cx, |_, editor, _, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                editor
+                    .selections
+                    .all::<usize>(cx)
+                    .into_iter()
+                    .map(|selection| Selection {
+                        id: selection.id,
+                        start: snapshot.anchor_before(selection.start),
+                        end: snapshot.anchor_after(selection.end),
+                        reversed: selection.reversed,
+                        goal: selection.goal,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let result = f(self, window, cx);
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.select_anchors(selections);
+            });
+        });
+        result
+    }
+
     fn helix_find_range_forward(
         &mut self,
         times: Option<usize>,
@@ -296,30 +525,162 @@ impl Vim {
     pub fn helix_delete(&mut self, _: &HelixDelete, window: &mut Window, cx: &mut Context<Self>) {
         self.store_visual_marks(window, cx);
         self.update_editor(window, cx, |vim, editor, window, cx| {
-            // Fixup selections so they have helix's semantics.
-            // Specifically:
-            //  - Make sure that each cursor acts as a 1 character wide selection
             editor.transact(window, cx, |editor, window, cx| {
-                editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
-                    s.move_with(|map, selection| {
-                        if selection.is_empty() && !selection.reversed {
-                            selection.end = movement::right(map, selection.end);
-                        }
-                    });
-                });
+                expand_cursors(editor, window, cx);
             });
 
             vim.copy_selections_content(editor, MotionKind::Exclusive, window, cx);
             editor.insert("", window, cx);
         });
     }
+
+    fn helix_yank(&mut self, _: &HelixYank, window: &mut Window, cx: &mut Context<Self>) {
+        self.update_editor(window, cx, |vim, editor, window, cx| {
+            let original_selections = editor.selections.disjoint_anchors();
+            expand_cursors(editor, window, cx);
+            vim.copy_selections_content(editor, MotionKind::Exclusive, window, cx);
+            editor.change_selections(None, window, cx, |s| {
+                s.select_anchors(original_selections.to_vec());
+            });
+        });
+    }
+
+    fn helix_change(&mut self, _: &HelixChange, window: &mut Window, cx: &mut Context<Self>) {
+        self.start_recording(cx);
+        self.update_editor(window, cx, |vim, editor, window, cx| {
+            editor.transact(window, cx, |editor, window, cx| {
+                expand_cursors(editor, window, cx);
+            });
+
+            vim.copy_selections_content(editor, MotionKind::Exclusive, window, cx);
+            editor.insert("", window, cx);
+        });
+        self.switch_mode(Mode::Insert, true, window, cx);
+    }
+
+    /// Opens the buffer search bar to read the regex used by `s` and `S`,
+    /// which is applied to the current selections by [`Self::helix_submit_regex`].
+    fn helix_search_selections(
+        &mut self,
+        kind: RegexSelection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(pane) = self.pane(window, cx) else {
+            return;
+        };
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            expand_cursors(editor, window, cx);
+        });
+        let prior_selections = self.editor_selections(window, cx);
+        pane.update(cx, |pane, cx| {
+            if let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() {
+                search_bar.update(cx, |search_bar, cx| {
+                    if !search_bar.show(window, cx) {
+                        return;
+                    }
+
+                    search_bar.select_query(window, cx);
+                    cx.focus_self(window);
+
+                    search_bar.set_replacement(None, cx);
+                    let mut options = SearchOptions::REGEX;
+                    if EditorSettings::get_global(cx).search.case_sensitive {
+                        options |= SearchOptions::CASE_SENSITIVE;
+                    }
+                    search_bar.set_search_options(options, cx);
+
+                    self.search = SearchState {
+                        direction: Direction::Next,
+                        count: 1,
+                        prior_selections,
+                        prior_operator: None,
+                        prior_mode: self.mode,
+                        select_regex: Some(kind),
+                    }
+                });
+            }
+        })
+    }
+
+    pub(crate) fn helix_submit_regex(
+        &mut self,
+        kind: RegexSelection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(pane) = self.pane(window, cx) else {
+            return;
+        };
+        let query = pane.update(cx, |pane, cx| {
+            let search_bar = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>()?;
+            search_bar.update(cx, |search_bar, cx| {
+                let query = search_bar.query(cx);
+                let case_sensitive = search_bar.has_search_option(SearchOptions::CASE_SENSITIVE);
+                search_bar.dismiss(&Default::default(), window, cx);
+                Some((query, case_sensitive))
+            })
+        });
+        let prior_selections = std::mem::take(&mut self.search.prior_selections);
+        let Some(regex) = query.and_then(|(query, case_sensitive)| {
+            RegexBuilder::new(&query)
+                .case_insensitive(!case_sensitive)
+                .build()
+                .ok()
+        }) else {
+            return;
+        };
+
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let mut ranges = Vec::new();
+            for range in &prior_selections {
+                let mut start = range.start.to_offset(&snapshot);
+                let mut end = range.end.to_offset(&snapshot);
+                if start > end {
+                    std::mem::swap(&mut start, &mut end);
+                }
+                let text = snapshot.text_for_range(start..end).collect::<String>();
+                let mut last_end = start;
+                for found in regex.find_iter(&text) {
+                    match kind {
+                        RegexSelection::Matches => {
+                            if !found.is_empty() {
+                                ranges.push(start + found.start()..start + found.end());
+                            }
+                        }
+                        RegexSelection::Splits => {
+                            if start + found.start() > last_end {
+                                ranges.push(last_end..start + found.start());
+                            }
+                            last_end = start + found.end();
+                        }
+                    }
+                }
+                if kind == RegexSelection::Splits && end > last_end {
+                    ranges.push(last_end..end);
+                }
+            }
+
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                if ranges.is_empty() {
+                    s.select_anchor_ranges(prior_selections);
+                } else {
+                    s.select_ranges(ranges);
+                }
+            });
+        });
+    }
 }
 
 #[cfg(test)]
 mod test {
     use indoc::indoc;
 
-    use crate::{state::Mode, test::VimTestContext};
+    use crate::{
+        state::Mode,
+        test::{HelixBackedTestContext, VimTestContext},
+    };
 
     #[gpui::test]
     async fn test_next_word_start(cx: &mut gpui::TestAppContext) {
@@ -356,6 +717,183 @@ mod test {
         );
     }
 
+    #[gpui::test]
+    async fn test_select_mode(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixBackedTestContext::new(cx).await;
+        cx.set_shared_state(indoc! {"
+            The quˇick brown
+            fox jumps over
+            the lazy dog."})
+            .await;
+
+        cx.simulate_shared_keystrokes("v l").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            The qu«icˇ»k brown
+            fox jumps over
+            the lazy dog."});
+
+        cx.simulate_shared_keystrokes("w").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            The qu«ick ˇ»brown
+            fox jumps over
+            the lazy dog."});
+
+        cx.simulate_shared_keystrokes("h h h h h").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            The «ˇqui»ck brown
+            fox jumps over
+            the lazy dog."});
+
+        cx.simulate_shared_keystrokes("v").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            The «ˇqui»ck brown
+            fox jumps over
+            the lazy dog."});
+    }
+
+    #[gpui::test]
+    async fn test_match_mode_objects(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixBackedTestContext::new(cx).await;
+        cx.set_shared_state("The (quˇick) brown").await;
+
+        cx.simulate_shared_keystrokes("m i w").await;
+        cx.shared_state().await.assert_eq("The («quickˇ») brown");
+
+        cx.simulate_shared_keystrokes("m a (").await;
+        cx.shared_state().await.assert_eq("The «(quick)ˇ» brown");
+    }
+
+    #[gpui::test]
+    async fn test_match_mode_surrounds(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixBackedTestContext::new(cx).await;
+        cx.set_shared_state("The «quickˇ» brown").await;
+
+        // the surrounded text stays selected along with its new pair
+        cx.simulate_shared_keystrokes("m s )").await;
+        cx.shared_state().await.assert_eq("The «(quick)ˇ» brown");
+
+        cx.simulate_shared_keystrokes("m r ) ]").await;
+        cx.shared_state().await.assert_eq("The «[quick]ˇ» brown");
+
+        cx.simulate_shared_keystrokes("m d ]").await;
+        cx.shared_state().await.assert_eq("The «quickˇ» brown");
+
+        // a cursor surrounds the character under it
+        cx.set_shared_state("The quˇick brown").await;
+        cx.simulate_shared_keystrokes("m s ]").await;
+        cx.shared_state().await.assert_eq("The qu«[i]ˇ»ck brown");
+    }
+
+    #[gpui::test]
+    async fn test_select_regex(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixBackedTestContext::new(cx).await;
+        cx.set_shared_state(indoc! {"
+            «The quick brown
+            fox jumps overˇ»
+            the lazy dog."})
+            .await;
+
+        cx.simulate_shared_keystrokes("s o enter").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            The quick br«oˇ»wn
+            f«oˇ»x jumps «oˇ»ver
+            the lazy dog."});
+
+        // no matches keeps the selections
+        cx.set_shared_state("«The quickˇ» brown").await;
+        cx.simulate_shared_keystrokes("s z enter").await;
+        cx.shared_state().await.assert_eq("«The quickˇ» brown");
+
+        // a lowercase regex ignores case
+        cx.set_shared_state("«This and thatˇ»").await;
+        cx.simulate_shared_keystrokes("s t h enter").await;
+        cx.shared_state().await.assert_eq("«Thˇ»is and «thˇ»at");
+    }
+
+    #[gpui::test]
+    async fn test_split_regex(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixBackedTestContext::new(cx).await;
+        cx.set_shared_state("«one, two, threeˇ»").await;
+
+        cx.simulate_shared_keystrokes("shift-s , space enter").await;
+        cx.shared_state()
+            .await
+            .assert_eq("«oneˇ», «twoˇ», «threeˇ»");
+
+        cx.set_shared_state("«oneXtwoxthreeˇ»").await;
+        cx.simulate_shared_keystrokes("shift-s x enter").await;
+        cx.shared_state().await.assert_eq("«oneˇ»X«twoˇ»x«threeˇ»");
+    }
+
+    #[gpui::test]
+    async fn test_yank_and_change(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixBackedTestContext::new(cx).await;
+        cx.set_shared_state("The qu«ickˇ» brown").await;
+
+        cx.simulate_shared_keystrokes("y").await;
+        cx.shared_state().await.assert_eq("The qu«ickˇ» brown");
+        assert_eq!(
+            cx.read_from_clipboard()
+                .map(|item| item.text().unwrap().to_string()),
+            Some("ick".into())
+        );
+
+        // a cursor yanks the character under it
+        cx.set_shared_state("The quˇick brown").await;
+        cx.simulate_shared_keystrokes("y").await;
+        cx.shared_state().await.assert_eq("The quˇick brown");
+        assert_eq!(
+            cx.read_from_clipboard()
+                .map(|item| item.text().unwrap().to_string()),
+            Some("i".into())
+        );
+
+        cx.set_shared_state("The qu«ickˇ» brown").await;
+        cx.simulate_shared_keystrokes("c").await;
+        cx.shared_state().await.assert_eq("The quˇ brown");
+    }
+
+    #[gpui::test]
+    async fn test_copy_cursor(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixBackedTestContext::new(cx).await;
+        cx.set_shared_state(indoc! {"
+            The quick brown
+            fox ˇjumps over
+            the lazy dog."})
+            .await;
+
+        cx.simulate_shared_keystrokes("shift-c").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            The quick brown
+            fox ˇjumps over
+            the ˇlazy dog."});
+
+        cx.set_shared_state(indoc! {"
+            The quick brown
+            fox ˇjumps over
+            the lazy dog."})
+            .await;
+        cx.simulate_shared_keystrokes("alt-shift-c").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            The ˇquick brown
+            fox ˇjumps over
+            the lazy dog."});
+    }
+
+    #[gpui::test]
+    async fn test_space_menu(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixBackedTestContext::new(cx).await;
+        cx.set_shared_state("The qu«ickˇ» brown").await;
+
+        cx.simulate_shared_keystrokes("space y").await;
+        cx.shared_state().await.assert_eq("The qu«ickˇ» brown");
+        assert_eq!(
+            cx.read_from_clipboard()
+                .map(|item| item.text().unwrap().to_string()),
+            Some("ick".into())
+        );
+    }
+
     // #[gpui::test]
     // async fn test_delete(cx: &mut gpui::TestAppContext) {
     //     let mut cx = VimTestContext::new(cx, true).await;
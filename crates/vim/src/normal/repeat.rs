@@ -8,7 +8,7 @@ use crate::{
     state::{Mode, Operator, RecordedSelection, ReplayableAction, VimGlobals},
 };
 use editor::Editor;
-use gpui::{Action, App, Context, Window, actions};
+use gpui::{Action, App, Context, Entity, KeyDownEvent, PlatformInput, Window, actions};
 use workspace::Workspace;
 
 actions!(vim, [Repeat, EndRepeat, ToggleRecord, ReplayLastRecording]);
@@ -40,7 +40,7 @@ fn repeatable_insert(action: &ReplayableAction) -> Option<Box<dyn Action>> {
                 None
             }
         }
-        ReplayableAction::Insertion { .. } => None,
+        ReplayableAction::Insertion { .. } | ReplayableAction::Keystroke(_) => None,
     }
 }
 
@@ -57,7 +57,8 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, |vim, _: &ToggleRecord, window, cx| {
         let globals = Vim::globals(cx);
         if let Some(char) = globals.recording_register.take() {
-            globals.last_recorded_register = Some(char)
+            globals.last_recorded_register = Some(char);
+            Vim::update_globals(cx, |globals, cx| globals.persist_recording(char, cx));
         } else {
             vim.push_operator(Operator::RecordRegister, window, cx);
         }
@@ -130,25 +131,47 @@ impl Replayer {
                 text,
                 utf16_range_to_replace,
             } => {
-                let Some(Some(workspace)) = window.root::<Workspace>() else {
-                    return;
-                };
-                let Some(editor) = workspace
-                    .read(cx)
-                    .active_item(cx)
-                    .and_then(|item| item.act_as::<Editor>(cx))
-                else {
+                let Some(editor) = active_editor(window, cx) else {
                     return;
                 };
                 editor.update(cx, |editor, cx| {
                     editor.replay_insert_event(&text, utf16_range_to_replace.clone(), window, cx)
                 })
             }
+            ReplayableAction::Keystroke(keystroke) => {
+                let keystroke = keystroke.with_simulated_ime();
+                let text = keystroke.key_char.clone();
+                let result = window.dispatch_event(
+                    PlatformInput::KeyDown(KeyDownEvent {
+                        keystroke,
+                        is_held: false,
+                    }),
+                    cx,
+                );
+                // Typed text goes straight to the editor, as the window's input handler
+                // is only updated on the next frame.
+                if let Some(text) = text.filter(|_| result.propagate) {
+                    let Some(editor) = active_editor(window, cx) else {
+                        return;
+                    };
+                    editor.update(cx, |editor, cx| {
+                        editor.replay_insert_event(&text, None, window, cx)
+                    })
+                }
+            }
         }
         window.defer(cx, move |window, cx| self.next(window, cx));
     }
 }
 
+fn active_editor(window: &mut Window, cx: &mut App) -> Option<Entity<Editor>> {
+    let workspace = window.root::<Workspace>().flatten()?;
+    workspace
+        .read(cx)
+        .active_item(cx)
+        .and_then(|item| item.act_as::<Editor>(cx))
+}
+
 impl Vim {
     pub(crate) fn record_register(
         &mut self,
@@ -159,7 +182,9 @@ impl Vim {
         let globals = Vim::globals(cx);
         globals.recording_register = Some(register);
         globals.recordings.remove(&register);
+        globals.recorded_keystrokes.remove(&register);
         globals.ignore_current_insertion = true;
+        globals.ignore_current_keystroke = true;
         self.clear_operator(window, cx)
     }
 
@@ -354,7 +379,8 @@ mod test {
     use gpui::EntityInputHandler;
 
     use crate::{
-        state::Mode,
+        Vim,
+        state::{Mode, PersistedState},
         test::{NeovimBackedTestContext, VimTestContext},
     };
 
@@ -779,4 +805,46 @@ mod test {
         cx.simulate_shared_keystrokes("@ b").await;
         cx.shared_state().await.assert_eq("aaaaaaabbbˇd");
     }
+
+    #[gpui::test]
+    async fn test_restored_recording(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three four", Mode::Normal);
+        cx.simulate_keystrokes("q a d w q");
+        cx.assert_state("ˇtwo three four", Mode::Normal);
+
+        let keystrokes = cx.update(|_, cx| {
+            Vim::globals(cx).recorded_keystrokes[&'a']
+                .iter()
+                .map(|keystroke| (keystroke.unparse(), keystroke.key_char.clone()))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(
+            keystrokes
+                .iter()
+                .map(|(keystroke, _)| keystroke.as_str())
+                .collect::<Vec<_>>(),
+            vec!["d", "w"]
+        );
+
+        // a recording from a previous session is replayed as keystrokes
+        cx.update(|_, cx| {
+            Vim::update_globals(cx, |globals, cx| {
+                globals.restore(
+                    PersistedState {
+                        recordings: vec![(
+                            "b".to_string(),
+                            serde_json::to_string(&keystrokes).unwrap(),
+                        )],
+                        ..Default::default()
+                    },
+                    cx,
+                )
+            })
+        });
+        cx.simulate_keystrokes("@ b");
+        cx.run_until_parked();
+        cx.assert_state("ˇthree four", Mode::Normal);
+    }
 }
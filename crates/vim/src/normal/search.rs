@@ -1,11 +1,13 @@
-use editor::{Editor, EditorSettings};
+use editor::{Anchor, Editor, EditorSettings, scroll::Autoscroll};
 use gpui::{Context, Window, actions, impl_actions, impl_internal_actions};
-use language::Point;
+use language::{Point, TransactionId};
+use multi_buffer::MultiBufferRow;
+use regex::RegexBuilder;
 use schemars::JsonSchema;
 use search::{BufferSearchBar, SearchOptions, buffer_search};
 use serde_derive::Deserialize;
 use settings::Settings;
-use std::{iter::Peekable, str::Chars};
+use std::{collections::VecDeque, iter::Peekable, ops::Range, str::Chars, sync::Arc};
 use util::serde::default_true;
 use workspace::{notifications::NotifyResultExt, searchable::Direction};
 
@@ -13,7 +15,7 @@ use crate::{
     Vim,
     command::CommandRange,
     motion::Motion,
-    state::{Mode, SearchState},
+    state::{Mode, Operator, SearchState},
 };
 
 #[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq)]
@@ -66,6 +68,17 @@ pub(crate) struct Replacement {
     replacement: String,
     should_replace_all: bool,
     is_case_sensitive: bool,
+    confirm: bool,
+}
+
+/// The state of a `:s///c` waiting for each match to be confirmed.
+pub(crate) struct SubstituteConfirmation {
+    /// The matches still to be confirmed, with the text that would replace them.
+    matches: VecDeque<(Range<Anchor>, String)>,
+    transaction_id: Option<TransactionId>,
+    /// The last match substituted by `a`, on whose line the cursor ends up. Otherwise the cursor
+    /// stays on the last match asked about, like in vim.
+    substituted_all: Option<Anchor>,
 }
 
 actions!(vim, [SearchSubmit, MoveToNextMatch, MoveToPreviousMatch]);
@@ -163,6 +176,7 @@ impl Vim {
                         options |= SearchOptions::CASE_SENSITIVE;
                     }
                     search_bar.set_search_options(options, cx);
+                    search_bar.restore_search_history(Vim::globals(cx).search_history.clone());
                     let prior_mode = if self.temp_mode {
                         Mode::Insert
                     } else {
@@ -175,6 +189,7 @@ impl Vim {
                         prior_selections,
                         prior_operator: self.operator_stack.last().cloned(),
                         prior_mode,
+                        select_regex: None,
                     }
                 });
             }
@@ -188,6 +203,10 @@ impl Vim {
     }
 
     pub fn search_submit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(kind) = self.search.select_regex.take() {
+            self.helix_submit_regex(kind, window, cx);
+            return;
+        }
         self.store_visual_marks(window, cx);
         let Some(pane) = self.pane(window, cx) else {
             return;
@@ -217,8 +236,11 @@ impl Vim {
                 let prior_mode = self.search.prior_mode;
                 let prior_operator = self.search.prior_operator.take();
 
-                let query = search_bar.query(cx).into();
-                Vim::globals(cx).registers.insert('/', query);
+                let query = search_bar.query(cx);
+                Vim::update_globals(cx, |globals, cx| {
+                    globals.registers.insert('/', query.clone().into());
+                    globals.add_search_history(query, cx);
+                });
                 Some((prior_selections, prior_mode, prior_operator))
             })
         });
@@ -429,6 +451,10 @@ impl Vim {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if action.replacement.confirm {
+            self.start_substitute_confirmation(action, window, cx);
+            return;
+        }
         let replacement = action.replacement.clone();
         let Some(((pane, workspace), editor)) = self
             .pane(window, cx)
@@ -470,6 +496,7 @@ impl Vim {
                 } else {
                     replacement.search
                 };
+                Vim::globals(cx).last_substitute_pattern = Some(search.clone());
                 if search_bar.should_use_smartcase_search(cx) {
                     options.set(
                         SearchOptions::CASE_SENSITIVE,
@@ -516,6 +543,202 @@ impl Vim {
             .detach_and_log_err(cx);
         })
     }
+
+    fn start_substitute_confirmation(
+        &mut self,
+        action: &ReplaceCommand,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let replacement = &action.replacement;
+        let search = if replacement.search.is_empty() {
+            self.pane(window, cx)
+                .and_then(|pane| {
+                    pane.read(cx)
+                        .toolbar()
+                        .read(cx)
+                        .item_of_type::<BufferSearchBar>()
+                })
+                .map(|search_bar| search_bar.read(cx).query(cx))
+                .unwrap_or_default()
+        } else {
+            replacement.search.clone()
+        };
+        Vim::globals(cx).last_substitute_pattern = Some(search.clone());
+        let case_sensitive = if EditorSettings::get_global(cx).use_smartcase_search {
+            search.chars().any(|c| c.is_uppercase())
+        } else {
+            replacement.is_case_sensitive
+        };
+
+        let result = self.update_editor(window, cx, |vim, editor, window, cx| {
+            let range = action.range.buffer_range(vim, editor, window, cx)?;
+            let regex = RegexBuilder::new(&search)
+                .case_insensitive(!case_sensitive)
+                .build()?;
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let mut matches = VecDeque::new();
+            for row in range.start.0..=range.end.0 {
+                let line_start = Point::new(row, 0);
+                let line_end = Point::new(row, snapshot.line_len(MultiBufferRow(row)));
+                let line = snapshot
+                    .text_for_range(line_start..line_end)
+                    .collect::<String>();
+                let line_offset = snapshot.point_to_offset(line_start);
+                let limit = if replacement.should_replace_all {
+                    usize::MAX
+                } else {
+                    1
+                };
+                for captures in regex.captures_iter(&line).take(limit) {
+                    let found = captures.get(0).unwrap();
+                    let mut text = String::new();
+                    captures.expand(&replacement.replacement, &mut text);
+                    let start = snapshot.anchor_after(line_offset + found.start());
+                    let end = snapshot.anchor_before(line_offset + found.end());
+                    matches.push_back((start..end, text));
+                }
+            }
+            if matches.is_empty() {
+                anyhow::bail!("pattern not found: {search}");
+            }
+            anyhow::Ok(SubstituteConfirmation {
+                matches,
+                transaction_id: None,
+                substituted_all: None,
+            })
+        });
+
+        match result {
+            None => {}
+            Some(Ok(confirmation)) => {
+                self.substitute_confirmation = Some(confirmation);
+                self.push_operator(Operator::ConfirmSubstitute, window, cx);
+                self.show_substitute_match(window, cx);
+            }
+            Some(e @ Err(_)) => {
+                let Some(workspace) = self.workspace(window) else {
+                    return;
+                };
+                workspace.update(cx, |workspace, cx| {
+                    e.notify_err(workspace, cx);
+                });
+            }
+        }
+    }
+
+    fn show_substitute_match(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((range, text)) = self
+            .substitute_confirmation
+            .as_ref()
+            .and_then(|confirmation| confirmation.matches.front().cloned())
+        else {
+            return;
+        };
+        self.status_label = Some(format!("replace with {text} (y/n/a/q/l/^E/^Y)?").into());
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            editor.highlight_background::<SubstituteConfirmation>(
+                &[range.clone()],
+                |colors| colors.search_match_background,
+                cx,
+            );
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.select_anchor_ranges([range.start..range.start]);
+            });
+        });
+        cx.notify();
+    }
+
+    pub(crate) fn confirm_substitute(
+        &mut self,
+        text: Arc<str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(mut confirmation) = self.substitute_confirmation.take() else {
+            return;
+        };
+        let substitutions = match text.as_ref() {
+            "y" => 1,
+            "l" => {
+                confirmation.matches.truncate(1);
+                1
+            }
+            "a" => confirmation.matches.len(),
+            "n" => {
+                confirmation.matches.pop_front();
+                0
+            }
+            "q" => {
+                confirmation.matches.clear();
+                0
+            }
+            _ => 0,
+        };
+        if substitutions > 0 {
+            let edits = confirmation
+                .matches
+                .drain(..substitutions)
+                .collect::<Vec<_>>();
+            if text.as_ref() == "a" {
+                confirmation.substituted_all = edits.last().map(|(range, _)| range.start);
+            }
+            self.update_editor(window, cx, |_, editor, window, cx| {
+                let cursor = editor.selections.newest::<Point>(cx).head();
+                let transaction_id =
+                    editor.transact(window, cx, |editor, _, cx| editor.edit(edits, cx));
+                confirmation.transaction_id = confirmation.transaction_id.or(transaction_id);
+                // the cursor stays at the start of the match it was on
+                editor.change_selections(None, window, cx, |s| {
+                    s.select_ranges([cursor..cursor]);
+                });
+            });
+        }
+
+        let done = confirmation.matches.is_empty();
+        self.substitute_confirmation = Some(confirmation);
+        if done {
+            self.clear_operator(window, cx);
+        } else {
+            self.show_substitute_match(window, cx);
+        }
+    }
+
+    /// Called whenever the operator is cleared, so that escape (or leaving normal
+    /// mode) stops a `:s///c` early.
+    pub(crate) fn end_substitute_confirmation(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(confirmation) = self.substitute_confirmation.take() else {
+            return;
+        };
+        self.status_label.take();
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            editor.clear_background_highlights::<SubstituteConfirmation>(cx);
+            // all the substitutions are undone together
+            if let Some(transaction_id) = confirmation.transaction_id {
+                editor.group_until_transaction(transaction_id, cx);
+            }
+            if let Some(last_substitution) = confirmation.substituted_all {
+                editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                    s.select_anchor_ranges([last_substitution..last_substitution]);
+                });
+            }
+        });
+        if confirmation.substituted_all.is_some() {
+            self.move_cursor(
+                Motion::FirstNonWhitespace {
+                    display_lines: false,
+                },
+                None,
+                window,
+                cx,
+            );
+        }
+        cx.notify();
+    }
 }
 
 impl Replacement {
@@ -577,12 +800,14 @@ impl Replacement {
             replacement,
             should_replace_all: false,
             is_case_sensitive: true,
+            confirm: false,
         };
 
         for c in flags.chars() {
             match c {
                 'g' => replacement.should_replace_all = true,
-                'c' | 'n' => replacement.should_replace_all = false,
+                'c' => replacement.confirm = true,
+                'n' => replacement.should_replace_all = false,
                 'i' => replacement.is_case_sensitive = false,
                 'I' => replacement.is_case_sensitive = true,
                 _ => {}
@@ -1003,4 +1228,102 @@ mod test {
                  "
         });
     }
+
+    #[gpui::test]
+    async fn test_replace_with_confirmation(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇa a
+            a a
+            a a
+            "})
+            .await;
+        cx.simulate_shared_keystrokes(": % s / a / b / g c enter")
+            .await;
+        cx.run_until_parked();
+        // scrolling keeps asking about the same match
+        cx.simulate_shared_keystrokes("ctrl-e ctrl-y y n a").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            b a
+            b b
+            ˇb b
+            "});
+
+        cx.set_shared_state(indoc! {"
+            ˇa a
+            a a
+            a a
+            "})
+            .await;
+        cx.simulate_shared_keystrokes(": % s / a / b / c enter")
+            .await;
+        cx.run_until_parked();
+        cx.simulate_shared_keystrokes("y q").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            b a
+            ˇa a
+            a a
+            "});
+
+        cx.simulate_shared_keystrokes(": % s / a / b / c enter")
+            .await;
+        cx.run_until_parked();
+        cx.simulate_shared_keystrokes("n l").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            b a
+            ˇb a
+            a a
+            "});
+
+        cx.set_shared_state(indoc! {"
+            a a
+            ˇa a
+            "})
+            .await;
+        cx.simulate_shared_keystrokes(": s / a / b / g c enter")
+            .await;
+        cx.run_until_parked();
+        cx.simulate_shared_keystrokes("y y").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            a a
+            b ˇb
+            "});
+    }
+
+    #[gpui::test]
+    async fn test_replace_with_confirmation_undo(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa a
+            a a
+            a a
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": % s / a / b / g c enter");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("y n a");
+        cx.assert_state(
+            indoc! {"
+            b a
+            b b
+            ˇb b
+            "},
+            Mode::Normal,
+        );
+
+        // the whole substitution is undone at once
+        cx.simulate_keystrokes("u");
+        cx.assert_state(
+            indoc! {"
+            ˇa a
+            a a
+            a a
+            "},
+            Mode::Normal,
+        );
+    }
 }
@@ -13,7 +13,8 @@ use editor::display_map::{is_invisible, replacement};
 use editor::{Anchor, ClipboardSelection, Editor, MultiBuffer, ToPoint as EditorToPoint};
 use gpui::{
     Action, App, AppContext, BorrowAppContext, ClipboardEntry, ClipboardItem, DismissEvent, Entity,
-    EntityId, Global, HighlightStyle, StyledText, Subscription, Task, TextStyle, WeakEntity,
+    EntityId, Global, HighlightStyle, Keystroke, StyledText, Subscription, Task, TextStyle,
+    WeakEntity,
 };
 use language::{Buffer, BufferEvent, BufferId, Chunk, Point};
 use multi_buffer::MultiBufferRow;
@@ -22,8 +23,9 @@ use project::{Project, ProjectItem, ProjectPath};
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
 use std::borrow::BorrowMut;
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 use std::path::Path;
+use std::rc::Rc;
 use std::{fmt::Display, ops::Range, sync::Arc};
 use text::{Bias, ToPoint};
 use theme::ThemeSettings;
@@ -44,6 +46,7 @@ pub enum Mode {
     VisualLine,
     VisualBlock,
     HelixNormal,
+    HelixSelect,
 }
 
 impl Display for Mode {
@@ -56,6 +59,7 @@ impl Display for Mode {
             Mode::VisualLine => write!(f, "VISUAL LINE"),
             Mode::VisualBlock => write!(f, "VISUAL BLOCK"),
             Mode::HelixNormal => write!(f, "HELIX NORMAL"),
+            Mode::HelixSelect => write!(f, "HELIX SELECT"),
         }
     }
 }
@@ -64,9 +68,15 @@ impl Mode {
     pub fn is_visual(&self) -> bool {
         match self {
             Self::Visual | Self::VisualLine | Self::VisualBlock => true,
-            Self::Normal | Self::Insert | Self::Replace | Self::HelixNormal => false,
+            Self::Normal | Self::Insert | Self::Replace | Self::HelixNormal | Self::HelixSelect => {
+                false
+            }
         }
     }
+
+    pub fn is_helix(&self) -> bool {
+        matches!(self, Self::HelixNormal | Self::HelixSelect)
+    }
 }
 
 impl Default for Mode {
@@ -130,6 +140,7 @@ pub enum Operator {
     ToggleComments,
     ReplaceWithRegister,
     Exchange,
+    ConfirmSubstitute,
 }
 
 #[derive(Default, Clone, Debug)]
@@ -194,6 +205,8 @@ impl From<String> for Register {
 #[derive(Default)]
 pub struct VimGlobals {
     pub last_find: Option<Motion>,
+    /// The search pattern of the last `:s`, used by the `\&` range address.
+    pub last_substitute_pattern: Option<String>,
 
     pub dot_recording: bool,
     pub dot_replaying: bool,
@@ -218,12 +231,56 @@ pub struct VimGlobals {
     pub last_yank: Option<SharedString>,
     pub registers: HashMap<char, Register>,
     pub recordings: HashMap<char, Vec<ReplayableAction>>,
+    /// The keystrokes typed while recording each macro, which is what gets persisted.
+    pub recorded_keystrokes: HashMap<char, Vec<Keystroke>>,
+    /// The keystrokes of a multi-stroke binding that is still being typed.
+    pub pending_keystrokes: Vec<Keystroke>,
+    pub ignore_current_keystroke: bool,
+
+    /// `:` commands and `/` searches, oldest first.
+    pub command_history: VecDeque<String>,
+    pub search_history: VecDeque<String>,
 
     pub focused_vim: Option<WeakEntity<Vim>>,
 
     pub marks: HashMap<EntityId, Entity<MarksState>>,
 }
 
+/// How many `:` commands and `/` searches are remembered.
+const MAX_HISTORY_LEN: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HistoryKind {
+    Command,
+    Search,
+}
+
+impl HistoryKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HistoryKind::Command => ":",
+            HistoryKind::Search => "/",
+        }
+    }
+
+    fn from_str(kind: &str) -> Option<Self> {
+        match kind {
+            ":" => Some(HistoryKind::Command),
+            "/" => Some(HistoryKind::Search),
+            _ => None,
+        }
+    }
+}
+
+/// Adds an entry to the end of a history, removing any earlier copy of it.
+fn add_to_history(history: &mut VecDeque<String>, entry: String) {
+    history.retain(|existing| existing != &entry);
+    if history.len() >= MAX_HISTORY_LEN {
+        history.pop_front();
+    }
+    history.push_back(entry);
+}
+
 pub struct MarksState {
     workspace: WeakEntity<Workspace>,
 
@@ -279,7 +336,11 @@ impl MarksState {
         })
     }
 
+    /// The id that marks are persisted under, or `None` if marks aren't persisted.
     fn workspace_id(&self, cx: &App) -> Option<WorkspaceId> {
+        if !VimSettings::get_global(cx).persist.marks {
+            return None;
+        }
         self.workspace
             .read_with(cx, |workspace, _| workspace.database_id())
             .ok()
@@ -676,6 +737,7 @@ impl VimGlobals {
         cx.set_global(VimGlobals::default());
 
         cx.observe_keystrokes(|event, _, cx| {
+            Vim::globals(cx).observe_keystroke(&event.keystroke);
             let Some(action) = event.action.as_ref().map(|action| action.boxed_clone()) else {
                 return;
             };
@@ -708,7 +770,24 @@ impl VimGlobals {
                 });
                 CommandPaletteInterceptor::update_global(cx, |interceptor, _| {
                     interceptor.set(Box::new(command_interceptor));
+                    interceptor.set_confirm_handler(Rc::new(|query, cx| {
+                        Vim::update_globals(cx, |globals, cx| {
+                            globals.add_command_history(query, cx)
+                        })
+                    }));
+                    interceptor.set_history_handler(Rc::new(|cx| {
+                        cx.try_global::<VimGlobals>()
+                            .map(|globals| {
+                                globals
+                                    .command_history
+                                    .iter()
+                                    .map(|command| format!(":{command}"))
+                                    .collect()
+                            })
+                            .unwrap_or_default()
+                    }));
                 });
+                VimGlobals::load_persisted(cx);
                 for window in cx.windows() {
                     if let Some(workspace) = window.downcast::<Workspace>() {
                         workspace
@@ -790,6 +869,7 @@ impl VimGlobals {
                     }
                 }
             }
+            self.persist_register(lower, cx);
         } else {
             let setting = VimSettings::get_global(cx).use_system_clipboard;
             if setting == UseSystemClipboard::Always
@@ -843,7 +923,11 @@ impl VimGlobals {
         };
         let lower = register.to_lowercase().next().unwrap_or(register);
         match lower {
-            '_' | ':' | '.' | '#' | '=' => None,
+            '_' | '.' | '#' | '=' => None,
+            ':' => self
+                .command_history
+                .back()
+                .map(|command| command.clone().into()),
             '+' => cx.read_from_clipboard().map(|item| item.into()),
             '*' => {
                 #[cfg(any(target_os = "linux", target_os = "freebsd"))]
@@ -931,6 +1015,167 @@ impl VimGlobals {
         }
     }
 
+    pub fn observe_keystroke(&mut self, keystroke: &Keystroke) {
+        let pending_keystrokes = std::mem::take(&mut self.pending_keystrokes);
+        if self.ignore_current_keystroke {
+            self.ignore_current_keystroke = false;
+            return;
+        }
+        if self.replayer.is_some() {
+            return;
+        }
+        if let Some(recording_register) = self.recording_register {
+            let keystrokes = self
+                .recorded_keystrokes
+                .entry(recording_register)
+                .or_default();
+            keystrokes.extend(pending_keystrokes);
+            keystrokes.push(keystroke.clone());
+        }
+    }
+
+    pub(crate) fn add_command_history(&mut self, command: &str, cx: &mut App) {
+        let command = command.trim_start_matches(':').trim();
+        if command.is_empty() {
+            return;
+        }
+        add_to_history(&mut self.command_history, command.to_string());
+        self.persist_history(HistoryKind::Command, command.to_string(), cx);
+    }
+
+    pub(crate) fn add_search_history(&mut self, query: String, cx: &mut App) {
+        if query.is_empty() {
+            return;
+        }
+        add_to_history(&mut self.search_history, query.clone());
+        self.persist_history(HistoryKind::Search, query, cx);
+    }
+
+    fn persist_register(&self, register: char, cx: &mut App) {
+        if !register.is_ascii_lowercase() || !VimSettings::get_global(cx).persist.registers {
+            return;
+        }
+        let Some(content) = self.registers.get(&register) else {
+            return;
+        };
+        let text = content.text.to_string();
+        let clipboard_selections = content.clipboard_selections.clone();
+        cx.background_spawn(async move {
+            let clipboard_selections = clipboard_selections
+                .map(|selections| serde_json::to_string(&selections))
+                .transpose()?;
+            DB.set_register(register.to_string(), text, clipboard_selections)
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn persist_recording(&self, register: char, cx: &mut App) {
+        if !VimSettings::get_global(cx).persist.macros {
+            return;
+        }
+        let keystrokes = self
+            .recorded_keystrokes
+            .get(&register)
+            .into_iter()
+            .flatten()
+            .map(|keystroke| (keystroke.unparse(), keystroke.key_char.clone()))
+            .collect::<Vec<_>>();
+        cx.background_spawn(async move {
+            let keystrokes = serde_json::to_string(&keystrokes)?;
+            DB.set_recording(register.to_string(), keystrokes).await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn persist_history(&self, kind: HistoryKind, entry: String, cx: &mut App) {
+        if !VimSettings::get_global(cx).persist.history {
+            return;
+        }
+        cx.background_spawn(async move { DB.add_history_entry(kind, entry).await })
+            .detach_and_log_err(cx);
+    }
+
+    fn load_persisted(cx: &mut App) {
+        cx.spawn(async move |cx| {
+            let persisted = cx
+                .background_spawn(async move {
+                    anyhow::Ok(PersistedState {
+                        registers: DB.get_registers()?,
+                        recordings: DB.get_recordings()?,
+                        history: DB.get_history()?,
+                    })
+                })
+                .await?;
+            cx.update(|cx| Vim::update_globals(cx, |globals, cx| globals.restore(persisted, cx)))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn restore(&mut self, persisted: PersistedState, cx: &mut App) {
+        let persist = VimSettings::get_global(cx).persist;
+        if persist.registers {
+            for register in persisted.registers {
+                let Some(name) = register.name.chars().next() else {
+                    continue;
+                };
+                self.registers.entry(name).or_insert(Register {
+                    text: register.text.into(),
+                    clipboard_selections: register.clipboard_selections,
+                });
+            }
+        }
+        if persist.macros {
+            for (name, keystrokes) in persisted.recordings {
+                let Some(name) = name.chars().next() else {
+                    continue;
+                };
+                if self.recordings.contains_key(&name) {
+                    continue;
+                }
+                let Some(keystrokes) =
+                    serde_json::from_str::<Vec<(String, Option<String>)>>(&keystrokes).log_err()
+                else {
+                    continue;
+                };
+                let keystrokes = keystrokes
+                    .into_iter()
+                    .filter_map(|(keystroke, key_char)| {
+                        let mut keystroke = Keystroke::parse(&keystroke).log_err()?;
+                        keystroke.key_char = key_char;
+                        Some(keystroke)
+                    })
+                    .collect::<Vec<_>>();
+                self.recordings.insert(
+                    name,
+                    keystrokes
+                        .iter()
+                        .cloned()
+                        .map(ReplayableAction::Keystroke)
+                        .collect(),
+                );
+                self.recorded_keystrokes.insert(name, keystrokes);
+            }
+        }
+        if persist.history {
+            let command_history = std::mem::take(&mut self.command_history);
+            let search_history = std::mem::take(&mut self.search_history);
+            for (kind, entry) in persisted.history {
+                match kind {
+                    HistoryKind::Command => add_to_history(&mut self.command_history, entry),
+                    HistoryKind::Search => add_to_history(&mut self.search_history, entry),
+                }
+            }
+            // anything from this session goes after the persisted history
+            for entry in command_history {
+                add_to_history(&mut self.command_history, entry);
+            }
+            for entry in search_history {
+                add_to_history(&mut self.search_history, entry);
+            }
+        }
+    }
+
     pub fn focused_vim(&self) -> Option<Entity<Vim>> {
         self.focused_vim.as_ref().and_then(|vim| vim.upgrade())
     }
@@ -956,6 +1201,7 @@ pub enum ReplayableAction {
         text: Arc<str>,
         utf16_range_to_replace: Option<Range<isize>>,
     },
+    Keystroke(Keystroke),
 }
 
 impl Clone for ReplayableAction {
@@ -969,6 +1215,7 @@ impl Clone for ReplayableAction {
                 text: text.clone(),
                 utf16_range_to_replace: utf16_range_to_replace.clone(),
             },
+            Self::Keystroke(keystroke) => Self::Keystroke(keystroke.clone()),
         }
     }
 }
@@ -981,6 +1228,16 @@ pub struct SearchState {
     pub prior_selections: Vec<Range<Anchor>>,
     pub prior_operator: Option<Operator>,
     pub prior_mode: Mode,
+    pub select_regex: Option<RegexSelection>,
+}
+
+/// What helix's `s` and `S` do with the regex matches inside each selection.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RegexSelection {
+    /// Select each match.
+    Matches,
+    /// Select the text between matches.
+    Splits,
 }
 
 impl Operator {
@@ -1022,6 +1279,7 @@ impl Operator {
             Operator::RecordRegister => "q",
             Operator::ReplayRegister => "@",
             Operator::ToggleComments => "gc",
+            Operator::ConfirmSubstitute => "s///c",
         }
     }
 
@@ -1041,7 +1299,9 @@ impl Operator {
 
     pub fn is_waiting(&self, mode: Mode) -> bool {
         match self {
-            Operator::AddSurrounds { target } => target.is_some() || mode.is_visual(),
+            Operator::AddSurrounds { target } => {
+                target.is_some() || mode.is_visual() || mode.is_helix()
+            }
             Operator::FindForward { .. }
             | Operator::Mark
             | Operator::Jump { .. }
@@ -1055,7 +1315,8 @@ impl Operator {
             | Operator::Digraph { .. }
             | Operator::Literal { .. }
             | Operator::ChangeSurrounds { target: Some(_) }
-            | Operator::DeleteSurrounds => true,
+            | Operator::DeleteSurrounds
+            | Operator::ConfirmSubstitute => true,
             Operator::Change
             | Operator::Delete
             | Operator::Yank
@@ -1112,7 +1373,8 @@ impl Operator {
             | Operator::Jump { .. }
             | Operator::Register
             | Operator::RecordRegister
-            | Operator::ReplayRegister => false,
+            | Operator::ReplayRegister
+            | Operator::ConfirmSubstitute => false,
         }
     }
 }
@@ -1684,9 +1946,40 @@ define_connection! (
             CREATE UNIQUE INDEX idx_vim_global_marks_paths
             ON vim_global_marks_paths(workspace_id, mark_name);
         ),
+        sql! (
+            CREATE TABLE vim_registers(
+                register_name TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                clipboard_selections TEXT
+            );
+            CREATE TABLE vim_recordings(
+                register_name TEXT PRIMARY KEY,
+                keystrokes TEXT NOT NULL
+            );
+            CREATE TABLE vim_history(
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                entry TEXT NOT NULL
+            );
+        ),
     ];
 );
 
+pub(crate) struct SerializedRegister {
+    name: String,
+    text: String,
+    clipboard_selections: Option<Vec<ClipboardSelection>>,
+}
+
+/// The vim state kept across restarts, as it is stored in the database.
+#[derive(Default)]
+pub(crate) struct PersistedState {
+    pub registers: Vec<SerializedRegister>,
+    /// Each register's keystrokes, as a JSON list of `(keystroke, key_char)` pairs.
+    pub recordings: Vec<(String, String)>,
+    pub history: Vec<(HistoryKind, String)>,
+}
+
 struct SerializedMark {
     path: Arc<Path>,
     name: String,
@@ -1801,4 +2094,88 @@ impl VimDb {
         })
         .await
     }
+
+    pub(crate) async fn set_register(
+        &self,
+        register_name: String,
+        value: String,
+        clipboard_selections: Option<String>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                INSERT OR REPLACE INTO vim_registers
+                    (register_name, value, clipboard_selections)
+                VALUES
+                    (?, ?, ?)
+            ))?((register_name, value, clipboard_selections))
+        })
+        .await
+    }
+
+    fn get_registers(&self) -> Result<Vec<SerializedRegister>> {
+        let result: Vec<(String, String, Option<String>)> = self.select(sql!(
+            SELECT register_name, value, clipboard_selections FROM vim_registers
+        ))?()?;
+
+        Ok(result
+            .into_iter()
+            .map(|(name, text, clipboard_selections)| SerializedRegister {
+                name,
+                text,
+                clipboard_selections: clipboard_selections
+                    .and_then(|selections| serde_json::from_str(&selections).log_err()),
+            })
+            .collect())
+    }
+
+    pub(crate) async fn set_recording(
+        &self,
+        register_name: String,
+        keystrokes: String,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                INSERT OR REPLACE INTO vim_recordings
+                    (register_name, keystrokes)
+                VALUES
+                    (?, ?)
+            ))?((register_name, keystrokes))
+        })
+        .await
+    }
+
+    fn get_recordings(&self) -> Result<Vec<(String, String)>> {
+        self.select(sql!(
+            SELECT register_name, keystrokes FROM vim_recordings
+        ))?()
+    }
+
+    pub(crate) async fn add_history_entry(&self, kind: HistoryKind, entry: String) -> Result<()> {
+        let kind = kind.as_str();
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                DELETE FROM vim_history WHERE kind = ? AND entry = ?
+            ))?((kind, entry.clone()))?;
+            conn.exec_bound(sql!(
+                INSERT INTO vim_history (kind, entry) VALUES (?, ?)
+            ))?((kind, entry))?;
+            conn.exec_bound(sql!(
+                DELETE FROM vim_history WHERE kind = ?1 AND id NOT IN (
+                    SELECT id FROM vim_history WHERE kind = ?1 ORDER BY id DESC LIMIT ?2
+                )
+            ))?((kind, MAX_HISTORY_LEN as i64))
+        })
+        .await
+    }
+
+    fn get_history(&self) -> Result<Vec<(HistoryKind, String)>> {
+        let result: Vec<(String, String)> = self.select(sql!(
+            SELECT kind, entry FROM vim_history ORDER BY id
+        ))?()?;
+
+        Ok(result
+            .into_iter()
+            .filter_map(|(kind, entry)| Some((HistoryKind::from_str(&kind)?, entry)))
+            .collect())
+    }
 }
@@ -118,7 +118,9 @@ impl Vim {
                 });
             });
         });
-        self.switch_mode(Mode::Normal, false, window, cx);
+        if !mode.is_helix() {
+            self.switch_mode(Mode::Normal, false, window, cx);
+        }
     }
 
     pub fn delete_surrounds(
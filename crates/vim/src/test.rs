@@ -1,3 +1,4 @@
+mod helix_backed_test_context;
 mod neovim_backed_test_context;
 mod neovim_connection;
 mod vim_test_context;
@@ -12,6 +13,7 @@ use editor::{
 };
 use futures::StreamExt;
 use gpui::{KeyBinding, Modifiers, MouseButton, TestAppContext};
+pub use helix_backed_test_context::*;
 use language::Point;
 pub use neovim_backed_test_context::*;
 use settings::SettingsStore;
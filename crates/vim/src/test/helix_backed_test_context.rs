@@ -0,0 +1,184 @@
+use collections::VecDeque;
+use indoc::indoc;
+use serde::{Deserialize, Serialize};
+use std::{
+    ops::{Deref, DerefMut},
+    path::PathBuf,
+    thread,
+};
+
+use super::VimTestContext;
+use crate::state::Mode;
+
+// Helix can't be embedded the way neovim is, so its behavior is recorded by hand: each test
+// replays `test_data/helix/<test name>.json`, which lists the state the test starts from, the
+// keystrokes sent and the state helix ended up in, in the same format as the neovim data.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+enum HelixData {
+    Put { state: String },
+    Key(String),
+    Get { state: String, mode: Mode },
+}
+
+pub struct HelixBackedTestContext {
+    cx: VimTestContext,
+    test_case_id: String,
+    data: VecDeque<HelixData>,
+
+    last_set_state: Option<String>,
+    recent_keystrokes: Vec<String>,
+}
+
+pub struct HelixSharedState {
+    helix: String,
+    editor: String,
+    initial: String,
+    helix_mode: Mode,
+    editor_mode: Mode,
+    recent_keystrokes: String,
+}
+
+impl HelixSharedState {
+    #[track_caller]
+    pub fn assert_eq(&self, marked_text: &str) {
+        if self.helix == marked_text
+            && self.helix == self.editor
+            && self.helix_mode == self.editor_mode
+        {
+            return;
+        }
+
+        let message = if self.helix != marked_text {
+            "Test is incorrect (currently expected != recorded helix state)"
+        } else {
+            "Editor does not match helix behavior"
+        };
+        panic!(
+            indoc! {"{}
+                # initial state:
+                {}
+                # keystrokes:
+                {}
+                # currently expected:
+                {}
+                # helix ({}):
+                {}
+                # zed ({}):
+                {}"},
+            message,
+            self.initial,
+            self.recent_keystrokes,
+            marked_text,
+            self.helix_mode,
+            self.helix,
+            self.editor_mode,
+            self.editor,
+        )
+    }
+}
+
+impl HelixBackedTestContext {
+    pub async fn new(cx: &mut gpui::TestAppContext) -> HelixBackedTestContext {
+        // the test data is named after the test, which rust stores on the current thread.
+        let test_case_id = thread::current()
+            .name()
+            .expect("thread is not named")
+            .split(':')
+            .next_back()
+            .unwrap()
+            .to_string();
+        Self {
+            cx: VimTestContext::new(cx, true).await,
+            data: Self::read_test_data(&test_case_id),
+            test_case_id,
+
+            last_set_state: None,
+            recent_keystrokes: Default::default(),
+        }
+    }
+
+    fn read_test_data(test_case_id: &str) -> VecDeque<HelixData> {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("test_data/helix");
+        path.push(format!("{}.json", test_case_id));
+        let json = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("Could not read recorded helix data at {path:?}"));
+
+        json.lines()
+            .map(|line| serde_json::from_str(line).expect("invalid recorded helix data"))
+            .collect()
+    }
+
+    #[track_caller]
+    fn next_data(&mut self) -> HelixData {
+        self.data.pop_front().unwrap_or_else(|| {
+            panic!(
+                "{} has no more recorded helix data; record the rest of the test",
+                self.test_case_id
+            )
+        })
+    }
+
+    #[track_caller]
+    pub async fn set_shared_state(&mut self, marked_text: &str) {
+        let recorded = self.next_data();
+        assert_eq!(
+            recorded,
+            HelixData::Put {
+                state: marked_text.to_string()
+            },
+            "recorded helix data starts from a different state"
+        );
+        self.set_state(marked_text, Mode::HelixNormal);
+        self.last_set_state = Some(marked_text.to_string());
+        self.recent_keystrokes = Vec::new();
+    }
+
+    #[track_caller]
+    pub async fn simulate_shared_keystrokes(&mut self, keystroke_texts: &str) {
+        for keystroke_text in keystroke_texts.split(' ') {
+            let recorded = self.next_data();
+            assert_eq!(
+                recorded,
+                HelixData::Key(keystroke_text.to_string()),
+                "recorded helix data sends different keystrokes"
+            );
+            self.recent_keystrokes.push(keystroke_text.to_string());
+        }
+        self.simulate_keystrokes(keystroke_texts);
+    }
+
+    #[must_use]
+    #[track_caller]
+    pub async fn shared_state(&mut self) -> HelixSharedState {
+        let HelixData::Get { state, mode } = self.next_data() else {
+            panic!("recorded helix data has no state to compare with here");
+        };
+        HelixSharedState {
+            helix: state,
+            helix_mode: mode,
+            editor: self.editor_state(),
+            editor_mode: self.mode(),
+            initial: self
+                .last_set_state
+                .as_ref()
+                .cloned()
+                .unwrap_or("N/A".to_string()),
+            recent_keystrokes: self.recent_keystrokes.join(" "),
+        }
+    }
+}
+
+impl Deref for HelixBackedTestContext {
+    type Target = VimTestContext;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cx
+    }
+}
+
+impl DerefMut for HelixBackedTestContext {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.cx
+    }
+}
@@ -33,7 +33,7 @@ use insert::{NormalBefore, TemporaryNormal};
 use language::{CharKind, CursorShape, Point, Selection, SelectionGoal, TransactionId};
 pub use mode_indicator::ModeIndicator;
 use motion::Motion;
-use normal::search::SearchSubmit;
+use normal::search::{SearchSubmit, SubstituteConfirmation};
 use object::Object;
 use schemars::JsonSchema;
 use serde::Deserialize;
@@ -125,6 +125,7 @@ actions!(
         SwitchToVisualLineMode,
         SwitchToVisualBlockMode,
         SwitchToHelixNormalMode,
+        SwitchToHelixSelectMode,
         ClearOperators,
         ClearExchange,
         Tab,
@@ -321,6 +322,7 @@ pub(crate) struct Vim {
 
     operator_stack: Vec<Operator>,
     pub(crate) replacements: Vec<(Range<editor::Anchor>, String)>,
+    pub(crate) substitute_confirmation: Option<SubstituteConfirmation>,
 
     pub(crate) stored_visual_mode: Option<(Mode, Vec<bool>)>,
 
@@ -366,6 +368,7 @@ impl Vim {
             exit_temporary_mode: false,
             operator_stack: Vec::new(),
             replacements: Vec::new(),
+            substitute_confirmation: None,
 
             stored_visual_mode: None,
             current_tx: None,
@@ -382,6 +385,12 @@ impl Vim {
             editor: editor.downgrade(),
             _subscriptions: vec![
                 cx.observe_keystrokes(Self::observe_keystrokes),
+                cx.observe_pending_input(window, |_, window, cx| {
+                    Vim::globals(cx).pending_keystrokes = window
+                        .pending_input_keystrokes()
+                        .map(<[_]>::to_vec)
+                        .unwrap_or_default();
+                }),
                 cx.subscribe_in(&editor, window, |this, _, event, window, cx| {
                     this.handle_editor_event(event, window, cx)
                 }),
@@ -479,6 +488,13 @@ impl Vim {
                     vim.switch_mode(Mode::HelixNormal, false, window, cx)
                 },
             );
+            Vim::action(
+                editor,
+                cx,
+                |vim, _: &SwitchToHelixSelectMode, window, cx| {
+                    vim.switch_mode(Mode::HelixSelect, false, window, cx)
+                },
+            );
             Vim::action(editor, cx, |_, _: &PushForcedMotion, _, cx| {
                 Vim::globals(cx).forced_motion = true;
             });
@@ -909,6 +925,7 @@ impl Vim {
         self.status_label.take();
         self.last_mode = last_mode;
         self.mode = mode;
+        self.end_substitute_confirmation(window, cx);
         self.operator_stack.clear();
         self.selected_register.take();
         self.cancel_running_command(window, cx);
@@ -1044,7 +1061,8 @@ impl Vim {
                         | Operator::Jump { .. }
                         | Operator::Register
                         | Operator::RecordRegister
-                        | Operator::ReplayRegister => CursorShape::Block,
+                        | Operator::ReplayRegister
+                        | Operator::ConfirmSubstitute => CursorShape::Block,
 
                         // All other operators -> Underline cursor
                         _ => CursorShape::Underline,
@@ -1053,7 +1071,9 @@ impl Vim {
                     cursor_shape.normal.unwrap_or(CursorShape::Block)
                 }
             }
-            Mode::HelixNormal => cursor_shape.normal.unwrap_or(CursorShape::Block),
+            Mode::HelixNormal | Mode::HelixSelect => {
+                cursor_shape.normal.unwrap_or(CursorShape::Block)
+            }
             Mode::Replace => cursor_shape.replace.unwrap_or(CursorShape::Underline),
             Mode::Visual | Mode::VisualLine | Mode::VisualBlock => {
                 cursor_shape.visual.unwrap_or(CursorShape::Block)
@@ -1076,6 +1096,7 @@ impl Vim {
             }
             Mode::Normal
             | Mode::HelixNormal
+            | Mode::HelixSelect
             | Mode::Replace
             | Mode::Visual
             | Mode::VisualLine
@@ -1094,7 +1115,8 @@ impl Vim {
             | Mode::VisualLine
             | Mode::VisualBlock
             | Mode::Replace
-            | Mode::HelixNormal => false,
+            | Mode::HelixNormal
+            | Mode::HelixSelect => false,
             Mode::Normal => true,
         }
     }
@@ -1106,6 +1128,7 @@ impl Vim {
             Mode::Insert => "insert",
             Mode::Replace => "replace",
             Mode::HelixNormal => "helix_normal",
+            Mode::HelixSelect => "helix_select",
         }
         .to_string();
 
@@ -1122,6 +1145,8 @@ impl Vim {
             if active_operator.is_waiting(self.mode) {
                 if matches!(active_operator, Operator::Literal { .. }) {
                     mode = "literal".to_string();
+                } else if matches!(active_operator, Operator::ConfirmSubstitute) {
+                    mode = "confirm".to_string();
                 } else {
                     mode = "waiting".to_string();
                 }
@@ -1131,7 +1156,12 @@ impl Vim {
             }
         }
 
-        if mode == "normal" || mode == "visual" || mode == "operator" || mode == "helix_normal" {
+        if mode == "normal"
+            || mode == "visual"
+            || mode == "operator"
+            || mode == "helix_normal"
+            || mode == "helix_select"
+        {
             context.add("VimControl");
         }
         context.set("vim_mode", mode);
@@ -1396,6 +1426,7 @@ impl Vim {
         Vim::take_forced_motion(cx);
         self.selected_register.take();
         self.operator_stack.clear();
+        self.end_substitute_confirmation(window, cx);
         self.sync_vim_settings(window, cx);
     }
 
@@ -1470,7 +1501,7 @@ impl Vim {
                     })
                 });
             }
-            Mode::Insert | Mode::Replace | Mode::HelixNormal => {}
+            Mode::Insert | Mode::Replace | Mode::HelixNormal | Mode::HelixSelect => {}
         }
     }
 
@@ -1607,6 +1638,10 @@ impl Vim {
                     self.add_surrounds(text, SurroundsType::Selection, window, cx);
                     self.clear_operator(window, cx);
                 }
+                Mode::HelixNormal | Mode::HelixSelect => {
+                    self.helix_add_surrounds(text, window, cx);
+                    self.clear_operator(window, cx);
+                }
                 _ => self.clear_operator(window, cx),
             },
             Some(Operator::ChangeSurrounds { target }) => match self.mode {
@@ -1616,6 +1651,12 @@ impl Vim {
                         self.clear_operator(window, cx);
                     }
                 }
+                Mode::HelixNormal | Mode::HelixSelect => {
+                    if let Some(target) = target {
+                        self.helix_change_surrounds(text, target, window, cx);
+                        self.clear_operator(window, cx);
+                    }
+                }
                 _ => self.clear_operator(window, cx),
             },
             Some(Operator::DeleteSurrounds) => match self.mode {
@@ -1623,6 +1664,10 @@ impl Vim {
                     self.delete_surrounds(text, window, cx);
                     self.clear_operator(window, cx);
                 }
+                Mode::HelixNormal | Mode::HelixSelect => {
+                    self.helix_delete_surrounds(text, window, cx);
+                    self.clear_operator(window, cx);
+                }
                 _ => self.clear_operator(window, cx),
             },
             Some(Operator::Mark) => self.create_mark(text, window, cx),
@@ -1654,6 +1699,7 @@ impl Vim {
                 }
             },
             Some(Operator::Jump { line }) => self.jump(text, line, true, window, cx),
+            Some(Operator::ConfirmSubstitute) => self.confirm_substitute(text, window, cx),
             _ => {
                 if self.mode == Mode::Replace {
                     self.multi_replace(text, window, cx)
@@ -1724,6 +1770,39 @@ struct CursorShapeSettings {
     pub insert: Option<CursorShape>,
 }
 
+/// The settings for what is kept across restarts.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+struct PersistSettings {
+    /// Whether to keep the contents of the named registers, `a` to `z`.
+    ///
+    /// Default: true
+    pub registers: bool,
+    /// Whether to keep recorded macros.
+    ///
+    /// Default: true
+    pub macros: bool,
+    /// Whether to keep marks, including global marks.
+    ///
+    /// Default: true
+    pub marks: bool,
+    /// Whether to keep the history of `:` commands and `/` searches.
+    ///
+    /// Default: true
+    pub history: bool,
+}
+
+impl Default for PersistSettings {
+    fn default() -> Self {
+        Self {
+            registers: true,
+            macros: true,
+            marks: true,
+            history: true,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct VimSettings {
     pub default_mode: Mode,
@@ -1734,6 +1813,7 @@ struct VimSettings {
     pub custom_digraphs: HashMap<String, Arc<str>>,
     pub highlight_on_yank_duration: u64,
     pub cursor_shape: CursorShapeSettings,
+    pub persist: PersistSettings,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -1746,6 +1826,7 @@ struct VimSettingsContent {
     pub custom_digraphs: Option<HashMap<String, Arc<str>>>,
     pub highlight_on_yank_duration: Option<u64>,
     pub cursor_shape: Option<CursorShapeSettings>,
+    pub persist: Option<PersistSettings>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -1759,6 +1840,7 @@ pub enum ModeContent {
     VisualLine,
     VisualBlock,
     HelixNormal,
+    HelixSelect,
 }
 
 impl From<ModeContent> for Mode {
@@ -1771,6 +1853,7 @@ impl From<ModeContent> for Mode {
             ModeContent::VisualLine => Self::VisualLine,
             ModeContent::VisualBlock => Self::VisualBlock,
             ModeContent::HelixNormal => Self::HelixNormal,
+            ModeContent::HelixSelect => Self::HelixSelect,
         }
     }
 }
@@ -1805,6 +1888,7 @@ impl Settings for VimSettings {
                 .highlight_on_yank_duration
                 .ok_or_else(Self::missing_default)?,
             cursor_shape: settings.cursor_shape.ok_or_else(Self::missing_default)?,
+            persist: settings.persist.ok_or_else(Self::missing_default)?,
         })
     }
 
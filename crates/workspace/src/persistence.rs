@@ -13,7 +13,9 @@ use client::DevServerProjectId;
 use db::{define_connection, query, sqlez::connection::Connection, sqlez_macros::sql};
 use gpui::{Axis, Bounds, Task, WindowBounds, WindowId, point, size};
 use itertools::Itertools;
-use project::debugger::breakpoint_store::{BreakpointState, SourceBreakpoint};
+use project::debugger::breakpoint_store::{
+    BreakpointState, DataBreakpoint, DataBreakpointAccessType, FunctionBreakpoint, SourceBreakpoint,
+};
 
 use language::{LanguageName, Toolchain};
 use project::WorktreeId;
@@ -542,6 +544,40 @@ define_connection! {
         ALTER TABLE breakpoints ADD COLUMN condition TEXT;
         ALTER TABLE breakpoints ADD COLUMN hit_condition TEXT;
     ),
+    sql!(
+        CREATE TABLE function_breakpoints (
+            workspace_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            condition TEXT,
+            hit_condition TEXT,
+            state INTEGER DEFAULT(0) NOT NULL,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        );
+        CREATE TABLE data_breakpoints (
+            workspace_id INTEGER NOT NULL,
+            data_id TEXT NOT NULL,
+            description TEXT NOT NULL,
+            access_type INTEGER,
+            condition TEXT,
+            hit_condition TEXT,
+            state INTEGER DEFAULT(0) NOT NULL,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        );
+    ),
+    sql!(
+        CREATE TABLE watch_expressions (
+            workspace_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            expression TEXT NOT NULL,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        );
+    ),
     ];
 }
 
@@ -629,6 +665,9 @@ impl WorkspaceDb {
             display,
             docks,
             session_id: None,
+            function_breakpoints: self.function_breakpoints(workspace_id),
+            data_breakpoints: self.data_breakpoints(workspace_id),
+            watch_expressions: self.watch_expressions(workspace_id),
             breakpoints: self.breakpoints(workspace_id),
             window_id,
         })
@@ -687,6 +726,9 @@ impl WorkspaceDb {
             display,
             docks,
             session_id: None,
+            function_breakpoints: self.function_breakpoints(workspace_id),
+            data_breakpoints: self.data_breakpoints(workspace_id),
+            watch_expressions: self.watch_expressions(workspace_id),
             window_id,
         })
     }
@@ -737,6 +779,112 @@ impl WorkspaceDb {
         }
     }
 
+    fn function_breakpoints(&self, workspace_id: WorkspaceId) -> Vec<FunctionBreakpoint> {
+        let breakpoints: Result<
+            Vec<(
+                Arc<str>,
+                Option<Arc<str>>,
+                Option<Arc<str>>,
+                BreakpointStateWrapper,
+            )>,
+        > = self
+            .select_bound(sql! {
+                SELECT name, condition, hit_condition, state
+                FROM function_breakpoints
+                WHERE workspace_id = ?
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id));
+
+        match breakpoints {
+            Ok(breakpoints) => breakpoints
+                .into_iter()
+                .map(
+                    |(name, condition, hit_condition, state)| FunctionBreakpoint {
+                        name,
+                        condition,
+                        hit_condition,
+                        state: state.0.into_owned(),
+                    },
+                )
+                .collect(),
+            Err(msg) => {
+                log::error!("Function breakpoints query failed with msg: {msg}");
+                Default::default()
+            }
+        }
+    }
+
+    fn watch_expressions(&self, workspace_id: WorkspaceId) -> Vec<Arc<str>> {
+        let expressions: Result<Vec<Arc<str>>> = self
+            .select_bound(sql! {
+                SELECT expression
+                FROM watch_expressions
+                WHERE workspace_id = ?
+                ORDER BY position
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id));
+
+        match expressions {
+            Ok(expressions) => expressions,
+            Err(msg) => {
+                log::error!("Watch expressions query failed with msg: {msg}");
+                Default::default()
+            }
+        }
+    }
+
+    fn data_breakpoints(&self, workspace_id: WorkspaceId) -> Vec<DataBreakpoint> {
+        let breakpoints: Result<
+            Vec<(
+                Arc<str>,
+                Arc<str>,
+                Option<i32>,
+                Option<Arc<str>>,
+                Option<Arc<str>>,
+                BreakpointStateWrapper,
+            )>,
+        > = self
+            .select_bound(sql! {
+                SELECT data_id, description, access_type, condition, hit_condition, state
+                FROM data_breakpoints
+                WHERE workspace_id = ?
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id));
+
+        match breakpoints {
+            Ok(breakpoints) => breakpoints
+                .into_iter()
+                .map(
+                    |(data_id, description, access_type, condition, hit_condition, state)| {
+                        DataBreakpoint {
+                            data_id,
+                            description,
+                            access_type: access_type.and_then(|access_type| match access_type {
+                                0 => Some(DataBreakpointAccessType::Read),
+                                1 => Some(DataBreakpointAccessType::Write),
+                                2 => Some(DataBreakpointAccessType::ReadWrite),
+                                _ => {
+                                    log::error!(
+                                        "Invalid DataBreakpointAccessType discriminant {access_type}"
+                                    );
+                                    None
+                                }
+                            }),
+                            condition,
+                            hit_condition,
+                            state: state.0.into_owned(),
+                            session_id: None,
+                        }
+                    },
+                )
+                .collect(),
+            Err(msg) => {
+                log::error!("Data breakpoints query failed with msg: {msg}");
+                Default::default()
+            }
+        }
+    }
+
     /// Saves a workspace using the worktree roots. Will garbage collect any workspaces
     /// that used this workspace previously
     pub(crate) async fn save_workspace(&self, workspace: SerializedWorkspace) {
@@ -779,6 +927,47 @@ impl WorkspaceDb {
 
                 }
 
+                conn.exec_bound(sql!(
+                    DELETE FROM function_breakpoints WHERE workspace_id = ?1;
+                    DELETE FROM data_breakpoints WHERE workspace_id = ?1;))?(workspace.id)
+                    .context("Clearing old function and data breakpoints")?;
+
+                for bp in workspace.function_breakpoints {
+                    let state = BreakpointStateWrapper::from(bp.state);
+                    conn.exec_bound(sql!(
+                        INSERT INTO function_breakpoints (workspace_id, name, condition, hit_condition, state)
+                        VALUES (?1, ?2, ?3, ?4, ?5);))?
+                    ((workspace.id, bp.name, bp.condition, bp.hit_condition, state))
+                    .log_err();
+                }
+
+                // Data ids that are only valid within a debug session are not worth keeping.
+                for bp in workspace.data_breakpoints.into_iter().filter(|bp| bp.session_id.is_none()) {
+                    let state = BreakpointStateWrapper::from(bp.state);
+                    conn.exec_bound(sql!(
+                        INSERT INTO data_breakpoints (workspace_id, data_id, description, access_type, condition, hit_condition, state)
+                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7);))?
+                    ((
+                        workspace.id,
+                        bp.data_id,
+                        bp.description,
+                        bp.access_type.map(|access_type| access_type.to_int()),
+                        bp.condition,
+                        bp.hit_condition,
+                        state,
+                    ))
+                    .log_err();
+                }
+
+                conn.exec_bound(sql!(DELETE FROM watch_expressions WHERE workspace_id = ?1))?(workspace.id)
+                    .context("Clearing old watch expressions")?;
+                for (position, expression) in workspace.watch_expressions.into_iter().enumerate() {
+                    conn.exec_bound(sql!(
+                        INSERT INTO watch_expressions (workspace_id, position, expression)
+                        VALUES (?1, ?2, ?3);))?
+                    ((workspace.id, position as i64, expression))
+                    .log_err();
+                }
 
                 match workspace.location {
                     SerializedWorkspaceLocation::Local(local_paths, local_paths_order) => {
@@ -1571,6 +1760,9 @@ mod tests {
                 map
             },
             session_id: None,
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: None,
         };
 
@@ -1690,6 +1882,9 @@ mod tests {
                 map
             },
             session_id: None,
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: None,
         };
 
@@ -1722,6 +1917,9 @@ mod tests {
             centered_layout: false,
             breakpoints: collections::BTreeMap::default(),
             session_id: None,
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: None,
         };
 
@@ -1736,6 +1934,126 @@ mod tests {
         assert!(empty_breakpoints.is_none());
     }
 
+    #[gpui::test]
+    async fn test_function_and_data_breakpoints() {
+        zlog::init_test();
+
+        let db = WorkspaceDb::open_test_db("test_function_and_data_breakpoints").await;
+        let id = db.next_id().await.unwrap();
+
+        let function_breakpoints = vec![
+            FunctionBreakpoint {
+                name: "main".into(),
+                condition: None,
+                hit_condition: None,
+                state: BreakpointState::Enabled,
+            },
+            FunctionBreakpoint {
+                name: "std::panicking::begin_panic".into(),
+                condition: Some("x > 5".into()),
+                hit_condition: Some(">= 3".into()),
+                state: BreakpointState::Disabled,
+            },
+        ];
+        let persistent_data_breakpoint = DataBreakpoint {
+            data_id: "0x1000/4".into(),
+            description: "counter".into(),
+            access_type: Some(DataBreakpointAccessType::Write),
+            condition: None,
+            hit_condition: None,
+            state: BreakpointState::Enabled,
+            session_id: None,
+        };
+        let session_data_breakpoint = DataBreakpoint {
+            data_id: "1000:counter".into(),
+            description: "counter".into(),
+            access_type: None,
+            condition: None,
+            hit_condition: None,
+            state: BreakpointState::Enabled,
+            session_id: Some(dap::client::SessionId(1)),
+        };
+
+        let workspace = SerializedWorkspace {
+            id,
+            location: SerializedWorkspaceLocation::from_local_paths(["/tmp"]),
+            center_group: Default::default(),
+            window_bounds: Default::default(),
+            display: Default::default(),
+            docks: Default::default(),
+            centered_layout: false,
+            breakpoints: Default::default(),
+            session_id: None,
+            function_breakpoints: function_breakpoints.clone(),
+            data_breakpoints: vec![persistent_data_breakpoint.clone(), session_data_breakpoint],
+            watch_expressions: Default::default(),
+            window_id: None,
+        };
+
+        db.save_workspace(workspace.clone()).await;
+
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(loaded.function_breakpoints, function_breakpoints);
+        assert_eq!(
+            loaded.data_breakpoints,
+            vec![persistent_data_breakpoint],
+            "Only data breakpoints that stay valid across sessions should be saved"
+        );
+
+        db.save_workspace(SerializedWorkspace {
+            function_breakpoints: Vec::new(),
+            data_breakpoints: Vec::new(),
+            watch_expressions: Default::default(),
+            ..workspace
+        })
+        .await;
+
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert!(loaded.function_breakpoints.is_empty());
+        assert!(loaded.data_breakpoints.is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_watch_expressions() {
+        zlog::init_test();
+
+        let db = WorkspaceDb::open_test_db("test_watch_expressions").await;
+        let id = db.next_id().await.unwrap();
+
+        let watch_expressions: Vec<Arc<str>> =
+            vec!["self.len()".into(), "a + b".into(), "*ptr".into()];
+        let workspace = SerializedWorkspace {
+            id,
+            location: SerializedWorkspaceLocation::from_local_paths(["/tmp"]),
+            center_group: Default::default(),
+            window_bounds: Default::default(),
+            display: Default::default(),
+            docks: Default::default(),
+            centered_layout: false,
+            breakpoints: Default::default(),
+            session_id: None,
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: watch_expressions.clone(),
+            window_id: None,
+        };
+
+        db.save_workspace(workspace.clone()).await;
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(
+            loaded.watch_expressions, watch_expressions,
+            "Watch expressions should be loaded in the order they were added in"
+        );
+
+        db.save_workspace(SerializedWorkspace {
+            watch_expressions: vec!["*ptr".into()],
+            ..workspace
+        })
+        .await;
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(loaded.watch_expressions, vec![Arc::<str>::from("*ptr")]);
+    }
+
     #[gpui::test]
     async fn test_next_id_stability() {
         zlog::init_test();
@@ -1816,6 +2134,9 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: None,
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: None,
         };
 
@@ -1829,6 +2150,9 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: None,
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: None,
         };
 
@@ -1937,6 +2261,9 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             session_id: None,
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: Some(999),
         };
 
@@ -1972,6 +2299,9 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             session_id: None,
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: Some(1),
         };
 
@@ -1985,6 +2315,9 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: None,
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: Some(2),
         };
 
@@ -2028,6 +2361,9 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             session_id: None,
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: Some(3),
         };
 
@@ -2065,6 +2401,9 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: Some("session-id-1".to_owned()),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: Some(10),
         };
 
@@ -2078,6 +2417,9 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: Some("session-id-1".to_owned()),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: Some(20),
         };
 
@@ -2091,6 +2433,9 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: Some("session-id-2".to_owned()),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: Some(30),
         };
 
@@ -2104,6 +2449,9 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: None,
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: None,
         };
 
@@ -2122,6 +2470,9 @@ mod tests {
             centered_layout: false,
             breakpoints: Default::default(),
             session_id: Some("session-id-2".to_owned()),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: Some(50),
         };
 
@@ -2138,6 +2489,9 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             session_id: Some("session-id-3".to_owned()),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: Some(60),
         };
 
@@ -2194,6 +2548,9 @@ mod tests {
             breakpoints: Default::default(),
             centered_layout: false,
             session_id: None,
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             window_id: None,
         }
     }
@@ -2239,6 +2596,9 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             breakpoints: Default::default(),
             window_id: Some(window_id),
         })
@@ -2332,6 +2692,9 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
+            watch_expressions: Default::default(),
             breakpoints: Default::default(),
             window_id: Some(window_id),
         })
@@ -1,14 +1,61 @@
 use std::process::ExitStatus;
 
-use anyhow::Result;
-use gpui::{AppContext, Context, Entity, Task};
+use anyhow::{Context as _, Result};
+use futures::{
+    FutureExt as _,
+    future::{self, LocalBoxFuture, Shared},
+};
+use gpui::{AppContext, AsyncWindowContext, Context, Entity, Task};
 use language::Buffer;
 use project::TaskSourceKind;
 use remote::ConnectionState;
-use task::{DebugScenario, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
+use task::{
+    DebugScenario, DependsOrder, ResolvedTask, RevealStrategy, SpawnInTerminal, TaskContext,
+    TaskTemplate,
+};
 use ui::Window;
+use util::ResultExt as _;
 
-use crate::Workspace;
+use crate::{Event, Workspace};
+
+/// Progress of a task run along with the tasks it depends on.
+pub struct TaskGraphRun {
+    /// Label of the task the run was started for.
+    pub label: String,
+    /// Labels and statuses of all tasks in the run, dependencies first.
+    pub tasks: Vec<(String, TaskRunStatus)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskRunStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    /// The task did not run, as some of the tasks it depends on failed.
+    Skipped,
+}
+
+impl TaskGraphRun {
+    pub fn is_finished(&self) -> bool {
+        self.tasks
+            .iter()
+            .all(|(_, status)| !matches!(status, TaskRunStatus::Pending | TaskRunStatus::Running))
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.tasks
+            .iter()
+            .all(|(_, status)| *status == TaskRunStatus::Succeeded)
+    }
+}
+
+/// A task of a [`TaskGraphRun`], resolved and ready to run.
+struct TaskGraphStep {
+    task: ResolvedTask,
+    dependencies: Vec<usize>,
+    depends_order: DependsOrder,
+}
 
 impl Workspace {
     pub fn schedule_task(
@@ -55,6 +102,21 @@ impl Workspace {
         cx: &mut Context<Workspace>,
     ) {
         let spawn_in_terminal = resolved_task.resolved.clone();
+        if !resolved_task.original_task().watch.is_empty() {
+            let task_store = self.project.read(cx).task_store().clone();
+            let watched = task_store.update(cx, |task_store, cx| {
+                task_store.watch_task(task_source_kind.clone(), resolved_task.clone(), cx)
+            });
+            if let Err(e) = watched {
+                log::error!("Failed to watch task files: {e:#}");
+                self.show_error(&format!("{e:#}"), cx);
+            }
+        }
+        let task_graph = if resolved_task.original_task().depends_on.is_empty() {
+            None
+        } else {
+            Some(self.resolve_task_graph(task_source_kind.clone(), resolved_task.clone(), cx))
+        };
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
                 debugger_provider.task_scheduled(cx);
@@ -71,7 +133,15 @@ impl Workspace {
             });
         }
 
-        if let Some(terminal_provider) = self.terminal_provider.as_ref() {
+        if let Some(task_graph) = task_graph {
+            match task_graph {
+                Ok(steps) => self.run_task_graph(steps, window, cx),
+                Err(e) => {
+                    log::error!("Failed to resolve task dependencies: {e:#}");
+                    self.show_error(&format!("{e:#}"), cx);
+                }
+            }
+        } else if let Some(terminal_provider) = self.terminal_provider.as_ref() {
             let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
             cx.background_spawn(async move {
                 match task_status.await {
@@ -90,6 +160,202 @@ impl Workspace {
         }
     }
 
+    /// Restarts a task after the files it watches changed, replacing its previous run.
+    pub(crate) fn restart_watched_task(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        mut task: ResolvedTask,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Reuse the task's terminal, terminating the previous run if it is still going.
+        task.resolved.allow_concurrent_runs = true;
+        task.resolved.use_new_terminal = false;
+        // The files are being edited, do not take the focus away from them.
+        if task.resolved.reveal == RevealStrategy::Always {
+            task.resolved.reveal = RevealStrategy::NoFocus;
+        }
+        self.schedule_resolved_task(task_source_kind, task, true, window, cx);
+    }
+
+    /// Resolves the tasks the task given depends on with the same context as the task itself.
+    fn resolve_task_graph(
+        &self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        cx: &Context<Self>,
+    ) -> Result<Vec<TaskGraphStep>> {
+        let project = self.project.read(cx);
+        let task_inventory = project
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned()
+            .context("tasks are not available in this project")?;
+        let worktree = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => resolved_task
+                .task_context()
+                .cwd
+                .as_ref()
+                .and_then(|cwd| project.find_worktree(cwd, cx))
+                .map(|(worktree, _)| worktree.read(cx).id()),
+        };
+        let task_graph = task_inventory.read(cx).task_graph(
+            task_source_kind,
+            resolved_task.original_task().clone(),
+            worktree,
+            cx,
+        )?;
+
+        let task_count = task_graph.tasks.len();
+        let mut resolved_task = Some(resolved_task);
+        task_graph
+            .tasks
+            .into_iter()
+            .enumerate()
+            .map(|(ix, node)| {
+                // The task itself is already resolved, possibly with overrides applied.
+                let task = if ix + 1 == task_count {
+                    resolved_task.take()
+                } else {
+                    resolved_task.as_ref().and_then(|resolved_task| {
+                        node.template.resolve_task(
+                            &node.source_kind.to_id_base(),
+                            resolved_task.task_context(),
+                        )
+                    })
+                }
+                .with_context(|| format!("failed to resolve task {:?}", node.template.label))?;
+                Ok(TaskGraphStep {
+                    task,
+                    dependencies: node.dependencies,
+                    depends_order: node.template.depends_order,
+                })
+            })
+            .collect()
+    }
+
+    /// Runs every task after the tasks it depends on succeed, each one at most once.
+    fn run_task_graph(
+        &mut self,
+        steps: Vec<TaskGraphStep>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(root) = steps.last() else {
+            return;
+        };
+        let run = cx.new(|_| TaskGraphRun {
+            label: root.task.display_label().to_string(),
+            tasks: steps
+                .iter()
+                .map(|step| {
+                    (
+                        step.task.display_label().to_string(),
+                        TaskRunStatus::Pending,
+                    )
+                })
+                .collect(),
+        });
+        cx.emit(Event::TaskGraphStarted(run.clone()));
+
+        cx.spawn_in(window, async move |workspace, cx| {
+            let set_status = {
+                let run = run.clone();
+                move |ix: usize, status: TaskRunStatus, cx: &mut AsyncWindowContext| {
+                    run.update(cx, |run, cx| {
+                        run.tasks[ix].1 = status;
+                        cx.notify();
+                    })
+                    .ok();
+                }
+            };
+
+            // Every task is a shared future, awaited by all the tasks depending on it.
+            // The futures are lazy, so with the `sequence` order, a dependency only starts once the previous one succeeds.
+            let mut step_futures = Vec::<Shared<LocalBoxFuture<'static, bool>>>::new();
+            for (ix, step) in steps.into_iter().enumerate() {
+                let dependencies = step
+                    .dependencies
+                    .iter()
+                    .map(|&dependency| step_futures[dependency].clone())
+                    .collect::<Vec<_>>();
+                let workspace = workspace.clone();
+                let set_status = set_status.clone();
+                let mut cx = cx.clone();
+                let step_future = async move {
+                    let dependencies_succeeded = match step.depends_order {
+                        DependsOrder::Parallel => future::join_all(dependencies)
+                            .await
+                            .into_iter()
+                            .all(|succeeded| succeeded),
+                        DependsOrder::Sequence => {
+                            let mut succeeded = true;
+                            for dependency in dependencies {
+                                if !dependency.await {
+                                    succeeded = false;
+                                    break;
+                                }
+                            }
+                            succeeded
+                        }
+                    };
+                    if !dependencies_succeeded {
+                        set_status(ix, TaskRunStatus::Skipped, &mut cx);
+                        return false;
+                    }
+
+                    set_status(ix, TaskRunStatus::Running, &mut cx);
+                    let succeeded = if step.task.original_task().is_compound() {
+                        true
+                    } else {
+                        let task_status = workspace.update_in(&mut cx, |workspace, window, cx| {
+                            workspace.spawn_in_terminal(step.task.resolved, window, cx)
+                        });
+                        match task_status {
+                            Ok(task_status) => match task_status.await {
+                                Some(Ok(status)) => status.success(),
+                                Some(Err(e)) => {
+                                    log::error!("Task spawn failed: {e}");
+                                    false
+                                }
+                                None => false,
+                            },
+                            Err(_) => false,
+                        }
+                    };
+                    set_status(
+                        ix,
+                        if succeeded {
+                            TaskRunStatus::Succeeded
+                        } else {
+                            TaskRunStatus::Failed
+                        },
+                        &mut cx,
+                    );
+                    succeeded
+                };
+                step_futures.push(step_future.boxed_local().shared());
+            }
+
+            if let Some(root) = step_futures.pop() {
+                root.await;
+            }
+            // Tasks listed after a failed one in the `sequence` order never started.
+            run.update(cx, |run, cx| {
+                for (_, status) in &mut run.tasks {
+                    if *status == TaskRunStatus::Pending {
+                        *status = TaskRunStatus::Skipped;
+                    }
+                }
+                cx.notify();
+            })
+            .log_err();
+        })
+        .detach();
+    }
+
     pub fn start_debug_session(
         &mut self,
         scenario: DebugScenario,
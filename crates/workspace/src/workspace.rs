@@ -69,6 +69,7 @@ use postage::stream::Stream;
 use project::{
     DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree, WorktreeId,
     debugger::{breakpoint_store::BreakpointStoreEvent, session::ThreadStatus},
+    task_store::TaskStoreEvent,
 };
 use remote::{SshClientDelegate, SshConnectionOptions, ssh_session::ConnectionIdentifier};
 use schemars::JsonSchema;
@@ -886,6 +887,7 @@ pub enum Event {
     ZoomChanged,
     ModalOpened,
     ClearActivityIndicator,
+    TaskGraphStarted(Entity<tasks::TaskGraphRun>),
 }
 
 #[derive(Debug)]
@@ -1090,7 +1092,10 @@ impl Workspace {
             window,
             |workspace, _, event, window, cx| match event {
                 BreakpointStoreEvent::BreakpointsUpdated(_, _)
-                | BreakpointStoreEvent::BreakpointsCleared(_) => {
+                | BreakpointStoreEvent::BreakpointsCleared(_)
+                | BreakpointStoreEvent::FunctionBreakpointsUpdated
+                | BreakpointStoreEvent::DataBreakpointsUpdated
+                | BreakpointStoreEvent::WatchExpressionsUpdated => {
                     workspace.serialize_workspace(window, cx);
                 }
                 BreakpointStoreEvent::SetDebugLine | BreakpointStoreEvent::ClearDebugLines => {}
@@ -1098,6 +1103,23 @@ impl Workspace {
         )
         .detach();
 
+        cx.subscribe_in(
+            &project.read(cx).task_store().clone(),
+            window,
+            |workspace, _, event: &TaskStoreEvent, window, cx| match event {
+                TaskStoreEvent::RestartWatchedTask {
+                    task_source_kind,
+                    task,
+                } => workspace.restart_watched_task(
+                    task_source_kind.clone(),
+                    task.clone(),
+                    window,
+                    cx,
+                ),
+            },
+        )
+        .detach();
+
         cx.on_focus_lost(window, |this, window, cx| {
             let focus_handle = this.focus_handle(cx);
             window.focus(&focus_handle);
@@ -5043,12 +5065,17 @@ impl Workspace {
         }
 
         if let Some(location) = self.serialize_workspace_location(cx) {
-            let breakpoints = self.project.update(cx, |project, cx| {
-                project
-                    .breakpoint_store()
-                    .read(cx)
-                    .all_source_breakpoints(cx)
-            });
+            let (breakpoints, function_breakpoints, data_breakpoints, watch_expressions) =
+                self.project.update(cx, |project, cx| {
+                    let breakpoint_store = project.breakpoint_store();
+                    let breakpoint_store = breakpoint_store.read(cx);
+                    (
+                        breakpoint_store.all_source_breakpoints(cx),
+                        breakpoint_store.function_breakpoints().to_vec(),
+                        breakpoint_store.data_breakpoints().to_vec(),
+                        breakpoint_store.watch_expressions().to_vec(),
+                    )
+                });
 
             let center_group = build_serialized_pane_group(&self.center.root, window, cx);
             let docks = build_serialized_docks(self, window, cx);
@@ -5063,6 +5090,9 @@ impl Workspace {
                 centered_layout: self.centered_layout,
                 session_id: self.session_id.clone(),
                 breakpoints,
+                function_breakpoints,
+                data_breakpoints,
+                watch_expressions,
                 window_id: Some(window.window_handle().window_id().as_u64()),
             };
 
@@ -5234,8 +5264,15 @@ impl Workspace {
                     project
                         .breakpoint_store()
                         .update(cx, |breakpoint_store, cx| {
-                            breakpoint_store
-                                .with_serialized_breakpoints(serialized_workspace.breakpoints, cx)
+                            breakpoint_store.with_serialized_watch_expressions(
+                                serialized_workspace.watch_expressions,
+                            );
+                            breakpoint_store.with_serialized_breakpoints(
+                                serialized_workspace.breakpoints,
+                                serialized_workspace.function_breakpoints,
+                                serialized_workspace.data_breakpoints,
+                                cx,
+                            )
                         })
                 })?
                 .await;
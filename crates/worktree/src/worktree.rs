@@ -19,7 +19,7 @@ use futures::{
 };
 use fuzzy::CharBag;
 use git::{
-    COMMIT_MESSAGE, DOT_GIT, FSMONITOR_DAEMON, GITIGNORE, INDEX_LOCK, LFS_DIR,
+    COMMIT_MESSAGE, DOT_GIT, DOT_JJ, FSMONITOR_DAEMON, GITIGNORE, INDEX_LOCK, LFS_DIR,
     repository::RepoPath, status::GitSummary,
 };
 use gpui::{
@@ -2976,7 +2976,7 @@ impl BackgroundScannerState {
     fn insert_entry(&mut self, mut entry: Entry, fs: &dyn Fs, watcher: &dyn Watcher) -> Entry {
         self.reuse_entry_id(&mut entry);
         let entry = self.snapshot.insert_entry(entry, fs);
-        if entry.path.file_name() == Some(&DOT_GIT) {
+        if entry.path.file_name() == Some(&DOT_GIT) || entry.path.file_name() == Some(&DOT_JJ) {
             self.insert_git_repository(entry.path.clone(), fs, watcher);
         }
 
@@ -4292,7 +4292,9 @@ impl BackgroundScanner {
             .collect::<Vec<_>>()
             .await;
 
-        // Ensure that .git and .gitignore are processed first.
+        // Ensure that .git and .gitignore are processed first, and that a
+        // colocated .git takes precedence over .jj.
+        swap_to_front(&mut child_paths, *DOT_JJ);
         swap_to_front(&mut child_paths, *GITIGNORE);
         swap_to_front(&mut child_paths, *DOT_GIT);
 
@@ -4301,7 +4303,7 @@ impl BackgroundScanner {
             let child_name = child_abs_path.file_name().unwrap();
             let child_path: Arc<Path> = job.path.join(child_name).into();
 
-            if child_name == *DOT_GIT {
+            if child_name == *DOT_GIT || child_name == *DOT_JJ {
                 let mut state = self.state.lock();
                 state.insert_git_repository(
                     child_path.clone(),
@@ -5550,7 +5552,28 @@ fn parse_gitfile(content: &str) -> anyhow::Result<&Path> {
     Ok(Path::new(path.trim()))
 }
 
+/// Finds the git store backing a pure jj workspace, which jj keeps at
+/// `.jj/repo/store/git` unless `store/git_target` points elsewhere. In
+/// secondary workspaces, `.jj/repo` is a file containing the repo's path.
+fn discover_jujutsu_git_store(dot_jj_abs_path: &Path, fs: &dyn Fs) -> Option<Arc<Path>> {
+    let mut repo_path = dot_jj_abs_path.join("repo");
+    if let Ok(contents) = smol::block_on(fs.load(&repo_path)) {
+        repo_path = dot_jj_abs_path.join(contents.trim());
+    }
+    let store_path = repo_path.join("store");
+    let git_target = smol::block_on(fs.load(&store_path.join("git_target"))).ok()?;
+    let git_store_path =
+        smol::block_on(fs.canonicalize(&store_path.join(git_target.trim()))).log_err()?;
+    Some(git_store_path.as_path().into())
+}
+
 fn discover_git_paths(dot_git_abs_path: &Arc<Path>, fs: &dyn Fs) -> (Arc<Path>, Arc<Path>) {
+    if dot_git_abs_path.file_name() == Some(*DOT_JJ) {
+        let git_store_abs_path =
+            discover_jujutsu_git_store(dot_git_abs_path, fs).unwrap_or(dot_git_abs_path.clone());
+        return (git_store_abs_path.clone(), git_store_abs_path);
+    }
+
     let mut repository_dir_abs_path = dot_git_abs_path.clone();
     let mut common_dir_abs_path = dot_git_abs_path.clone();
 
@@ -196,6 +196,15 @@ Error: Running Zed as root or via sudo is unsupported.
         return;
     }
 
+    #[cfg(unix)]
+    if let Some(socket) = &args.terminal_session_server {
+        if let Err(error) = terminal::run_session_server(Path::new(socket)) {
+            eprintln!("{error:#}");
+            process::exit(1);
+        }
+        return;
+    }
+
     if args.dump_all_actions {
         dump_all_gpui_actions();
         return;
@@ -1057,6 +1066,11 @@ struct Args {
     #[arg(long, hide = true)]
     askpass: Option<String>,
 
+    /// Runs the background process hosting persistent terminal sessions, on this Unix socket.
+    #[arg(long, hide = true)]
+    #[cfg(unix)]
+    terminal_session_server: Option<String>,
+
     /// Run zed in the foreground, only used on Windows, to match the behavior on macOS.
     #[arg(long)]
     #[cfg(target_os = "windows")]
@@ -20,7 +20,7 @@ use collections::VecDeque;
 use debugger_ui::debugger_panel::DebugPanel;
 use editor::ProposedChangesEditorToolbar;
 use editor::{Editor, MultiBuffer, scroll::Autoscroll};
-use feature_flags::{DebuggerFeatureFlag, FeatureFlagViewExt};
+use feature_flags::{DebuggerFeatureFlag, FeatureFlagViewExt, JjUiFeatureFlag};
 use futures::future::Either;
 use futures::{StreamExt, channel::mpsc, select_biased};
 use git_ui::git_panel::GitPanel;
@@ -522,6 +522,21 @@ fn initialize_panels(
                 )
                 .detach()
             });
+            cx.when_flag_enabled::<JjUiFeatureFlag>(window, |_, window, cx| {
+                cx.spawn_in(
+                    window,
+                    async move |workspace: gpui::WeakEntity<Workspace>,
+                                cx: &mut AsyncWindowContext| {
+                        let jj_panel =
+                            jj_ui::ChangeGraphPanel::load(workspace.clone(), cx.clone()).await?;
+                        workspace.update_in(cx, |workspace, window, cx| {
+                            workspace.add_panel(jj_panel, window, cx);
+                        })?;
+                        anyhow::Ok(())
+                    },
+                )
+                .detach()
+            });
         })?;
 
         let is_assistant2_enabled = !cfg!(test);
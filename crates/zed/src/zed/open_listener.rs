@@ -12,6 +12,7 @@ use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use futures::channel::{mpsc, oneshot};
 use futures::future::join_all;
 use futures::{FutureExt, SinkExt, StreamExt};
+use git_ui::{file_diff_view::FileDiffView, merge_view::MergeView};
 use gpui::{App, AsyncApp, Global, WindowHandle};
 use language::Point;
 use recent_projects::{SshSettings, open_ssh_project};
@@ -260,6 +261,8 @@ pub async fn handle_cli_connection(
             CliRequest::Open {
                 urls,
                 paths,
+                diff_paths,
+                merge_paths,
                 wait,
                 open_new_workspace,
                 env,
@@ -288,6 +291,8 @@ pub async fn handle_cli_connection(
 
                 let open_workspace_result = open_workspaces(
                     paths,
+                    diff_paths,
+                    merge_paths,
                     open_new_workspace,
                     &responses,
                     wait,
@@ -306,6 +311,8 @@ pub async fn handle_cli_connection(
 
 async fn open_workspaces(
     paths: Vec<String>,
+    diff_paths: Vec<[String; 2]>,
+    merge_paths: Vec<[String; 4]>,
     open_new_workspace: Option<bool>,
     responses: &IpcSender<CliResponse>,
     wait: bool,
@@ -313,7 +320,7 @@ async fn open_workspaces(
     env: Option<collections::HashMap<String, String>>,
     cx: &mut AsyncApp,
 ) -> Result<()> {
-    let grouped_locations = if paths.is_empty() {
+    let grouped_locations = if paths.is_empty() && diff_paths.is_empty() && merge_paths.is_empty() {
         // If no paths are provided, restore from previous workspaces unless a new workspace is requested with -n
         if open_new_workspace == Some(true) {
             Vec::new()
@@ -362,6 +369,8 @@ async fn open_workspaces(
 
                     let workspace_failed_to_open = open_local_workspace(
                         workspace_paths,
+                        &diff_paths,
+                        &merge_paths,
                         open_new_workspace,
                         wait,
                         responses,
@@ -411,6 +420,8 @@ async fn open_workspaces(
 
 async fn open_local_workspace(
     workspace_paths: Vec<String>,
+    diff_paths: &[[String; 2]],
+    merge_paths: &[[String; 4]],
     open_new_workspace: Option<bool>,
     wait: bool,
     responses: &IpcSender<CliResponse>,
@@ -465,10 +476,82 @@ async fn open_local_workspace(
                 }
             }
 
+            // Diff and merge views are opened in the same workspace, and `--wait` waits for
+            // them to be closed like any other item.
+            let tool_items = diff_paths
+                .iter()
+                .map(|[old_path, new_path]| {
+                    let item = workspace.update(cx, |workspace, window, cx| {
+                        let diff_view = FileDiffView::open(
+                            old_path.into(),
+                            new_path.into(),
+                            workspace,
+                            window,
+                            cx,
+                        );
+                        cx.spawn(async move |_, _| {
+                            anyhow::Ok(Box::new(diff_view.await?) as Box<dyn ItemHandle>)
+                        })
+                    });
+                    (format!("diff of {old_path:?} and {new_path:?}"), item)
+                })
+                .chain(merge_paths.iter().map(|[ours, theirs, base, result]| {
+                    let item = workspace.update(cx, |workspace, window, cx| {
+                        let merge_view = MergeView::open_files(
+                            ours.into(),
+                            theirs.into(),
+                            base.into(),
+                            result.into(),
+                            workspace,
+                            window,
+                            cx,
+                        );
+                        cx.spawn(async move |_, _| {
+                            anyhow::Ok(Box::new(merge_view.await?) as Box<dyn ItemHandle>)
+                        })
+                    });
+                    (format!("merge of {result:?}"), item)
+                }))
+                .collect::<Vec<_>>();
+
+            for (description, item) in tool_items {
+                let item = match item {
+                    Ok(item) => item.await,
+                    Err(err) => Err(err),
+                };
+                match item {
+                    Ok(item) => {
+                        cx.update(|cx| {
+                            let released = oneshot::channel();
+                            item.on_release(
+                                cx,
+                                Box::new(move |_| {
+                                    let _ = released.0.send(());
+                                }),
+                            )
+                            .detach();
+                            item_release_futures.push(released.1);
+                        })
+                        .log_err();
+                    }
+                    Err(err) => {
+                        responses
+                            .send(CliResponse::Stderr {
+                                message: format!("error opening {description}: {err}"),
+                            })
+                            .log_err();
+                        errored = true;
+                    }
+                }
+            }
+
             if wait {
                 let background = cx.background_executor().clone();
+                let wait_for_workspace = paths_with_position.is_empty()
+                    && diff_paths.is_empty()
+                    && merge_paths.is_empty();
                 let wait = async move {
-                    if paths_with_position.is_empty() {
+                    if wait_for_workspace {
                         let (done_tx, done_rx) = oneshot::channel();
                         let _subscription = workspace.update(cx, |_, _, cx| {
                             cx.on_release(move |_, _| {
@@ -692,6 +775,8 @@ mod tests {
             .spawn(|mut cx| async move {
                 open_local_workspace(
                     workspace_paths,
+                    &[],
+                    &[],
                     open_new_workspace,
                     false,
                     &response_tx,
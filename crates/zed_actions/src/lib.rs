@@ -144,7 +144,7 @@ pub mod workspace {
 pub mod git {
     use gpui::{action_with_deprecated_aliases, actions};
 
-    actions!(git, [CheckoutBranch, Switch, SelectRepo]);
+    actions!(git, [CheckoutBranch, Switch, SelectRepo, CompareWithBranch]);
     action_with_deprecated_aliases!(git, Branch, ["branches::OpenRecent"]);
 }
 
@@ -333,7 +333,16 @@ pub struct Rerun {
     pub task_id: Option<String>,
 }
 
-impl_actions!(task, [Spawn, Rerun]);
+/// Stop restarting tasks when the files they watch change.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StopWatching {
+    /// If present, stop watching for the task with this ID, otherwise for all tasks.
+    #[serde(skip)]
+    pub task_id: Option<String>,
+}
+
+impl_actions!(task, [Spawn, Rerun, StopWatching]);
 
 pub mod outline {
     use std::sync::OnceLock;